@@ -0,0 +1,241 @@
+use std::fmt;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sha3::{Digest, Sha3_256};
+
+use super::types::{
+    AccountAddress, Ed25519PublicKey, Ed25519Signature, TransactionPayload, TypeTag,
+};
+use crate::canonical_json::{hex_value, int_value, variant_value, CanonicalJson};
+use crate::constants::ED25519_SIGNATURE_LENGTH;
+
+/// The domain separator of raw transactions, hashed and prepended to them before signing.
+const RAW_TRANSACTION_SALT: &[u8] = b"APTOS::RawTransaction";
+
+/// The variant index of single Ed25519 transaction authenticators.
+const ED25519_AUTHENTICATOR_INDEX: u8 = 0;
+
+/// An Aptos raw transaction, serialized with BCS.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub struct AptosTransaction {
+    pub sender: AccountAddress,
+    /// The sequence number of the sender account, which must match the on-chain one.
+    pub sequence_number: u64,
+    pub payload: TransactionPayload,
+    /// The maximum amount of gas units the transaction may use.
+    pub max_gas_amount: u64,
+    /// The price of a gas unit, in octas.
+    pub gas_unit_price: u64,
+    /// The transaction is discarded if it is not executed before this Unix timestamp.
+    pub expiration_timestamp_secs: u64,
+    /// 1 for mainnet, 2 for testnet.
+    pub chain_id: u8,
+}
+
+impl AptosTransaction {
+    /// Returns the BCS encoding of the raw transaction.
+    pub fn encode(&self) -> Vec<u8> {
+        let mut buffer = Vec::new();
+
+        buffer.extend_from_slice(&self.sender.0);
+        buffer.extend_from_slice(&self.sequence_number.to_le_bytes());
+        self.payload.encode(&mut buffer);
+        buffer.extend_from_slice(&self.max_gas_amount.to_le_bytes());
+        buffer.extend_from_slice(&self.gas_unit_price.to_le_bytes());
+        buffer.extend_from_slice(&self.expiration_timestamp_secs.to_le_bytes());
+        buffer.push(self.chain_id);
+
+        buffer
+    }
+
+    /// Returns the message signed by the sender: the SHA3-256 hash of the
+    /// `APTOS::RawTransaction` salt followed by the encoded raw transaction.
+    pub fn build_for_signing(&self) -> Vec<u8> {
+        let mut message = Sha3_256::digest(RAW_TRANSACTION_SALT).to_vec();
+        message.extend_from_slice(&self.encode());
+        message
+    }
+
+    /// Returns the signed transaction, ready to be submitted as BCS to the
+    /// `/transactions` endpoint of a node.
+    pub fn build_with_signature(
+        &self,
+        public_key: &Ed25519PublicKey,
+        signature: &Ed25519Signature,
+    ) -> Vec<u8> {
+        let mut buffer = self.encode();
+
+        buffer.push(ED25519_AUTHENTICATOR_INDEX);
+        buffer.push(public_key.0.len() as u8);
+        buffer.extend_from_slice(&public_key.0);
+        buffer.push(ED25519_SIGNATURE_LENGTH as u8);
+        buffer.extend_from_slice(&signature.0);
+
+        buffer
+    }
+}
+
+/// A one-line summary of the transaction, for logs: the called function and the gas paid.
+impl fmt::Display for AptosTransaction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let TransactionPayload::EntryFunction(entry_function) = &self.payload;
+
+        write!(
+            f,
+            "Aptos chain {} {} sequence {}: {}::{}::{} with {} arguments, \
+             max gas {} at {} octas/gas",
+            self.chain_id,
+            self.sender,
+            self.sequence_number,
+            entry_function.module.address,
+            entry_function.module.name,
+            entry_function.function,
+            entry_function.args.len(),
+            self.max_gas_amount,
+            self.gas_unit_price
+        )
+    }
+}
+
+impl CanonicalJson for AptosTransaction {
+    fn canonical_value(&self) -> Value {
+        let payload = match &self.payload {
+            TransactionPayload::EntryFunction(entry_function) => variant_value(
+                "EntryFunction",
+                json!({
+                    "module": {
+                        "address": hex_value(entry_function.module.address.0),
+                        "name": entry_function.module.name,
+                    },
+                    "function": entry_function.function,
+                    "ty_args": entry_function
+                        .ty_args
+                        .iter()
+                        .map(canonical_type_tag)
+                        .collect::<Vec<_>>(),
+                    "args": entry_function.args.iter().map(hex_value).collect::<Vec<_>>(),
+                }),
+            ),
+        };
+
+        json!({
+            "sender": hex_value(self.sender.0),
+            "sequence_number": int_value(self.sequence_number),
+            "payload": payload,
+            "max_gas_amount": int_value(self.max_gas_amount),
+            "gas_unit_price": int_value(self.gas_unit_price),
+            "expiration_timestamp_secs": int_value(self.expiration_timestamp_secs),
+            "chain_id": int_value(self.chain_id),
+        })
+    }
+}
+
+fn canonical_type_tag(type_tag: &TypeTag) -> Value {
+    match type_tag {
+        TypeTag::Bool => json!("Bool"),
+        TypeTag::U8 => json!("U8"),
+        TypeTag::U16 => json!("U16"),
+        TypeTag::U32 => json!("U32"),
+        TypeTag::U64 => json!("U64"),
+        TypeTag::U128 => json!("U128"),
+        TypeTag::U256 => json!("U256"),
+        TypeTag::Address => json!("Address"),
+        TypeTag::Signer => json!("Signer"),
+        TypeTag::Vector(type_tag) => variant_value("Vector", canonical_type_tag(type_tag)),
+        TypeTag::Struct(struct_tag) => variant_value(
+            "Struct",
+            json!({
+                "address": hex_value(struct_tag.address.0),
+                "module": struct_tag.module,
+                "name": struct_tag.name,
+                "type_args": struct_tag
+                    .type_args
+                    .iter()
+                    .map(canonical_type_tag)
+                    .collect::<Vec<_>>(),
+            }),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aptos::utils::{aptos_transfer, APTOS_FRAMEWORK_ADDRESS};
+
+    fn transfer_transaction() -> AptosTransaction {
+        AptosTransaction {
+            sender: AccountAddress([1u8; 32]),
+            sequence_number: 7,
+            payload: aptos_transfer(&AccountAddress([2u8; 32]), 1_000).into(),
+            max_gas_amount: 2_000,
+            gas_unit_price: 100,
+            expiration_timestamp_secs: 1_700_000_000,
+            chain_id: 2,
+        }
+    }
+
+    #[test]
+    fn test_encode_transfer() {
+        let mut expected = vec![];
+        expected.extend_from_slice(&[1u8; 32]);
+        expected.extend_from_slice(&7u64.to_le_bytes());
+        expected.push(2);
+        expected.extend_from_slice(&APTOS_FRAMEWORK_ADDRESS.0);
+        expected.push(13);
+        expected.extend_from_slice(b"aptos_account");
+        expected.push(8);
+        expected.extend_from_slice(b"transfer");
+        expected.extend_from_slice(&[0, 2, 32]);
+        expected.extend_from_slice(&[2u8; 32]);
+        expected.push(8);
+        expected.extend_from_slice(&1_000u64.to_le_bytes());
+        expected.extend_from_slice(&2_000u64.to_le_bytes());
+        expected.extend_from_slice(&100u64.to_le_bytes());
+        expected.extend_from_slice(&1_700_000_000u64.to_le_bytes());
+        expected.push(2);
+
+        assert_eq!(transfer_transaction().encode(), expected);
+    }
+
+    #[test]
+    fn test_build_for_signing() {
+        let tx = transfer_transaction();
+        let message = tx.build_for_signing();
+
+        assert_eq!(
+            hex::encode(&message[..32]),
+            "b5e97db07fa0bd0e5598aa3643a9bc6f6693bddc1a9fec9e674a461eaa00b193"
+        );
+        assert_eq!(message[32..], tx.encode());
+    }
+
+    #[test]
+    fn test_build_with_signature() {
+        let tx = transfer_transaction();
+        let signed =
+            tx.build_with_signature(&Ed25519PublicKey([3u8; 32]), &Ed25519Signature([4u8; 64]));
+
+        let mut expected = tx.encode();
+        expected.extend_from_slice(&[0, 32]);
+        expected.extend_from_slice(&[3u8; 32]);
+        expected.push(64);
+        expected.extend_from_slice(&[4u8; 64]);
+        assert_eq!(signed, expected);
+    }
+
+    #[test]
+    fn test_display_summary() {
+        assert_eq!(
+            transfer_transaction().to_string(),
+            format!(
+                "Aptos chain 2 0x{} sequence 7: {}::aptos_account::transfer with 2 arguments, \
+                 max gas 2000 at 100 octas/gas",
+                "01".repeat(32),
+                APTOS_FRAMEWORK_ADDRESS
+            )
+        );
+    }
+}
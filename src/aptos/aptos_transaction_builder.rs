@@ -0,0 +1,165 @@
+use super::{
+    aptos_transaction::AptosTransaction,
+    types::{AccountAddress, TransactionPayload},
+};
+use crate::transaction_builder::{BuildError, TxBuilder};
+
+pub struct AptosTransactionBuilder {
+    pub sender: Option<AccountAddress>,
+    pub sequence_number: Option<u64>,
+    pub payload: Option<TransactionPayload>,
+    pub max_gas_amount: Option<u64>,
+    pub gas_unit_price: Option<u64>,
+    pub expiration_timestamp_secs: Option<u64>,
+    pub chain_id: Option<u8>,
+}
+
+impl Default for AptosTransactionBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TxBuilder<AptosTransaction> for AptosTransactionBuilder {
+    fn build(&self) -> AptosTransaction {
+        AptosTransaction {
+            sender: self.sender.expect("Missing sender"),
+            sequence_number: self.sequence_number.expect("Missing sequence number"),
+            payload: self.payload.clone().expect("Missing payload"),
+            max_gas_amount: self.max_gas_amount.expect("Missing max gas amount"),
+            gas_unit_price: self.gas_unit_price.expect("Missing gas unit price"),
+            expiration_timestamp_secs: self
+                .expiration_timestamp_secs
+                .expect("Missing expiration timestamp"),
+            chain_id: self.chain_id.expect("Missing chain ID"),
+        }
+    }
+}
+
+impl AptosTransactionBuilder {
+    /// Same as [`TxBuilder::build`], but returns an error instead of panicking if a mandatory
+    /// field is missing.
+    pub fn try_build(&self) -> Result<AptosTransaction, BuildError> {
+        Ok(AptosTransaction {
+            sender: self.sender.ok_or(BuildError::MissingField("sender"))?,
+            sequence_number: self
+                .sequence_number
+                .ok_or(BuildError::MissingField("sequence_number"))?,
+            payload: self
+                .payload
+                .clone()
+                .ok_or(BuildError::MissingField("payload"))?,
+            max_gas_amount: self
+                .max_gas_amount
+                .ok_or(BuildError::MissingField("max_gas_amount"))?,
+            gas_unit_price: self
+                .gas_unit_price
+                .ok_or(BuildError::MissingField("gas_unit_price"))?,
+            expiration_timestamp_secs: self
+                .expiration_timestamp_secs
+                .ok_or(BuildError::MissingField("expiration_timestamp_secs"))?,
+            chain_id: self.chain_id.ok_or(BuildError::MissingField("chain_id"))?,
+        })
+    }
+
+    pub const fn new() -> Self {
+        Self {
+            sender: None,
+            sequence_number: None,
+            payload: None,
+            max_gas_amount: None,
+            gas_unit_price: None,
+            expiration_timestamp_secs: None,
+            chain_id: None,
+        }
+    }
+
+    pub const fn sender(mut self, sender: AccountAddress) -> Self {
+        self.sender = Some(sender);
+        self
+    }
+
+    pub const fn sequence_number(mut self, sequence_number: u64) -> Self {
+        self.sequence_number = Some(sequence_number);
+        self
+    }
+
+    pub fn payload(mut self, payload: impl Into<TransactionPayload>) -> Self {
+        self.payload = Some(payload.into());
+        self
+    }
+
+    pub const fn max_gas_amount(mut self, max_gas_amount: u64) -> Self {
+        self.max_gas_amount = Some(max_gas_amount);
+        self
+    }
+
+    pub const fn gas_unit_price(mut self, gas_unit_price: u64) -> Self {
+        self.gas_unit_price = Some(gas_unit_price);
+        self
+    }
+
+    pub const fn expiration_timestamp_secs(mut self, expiration_timestamp_secs: u64) -> Self {
+        self.expiration_timestamp_secs = Some(expiration_timestamp_secs);
+        self
+    }
+
+    pub const fn chain_id(mut self, chain_id: u8) -> Self {
+        self.chain_id = Some(chain_id);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aptos::utils::aptos_transfer;
+    use crate::transaction_builder::TransactionBuilder;
+    use crate::types::APTOS;
+
+    #[test]
+    fn test_aptos_transaction_builder_typed() {
+        let sender: AccountAddress = "0x1".parse().unwrap();
+        let payload = aptos_transfer(&AccountAddress([2u8; 32]), 42);
+
+        let tx = TransactionBuilder::new::<APTOS>()
+            .sender(sender)
+            .sequence_number(0)
+            .payload(payload.clone())
+            .max_gas_amount(1_000)
+            .gas_unit_price(100)
+            .expiration_timestamp_secs(1_700_000_000)
+            .chain_id(1)
+            .build();
+
+        assert_eq!(
+            tx,
+            AptosTransaction {
+                sender,
+                sequence_number: 0,
+                payload: payload.into(),
+                max_gas_amount: 1_000,
+                gas_unit_price: 100,
+                expiration_timestamp_secs: 1_700_000_000,
+                chain_id: 1,
+            }
+        );
+    }
+
+    #[test]
+    fn test_try_build_missing_field() {
+        let result = AptosTransactionBuilder::new()
+            .sender(AccountAddress([1u8; 32]))
+            .sequence_number(0)
+            .payload(aptos_transfer(&AccountAddress([2u8; 32]), 42))
+            .max_gas_amount(1_000)
+            .gas_unit_price(100)
+            .chain_id(1)
+            .try_build();
+
+        assert_eq!(
+            result,
+            Err(BuildError::MissingField("expiration_timestamp_secs"))
+        );
+    }
+}
@@ -0,0 +1,4 @@
+pub mod aptos_transaction;
+pub mod aptos_transaction_builder;
+pub mod types;
+pub mod utils;
@@ -0,0 +1,247 @@
+use std::{fmt, str::FromStr};
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use serde::{Deserialize, Serialize};
+use serde_big_array::BigArray;
+
+use super::utils::{encode_bytes, encode_uleb128};
+use crate::constants::ED25519_SIGNATURE_LENGTH;
+
+/// A 32-byte account address.
+///
+/// It parses from hex with or without the `0x` prefix, including the short form of special
+/// addresses such as `0x1`, and displays in the long form.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+    BorshSerialize,
+    BorshDeserialize,
+)]
+pub struct AccountAddress(pub [u8; 32]);
+
+/// The Ed25519 public key of the signer.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize,
+)]
+pub struct Ed25519PublicKey(pub [u8; 32]);
+
+/// An Ed25519 signature of the signing message.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize,
+)]
+pub struct Ed25519Signature(#[serde(with = "BigArray")] pub [u8; ED25519_SIGNATURE_LENGTH]);
+
+/// A Move module, e.g. `0x1::aptos_account`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub struct ModuleId {
+    pub address: AccountAddress,
+    pub name: String,
+}
+
+/// A Move struct type, e.g. `0x1::aptos_coin::AptosCoin`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub struct StructTag {
+    pub address: AccountAddress,
+    pub module: String,
+    pub name: String,
+    pub type_args: Vec<TypeTag>,
+}
+
+/// A Move type, used for the type arguments of a generic function.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub enum TypeTag {
+    Bool,
+    U8,
+    U16,
+    U32,
+    U64,
+    U128,
+    U256,
+    Address,
+    Signer,
+    Vector(Box<TypeTag>),
+    Struct(Box<StructTag>),
+}
+
+/// A call to a public entry function of a published module.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub struct EntryFunction {
+    pub module: ModuleId,
+    pub function: String,
+    pub ty_args: Vec<TypeTag>,
+    /// The BCS encoded arguments, e.g. the little endian bytes of a `u64`.
+    pub args: Vec<Vec<u8>>,
+}
+
+/// What the transaction executes.
+///
+/// Only entry function calls are supported, scripts and multisig payloads are not.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub enum TransactionPayload {
+    EntryFunction(EntryFunction),
+}
+
+impl From<EntryFunction> for TransactionPayload {
+    fn from(entry_function: EntryFunction) -> Self {
+        Self::EntryFunction(entry_function)
+    }
+}
+
+impl ModuleId {
+    pub(crate) fn encode(&self, buffer: &mut Vec<u8>) {
+        buffer.extend_from_slice(&self.address.0);
+        encode_bytes(buffer, self.name.as_bytes());
+    }
+}
+
+impl StructTag {
+    pub(crate) fn encode(&self, buffer: &mut Vec<u8>) {
+        buffer.extend_from_slice(&self.address.0);
+        encode_bytes(buffer, self.module.as_bytes());
+        encode_bytes(buffer, self.name.as_bytes());
+        encode_uleb128(buffer, self.type_args.len());
+        for type_arg in &self.type_args {
+            type_arg.encode(buffer);
+        }
+    }
+}
+
+impl TypeTag {
+    pub(crate) fn encode(&self, buffer: &mut Vec<u8>) {
+        // The variant indices are not in declaration order: the smaller integer types were
+        // appended to the on-chain enum later.
+        let index = match self {
+            Self::Bool => 0,
+            Self::U8 => 1,
+            Self::U64 => 2,
+            Self::U128 => 3,
+            Self::Address => 4,
+            Self::Signer => 5,
+            Self::Vector(_) => 6,
+            Self::Struct(_) => 7,
+            Self::U16 => 8,
+            Self::U32 => 9,
+            Self::U256 => 10,
+        };
+        encode_uleb128(buffer, index);
+
+        match self {
+            Self::Vector(element) => element.encode(buffer),
+            Self::Struct(struct_tag) => struct_tag.encode(buffer),
+            _ => {}
+        }
+    }
+}
+
+impl EntryFunction {
+    pub(crate) fn encode(&self, buffer: &mut Vec<u8>) {
+        self.module.encode(buffer);
+        encode_bytes(buffer, self.function.as_bytes());
+        encode_uleb128(buffer, self.ty_args.len());
+        for ty_arg in &self.ty_args {
+            ty_arg.encode(buffer);
+        }
+        encode_uleb128(buffer, self.args.len());
+        for arg in &self.args {
+            encode_bytes(buffer, arg);
+        }
+    }
+}
+
+impl TransactionPayload {
+    /// The on-chain variant index of entry function payloads, after `Script` and the
+    /// deprecated `ModuleBundle`.
+    const ENTRY_FUNCTION_INDEX: usize = 2;
+
+    pub(crate) fn encode(&self, buffer: &mut Vec<u8>) {
+        match self {
+            Self::EntryFunction(entry_function) => {
+                encode_uleb128(buffer, Self::ENTRY_FUNCTION_INDEX);
+                entry_function.encode(buffer);
+            }
+        }
+    }
+}
+
+impl FromStr for AccountAddress {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let hex_str = s.strip_prefix("0x").unwrap_or(s);
+        if hex_str.is_empty() || hex_str.len() > 64 {
+            return Err(format!("{} should be 1 to 64 hex characters", s));
+        }
+
+        // Short addresses are left padded with zeros.
+        let padded = format!("{:0>64}", hex_str);
+        let bytes = hex::decode(padded).map_err(|e| e.to_string())?;
+
+        Ok(Self(
+            bytes.try_into().expect("64 hex characters are 32 bytes"),
+        ))
+    }
+}
+
+impl fmt::Display for AccountAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "0x{}", hex::encode(self.0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_account_address_from_str() {
+        let mut expected = [0u8; 32];
+        expected[31] = 0x0a;
+        assert_eq!("0xa".parse::<AccountAddress>().unwrap().0, expected);
+        assert_eq!("a".parse::<AccountAddress>().unwrap().0, expected);
+
+        let long = "0x9a5a3eb1d3d44bde0a22fb1b5b4f9c0e0a5e2f6a2a3c1d5d0c9ef5a4a7b3c2d1";
+        let address: AccountAddress = long.parse().unwrap();
+        assert_eq!(address.to_string(), long);
+
+        assert!("0x".parse::<AccountAddress>().is_err());
+        assert!("0xzz".parse::<AccountAddress>().is_err());
+        assert!(format!("{}0", long).parse::<AccountAddress>().is_err());
+    }
+
+    #[test]
+    fn test_encode_type_tag() {
+        let coin = TypeTag::Struct(Box::new(StructTag {
+            address: "0x1".parse().unwrap(),
+            module: "aptos_coin".to_string(),
+            name: "AptosCoin".to_string(),
+            type_args: vec![],
+        }));
+
+        let mut buffer = vec![];
+        TypeTag::Vector(Box::new(coin)).encode(&mut buffer);
+
+        let mut expected = vec![6, 7];
+        expected.extend_from_slice(&[0u8; 31]);
+        expected.push(1);
+        expected.push(10);
+        expected.extend_from_slice(b"aptos_coin");
+        expected.push(9);
+        expected.extend_from_slice(b"AptosCoin");
+        expected.push(0);
+        assert_eq!(buffer, expected);
+
+        for (type_tag, index) in [(TypeTag::U16, 8), (TypeTag::U32, 9), (TypeTag::U256, 10)] {
+            let mut buffer = vec![];
+            type_tag.encode(&mut buffer);
+            assert_eq!(buffer, vec![index]);
+        }
+    }
+}
@@ -0,0 +1,70 @@
+//! Minimal BCS (Binary Canonical Serialization) encoding of the types needed for transactions.
+//!
+//! Integers are little endian, sequences and enum variant indices are prefixed by their
+//! ULEB128 encoded length or index, and structs are the concatenation of their fields.
+
+use super::types::{AccountAddress, EntryFunction, ModuleId};
+
+/// The address of the Aptos framework, `0x1`.
+pub const APTOS_FRAMEWORK_ADDRESS: AccountAddress = AccountAddress({
+    let mut address = [0u8; 32];
+    address[31] = 1;
+    address
+});
+
+/// Encodes a length or an enum variant index as ULEB128.
+pub(crate) fn encode_uleb128(buffer: &mut Vec<u8>, value: usize) {
+    let mut value = u32::try_from(value).expect("BCS lengths should fit in a u32");
+    while value >= 0x80 {
+        buffer.push((value as u8 & 0x7f) | 0x80);
+        value >>= 7;
+    }
+    buffer.push(value as u8);
+}
+
+/// Encodes a byte sequence, prefixed by its length.
+pub(crate) fn encode_bytes(buffer: &mut Vec<u8>, bytes: &[u8]) {
+    encode_uleb128(buffer, bytes.len());
+    buffer.extend_from_slice(bytes);
+}
+
+/// Creates a call to `0x1::aptos_account::transfer`, sending `amount` octas of APT to `to`.
+///
+/// Unlike `0x1::coin::transfer`, it creates the recipient account if it does not exist yet.
+pub fn aptos_transfer(to: &AccountAddress, amount: u64) -> EntryFunction {
+    EntryFunction {
+        module: ModuleId {
+            address: APTOS_FRAMEWORK_ADDRESS,
+            name: "aptos_account".to_string(),
+        },
+        function: "transfer".to_string(),
+        ty_args: vec![],
+        args: vec![to.0.to_vec(), amount.to_le_bytes().to_vec()],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_uleb128() {
+        for (value, expected) in [
+            (0usize, vec![0x00]),
+            (0x7f, vec![0x7f]),
+            (0x80, vec![0x80, 0x01]),
+            (0x3fff, vec![0xff, 0x7f]),
+            (0x4000, vec![0x80, 0x80, 0x01]),
+            (0xffff_ffff, vec![0xff, 0xff, 0xff, 0xff, 0x0f]),
+        ] {
+            let mut buffer = vec![];
+            encode_uleb128(&mut buffer, value);
+            assert_eq!(buffer, expected, "value {}", value);
+        }
+    }
+
+    #[test]
+    fn test_aptos_framework_address() {
+        assert_eq!(APTOS_FRAMEWORK_ADDRESS, "0x1".parse().unwrap());
+    }
+}
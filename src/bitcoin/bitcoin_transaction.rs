@@ -1,16 +1,24 @@
+use std::fmt;
 use std::io::{BufRead, Write};
 
 use borsh::{BorshDeserialize, BorshSerialize};
-use near_sdk::serde::{Deserialize, Serialize};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
 use sha2::{Digest, Sha256};
 
 use super::{
-    constants::{SEGWIT_FLAG, SEGWIT_MARKER},
+    constants::{SEGWIT_FLAG, SEGWIT_MARKER, WITNESS_SCALE_FACTOR},
     encoding::{decode::MAX_VEC_SIZE, utils::VarInt, Decodable, Encodable, ToU64},
+    hash_types::Wtxid,
     types::{
-        EcdsaSighashType, LockTime, ScriptBuf, TransactionType, TxIn, TxOut, Version, Witness,
+        opcodes::OP_0, Amount, Coin, ControlBlock, EcdsaSighashType, Hash, LockTime, ScriptBuf,
+        ScriptBuilder, Sequence, TapLeafHash, TapSighashType, TransactionType, TxIn, TxOut, Txid,
+        Version, Witness, MAX_SCRIPT_ELEMENT_SIZE, MAX_SCRIPT_SIZE,
     },
 };
+use crate::canonical_json::{hex_value, int_value, CanonicalJson};
+use crate::signature_error::SignatureError;
+use crate::validation::{Validate, ValidationIssue};
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
 pub struct BitcoinTransaction {
@@ -30,12 +38,72 @@ pub struct BitcoinTransaction {
 }
 
 // Function to compute sha256d (double SHA-256)
-fn sha256d(data: &[u8]) -> Vec<u8> {
+pub(crate) fn sha256d(data: &[u8]) -> Vec<u8> {
     let hash1 = Sha256::digest(data);
     let hash2 = Sha256::digest(hash1);
     hash2.to_vec()
 }
 
+fn sha256d_array(data: &[u8]) -> [u8; 32] {
+    sha256d(data)
+        .try_into()
+        .expect("SHA-256 output is 32 bytes")
+}
+
+/// Hashes `data` with sha256d and returns it as a [`Txid`], in display (reversed) byte order.
+fn txid_from_sha256d(data: &[u8]) -> Txid {
+    let mut hash = sha256d_array(data);
+    hash.reverse();
+    Txid(Hash::from_byte_array(hash))
+}
+
+/// Computes a BIP-340 tagged hash: `sha256(sha256(tag) || sha256(tag) || data)`.
+pub(crate) fn tagged_hash(tag: &str, parts: &[&[u8]]) -> [u8; 32] {
+    let tag_hash = Sha256::digest(tag.as_bytes());
+    let mut hasher = Sha256::new();
+    hasher.update(tag_hash);
+    hasher.update(tag_hash);
+    for part in parts {
+        hasher.update(part);
+    }
+    hasher.finalize().into()
+}
+
+/// Errors returned when parsing a raw [`BitcoinTransaction`] or [`Witness`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BitcoinDecodeError {
+    /// The input is not valid hex.
+    InvalidHex(String),
+    /// The bytes are not a valid consensus encoding of the value.
+    InvalidEncoding(String),
+    /// Bytes are left after the decoded value.
+    TrailingBytes(usize),
+    /// The JSON is not a valid `decoderawtransaction` output.
+    InvalidJson(String),
+}
+
+impl fmt::Display for BitcoinDecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidHex(reason) => write!(f, "invalid hex: {}", reason),
+            Self::InvalidEncoding(reason) => write!(f, "invalid encoding: {}", reason),
+            Self::TrailingBytes(len) => write!(f, "{} trailing bytes", len),
+            Self::InvalidJson(reason) => write!(f, "invalid transaction JSON: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for BitcoinDecodeError {}
+
+/// First byte of a Taproot annex, the last witness item if present (BIP-341).
+pub const TAPROOT_ANNEX_PREFIX: u8 = 0x50;
+
+/// Public key version of BIP-342 signatures, committed to by script path sighashes.
+const TAPROOT_KEY_VERSION_0: u8 = 0x00;
+
+/// Code separator position committed to when no `OP_CODESEPARATOR` was executed (BIP-342).
+const NO_CODE_SEPARATOR: u32 = 0xffff_ffff;
+
 impl BitcoinTransaction {
     // Common
     pub fn serialize(&self) -> Vec<u8> {
@@ -46,7 +114,120 @@ impl BitcoinTransaction {
         buffer
     }
 
+    /// Parses a raw transaction in either the legacy or the segwit (BIP-144) serialization,
+    /// e.g. as returned by a node's `getrawtransaction`.
+    ///
+    /// The whole input must be consumed.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, BitcoinDecodeError> {
+        let mut reader = bytes;
+        let tx = Self::decode(&mut reader)
+            .map_err(|e| BitcoinDecodeError::InvalidEncoding(e.to_string()))?;
+        if !reader.is_empty() {
+            return Err(BitcoinDecodeError::TrailingBytes(reader.len()));
+        }
+
+        Ok(tx)
+    }
+
+    /// Same as [`Self::from_bytes`], for a hex encoded transaction.
+    pub fn deserialize_hex(s: &str) -> Result<Self, BitcoinDecodeError> {
+        let bytes = hex::decode(s).map_err(|e| BitcoinDecodeError::InvalidHex(e.to_string()))?;
+        Self::from_bytes(&bytes)
+    }
+
+    /// Returns the transaction id: the double SHA-256 of the transaction serialized without
+    /// witnesses, so it doesn't change when inputs are signed with a witness.
+    pub fn compute_txid(&self) -> Txid {
+        let mut buffer = Vec::new();
+        let _ = self.encode_without_witnesses(&mut buffer);
+
+        txid_from_sha256d(&buffer)
+    }
+
+    /// Returns the witness transaction id (BIP-141): the double SHA-256 of the transaction
+    /// serialized with its witnesses. Equal to [`Self::compute_txid`] if no input has a witness.
+    ///
+    /// Note that the wtxid of a coinbase transaction is defined as all zeros when computing the
+    /// witness commitment, which this function doesn't special case.
+    pub fn compute_wtxid(&self) -> Wtxid {
+        Wtxid::from_txid(txid_from_sha256d(&self.serialize()))
+    }
+
+    /// Returns the size of the serialized transaction, witnesses included, in bytes.
+    pub fn total_size(&self) -> usize {
+        self.encoded_size()
+    }
+
+    /// Returns the size of the transaction serialized without witnesses, in bytes.
+    pub fn base_size(&self) -> usize {
+        self.encode_without_witnesses(&mut std::io::sink())
+            .expect("writing to a sink should not fail")
+    }
+
+    /// Returns the weight of the transaction as defined by BIP-141: non-witness bytes weigh 4
+    /// and witness bytes (including the segwit marker and flag) weigh 1.
+    pub fn weight(&self) -> u64 {
+        let base_size = self.base_size() as u64;
+        let total_size = self.total_size() as u64;
+        base_size * (WITNESS_SCALE_FACTOR - 1) + total_size
+    }
+
+    /// Returns the virtual size of the transaction: its weight divided by 4, rounded up.
+    pub fn vsize(&self) -> u64 {
+        self.weight().div_ceil(WITNESS_SCALE_FACTOR)
+    }
+
+    /// Returns the fee paying `fee_rate` sat/vB for the transaction, or `None` on overflow.
+    ///
+    /// The size of unsigned inputs doesn't account for their script sig or witness, so sign
+    /// (or fill in placeholder unlocks of the same size) before estimating the fee.
+    pub fn fee_for_rate(&self, fee_rate: u64) -> Option<Amount> {
+        self.vsize().checked_mul(fee_rate).map(Amount::from_sat)
+    }
+
+    /// Encodes the transaction in the legacy serialization, ignoring witnesses. Unlike
+    /// [`Encodable::encode`], this never uses the segwit serialization, even with no inputs.
+    pub(crate) fn encode_without_witnesses<W: Write + ?Sized>(
+        &self,
+        w: &mut W,
+    ) -> Result<usize, std::io::Error> {
+        let mut len = 0;
+        len += self.version.encode(w)?;
+        len += self.input.encode(w)?;
+        len += self.output.encode(w)?;
+        len += self.lock_time.encode(w)?;
+        Ok(len)
+    }
+
+    /// Returns a copy of the transaction with all script sigs and witnesses cleared.
+    ///
+    /// Useful to re-sign the same inputs and outputs, e.g. with a different sighash type or
+    /// after a failed signing round.
+    pub fn unsigned_clone(&self) -> Self {
+        Self {
+            version: self.version,
+            lock_time: self.lock_time,
+            input: self
+                .input
+                .iter()
+                .map(|txin| TxIn {
+                    script_sig: ScriptBuf::default(),
+                    witness: Witness::default(),
+                    ..txin.clone()
+                })
+                .collect(),
+            output: self.output.clone(),
+        }
+    }
+
     // Legacy
+    /// Returns the transaction followed by the sighash type, as is.
+    ///
+    /// This is the legacy sighash preimage only for `SIGHASH_ALL` and if the caller already
+    /// placed the script code in the script sig of the signed input and emptied the others.
+    #[deprecated(
+        note = "doesn't know which input is signed, use `build_for_signing_legacy_input` instead"
+    )]
     pub fn build_for_signing_legacy(&self, sighash_type: EcdsaSighashType) -> Vec<u8> {
         let mut buffer = Vec::new();
 
@@ -58,6 +239,122 @@ impl BitcoinTransaction {
         buffer
     }
 
+    /// Returns the double SHA-256 of [`Self::build_for_signing_legacy`], the digest signed by
+    /// ECDSA.
+    #[deprecated(
+        note = "doesn't know which input is signed, use `hash_for_signing_legacy_input` instead"
+    )]
+    pub fn hash_for_signing_legacy(&self, sighash_type: EcdsaSighashType) -> [u8; 32] {
+        #[allow(deprecated)]
+        sha256d_array(&self.build_for_signing_legacy(sighash_type))
+    }
+
+    /// Returns the legacy sighash preimage of the input at `input_index`, spending an output
+    /// locked by `script_code` (e.g. the P2PKH script pubkey, or the P2SH redeem script).
+    ///
+    /// The signed copy of the transaction follows the consensus rules of `sighash_type`: the
+    /// script sigs of the other inputs are emptied, their sequences are zeroed for `NONE` and
+    /// `SINGLE`, outputs are removed for `NONE` or blanked after `input_index` for `SINGLE`, and
+    /// the other inputs are removed for `ANYONECANPAY`.
+    ///
+    /// Panics if `input_index` is out of range, or for `SINGLE` without an output at
+    /// `input_index`: that signature commits to no preimage, see
+    /// [`Self::hash_for_signing_legacy_input`].
+    pub fn build_for_signing_legacy_input(
+        &self,
+        input_index: usize,
+        script_code: &ScriptBuf,
+        sighash_type: EcdsaSighashType,
+    ) -> Vec<u8> {
+        assert!(
+            input_index < self.input.len(),
+            "Input index {} out of range",
+            input_index
+        );
+        assert!(
+            !sighash_type.is_single() || input_index < self.output.len(),
+            "SIGHASH_SINGLE needs an output at index {}",
+            input_index
+        );
+
+        let signed_input = |txin: &TxIn| TxIn {
+            script_sig: script_code.clone(),
+            witness: Witness::default(),
+            ..txin.clone()
+        };
+        let input: Vec<TxIn> = if sighash_type.anyone_can_pay() {
+            vec![signed_input(&self.input[input_index])]
+        } else {
+            self.input
+                .iter()
+                .enumerate()
+                .map(|(i, txin)| {
+                    if i == input_index {
+                        return signed_input(txin);
+                    }
+                    TxIn {
+                        script_sig: ScriptBuf::default(),
+                        sequence: if sighash_type.is_none() || sighash_type.is_single() {
+                            Sequence::ZERO
+                        } else {
+                            txin.sequence
+                        },
+                        witness: Witness::default(),
+                        ..txin.clone()
+                    }
+                })
+                .collect()
+        };
+
+        let output: Vec<TxOut> = if sighash_type.is_none() {
+            vec![]
+        } else if sighash_type.is_single() {
+            // Outputs before the signed one are blanked: a value of -1 and an empty script.
+            let blank = TxOut {
+                value: Amount::MAX,
+                script_pubkey: ScriptBuf::default(),
+            };
+            let mut output = vec![blank; input_index];
+            output.push(self.output[input_index].clone());
+            output
+        } else {
+            self.output.clone()
+        };
+
+        let mut buffer = Vec::new();
+        let tx = Self {
+            version: self.version,
+            lock_time: self.lock_time,
+            input,
+            output,
+        };
+        let _ = tx.encode_without_witnesses(&mut buffer);
+        buffer.extend_from_slice(&(sighash_type as u32).to_le_bytes());
+
+        buffer
+    }
+
+    /// Returns the double SHA-256 of [`Self::build_for_signing_legacy_input`], the digest
+    /// signed by ECDSA.
+    ///
+    /// For `SINGLE` without an output at `input_index`, consensus signs the number 1 instead
+    /// (the "SIGHASH_SINGLE bug"): such a signature can be replayed to spend the input in any
+    /// transaction, so it should not be requested.
+    pub fn hash_for_signing_legacy_input(
+        &self,
+        input_index: usize,
+        script_code: &ScriptBuf,
+        sighash_type: EcdsaSighashType,
+    ) -> [u8; 32] {
+        if sighash_type.is_single() && input_index >= self.output.len() {
+            let mut one = [0u8; 32];
+            one[0] = 1;
+            return one;
+        }
+
+        sha256d_array(&self.build_for_signing_legacy_input(input_index, script_code, sighash_type))
+    }
+
     pub fn build_with_script_sig(
         &mut self,
         input_index: usize,
@@ -68,7 +365,7 @@ impl BitcoinTransaction {
             TransactionType::P2PKH | TransactionType::P2SH => {
                 self.input[input_index].script_sig = script_sig;
             }
-            TransactionType::P2WPKH | TransactionType::P2WSH => {
+            TransactionType::P2WPKH | TransactionType::P2WSH | TransactionType::P2TR => {
                 panic!("Use build_with_witness for SegWit transactions");
             }
         }
@@ -79,7 +376,91 @@ impl BitcoinTransaction {
         buffer
     }
 
+    /// Same as [`Self::build_with_script_sig`], but first checks the structure of the script
+    /// sig: it must only contain data pushes and, for P2PKH, exactly a strictly DER encoded
+    /// signature followed by its sighash type byte and a public key.
+    pub fn try_build_with_script_sig(
+        &mut self,
+        input_index: usize,
+        script_sig: ScriptBuf,
+        tx_type: TransactionType,
+    ) -> Result<Vec<u8>, SignatureError> {
+        if input_index >= self.input.len() {
+            return Err(SignatureError::InputIndexOutOfRange(input_index));
+        }
+
+        let pushes = parse_pushes(&script_sig.0).ok_or(SignatureError::MalformedScriptSig)?;
+
+        match tx_type {
+            TransactionType::P2PKH => {
+                let [signature, public_key] = pushes.as_slice() else {
+                    return Err(SignatureError::WrongItemCount {
+                        expected: 2,
+                        actual: pushes.len(),
+                    });
+                };
+                check_ecdsa_signature(signature)?;
+                check_public_key(public_key, true)?;
+            }
+            TransactionType::P2SH => {
+                // The last push is the redeem script, whose content is not inspected.
+                if pushes.is_empty() {
+                    return Err(SignatureError::WrongItemCount {
+                        expected: 1,
+                        actual: 0,
+                    });
+                }
+            }
+            TransactionType::P2WPKH | TransactionType::P2WSH | TransactionType::P2TR => {
+                return Err(SignatureError::WrongSpendType);
+            }
+        }
+
+        Ok(self.build_with_script_sig(input_index, script_sig, tx_type))
+    }
+
+    /// Sets the script sig of the P2SH multisig input at `input_index` from the signatures of
+    /// its `redeem_script`, see [`ScriptBuf::new_multisig`].
+    ///
+    /// `signatures` are `(public_key, signature)` pairs, in any order: they are placed in the
+    /// order of their keys in the redeem script, as `OP_CHECKMULTISIG` requires, after the
+    /// extra `OP_0` it pops and before the redeem script itself. Exactly as many signatures as
+    /// the script requires must be given, each strictly DER encoded and followed by its sighash
+    /// type byte.
+    ///
+    /// The signed digest is [`Self::hash_for_signing_legacy_input`] with the redeem script as
+    /// the script code.
+    pub fn build_with_p2sh_multisig<K: AsRef<[u8]>, S: AsRef<[u8]>>(
+        &mut self,
+        input_index: usize,
+        redeem_script: &ScriptBuf,
+        signatures: &[(K, S)],
+    ) -> Result<Vec<u8>, SignatureError> {
+        if input_index >= self.input.len() {
+            return Err(SignatureError::InputIndexOutOfRange(input_index));
+        }
+
+        // The redeem script is pushed, so it is limited to the size of a stack element.
+        if redeem_script.0.len() > MAX_SCRIPT_ELEMENT_SIZE {
+            return Err(SignatureError::InvalidRedeemScript);
+        }
+        let signatures = order_multisig_signatures(redeem_script, signatures)?;
+
+        let script_sig = signatures
+            .into_iter()
+            .fold(
+                ScriptBuilder::new().push_opcode(OP_0),
+                |builder, signature| builder.push_slice(signature),
+            )
+            .push_slice(&redeem_script.0)
+            .into_script();
+
+        Ok(self.build_with_script_sig(input_index, script_sig, TransactionType::P2SH))
+    }
+
     // Segwit
+    /// Returns the BIP-143 sighash preimage of the input at `input_index`, spending `value`
+    /// satoshis locked by `script_code`, for any sighash type.
     pub fn build_for_signing_segwit(
         &self,
         sighash_type: EcdsaSighashType,
@@ -87,13 +468,69 @@ impl BitcoinTransaction {
         script_code: &ScriptBuf,
         value: u64,
     ) -> Vec<u8> {
-        if self.version != Version::Two {
-            panic!("SegWit transactions must be version 2");
+        self.build_for_signing_segwit_with_hashes(
+            &SegwitHashes::new(self),
+            sighash_type,
+            input_index,
+            script_code,
+            value,
+        )
+    }
+
+    /// Same as [`Self::build_for_signing_segwit`], with the hashes of the inputs and outputs
+    /// computed beforehand.
+    pub(crate) fn build_for_signing_segwit_with_hashes(
+        &self,
+        hashes: &SegwitHashes,
+        sighash_type: EcdsaSighashType,
+        input_index: usize,
+        script_code: &ScriptBuf,
+        value: u64,
+    ) -> Vec<u8> {
+        if self.version < Version::TWO {
+            panic!("SegWit transactions must be version 2 or higher");
         }
 
+        self.segwit_preimage(hashes, sighash_type, input_index, script_code, value)
+    }
+
+    /// Returns the double SHA-256 of the BIP-143 sighash preimage whatever the version of the
+    /// transaction, for checking inputs of version 1 transactions spending SegWit outputs.
+    #[cfg(feature = "verify")]
+    pub(crate) fn hash_for_signing_segwit_any_version(
+        &self,
+        sighash_type: EcdsaSighashType,
+        input_index: usize,
+        script_code: &ScriptBuf,
+        value: u64,
+    ) -> [u8; 32] {
+        sha256d_array(&self.segwit_preimage(
+            &SegwitHashes::new(self),
+            sighash_type,
+            input_index,
+            script_code,
+            value,
+        ))
+    }
+
+    fn segwit_preimage(
+        &self,
+        hashes: &SegwitHashes,
+        sighash_type: EcdsaSighashType,
+        input_index: usize,
+        script_code: &ScriptBuf,
+        value: u64,
+    ) -> Vec<u8> {
         let mut buffer = Vec::new();
 
-        self.encode_for_sighash_for_segwig(&mut buffer, input_index, script_code, value);
+        self.encode_for_sighash_for_segwig(
+            &mut buffer,
+            hashes,
+            sighash_type,
+            input_index,
+            script_code,
+            value,
+        );
 
         // Sighash type
         buffer.extend_from_slice(&(sighash_type as u32).to_le_bytes());
@@ -101,6 +538,45 @@ impl BitcoinTransaction {
         buffer
     }
 
+    /// Returns the double SHA-256 of [`Self::build_for_signing_segwit`], the digest signed by
+    /// ECDSA. Taproot inputs sign [`Self::build_for_signing_taproot`] as is.
+    pub fn hash_for_signing_segwit(
+        &self,
+        sighash_type: EcdsaSighashType,
+        input_index: usize,
+        script_code: &ScriptBuf,
+        value: u64,
+    ) -> [u8; 32] {
+        sha256d_array(&self.build_for_signing_segwit(sighash_type, input_index, script_code, value))
+    }
+
+    /// Returns the BIP-143 sighash preimage of the P2WSH input at `input_index`, spending
+    /// `value` satoshis locked by the hash of `witness_script`.
+    ///
+    /// The script code of a P2WSH input is the full witness script, serialized with its length
+    /// prefix like any script.
+    pub fn build_for_signing_p2wsh(
+        &self,
+        sighash_type: EcdsaSighashType,
+        input_index: usize,
+        witness_script: &ScriptBuf,
+        value: u64,
+    ) -> Vec<u8> {
+        self.build_for_signing_segwit(sighash_type, input_index, witness_script, value)
+    }
+
+    /// Returns the double SHA-256 of [`Self::build_for_signing_p2wsh`], the digest signed by
+    /// ECDSA.
+    pub fn hash_for_signing_p2wsh(
+        &self,
+        sighash_type: EcdsaSighashType,
+        input_index: usize,
+        witness_script: &ScriptBuf,
+        value: u64,
+    ) -> [u8; 32] {
+        self.hash_for_signing_segwit(sighash_type, input_index, witness_script, value)
+    }
+
     pub fn build_with_witness(
         &mut self,
         input_index: usize,
@@ -108,7 +584,7 @@ impl BitcoinTransaction {
         tx_type: TransactionType,
     ) -> Vec<u8> {
         match tx_type {
-            TransactionType::P2WPKH | TransactionType::P2WSH => {
+            TransactionType::P2WPKH | TransactionType::P2WSH | TransactionType::P2TR => {
                 self.input[input_index].witness = Witness::from_slice(&witness);
             }
             TransactionType::P2PKH | TransactionType::P2SH => {
@@ -125,39 +601,274 @@ impl BitcoinTransaction {
         buffer
     }
 
+    /// Sets the witness of the P2WSH multisig input at `input_index` from the signatures of
+    /// its `witness_script`, see [`ScriptBuf::new_multisig`] and [`ScriptBuf::to_p2wsh`].
+    ///
+    /// Like [`Self::build_with_p2sh_multisig`], the `(public_key, signature)` pairs are placed
+    /// in the order of their keys in the witness script, after an empty item for the extra
+    /// element popped by `OP_CHECKMULTISIG`, and the witness script comes last.
+    ///
+    /// The signed digest is [`Self::hash_for_signing_p2wsh`].
+    pub fn build_with_p2wsh_multisig<K: AsRef<[u8]>, S: AsRef<[u8]>>(
+        &mut self,
+        input_index: usize,
+        witness_script: &ScriptBuf,
+        signatures: &[(K, S)],
+    ) -> Result<Vec<u8>, SignatureError> {
+        if input_index >= self.input.len() {
+            return Err(SignatureError::InputIndexOutOfRange(input_index));
+        }
+
+        if witness_script.0.len() > MAX_SCRIPT_SIZE {
+            return Err(SignatureError::InvalidRedeemScript);
+        }
+        let signatures = order_multisig_signatures(witness_script, signatures)?;
+
+        let mut witness = vec![vec![]];
+        witness.extend(signatures.into_iter().map(<[u8]>::to_vec));
+        witness.push(witness_script.0.clone());
+
+        Ok(self.build_with_witness(input_index, witness, TransactionType::P2WSH))
+    }
+
+    /// Same as [`Self::build_with_witness`], but first checks the structure of the witness:
+    /// for P2WPKH it must be exactly a strictly DER encoded signature followed by its sighash
+    /// type byte and a compressed public key, for P2WSH it must at least hold the witness script,
+    /// and for P2TR it must be a Schnorr signature (key path) or end with a well-formed control
+    /// block (script path), optionally followed by an annex.
+    pub fn try_build_with_witness(
+        &mut self,
+        input_index: usize,
+        witness: Vec<Vec<u8>>,
+        tx_type: TransactionType,
+    ) -> Result<Vec<u8>, SignatureError> {
+        if input_index >= self.input.len() {
+            return Err(SignatureError::InputIndexOutOfRange(input_index));
+        }
+
+        match tx_type {
+            TransactionType::P2WPKH => {
+                let [signature, public_key] = witness.as_slice() else {
+                    return Err(SignatureError::WrongItemCount {
+                        expected: 2,
+                        actual: witness.len(),
+                    });
+                };
+                check_ecdsa_signature(signature)?;
+                check_public_key(public_key, false)?;
+            }
+            TransactionType::P2WSH => {
+                if witness.is_empty() {
+                    return Err(SignatureError::WrongItemCount {
+                        expected: 1,
+                        actual: 0,
+                    });
+                }
+            }
+            TransactionType::P2TR => {
+                let stack = match witness.as_slice() {
+                    [stack @ .., annex]
+                        if !stack.is_empty() && annex.first() == Some(&TAPROOT_ANNEX_PREFIX) =>
+                    {
+                        stack
+                    }
+                    stack => stack,
+                };
+                match stack {
+                    [signature] => check_schnorr_signature(signature)?,
+                    // Script path: the script inputs, the script and the control block.
+                    [.., _script, control_block] => {
+                        ControlBlock::from_slice(control_block)?;
+                    }
+                    [] => {
+                        return Err(SignatureError::WrongItemCount {
+                            expected: 1,
+                            actual: 0,
+                        })
+                    }
+                }
+            }
+            TransactionType::P2PKH | TransactionType::P2SH => {
+                return Err(SignatureError::WrongSpendType);
+            }
+        }
+
+        Ok(self.build_with_witness(input_index, witness, tx_type))
+    }
+
+    // Taproot
+    /// Returns the BIP-341 signature hash of a key path spend of the input at `input_index`.
+    ///
+    /// Unlike the legacy and SegWit payloads, this is the final 32-byte hash: BIP-340 Schnorr
+    /// signatures sign it as is. `prevouts` are the outputs spent by every input of the
+    /// transaction, in order, and `annex` is the annex which will be appended to the witness,
+    /// if any, starting with [`TAPROOT_ANNEX_PREFIX`].
+    pub fn build_for_signing_taproot(
+        &self,
+        input_index: usize,
+        prevouts: &[TxOut],
+        sighash_type: TapSighashType,
+        annex: Option<&[u8]>,
+    ) -> [u8; 32] {
+        self.taproot_sighash(input_index, prevouts, sighash_type, annex, None)
+    }
+
+    /// Returns the BIP-342 signature hash of a script path spend of the input at `input_index`,
+    /// for signatures checked by the tapscript leaf with hash `leaf_hash`.
+    ///
+    /// The arguments are the same as for [`Self::build_for_signing_taproot`]. The script is
+    /// assumed not to execute `OP_CODESEPARATOR`.
+    pub fn build_for_signing_taproot_script_path(
+        &self,
+        input_index: usize,
+        prevouts: &[TxOut],
+        sighash_type: TapSighashType,
+        annex: Option<&[u8]>,
+        leaf_hash: &TapLeafHash,
+    ) -> [u8; 32] {
+        self.taproot_sighash(input_index, prevouts, sighash_type, annex, Some(leaf_hash))
+    }
+
+    fn taproot_sighash(
+        &self,
+        input_index: usize,
+        prevouts: &[TxOut],
+        sighash_type: TapSighashType,
+        annex: Option<&[u8]>,
+        leaf_hash: Option<&TapLeafHash>,
+    ) -> [u8; 32] {
+        assert!(
+            input_index < self.input.len(),
+            "Input index {} out of range",
+            input_index
+        );
+        assert_eq!(
+            prevouts.len(),
+            self.input.len(),
+            "Taproot sighashes need the outputs spent by every input"
+        );
+        if let Some(annex) = annex {
+            assert_eq!(
+                annex.first(),
+                Some(&TAPROOT_ANNEX_PREFIX),
+                "The annex must start with 0x50"
+            );
+        }
+
+        let single = matches!(
+            sighash_type,
+            TapSighashType::Single | TapSighashType::SinglePlusAnyoneCanPay
+        );
+        let none = matches!(
+            sighash_type,
+            TapSighashType::None | TapSighashType::NonePlusAnyoneCanPay
+        );
+        if single {
+            assert!(
+                input_index < self.output.len(),
+                "SIGHASH_SINGLE requires an output with the same index as the input"
+            );
+        }
+
+        // Epoch
+        let mut buffer = vec![0x00];
+
+        // Control
+        buffer.push(sighash_type as u8);
+        self.version.encode(&mut buffer).unwrap();
+        self.lock_time.encode(&mut buffer).unwrap();
+
+        // Transaction data
+        if !sighash_type.anyone_can_pay() {
+            let mut prevouts_data = Vec::new();
+            let mut amounts = Vec::new();
+            let mut script_pubkeys = Vec::new();
+            let mut sequences = Vec::new();
+            for (input, prevout) in self.input.iter().zip(prevouts) {
+                input.previous_output.encode(&mut prevouts_data).unwrap();
+                prevout.value.encode(&mut amounts).unwrap();
+                prevout.script_pubkey.encode(&mut script_pubkeys).unwrap();
+                input.sequence.encode(&mut sequences).unwrap();
+            }
+            buffer.extend_from_slice(&Sha256::digest(&prevouts_data));
+            buffer.extend_from_slice(&Sha256::digest(&amounts));
+            buffer.extend_from_slice(&Sha256::digest(&script_pubkeys));
+            buffer.extend_from_slice(&Sha256::digest(&sequences));
+        }
+        if !single && !none {
+            let mut outputs = Vec::new();
+            for output in &self.output {
+                output.encode(&mut outputs).unwrap();
+            }
+            buffer.extend_from_slice(&Sha256::digest(&outputs));
+        }
+
+        // Data about this input
+        let spend_type = (u8::from(leaf_hash.is_some()) << 1) | u8::from(annex.is_some());
+        buffer.push(spend_type);
+        if sighash_type.anyone_can_pay() {
+            let input = &self.input[input_index];
+            let prevout = &prevouts[input_index];
+            input.previous_output.encode(&mut buffer).unwrap();
+            prevout.value.encode(&mut buffer).unwrap();
+            prevout.script_pubkey.encode(&mut buffer).unwrap();
+            input.sequence.encode(&mut buffer).unwrap();
+        } else {
+            buffer.extend_from_slice(&(input_index as u32).to_le_bytes());
+        }
+        if let Some(annex) = annex {
+            let mut annex_data = Vec::new();
+            annex.to_vec().encode(&mut annex_data).unwrap();
+            buffer.extend_from_slice(&Sha256::digest(&annex_data));
+        }
+
+        // Data about this output
+        if single {
+            let mut output = Vec::new();
+            self.output[input_index].encode(&mut output).unwrap();
+            buffer.extend_from_slice(&Sha256::digest(&output));
+        }
+
+        // Script path extension (BIP-342)
+        if let Some(leaf_hash) = leaf_hash {
+            buffer.extend_from_slice(&leaf_hash.0);
+            buffer.push(TAPROOT_KEY_VERSION_0);
+            buffer.extend_from_slice(&NO_CODE_SEPARATOR.to_le_bytes());
+        }
+
+        tagged_hash("TapSighash", &[&buffer])
+    }
+
+    /// Writes the BIP-143 preimage of the input at `input_index`, without the trailing sighash
+    /// type. The hashes of the other inputs and outputs are replaced by zeros when
+    /// `sighash_type` doesn't commit to them.
     fn encode_for_sighash_for_segwig(
         &self,
         buffer: &mut Vec<u8>,
+        hashes: &SegwitHashes,
+        sighash_type: EcdsaSighashType,
         input_index: usize,
         script_code: &ScriptBuf,
         value: u64,
     ) {
+        let zero_hash = [0u8; 32];
+
         // Version
         self.version.encode(buffer).unwrap();
 
-        let has_witness = self.input.iter().any(|input| !input.witness.is_empty());
-
-        if has_witness {
-            // Marker and Flag
-            buffer.push(SEGWIT_MARKER);
-            buffer.push(SEGWIT_FLAG);
-        }
-
         // Hash prevouts
-        let mut prevouts = Vec::new();
-        for input in &self.input {
-            input.previous_output.encode(&mut prevouts).unwrap();
+        if sighash_type.anyone_can_pay() {
+            buffer.extend_from_slice(&zero_hash);
+        } else {
+            buffer.extend_from_slice(&hashes.prevouts);
         }
-        let prevouts_hash = sha256d(&prevouts);
-        buffer.extend_from_slice(&prevouts_hash);
 
         // Hash sequences
-        let mut sequences = Vec::new();
-        for input in &self.input {
-            input.sequence.encode(&mut sequences).unwrap();
+        if sighash_type.anyone_can_pay() || sighash_type.is_none() || sighash_type.is_single() {
+            buffer.extend_from_slice(&zero_hash);
+        } else {
+            buffer.extend_from_slice(&hashes.sequences);
         }
-        let sequences_hash = sha256d(&sequences);
-        buffer.extend_from_slice(&sequences_hash);
 
         // Outpoint
         self.input[input_index]
@@ -175,12 +886,21 @@ impl BitcoinTransaction {
         self.input[input_index].sequence.encode(buffer).unwrap();
 
         // Hash outputs
-        let mut outputs = Vec::new();
-        for output in &self.output {
-            output.encode(&mut outputs).unwrap();
+        if sighash_type.is_single() {
+            match self.output.get(input_index) {
+                Some(output) => {
+                    let mut encoded = Vec::new();
+                    output.encode(&mut encoded).unwrap();
+                    buffer.extend_from_slice(&sha256d(&encoded));
+                }
+                // Unlike legacy sighashes, a missing output simply commits to no output.
+                None => buffer.extend_from_slice(&zero_hash),
+            }
+        } else if sighash_type.is_none() {
+            buffer.extend_from_slice(&zero_hash);
+        } else {
+            buffer.extend_from_slice(&hashes.outputs);
         }
-        let outputs_hash = sha256d(&outputs);
-        buffer.extend_from_slice(&outputs_hash);
 
         // Locktime
         self.lock_time.encode(buffer).unwrap();
@@ -196,6 +916,36 @@ impl BitcoinTransaction {
     }
 }
 
+/// The BIP-143 hashes of the outpoints, sequences and outputs of a transaction, shared by the
+/// sighashes of all its inputs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct SegwitHashes {
+    prevouts: [u8; 32],
+    sequences: [u8; 32],
+    outputs: [u8; 32],
+}
+
+impl SegwitHashes {
+    pub(crate) fn new(tx: &BitcoinTransaction) -> Self {
+        let mut prevouts = Vec::new();
+        let mut sequences = Vec::new();
+        for input in &tx.input {
+            input.previous_output.encode(&mut prevouts).unwrap();
+            input.sequence.encode(&mut sequences).unwrap();
+        }
+        let mut outputs = Vec::new();
+        for output in &tx.output {
+            output.encode(&mut outputs).unwrap();
+        }
+
+        Self {
+            prevouts: sha256d_array(&prevouts),
+            sequences: sha256d_array(&sequences),
+            outputs: sha256d_array(&outputs),
+        }
+    }
+}
+
 impl Encodable for Vec<TxIn> {
     fn encode<W: Write + ?Sized>(&self, w: &mut W) -> core::result::Result<usize, std::io::Error> {
         let mut len = 0;
@@ -281,11 +1031,372 @@ impl Encodable for BitcoinTransaction {
         Ok(len)
     }
 }
+
+impl Decodable for BitcoinTransaction {
+    fn decode_from_finite_reader<R: BufRead + ?Sized>(r: &mut R) -> Result<Self, std::io::Error> {
+        let version = Version::decode_from_finite_reader(r)?;
+        let input = Vec::<TxIn>::decode_from_finite_reader(r)?;
+
+        // Legacy serialization, unless the input count is the segwit marker.
+        if !input.is_empty() {
+            return Ok(Self {
+                version,
+                input,
+                output: Vec::<TxOut>::decode_from_finite_reader(r)?,
+                lock_time: LockTime::decode_from_finite_reader(r)?,
+            });
+        }
+
+        // BIP-144 (segwit) serialization: marker, flag, inputs, outputs and witnesses.
+        let flag = u8::decode_from_finite_reader(r)?;
+        if flag != SEGWIT_FLAG {
+            return Err(std::io::Error::other(format!(
+                "unsupported segwit flag: {:#04x}",
+                flag
+            )));
+        }
+        let mut input = Vec::<TxIn>::decode_from_finite_reader(r)?;
+        let output = Vec::<TxOut>::decode_from_finite_reader(r)?;
+        for txin in input.iter_mut() {
+            txin.witness = Witness::decode_from_finite_reader(r)?;
+        }
+        // A transaction with inputs but no witness must use the legacy serialization.
+        if !input.is_empty() && input.iter().all(|txin| txin.witness.is_empty()) {
+            return Err(std::io::Error::other(
+                "segwit flag set but no witness present",
+            ));
+        }
+
+        Ok(Self {
+            version,
+            input,
+            output,
+            lock_time: LockTime::decode_from_finite_reader(r)?,
+        })
+    }
+}
+
+/// Splits a push-only script into its pushed items, or returns `None` if the script contains
+/// any other opcode or is truncated.
+pub(crate) fn parse_pushes(script: &[u8]) -> Option<Vec<&[u8]>> {
+    const OP_PUSHDATA1: u8 = 0x4c;
+    const OP_PUSHDATA2: u8 = 0x4d;
+    const OP_PUSHDATA4: u8 = 0x4e;
+    const OP_1NEGATE: u8 = 0x4f;
+    const OP_1: u8 = 0x51;
+    const OP_16: u8 = 0x60;
+
+    let mut pushes = Vec::new();
+    let mut rest = script;
+
+    while let Some((&opcode, tail)) = rest.split_first() {
+        let (len, tail) = match opcode {
+            0x00..=0x4b => (opcode as usize, tail),
+            OP_PUSHDATA1 => (*tail.first()? as usize, tail.get(1..)?),
+            OP_PUSHDATA2 => (
+                u16::from_le_bytes(tail.get(..2)?.try_into().ok()?) as usize,
+                tail.get(2..)?,
+            ),
+            OP_PUSHDATA4 => (
+                u32::from_le_bytes(tail.get(..4)?.try_into().ok()?) as usize,
+                tail.get(4..)?,
+            ),
+            // Small integers, pushed as the opcode itself.
+            OP_1NEGATE | OP_1..=OP_16 => {
+                pushes.push(&rest[..1]);
+                rest = tail;
+                continue;
+            }
+            _ => return None,
+        };
+
+        pushes.push(tail.get(..len)?);
+        rest = &tail[len..];
+    }
+
+    Some(pushes)
+}
+
+/// Returns the `(public_key, signature)` pairs of a multisig spend of `script` as the
+/// signatures `OP_CHECKMULTISIG` expects: exactly as many as required, in the order of their
+/// keys in the script.
+fn order_multisig_signatures<'a, K: AsRef<[u8]>, S: AsRef<[u8]>>(
+    script: &ScriptBuf,
+    signatures: &'a [(K, S)],
+) -> Result<Vec<&'a [u8]>, SignatureError> {
+    let (required, public_keys) = script
+        .parse_multisig()
+        .ok_or(SignatureError::InvalidRedeemScript)?;
+    if signatures.len() != required as usize {
+        return Err(SignatureError::WrongItemCount {
+            expected: required as usize,
+            actual: signatures.len(),
+        });
+    }
+
+    let mut ordered = vec![None; public_keys.len()];
+    for (public_key, signature) in signatures {
+        check_ecdsa_signature(signature.as_ref())?;
+        // The same key may appear more than once in the script, take its first free slot.
+        let position = public_keys
+            .iter()
+            .zip(&ordered)
+            .position(|(key, slot)| *key == public_key.as_ref() && slot.is_none())
+            .ok_or(SignatureError::UnknownPublicKey)?;
+        ordered[position] = Some(signature.as_ref());
+    }
+
+    Ok(ordered.into_iter().flatten().collect())
+}
+
+/// Checks that `signature` is a strictly DER encoded ECDSA signature followed by a standard
+/// sighash type byte.
+fn check_ecdsa_signature(signature: &[u8]) -> Result<(), SignatureError> {
+    if !is_valid_signature_encoding(signature) {
+        // A valid DER signature which only lacks the trailing sighash type byte.
+        let mut with_sighash = signature.to_vec();
+        with_sighash.push(EcdsaSighashType::All as u8);
+        if is_valid_signature_encoding(&with_sighash) {
+            return Err(SignatureError::MissingSighashType);
+        }
+        return Err(SignatureError::InvalidDer);
+    }
+
+    let sighash_type = signature[signature.len() - 1];
+    // SIGHASH_ALL, SIGHASH_NONE or SIGHASH_SINGLE, optionally with SIGHASH_ANYONECANPAY.
+    if !(0x01..=0x03).contains(&(sighash_type & !0x80)) {
+        return Err(SignatureError::InvalidSighashType(sighash_type));
+    }
+
+    Ok(())
+}
+
+/// Checks that `signature` is a 64-byte Schnorr signature, optionally followed by a sighash type
+/// byte other than [`TapSighashType::Default`] (which must be omitted instead).
+fn check_schnorr_signature(signature: &[u8]) -> Result<(), SignatureError> {
+    match signature.len() {
+        64 => Ok(()),
+        65 => match TapSighashType::from_consensus_u8(signature[64]) {
+            Some(TapSighashType::Default) | None => {
+                Err(SignatureError::InvalidSighashType(signature[64]))
+            }
+            Some(_) => Ok(()),
+        },
+        actual => Err(SignatureError::InvalidLength {
+            component: "signature",
+            expected: 64,
+            actual,
+        }),
+    }
+}
+
+/// Checks that `public_key` is a compressed (or, if allowed, uncompressed) SEC1 public key.
+fn check_public_key(public_key: &[u8], allow_uncompressed: bool) -> Result<(), SignatureError> {
+    match (public_key.len(), public_key.first()) {
+        (33, Some(0x02 | 0x03)) => Ok(()),
+        (65, Some(0x04)) if allow_uncompressed => Ok(()),
+        _ => Err(SignatureError::InvalidPublicKey),
+    }
+}
+
+/// A one-line summary of the transaction, for logs: its ID, the outpoints it spends, and the
+/// value and script of each output.
+impl fmt::Display for BitcoinTransaction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let inputs: Vec<String> = self
+            .input
+            .iter()
+            .map(|tx_in| tx_in.previous_output.to_string())
+            .collect();
+        let outputs: Vec<String> = self
+            .output
+            .iter()
+            .map(|tx_out| format!("{} sat to {}", tx_out.value.to_sat(), tx_out.script_pubkey))
+            .collect();
+
+        write!(
+            f,
+            "Bitcoin {} version {} lock time {}: spends [{}], pays [{}]",
+            self.compute_txid(),
+            self.version.0,
+            self.lock_time.to_u32(),
+            inputs.join(", "),
+            outputs.join(", ")
+        )
+    }
+}
+
+impl CanonicalJson for BitcoinTransaction {
+    fn canonical_value(&self) -> Value {
+        let input: Vec<Value> = self
+            .input
+            .iter()
+            .map(|tx_in| {
+                let mut txid = Vec::with_capacity(32);
+                tx_in
+                    .previous_output
+                    .txid
+                    .encode(&mut txid)
+                    .expect("writing to a Vec cannot fail");
+
+                json!({
+                    "previous_output": {
+                        "txid": hex_value(txid),
+                        "vout": int_value(tx_in.previous_output.vout),
+                    },
+                    "script_sig": hex_value(&tx_in.script_sig.0),
+                    "sequence": int_value(tx_in.sequence.0),
+                    "witness": tx_in.witness.iter().map(hex_value).collect::<Vec<_>>(),
+                })
+            })
+            .collect();
+        let output: Vec<Value> = self
+            .output
+            .iter()
+            .map(|tx_out| {
+                json!({
+                    "value": int_value(tx_out.value.to_sat()),
+                    "script_pubkey": hex_value(&tx_out.script_pubkey.0),
+                })
+            })
+            .collect();
+
+        json!({
+            "version": int_value(self.version.0),
+            "lock_time": int_value(self.lock_time.to_u32()),
+            "input": input,
+            "output": output,
+        })
+    }
+}
+
+impl Validate for BitcoinTransaction {
+    fn validate(&self) -> Vec<ValidationIssue> {
+        self.validate_for_coin(Coin::Bitcoin)
+    }
+}
+
+impl BitcoinTransaction {
+    /// Same as [`Validate::validate`], against the rules of `coin` instead of Bitcoin's: the
+    /// amount limits differ, and witnesses are errors on coins without SegWit.
+    pub fn validate_for_coin(&self, coin: Coin) -> Vec<ValidationIssue> {
+        let mut issues = Vec::new();
+
+        if !self.version.is_standard() {
+            issues.push(ValidationIssue::warning(
+                "version",
+                format!("non-standard version {}", self.version.0),
+            ));
+        }
+
+        if self.input.is_empty() {
+            issues.push(ValidationIssue::error("input", "transaction has no inputs"));
+        }
+        for (i, input) in self.input.iter().enumerate() {
+            if self.input[..i]
+                .iter()
+                .any(|previous| previous.previous_output == input.previous_output)
+            {
+                issues.push(ValidationIssue::error(
+                    format!("input[{}].previous_output", i),
+                    "outpoint is spent twice",
+                ));
+            }
+            issues.extend(
+                input
+                    .script_sig
+                    .standardness_issues(&format!("input[{}].script_sig", i)),
+            );
+            if !coin.supports_segwit() && !input.witness.is_empty() {
+                issues.push(ValidationIssue::error(
+                    format!("input[{}].witness", i),
+                    format!("{:?} does not support witnesses", coin),
+                ));
+            }
+        }
+
+        if self.output.is_empty() {
+            issues.push(ValidationIssue::error(
+                "output",
+                "transaction has no outputs",
+            ));
+        }
+        let mut total = Amount::ZERO;
+        for (i, output) in self.output.iter().enumerate() {
+            if output.value > coin.max_money() {
+                issues.push(ValidationIssue::error(
+                    format!("output[{}].value", i),
+                    "value exceeds the maximum supply",
+                ));
+            } else if output.value == Amount::ZERO && !output.is_anchor() {
+                issues.push(ValidationIssue::warning(
+                    format!("output[{}].value", i),
+                    "zero value output",
+                ));
+            }
+            issues.extend(
+                output
+                    .script_pubkey
+                    .standardness_issues(&format!("output[{}].script_pubkey", i)),
+            );
+            total = total.checked_add(output.value).unwrap_or(Amount::MAX);
+        }
+        if total > coin.max_money() {
+            issues.push(ValidationIssue::error(
+                "output",
+                "total output value exceeds the maximum supply",
+            ));
+        }
+
+        issues
+    }
+}
+
+/// BIP-66 strict DER check of a signature including its trailing sighash type byte, as done by
+/// Bitcoin Core's `IsValidSignatureEncoding`.
+fn is_valid_signature_encoding(sig: &[u8]) -> bool {
+    // Format: 0x30 [total-length] 0x02 [R-length] [R] 0x02 [S-length] [S] [sighash]
+    if sig.len() < 9 || sig.len() > 73 {
+        return false;
+    }
+    if sig[0] != 0x30 || sig[1] as usize != sig.len() - 3 {
+        return false;
+    }
+
+    let len_r = sig[3] as usize;
+    if 5 + len_r >= sig.len() {
+        return false;
+    }
+    let len_s = sig[5 + len_r] as usize;
+    if len_r + len_s + 7 != sig.len() {
+        return false;
+    }
+
+    // R must be a non-empty, non-negative integer without excessive padding.
+    if sig[2] != 0x02 || len_r == 0 || sig[4] & 0x80 != 0 {
+        return false;
+    }
+    if len_r > 1 && sig[4] == 0x00 && sig[5] & 0x80 == 0 {
+        return false;
+    }
+
+    // Same for S.
+    if sig[len_r + 4] != 0x02 || len_s == 0 || sig[len_r + 6] & 0x80 != 0 {
+        return false;
+    }
+    if len_s > 1 && sig[len_r + 6] == 0x00 && sig[len_r + 7] & 0x80 == 0 {
+        return false;
+    }
+
+    true
+}
+
 #[cfg(test)]
 mod tests {
     // Omni imports
     use super::BitcoinTransaction as OmniBitcoinTransaction;
     use super::*;
+    use crate::bitcoin::hash_types::{PubkeyHash, ScriptHash, WPubkeyHash};
     use crate::bitcoin::types::{
         Amount as OmniAmount, EcdsaSighashType as OmniSighashType, Hash as OmniHash,
         OutPoint as OmniOutPoint, ScriptBuf as OmniScriptBuf, Sequence as OmniSequence,
@@ -343,7 +1454,7 @@ mod tests {
 
         // Omni implementation
         let omni_tx = OmniBitcoinTransaction {
-            version: Version::One,
+            version: Version::ONE,
             lock_time: LockTime::from_height(height).unwrap(),
             input: vec![TxIn {
                 previous_output: OmniOutPoint {
@@ -360,10 +1471,26 @@ mod tests {
             }],
         };
 
-        let serialized = omni_tx.build_for_signing_legacy(OmniSighashType::All);
+        let serialized = omni_tx.build_for_signing_legacy_input(
+            0,
+            &OmniScriptBuf::default(),
+            OmniSighashType::All,
+        );
 
         assert_eq!(buffer.len(), serialized.len());
         assert_eq!(buffer, serialized);
+
+        let sighash = sighasher
+            .legacy_signature_hash(0, &ScriptBuf::default(), sighash_type.to_u32())
+            .unwrap();
+        assert_eq!(
+            omni_tx.hash_for_signing_legacy_input(
+                0,
+                &OmniScriptBuf::default(),
+                OmniSighashType::All
+            ),
+            sighash.to_byte_array()
+        );
     }
 
     #[test]
@@ -403,7 +1530,7 @@ mod tests {
 
         // Omni implementation
         let omni_tx = OmniBitcoinTransaction {
-            version: Version::Two,
+            version: Version::TWO,
             lock_time: LockTime::from_height(height).unwrap(),
             input: vec![TxIn {
                 previous_output: OmniOutPoint {
@@ -420,7 +1547,11 @@ mod tests {
             }],
         };
 
-        let serialized = omni_tx.build_for_signing_legacy(OmniSighashType::All);
+        let serialized = omni_tx.build_for_signing_legacy_input(
+            0,
+            &OmniScriptBuf::default(),
+            OmniSighashType::All,
+        );
         println!("serialized BTC Omni: {:?}", serialized);
 
         assert_eq!(buffer.len(), serialized.len());
@@ -465,7 +1596,7 @@ mod tests {
         println!("serialized buffer {:?}", buffer);
         // Omni implementation
         let omni_tx = OmniBitcoinTransaction {
-            version: Version::Two,
+            version: Version::TWO,
             lock_time: LockTime::from_height(height).unwrap(),
             input: vec![TxIn {
                 previous_output: OmniOutPoint {
@@ -492,5 +1623,1045 @@ mod tests {
 
         assert_eq!(buffer.len(), serialized.len());
         assert_eq!(buffer, serialized);
+
+        let sighash = sighasher
+            .p2wsh_signature_hash(0, &ScriptBuf::default(), Amount::from_sat(0), sighash_type)
+            .unwrap();
+        assert_eq!(
+            omni_tx.hash_for_signing_segwit(OmniSighashType::All, 0, &OmniScriptBuf::default(), 0),
+            sighash.to_byte_array()
+        );
+    }
+
+    #[test]
+    fn test_from_bytes_against_rust_bitcoin() {
+        let mut tx = RustBitcoinTransaction {
+            version: RustBitcoinVersion(2),
+            lock_time: RustBitcoinLockTime::from_height(800_000).unwrap(),
+            input: vec![
+                RustBitcoinTxIn {
+                    previous_output: OutPoint {
+                        txid: Txid::from_byte_array([1; 32]),
+                        vout: 3,
+                    },
+                    script_sig: ScriptBuf::from_bytes(vec![0x51]),
+                    sequence: RustBitcoinSequence::MAX,
+                    witness: Witness::default(),
+                },
+                RustBitcoinTxIn {
+                    previous_output: OutPoint {
+                        txid: Txid::from_byte_array([2; 32]),
+                        vout: 0,
+                    },
+                    script_sig: ScriptBuf::default(),
+                    sequence: RustBitcoinSequence::ENABLE_RBF_NO_LOCKTIME,
+                    witness: Witness::default(),
+                },
+            ],
+            output: vec![RustBitcoinTxOut {
+                value: Amount::from_sat(50_000),
+                script_pubkey: ScriptBuf::from_bytes(vec![0x00, 0x14, 0xab]),
+            }],
+        };
+
+        let legacy = bitcoin::consensus::serialize(&tx);
+        let decoded = OmniBitcoinTransaction::from_bytes(&legacy).unwrap();
+        assert_eq!(decoded.input.len(), 2);
+        assert_eq!(decoded.serialize(), legacy);
+
+        tx.input[1].witness = Witness::from_slice(&[vec![0x30; 71], vec![0x02; 33]]);
+        let segwit = bitcoin::consensus::serialize(&tx);
+        let decoded = OmniBitcoinTransaction::deserialize_hex(&hex::encode(&segwit)).unwrap();
+        assert!(decoded.input[0].witness.is_empty());
+        assert_eq!(decoded.input[1].witness.len(), 2);
+        assert_eq!(decoded.serialize(), segwit);
+    }
+
+    #[test]
+    fn test_compute_txid_and_wtxid_against_rust_bitcoin() {
+        let mut tx = RustBitcoinTransaction {
+            version: RustBitcoinVersion(2),
+            lock_time: RustBitcoinLockTime::from_height(0).unwrap(),
+            input: vec![RustBitcoinTxIn {
+                previous_output: OutPoint {
+                    txid: Txid::from_byte_array([7; 32]),
+                    vout: 1,
+                },
+                script_sig: ScriptBuf::default(),
+                sequence: RustBitcoinSequence::MAX,
+                witness: Witness::default(),
+            }],
+            output: vec![RustBitcoinTxOut {
+                value: Amount::from_sat(10_000),
+                script_pubkey: ScriptBuf::from_bytes(vec![0x51]),
+            }],
+        };
+
+        let omni_tx =
+            OmniBitcoinTransaction::from_bytes(&bitcoin::consensus::serialize(&tx)).unwrap();
+        assert_eq!(
+            omni_tx.compute_txid().0.to_string(),
+            tx.compute_txid().to_string()
+        );
+        assert_eq!(
+            omni_tx.compute_wtxid(),
+            Wtxid::from_txid(omni_tx.compute_txid())
+        );
+
+        tx.input[0].witness = Witness::from_slice(&[vec![0x30; 71], vec![0x02; 33]]);
+        let omni_tx =
+            OmniBitcoinTransaction::from_bytes(&bitcoin::consensus::serialize(&tx)).unwrap();
+        assert_eq!(
+            omni_tx.compute_txid().0.to_string(),
+            tx.compute_txid().to_string()
+        );
+        assert_eq!(
+            omni_tx.compute_wtxid().to_string(),
+            tx.compute_wtxid().to_string()
+        );
+        assert_ne!(
+            omni_tx.compute_wtxid(),
+            Wtxid::from_txid(omni_tx.compute_txid())
+        );
+
+        // Without inputs the transaction is serialized as segwit, but the txid still isn't.
+        tx.input.clear();
+        let omni_tx = OmniBitcoinTransaction {
+            input: vec![],
+            ..omni_tx
+        };
+        assert_eq!(
+            omni_tx.compute_txid().0.to_string(),
+            tx.compute_txid().to_string()
+        );
+    }
+
+    #[test]
+    fn test_weight_and_vsize_against_rust_bitcoin() {
+        let mut tx = RustBitcoinTransaction {
+            version: RustBitcoinVersion(2),
+            lock_time: RustBitcoinLockTime::from_height(0).unwrap(),
+            input: vec![
+                RustBitcoinTxIn {
+                    previous_output: OutPoint {
+                        txid: Txid::from_byte_array([5; 32]),
+                        vout: 0,
+                    },
+                    script_sig: ScriptBuf::from_bytes(vec![0x51; 107]),
+                    sequence: RustBitcoinSequence::MAX,
+                    witness: Witness::default(),
+                },
+                RustBitcoinTxIn {
+                    previous_output: OutPoint {
+                        txid: Txid::from_byte_array([6; 32]),
+                        vout: 1,
+                    },
+                    script_sig: ScriptBuf::default(),
+                    sequence: RustBitcoinSequence::MAX,
+                    witness: Witness::default(),
+                },
+            ],
+            output: vec![RustBitcoinTxOut {
+                value: Amount::from_sat(10_000),
+                script_pubkey: ScriptBuf::from_bytes(vec![0x00; 22]),
+            }],
+        };
+
+        for with_witness in [false, true] {
+            if with_witness {
+                tx.input[1].witness = Witness::from_slice(&[vec![0x30; 72], vec![0x02; 33]]);
+            }
+            let omni_tx =
+                OmniBitcoinTransaction::from_bytes(&bitcoin::consensus::serialize(&tx)).unwrap();
+
+            assert_eq!(omni_tx.total_size(), tx.total_size());
+            assert_eq!(omni_tx.base_size(), tx.base_size());
+            for (omni_txin, txin) in omni_tx.input.iter().zip(&tx.input) {
+                assert_eq!(
+                    omni_txin.encoded_size(),
+                    bitcoin::consensus::serialize(txin).len()
+                );
+                assert_eq!(
+                    omni_txin.witness.encoded_size(),
+                    bitcoin::consensus::serialize(&txin.witness).len()
+                );
+            }
+            assert_eq!(
+                omni_tx.output[0].encoded_size(),
+                bitcoin::consensus::serialize(&tx.output[0]).len()
+            );
+            assert_eq!(omni_tx.weight(), tx.weight().to_wu());
+            assert_eq!(omni_tx.vsize(), tx.vsize() as u64);
+            assert_eq!(
+                omni_tx.fee_for_rate(3),
+                Some(OmniAmount::from_sat(tx.vsize() as u64 * 3))
+            );
+        }
+
+        let omni_tx =
+            OmniBitcoinTransaction::from_bytes(&bitcoin::consensus::serialize(&tx)).unwrap();
+        assert_eq!(omni_tx.fee_for_rate(u64::MAX), None);
+    }
+
+    #[test]
+    fn test_build_for_signing_legacy_input_against_rust_bitcoin() {
+        let input = |n: u8| RustBitcoinTxIn {
+            previous_output: OutPoint {
+                txid: Txid::from_byte_array([n; 32]),
+                vout: n.into(),
+            },
+            script_sig: ScriptBuf::from_bytes(vec![0x51; n as usize]),
+            sequence: RustBitcoinSequence(0xffff_fff0 + u32::from(n)),
+            witness: Witness::default(),
+        };
+        let output = |n: u64| RustBitcoinTxOut {
+            value: Amount::from_sat(n * 1000),
+            script_pubkey: ScriptBuf::from_bytes(vec![0x6a, n as u8]),
+        };
+        let tx = RustBitcoinTransaction {
+            version: RustBitcoinVersion(1),
+            lock_time: RustBitcoinLockTime::from_height(100).unwrap(),
+            input: vec![input(1), input(2), input(3)],
+            output: vec![output(1), output(2)],
+        };
+        let omni_tx =
+            OmniBitcoinTransaction::from_bytes(&bitcoin::consensus::serialize(&tx)).unwrap();
+
+        let script_code = ScriptBuf::from_bytes(vec![0x76, 0xa9, 0x14, 0x88, 0xac]);
+        let omni_script_code = OmniScriptBuf(script_code.to_bytes());
+        let sighasher = SighashCache::new(&tx);
+
+        for sighash_type in [
+            OmniSighashType::All,
+            OmniSighashType::None,
+            OmniSighashType::Single,
+            OmniSighashType::AllPlusAnyoneCanPay,
+            OmniSighashType::NonePlusAnyoneCanPay,
+            OmniSighashType::SinglePlusAnyoneCanPay,
+        ] {
+            for input_index in 0..tx.input.len() {
+                let expected = sighasher
+                    .legacy_signature_hash(input_index, &script_code, sighash_type as u32)
+                    .unwrap();
+
+                assert_eq!(
+                    omni_tx.hash_for_signing_legacy_input(
+                        input_index,
+                        &omni_script_code,
+                        sighash_type
+                    ),
+                    expected.to_byte_array(),
+                    "{:?} of input {}",
+                    sighash_type,
+                    input_index
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_build_for_signing_segwit_sighash_types_against_rust_bitcoin() {
+        let input = |n: u8| RustBitcoinTxIn {
+            previous_output: OutPoint {
+                txid: Txid::from_byte_array([n; 32]),
+                vout: n.into(),
+            },
+            script_sig: ScriptBuf::default(),
+            sequence: RustBitcoinSequence(0xffff_fff0 + u32::from(n)),
+            witness: Witness::default(),
+        };
+        let output = |n: u64| RustBitcoinTxOut {
+            value: Amount::from_sat(n * 1000),
+            script_pubkey: ScriptBuf::from_bytes(vec![0x00, 0x14, n as u8]),
+        };
+        let mut tx = RustBitcoinTransaction {
+            version: RustBitcoinVersion(2),
+            lock_time: RustBitcoinLockTime::from_height(100).unwrap(),
+            input: vec![input(1), input(2), input(3)],
+            output: vec![output(1), output(2)],
+        };
+        // An already signed input doesn't change the preimages of the others.
+        tx.input[0].witness = Witness::from_slice(&[vec![0x30; 71], vec![0x02; 33]]);
+        let omni_tx =
+            OmniBitcoinTransaction::from_bytes(&bitcoin::consensus::serialize(&tx)).unwrap();
+
+        let script_code = ScriptBuf::from_bytes(vec![0x76, 0xa9, 0x14, 0x88, 0xac]);
+        let omni_script_code = OmniScriptBuf(script_code.to_bytes());
+        let mut sighasher = SighashCache::new(&tx);
+
+        for (sighash_type, omni_sighash_type) in [
+            (EcdsaSighashType::All, OmniSighashType::All),
+            (EcdsaSighashType::None, OmniSighashType::None),
+            (EcdsaSighashType::Single, OmniSighashType::Single),
+            (
+                EcdsaSighashType::AllPlusAnyoneCanPay,
+                OmniSighashType::AllPlusAnyoneCanPay,
+            ),
+            (
+                EcdsaSighashType::NonePlusAnyoneCanPay,
+                OmniSighashType::NonePlusAnyoneCanPay,
+            ),
+            (
+                EcdsaSighashType::SinglePlusAnyoneCanPay,
+                OmniSighashType::SinglePlusAnyoneCanPay,
+            ),
+        ] {
+            for input_index in 0..tx.input.len() {
+                let expected = sighasher
+                    .p2wsh_signature_hash(
+                        input_index,
+                        &script_code,
+                        Amount::from_sat(5000),
+                        sighash_type,
+                    )
+                    .unwrap();
+
+                assert_eq!(
+                    omni_tx.hash_for_signing_segwit(
+                        omni_sighash_type,
+                        input_index,
+                        &omni_script_code,
+                        5000
+                    ),
+                    expected.to_byte_array(),
+                    "{:?} of input {}",
+                    sighash_type,
+                    input_index
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_from_bytes_rejects_invalid_encodings() {
+        let tx = OmniBitcoinTransaction {
+            version: Version::TWO,
+            lock_time: LockTime::from_height(0).unwrap(),
+            input: vec![TxIn {
+                previous_output: OmniOutPoint::new(OmniTxid(OmniHash::all_zeros()), 0),
+                script_sig: OmniScriptBuf::default(),
+                sequence: OmniSequence::MAX,
+                witness: OmniWitness::from_slice(&[vec![1u8; 64]]),
+            }],
+            output: vec![],
+        };
+        let bytes = tx.serialize();
+
+        assert_eq!(OmniBitcoinTransaction::from_bytes(&bytes), Ok(tx));
+        assert_eq!(
+            OmniBitcoinTransaction::from_bytes(&[&bytes[..], &[0, 0]].concat()),
+            Err(BitcoinDecodeError::TrailingBytes(2))
+        );
+        assert!(matches!(
+            OmniBitcoinTransaction::from_bytes(&bytes[..bytes.len() - 1]),
+            Err(BitcoinDecodeError::InvalidEncoding(_))
+        ));
+        assert!(matches!(
+            OmniBitcoinTransaction::deserialize_hex("0x02"),
+            Err(BitcoinDecodeError::InvalidHex(_))
+        ));
+
+        // Segwit flag other than 0x01.
+        let mut bad_flag = bytes.clone();
+        bad_flag[5] = 0x02;
+        assert!(OmniBitcoinTransaction::from_bytes(&bad_flag).is_err());
+
+        // Segwit serialization of a transaction without witness.
+        let mut no_witness = bytes[..bytes.len() - 4 - 66].to_vec();
+        no_witness.push(0x00);
+        no_witness.extend_from_slice(&[0; 4]);
+        assert!(OmniBitcoinTransaction::from_bytes(&no_witness).is_err());
+    }
+
+    #[test]
+    fn test_unsigned_clone() {
+        let mut tx = OmniBitcoinTransaction {
+            version: Version::TWO,
+            lock_time: LockTime::from_height(1000000).unwrap(),
+            input: vec![TxIn {
+                previous_output: OmniOutPoint {
+                    txid: OmniTxid(OmniHash::all_zeros()),
+                    vout: 0,
+                },
+                script_sig: OmniScriptBuf::default(),
+                sequence: OmniSequence::default(),
+                witness: OmniWitness::default(),
+            }],
+            output: vec![TxOut {
+                value: OmniAmount::from_sat(10000),
+                script_pubkey: OmniScriptBuf::default(),
+            }],
+        };
+        let unsigned = tx.clone();
+
+        tx.build_with_witness(
+            0,
+            vec![vec![1u8; 72], vec![2u8; 33]],
+            TransactionType::P2WPKH,
+        );
+        tx.input[0].script_sig = OmniScriptBuf(vec![0x16, 0x00, 0x14]);
+        assert_ne!(tx, unsigned);
+
+        assert_eq!(tx.unsigned_clone(), unsigned);
+    }
+
+    const DER_SIGNATURE: &str = "30450221008964e5a86ae770d76a0cb2be7d946fa4a286039af48f75bd4651eb068a74fc7a0220137695e117d53e4f1bd6050707d712c5dd185aee0265b4872a2c23d9dc460594";
+    const COMPRESSED_PUBLIC_KEY: &str =
+        "02989c0b76cb563971fdc9bef31ec06c3560f3249d6ee9e5d83c57625596e05f6f";
+
+    fn single_input_transaction() -> OmniBitcoinTransaction {
+        OmniBitcoinTransaction {
+            version: Version::TWO,
+            lock_time: LockTime::from_height(0).unwrap(),
+            input: vec![TxIn {
+                previous_output: OmniOutPoint {
+                    txid: OmniTxid(OmniHash::all_zeros()),
+                    vout: 0,
+                },
+                script_sig: OmniScriptBuf::default(),
+                sequence: OmniSequence::default(),
+                witness: OmniWitness::default(),
+            }],
+            output: vec![],
+        }
+    }
+
+    #[test]
+    fn test_try_build_with_witness_validates_items() {
+        let mut signature = hex::decode(DER_SIGNATURE).unwrap();
+        let public_key = hex::decode(COMPRESSED_PUBLIC_KEY).unwrap();
+        let mut tx = single_input_transaction();
+
+        assert_eq!(
+            tx.try_build_with_witness(
+                0,
+                vec![signature.clone(), public_key.clone()],
+                TransactionType::P2WPKH
+            ),
+            Err(SignatureError::MissingSighashType)
+        );
+
+        signature.push(0x04);
+        assert_eq!(
+            tx.try_build_with_witness(
+                0,
+                vec![signature.clone(), public_key.clone()],
+                TransactionType::P2WPKH
+            ),
+            Err(SignatureError::InvalidSighashType(0x04))
+        );
+
+        *signature.last_mut().unwrap() = OmniSighashType::All as u8;
+        assert_eq!(
+            tx.try_build_with_witness(0, vec![signature.clone()], TransactionType::P2WPKH),
+            Err(SignatureError::WrongItemCount {
+                expected: 2,
+                actual: 1
+            })
+        );
+        assert_eq!(
+            tx.try_build_with_witness(
+                1,
+                vec![signature.clone(), public_key.clone()],
+                TransactionType::P2WPKH
+            ),
+            Err(SignatureError::InputIndexOutOfRange(1))
+        );
+
+        let mut corrupted = signature.clone();
+        corrupted[0] = 0x31;
+        assert_eq!(
+            tx.try_build_with_witness(
+                0,
+                vec![corrupted, public_key.clone()],
+                TransactionType::P2WPKH
+            ),
+            Err(SignatureError::InvalidDer)
+        );
+
+        assert!(tx
+            .try_build_with_witness(0, vec![signature, public_key], TransactionType::P2WPKH)
+            .is_ok());
+    }
+
+    #[test]
+    fn test_try_build_with_script_sig_validates_pushes() {
+        let mut signature = hex::decode(DER_SIGNATURE).unwrap();
+        signature.push(OmniSighashType::All as u8);
+        let public_key = hex::decode(COMPRESSED_PUBLIC_KEY).unwrap();
+        let mut tx = single_input_transaction();
+
+        let mut script_sig = vec![signature.len() as u8];
+        script_sig.extend_from_slice(&signature);
+        script_sig.push(public_key.len() as u8);
+        script_sig.extend_from_slice(&public_key);
+
+        assert!(tx
+            .try_build_with_script_sig(0, OmniScriptBuf(script_sig.clone()), TransactionType::P2PKH)
+            .is_ok());
+
+        // Truncated public key push.
+        script_sig.pop();
+        assert_eq!(
+            tx.try_build_with_script_sig(0, OmniScriptBuf(script_sig), TransactionType::P2PKH),
+            Err(SignatureError::MalformedScriptSig)
+        );
+
+        // OP_DUP is not a push.
+        assert_eq!(
+            tx.try_build_with_script_sig(0, OmniScriptBuf(vec![0x76]), TransactionType::P2SH),
+            Err(SignatureError::MalformedScriptSig)
+        );
+
+        assert_eq!(
+            tx.try_build_with_script_sig(0, OmniScriptBuf(vec![]), TransactionType::P2WPKH),
+            Err(SignatureError::WrongSpendType)
+        );
+    }
+
+    #[test]
+    fn test_build_with_p2sh_multisig() {
+        use bitcoin::secp256k1::{Message, PublicKey, Secp256k1, SecretKey};
+
+        let secp = Secp256k1::new();
+        let secret_keys = [1u8, 2, 3].map(|n| SecretKey::from_slice(&[n; 32]).unwrap());
+        let public_keys = secret_keys.map(|key| key.public_key(&secp).serialize());
+        let redeem_script = OmniScriptBuf::new_multisig(2, &public_keys);
+        let mut tx = single_input_transaction();
+
+        let sighash = tx.hash_for_signing_legacy_input(0, &redeem_script, OmniSighashType::All);
+        let rust_bitcoin_tx: RustBitcoinTransaction =
+            bitcoin::consensus::deserialize(&tx.serialize()).unwrap();
+        assert_eq!(
+            SighashCache::new(&rust_bitcoin_tx)
+                .legacy_signature_hash(
+                    0,
+                    &ScriptBuf::from_bytes(redeem_script.0.clone()),
+                    OmniSighashType::All as u32
+                )
+                .unwrap()
+                .to_byte_array(),
+            sighash
+        );
+
+        let message = Message::from_digest(sighash);
+        let sign = |key: &SecretKey| {
+            let mut signature = secp.sign_ecdsa(&message, key).serialize_der().to_vec();
+            signature.push(OmniSighashType::All as u8);
+            signature
+        };
+        // Given out of order, signed by the third and first keys.
+        let signatures = [
+            (public_keys[2].to_vec(), sign(&secret_keys[2])),
+            (public_keys[0].to_vec(), sign(&secret_keys[0])),
+        ];
+
+        tx.build_with_p2sh_multisig(0, &redeem_script, &signatures)
+            .unwrap();
+        let pushes = parse_pushes(&tx.input[0].script_sig.0).unwrap();
+        assert_eq!(
+            pushes,
+            [
+                &[][..],
+                &signatures[1].1,
+                &signatures[0].1,
+                &redeem_script.0
+            ]
+        );
+        for (push, public_key) in pushes[1..3].iter().zip([public_keys[0], public_keys[2]]) {
+            let signature =
+                bitcoin::secp256k1::ecdsa::Signature::from_der(&push[..push.len() - 1]).unwrap();
+            secp.verify_ecdsa(
+                &message,
+                &signature,
+                &PublicKey::from_slice(&public_key).unwrap(),
+            )
+            .unwrap();
+        }
+
+        assert_eq!(
+            tx.build_with_p2sh_multisig(0, &redeem_script, &signatures[..1]),
+            Err(SignatureError::WrongItemCount {
+                expected: 2,
+                actual: 1
+            })
+        );
+        assert_eq!(
+            tx.build_with_p2sh_multisig(
+                0,
+                &redeem_script,
+                &[signatures[0].clone(), signatures[0].clone()]
+            ),
+            Err(SignatureError::UnknownPublicKey)
+        );
+        assert_eq!(
+            tx.build_with_p2sh_multisig(
+                0,
+                &OmniScriptBuf::new_p2sh(&ScriptHash::from_byte_array([0; 20])),
+                &signatures
+            ),
+            Err(SignatureError::InvalidRedeemScript)
+        );
+        assert_eq!(
+            tx.build_with_p2sh_multisig(1, &redeem_script, &signatures),
+            Err(SignatureError::InputIndexOutOfRange(1))
+        );
+    }
+
+    #[test]
+    fn test_build_with_p2wsh_multisig() {
+        use bitcoin::secp256k1::{Message, PublicKey, Secp256k1, SecretKey};
+
+        let secp = Secp256k1::new();
+        let secret_keys = [1u8, 2, 3].map(|n| SecretKey::from_slice(&[n; 32]).unwrap());
+        let public_keys = secret_keys.map(|key| key.public_key(&secp).serialize());
+        let witness_script = OmniScriptBuf::new_multisig(2, &public_keys);
+        let value = 50_000;
+        let mut tx = single_input_transaction();
+        tx.output.push(TxOut {
+            value: OmniAmount::from_sat(40_000),
+            script_pubkey: witness_script.to_p2wsh(),
+        });
+
+        let sighash = tx.hash_for_signing_p2wsh(OmniSighashType::All, 0, &witness_script, value);
+        let rust_bitcoin_tx: RustBitcoinTransaction =
+            bitcoin::consensus::deserialize(&tx.serialize()).unwrap();
+        assert_eq!(
+            SighashCache::new(&rust_bitcoin_tx)
+                .p2wsh_signature_hash(
+                    0,
+                    &ScriptBuf::from_bytes(witness_script.0.clone()),
+                    Amount::from_sat(value),
+                    EcdsaSighashType::All
+                )
+                .unwrap()
+                .to_byte_array(),
+            sighash
+        );
+
+        let message = Message::from_digest(sighash);
+        let sign = |key: &SecretKey| {
+            let mut signature = secp.sign_ecdsa(&message, key).serialize_der().to_vec();
+            signature.push(OmniSighashType::All as u8);
+            signature
+        };
+        let signatures = [
+            (public_keys[1], sign(&secret_keys[1])),
+            (public_keys[0], sign(&secret_keys[0])),
+        ];
+
+        tx.build_with_p2wsh_multisig(0, &witness_script, &signatures)
+            .unwrap();
+        let witness = tx.input[0].witness.to_vec();
+        assert_eq!(
+            witness,
+            [
+                vec![],
+                signatures[1].1.clone(),
+                signatures[0].1.clone(),
+                witness_script.0.clone()
+            ]
+        );
+        for (item, public_key) in witness[1..3].iter().zip(&public_keys) {
+            let signature =
+                bitcoin::secp256k1::ecdsa::Signature::from_der(&item[..item.len() - 1]).unwrap();
+            secp.verify_ecdsa(
+                &message,
+                &signature,
+                &PublicKey::from_slice(public_key).unwrap(),
+            )
+            .unwrap();
+        }
+
+        let unknown_key = SecretKey::from_slice(&[4; 32]).unwrap();
+        assert_eq!(
+            tx.build_with_p2wsh_multisig(
+                0,
+                &witness_script,
+                &[
+                    signatures[0].clone(),
+                    (
+                        unknown_key.public_key(&secp).serialize(),
+                        sign(&unknown_key)
+                    )
+                ]
+            ),
+            Err(SignatureError::UnknownPublicKey)
+        );
+    }
+
+    #[test]
+    fn test_validate_reports_all_issues() {
+        let input = TxIn {
+            previous_output: OmniOutPoint {
+                txid: OmniTxid(OmniHash::all_zeros()),
+                vout: 0,
+            },
+            script_sig: OmniScriptBuf::default(),
+            sequence: OmniSequence::default(),
+            witness: OmniWitness::default(),
+        };
+        let tx = OmniBitcoinTransaction {
+            version: Version::non_standard(3),
+            lock_time: LockTime::from_height(0).unwrap(),
+            input: vec![input.clone(), input],
+            output: vec![
+                TxOut {
+                    value: OmniAmount::ZERO,
+                    script_pubkey: OmniScriptBuf::default(),
+                },
+                TxOut::ephemeral_anchor(),
+                TxOut {
+                    value: OmniAmount::MAX,
+                    script_pubkey: OmniScriptBuf::default(),
+                },
+            ],
+        };
+
+        let issues = tx.validate();
+
+        let fields: Vec<_> = issues
+            .iter()
+            .map(|issue| (issue.field.as_str(), issue.is_error()))
+            .collect();
+        assert_eq!(
+            fields,
+            vec![
+                ("version", false),
+                ("input[1].previous_output", true),
+                ("output[0].value", false),
+                ("output[2].value", true),
+                ("output", true),
+            ]
+        );
+        assert!(!tx.is_valid());
+    }
+
+    #[test]
+    fn test_validate_for_coin() {
+        let mut tx = OmniBitcoinTransaction {
+            version: Version::ONE,
+            lock_time: LockTime::from_height(0).unwrap(),
+            input: vec![TxIn {
+                previous_output: OmniOutPoint {
+                    txid: OmniTxid(OmniHash::all_zeros()),
+                    vout: 0,
+                },
+                script_sig: OmniScriptBuf::default(),
+                sequence: OmniSequence::default(),
+                witness: OmniWitness::from_slice(&[vec![0x30; 71], vec![0x02; 33]]),
+            }],
+            output: vec![TxOut {
+                // Above the Bitcoin supply, below the Litecoin one.
+                value: OmniAmount::from_int_btc(50_000_000),
+                script_pubkey: OmniScriptBuf::new_p2pkh(&PubkeyHash::from_byte_array([1; 20])),
+            }],
+        };
+
+        let fields = |coin| -> Vec<String> {
+            tx.validate_for_coin(coin)
+                .into_iter()
+                .map(|issue| issue.field)
+                .collect()
+        };
+        assert_eq!(fields(Coin::Bitcoin), vec!["output[0].value", "output"]);
+        assert!(fields(Coin::Litecoin).is_empty());
+        assert_eq!(fields(Coin::Dogecoin), vec!["input[0].witness"]);
+        assert_eq!(tx.validate(), tx.validate_for_coin(Coin::Bitcoin));
+
+        // Dogecoin transactions are legacy ones.
+        tx.input[0].witness = OmniWitness::default();
+        assert!(tx.validate_for_coin(Coin::Dogecoin).is_empty());
+        assert_eq!(
+            OmniBitcoinTransaction::from_bytes(&tx.serialize()).unwrap(),
+            tx
+        );
+    }
+
+    fn taproot_test_transactions() -> (RustBitcoinTransaction, OmniBitcoinTransaction) {
+        let key_spend_script = |byte: u8| [&[0x51, 0x20][..], &[byte; 32]].concat();
+        let txid = "2bd9e2b59a1a5be2cbbe07aaa6a71dc6a3c5eb0c1e4e7d6ab2d5e64c40b6a8e0";
+
+        let tx = RustBitcoinTransaction {
+            version: RustBitcoinVersion(2),
+            lock_time: RustBitcoinLockTime::from_height(800_000).unwrap(),
+            input: (0..2)
+                .map(|vout| RustBitcoinTxIn {
+                    previous_output: OutPoint {
+                        txid: txid.parse().unwrap(),
+                        vout,
+                    },
+                    script_sig: ScriptBuf::default(),
+                    sequence: RustBitcoinSequence::ENABLE_RBF_NO_LOCKTIME,
+                    witness: Witness::default(),
+                })
+                .collect(),
+            output: (0..2)
+                .map(|i| RustBitcoinTxOut {
+                    value: Amount::from_sat(40_000 + i),
+                    script_pubkey: ScriptBuf::from_bytes(key_spend_script(0xaa + i as u8)),
+                })
+                .collect(),
+        };
+
+        let omni_tx = OmniBitcoinTransaction {
+            version: Version::TWO,
+            lock_time: LockTime::from_height(800_000).unwrap(),
+            input: (0..2)
+                .map(|vout| TxIn {
+                    previous_output: OmniOutPoint {
+                        txid: OmniTxid(OmniHash::from_hex(txid).unwrap()),
+                        vout,
+                    },
+                    script_sig: OmniScriptBuf::default(),
+                    sequence: OmniSequence::ENABLE_RBF_NO_LOCKTIME,
+                    witness: OmniWitness::default(),
+                })
+                .collect(),
+            output: (0..2)
+                .map(|i| TxOut {
+                    value: OmniAmount::from_sat(40_000 + i),
+                    script_pubkey: OmniScriptBuf(key_spend_script(0xaa + i as u8)),
+                })
+                .collect(),
+        };
+
+        (tx, omni_tx)
+    }
+
+    #[test]
+    fn test_build_for_signing_taproot_against_rust_bitcoin() {
+        use bitcoin::sighash::{Annex, Prevouts, TapSighashType as RustBitcoinTapSighashType};
+
+        let (tx, omni_tx) = taproot_test_transactions();
+        let prevouts: Vec<_> = (0..2)
+            .map(|i| RustBitcoinTxOut {
+                value: Amount::from_sat(50_000 + i),
+                script_pubkey: ScriptBuf::from_bytes([&[0x51, 0x20][..], &[i as u8; 32]].concat()),
+            })
+            .collect();
+        let omni_prevouts: Vec<_> = prevouts
+            .iter()
+            .map(|prevout| TxOut {
+                value: OmniAmount::from_sat(prevout.value.to_sat()),
+                script_pubkey: OmniScriptBuf(prevout.script_pubkey.to_bytes()),
+            })
+            .collect();
+        let annex = [TAPROOT_ANNEX_PREFIX, 0x01, 0x02];
+
+        for (sighash_type, omni_sighash_type) in [
+            (RustBitcoinTapSighashType::Default, TapSighashType::Default),
+            (RustBitcoinTapSighashType::All, TapSighashType::All),
+            (RustBitcoinTapSighashType::None, TapSighashType::None),
+            (RustBitcoinTapSighashType::Single, TapSighashType::Single),
+            (
+                RustBitcoinTapSighashType::AllPlusAnyoneCanPay,
+                TapSighashType::AllPlusAnyoneCanPay,
+            ),
+            (
+                RustBitcoinTapSighashType::NonePlusAnyoneCanPay,
+                TapSighashType::NonePlusAnyoneCanPay,
+            ),
+            (
+                RustBitcoinTapSighashType::SinglePlusAnyoneCanPay,
+                TapSighashType::SinglePlusAnyoneCanPay,
+            ),
+        ] {
+            for with_annex in [false, true] {
+                let expected = SighashCache::new(&tx)
+                    .taproot_signature_hash(
+                        1,
+                        &Prevouts::All(&prevouts),
+                        with_annex.then(|| Annex::new(&annex).unwrap()),
+                        None,
+                        sighash_type,
+                    )
+                    .unwrap();
+
+                let sighash = omni_tx.build_for_signing_taproot(
+                    1,
+                    &omni_prevouts,
+                    omni_sighash_type,
+                    with_annex.then_some(&annex[..]),
+                );
+
+                assert_eq!(
+                    sighash,
+                    expected.to_byte_array(),
+                    "{:?}, annex: {}",
+                    sighash_type,
+                    with_annex
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_build_for_signing_taproot_script_path_against_rust_bitcoin() {
+        use bitcoin::sighash::{Annex, Prevouts, TapSighashType as RustBitcoinTapSighashType};
+        use bitcoin::taproot::{LeafVersion, TapLeafHash as RustBitcoinTapLeafHash};
+
+        let (tx, omni_tx) = taproot_test_transactions();
+        let prevouts: Vec<_> = (0..2)
+            .map(|i| RustBitcoinTxOut {
+                value: Amount::from_sat(50_000 + i),
+                script_pubkey: ScriptBuf::from_bytes([&[0x51, 0x20][..], &[i as u8; 32]].concat()),
+            })
+            .collect();
+        let omni_prevouts: Vec<_> = prevouts
+            .iter()
+            .map(|prevout| TxOut {
+                value: OmniAmount::from_sat(prevout.value.to_sat()),
+                script_pubkey: OmniScriptBuf(prevout.script_pubkey.to_bytes()),
+            })
+            .collect();
+        let annex = [TAPROOT_ANNEX_PREFIX, 0x01, 0x02];
+
+        let tapscript = [&[0x20][..], &[0x11; 32], &[0xac]].concat();
+        let leaf_hash = RustBitcoinTapLeafHash::from_script(
+            &ScriptBuf::from_bytes(tapscript.clone()),
+            LeafVersion::TapScript,
+        );
+        let omni_leaf_hash = TapLeafHash::from_tapscript(&OmniScriptBuf(tapscript));
+        assert_eq!(omni_leaf_hash.0, leaf_hash.to_byte_array());
+
+        for (sighash_type, omni_sighash_type) in [
+            (RustBitcoinTapSighashType::Default, TapSighashType::Default),
+            (RustBitcoinTapSighashType::None, TapSighashType::None),
+            (
+                RustBitcoinTapSighashType::SinglePlusAnyoneCanPay,
+                TapSighashType::SinglePlusAnyoneCanPay,
+            ),
+        ] {
+            for with_annex in [false, true] {
+                let expected = SighashCache::new(&tx)
+                    .taproot_signature_hash(
+                        1,
+                        &Prevouts::All(&prevouts),
+                        with_annex.then(|| Annex::new(&annex).unwrap()),
+                        Some((leaf_hash, 0xffff_ffff)),
+                        sighash_type,
+                    )
+                    .unwrap();
+
+                let sighash = omni_tx.build_for_signing_taproot_script_path(
+                    1,
+                    &omni_prevouts,
+                    omni_sighash_type,
+                    with_annex.then_some(&annex[..]),
+                    &omni_leaf_hash,
+                );
+
+                assert_eq!(
+                    sighash,
+                    expected.to_byte_array(),
+                    "{:?}, annex: {}",
+                    sighash_type,
+                    with_annex
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_try_build_with_witness_taproot() {
+        use crate::bitcoin::constants::TAPROOT_LEAF_TAPSCRIPT;
+
+        let (_, omni_tx) = taproot_test_transactions();
+
+        let mut tx = omni_tx.clone();
+        assert!(tx
+            .try_build_with_witness(0, vec![vec![0x11; 64]], TransactionType::P2TR)
+            .is_ok());
+
+        let mut signature = vec![0x11; 64];
+        signature.push(TapSighashType::SinglePlusAnyoneCanPay as u8);
+        let annex = vec![TAPROOT_ANNEX_PREFIX, 0x01];
+        let mut tx = omni_tx.clone();
+        let serialized = tx
+            .try_build_with_witness(1, vec![signature, annex], TransactionType::P2TR)
+            .unwrap();
+        assert_eq!(serialized, tx.serialize());
+        assert_eq!(tx.input[1].witness.len(), 2);
+
+        let mut tx = omni_tx.clone();
+        assert_eq!(
+            tx.try_build_with_witness(0, vec![vec![0x11; 71]], TransactionType::P2TR),
+            Err(SignatureError::InvalidLength {
+                component: "signature",
+                expected: 64,
+                actual: 71,
+            })
+        );
+        // The default sighash type must be omitted rather than explicit.
+        let mut signature = vec![0x11; 64];
+        signature.push(TapSighashType::Default as u8);
+        assert_eq!(
+            tx.try_build_with_witness(0, vec![signature], TransactionType::P2TR),
+            Err(SignatureError::InvalidSighashType(0x00))
+        );
+        // A second item which is neither an annex nor a control block.
+        assert_eq!(
+            tx.try_build_with_witness(
+                0,
+                vec![vec![0x11; 64], vec![0x02; 34]],
+                TransactionType::P2TR
+            ),
+            Err(SignatureError::InvalidControlBlock)
+        );
+        assert_eq!(
+            tx.try_build_with_witness(0, vec![], TransactionType::P2TR),
+            Err(SignatureError::WrongItemCount {
+                expected: 1,
+                actual: 0,
+            })
+        );
+
+        // Script path: a signature, the script and its control block, then an annex.
+        let control_block = ControlBlock {
+            leaf_version: TAPROOT_LEAF_TAPSCRIPT,
+            output_key_parity: true,
+            internal_key: [0x22; 32],
+            merkle_branch: vec![[0x33; 32]],
+        };
+        let witness = vec![
+            vec![0x11; 64],
+            [&[0x20][..], &[0x44; 32], &[0xac]].concat(),
+            control_block.serialize(),
+            vec![TAPROOT_ANNEX_PREFIX],
+        ];
+        let mut tx = omni_tx.clone();
+        assert!(tx
+            .try_build_with_witness(0, witness, TransactionType::P2TR)
+            .is_ok());
+        assert_eq!(tx.input[0].witness.len(), 4);
+    }
+
+    #[test]
+    fn test_display_summary() {
+        let tx = OmniBitcoinTransaction {
+            version: Version::TWO,
+            lock_time: LockTime::from_height(0).unwrap(),
+            input: vec![TxIn {
+                previous_output: OmniOutPoint::new(OmniTxid(OmniHash::from_byte_array([1; 32])), 1),
+                script_sig: OmniScriptBuf::default(),
+                sequence: OmniSequence::MAX,
+                witness: OmniWitness::from_slice(&[[0x30; 4]]),
+            }],
+            output: vec![TxOut::new_to_script(
+                OmniAmount::from_sat(10_000),
+                OmniScriptBuf::new_p2wpkh(&WPubkeyHash::from_byte_array([2; 20])),
+            )],
+        };
+
+        assert_eq!(
+            tx.to_string(),
+            format!(
+                "Bitcoin {} version 2 lock time 0: spends [{}:1], pays [10000 sat to 0014{}]",
+                tx.compute_txid(),
+                "01".repeat(32),
+                "02".repeat(20)
+            )
+        );
     }
 }
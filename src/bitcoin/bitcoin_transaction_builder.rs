@@ -2,7 +2,7 @@ use super::{
     bitcoin_transaction::BitcoinTransaction,
     types::{LockTime, TxIn, TxOut, Version},
 };
-use crate::transaction_builder::TxBuilder;
+use crate::transaction_builder::{BuildError, TxBuilder};
 
 pub struct BitcoinTransactionBuilder {
     pub version: Option<Version>,
@@ -29,6 +29,25 @@ impl TxBuilder<BitcoinTransaction> for BitcoinTransactionBuilder {
 }
 
 impl BitcoinTransactionBuilder {
+    /// Same as [`TxBuilder::build`], but returns an error instead of panicking if a mandatory
+    /// field is missing.
+    pub fn try_build(&self) -> Result<BitcoinTransaction, BuildError> {
+        Ok(BitcoinTransaction {
+            version: self.version.ok_or(BuildError::MissingField("version"))?,
+            lock_time: self
+                .lock_time
+                .ok_or(BuildError::MissingField("lock_time"))?,
+            input: self
+                .inputs
+                .clone()
+                .ok_or(BuildError::MissingField("inputs"))?,
+            output: self
+                .outputs
+                .clone()
+                .ok_or(BuildError::MissingField("outputs"))?,
+        })
+    }
+
     pub const fn new() -> Self {
         Self {
             version: None,
@@ -67,13 +86,13 @@ mod tests {
     fn test_build() {
         let block_height = 10000;
         let builder = BitcoinTransactionBuilder::new()
-            .version(Version::One)
+            .version(Version::ONE)
             .lock_time(LockTime::from_height(block_height).unwrap())
             .inputs(vec![])
             .outputs(vec![])
             .build();
 
-        assert_eq!(builder.version, Version::One);
+        assert_eq!(builder.version, Version::ONE);
         assert_eq!(
             builder.lock_time,
             LockTime::from_height(block_height).unwrap()
@@ -84,10 +103,27 @@ mod tests {
     fn test_sighash() {
         let block_height = 10000;
         let _builder = BitcoinTransactionBuilder::new()
-            .version(Version::One)
+            .version(Version::ONE)
             .lock_time(LockTime::from_height(block_height).unwrap())
             .inputs(vec![])
             .outputs(vec![])
             .build();
     }
+
+    #[test]
+    fn test_try_build_missing_field() {
+        let builder = BitcoinTransactionBuilder::new()
+            .version(Version::TWO)
+            .lock_time(LockTime::from_height(10000).unwrap())
+            .inputs(vec![]);
+
+        assert_eq!(
+            builder.try_build(),
+            Err(BuildError::MissingField("outputs"))
+        );
+        assert_eq!(
+            builder.outputs(vec![]).try_build().unwrap().version,
+            Version::TWO
+        );
+    }
 }
@@ -0,0 +1,242 @@
+use std::fmt;
+
+use super::hash_types::{PubkeyHash, ScriptHash, WPubkeyHash, WScriptHash};
+use super::types::{Amount, ScriptBuf, TransactionType, TxOut};
+
+pub use super::types::DEFAULT_DUST_RELAY_FEE_RATE;
+
+/// Errors returned by [`plan_change`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChangeError {
+    /// The inputs cannot pay for the outputs and the fee, in satoshis.
+    InsufficientFunds { inputs: u64, outputs: u64, fee: u64 },
+    /// The outputs and the fee together overflow.
+    Overflow,
+}
+
+impl fmt::Display for ChangeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InsufficientFunds {
+                inputs,
+                outputs,
+                fee,
+            } => write!(
+                f,
+                "insufficient funds: inputs {} sat, outputs {} sat, fee {} sat",
+                inputs, outputs, fee
+            ),
+            Self::Overflow => write!(f, "value overflows"),
+        }
+    }
+}
+
+impl std::error::Error for ChangeError {}
+
+/// The outcome of planning the change of a transaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChangePlan {
+    /// The fee paid by the transaction, including any folded dust.
+    pub fee: Amount,
+    /// The value of the change output, if one should be created.
+    pub change: Option<Amount>,
+    /// The change that was below the dust threshold and was added to the fee instead.
+    pub folded_dust: Amount,
+}
+
+impl ChangePlan {
+    /// Returns `true` if some change was folded into the fee instead of creating a dust output.
+    pub fn is_dust_folded(&self) -> bool {
+        self.folded_dust != Amount::ZERO
+    }
+
+    /// Returns the change output paying to `script_pubkey`, if one should be created.
+    pub fn change_output(&self, script_pubkey: ScriptBuf) -> Option<TxOut> {
+        self.change.map(|value| TxOut {
+            value,
+            script_pubkey,
+        })
+    }
+}
+
+/// Computes the change of a transaction spending `inputs_value` to `outputs_value` while paying
+/// at least `fee`.
+///
+/// Change below `dust_threshold` is not worth an output: nodes refuse to relay dust and spending
+/// it would cost more than it is worth. In that case it is folded into the fee and reported in
+/// [`ChangePlan::folded_dust`].
+///
+/// Fails if the inputs do not cover the outputs and the fee.
+pub fn plan_change(
+    inputs_value: Amount,
+    outputs_value: Amount,
+    fee: Amount,
+    dust_threshold: Amount,
+) -> Result<ChangePlan, ChangeError> {
+    let required = outputs_value
+        .checked_add(fee)
+        .ok_or(ChangeError::Overflow)?;
+    let change =
+        inputs_value
+            .checked_sub(required)
+            .ok_or_else(|| ChangeError::InsufficientFunds {
+                inputs: inputs_value.to_sat(),
+                outputs: outputs_value.to_sat(),
+                fee: fee.to_sat(),
+            })?;
+
+    if change == Amount::ZERO {
+        return Ok(ChangePlan {
+            fee,
+            change: None,
+            folded_dust: Amount::ZERO,
+        });
+    }
+
+    if change.to_sat() < dust_threshold.to_sat() {
+        return Ok(ChangePlan {
+            fee: fee + change,
+            change: None,
+            folded_dust: change,
+        });
+    }
+
+    Ok(ChangePlan {
+        fee,
+        change: Some(change),
+        folded_dust: Amount::ZERO,
+    })
+}
+
+/// The dust threshold of a Pay-to-Anchor (P2A) output at the default dust relay fee (3 sat/vB).
+///
+/// Zero value (ephemeral) anchors are exempt, provided they are spent in the same package.
+pub const P2A_DUST_THRESHOLD: Amount = Amount::from_sat(240);
+
+/// Returns the dust threshold of an output of the given type at the default dust relay fee
+/// (3 sat/vB), see [`TxOut::dust_threshold`].
+pub fn dust_threshold(tx_type: TransactionType) -> Amount {
+    let script_pubkey = match tx_type {
+        TransactionType::P2PKH => ScriptBuf::new_p2pkh(&PubkeyHash::all_zeros()),
+        TransactionType::P2SH => ScriptBuf::new_p2sh(&ScriptHash::all_zeros()),
+        TransactionType::P2WPKH => ScriptBuf::new_p2wpkh(&WPubkeyHash::all_zeros()),
+        // Taproot outputs are 32-byte witness programs too, the same size as P2WSH ones.
+        TransactionType::P2WSH | TransactionType::P2TR => {
+            ScriptBuf::new_p2wsh(&WScriptHash::all_zeros())
+        }
+    };
+    TxOut::new_to_script(Amount::ZERO, script_pubkey).dust_threshold(DEFAULT_DUST_RELAY_FEE_RATE)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_change_above_dust_creates_output() {
+        let plan = plan_change(
+            Amount::from_sat(10_000),
+            Amount::from_sat(5_000),
+            Amount::from_sat(1_000),
+            dust_threshold(TransactionType::P2WPKH),
+        )
+        .unwrap();
+
+        assert_eq!(plan.fee, Amount::from_sat(1_000));
+        assert_eq!(plan.change, Some(Amount::from_sat(4_000)));
+        assert!(!plan.is_dust_folded());
+        assert_eq!(
+            plan.change_output(ScriptBuf::default()).unwrap().value,
+            Amount::from_sat(4_000)
+        );
+    }
+
+    #[test]
+    fn test_change_below_dust_is_folded_into_fee() {
+        let plan = plan_change(
+            Amount::from_sat(10_000),
+            Amount::from_sat(8_500),
+            Amount::from_sat(1_000),
+            dust_threshold(TransactionType::P2PKH),
+        )
+        .unwrap();
+
+        assert_eq!(plan.fee, Amount::from_sat(1_500));
+        assert_eq!(plan.change, None);
+        assert_eq!(plan.folded_dust, Amount::from_sat(500));
+        assert!(plan.is_dust_folded());
+        assert!(plan.change_output(ScriptBuf::default()).is_none());
+    }
+
+    #[test]
+    fn test_change_at_dust_threshold_creates_output() {
+        let plan = plan_change(
+            Amount::from_sat(10_000),
+            Amount::from_sat(8_706),
+            Amount::from_sat(1_000),
+            dust_threshold(TransactionType::P2WPKH),
+        )
+        .unwrap();
+
+        assert_eq!(plan.change, Some(Amount::from_sat(294)));
+        assert!(!plan.is_dust_folded());
+    }
+
+    #[test]
+    fn test_exact_amount_has_no_change() {
+        let plan = plan_change(
+            Amount::from_sat(10_000),
+            Amount::from_sat(9_000),
+            Amount::from_sat(1_000),
+            dust_threshold(TransactionType::P2WPKH),
+        )
+        .unwrap();
+
+        assert_eq!(plan.change, None);
+        assert!(!plan.is_dust_folded());
+    }
+
+    #[test]
+    fn test_insufficient_funds() {
+        assert_eq!(
+            plan_change(
+                Amount::from_sat(10_000),
+                Amount::from_sat(9_500),
+                Amount::from_sat(1_000),
+                dust_threshold(TransactionType::P2WPKH),
+            ),
+            Err(ChangeError::InsufficientFunds {
+                inputs: 10_000,
+                outputs: 9_500,
+                fee: 1_000
+            })
+        );
+        assert_eq!(
+            plan_change(
+                Amount::from_sat(10_000),
+                Amount::from_sat(u64::MAX),
+                Amount::from_sat(1),
+                Amount::ZERO,
+            ),
+            Err(ChangeError::Overflow)
+        );
+    }
+
+    #[test]
+    fn test_dust_threshold_by_type() {
+        assert_eq!(
+            dust_threshold(TransactionType::P2PKH),
+            Amount::from_sat(546)
+        );
+        assert_eq!(dust_threshold(TransactionType::P2SH), Amount::from_sat(540));
+        assert_eq!(
+            dust_threshold(TransactionType::P2WPKH),
+            Amount::from_sat(294)
+        );
+        assert_eq!(
+            dust_threshold(TransactionType::P2WSH),
+            Amount::from_sat(330)
+        );
+        assert_eq!(dust_threshold(TransactionType::P2TR), Amount::from_sat(330));
+    }
+}
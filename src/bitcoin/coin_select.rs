@@ -0,0 +1,618 @@
+//! Coin selection: choosing which UTXOs fund a transaction.
+//!
+//! Two selectors are provided: [`select_branch_and_bound`] looks for a set of inputs matching
+//! the target closely enough to not need a change output, and [`select_largest_first`] spends
+//! the largest UTXOs first and sends the excess to a change output. [`select_coins`] tries the
+//! former and falls back to the latter, like Bitcoin Core.
+//!
+//! Fees are estimated from the weight of the signed transaction, so each candidate carries the
+//! weight of the input spending it.
+use core::cmp::Reverse;
+use core::fmt;
+
+use super::{
+    constants::WITNESS_SCALE_FACTOR,
+    encoding::Encodable,
+    types::{Amount, OutPoint, TransactionType, TxOut, Utxo},
+};
+
+/// Errors returned by the coin selectors.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CoinSelectionError {
+    /// All candidates together cannot pay for the target and the fee, in satoshis.
+    InsufficientFunds {
+        available: u64,
+        target: u64,
+        fee: u64,
+    },
+    /// No selection matches the target without change, see [`select_branch_and_bound`].
+    NoChangelessMatch,
+    /// The total value of the selected candidates overflows.
+    Overflow,
+}
+
+impl fmt::Display for CoinSelectionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InsufficientFunds {
+                available,
+                target,
+                fee,
+            } => write!(
+                f,
+                "insufficient funds: candidates {} sat, target {} sat, fee {} sat",
+                available, target, fee
+            ),
+            Self::NoChangelessMatch => {
+                write!(f, "no selection matches the target without change")
+            }
+            Self::Overflow => write!(f, "selected value overflows"),
+        }
+    }
+}
+
+impl std::error::Error for CoinSelectionError {}
+
+/// Largest number of branches explored by [`select_branch_and_bound`].
+const BNB_MAX_TRIES: usize = 100_000;
+
+/// Weight of the version and lock time.
+const VERSION_AND_LOCK_TIME_WEIGHT: u64 = 8 * WITNESS_SCALE_FACTOR;
+/// Weight of the segwit marker and flag.
+const SEGWIT_MARKER_AND_FLAG_WEIGHT: u64 = 2;
+
+/// Returns the weight of an input spending a single key output of type `tx_type`, once signed,
+/// or `None` for script outputs (P2SH, P2WSH) whose unlock depends on the script.
+///
+/// ECDSA signatures are counted at their largest DER size (73 bytes with the sighash type) and
+/// Schnorr signatures with an explicit sighash type (65 bytes), so the fee is never underpaid.
+pub const fn input_weight(tx_type: TransactionType) -> Option<u64> {
+    // Outpoint, script sig length and sequence.
+    const BASE: u64 = 36 + 1 + 4;
+
+    match tx_type {
+        // Script sig: <73-byte signature> <33-byte public key>.
+        TransactionType::P2PKH => Some((BASE + 1 + 73 + 1 + 33) * WITNESS_SCALE_FACTOR),
+        // Witness: item count, <73-byte signature> <33-byte public key>.
+        TransactionType::P2WPKH => Some(BASE * WITNESS_SCALE_FACTOR + 1 + 1 + 73 + 1 + 33),
+        // Witness: item count, <65-byte Schnorr signature>.
+        TransactionType::P2TR => Some(BASE * WITNESS_SCALE_FACTOR + 1 + 1 + 65),
+        TransactionType::P2SH | TransactionType::P2WSH => None,
+    }
+}
+
+/// Returns the weight of an output locked by a standard script of type `tx_type`.
+pub const fn output_weight(tx_type: TransactionType) -> u64 {
+    let script_len = match tx_type {
+        TransactionType::P2PKH => 25,
+        TransactionType::P2SH => 23,
+        TransactionType::P2WPKH => 22,
+        TransactionType::P2WSH | TransactionType::P2TR => 34,
+    };
+
+    // Value, script length and script.
+    (8 + 1 + script_len) * WITNESS_SCALE_FACTOR
+}
+
+/// A UTXO which may be spent to fund a transaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Candidate {
+    pub outpoint: OutPoint,
+    pub value: Amount,
+    /// The weight of the input spending the UTXO, once signed.
+    pub input_weight: u64,
+}
+
+impl Candidate {
+    pub const fn new(outpoint: OutPoint, value: Amount, input_weight: u64) -> Self {
+        Self {
+            outpoint,
+            value,
+            input_weight,
+        }
+    }
+
+    /// Creates a candidate spending a single key output of type `tx_type`, or `None` if the
+    /// weight of its input is unknown, see [`input_weight`].
+    pub const fn single_key(
+        outpoint: OutPoint,
+        value: Amount,
+        tx_type: TransactionType,
+    ) -> Option<Self> {
+        match input_weight(tx_type) {
+            Some(input_weight) => Some(Self::new(outpoint, value, input_weight)),
+            None => None,
+        }
+    }
+
+    /// Creates a candidate from a wallet UTXO locked by a single key, or `None` if its script
+    /// pubkey is not P2PKH, P2WPKH or P2TR.
+    pub fn from_utxo(utxo: &Utxo) -> Option<Self> {
+        Self::single_key(
+            utxo.outpoint,
+            utxo.value,
+            TransactionType::classify(&utxo.script_pubkey)?,
+        )
+    }
+}
+
+/// What the selected inputs have to pay for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CoinSelectionParams {
+    /// The value sent to the outputs, change excluded.
+    pub target: Amount,
+    /// The fee rate, in sat/vB.
+    pub fee_rate: u64,
+    /// The weight of the transaction without its inputs nor change: version, lock time, input
+    /// and output counts, outputs and segwit marker.
+    pub base_weight: u64,
+    /// The weight of the change output.
+    pub change_output_weight: u64,
+    /// The weight of the input spending the change output later. Along with
+    /// `change_output_weight`, it is the cost of creating change which a changeless
+    /// selection may give away as fee instead.
+    pub change_spend_weight: u64,
+    /// The smallest change output worth creating, see
+    /// [`dust_threshold`](super::change::dust_threshold).
+    pub dust_threshold: Amount,
+}
+
+impl CoinSelectionParams {
+    /// Creates the parameters to pay `outputs` at `fee_rate` sat/vB, with change of type
+    /// `change_type`.
+    ///
+    /// The base weight assumes segwit inputs (adding the 2 WU marker and flag) and fewer than
+    /// 253 inputs. Change spent by a script (P2SH, P2WSH) is priced as free to spend later.
+    pub fn new(outputs: &[TxOut], fee_rate: u64, change_type: TransactionType) -> Self {
+        let target = outputs
+            .iter()
+            .fold(Amount::ZERO, |total, output| total + output.value);
+        let outputs_size = outputs
+            .iter()
+            .map(|output| output.encoded_size() as u64)
+            .sum::<u64>();
+        // With room for the change output in the output count.
+        let counts_size = 1 + super::encoding::utils::VarInt(outputs.len() as u64 + 1).size();
+
+        Self {
+            target,
+            fee_rate,
+            base_weight: VERSION_AND_LOCK_TIME_WEIGHT
+                + (counts_size as u64 + outputs_size) * WITNESS_SCALE_FACTOR
+                + SEGWIT_MARKER_AND_FLAG_WEIGHT,
+            change_output_weight: output_weight(change_type),
+            change_spend_weight: input_weight(change_type).unwrap_or_default(),
+            dust_threshold: super::change::dust_threshold(change_type),
+        }
+    }
+
+    /// Returns the fee paying `fee_rate` for `weight`, rounded up to the next virtual byte.
+    fn fee(&self, weight: u64) -> u64 {
+        weight
+            .div_ceil(WITNESS_SCALE_FACTOR)
+            .saturating_mul(self.fee_rate)
+    }
+}
+
+/// The inputs chosen to fund a transaction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Selection {
+    pub selected: Vec<Candidate>,
+    /// The fee paid by the transaction, including any excess not worth a change output.
+    pub fee: Amount,
+    /// The value of the change output, if one should be created.
+    pub change: Option<Amount>,
+}
+
+impl Selection {
+    /// Returns the total value of the selected inputs.
+    pub fn selected_value(&self) -> Amount {
+        self.selected
+            .iter()
+            .fold(Amount::ZERO, |total, candidate| total + candidate.value)
+    }
+}
+
+/// Selects inputs with [`select_branch_and_bound`], falling back to [`select_largest_first`]
+/// if no changeless selection is found.
+pub fn select_coins(
+    candidates: &[Candidate],
+    params: &CoinSelectionParams,
+) -> Result<Selection, CoinSelectionError> {
+    select_branch_and_bound(candidates, params)
+        .or_else(|_| select_largest_first(candidates, params))
+}
+
+/// Selects the largest candidates until they pay for the target and the fee, sending the excess
+/// to a change output unless it is below the dust threshold.
+///
+/// Fails if all candidates together cannot pay for the target and the fee.
+pub fn select_largest_first(
+    candidates: &[Candidate],
+    params: &CoinSelectionParams,
+) -> Result<Selection, CoinSelectionError> {
+    let mut sorted = candidates.to_vec();
+    sorted.sort_by_key(|candidate| Reverse(candidate.value));
+
+    let target = params.target.to_sat();
+    let mut selected_value = 0u64;
+    let mut weight = params.base_weight;
+
+    for (count, candidate) in sorted.iter().enumerate() {
+        selected_value = selected_value
+            .checked_add(candidate.value.to_sat())
+            .ok_or(CoinSelectionError::Overflow)?;
+        weight += candidate.input_weight;
+
+        let fee = params.fee(weight);
+        match target.checked_add(fee) {
+            Some(needed) if selected_value >= needed => {}
+            _ => continue,
+        }
+
+        let fee_with_change = params.fee(weight + params.change_output_weight);
+        let change = target
+            .checked_add(fee_with_change)
+            .and_then(|needed| selected_value.checked_sub(needed));
+        let (fee, change) = match change {
+            Some(change) if change >= params.dust_threshold.to_sat() => {
+                (fee_with_change, Some(Amount::from_sat(change)))
+            }
+            // Not worth a change output, the excess goes to the fee.
+            _ => (selected_value - target, None),
+        };
+
+        sorted.truncate(count + 1);
+        return Ok(Selection {
+            selected: sorted,
+            fee: Amount::from_sat(fee),
+            change,
+        });
+    }
+
+    Err(CoinSelectionError::InsufficientFunds {
+        available: selected_value,
+        target,
+        fee: params.fee(weight),
+    })
+}
+
+/// Searches for candidates paying for the target and the fee without change, i.e. with an
+/// excess smaller than the cost of creating and later spending a change output, which is
+/// given to the fee. Among the matches found, the one with the smallest excess is returned.
+///
+/// This is the branch and bound algorithm of Bitcoin Core, exploring at most 100,000
+/// branches. Fails if no match is found, in which case a selector creating change, like
+/// [`select_largest_first`], should be used instead.
+pub fn select_branch_and_bound(
+    candidates: &[Candidate],
+    params: &CoinSelectionParams,
+) -> Result<Selection, CoinSelectionError> {
+    // The value of each candidate once the fee of its input is paid, largest first. Candidates
+    // costing more than they are worth are never useful.
+    let mut pool: Vec<(u64, &Candidate)> = candidates
+        .iter()
+        .filter_map(|candidate| {
+            let fee = params.fee(candidate.input_weight);
+            candidate
+                .value
+                .to_sat()
+                .checked_sub(fee)
+                .filter(|value| *value > 0)
+                .map(|value| (value, candidate))
+        })
+        .collect();
+    pool.sort_by_key(|(value, _)| Reverse(*value));
+
+    let target = params
+        .target
+        .to_sat()
+        .saturating_add(params.fee(params.base_weight));
+    let cost_of_change =
+        params.fee(params.change_output_weight) + params.fee(params.change_spend_weight);
+    let upper_bound = target.saturating_add(cost_of_change);
+
+    // Depth-first search: each candidate in turn is included, then excluded on backtrack.
+    let mut included: Vec<usize> = Vec::new();
+    let mut index = 0;
+    let mut current = 0u64;
+    let mut remaining = pool
+        .iter()
+        .try_fold(0u64, |total, (value, _)| total.checked_add(*value))
+        .ok_or(CoinSelectionError::Overflow)?;
+    let mut best: Option<(u64, Vec<usize>)> = None;
+
+    for _ in 0..BNB_MAX_TRIES {
+        let backtrack = if current.saturating_add(remaining) < target || current > upper_bound {
+            true
+        } else if current >= target {
+            let excess = current - target;
+            if !matches!(&best, Some((best_excess, _)) if *best_excess <= excess) {
+                best = Some((excess, included.clone()));
+            }
+            if excess == 0 {
+                break;
+            }
+            true
+        } else {
+            false
+        };
+
+        if backtrack {
+            // Exclude the last included candidate, the ones after it are undecided again.
+            let Some(last) = included.pop() else {
+                break;
+            };
+            remaining += pool[last + 1..index]
+                .iter()
+                .map(|(value, _)| *value)
+                .sum::<u64>();
+            current -= pool[last].0;
+            index = last + 1;
+            continue;
+        }
+
+        remaining -= pool[index].0;
+        current += pool[index].0;
+        included.push(index);
+        index += 1;
+    }
+
+    let (_, included) = best.ok_or(CoinSelectionError::NoChangelessMatch)?;
+    let selected: Vec<Candidate> = included.iter().map(|&i| *pool[i].1).collect();
+    let selected_value = selected
+        .iter()
+        .map(|candidate| candidate.value.to_sat())
+        .sum::<u64>();
+
+    Ok(Selection {
+        selected,
+        fee: Amount::from_sat(selected_value - params.target.to_sat()),
+        change: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bitcoin::hash_types::{PubkeyHash, ScriptHash, WPubkeyHash};
+    use crate::bitcoin::{
+        bitcoin_transaction::BitcoinTransaction,
+        types::{Hash, LockTime, ScriptBuf, Sequence, TxIn, Txid, Version, Witness},
+    };
+
+    fn candidate(n: u8, value: u64) -> Candidate {
+        Candidate::single_key(
+            OutPoint::new(Txid(Hash::from_byte_array([n; 32])), 0),
+            Amount::from_sat(value),
+            TransactionType::P2WPKH,
+        )
+        .unwrap()
+    }
+
+    fn params(target: u64, fee_rate: u64) -> CoinSelectionParams {
+        let outputs = [TxOut {
+            value: Amount::from_sat(target),
+            script_pubkey: ScriptBuf::new_p2wpkh(&WPubkeyHash::from_byte_array([1; 20])),
+        }];
+        CoinSelectionParams::new(&outputs, fee_rate, TransactionType::P2WPKH)
+    }
+
+    /// Builds the transaction spending `selection` with the largest possible P2WPKH unlocks.
+    fn signed_transaction(selection: &Selection, target: u64) -> BitcoinTransaction {
+        let mut output = vec![TxOut {
+            value: Amount::from_sat(target),
+            script_pubkey: ScriptBuf::new_p2wpkh(&WPubkeyHash::from_byte_array([1; 20])),
+        }];
+        output.extend(selection.change.map(|value| TxOut {
+            value,
+            script_pubkey: ScriptBuf::new_p2wpkh(&WPubkeyHash::from_byte_array([2; 20])),
+        }));
+
+        BitcoinTransaction {
+            version: Version::TWO,
+            lock_time: LockTime::from_height(0).unwrap(),
+            input: selection
+                .selected
+                .iter()
+                .map(|candidate| TxIn {
+                    previous_output: candidate.outpoint,
+                    script_sig: ScriptBuf::default(),
+                    sequence: Sequence::MAX,
+                    witness: Witness::from_slice(&[vec![0x30; 73], vec![0x02; 33]]),
+                })
+                .collect(),
+            output,
+        }
+    }
+
+    #[test]
+    fn test_weights_match_signed_transaction() {
+        let selection = Selection {
+            selected: vec![candidate(1, 10_000), candidate(2, 20_000)],
+            fee: Amount::ZERO,
+            change: Some(Amount::from_sat(1_000)),
+        };
+        let params = params(5_000, 1);
+        let tx = signed_transaction(&selection, 5_000);
+
+        assert_eq!(
+            params.base_weight
+                + params.change_output_weight
+                + selection
+                    .selected
+                    .iter()
+                    .map(|candidate| candidate.input_weight)
+                    .sum::<u64>(),
+            tx.weight()
+        );
+        assert_eq!(output_weight(TransactionType::P2WPKH), 31 * 4);
+    }
+
+    #[test]
+    fn test_from_utxo() {
+        let outpoint = OutPoint::new(Txid::all_zeros(), 1);
+        let utxo = |script_pubkey| Utxo::new(outpoint, Amount::from_sat(1_000), script_pubkey, 6);
+
+        assert_eq!(
+            Candidate::from_utxo(&utxo(ScriptBuf::new_p2pkh(&PubkeyHash::from_byte_array(
+                [1; 20]
+            )))),
+            Some(Candidate::new(outpoint, Amount::from_sat(1_000), 596))
+        );
+        assert_eq!(
+            Candidate::from_utxo(&utxo(ScriptBuf::new_p2sh(&ScriptHash::from_byte_array(
+                [1; 20]
+            )))),
+            None
+        );
+        assert_eq!(Candidate::from_utxo(&utxo(ScriptBuf::new_p2a())), None);
+    }
+
+    #[test]
+    fn test_largest_first_with_change() {
+        let candidates = [
+            candidate(1, 5_000),
+            candidate(2, 50_000),
+            candidate(3, 20_000),
+        ];
+        let params = params(60_000, 2);
+
+        let selection = select_largest_first(&candidates, &params).unwrap();
+
+        assert_eq!(selection.selected, [candidates[1], candidates[2]]);
+        let change = selection.change.unwrap();
+        assert_eq!(
+            selection.fee.to_sat() + change.to_sat() + 60_000,
+            selection.selected_value().to_sat()
+        );
+        let tx = signed_transaction(&selection, 60_000);
+        assert!(selection.fee.to_sat() >= tx.vsize() * 2);
+    }
+
+    #[test]
+    fn test_largest_first_folds_dust_into_fee() {
+        let candidates = [candidate(1, 10_000)];
+        let fee = params(0, 1).fee(params(0, 1).base_weight + candidates[0].input_weight);
+        // Just enough for the fee without change, plus some dust.
+        let target = 10_000 - fee - 100;
+
+        let selection = select_largest_first(&candidates, &params(target, 1)).unwrap();
+
+        assert_eq!(selection.change, None);
+        assert_eq!(selection.fee, Amount::from_sat(fee + 100));
+    }
+
+    #[test]
+    fn test_largest_first_insufficient_funds() {
+        let candidates = [candidate(1, 5_000), candidate(2, 5_000)];
+
+        let err = select_largest_first(&candidates, &params(10_000, 1)).unwrap_err();
+
+        assert!(
+            matches!(
+                err,
+                CoinSelectionError::InsufficientFunds {
+                    available: 10_000,
+                    target: 10_000,
+                    ..
+                }
+            ),
+            "{}",
+            err
+        );
+        assert!(select_largest_first(&[], &params(1, 1)).is_err());
+        assert_eq!(
+            select_largest_first(
+                &[candidate(1, u64::MAX), candidate(2, 1)],
+                &params(u64::MAX, 1)
+            ),
+            Err(CoinSelectionError::Overflow)
+        );
+    }
+
+    #[test]
+    fn test_branch_and_bound_finds_changeless_match() {
+        let params = params(30_000, 1);
+        let input_fee = params.fee(candidate(0, 0).input_weight);
+        let base_fee = params.fee(params.base_weight);
+        // Two candidates pay exactly the target and the fee.
+        let candidates = [
+            candidate(1, 50_000),
+            candidate(2, 10_000 + input_fee),
+            candidate(3, 20_000 + input_fee + base_fee),
+            candidate(4, 25_000),
+        ];
+
+        let selection = select_branch_and_bound(&candidates, &params).unwrap();
+
+        assert_eq!(selection.selected, [candidates[2], candidates[1]]);
+        assert_eq!(selection.change, None);
+        assert_eq!(selection.fee, Amount::from_sat(2 * input_fee + base_fee));
+        let tx = signed_transaction(&selection, 30_000);
+        assert!(selection.fee.to_sat() >= tx.vsize());
+
+        assert_eq!(select_coins(&candidates, &params), Ok(selection));
+    }
+
+    #[test]
+    fn test_branch_and_bound_matches_exhaustive_search() {
+        // Deterministic pseudo-random values.
+        let mut seed = 42u64;
+        let mut next = || {
+            seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1);
+            seed >> 33
+        };
+
+        for _ in 0..50 {
+            let candidates: Vec<Candidate> = (0..10)
+                .map(|n| candidate(n, 1_000 + next() % 20_000))
+                .collect();
+            let params = params(10_000 + next() % 50_000, 1 + next() % 5);
+
+            let target = params.target.to_sat() + params.fee(params.base_weight);
+            let upper_bound = target
+                + params.fee(params.change_output_weight)
+                + params.fee(params.change_spend_weight);
+            let effective_value =
+                |c: &Candidate| c.value.to_sat().saturating_sub(params.fee(c.input_weight));
+
+            // The smallest excess of all subsets within the bounds.
+            let best_excess = (1..1u32 << candidates.len())
+                .filter_map(|mask| {
+                    let value = (0..candidates.len())
+                        .filter(|i| mask & (1 << i) != 0)
+                        .map(|i| effective_value(&candidates[i]))
+                        .sum::<u64>();
+                    (target..=upper_bound)
+                        .contains(&value)
+                        .then(|| value - target)
+                })
+                .min();
+
+            match select_branch_and_bound(&candidates, &params) {
+                Ok(selection) => {
+                    let value = selection.selected.iter().map(effective_value).sum::<u64>();
+                    assert_eq!(Some(value - target), best_excess);
+                }
+                Err(_) => assert_eq!(best_excess, None),
+            }
+        }
+    }
+
+    #[test]
+    fn test_select_coins_falls_back_to_largest_first() {
+        let candidates = [candidate(1, 100_000)];
+        let params = params(10_000, 1);
+
+        assert_eq!(
+            select_branch_and_bound(&candidates, &params),
+            Err(CoinSelectionError::NoChangelessMatch)
+        );
+        assert_eq!(
+            select_coins(&candidates, &params),
+            select_largest_first(&candidates, &params)
+        );
+        assert!(select_coins(&candidates, &params).unwrap().change.is_some());
+    }
+}
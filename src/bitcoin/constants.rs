@@ -1,5 +1,89 @@
+use super::types::Hash;
+
 /// The marker MUST be a 1-byte zero value: 0x00. (BIP-141)
 pub const SEGWIT_MARKER: u8 = 0x00;
 
 /// The flag MUST be a 1-byte non-zero value. Currently, 0x01 MUST be used. (BIP-141)
 pub const SEGWIT_FLAG: u8 = 0x01;
+
+/// Weight of a byte outside of the witness, witness bytes weighing 1. (BIP-141)
+pub const WITNESS_SCALE_FACTOR: u64 = 4;
+
+/// Leaf version of BIP-342 tapscripts.
+pub const TAPROOT_LEAF_TAPSCRIPT: u8 = 0xc0;
+
+/// Size of a control block without its merkle branch: the leaf version and parity byte followed
+/// by the internal key. (BIP-341)
+pub const TAPROOT_CONTROL_BASE_SIZE: usize = 33;
+
+/// Size of each node of the merkle branch of a control block. (BIP-341)
+pub const TAPROOT_CONTROL_NODE_SIZE: usize = 32;
+
+/// Maximum depth of a Taproot script tree, i.e. number of nodes in a merkle branch. (BIP-341)
+pub const TAPROOT_CONTROL_MAX_NODE_COUNT: usize = 128;
+
+/// Magic bytes starting every P2P message on mainnet, in wire order.
+pub const MAINNET_MAGIC: [u8; 4] = [0xf9, 0xbe, 0xb4, 0xd9];
+/// Magic bytes starting every P2P message on testnet3, in wire order.
+pub const TESTNET_MAGIC: [u8; 4] = [0x0b, 0x11, 0x09, 0x07];
+/// Magic bytes starting every P2P message on the default signet, in wire order.
+///
+/// Custom signets derive their magic from their challenge script.
+pub const SIGNET_MAGIC: [u8; 4] = [0x0a, 0x03, 0xcf, 0x40];
+/// Magic bytes starting every P2P message on regtest, in wire order.
+pub const REGTEST_MAGIC: [u8; 4] = [0xfa, 0xbf, 0xb5, 0xda];
+
+/// Default P2P port of mainnet nodes.
+pub const MAINNET_DEFAULT_PORT: u16 = 8333;
+/// Default P2P port of testnet3 nodes.
+pub const TESTNET_DEFAULT_PORT: u16 = 18333;
+/// Default P2P port of signet nodes.
+pub const SIGNET_DEFAULT_PORT: u16 = 38333;
+/// Default P2P port of regtest nodes.
+pub const REGTEST_DEFAULT_PORT: u16 = 18444;
+
+/// Hash of the mainnet genesis block,
+/// `000000000019d6689c085ae165831e934ff763ae46a2a6c172b3f1b60a8ce26f`.
+pub const MAINNET_GENESIS_BLOCK_HASH: Hash = Hash::from_byte_array([
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x19, 0xd6, 0x68, 0x9c, 0x08, 0x5a, 0xe1, 0x65, 0x83, 0x1e, 0x93,
+    0x4f, 0xf7, 0x63, 0xae, 0x46, 0xa2, 0xa6, 0xc1, 0x72, 0xb3, 0xf1, 0xb6, 0x0a, 0x8c, 0xe2, 0x6f,
+]);
+/// Hash of the testnet3 genesis block,
+/// `000000000933ea01ad0ee984209779baaec3ced90fa3f408719526f8d77f4943`.
+pub const TESTNET_GENESIS_BLOCK_HASH: Hash = Hash::from_byte_array([
+    0x00, 0x00, 0x00, 0x00, 0x09, 0x33, 0xea, 0x01, 0xad, 0x0e, 0xe9, 0x84, 0x20, 0x97, 0x79, 0xba,
+    0xae, 0xc3, 0xce, 0xd9, 0x0f, 0xa3, 0xf4, 0x08, 0x71, 0x95, 0x26, 0xf8, 0xd7, 0x7f, 0x49, 0x43,
+]);
+/// Hash of the default signet genesis block,
+/// `00000008819873e925422c1ff0f99f7cc9bbb232af63a077a480a3633bee1ef6`.
+pub const SIGNET_GENESIS_BLOCK_HASH: Hash = Hash::from_byte_array([
+    0x00, 0x00, 0x00, 0x08, 0x81, 0x98, 0x73, 0xe9, 0x25, 0x42, 0x2c, 0x1f, 0xf0, 0xf9, 0x9f, 0x7c,
+    0xc9, 0xbb, 0xb2, 0x32, 0xaf, 0x63, 0xa0, 0x77, 0xa4, 0x80, 0xa3, 0x63, 0x3b, 0xee, 0x1e, 0xf6,
+]);
+/// Hash of the regtest genesis block,
+/// `0f9188f13cb7b2c71f2a335e3a4fc328bf5beb436012afca590b1a11466e2206`.
+pub const REGTEST_GENESIS_BLOCK_HASH: Hash = Hash::from_byte_array([
+    0x0f, 0x91, 0x88, 0xf1, 0x3c, 0xb7, 0xb2, 0xc7, 0x1f, 0x2a, 0x33, 0x5e, 0x3a, 0x4f, 0xc3, 0x28,
+    0xbf, 0x5b, 0xeb, 0x43, 0x60, 0x12, 0xaf, 0xca, 0x59, 0x0b, 0x1a, 0x11, 0x46, 0x6e, 0x22, 0x06,
+]);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bitcoin::bitcoin_transaction::sha256d;
+
+    #[test]
+    fn test_genesis_block_hashes() {
+        // The genesis block headers only differ by their timestamp, target and nonce.
+        for (header, hash) in [
+            ("0100000000000000000000000000000000000000000000000000000000000000000000003ba3edfd7a7b12b27ac72c3e67768f617fc81bc3888a51323a9fb8aa4b1e5e4a29ab5f49ffff001d1dac2b7c", MAINNET_GENESIS_BLOCK_HASH),
+            ("0100000000000000000000000000000000000000000000000000000000000000000000003ba3edfd7a7b12b27ac72c3e67768f617fc81bc3888a51323a9fb8aa4b1e5e4adae5494dffff001d1aa4ae18", TESTNET_GENESIS_BLOCK_HASH),
+            ("0100000000000000000000000000000000000000000000000000000000000000000000003ba3edfd7a7b12b27ac72c3e67768f617fc81bc3888a51323a9fb8aa4b1e5e4a008f4d5fae77031e8ad22203", SIGNET_GENESIS_BLOCK_HASH),
+            ("0100000000000000000000000000000000000000000000000000000000000000000000003ba3edfd7a7b12b27ac72c3e67768f617fc81bc3888a51323a9fb8aa4b1e5e4adae5494dffff7f2002000000", REGTEST_GENESIS_BLOCK_HASH),
+        ] {
+            let mut computed = sha256d(&hex::decode(header).unwrap());
+            computed.reverse();
+            assert_eq!(computed, hash.as_byte_array(), "header {}", header);
+        }
+    }
+}
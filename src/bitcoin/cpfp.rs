@@ -0,0 +1,393 @@
+//! Child-pays-for-parent (CPFP) fee bumping.
+//!
+//! A presigned parent transaction (e.g. in an MPC signed transaction tree) cannot change its
+//! fee. Instead, a child spending one of its outputs, typically a P2A anchor or a change output,
+//! pays for both so the package reaches the target fee rate.
+use core::fmt;
+
+use super::{
+    bitcoin_transaction::BitcoinTransaction,
+    types::{
+        Amount, LockTime, OutPoint, ScriptBuf, Sequence, TransactionType, TxIn, TxOut, Utxo,
+        Witness,
+    },
+};
+
+/// Length of the largest DER signature along with its sighash type byte.
+const MAX_SIGNATURE_LEN: usize = 73;
+/// Length of a compressed public key.
+const PUBLIC_KEY_LEN: usize = 33;
+
+/// Errors returned by [`build_cpfp_package`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CpfpError {
+    /// The parent has no output at the spent index.
+    UnknownVout(u32),
+    /// A value or fee overflows.
+    Overflow,
+    /// The child inputs, in satoshis, cannot pay the child fee and a change above its dust
+    /// threshold.
+    InsufficientFunds { inputs: u64, fee: u64 },
+    /// The size of an input spending this type of output can't be estimated.
+    UnsupportedSpendKind(TransactionType),
+}
+
+impl fmt::Display for CpfpError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownVout(vout) => write!(f, "parent has no output {}", vout),
+            Self::Overflow => write!(f, "value overflows"),
+            Self::InsufficientFunds { inputs, fee } => write!(
+                f,
+                "child inputs ({} sat) cannot pay a {} sat fee and a change above dust",
+                inputs, fee
+            ),
+            Self::UnsupportedSpendKind(tx_type) => {
+                write!(f, "cannot estimate the size of a {:?} input", tx_type)
+            }
+        }
+    }
+}
+
+impl std::error::Error for CpfpError {}
+
+/// How an input of the child transaction is spent, used to estimate the child size before it
+/// is signed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpendKind {
+    /// A Pay-to-Anchor output, spent with an empty witness.
+    Anchor,
+    /// A single key output, spent with one signature.
+    ///
+    /// Only [`TransactionType::P2PKH`] and [`TransactionType::P2WPKH`] have a known size.
+    SingleKey(TransactionType),
+}
+
+/// The parameters of a CPFP child transaction.
+#[derive(Debug, Clone)]
+pub struct CpfpRequest {
+    /// The fee paid by the parent transaction.
+    pub parent_fee: Amount,
+    /// The index of the parent output spent by the child.
+    pub vout: u32,
+    /// How the parent output is spent.
+    pub vout_spend: SpendKind,
+    /// Additional confirmed outputs funding the child, needed when the parent output (e.g. an
+    /// ephemeral anchor) is too small to pay the fee.
+    pub funding: Vec<(Utxo, SpendKind)>,
+    /// The output receiving what is left of the child inputs.
+    pub change_script_pubkey: ScriptBuf,
+    /// The smallest change the child may create.
+    pub change_dust_threshold: Amount,
+    /// The target fee rate of the parent and child package, in sat/vB.
+    pub package_fee_rate: u64,
+}
+
+/// A parent transaction along with its CPFP child.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CpfpPackage {
+    /// The serialized parent transaction.
+    pub parent: Vec<u8>,
+    /// The child transaction, whose single key inputs still have to be signed.
+    pub child: BitcoinTransaction,
+    /// The serialized child transaction. It is final if the child only spends anchors.
+    pub child_serialized: Vec<u8>,
+    /// The fee paid by the child.
+    pub child_fee: Amount,
+    /// The estimated virtual size of the package, once the child is signed.
+    pub package_vsize: u64,
+}
+
+/// Builds a child transaction spending `request.vout` of the (fully signed) `parent`, which
+/// pays enough for the package to reach `request.package_fee_rate`.
+///
+/// The child pays at least the target fee rate for its own size, even if the parent already
+/// pays more than the target.
+pub fn build_cpfp_package(
+    parent: &BitcoinTransaction,
+    request: &CpfpRequest,
+) -> Result<CpfpPackage, CpfpError> {
+    let spent_output = parent
+        .output
+        .get(request.vout as usize)
+        .ok_or(CpfpError::UnknownVout(request.vout))?;
+
+    let mut inputs = vec![(
+        OutPoint::new(parent.compute_txid(), request.vout),
+        spent_output.value,
+        request.vout_spend,
+    )];
+    inputs.extend(
+        request
+            .funding
+            .iter()
+            .map(|(utxo, spend)| (utxo.outpoint, utxo.value, *spend)),
+    );
+
+    let inputs_value = inputs
+        .iter()
+        .try_fold(Amount::ZERO, |total, (_, value, _)| {
+            total.checked_add(*value)
+        })
+        .ok_or(CpfpError::Overflow)?;
+
+    let mut child = BitcoinTransaction {
+        // A child of a v3 (TRUC) parent must be v3 as well.
+        version: parent.version,
+        lock_time: LockTime::from_height(0).expect("0 is a valid height"),
+        input: inputs
+            .iter()
+            .map(|(previous_output, _, _)| TxIn {
+                previous_output: *previous_output,
+                script_sig: ScriptBuf::default(),
+                sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+                witness: Witness::default(),
+            })
+            .collect(),
+        output: vec![TxOut {
+            value: inputs_value,
+            script_pubkey: request.change_script_pubkey.clone(),
+        }],
+    };
+
+    let parent_vsize = parent.vsize();
+    let child_vsize = with_dummy_unlocks(&child, &inputs)?.vsize();
+    let package_vsize = parent_vsize + child_vsize;
+
+    let rate = request.package_fee_rate;
+    let package_fee = package_vsize.checked_mul(rate).ok_or(CpfpError::Overflow)?;
+    let child_fee = Amount::from_sat(
+        package_fee
+            .saturating_sub(request.parent_fee.to_sat())
+            .max(child_vsize.saturating_mul(rate)),
+    );
+
+    let change = inputs_value
+        .checked_sub(child_fee)
+        .filter(|change| *change >= request.change_dust_threshold)
+        .ok_or(CpfpError::InsufficientFunds {
+            inputs: inputs_value.to_sat(),
+            fee: child_fee.to_sat(),
+        })?;
+    child.output[0].value = change;
+
+    Ok(CpfpPackage {
+        parent: parent.serialize(),
+        child_serialized: child.serialize(),
+        child,
+        child_fee,
+        package_vsize,
+    })
+}
+
+/// Returns a copy of the child with placeholder unlocks of the largest possible size.
+fn with_dummy_unlocks(
+    child: &BitcoinTransaction,
+    inputs: &[(OutPoint, Amount, SpendKind)],
+) -> Result<BitcoinTransaction, CpfpError> {
+    let mut child = child.clone();
+
+    for (input, (_, _, spend)) in child.input.iter_mut().zip(inputs) {
+        match spend {
+            SpendKind::Anchor => {}
+            SpendKind::SingleKey(TransactionType::P2PKH) => {
+                let mut script_sig = vec![MAX_SIGNATURE_LEN as u8];
+                script_sig.extend_from_slice(&[0u8; MAX_SIGNATURE_LEN]);
+                script_sig.push(PUBLIC_KEY_LEN as u8);
+                script_sig.extend_from_slice(&[0u8; PUBLIC_KEY_LEN]);
+                input.script_sig = ScriptBuf(script_sig);
+            }
+            SpendKind::SingleKey(TransactionType::P2WPKH) => {
+                input.witness = Witness::from_slice(&[
+                    [0u8; MAX_SIGNATURE_LEN].to_vec(),
+                    [0u8; PUBLIC_KEY_LEN].to_vec(),
+                ]);
+            }
+            SpendKind::SingleKey(tx_type) => {
+                return Err(CpfpError::UnsupportedSpendKind(*tx_type));
+            }
+        }
+    }
+
+    Ok(child)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bitcoin::types::{Hash, Txid, Version};
+
+    fn parent() -> BitcoinTransaction {
+        BitcoinTransaction {
+            version: Version::non_standard(3),
+            lock_time: LockTime::from_height(0).unwrap(),
+            input: vec![TxIn {
+                previous_output: OutPoint::new(Txid(Hash::all_zeros()), 0),
+                script_sig: ScriptBuf::default(),
+                sequence: Sequence::MAX,
+                witness: Witness::from_slice(&[vec![1u8; 72], vec![2u8; 33]]),
+            }],
+            output: vec![
+                TxOut {
+                    value: Amount::from_sat(50_000),
+                    script_pubkey: ScriptBuf(
+                        vec![0x00, 0x14].into_iter().chain([3u8; 20]).collect(),
+                    ),
+                },
+                TxOut::ephemeral_anchor(),
+            ],
+        }
+    }
+
+    fn funding() -> (Utxo, SpendKind) {
+        let utxo = Utxo::new(
+            OutPoint::new(Txid(Hash::from_byte_array([9u8; 32])), 1),
+            Amount::from_sat(20_000),
+            ScriptBuf::default(),
+            6,
+        );
+        (utxo, SpendKind::SingleKey(TransactionType::P2WPKH))
+    }
+
+    #[test]
+    fn test_child_pays_for_zero_fee_parent() {
+        let parent = parent();
+        let request = CpfpRequest {
+            parent_fee: Amount::ZERO,
+            vout: 1,
+            vout_spend: SpendKind::Anchor,
+            funding: vec![funding()],
+            change_script_pubkey: ScriptBuf(vec![0x51]),
+            change_dust_threshold: Amount::from_sat(294),
+            package_fee_rate: 10,
+        };
+
+        let package = build_cpfp_package(&parent, &request).unwrap();
+
+        let parent_vsize = parent.vsize();
+        assert_eq!(package.parent, parent.serialize());
+        assert!(package.package_vsize > parent_vsize);
+        assert_eq!(
+            package.child_fee,
+            Amount::from_sat(package.package_vsize * 10)
+        );
+        assert_eq!(
+            package.child.output[0].value,
+            Amount::from_sat(20_000 - package.child_fee.to_sat())
+        );
+        // The child spends the anchor of the parent and keeps its version.
+        assert_eq!(
+            package.child.input[0].previous_output.txid,
+            parent.compute_txid()
+        );
+        assert_eq!(package.child.input[0].previous_output.vout, 1);
+        assert_eq!(package.child.version, parent.version);
+    }
+
+    #[test]
+    fn test_child_pays_at_least_its_own_size() {
+        let parent = parent();
+        let request = CpfpRequest {
+            parent_fee: Amount::from_sat(100_000),
+            vout: 0,
+            vout_spend: SpendKind::SingleKey(TransactionType::P2WPKH),
+            funding: vec![],
+            change_script_pubkey: ScriptBuf(vec![0x51]),
+            change_dust_threshold: Amount::from_sat(294),
+            package_fee_rate: 2,
+        };
+
+        let package = build_cpfp_package(&parent, &request).unwrap();
+
+        let child_vsize = package.package_vsize - parent.vsize();
+        assert_eq!(package.child_fee, Amount::from_sat(child_vsize * 2));
+    }
+
+    #[test]
+    fn test_anchor_alone_cannot_pay() {
+        let request = CpfpRequest {
+            parent_fee: Amount::ZERO,
+            vout: 1,
+            vout_spend: SpendKind::Anchor,
+            funding: vec![],
+            change_script_pubkey: ScriptBuf(vec![0x51]),
+            change_dust_threshold: Amount::from_sat(294),
+            package_fee_rate: 1,
+        };
+
+        assert!(matches!(
+            build_cpfp_package(&parent(), &request),
+            Err(CpfpError::InsufficientFunds { inputs: 0, .. })
+        ));
+    }
+
+    #[test]
+    fn test_invalid_requests() {
+        let mut request = CpfpRequest {
+            parent_fee: Amount::ZERO,
+            vout: 2,
+            vout_spend: SpendKind::Anchor,
+            funding: vec![funding()],
+            change_script_pubkey: ScriptBuf(vec![0x51]),
+            change_dust_threshold: Amount::from_sat(294),
+            package_fee_rate: 1,
+        };
+        assert_eq!(
+            build_cpfp_package(&parent(), &request),
+            Err(CpfpError::UnknownVout(2))
+        );
+
+        request.vout = 0;
+        request.vout_spend = SpendKind::SingleKey(TransactionType::P2TR);
+        assert_eq!(
+            build_cpfp_package(&parent(), &request),
+            Err(CpfpError::UnsupportedSpendKind(TransactionType::P2TR))
+        );
+
+        request.vout_spend = SpendKind::SingleKey(TransactionType::P2WPKH);
+        request.package_fee_rate = u64::MAX;
+        assert_eq!(
+            build_cpfp_package(&parent(), &request),
+            Err(CpfpError::Overflow)
+        );
+    }
+
+    #[test]
+    fn test_compute_txid() {
+        // The first bitcoin transfer, in block 170.
+        let tx = BitcoinTransaction {
+            version: Version::ONE,
+            lock_time: LockTime::from_height(0).unwrap(),
+            input: vec![TxIn {
+                previous_output: OutPoint::new(
+                    Txid(
+                        "0437cd7f8525ceed2324359c2d0ba26006d92d856a9c20fa0241106ee5a597c9"
+                            .parse()
+                            .unwrap(),
+                    ),
+                    0,
+                ),
+                script_sig: ScriptBuf::from_hex("47304402204e45e16932b8af514961a1d3a1a25fdf3f4f7732e9d624c6c61548ab5fb8cd410220181522ec8eca07de4860a4acdd12909d831cc56cbbac4622082221a8768d1d0901").unwrap(),
+                sequence: Sequence::MAX,
+                witness: Witness::default(),
+            }],
+            output: vec![
+                TxOut {
+                    value: Amount::from_sat(1_000_000_000),
+                    script_pubkey: ScriptBuf::from_hex("4104ae1a62fe09c5f51b13905f07f06b99a2f7159b2225f374cd378d71302fa28414e7aab37397f554a7df5f142c21c1b7303b8a0626f1baded5c72a704f7e6cd84cac").unwrap(),
+                },
+                TxOut {
+                    value: Amount::from_sat(4_000_000_000),
+                    script_pubkey: ScriptBuf::from_hex("410411db93e1dcdb8a016b49840f8c53bc1eb68a382e97b1482ecad7b148a6909a5cb2e0eaddfb84ccf9744464f82e160bfa9b8b64f9d4c03f999b8643f656b412a3ac").unwrap(),
+                },
+            ],
+        };
+
+        assert_eq!(
+            tx.compute_txid().0.to_string(),
+            "f4184fc596403b9d638783cf57adfe4c75c605f6356fbc91338530e9831e9e16"
+        );
+        // Without witnesses, the virtual size is the serialized size.
+        assert_eq!(tx.vsize(), 275);
+    }
+}
@@ -4,7 +4,7 @@ use super::{
     decode::Decodable,
     extensions::{ReadExt, WriteExt},
     macros::{impl_array, impl_int_encodable},
-    utils::encode_with_size,
+    utils::{encode_with_size, VarInt},
 };
 
 /// Data which can be encoded in a bitcoin-consistent way.
@@ -16,6 +16,12 @@ pub trait Encodable {
     /// The number of bytes written on success. The only errors returned are errors propagated from
     /// the writer.
     fn encode<W: Write + ?Sized>(&self, writer: &mut W) -> Result<usize, std::io::Error>;
+
+    /// Returns the number of bytes written by [`Self::encode`], without allocating a buffer.
+    fn encoded_size(&self) -> usize {
+        self.encode(&mut std::io::sink())
+            .expect("writing to a sink should not fail")
+    }
 }
 
 // Encodable implementations for arrays
@@ -32,4 +38,8 @@ impl Encodable for Vec<u8> {
     fn encode<W: Write + ?Sized>(&self, w: &mut W) -> Result<usize, std::io::Error> {
         encode_with_size(self, w)
     }
+
+    fn encoded_size(&self) -> usize {
+        VarInt(self.len() as u64).size() + self.len()
+    }
 }
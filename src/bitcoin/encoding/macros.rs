@@ -67,9 +67,92 @@ macro_rules! impl_to_u64 {
     }
 }
 
+/// Implements [`Encodable`](crate::bitcoin::encoding::Encodable) and
+/// [`Decodable`](crate::bitcoin::encoding::Decodable) for a struct by encoding its fields one
+/// after the other, in the given order, without any length prefix nor separator.
+///
+/// Every listed field must itself implement both traits, and the struct must have no other
+/// field. This lets crates extending the bitcoin module, e.g. to embed metadata in an
+/// `OP_RETURN` output, define composite types that serialize like the built-in ones.
+///
+/// ```ignore
+/// use omni_transaction::bitcoin::types::Txid;
+/// use omni_transaction::impl_consensus_encoding;
+///
+/// pub struct Anchor {
+///     pub txid: Txid,
+///     pub height: u32,
+/// }
+///
+/// impl_consensus_encoding!(Anchor, txid, height);
+/// ```
+#[macro_export]
+macro_rules! impl_consensus_encoding {
+    ($thing:ident, $($field:ident),+ $(,)?) => {
+        impl $crate::bitcoin::encoding::Encodable for $thing {
+            fn encode<W: std::io::Write + ?Sized>(
+                &self,
+                w: &mut W,
+            ) -> core::result::Result<usize, std::io::Error> {
+                let mut len = 0;
+                $(len += $crate::bitcoin::encoding::Encodable::encode(&self.$field, w)?;)+
+                Ok(len)
+            }
+        }
+
+        impl $crate::bitcoin::encoding::Decodable for $thing {
+            fn decode_from_finite_reader<R: std::io::BufRead + ?Sized>(
+                r: &mut R,
+            ) -> core::result::Result<Self, std::io::Error> {
+                Ok($thing {
+                    $($field: $crate::bitcoin::encoding::Decodable::decode_from_finite_reader(r)?),+
+                })
+            }
+        }
+    };
+}
+
 // Export macros for use in other modules
+pub use crate::impl_consensus_encoding;
 pub(crate) use decoder_fn;
 pub(crate) use encoder_fn;
 pub(crate) use impl_array;
 pub(crate) use impl_int_encodable;
 pub(crate) use impl_to_u64;
+
+#[cfg(test)]
+mod tests {
+    use crate::bitcoin::encoding::{Decodable, Encodable};
+    use crate::bitcoin::types::{ScriptBuf, Txid};
+
+    #[derive(Debug, PartialEq)]
+    struct Anchor {
+        txid: Txid,
+        height: u32,
+        payload: ScriptBuf,
+    }
+
+    impl_consensus_encoding!(Anchor, txid, height, payload);
+
+    #[test]
+    fn test_impl_consensus_encoding() {
+        let anchor = Anchor {
+            txid: Txid::all_zeros(),
+            height: 840_000,
+            payload: ScriptBuf::from_bytes(vec![0x6a, 0x01, 0xff]),
+        };
+
+        let mut buffer = vec![];
+        let len = anchor.encode(&mut buffer).unwrap();
+
+        let mut expected = vec![0; 32];
+        expected.extend_from_slice(&840_000u32.to_le_bytes());
+        expected.extend_from_slice(&[0x03, 0x6a, 0x01, 0xff]);
+        assert_eq!(buffer, expected);
+        assert_eq!(len, buffer.len());
+        assert_eq!(anchor.encoded_size(), buffer.len());
+
+        let decoded = Anchor::decode(&mut buffer.as_slice()).unwrap();
+        assert_eq!(decoded, anchor);
+    }
+}
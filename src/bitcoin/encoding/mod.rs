@@ -7,4 +7,5 @@ pub mod utils;
 pub use decode::Decodable;
 pub use encode::Encodable;
 pub use extensions::{ReadExt, WriteExt};
+pub use macros::impl_consensus_encoding;
 pub use utils::{encode_with_size, ToU64};
@@ -0,0 +1,257 @@
+//! Fixed-size hashes with a distinct type per use, so a block hash can't be passed where a
+//! txid is expected.
+//!
+//! Like [`Hash`](struct@Hash), the double SHA-256 hashes ([`Txid`], [`Wtxid`], [`BlockHash`])
+//! store their bytes in display order, i.e. as printed by block explorers and bitcoind, and are
+//! serialized in reverse. The HASH160 hashes of scripts and keys ([`PubkeyHash`],
+//! [`ScriptHash`], [`WPubkeyHash`]) and the SHA-256 of witness scripts ([`WScriptHash`]) are
+//! displayed in the order they are serialized in.
+use core::fmt;
+use std::io::{BufRead, Write};
+use std::str::FromStr;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use serde::{Deserialize, Serialize};
+
+use super::encoding::{Decodable, Encodable};
+pub use super::types::{Hash, Txid};
+
+/// Writes `bytes` to `w`, reversed if they are stored in display order.
+fn encode_bytes<const N: usize, W: Write + ?Sized>(
+    bytes: &[u8; N],
+    reversed: bool,
+    w: &mut W,
+) -> Result<usize, std::io::Error> {
+    if reversed {
+        let mut wire = *bytes;
+        wire.reverse();
+        w.write_all(&wire)?;
+    } else {
+        w.write_all(bytes)?;
+    }
+    Ok(N)
+}
+
+/// Reads `N` bytes from `r`, reversed if they are stored in display order.
+fn decode_bytes<const N: usize, R: BufRead + ?Sized>(
+    reversed: bool,
+    r: &mut R,
+) -> Result<[u8; N], std::io::Error> {
+    let mut bytes = [0u8; N];
+    r.read_exact(&mut bytes)?;
+    if reversed {
+        bytes.reverse();
+    }
+    Ok(bytes)
+}
+
+/// Defines a hash newtype over `[u8; $len]`, serialized in reverse of its display order if
+/// `$reversed`.
+macro_rules! hash_newtype {
+    ($(#[$attr:meta])* $name:ident, $len:expr, $reversed:expr) => {
+        $(#[$attr])*
+        #[derive(
+            Debug,
+            Copy,
+            Clone,
+            Eq,
+            PartialEq,
+            Hash,
+            Serialize,
+            Deserialize,
+            BorshSerialize,
+            BorshDeserialize,
+        )]
+        pub struct $name([u8; $len]);
+
+        impl $name {
+            /// Length of the hash in bytes.
+            pub const LEN: usize = $len;
+
+            /// Creates a hash from bytes in display order.
+            pub const fn from_byte_array(bytes: [u8; $len]) -> Self {
+                Self(bytes)
+            }
+
+            /// Returns the bytes of the hash in display order.
+            pub const fn as_byte_array(&self) -> [u8; $len] {
+                self.0
+            }
+
+            pub const fn all_zeros() -> Self {
+                Self([0; $len])
+            }
+
+            /// Parses a hash in display order.
+            pub fn from_hex(hex: &str) -> Result<Self, hex::FromHexError> {
+                let mut bytes = [0u8; $len];
+                hex::decode_to_slice(hex, &mut bytes)?;
+                Ok(Self(bytes))
+            }
+        }
+
+        impl From<[u8; $len]> for $name {
+            fn from(bytes: [u8; $len]) -> Self {
+                Self(bytes)
+            }
+        }
+
+        impl From<$name> for [u8; $len] {
+            fn from(hash: $name) -> Self {
+                hash.0
+            }
+        }
+
+        impl Encodable for $name {
+            fn encode<W: Write + ?Sized>(&self, w: &mut W) -> Result<usize, std::io::Error> {
+                encode_bytes(&self.0, $reversed, w)
+            }
+        }
+
+        impl Decodable for $name {
+            fn decode<R: BufRead + ?Sized>(r: &mut R) -> Result<Self, std::io::Error> {
+                decode_bytes($reversed, r).map(Self)
+            }
+        }
+
+        impl FromStr for $name {
+            type Err = hex::FromHexError;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                Self::from_hex(s)
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "{}", hex::encode(self.0))
+            }
+        }
+    };
+}
+
+/// Defines conversions between a double SHA-256 hash newtype and [`Hash`], which share the
+/// same display order.
+macro_rules! impl_hash_conversions {
+    ($name:ident) => {
+        impl From<Hash> for $name {
+            fn from(hash: Hash) -> Self {
+                Self(hash.as_byte_array())
+            }
+        }
+
+        impl From<$name> for Hash {
+            fn from(hash: $name) -> Self {
+                Hash::from_byte_array(hash.0)
+            }
+        }
+    };
+}
+
+hash_newtype!(
+    /// The witness transaction id: the double SHA-256 of the transaction serialized with its
+    /// witnesses, see [`BitcoinTransaction::compute_wtxid`].
+    ///
+    /// [`BitcoinTransaction::compute_wtxid`]: super::bitcoin_transaction::BitcoinTransaction::compute_wtxid
+    Wtxid,
+    32,
+    true
+);
+hash_newtype!(
+    /// The hash of a block: the double SHA-256 of its header.
+    BlockHash,
+    32,
+    true
+);
+hash_newtype!(
+    /// The HASH160 of a public key, locked by P2PKH outputs.
+    PubkeyHash,
+    20,
+    false
+);
+hash_newtype!(
+    /// The HASH160 of a redeem script, locked by P2SH outputs.
+    ScriptHash,
+    20,
+    false
+);
+hash_newtype!(
+    /// The HASH160 of a compressed public key, locked by P2WPKH outputs.
+    WPubkeyHash,
+    20,
+    false
+);
+hash_newtype!(
+    /// The SHA-256 of a witness script, locked by P2WSH outputs.
+    WScriptHash,
+    32,
+    false
+);
+
+impl_hash_conversions!(Wtxid);
+impl_hash_conversions!(BlockHash);
+
+impl Wtxid {
+    /// Returns the wtxid of a transaction without witnesses, which is its txid.
+    pub const fn from_txid(txid: Txid) -> Self {
+        Self(txid.as_byte_array())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bitcoin::hashes::Hash as _;
+
+    #[test]
+    fn test_display_and_serialization_order_against_rust_bitcoin() {
+        let hex = "000000000019d6689c085ae165831e934ff763ae46a2a6c172b3f1b60a8ce26f";
+
+        let block_hash: BlockHash = hex.parse().unwrap();
+        let expected: bitcoin::BlockHash = hex.parse().unwrap();
+        assert_eq!(block_hash.to_string(), expected.to_string());
+        assert_eq!(
+            block_hash.encoded_size(),
+            bitcoin::consensus::serialize(&expected).len()
+        );
+        let mut buf = Vec::new();
+        block_hash.encode(&mut buf).unwrap();
+        assert_eq!(buf, bitcoin::consensus::serialize(&expected));
+        assert_eq!(BlockHash::decode(&mut buf.as_slice()).unwrap(), block_hash);
+        assert_eq!(Hash::from(block_hash).to_string(), hex);
+
+        let hash = [0x42; 20];
+        let script_hash = ScriptHash::from_byte_array(hash);
+        assert_eq!(
+            script_hash.to_string(),
+            bitcoin::ScriptHash::from_byte_array(hash).to_string()
+        );
+        let mut buf = Vec::new();
+        script_hash.encode(&mut buf).unwrap();
+        assert_eq!(buf, hash);
+
+        let mut hash = [0u8; 32];
+        hash[0] = 1;
+        assert_eq!(
+            WScriptHash::from(hash).to_string(),
+            bitcoin::WScriptHash::from_byte_array(hash).to_string()
+        );
+    }
+
+    #[test]
+    fn test_from_hex() {
+        let hex = "62e907b15cbf27d5425399ebf6f0fb50ebb88f18";
+        let pubkey_hash = PubkeyHash::from_hex(hex).unwrap();
+
+        assert_eq!(pubkey_hash.as_byte_array()[0], 0x62);
+        assert_eq!(pubkey_hash.to_string(), hex);
+        assert_eq!(
+            WPubkeyHash::from_hex(&hex[2..]),
+            Err(hex::FromHexError::InvalidStringLength)
+        );
+        assert_eq!(
+            "zz".repeat(20).parse::<WPubkeyHash>(),
+            Err(hex::FromHexError::InvalidHexCharacter { c: 'z', index: 0 })
+        );
+    }
+}
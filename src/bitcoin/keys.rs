@@ -0,0 +1,289 @@
+//! WIF private key and SEC1 public key parsing.
+//!
+//! These helpers are meant for tests and off-chain tooling. Contracts signing through MPC never
+//! hold private keys.
+use k256::elliptic_curve::sec1::ToEncodedPoint;
+use sha2::{Digest, Sha256};
+use std::fmt;
+
+use super::types::Network;
+
+/// Length of a compressed SEC1 public key.
+const COMPRESSED_PUBLIC_KEY_LEN: usize = 33;
+/// Length of an uncompressed SEC1 public key.
+const UNCOMPRESSED_PUBLIC_KEY_LEN: usize = 65;
+/// Suffix of the WIF payload marking a key whose public key is compressed.
+const WIF_COMPRESSED_SUFFIX: u8 = 0x01;
+
+/// Errors returned when parsing a WIF private key or a SEC1 public key.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KeyError {
+    /// The input is not valid hex.
+    InvalidHex(String),
+    /// The input is not valid Base58.
+    InvalidBase58(String),
+    /// The Base58Check checksum is missing or does not match the payload.
+    InvalidChecksum,
+    /// The WIF payload is neither 33 nor 34 bytes long.
+    InvalidWifLength(usize),
+    /// The last byte of a 34-byte WIF payload is not the compression flag.
+    InvalidCompressionFlag,
+    /// The WIF prefix is not the one of a known network.
+    UnknownWifPrefix(u8),
+    /// The private key is zero or not lower than the curve order.
+    InvalidPrivateKey,
+    /// The public key is neither 33 nor 65 bytes long.
+    InvalidPublicKeyLength(usize),
+    /// The public key is not a valid point on the curve.
+    InvalidPublicKey,
+}
+
+impl fmt::Display for KeyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidHex(reason) => write!(f, "invalid hex: {}", reason),
+            Self::InvalidBase58(reason) => write!(f, "invalid base58: {}", reason),
+            Self::InvalidChecksum => write!(f, "invalid Base58Check checksum"),
+            Self::InvalidWifLength(len) => write!(f, "invalid WIF payload length: {}", len),
+            Self::InvalidCompressionFlag => write!(f, "invalid WIF compression flag"),
+            Self::UnknownWifPrefix(prefix) => write!(f, "unknown WIF prefix: {:#04x}", prefix),
+            Self::InvalidPrivateKey => write!(f, "invalid private key"),
+            Self::InvalidPublicKeyLength(len) => write!(f, "invalid public key length: {}", len),
+            Self::InvalidPublicKey => write!(f, "invalid public key"),
+        }
+    }
+}
+
+impl std::error::Error for KeyError {}
+
+/// A secp256k1 private key, along with the network and public key format it is used with.
+#[derive(Clone, PartialEq, Eq)]
+pub struct PrivateKey {
+    pub network: Network,
+    /// Whether the matching public key is serialized in its compressed form.
+    pub compressed: bool,
+    inner: k256::SecretKey,
+}
+
+impl PrivateKey {
+    /// Creates a private key from its 32 bytes.
+    pub fn from_slice(bytes: &[u8], network: Network, compressed: bool) -> Result<Self, KeyError> {
+        let inner = k256::SecretKey::from_slice(bytes).map_err(|_| KeyError::InvalidPrivateKey)?;
+
+        Ok(Self {
+            network,
+            compressed,
+            inner,
+        })
+    }
+
+    /// Parses a WIF encoded private key.
+    ///
+    /// Keys of the test networks share the same prefix, they are parsed as [`Network::Testnet`].
+    pub fn from_wif(wif: &str) -> Result<Self, KeyError> {
+        let payload = decode_check(wif)?;
+
+        let (compressed, key) = match payload.len() {
+            33 => (false, &payload[1..]),
+            34 if payload[33] == WIF_COMPRESSED_SUFFIX => (true, &payload[1..33]),
+            34 => return Err(KeyError::InvalidCompressionFlag),
+            len => return Err(KeyError::InvalidWifLength(len)),
+        };
+
+        let network = match payload[0] {
+            prefix if prefix == Network::Bitcoin.wif_prefix() => Network::Bitcoin,
+            prefix if prefix == Network::Testnet.wif_prefix() => Network::Testnet,
+            prefix => return Err(KeyError::UnknownWifPrefix(prefix)),
+        };
+
+        Self::from_slice(key, network, compressed)
+    }
+
+    /// Returns the WIF encoding of the private key.
+    pub fn to_wif(&self) -> String {
+        let mut payload = Vec::with_capacity(34);
+        payload.push(self.network.wif_prefix());
+        payload.extend_from_slice(&self.to_bytes());
+        if self.compressed {
+            payload.push(WIF_COMPRESSED_SUFFIX);
+        }
+
+        encode_check(&payload)
+    }
+
+    /// Returns the 32 bytes of the private key.
+    pub fn to_bytes(&self) -> [u8; 32] {
+        self.inner.to_bytes().into()
+    }
+
+    /// Returns the public key, in the format given by [`PrivateKey::compressed`].
+    pub fn public_key(&self) -> PublicKey {
+        PublicKey {
+            compressed: self.compressed,
+            inner: self.inner.public_key(),
+        }
+    }
+}
+
+impl std::fmt::Debug for PrivateKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PrivateKey")
+            .field("network", &self.network)
+            .field("compressed", &self.compressed)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A secp256k1 public key, along with the SEC1 format it is serialized with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PublicKey {
+    pub compressed: bool,
+    inner: k256::PublicKey,
+}
+
+impl PublicKey {
+    /// Parses a compressed (33 bytes) or uncompressed (65 bytes) SEC1 public key, checking that
+    /// it is a point on the curve.
+    pub fn from_slice(bytes: &[u8]) -> Result<Self, KeyError> {
+        let compressed = match bytes.len() {
+            COMPRESSED_PUBLIC_KEY_LEN => true,
+            UNCOMPRESSED_PUBLIC_KEY_LEN => false,
+            len => return Err(KeyError::InvalidPublicKeyLength(len)),
+        };
+        let inner =
+            k256::PublicKey::from_sec1_bytes(bytes).map_err(|_| KeyError::InvalidPublicKey)?;
+
+        Ok(Self { compressed, inner })
+    }
+
+    /// Parses a hex encoded SEC1 public key.
+    pub fn from_hex(s: &str) -> Result<Self, KeyError> {
+        Self::from_slice(&hex::decode(s).map_err(|e| KeyError::InvalidHex(e.to_string()))?)
+    }
+
+    /// Returns the SEC1 encoding of the public key, in the format it was created with.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.inner
+            .to_encoded_point(self.compressed)
+            .as_bytes()
+            .to_vec()
+    }
+
+    /// Returns the hex encoded SEC1 encoding of the public key.
+    pub fn to_hex(&self) -> String {
+        hex::encode(self.to_bytes())
+    }
+}
+
+fn checksum(payload: &[u8]) -> [u8; 4] {
+    let hash = Sha256::digest(Sha256::digest(payload));
+    [hash[0], hash[1], hash[2], hash[3]]
+}
+
+fn encode_check(payload: &[u8]) -> String {
+    let mut data = payload.to_vec();
+    data.extend_from_slice(&checksum(payload));
+    bs58::encode(data).into_string()
+}
+
+fn decode_check(s: &str) -> Result<Vec<u8>, KeyError> {
+    let mut data = bs58::decode(s)
+        .into_vec()
+        .map_err(|e| KeyError::InvalidBase58(e.to_string()))?;
+    if data.len() < 4 {
+        return Err(KeyError::InvalidChecksum);
+    }
+
+    let payload_len = data.len() - 4;
+    if data[payload_len..] != checksum(&data[..payload_len]) {
+        return Err(KeyError::InvalidChecksum);
+    }
+
+    data.truncate(payload_len);
+    Ok(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SECRET: &str = "0c28fca386c7a227600b2fe50b7cae11ec86d3bf1fbe471be89827e19d72aa1d";
+
+    #[test]
+    fn test_wif_roundtrip() {
+        let secret = hex::decode(SECRET).unwrap();
+
+        let uncompressed =
+            PrivateKey::from_wif("5HueCGU8rMjxEXxiPuD5BDku4MkFqeZyd4dZ1jvhTVqvbTLvyTJ").unwrap();
+        assert_eq!(uncompressed.network, Network::Bitcoin);
+        assert!(!uncompressed.compressed);
+        assert_eq!(uncompressed.to_bytes().to_vec(), secret);
+
+        let compressed = PrivateKey::from_slice(&secret, Network::Bitcoin, true).unwrap();
+        assert_eq!(
+            compressed.to_wif(),
+            "KwdMAjGmerYanjeui5SHS7JkmpZvVipYvB2LJGU1ZxJwYvP98617"
+        );
+        assert_eq!(
+            PrivateKey::from_wif(&compressed.to_wif()).unwrap(),
+            compressed
+        );
+    }
+
+    #[test]
+    fn test_wif_testnet() {
+        let secret = hex::decode(SECRET).unwrap();
+        let key = PrivateKey::from_slice(&secret, Network::Regtest, true).unwrap();
+
+        let parsed = PrivateKey::from_wif(&key.to_wif()).unwrap();
+
+        assert_eq!(parsed.network, Network::Testnet);
+        assert_eq!(parsed.to_bytes(), key.to_bytes());
+    }
+
+    #[test]
+    fn test_invalid_wif_checksum() {
+        assert_eq!(
+            PrivateKey::from_wif("5HueCGU8rMjxEXxiPuD5BDku4MkFqeZyd4dZ1jvhTVqvbTLvyTK"),
+            Err(KeyError::InvalidChecksum)
+        );
+    }
+
+    #[test]
+    fn test_public_key_formats() {
+        let secret = hex::decode(SECRET).unwrap();
+        let key = PrivateKey::from_slice(&secret, Network::Bitcoin, false).unwrap();
+
+        let uncompressed = key.public_key();
+        assert_eq!(
+            uncompressed.to_hex(),
+            "04d0de0aaeaefad02b8bdc8a01a1b8b11c696bd3d66a2c5f10780d95b7df42645cd85228a6fb29940e858e7e55842ae2bd115d1ed7cc0e82d934e929c97648cb0a"
+        );
+        assert_eq!(
+            PublicKey::from_hex(&uncompressed.to_hex()).unwrap(),
+            uncompressed
+        );
+
+        let compressed = PublicKey::from_hex(
+            "02d0de0aaeaefad02b8bdc8a01a1b8b11c696bd3d66a2c5f10780d95b7df42645c",
+        )
+        .unwrap();
+        assert!(compressed.compressed);
+        assert_ne!(compressed, uncompressed);
+        assert_eq!(compressed.inner, uncompressed.inner);
+    }
+
+    #[test]
+    fn test_invalid_public_key() {
+        // Wrong length.
+        assert_eq!(
+            PublicKey::from_hex("02d0de0a"),
+            Err(KeyError::InvalidPublicKeyLength(4))
+        );
+        // Not a point on the curve.
+        assert_eq!(
+            PublicKey::from_slice(&[&[0x02][..], &[0xff; 32]].concat()),
+            Err(KeyError::InvalidPublicKey)
+        );
+    }
+}
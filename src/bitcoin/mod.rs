@@ -1,5 +1,20 @@
 pub mod bitcoin_transaction;
 pub mod bitcoin_transaction_builder;
+pub mod change;
+pub mod coin_select;
 pub mod constants;
+pub mod cpfp;
 pub mod encoding;
+pub mod hash_types;
+#[cfg(feature = "keys")]
+pub mod keys;
+pub mod prelude;
+pub mod psbt;
+pub mod rbf;
+pub mod rpc;
+#[cfg(feature = "verify")]
+pub mod schnorr;
+pub mod signing_session;
 pub mod types;
+#[cfg(feature = "verify")]
+pub mod verify;
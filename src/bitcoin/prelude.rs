@@ -0,0 +1,17 @@
+//! Everything needed to build a Bitcoin transaction in a single import.
+//!
+//! ```ignore
+//! use omni_transaction::bitcoin::prelude::*;
+//! ```
+pub use super::bitcoin_transaction::BitcoinTransaction;
+pub use super::bitcoin_transaction_builder::BitcoinTransactionBuilder;
+pub use super::hash_types::{BlockHash, PubkeyHash, ScriptHash, WPubkeyHash, WScriptHash, Wtxid};
+pub use super::psbt::{Psbt, PsbtError};
+pub use super::signing_session::BitcoinSigningSession;
+pub use super::types::{
+    Amount, Coin, ControlBlock, EcdsaSighashType, Hash, LockTime, LockTimeError, Network, OutPoint,
+    ParseAmountError, ParseOutPointError, ScriptBuf, ScriptBuilder, Sequence, TapBranchHash,
+    TapLeafHash, TapSighashType, TransactionType, TxIn, TxOut, Txid, Version, Witness,
+};
+pub use crate::transaction_builder::{BuildError, TransactionBuilder, TxBuilder};
+pub use crate::types::BITCOIN;
@@ -0,0 +1,501 @@
+//! Partially Signed Bitcoin Transactions, as specified by
+//! [BIP-174](https://github.com/bitcoin/bips/blob/master/bip-0174.mediawiki) (version 0).
+//!
+//! A [`Psbt`] wraps an unsigned [`BitcoinTransaction`] along with the data each signer needs,
+//! so a transaction can be handed off between a contract and external signers.
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io::Write;
+
+use super::{
+    bitcoin_transaction::BitcoinTransaction,
+    encoding::{utils::VarInt, Decodable, Encodable, ToU64},
+    types::{LockTime, ScriptBuf, TxIn, TxOut, Version, Witness},
+};
+
+/// Magic bytes starting every PSBT: `psbt` followed by `0xff`.
+pub const PSBT_MAGIC: [u8; 5] = *b"psbt\xff";
+
+const PSBT_GLOBAL_UNSIGNED_TX: u8 = 0x00;
+
+const PSBT_IN_WITNESS_UTXO: u8 = 0x01;
+const PSBT_IN_PARTIAL_SIG: u8 = 0x02;
+const PSBT_IN_SIGHASH_TYPE: u8 = 0x03;
+const PSBT_IN_FINAL_SCRIPTSIG: u8 = 0x07;
+const PSBT_IN_FINAL_SCRIPTWITNESS: u8 = 0x08;
+
+/// Raw key-value pairs, keyed by the full key (type byte followed by the key data).
+pub type PsbtMap = BTreeMap<Vec<u8>, Vec<u8>>;
+
+/// Errors returned when building, parsing or extracting a [`Psbt`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PsbtError {
+    /// The input is not valid hex.
+    InvalidHex(String),
+    /// The PSBT does not start with [`PSBT_MAGIC`].
+    InvalidMagic,
+    /// The global map has no unsigned transaction.
+    MissingUnsignedTx,
+    /// A field is not a valid encoding of its value.
+    InvalidEncoding { field: &'static str, reason: String },
+    /// A key appears twice in the same map.
+    DuplicateKey,
+    /// A sighash type value is not 4 bytes long.
+    InvalidSighashType,
+    /// A partial signature public key is neither 33 nor 65 bytes long.
+    InvalidPartialSigPublicKey(usize),
+    /// Bytes are left after the last output map.
+    TrailingBytes(usize),
+    /// An input of the unsigned transaction has a script sig or a witness.
+    SignedInput(usize),
+    /// An input has neither a final script sig nor a final witness.
+    InputNotFinalized(usize),
+}
+
+impl fmt::Display for PsbtError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidHex(reason) => write!(f, "invalid hex: {}", reason),
+            Self::InvalidMagic => write!(f, "invalid PSBT magic bytes"),
+            Self::MissingUnsignedTx => write!(f, "missing PSBT unsigned transaction"),
+            Self::InvalidEncoding { field, reason } => {
+                write!(f, "invalid PSBT {}: {}", field, reason)
+            }
+            Self::DuplicateKey => write!(f, "duplicate PSBT key"),
+            Self::InvalidSighashType => write!(f, "invalid PSBT sighash type"),
+            Self::InvalidPartialSigPublicKey(len) => write!(
+                f,
+                "invalid PSBT partial signature public key length: {}",
+                len
+            ),
+            Self::TrailingBytes(len) => write!(f, "{} trailing bytes after the PSBT", len),
+            Self::SignedInput(index) => {
+                write!(f, "input {} of the unsigned transaction is signed", index)
+            }
+            Self::InputNotFinalized(index) => write!(f, "input {} is not finalized", index),
+        }
+    }
+}
+
+impl std::error::Error for PsbtError {}
+
+/// A partially signed transaction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Psbt {
+    /// The transaction being signed, without any script sig or witness.
+    pub unsigned_tx: BitcoinTransaction,
+    /// Global key-value pairs this implementation doesn't interpret, kept for round trips.
+    pub unknown: PsbtMap,
+    /// Data of each input, in the order of the transaction inputs.
+    pub inputs: Vec<PsbtInput>,
+    /// Data of each output, in the order of the transaction outputs.
+    pub outputs: Vec<PsbtOutput>,
+}
+
+/// The data of a PSBT input.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct PsbtInput {
+    /// The output spent by a segwit input.
+    pub witness_utxo: Option<TxOut>,
+    /// Signatures, including their sighash type byte, keyed by SEC1 public key.
+    pub partial_sigs: BTreeMap<Vec<u8>, Vec<u8>>,
+    /// The sighash type signers must use.
+    pub sighash_type: Option<u32>,
+    /// The final script sig, set once the input is finalized.
+    pub final_script_sig: Option<ScriptBuf>,
+    /// The final witness, set once the input is finalized.
+    pub final_script_witness: Option<Witness>,
+    /// Key-value pairs this implementation doesn't interpret, kept for round trips.
+    pub unknown: PsbtMap,
+}
+
+/// The data of a PSBT output.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct PsbtOutput {
+    /// Key-value pairs this implementation doesn't interpret, kept for round trips.
+    pub unknown: PsbtMap,
+}
+
+impl Psbt {
+    /// Wraps an unsigned transaction, with empty input and output data.
+    ///
+    /// Fails if any input has a script sig or a witness; use
+    /// [`BitcoinTransaction::unsigned_clone`] to clear them.
+    pub fn from_unsigned_tx(unsigned_tx: BitcoinTransaction) -> Result<Self, PsbtError> {
+        check_unsigned(&unsigned_tx)?;
+
+        Ok(Self {
+            inputs: vec![PsbtInput::default(); unsigned_tx.input.len()],
+            outputs: vec![PsbtOutput::default(); unsigned_tx.output.len()],
+            unsigned_tx,
+            unknown: PsbtMap::new(),
+        })
+    }
+
+    /// Returns the BIP-174 binary encoding of the PSBT.
+    pub fn serialize(&self) -> Vec<u8> {
+        let mut buffer = PSBT_MAGIC.to_vec();
+
+        // The unsigned transaction is always in the legacy serialization, even with no inputs,
+        // where `BitcoinTransaction::serialize` would write the segwit marker and flag.
+        let mut unsigned_tx = Vec::new();
+        let _ = self.unsigned_tx.encode_without_witnesses(&mut unsigned_tx);
+        write_pair(&mut buffer, &[PSBT_GLOBAL_UNSIGNED_TX], &unsigned_tx);
+        write_map(&mut buffer, &self.unknown);
+
+        for input in &self.inputs {
+            if let Some(witness_utxo) = &input.witness_utxo {
+                write_pair(&mut buffer, &[PSBT_IN_WITNESS_UTXO], &encode(witness_utxo));
+            }
+            for (public_key, signature) in &input.partial_sigs {
+                let key = [&[PSBT_IN_PARTIAL_SIG][..], public_key].concat();
+                write_pair(&mut buffer, &key, signature);
+            }
+            if let Some(sighash_type) = input.sighash_type {
+                write_pair(
+                    &mut buffer,
+                    &[PSBT_IN_SIGHASH_TYPE],
+                    &sighash_type.to_le_bytes(),
+                );
+            }
+            if let Some(script_sig) = &input.final_script_sig {
+                write_pair(&mut buffer, &[PSBT_IN_FINAL_SCRIPTSIG], &script_sig.0);
+            }
+            if let Some(witness) = &input.final_script_witness {
+                write_pair(
+                    &mut buffer,
+                    &[PSBT_IN_FINAL_SCRIPTWITNESS],
+                    &encode(witness),
+                );
+            }
+            write_map(&mut buffer, &input.unknown);
+        }
+
+        for output in &self.outputs {
+            write_map(&mut buffer, &output.unknown);
+        }
+
+        buffer
+    }
+
+    /// Parses the BIP-174 binary encoding of a PSBT.
+    pub fn deserialize(bytes: &[u8]) -> Result<Self, PsbtError> {
+        let mut reader = bytes
+            .strip_prefix(&PSBT_MAGIC)
+            .ok_or(PsbtError::InvalidMagic)?;
+
+        let mut unknown = read_map(&mut reader)?;
+        let unsigned_tx = unknown
+            .remove(&[PSBT_GLOBAL_UNSIGNED_TX][..])
+            .ok_or(PsbtError::MissingUnsignedTx)?;
+        let unsigned_tx = decode_unsigned_tx(&unsigned_tx)?;
+        check_unsigned(&unsigned_tx)?;
+
+        let inputs = (0..unsigned_tx.input.len())
+            .map(|_| PsbtInput::from_map(read_map(&mut reader)?))
+            .collect::<Result<Vec<_>, _>>()?;
+        let outputs = (0..unsigned_tx.output.len())
+            .map(|_| {
+                Ok(PsbtOutput {
+                    unknown: read_map(&mut reader)?,
+                })
+            })
+            .collect::<Result<Vec<_>, PsbtError>>()?;
+
+        if !reader.is_empty() {
+            return Err(PsbtError::TrailingBytes(reader.len()));
+        }
+
+        Ok(Self {
+            unsigned_tx,
+            unknown,
+            inputs,
+            outputs,
+        })
+    }
+
+    /// Parses a hex encoded PSBT.
+    pub fn from_hex(s: &str) -> Result<Self, PsbtError> {
+        Self::deserialize(&hex::decode(s).map_err(|e| PsbtError::InvalidHex(e.to_string()))?)
+    }
+
+    /// Returns the signed transaction, once every input has a final script sig or witness.
+    pub fn extract_tx(&self) -> Result<BitcoinTransaction, PsbtError> {
+        let mut tx = self.unsigned_tx.clone();
+
+        for (i, (txin, input)) in tx.input.iter_mut().zip(&self.inputs).enumerate() {
+            if input.final_script_sig.is_none() && input.final_script_witness.is_none() {
+                return Err(PsbtError::InputNotFinalized(i));
+            }
+            txin.script_sig = input.final_script_sig.clone().unwrap_or_default();
+            txin.witness = input.final_script_witness.clone().unwrap_or_default();
+        }
+
+        Ok(tx)
+    }
+}
+
+impl PsbtInput {
+    fn from_map(mut map: PsbtMap) -> Result<Self, PsbtError> {
+        let mut input = Self::default();
+
+        if let Some(value) = map.remove(&[PSBT_IN_WITNESS_UTXO][..]) {
+            input.witness_utxo = Some(decode_value(&value, "witness UTXO")?);
+        }
+        if let Some(value) = map.remove(&[PSBT_IN_SIGHASH_TYPE][..]) {
+            let value: [u8; 4] = value
+                .try_into()
+                .map_err(|_| PsbtError::InvalidSighashType)?;
+            input.sighash_type = Some(u32::from_le_bytes(value));
+        }
+        if let Some(value) = map.remove(&[PSBT_IN_FINAL_SCRIPTSIG][..]) {
+            input.final_script_sig = Some(ScriptBuf(value));
+        }
+        if let Some(value) = map.remove(&[PSBT_IN_FINAL_SCRIPTWITNESS][..]) {
+            input.final_script_witness = Some(decode_value(&value, "final script witness")?);
+        }
+
+        let partial_sig_keys: Vec<Vec<u8>> = map
+            .keys()
+            .filter(|key| key.first() == Some(&PSBT_IN_PARTIAL_SIG))
+            .cloned()
+            .collect();
+        for key in partial_sig_keys {
+            let public_key = key[1..].to_vec();
+            if !matches!(public_key.len(), 33 | 65) {
+                return Err(PsbtError::InvalidPartialSigPublicKey(public_key.len()));
+            }
+            let signature = map.remove(&key).expect("key was just listed");
+            input.partial_sigs.insert(public_key, signature);
+        }
+
+        input.unknown = map;
+        Ok(input)
+    }
+}
+
+/// Checks that no input of `tx` carries a script sig or a witness.
+fn check_unsigned(tx: &BitcoinTransaction) -> Result<(), PsbtError> {
+    match tx
+        .input
+        .iter()
+        .position(|txin| !txin.script_sig.0.is_empty() || !txin.witness.is_empty())
+    {
+        Some(i) => Err(PsbtError::SignedInput(i)),
+        None => Ok(()),
+    }
+}
+
+/// Decodes a transaction in the legacy serialization, the only one allowed in a PSBT.
+///
+/// Unlike [`BitcoinTransaction::from_bytes`], this never tries the segwit serialization, which
+/// would misread a transaction with no inputs as a segwit marker and flag.
+fn decode_unsigned_tx(bytes: &[u8]) -> Result<BitcoinTransaction, PsbtError> {
+    let mut reader = bytes;
+    let decode = |reader: &mut &[u8]| -> Result<BitcoinTransaction, std::io::Error> {
+        Ok(BitcoinTransaction {
+            version: Version::decode(reader)?,
+            input: Vec::<TxIn>::decode(reader)?,
+            output: Vec::<TxOut>::decode(reader)?,
+            lock_time: LockTime::decode(reader)?,
+        })
+    };
+
+    let invalid = |reason: String| PsbtError::InvalidEncoding {
+        field: "unsigned transaction",
+        reason,
+    };
+    let tx = decode(&mut reader).map_err(|e| invalid(e.to_string()))?;
+    if !reader.is_empty() {
+        return Err(invalid("trailing data".to_string()));
+    }
+
+    Ok(tx)
+}
+
+/// Decodes a value which must be entirely consumed.
+fn decode_value<T: Decodable>(value: &[u8], field: &'static str) -> Result<T, PsbtError> {
+    let invalid = |reason: String| PsbtError::InvalidEncoding { field, reason };
+    let mut reader = value;
+    let decoded = T::decode(&mut reader).map_err(|e| invalid(e.to_string()))?;
+    if !reader.is_empty() {
+        return Err(invalid("trailing data".to_string()));
+    }
+
+    Ok(decoded)
+}
+
+/// Reads key-value pairs up to the `0x00` separator, rejecting duplicate keys.
+fn read_map(reader: &mut &[u8]) -> Result<PsbtMap, PsbtError> {
+    let mut map = PsbtMap::new();
+    let read = |reader: &mut &[u8], field: &'static str| {
+        Vec::<u8>::decode(reader).map_err(|e| PsbtError::InvalidEncoding {
+            field,
+            reason: e.to_string(),
+        })
+    };
+
+    loop {
+        let key = read(reader, "key")?;
+        if key.is_empty() {
+            return Ok(map);
+        }
+        let value = read(reader, "value")?;
+
+        if map.insert(key, value).is_some() {
+            return Err(PsbtError::DuplicateKey);
+        }
+    }
+}
+
+/// Writes the pairs of `map` followed by the `0x00` separator.
+fn write_map(buffer: &mut Vec<u8>, map: &PsbtMap) {
+    for (key, value) in map {
+        write_pair(buffer, key, value);
+    }
+    buffer.push(0x00);
+}
+
+fn write_pair(buffer: &mut Vec<u8>, key: &[u8], value: &[u8]) {
+    write_bytes(buffer, key);
+    write_bytes(buffer, value);
+}
+
+fn write_bytes(buffer: &mut Vec<u8>, bytes: &[u8]) {
+    let _ = VarInt(bytes.len().to_u64()).encode(buffer);
+    let _ = buffer.write_all(bytes);
+}
+
+fn encode<T: Encodable>(value: &T) -> Vec<u8> {
+    let mut buffer = Vec::new();
+    let _ = value.encode(&mut buffer);
+    buffer
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bitcoin::types::{Amount, Hash, OutPoint, Sequence, Txid};
+    use bitcoin::psbt::Psbt as RustBitcoinPsbt;
+
+    fn unsigned_tx() -> BitcoinTransaction {
+        BitcoinTransaction {
+            version: Version::TWO,
+            lock_time: LockTime::from_height(800_000).unwrap(),
+            input: vec![TxIn {
+                previous_output: OutPoint::new(Txid(Hash::from_byte_array([7; 32])), 1),
+                script_sig: ScriptBuf::default(),
+                sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+                witness: Witness::default(),
+            }],
+            output: vec![TxOut {
+                value: Amount::from_sat(90_000),
+                script_pubkey: ScriptBuf::from_hex("0014751e76e8199196d454941c45d1b3a323f1433bd6")
+                    .unwrap(),
+            }],
+        }
+    }
+
+    fn psbt() -> Psbt {
+        let mut psbt = Psbt::from_unsigned_tx(unsigned_tx()).unwrap();
+        let input = &mut psbt.inputs[0];
+        input.witness_utxo = Some(TxOut {
+            value: Amount::from_sat(100_000),
+            script_pubkey: ScriptBuf::from_hex("0014751e76e8199196d454941c45d1b3a323f1433bd6")
+                .unwrap(),
+        });
+        input.sighash_type = Some(1);
+        input.partial_sigs.insert(
+            hex::decode("0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798")
+                .unwrap(),
+            hex::decode("3044022044dc17b0887c161bb67ba9635bf758735bdde503e4b0a0987f587f14a4e1143d022009a215772d49a85dae40d8ca03955af26ad3978a0ff965faa12915e9586249a501").unwrap(),
+        );
+        input.unknown.insert(vec![0xf0, 0x01], vec![0xab]);
+        psbt.outputs[0].unknown.insert(vec![0xf0, 0x02], vec![]);
+        psbt
+    }
+
+    #[test]
+    fn test_roundtrip_against_rust_bitcoin() {
+        let psbt = psbt();
+        let serialized = psbt.serialize();
+
+        let rust_bitcoin_psbt = RustBitcoinPsbt::deserialize(&serialized).unwrap();
+        assert_eq!(rust_bitcoin_psbt.serialize(), serialized);
+        assert_eq!(rust_bitcoin_psbt.inputs[0].partial_sigs.len(), 1);
+        assert_eq!(
+            rust_bitcoin_psbt.inputs[0]
+                .witness_utxo
+                .as_ref()
+                .unwrap()
+                .value,
+            bitcoin::Amount::from_sat(100_000)
+        );
+
+        assert_eq!(Psbt::deserialize(&serialized).unwrap(), psbt);
+    }
+
+    #[test]
+    fn test_zero_input_tx_uses_legacy_serialization() {
+        let mut tx = unsigned_tx();
+        tx.input.clear();
+        let serialized = Psbt::from_unsigned_tx(tx.clone()).unwrap().serialize();
+
+        let mut legacy = Vec::new();
+        tx.encode_without_witnesses(&mut legacy).unwrap();
+        let mut expected = PSBT_MAGIC.to_vec();
+        write_pair(&mut expected, &[PSBT_GLOBAL_UNSIGNED_TX], &legacy);
+        assert!(serialized.starts_with(&expected));
+
+        let rust_bitcoin_psbt = RustBitcoinPsbt::deserialize(&serialized).unwrap();
+        assert!(rust_bitcoin_psbt.unsigned_tx.input.is_empty());
+        assert_eq!(rust_bitcoin_psbt.serialize(), serialized);
+
+        let psbt = Psbt::deserialize(&serialized).unwrap();
+        assert_eq!(psbt.unsigned_tx, tx);
+        assert!(psbt.inputs.is_empty());
+    }
+
+    #[test]
+    fn test_extract_tx() {
+        let mut psbt = psbt();
+        assert_eq!(psbt.extract_tx(), Err(PsbtError::InputNotFinalized(0)));
+
+        let witness = Witness::from_slice(&[vec![1u8; 71], vec![2u8; 33]]);
+        psbt.inputs[0].final_script_witness = Some(witness.clone());
+        let psbt = Psbt::deserialize(&psbt.serialize()).unwrap();
+
+        let tx = psbt.extract_tx().unwrap();
+        assert_eq!(tx.input[0].witness, witness);
+        assert_eq!(tx.unsigned_clone(), psbt.unsigned_tx);
+    }
+
+    #[test]
+    fn test_invalid_psbt() {
+        let serialized = psbt().serialize();
+
+        assert_eq!(
+            Psbt::deserialize(&serialized[1..]),
+            Err(PsbtError::InvalidMagic)
+        );
+        assert!(matches!(
+            Psbt::deserialize(&serialized[..serialized.len() - 1]),
+            Err(PsbtError::InvalidEncoding { field: "key", .. })
+        ));
+        assert_eq!(
+            Psbt::deserialize(&[&serialized[..], &[0x00]].concat()),
+            Err(PsbtError::TrailingBytes(1))
+        );
+        assert!(matches!(
+            Psbt::from_hex("zz"),
+            Err(PsbtError::InvalidHex(_))
+        ));
+
+        let mut signed = unsigned_tx();
+        signed.input[0].script_sig = ScriptBuf(vec![0x51]);
+        assert_eq!(
+            Psbt::from_unsigned_tx(signed),
+            Err(PsbtError::SignedInput(0))
+        );
+    }
+}
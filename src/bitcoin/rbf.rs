@@ -0,0 +1,259 @@
+//! Replace-by-fee (RBF) fee bumping.
+//!
+//! A transaction stuck in the mempool can be replaced by a conflicting one paying a higher
+//! fee (BIP-125). The replacement spends the same inputs to the same outputs, and the extra fee
+//! is taken from the change output.
+use core::fmt;
+
+use super::{
+    bitcoin_transaction::BitcoinTransaction,
+    change::dust_threshold,
+    types::{Amount, Sequence, TransactionType},
+};
+
+/// The fee rate, in sat/vB, by which a replacement must pay for its own size on top of the fee
+/// of the replaced transaction, at the default relay policy of Bitcoin Core.
+pub const INCREMENTAL_RELAY_FEE_RATE: u64 = 1;
+
+/// Errors returned by [`BitcoinTransaction::bump_fee`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FeeBumpError {
+    /// The spent outputs are worth less than the outputs of the transaction, in satoshis.
+    InputsBelowOutputs { inputs: u64, outputs: u64 },
+    /// The transaction has no output at the change index.
+    ChangeIndexOutOfRange(u32),
+    /// The change output, in satoshis, cannot pay the fee increase required by the new fee
+    /// rate (at least the BIP-125 minimum) and stay above its dust threshold.
+    ChangeBelowDust { change: u64, fee_delta: u64 },
+    /// A value or fee overflows.
+    Overflow,
+}
+
+impl fmt::Display for FeeBumpError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InputsBelowOutputs { inputs, outputs } => write!(
+                f,
+                "inputs ({} sat) are worth less than outputs ({} sat)",
+                inputs, outputs
+            ),
+            Self::ChangeIndexOutOfRange(vout) => write!(f, "transaction has no output {}", vout),
+            Self::ChangeBelowDust { change, fee_delta } => write!(
+                f,
+                "change ({} sat) cannot pay a {} sat fee increase and stay above dust",
+                change, fee_delta
+            ),
+            Self::Overflow => write!(f, "value overflows"),
+        }
+    }
+}
+
+impl std::error::Error for FeeBumpError {}
+
+/// A replacement transaction paying a higher fee.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FeeBump {
+    /// The replacement, whose inputs have to be signed again.
+    pub transaction: BitcoinTransaction,
+    /// The fee paid by the replaced transaction.
+    pub old_fee: Amount,
+    /// The fee paid by the replacement.
+    pub new_fee: Amount,
+    /// How much the change output was reduced by, i.e. `new_fee - old_fee`.
+    pub fee_delta: Amount,
+}
+
+impl BitcoinTransaction {
+    /// Returns a replacement of the transaction paying `new_fee_rate` sat/vB, taking the extra
+    /// fee from output `change_vout`. `inputs_value` is the total value of the spent outputs.
+    ///
+    /// The fee rate is computed from the size of `self`, which should be signed: the
+    /// replacement is returned unsigned, and its signatures are expected to have the same size.
+    /// The replacement also pays at least the fee of `self` plus [`INCREMENTAL_RELAY_FEE_RATE`]
+    /// for its size, as required by BIP-125, even if `new_fee_rate` is lower.
+    ///
+    /// Inputs which don't signal replace-by-fee get the [`Sequence::ENABLE_RBF_NO_LOCKTIME`]
+    /// sequence, so the replacement can itself be replaced. Fails if the change output would
+    /// fall below its dust threshold, see [`FeeBumpError`].
+    pub fn bump_fee(
+        &self,
+        new_fee_rate: u64,
+        inputs_value: Amount,
+        change_vout: u32,
+    ) -> Result<FeeBump, FeeBumpError> {
+        let outputs_value = self
+            .output
+            .iter()
+            .try_fold(Amount::ZERO, |total, output| {
+                total.checked_add(output.value)
+            })
+            .ok_or(FeeBumpError::Overflow)?;
+        let old_fee =
+            inputs_value
+                .checked_sub(outputs_value)
+                .ok_or(FeeBumpError::InputsBelowOutputs {
+                    inputs: inputs_value.to_sat(),
+                    outputs: outputs_value.to_sat(),
+                })?;
+
+        let vsize = self.vsize();
+        let min_fee = vsize
+            .checked_mul(INCREMENTAL_RELAY_FEE_RATE)
+            .and_then(|fee| fee.checked_add(old_fee.to_sat()))
+            .ok_or(FeeBumpError::Overflow)?;
+        let new_fee = vsize
+            .checked_mul(new_fee_rate)
+            .ok_or(FeeBumpError::Overflow)?
+            .max(min_fee);
+        let fee_delta = Amount::from_sat(new_fee - old_fee.to_sat());
+
+        let mut transaction = self.unsigned_clone();
+        let change = transaction
+            .output
+            .get_mut(change_vout as usize)
+            .ok_or(FeeBumpError::ChangeIndexOutOfRange(change_vout))?;
+        // The highest threshold for scripts of unknown type.
+        let threshold = TransactionType::classify(&change.script_pubkey)
+            .map_or(dust_threshold(TransactionType::P2PKH), dust_threshold);
+        change.value = change
+            .value
+            .checked_sub(fee_delta)
+            .filter(|value| *value >= threshold)
+            .ok_or(FeeBumpError::ChangeBelowDust {
+                change: change.value.to_sat(),
+                fee_delta: fee_delta.to_sat(),
+            })?;
+
+        for input in &mut transaction.input {
+            if !input.sequence.is_rbf() {
+                input.sequence = Sequence::ENABLE_RBF_NO_LOCKTIME;
+            }
+        }
+
+        Ok(FeeBump {
+            transaction,
+            old_fee,
+            new_fee: Amount::from_sat(new_fee),
+            fee_delta,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bitcoin::hash_types::WPubkeyHash;
+    use crate::bitcoin::types::{
+        Hash, LockTime, OutPoint, ScriptBuf, TxIn, TxOut, Txid, Version, Witness,
+    };
+
+    fn signed_transaction(sequence: Sequence) -> BitcoinTransaction {
+        BitcoinTransaction {
+            version: Version::TWO,
+            lock_time: LockTime::from_height(0).unwrap(),
+            input: vec![
+                TxIn {
+                    previous_output: OutPoint::new(Txid(Hash::all_zeros()), 0),
+                    script_sig: ScriptBuf::default(),
+                    sequence,
+                    witness: Witness::from_slice(&[vec![1u8; 72], vec![2u8; 33]]),
+                },
+                TxIn {
+                    previous_output: OutPoint::new(Txid(Hash::all_zeros()), 1),
+                    script_sig: ScriptBuf::default(),
+                    sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+                    witness: Witness::from_slice(&[vec![1u8; 72], vec![2u8; 33]]),
+                },
+            ],
+            output: vec![
+                TxOut {
+                    value: Amount::from_sat(50_000),
+                    script_pubkey: ScriptBuf::new_p2wpkh(&WPubkeyHash::from_byte_array([3; 20])),
+                },
+                TxOut {
+                    value: Amount::from_sat(20_000),
+                    script_pubkey: ScriptBuf::new_p2wpkh(&WPubkeyHash::from_byte_array([4; 20])),
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_bump_fee_reduces_change() {
+        let tx = signed_transaction(Sequence::MAX);
+        let vsize = tx.vsize();
+
+        let bump = tx.bump_fee(10, Amount::from_sat(71_000), 1).unwrap();
+
+        assert_eq!(bump.old_fee, Amount::from_sat(1_000));
+        assert_eq!(bump.new_fee, Amount::from_sat(vsize * 10));
+        assert_eq!(bump.fee_delta, bump.new_fee - bump.old_fee);
+        assert_eq!(
+            bump.transaction.output[1].value,
+            Amount::from_sat(20_000) - bump.fee_delta
+        );
+        assert_eq!(bump.transaction.output[0], tx.output[0]);
+
+        // Same inputs, unsigned, all signaling replace-by-fee.
+        let unsigned = tx.unsigned_clone();
+        assert_eq!(bump.transaction.input.len(), 2);
+        for (input, original) in bump.transaction.input.iter().zip(&unsigned.input) {
+            assert_eq!(input.previous_output, original.previous_output);
+            assert!(input.witness.is_empty());
+            assert_eq!(input.sequence, Sequence::ENABLE_RBF_NO_LOCKTIME);
+        }
+    }
+
+    #[test]
+    fn test_bump_fee_keeps_rbf_sequences() {
+        let sequence = Sequence::from_height(10);
+        let tx = signed_transaction(sequence);
+
+        let bump = tx.bump_fee(10, Amount::from_sat(71_000), 1).unwrap();
+
+        assert_eq!(bump.transaction.input[0].sequence, sequence);
+    }
+
+    #[test]
+    fn test_bump_fee_pays_incremental_relay_fee() {
+        let tx = signed_transaction(Sequence::ENABLE_RBF_NO_LOCKTIME);
+        let vsize = tx.vsize();
+
+        // The original already pays more than 1 sat/vB.
+        let bump = tx.bump_fee(1, Amount::from_sat(75_000), 1).unwrap();
+
+        assert_eq!(bump.old_fee, Amount::from_sat(5_000));
+        assert_eq!(
+            bump.fee_delta,
+            Amount::from_sat(vsize * INCREMENTAL_RELAY_FEE_RATE)
+        );
+    }
+
+    #[test]
+    fn test_bump_fee_errors() {
+        let tx = signed_transaction(Sequence::MAX);
+
+        // The change would fall below the P2WPKH dust threshold.
+        let err = tx.bump_fee(100, Amount::from_sat(71_000), 1).unwrap_err();
+        assert!(
+            matches!(err, FeeBumpError::ChangeBelowDust { change: 20_000, .. }),
+            "{}",
+            err
+        );
+        assert_eq!(
+            tx.bump_fee(10, Amount::from_sat(71_000), 2),
+            Err(FeeBumpError::ChangeIndexOutOfRange(2))
+        );
+        assert_eq!(
+            tx.bump_fee(10, Amount::from_sat(60_000), 1),
+            Err(FeeBumpError::InputsBelowOutputs {
+                inputs: 60_000,
+                outputs: 70_000
+            })
+        );
+        assert_eq!(
+            tx.bump_fee(u64::MAX, Amount::from_sat(71_000), 1),
+            Err(FeeBumpError::Overflow)
+        );
+    }
+}
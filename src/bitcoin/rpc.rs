@@ -0,0 +1,321 @@
+//! Conversions between [`BitcoinTransaction`] and the JSON returned by bitcoind's
+//! `decoderawtransaction` and verbose `getrawtransaction`.
+//!
+//! Inputs are read from `vin` (`txid`, `vout`, `scriptSig.hex`, `txinwitness`, `sequence`, or
+//! `coinbase` for coinbase inputs) and outputs from `vout` (`value` in BTC, `scriptPubKey.hex`).
+//! Other fields, such as `asm` or `address`, are ignored when parsing and not emitted.
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use super::{
+    bitcoin_transaction::{BitcoinDecodeError, BitcoinTransaction},
+    types::{
+        Amount, Hash, LockTime, OutPoint, ScriptBuf, Sequence, TxIn, TxOut, Txid, Version, Witness,
+    },
+};
+
+#[derive(Deserialize)]
+struct RpcTransaction {
+    version: u32,
+    locktime: u32,
+    vin: Vec<RpcTxIn>,
+    vout: Vec<RpcTxOut>,
+}
+
+#[derive(Deserialize)]
+struct RpcTxIn {
+    #[serde(default)]
+    txid: Option<String>,
+    #[serde(default)]
+    vout: Option<u32>,
+    #[serde(default)]
+    coinbase: Option<String>,
+    #[serde(default, rename = "scriptSig")]
+    script_sig: Option<RpcScript>,
+    #[serde(default)]
+    txinwitness: Vec<String>,
+    sequence: u32,
+}
+
+#[derive(Deserialize)]
+struct RpcTxOut {
+    value: Value,
+    #[serde(rename = "scriptPubKey")]
+    script_pubkey: RpcScript,
+}
+
+#[derive(Deserialize)]
+struct RpcScript {
+    hex: String,
+}
+
+impl BitcoinTransaction {
+    /// Parses the output of `decoderawtransaction`, e.g. a test fixture or an RPC response.
+    pub fn from_rpc_json(json: &str) -> Result<Self, BitcoinDecodeError> {
+        let rpc: RpcTransaction = serde_json::from_str(json)
+            .map_err(|e| BitcoinDecodeError::InvalidJson(e.to_string()))?;
+
+        let input = rpc
+            .vin
+            .into_iter()
+            .map(RpcTxIn::try_into_tx_in)
+            .collect::<Result<_, _>>()?;
+        let output = rpc
+            .vout
+            .into_iter()
+            .map(RpcTxOut::try_into_tx_out)
+            .collect::<Result<_, _>>()?;
+
+        // Every value is either a valid height or a valid timestamp.
+        let lock_time = LockTime::from_height(rpc.locktime)
+            .or_else(|_| LockTime::from_time(rpc.locktime))
+            .expect("lock time is a height or a timestamp");
+
+        Ok(Self {
+            version: Version(rpc.version),
+            lock_time,
+            input,
+            output,
+        })
+    }
+
+    /// Returns the transaction in the format of `decoderawtransaction`, without the script
+    /// `asm`, `desc`, `address` and `type` fields.
+    pub fn to_rpc_json(&self) -> Value {
+        let vin: Vec<Value> = self.input.iter().map(tx_in_to_rpc_json).collect();
+        let vout: Vec<Value> = self
+            .output
+            .iter()
+            .enumerate()
+            .map(|(n, tx_out)| {
+                json!({
+                    "value": tx_out.value.to_sat() as f64 / 100_000_000.0,
+                    "n": n,
+                    "scriptPubKey": { "hex": hex::encode(&tx_out.script_pubkey.0) },
+                })
+            })
+            .collect();
+
+        json!({
+            "txid": self.compute_txid().to_string(),
+            "hash": self.compute_wtxid().to_string(),
+            "version": self.version.0,
+            "size": self.total_size(),
+            "vsize": self.vsize(),
+            "weight": self.weight(),
+            "locktime": self.lock_time.to_u32(),
+            "vin": vin,
+            "vout": vout,
+        })
+    }
+}
+
+fn tx_in_to_rpc_json(tx_in: &TxIn) -> Value {
+    let mut rpc = if tx_in.previous_output.is_null() {
+        json!({ "coinbase": hex::encode(&tx_in.script_sig.0) })
+    } else {
+        json!({
+            "txid": tx_in.previous_output.txid.to_string(),
+            "vout": tx_in.previous_output.vout,
+            "scriptSig": { "hex": hex::encode(&tx_in.script_sig.0) },
+        })
+    };
+
+    if !tx_in.witness.is_empty() {
+        rpc["txinwitness"] = tx_in.witness.to_hex_items().into();
+    }
+    rpc["sequence"] = tx_in.sequence.0.into();
+
+    rpc
+}
+
+fn invalid(field: &str, reason: impl std::fmt::Display) -> BitcoinDecodeError {
+    BitcoinDecodeError::InvalidJson(format!("{}: {}", field, reason))
+}
+
+fn decode_hex(field: &str, s: &str) -> Result<Vec<u8>, BitcoinDecodeError> {
+    hex::decode(s).map_err(|e| invalid(field, e))
+}
+
+impl RpcTxIn {
+    fn try_into_tx_in(self) -> Result<TxIn, BitcoinDecodeError> {
+        let (previous_output, script_sig) = match (self.coinbase, self.txid, self.vout) {
+            (Some(coinbase), _, _) => (OutPoint::null(), decode_hex("coinbase", &coinbase)?),
+            (None, Some(txid), Some(vout)) => {
+                let txid: [u8; 32] = decode_hex("txid", &txid)?
+                    .try_into()
+                    .map_err(|_| invalid("txid", "should be 32 bytes long"))?;
+                let script_sig = match self.script_sig {
+                    Some(script_sig) => decode_hex("scriptSig", &script_sig.hex)?,
+                    None => vec![],
+                };
+                (
+                    OutPoint::new(Txid(Hash::from_byte_array(txid)), vout),
+                    script_sig,
+                )
+            }
+            _ => {
+                return Err(invalid(
+                    "vin",
+                    "either coinbase or txid and vout are needed",
+                ))
+            }
+        };
+
+        let witness =
+            Witness::from_hex_items(&self.txinwitness).map_err(|e| invalid("txinwitness", e))?;
+
+        Ok(TxIn {
+            previous_output,
+            script_sig: ScriptBuf(script_sig),
+            sequence: Sequence(self.sequence),
+            witness,
+        })
+    }
+}
+
+impl RpcTxOut {
+    fn try_into_tx_out(self) -> Result<TxOut, BitcoinDecodeError> {
+        Ok(TxOut {
+            value: Amount::from_rpc_value(&self.value).map_err(|e| invalid("value", e))?,
+            script_pubkey: ScriptBuf(decode_hex("scriptPubKey", &self.script_pubkey.hex)?),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bitcoin::consensus::deserialize;
+
+    // A segwit transaction as printed by `decoderawtransaction`, with one P2WPKH input and a
+    // P2WPKH and a P2PKH output.
+    const DECODED: &str = r#"{
+        "txid": "55ebcf269168cd68e2f00a9a719eb4199d2efb9f502800fbc9e390600f7e1165",
+        "hash": "69f346dd94a58260b9296ca1b39b1b0ffa65470980e39202817451dbd882804a",
+        "version": 2,
+        "size": 225,
+        "vsize": 144,
+        "weight": 573,
+        "locktime": 840000,
+        "vin": [
+            {
+                "txid": "2ece6cd71fee90ff613cee8f30a52c3ecc58685acf9b817b9c467b7ff199871c",
+                "vout": 1,
+                "scriptSig": { "asm": "", "hex": "" },
+                "txinwitness": [
+                    "3044022034cd6bb7e9a1ad4bd4e7c2b8fb5b61e4bc4c2eb7ca7e6c9da0c0a7e4b8d6f0a002202d1b8a0f9f8ef4e6a4b7b9d0c9a1c2f5d3e0b7a9c8d6f4e2a1b0c9d8e7f6a5b401",
+                    "02c6047f9441ed7d6d3045406e95c07cd85c778e4b8cef3ca7abac09b95c709ee5"
+                ],
+                "sequence": 4294967293
+            }
+        ],
+        "vout": [
+            {
+                "value": 0.00049000,
+                "n": 0,
+                "scriptPubKey": {
+                    "asm": "0 cb8a3018cf279311b148cb8d13728bd8cbe95bda",
+                    "desc": "addr(bc1qew9rqxx0y7f3rv2gewx3xu5tmr97jk76nlkud4)#xxxxxxxx",
+                    "hex": "0014cb8a3018cf279311b148cb8d13728bd8cbe95bda",
+                    "address": "bc1qew9rqxx0y7f3rv2gewx3xu5tmr97jk76nlkud4",
+                    "type": "witness_v0_keyhash"
+                }
+            },
+            {
+                "value": 21.3,
+                "n": 1,
+                "scriptPubKey": {
+                    "asm": "OP_DUP OP_HASH160 cb8a3018cf279311b148cb8d13728bd8cbe95bda OP_EQUALVERIFY OP_CHECKSIG",
+                    "hex": "76a914cb8a3018cf279311b148cb8d13728bd8cbe95bda88ac",
+                    "type": "pubkeyhash"
+                }
+            }
+        ]
+    }"#;
+
+    #[test]
+    fn test_from_rpc_json() {
+        let tx = BitcoinTransaction::from_rpc_json(DECODED).unwrap();
+
+        assert_eq!(tx.version, Version(2));
+        assert_eq!(tx.lock_time, LockTime::from_height(840_000).unwrap());
+        assert_eq!(tx.input.len(), 1);
+        assert_eq!(tx.input[0].previous_output.vout, 1);
+        assert_eq!(tx.input[0].sequence, Sequence::ENABLE_RBF_NO_LOCKTIME);
+        assert_eq!(tx.input[0].witness.len(), 2);
+        assert_eq!(tx.output[0].value, Amount::from_sat(49_000));
+        assert_eq!(tx.output[1].value, Amount::from_sat(2_130_000_000));
+
+        let expected: bitcoin::Transaction = deserialize(&tx.serialize()).unwrap();
+        assert_eq!(
+            tx.compute_txid().to_string(),
+            expected.compute_txid().to_string()
+        );
+        assert_eq!(
+            tx.input[0].previous_output.txid.to_string(),
+            expected.input[0].previous_output.txid.to_string()
+        );
+        let decoded: Value = serde_json::from_str(DECODED).unwrap();
+        assert_eq!(decoded["txid"], tx.compute_txid().to_string());
+        assert_eq!(decoded["hash"], tx.compute_wtxid().to_string());
+    }
+
+    #[test]
+    fn test_rpc_json_roundtrip() {
+        let tx = BitcoinTransaction::from_rpc_json(DECODED).unwrap();
+        let json = tx.to_rpc_json();
+
+        let decoded: Value = serde_json::from_str(DECODED).unwrap();
+        for field in [
+            "txid", "hash", "version", "size", "vsize", "weight", "locktime",
+        ] {
+            assert_eq!(json[field], decoded[field], "{}", field);
+        }
+        assert_eq!(json["vout"][1]["n"], 1);
+        assert_eq!(
+            BitcoinTransaction::from_rpc_json(&json.to_string()).unwrap(),
+            tx
+        );
+    }
+
+    #[test]
+    fn test_coinbase_roundtrip() {
+        let tx = BitcoinTransaction {
+            version: Version(1),
+            lock_time: LockTime::from_height(0).unwrap(),
+            input: vec![TxIn {
+                previous_output: OutPoint::null(),
+                script_sig: ScriptBuf(vec![0x03, 0x40, 0xd1, 0x0c]),
+                sequence: Sequence::MAX,
+                witness: Witness::new(),
+            }],
+            output: vec![TxOut {
+                value: Amount::from_sat(1),
+                script_pubkey: ScriptBuf::new_p2a(),
+            }],
+        };
+
+        let json = tx.to_rpc_json();
+        assert_eq!(json["vin"][0]["coinbase"], "0340d10c");
+        assert_eq!(json["vout"][0]["value"].to_string(), "1e-8");
+        assert_eq!(
+            BitcoinTransaction::from_rpc_json(&json.to_string()).unwrap(),
+            tx
+        );
+    }
+
+    #[test]
+    fn test_from_rpc_json_errors() {
+        assert!(matches!(
+            BitcoinTransaction::from_rpc_json("{}"),
+            Err(BitcoinDecodeError::InvalidJson(_))
+        ));
+
+        let missing_vout = DECODED.replace(r#""vout": 1,"#, "");
+        assert!(BitcoinTransaction::from_rpc_json(&missing_vout).is_err());
+
+        let too_precise = DECODED.replace("0.00049000", "0.000490001");
+        assert!(BitcoinTransaction::from_rpc_json(&too_precise).is_err());
+    }
+}
@@ -0,0 +1,303 @@
+//! Signing every input of a transaction.
+//!
+//! [`BitcoinTransaction::build_for_signing_segwit`] hashes all the outpoints, sequences and
+//! outputs for each input, so signing `n` inputs takes `O(n²)`. A [`BitcoinSigningSession`]
+//! computes these BIP-143 hashes once, hands out the payload of each input, and collects the
+//! script sigs and witnesses until the transaction is fully signed:
+//!
+//! ```ignore
+//! let mut session = BitcoinSigningSession::new(tx);
+//! for (i, utxo) in utxos.iter().enumerate() {
+//!     let payload = session.hash_for_signing_segwit(
+//!         EcdsaSighashType::All,
+//!         i,
+//!         &utxo.script_code,
+//!         utxo.value,
+//!     );
+//!     // ... request a signature of `payload` from the MPC signer ...
+//!     session.set_witness(i, Witness::p2wpkh(&signature, EcdsaSighashType::All, &public_key))?;
+//! }
+//! let signed_tx = session.finalize()?;
+//! ```
+use super::{
+    bitcoin_transaction::{sha256d, BitcoinTransaction, SegwitHashes},
+    types::{EcdsaSighashType, ScriptBuf, Witness},
+};
+use crate::signature_error::SignatureError;
+
+/// A transaction being signed input by input.
+///
+/// The outpoints, sequences and outputs can't change during the session, only the unlocks of
+/// the inputs are set. The payloads don't depend on the unlocks, so inputs may be signed in any
+/// order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BitcoinSigningSession {
+    transaction: BitcoinTransaction,
+    hashes: SegwitHashes,
+}
+
+impl BitcoinSigningSession {
+    pub fn new(transaction: BitcoinTransaction) -> Self {
+        Self {
+            hashes: SegwitHashes::new(&transaction),
+            transaction,
+        }
+    }
+
+    /// Returns the transaction, with the unlocks set so far.
+    pub const fn transaction(&self) -> &BitcoinTransaction {
+        &self.transaction
+    }
+
+    /// Same as [`BitcoinTransaction::build_for_signing_legacy_input`]. Legacy sighashes
+    /// serialize the whole transaction, so nothing is shared between inputs.
+    pub fn build_for_signing_legacy_input(
+        &self,
+        input_index: usize,
+        script_code: &ScriptBuf,
+        sighash_type: EcdsaSighashType,
+    ) -> Vec<u8> {
+        self.transaction
+            .build_for_signing_legacy_input(input_index, script_code, sighash_type)
+    }
+
+    /// Same as [`BitcoinTransaction::hash_for_signing_legacy_input`].
+    pub fn hash_for_signing_legacy_input(
+        &self,
+        input_index: usize,
+        script_code: &ScriptBuf,
+        sighash_type: EcdsaSighashType,
+    ) -> [u8; 32] {
+        self.transaction
+            .hash_for_signing_legacy_input(input_index, script_code, sighash_type)
+    }
+
+    /// Same as [`BitcoinTransaction::build_for_signing_segwit`], from the hashes computed when
+    /// the session was created.
+    pub fn build_for_signing_segwit(
+        &self,
+        sighash_type: EcdsaSighashType,
+        input_index: usize,
+        script_code: &ScriptBuf,
+        value: u64,
+    ) -> Vec<u8> {
+        self.transaction.build_for_signing_segwit_with_hashes(
+            &self.hashes,
+            sighash_type,
+            input_index,
+            script_code,
+            value,
+        )
+    }
+
+    /// Returns the double SHA-256 of [`Self::build_for_signing_segwit`], the digest signed by
+    /// ECDSA.
+    pub fn hash_for_signing_segwit(
+        &self,
+        sighash_type: EcdsaSighashType,
+        input_index: usize,
+        script_code: &ScriptBuf,
+        value: u64,
+    ) -> [u8; 32] {
+        sha256d(&self.build_for_signing_segwit(sighash_type, input_index, script_code, value))
+            .try_into()
+            .expect("SHA-256 output is 32 bytes")
+    }
+
+    /// Sets the script sig of the input at `input_index`, e.g. built with a
+    /// [`ScriptBuilder`](super::types::ScriptBuilder).
+    pub fn set_script_sig(
+        &mut self,
+        input_index: usize,
+        script_sig: ScriptBuf,
+    ) -> Result<(), SignatureError> {
+        self.transaction
+            .input
+            .get_mut(input_index)
+            .ok_or(SignatureError::InputIndexOutOfRange(input_index))?
+            .script_sig = script_sig;
+        Ok(())
+    }
+
+    /// Sets the witness of the input at `input_index`, e.g. built with [`Witness::p2wpkh`].
+    pub fn set_witness(
+        &mut self,
+        input_index: usize,
+        witness: Witness,
+    ) -> Result<(), SignatureError> {
+        self.transaction
+            .input
+            .get_mut(input_index)
+            .ok_or(SignatureError::InputIndexOutOfRange(input_index))?
+            .witness = witness;
+        Ok(())
+    }
+
+    /// Returns the indices of the inputs which have neither a script sig nor a witness yet.
+    pub fn unsigned_inputs(&self) -> Vec<usize> {
+        self.transaction
+            .input
+            .iter()
+            .enumerate()
+            .filter(|(_, input)| input.script_sig.0.is_empty() && input.witness.is_empty())
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// Returns the signed transaction, or [`SignatureError::UnsignedInput`] with the first
+    /// input which has neither a script sig nor a witness.
+    pub fn finalize(self) -> Result<BitcoinTransaction, SignatureError> {
+        match self.unsigned_inputs().first() {
+            Some(&index) => Err(SignatureError::UnsignedInput(index)),
+            None => Ok(self.transaction),
+        }
+    }
+}
+
+impl From<BitcoinTransaction> for BitcoinSigningSession {
+    fn from(transaction: BitcoinTransaction) -> Self {
+        Self::new(transaction)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bitcoin::hash_types::{PubkeyHash, WPubkeyHash};
+    use crate::bitcoin::types::{
+        Amount, Hash, LockTime, OutPoint, ScriptBuilder, Sequence, TxIn, TxOut, Txid, Version,
+    };
+    use bitcoin::secp256k1::{Message, Secp256k1, SecretKey};
+
+    fn transaction() -> BitcoinTransaction {
+        BitcoinTransaction {
+            version: Version::TWO,
+            lock_time: LockTime::from_height(0).unwrap(),
+            input: (0..3)
+                .map(|i| TxIn {
+                    previous_output: OutPoint::new(Txid(Hash::from_byte_array([i; 32])), i as u32),
+                    script_sig: ScriptBuf::default(),
+                    sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+                    witness: Witness::default(),
+                })
+                .collect(),
+            output: vec![
+                TxOut {
+                    value: Amount::from_sat(10_000),
+                    script_pubkey: ScriptBuf::new_p2wpkh(&WPubkeyHash::from_byte_array([1; 20])),
+                },
+                TxOut {
+                    value: Amount::from_sat(5_000),
+                    script_pubkey: ScriptBuf::new_p2wpkh(&WPubkeyHash::from_byte_array([2; 20])),
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_payloads_match_transaction() {
+        let tx = transaction();
+        let session = BitcoinSigningSession::new(tx.clone());
+        let script_code = ScriptBuf::new_p2pkh(&PubkeyHash::from_byte_array([3; 20]));
+
+        for input_index in 0..3 {
+            for sighash_type in [
+                EcdsaSighashType::All,
+                EcdsaSighashType::None,
+                EcdsaSighashType::Single,
+                EcdsaSighashType::AllPlusAnyoneCanPay,
+            ] {
+                assert_eq!(
+                    session.build_for_signing_segwit(
+                        sighash_type,
+                        input_index,
+                        &script_code,
+                        20_000
+                    ),
+                    tx.build_for_signing_segwit(sighash_type, input_index, &script_code, 20_000)
+                );
+                assert_eq!(
+                    session.hash_for_signing_segwit(
+                        sighash_type,
+                        input_index,
+                        &script_code,
+                        20_000
+                    ),
+                    tx.hash_for_signing_segwit(sighash_type, input_index, &script_code, 20_000)
+                );
+            }
+            assert_eq!(
+                session.hash_for_signing_legacy_input(
+                    input_index,
+                    &script_code,
+                    EcdsaSighashType::All
+                ),
+                tx.hash_for_signing_legacy_input(input_index, &script_code, EcdsaSighashType::All)
+            );
+        }
+    }
+
+    #[test]
+    fn test_sign_every_input() {
+        let secp = Secp256k1::new();
+        let secret_key = SecretKey::from_slice(&[0x42; 32]).unwrap();
+        let public_key = secret_key.public_key(&secp).serialize();
+        let script_code = ScriptBuf::new_p2pkh(&PubkeyHash::from_byte_array([3; 20]));
+
+        let mut session = BitcoinSigningSession::new(transaction());
+        let mut expected = transaction();
+
+        // Signing inputs in any order, the unlocks don't change the payloads.
+        for input_index in [2, 0, 1] {
+            let payload = if input_index == 1 {
+                session.hash_for_signing_legacy_input(
+                    input_index,
+                    &script_code,
+                    EcdsaSighashType::All,
+                )
+            } else {
+                session.hash_for_signing_segwit(
+                    EcdsaSighashType::All,
+                    input_index,
+                    &script_code,
+                    20_000,
+                )
+            };
+            let signature = secp
+                .sign_ecdsa(&Message::from_digest(payload), &secret_key)
+                .serialize_der();
+
+            if input_index == 1 {
+                let script_sig = ScriptBuilder::new()
+                    .push_slice(&[&signature[..], &[EcdsaSighashType::All as u8]].concat())
+                    .push_slice(&public_key)
+                    .into_script();
+                expected.input[1].script_sig = script_sig.clone();
+                session.set_script_sig(input_index, script_sig).unwrap();
+            } else {
+                let witness = Witness::p2wpkh(&signature, EcdsaSighashType::All, &public_key);
+                expected.input[input_index].witness = witness.clone();
+                session.set_witness(input_index, witness).unwrap();
+            }
+        }
+
+        assert_eq!(session.unsigned_inputs(), Vec::<usize>::new());
+        assert_eq!(session.finalize().unwrap(), expected);
+    }
+
+    #[test]
+    fn test_errors() {
+        let mut session = BitcoinSigningSession::new(transaction());
+
+        assert_eq!(
+            session.set_witness(3, Witness::default()),
+            Err(SignatureError::InputIndexOutOfRange(3))
+        );
+        session
+            .set_witness(0, Witness::from_slice(&[[1u8; 64]]))
+            .unwrap();
+
+        assert_eq!(session.unsigned_inputs(), vec![1, 2]);
+        assert_eq!(session.finalize(), Err(SignatureError::UnsignedInput(1)));
+    }
+}
@@ -0,0 +1,51 @@
+use serde::{Deserialize, Serialize};
+
+use super::Amount;
+
+/// A coin sharing the Bitcoin transaction wire format.
+///
+/// Litecoin and Dogecoin transactions are serialized, signed and hashed exactly like Bitcoin
+/// ones, so [`BitcoinTransaction`](crate::bitcoin::bitcoin_transaction::BitcoinTransaction)
+/// builds them as is. The coin only changes which features and amounts are valid, see
+/// [`BitcoinTransaction::validate_for_coin`](crate::bitcoin::bitcoin_transaction::BitcoinTransaction::validate_for_coin).
+///
+/// Dogecoin's AuxPoW only extends block headers, transactions are unaffected. Litecoin MWEB
+/// transactions (segwit flag `0x08`) are not supported.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Coin {
+    #[default]
+    Bitcoin,
+    Litecoin,
+    Dogecoin,
+}
+
+impl Coin {
+    /// Returns `true` if the coin activated SegWit (BIP-141), i.e. accepts witnesses.
+    pub const fn supports_segwit(&self) -> bool {
+        match self {
+            Self::Bitcoin | Self::Litecoin => true,
+            Self::Dogecoin => false,
+        }
+    }
+
+    /// Returns `true` if the coin activated Taproot (BIP-341).
+    pub const fn supports_taproot(&self) -> bool {
+        match self {
+            Self::Bitcoin | Self::Litecoin => true,
+            Self::Dogecoin => false,
+        }
+    }
+
+    /// Returns the largest valid amount of an output, or of all the outputs of a transaction.
+    ///
+    /// This is the total supply for Bitcoin and Litecoin. Dogecoin has no supply cap, its
+    /// nodes enforce an arbitrary 10 billion DOGE limit instead.
+    pub const fn max_money(&self) -> Amount {
+        match self {
+            Self::Bitcoin => Amount::MAX_MONEY,
+            Self::Litecoin => Amount::from_int_btc(84_000_000),
+            Self::Dogecoin => Amount::from_int_btc(10_000_000_000),
+        }
+    }
+}
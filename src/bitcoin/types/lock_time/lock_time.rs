@@ -1,10 +1,11 @@
 use crate::bitcoin::encoding::{Decodable, Encodable};
 
 use super::{height::Height, time::Time};
+use std::fmt;
 use std::io::{BufRead, Write};
 
 use borsh::{BorshDeserialize, BorshSerialize};
-use near_sdk::serde::{Deserialize, Serialize};
+use serde::{Deserialize, Serialize};
 
 /// Locktime itself is an unsigned 4-byte integer which can be parsed two ways:
 ///
@@ -25,19 +26,19 @@ impl LockTime {
     /// The number of bytes that the locktime contributes to the size of a transaction.
     pub const SIZE: usize = 4; // Serialized length of a u32.
 
-    pub fn from_height(height: u32) -> Result<Self, String> {
+    pub fn from_height(height: u32) -> Result<Self, LockTimeError> {
         if Height::is_valid(height) {
             Ok(Self(height))
         } else {
-            Err(format!("Invalid block height: {}", height))
+            Err(LockTimeError::InvalidHeight(height))
         }
     }
 
-    pub fn from_time(time: u32) -> Result<Self, String> {
+    pub fn from_time(time: u32) -> Result<Self, LockTimeError> {
         if Time::is_valid(time) {
             Ok(Self(time))
         } else {
-            Err(format!("Invalid timestamp: {}", time))
+            Err(LockTimeError::InvalidTime(time))
         }
     }
 
@@ -54,6 +55,32 @@ impl LockTime {
     }
 }
 
+/// Errors returned when creating a [`LockTime`] from a height or a timestamp, or a relative
+/// lock time [`Sequence`](crate::bitcoin::types::Sequence).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LockTimeError {
+    /// The value is not below the 500 million threshold, so it would be read as a timestamp.
+    InvalidHeight(u32),
+    /// The value is below the 500 million threshold, so it would be read as a block height.
+    InvalidTime(u32),
+    /// The relative lock time, in seconds, is too long to be encoded in a sequence number.
+    InvalidRelativeTime(u32),
+}
+
+impl fmt::Display for LockTimeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidHeight(height) => write!(f, "invalid block height: {}", height),
+            Self::InvalidTime(time) => write!(f, "invalid timestamp: {}", time),
+            Self::InvalidRelativeTime(seconds) => {
+                write!(f, "relative lock time of {} seconds is too long", seconds)
+            }
+        }
+    }
+}
+
+impl std::error::Error for LockTimeError {}
+
 impl Encodable for LockTime {
     fn encode<W: Write + ?Sized>(&self, w: &mut W) -> Result<usize, std::io::Error> {
         self.0.encode(w)
@@ -100,12 +127,18 @@ mod tests {
 
     #[test]
     fn test_locktime_invalid_height() {
-        assert!(LockTime::from_height(Height::MAX + 1).is_err());
+        assert_eq!(
+            LockTime::from_height(Height::MAX + 1),
+            Err(LockTimeError::InvalidHeight(Height::MAX + 1))
+        );
     }
 
     #[test]
     fn test_locktime_invalid_time() {
-        assert!(LockTime::from_time(Time::MIN - 1).is_err());
+        assert_eq!(
+            LockTime::from_time(Time::MIN - 1),
+            Err(LockTimeError::InvalidTime(Time::MIN - 1))
+        );
     }
 
     #[test]
@@ -5,4 +5,4 @@ pub mod height;
 mod lock_time;
 pub mod time;
 
-pub use self::lock_time::LockTime;
+pub use self::lock_time::{LockTime, LockTimeError};
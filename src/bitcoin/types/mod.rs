@@ -1,24 +1,36 @@
 /// Minimal required Bitcoin types, inspired by <https://github.com/rust-bitcoin/rust-bitcoin>
+mod coin;
 mod lock_time;
+mod network;
+pub mod opcodes;
 mod script_buf;
+mod script_builder;
 mod sighash;
+mod taproot;
 mod transaction_type;
 mod tx_in;
 mod tx_out;
+mod utxo;
 mod version;
 
+pub use self::coin::Coin;
 pub use self::lock_time::height::Height;
 pub use self::lock_time::time::Time;
-pub use self::lock_time::LockTime;
-pub use self::script_buf::ScriptBuf;
-pub use self::sighash::EcdsaSighashType;
+pub use self::lock_time::{LockTime, LockTimeError};
+pub use self::network::Network;
+pub use self::script_buf::{ScriptBuf, MAX_SCRIPT_ELEMENT_SIZE, MAX_SCRIPT_SIZE};
+pub use self::script_builder::ScriptBuilder;
+pub use self::sighash::{EcdsaSighashType, TapSighashType};
+pub use self::taproot::{ControlBlock, TapBranchHash, TapLeafHash};
 pub use self::transaction_type::TransactionType;
 pub use self::tx_in::Hash;
 pub use self::tx_in::OutPoint;
+pub use self::tx_in::ParseOutPointError;
 pub use self::tx_in::Sequence;
 pub use self::tx_in::TxIn;
 pub use self::tx_in::Txid;
 pub use self::tx_in::Witness;
-pub use self::tx_out::Amount;
-pub use self::tx_out::TxOut;
+pub use self::tx_out::{Amount, ParseAmountError};
+pub use self::tx_out::{TxOut, DEFAULT_DUST_RELAY_FEE_RATE};
+pub use self::utxo::Utxo;
 pub use self::version::Version;
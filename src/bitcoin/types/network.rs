@@ -0,0 +1,51 @@
+use super::Hash;
+use crate::bitcoin::constants::{
+    MAINNET_DEFAULT_PORT, MAINNET_GENESIS_BLOCK_HASH, MAINNET_MAGIC, REGTEST_DEFAULT_PORT,
+    REGTEST_GENESIS_BLOCK_HASH, REGTEST_MAGIC, SIGNET_DEFAULT_PORT, SIGNET_GENESIS_BLOCK_HASH,
+    SIGNET_MAGIC, TESTNET_DEFAULT_PORT, TESTNET_GENESIS_BLOCK_HASH, TESTNET_MAGIC,
+};
+
+pub use crate::types::BitcoinNetwork as Network;
+
+impl Network {
+    /// Returns the version byte of WIF encoded private keys on this network.
+    ///
+    /// All test networks share the same prefix, so a WIF key only tells mainnet and test
+    /// networks apart.
+    pub const fn wif_prefix(&self) -> u8 {
+        match self {
+            Self::Bitcoin => 0x80,
+            Self::Testnet | Self::Signet | Self::Regtest => 0xef,
+        }
+    }
+
+    /// Returns the magic bytes starting every P2P message on this network.
+    pub const fn magic(&self) -> [u8; 4] {
+        match self {
+            Self::Bitcoin => MAINNET_MAGIC,
+            Self::Testnet => TESTNET_MAGIC,
+            Self::Signet => SIGNET_MAGIC,
+            Self::Regtest => REGTEST_MAGIC,
+        }
+    }
+
+    /// Returns the default P2P port of nodes on this network.
+    pub const fn default_port(&self) -> u16 {
+        match self {
+            Self::Bitcoin => MAINNET_DEFAULT_PORT,
+            Self::Testnet => TESTNET_DEFAULT_PORT,
+            Self::Signet => SIGNET_DEFAULT_PORT,
+            Self::Regtest => REGTEST_DEFAULT_PORT,
+        }
+    }
+
+    /// Returns the hash of the genesis block of this network.
+    pub const fn genesis_block_hash(&self) -> Hash {
+        match self {
+            Self::Bitcoin => MAINNET_GENESIS_BLOCK_HASH,
+            Self::Testnet => TESTNET_GENESIS_BLOCK_HASH,
+            Self::Signet => SIGNET_GENESIS_BLOCK_HASH,
+            Self::Regtest => REGTEST_GENESIS_BLOCK_HASH,
+        }
+    }
+}
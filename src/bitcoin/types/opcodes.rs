@@ -0,0 +1,36 @@
+//! Script opcodes used by the standard output and input scripts.
+
+/// Pushes an empty array, also the witness version 0.
+pub const OP_0: u8 = 0x00;
+/// The next byte is the number of bytes to push.
+pub const OP_PUSHDATA1: u8 = 0x4c;
+/// The next two bytes (little endian) are the number of bytes to push.
+pub const OP_PUSHDATA2: u8 = 0x4d;
+/// The next four bytes (little endian) are the number of bytes to push.
+pub const OP_PUSHDATA4: u8 = 0x4e;
+/// Pushes the number -1.
+pub const OP_1NEGATE: u8 = 0x4f;
+/// Pushes the number 1, also the witness version 1 (Taproot).
+pub const OP_1: u8 = 0x51;
+/// Pushes the number 16, `OP_2` to `OP_15` push the numbers in between.
+pub const OP_16: u8 = 0x60;
+/// Marks the output as unspendable, used to embed data.
+pub const OP_RETURN: u8 = 0x6a;
+/// Removes the top stack item.
+pub const OP_DROP: u8 = 0x75;
+/// Duplicates the top stack item.
+pub const OP_DUP: u8 = 0x76;
+/// Pushes 1 if the two top stack items are equal, 0 otherwise.
+pub const OP_EQUAL: u8 = 0x87;
+/// Same as `OP_EQUAL`, but fails the script instead of pushing 0.
+pub const OP_EQUALVERIFY: u8 = 0x88;
+/// Replaces the top stack item with its RIPEMD-160 of SHA-256.
+pub const OP_HASH160: u8 = 0xa9;
+/// Checks a signature against a public key.
+pub const OP_CHECKSIG: u8 = 0xac;
+/// Checks `m` signatures against `n` public keys.
+pub const OP_CHECKMULTISIG: u8 = 0xae;
+/// Fails unless the lock time of the transaction is past the top stack item (BIP-65).
+pub const OP_CHECKLOCKTIMEVERIFY: u8 = 0xb1;
+/// Fails unless the relative lock time of the input is past the top stack item (BIP-112).
+pub const OP_CHECKSEQUENCEVERIFY: u8 = 0xb2;
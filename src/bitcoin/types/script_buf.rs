@@ -3,17 +3,219 @@ use std::io::{BufRead, Write};
 
 use borsh::{BorshDeserialize, BorshSerialize};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 
+use super::opcodes::{
+    OP_0, OP_1, OP_16, OP_1NEGATE, OP_CHECKMULTISIG, OP_CHECKSIG, OP_DUP, OP_EQUAL, OP_EQUALVERIFY,
+    OP_HASH160, OP_PUSHDATA1, OP_PUSHDATA2, OP_PUSHDATA4, OP_RETURN,
+};
+use super::ScriptBuilder;
 use crate::bitcoin::encoding::{encode::Encodable, Decodable};
+use crate::bitcoin::hash_types::{PubkeyHash, ScriptHash, WPubkeyHash, WScriptHash};
+use crate::validation::ValidationIssue;
 
 #[derive(
     Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize,
 )]
 pub struct ScriptBuf(pub Vec<u8>);
 
+/// Formats the script as hex, as in the `hex` field of the scripts returned by bitcoind.
+impl fmt::Display for ScriptBuf {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&hex::encode(&self.0))
+    }
+}
+
+/// The Pay-to-Anchor (P2A) output script: `OP_1 OP_PUSHBYTES_2 4e73`.
+const P2A_SCRIPT: [u8; 4] = [0x51, 0x02, 0x4e, 0x73];
+
+/// Maximum size of a script, larger scripts fail when executed.
+pub const MAX_SCRIPT_SIZE: usize = 10_000;
+/// Maximum size of an element pushed on the stack.
+pub const MAX_SCRIPT_ELEMENT_SIZE: usize = 520;
+
 impl ScriptBuf {
+    /// Creates a Pay-to-Anchor (P2A) output script.
+    ///
+    /// P2A is a keyless, witness v1 anchor output which anyone can spend with an empty witness.
+    /// It lets any party bump a presigned transaction with a CPFP child, and is standard since
+    /// Bitcoin Core 28.0.
+    pub fn new_p2a() -> Self {
+        Self(P2A_SCRIPT.to_vec())
+    }
+
+    /// Creates a Pay-to-Public-Key-Hash (P2PKH) output script:
+    /// `OP_DUP OP_HASH160 <pubkey_hash> OP_EQUALVERIFY OP_CHECKSIG`.
+    pub fn new_p2pkh(pubkey_hash: &PubkeyHash) -> Self {
+        ScriptBuilder::new()
+            .push_opcode(OP_DUP)
+            .push_opcode(OP_HASH160)
+            .push_slice(&pubkey_hash.as_byte_array())
+            .push_opcode(OP_EQUALVERIFY)
+            .push_opcode(OP_CHECKSIG)
+            .into_script()
+    }
+
+    /// Creates a Pay-to-Script-Hash (P2SH) output script: `OP_HASH160 <script_hash> OP_EQUAL`.
+    pub fn new_p2sh(script_hash: &ScriptHash) -> Self {
+        ScriptBuilder::new()
+            .push_opcode(OP_HASH160)
+            .push_slice(&script_hash.as_byte_array())
+            .push_opcode(OP_EQUAL)
+            .into_script()
+    }
+
+    /// Creates a Pay-to-Witness-Public-Key-Hash (P2WPKH) output script: `OP_0 <pubkey_hash>`.
+    pub fn new_p2wpkh(pubkey_hash: &WPubkeyHash) -> Self {
+        ScriptBuilder::new()
+            .push_opcode(OP_0)
+            .push_slice(&pubkey_hash.as_byte_array())
+            .into_script()
+    }
+
+    /// Creates a Pay-to-Witness-Script-Hash (P2WSH) output script: `OP_0 <script_hash>`.
+    pub fn new_p2wsh(script_hash: &WScriptHash) -> Self {
+        ScriptBuilder::new()
+            .push_opcode(OP_0)
+            .push_slice(&script_hash.as_byte_array())
+            .into_script()
+    }
+
+    /// Returns the P2WSH output script locked by this witness script, i.e. by its SHA-256.
+    pub fn to_p2wsh(&self) -> Self {
+        Self::new_p2wsh(&WScriptHash::from_byte_array(
+            Sha256::digest(&self.0).into(),
+        ))
+    }
+
+    /// Creates a bare multisig script requiring `required` signatures from `public_keys`:
+    /// `OP_m <public_keys...> OP_n OP_CHECKMULTISIG`.
+    ///
+    /// This is the redeem script of a P2SH multisig, the signatures of a spend must be in the
+    /// order of their keys in the script.
+    ///
+    /// Panics if `required` is zero or larger than the number of keys, or if there are more
+    /// than 16 keys.
+    pub fn new_multisig<K: AsRef<[u8]>>(required: u8, public_keys: &[K]) -> Self {
+        assert!(
+            public_keys.len() <= 16,
+            "A multisig script has at most 16 keys, got {}",
+            public_keys.len()
+        );
+        assert!(
+            required > 0 && required as usize <= public_keys.len(),
+            "Cannot require {} of {} signatures",
+            required,
+            public_keys.len()
+        );
+
+        let builder = public_keys.iter().fold(
+            ScriptBuilder::new().push_int(required.into()),
+            |builder, key| builder.push_slice(key.as_ref()),
+        );
+
+        builder
+            .push_int(public_keys.len() as i64)
+            .push_opcode(OP_CHECKMULTISIG)
+            .into_script()
+    }
+
+    /// Creates an unspendable output script carrying `data`: `OP_RETURN <data>`.
+    ///
+    /// By default, nodes only relay a single `OP_RETURN` output of at most 83 bytes, i.e. 80
+    /// bytes of data.
+    pub fn new_op_return(data: &[u8]) -> Self {
+        ScriptBuilder::new()
+            .push_opcode(OP_RETURN)
+            .push_slice(data)
+            .into_script()
+    }
+
+    /// Returns a [`ScriptBuilder`] to build a custom script.
+    pub const fn builder() -> ScriptBuilder {
+        ScriptBuilder::new()
+    }
+
+    /// Returns `true` if this is a Pay-to-Anchor (P2A) output script.
+    pub fn is_p2a(&self) -> bool {
+        self.0 == P2A_SCRIPT
+    }
+
+    /// Returns `true` if this is a P2PKH output script, see [`Self::new_p2pkh`].
+    pub fn is_p2pkh(&self) -> bool {
+        matches!(
+            self.0.as_slice(),
+            [OP_DUP, OP_HASH160, 20, .., OP_EQUALVERIFY, OP_CHECKSIG] if self.0.len() == 25
+        )
+    }
+
+    /// Returns `true` if this is a P2SH output script, see [`Self::new_p2sh`].
+    pub fn is_p2sh(&self) -> bool {
+        matches!(self.0.as_slice(), [OP_HASH160, 20, .., OP_EQUAL] if self.0.len() == 23)
+    }
+
+    /// Returns `true` if this is a P2WPKH output script, see [`Self::new_p2wpkh`].
+    pub fn is_p2wpkh(&self) -> bool {
+        matches!(self.0.as_slice(), [OP_0, 20, ..] if self.0.len() == 22)
+    }
+
+    /// Returns `true` if this is a P2WSH output script, see [`Self::new_p2wsh`].
+    pub fn is_p2wsh(&self) -> bool {
+        matches!(self.0.as_slice(), [OP_0, 32, ..] if self.0.len() == 34)
+    }
+
+    /// Returns `true` if this is a P2TR output script: `OP_1 <32-byte output key>`.
+    pub fn is_p2tr(&self) -> bool {
+        matches!(self.0.as_slice(), [OP_1, 32, ..] if self.0.len() == 34)
+    }
+
+    /// Returns `true` if this is a segwit output script of any version: a version opcode followed
+    /// by a single push of a 2 to 40 byte witness program. (BIP-141)
+    pub fn is_witness_program(&self) -> bool {
+        match self.0.as_slice() {
+            [version, len, program @ ..] => {
+                (*version == OP_0 || (OP_1..=OP_16).contains(version))
+                    && (2..=40).contains(len)
+                    && program.len() == *len as usize
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns `true` if outputs locked by this script can never be spent: it starts with
+    /// `OP_RETURN` or is larger than [`MAX_SCRIPT_SIZE`].
+    pub fn is_unspendable(&self) -> bool {
+        self.0.first() == Some(&OP_RETURN) || self.0.len() > MAX_SCRIPT_SIZE
+    }
+
+    /// Returns the number of required signatures and the public keys of a bare multisig script
+    /// (see [`Self::new_multisig`]), or `None` if this is not one.
+    ///
+    /// Keys must be pushed as 33 or 65 bytes, their content is not checked.
+    pub fn parse_multisig(&self) -> Option<(u8, Vec<&[u8]>)> {
+        let [first, middle @ .., last_key_count, OP_CHECKMULTISIG] = self.0.as_slice() else {
+            return None;
+        };
+        let small_int = |opcode: u8| (OP_1..=OP_16).contains(&opcode).then(|| opcode - OP_1 + 1);
+        let required = small_int(*first)?;
+        let key_count = small_int(*last_key_count)?;
+
+        let mut public_keys = Vec::new();
+        let mut rest = middle;
+        while let Some((&len, tail)) = rest.split_first() {
+            if len != 33 && len != 65 {
+                return None;
+            }
+            public_keys.push(tail.get(..len as usize)?);
+            rest = &tail[len as usize..];
+        }
+
+        (public_keys.len() == key_count as usize && required <= key_count)
+            .then_some((required, public_keys))
+    }
+
     /// Creates a [`ScriptBuf`] from a hex string.
-    pub fn from_hex(s: &str) -> Result<Self, String> {
+    pub fn from_hex(s: &str) -> Result<Self, hex::FromHexError> {
         let v = Vec::from_hex(s)?;
         Ok(Self::from_bytes(v))
     }
@@ -24,6 +226,98 @@ impl ScriptBuf {
     pub const fn from_bytes(bytes: Vec<u8>) -> Self {
         Self(bytes)
     }
+
+    /// Checks the script against the size and push rules enforced by consensus and relay policy.
+    ///
+    /// Scripts larger than [`MAX_SCRIPT_SIZE`], pushes larger than [`MAX_SCRIPT_ELEMENT_SIZE`]
+    /// and truncated pushes are errors. Pushes which don't use the smallest possible encoding
+    /// (BIP-62 minimal push) are warnings, as the script is valid but its spend is not relayed
+    /// by default.
+    pub fn validate_standardness(&self) -> Vec<ValidationIssue> {
+        self.standardness_issues("script")
+    }
+
+    /// Same as [`Self::validate_standardness`], reporting the issues against `field`.
+    pub(crate) fn standardness_issues(&self, field: &str) -> Vec<ValidationIssue> {
+        let mut issues = Vec::new();
+
+        if self.0.len() > MAX_SCRIPT_SIZE {
+            issues.push(ValidationIssue::error(
+                field,
+                format!(
+                    "script is {} bytes, the maximum is {}",
+                    self.0.len(),
+                    MAX_SCRIPT_SIZE
+                ),
+            ));
+        }
+
+        let mut position = 0;
+        while let Some(&opcode) = self.0.get(position) {
+            let (len_size, len) = match opcode {
+                0x01..=0x4b => (0, Some(opcode as usize)),
+                OP_PUSHDATA1 => (1, self.0.get(position + 1).map(|&len| len as usize)),
+                OP_PUSHDATA2 => (
+                    2,
+                    self.0
+                        .get(position + 1..position + 3)
+                        .map(|len| u16::from_le_bytes([len[0], len[1]]) as usize),
+                ),
+                OP_PUSHDATA4 => (
+                    4,
+                    self.0
+                        .get(position + 1..position + 5)
+                        .map(|len| u32::from_le_bytes([len[0], len[1], len[2], len[3]]) as usize),
+                ),
+                // Not a push of data.
+                _ => {
+                    position += 1;
+                    continue;
+                }
+            };
+
+            let start = position + 1 + len_size;
+            let Some(data) = len.and_then(|len| self.0.get(start..start + len)) else {
+                issues.push(ValidationIssue::error(
+                    field,
+                    format!("truncated push at byte {}", position),
+                ));
+                break;
+            };
+
+            if data.len() > MAX_SCRIPT_ELEMENT_SIZE {
+                issues.push(ValidationIssue::error(
+                    field,
+                    format!(
+                        "push of {} bytes at byte {}, the maximum is {}",
+                        data.len(),
+                        position,
+                        MAX_SCRIPT_ELEMENT_SIZE
+                    ),
+                ));
+            }
+
+            let minimal_opcode = match data {
+                [] => OP_0,
+                [value @ 1..=16] => OP_1 + value - 1,
+                [0x81] => OP_1NEGATE,
+                _ if data.len() <= 0x4b => data.len() as u8,
+                _ if data.len() <= 0xff => OP_PUSHDATA1,
+                _ if data.len() <= 0xffff => OP_PUSHDATA2,
+                _ => OP_PUSHDATA4,
+            };
+            if opcode != minimal_opcode {
+                issues.push(ValidationIssue::warning(
+                    field,
+                    format!("non-minimal push at byte {}", position),
+                ));
+            }
+
+            position = start + data.len();
+        }
+
+        issues
+    }
 }
 
 pub trait FromHex: Sized {
@@ -35,10 +329,10 @@ pub trait FromHex: Sized {
 }
 
 impl FromHex for Vec<u8> {
-    type Error = String;
+    type Error = hex::FromHexError;
 
     fn from_hex(s: &str) -> Result<Self, Self::Error> {
-        hex::decode(s).map_err(|e| e.to_string())
+        hex::decode(s)
     }
 }
 
@@ -46,6 +340,10 @@ impl Encodable for ScriptBuf {
     fn encode<W: Write + ?Sized>(&self, w: &mut W) -> Result<usize, std::io::Error> {
         self.0.encode(w)
     }
+
+    fn encoded_size(&self) -> usize {
+        self.0.encoded_size()
+    }
 }
 
 impl Decodable for ScriptBuf {
@@ -53,3 +351,179 @@ impl Decodable for ScriptBuf {
         Ok(Self(Decodable::decode_from_finite_reader(r)?))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::validation::Severity;
+
+    #[test]
+    fn test_p2a_script() {
+        let script = ScriptBuf::new_p2a();
+
+        assert_eq!(script, ScriptBuf::from_hex("51024e73").unwrap());
+        assert!(script.is_p2a());
+        // A witness v1 program of another length is not an anchor.
+        assert!(!ScriptBuf::from_hex("51034e7300").unwrap().is_p2a());
+        assert!(!ScriptBuf::default().is_p2a());
+    }
+
+    #[test]
+    fn test_from_invalid_hex() {
+        assert_eq!(
+            ScriptBuf::from_hex("510"),
+            Err(hex::FromHexError::OddLength)
+        );
+        assert!(matches!(
+            ScriptBuf::from_hex("zz"),
+            Err(hex::FromHexError::InvalidHexCharacter { c: 'z', index: 0 })
+        ));
+    }
+
+    #[test]
+    fn test_standard_scripts_against_rust_bitcoin() {
+        use bitcoin::hashes::Hash;
+
+        let hash = [0x42; 20];
+
+        assert_eq!(
+            ScriptBuf::new_p2pkh(&PubkeyHash::from_byte_array(hash)).0,
+            bitcoin::ScriptBuf::new_p2pkh(&bitcoin::PubkeyHash::from_byte_array(hash)).into_bytes()
+        );
+        assert_eq!(
+            ScriptBuf::new_p2sh(&ScriptHash::from_byte_array(hash)).0,
+            bitcoin::ScriptBuf::new_p2sh(&bitcoin::ScriptHash::from_byte_array(hash)).into_bytes()
+        );
+        assert_eq!(
+            ScriptBuf::new_p2wpkh(&WPubkeyHash::from_byte_array(hash)).0,
+            bitcoin::ScriptBuf::new_p2wpkh(&bitcoin::WPubkeyHash::from_byte_array(hash))
+                .into_bytes()
+        );
+        assert_eq!(
+            ScriptBuf::new_p2wsh(&WScriptHash::from_byte_array([0x42; 32])).0,
+            bitcoin::ScriptBuf::new_p2wsh(&bitcoin::WScriptHash::from_byte_array([0x42; 32]))
+                .into_bytes()
+        );
+        let witness_script = ScriptBuf::new_multisig(1, &[[0x02; 33]]);
+        assert_eq!(
+            witness_script.to_p2wsh().0,
+            bitcoin::ScriptBuf::from_bytes(witness_script.0.clone())
+                .to_p2wsh()
+                .into_bytes()
+        );
+        assert_eq!(
+            ScriptBuf::new_op_return(b"omni").0,
+            bitcoin::ScriptBuf::new_op_return(b"omni").into_bytes()
+        );
+    }
+
+    #[test]
+    fn test_classify_standard_scripts() {
+        let scripts = [
+            ScriptBuf::new_p2pkh(&PubkeyHash::from_byte_array([1; 20])),
+            ScriptBuf::new_p2sh(&ScriptHash::from_byte_array([1; 20])),
+            ScriptBuf::new_p2wpkh(&WPubkeyHash::from_byte_array([1; 20])),
+            ScriptBuf::new_p2wsh(&WScriptHash::from_byte_array([1; 32])),
+            ScriptBuf::builder()
+                .push_opcode(OP_1)
+                .push_slice(&[1; 32])
+                .into_script(),
+        ];
+        let predicates: [fn(&ScriptBuf) -> bool; 5] = [
+            ScriptBuf::is_p2pkh,
+            ScriptBuf::is_p2sh,
+            ScriptBuf::is_p2wpkh,
+            ScriptBuf::is_p2wsh,
+            ScriptBuf::is_p2tr,
+        ];
+
+        for (i, script) in scripts.iter().enumerate() {
+            for (j, predicate) in predicates.iter().enumerate() {
+                assert_eq!(predicate(script), i == j, "script {} predicate {}", i, j);
+            }
+        }
+
+        // Same prefix, wrong length.
+        let mut truncated = scripts[3].clone();
+        truncated.0.pop();
+        assert!(!truncated.is_p2wsh());
+        assert!(!ScriptBuf::new_p2a().is_p2tr());
+    }
+
+    #[test]
+    fn test_multisig_script() {
+        let keys = [[0x02; 33], [0x03; 33], [0x02; 33]];
+        let script = ScriptBuf::new_multisig(2, &keys);
+
+        let mut expected = bitcoin::script::Builder::new().push_int(2);
+        for key in &keys {
+            expected = expected.push_slice(key);
+        }
+        let expected = expected
+            .push_int(3)
+            .push_opcode(bitcoin::opcodes::all::OP_CHECKMULTISIG)
+            .into_script();
+        assert_eq!(script.0, expected.into_bytes());
+
+        let (required, public_keys) = script.parse_multisig().unwrap();
+        assert_eq!(required, 2);
+        assert_eq!(public_keys, keys.iter().map(|k| &k[..]).collect::<Vec<_>>());
+
+        // 4 of 3.
+        let mut invalid = script.clone();
+        invalid.0[0] = OP_1 + 3;
+        assert_eq!(invalid.parse_multisig(), None);
+        // Wrong key count.
+        let mut invalid = script.clone();
+        invalid.0[script.0.len() - 2] = OP_1 + 1;
+        assert_eq!(invalid.parse_multisig(), None);
+        // Truncated key.
+        let mut invalid = script.0.clone();
+        invalid.remove(1 + 33);
+        assert_eq!(ScriptBuf(invalid).parse_multisig(), None);
+        assert_eq!(
+            ScriptBuf::new_p2sh(&ScriptHash::from_byte_array([0; 20])).parse_multisig(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_validate_standardness() {
+        let p2pkh =
+            ScriptBuf::from_hex("76a91462e907b15cbf27d5425399ebf6f0fb50ebb88f1888ac").unwrap();
+        assert!(p2pkh.validate_standardness().is_empty());
+        let pushdata1 = ScriptBuf([&[0x4c, 0x4c][..], &[0xab; 0x4c]].concat());
+        assert!(pushdata1.validate_standardness().is_empty());
+
+        let non_minimal = [
+            ("0105", "OP_PUSHBYTES_1 0x05 instead of OP_5"),
+            ("0181", "OP_PUSHBYTES_1 0x81 instead of OP_1NEGATE"),
+            ("4c00", "OP_PUSHDATA1 of an empty element instead of OP_0"),
+            ("4c02abcd", "OP_PUSHDATA1 of 2 bytes, not OP_PUSHBYTES_2"),
+            ("4d0100ab", "OP_PUSHDATA2 of 1 byte"),
+        ];
+        for (hex, case) in non_minimal {
+            let issues = ScriptBuf::from_hex(hex).unwrap().validate_standardness();
+            assert_eq!(issues.len(), 1, "{}", case);
+            assert_eq!(issues[0].severity, Severity::Warning);
+            assert_eq!(issues[0].message, "non-minimal push at byte 0");
+        }
+
+        let truncated = ScriptBuf::from_hex("0002ab")
+            .unwrap()
+            .validate_standardness();
+        assert_eq!(truncated.len(), 1);
+        assert!(truncated[0].is_error());
+        assert_eq!(truncated[0].message, "truncated push at byte 1");
+
+        let large_push = ScriptBuf([&[0x4d, 0x09, 0x02][..], &[0; 521]].concat());
+        let issues = large_push.validate_standardness();
+        assert_eq!(issues.len(), 1);
+        assert!(issues[0].is_error());
+
+        let large_script = ScriptBuf(vec![0x61; MAX_SCRIPT_SIZE + 1]);
+        let issues = large_script.validate_standardness();
+        assert_eq!(issues.len(), 1);
+        assert!(issues[0].is_error());
+    }
+}
@@ -0,0 +1,147 @@
+use super::opcodes::{OP_0, OP_1, OP_1NEGATE, OP_PUSHDATA1, OP_PUSHDATA2, OP_PUSHDATA4};
+use super::ScriptBuf;
+
+/// Builds a script one opcode or push at a time.
+///
+/// ```ignore
+/// let script_sig = ScriptBuilder::new()
+///     .push_slice(&signature)
+///     .push_slice(&public_key)
+///     .into_script();
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ScriptBuilder(Vec<u8>);
+
+impl ScriptBuilder {
+    pub const fn new() -> Self {
+        Self(Vec::new())
+    }
+
+    /// Appends an opcode, see [`opcodes`](super::opcodes).
+    pub fn push_opcode(mut self, opcode: u8) -> Self {
+        self.0.push(opcode);
+        self
+    }
+
+    /// Appends a push of `data`, using the smallest push opcode for its length.
+    ///
+    /// Unlike [`Self::push_int`], single bytes are pushed as data, which is what signatures
+    /// and public keys need.
+    pub fn push_slice(mut self, data: &[u8]) -> Self {
+        match data.len() {
+            len @ 0..=0x4b => self.0.push(len as u8),
+            len @ 0x4c..=0xff => {
+                self.0.push(OP_PUSHDATA1);
+                self.0.push(len as u8);
+            }
+            len @ 0x100..=0xffff => {
+                self.0.push(OP_PUSHDATA2);
+                self.0.extend_from_slice(&(len as u16).to_le_bytes());
+            }
+            len => {
+                self.0.push(OP_PUSHDATA4);
+                self.0.extend_from_slice(
+                    &u32::try_from(len)
+                        .expect("script pushes are limited to 4 GiB")
+                        .to_le_bytes(),
+                );
+            }
+        }
+        self.0.extend_from_slice(data);
+        self
+    }
+
+    /// Appends a number: `OP_1NEGATE`, `OP_0` or `OP_1` to `OP_16` if possible, a push of its
+    /// minimal script number encoding otherwise.
+    pub fn push_int(self, n: i64) -> Self {
+        match n {
+            -1 => self.push_opcode(OP_1NEGATE),
+            0 => self.push_opcode(OP_0),
+            1..=16 => self.push_opcode(OP_1 + (n as u8 - 1)),
+            _ => self.push_slice(&script_num(n)),
+        }
+    }
+
+    pub fn into_script(self) -> ScriptBuf {
+        ScriptBuf(self.0)
+    }
+}
+
+/// Encodes `n` as a script number: little endian magnitude, with the sign in the most
+/// significant bit.
+fn script_num(n: i64) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    let mut magnitude = n.unsigned_abs();
+    while magnitude > 0 {
+        bytes.push((magnitude & 0xff) as u8);
+        magnitude >>= 8;
+    }
+
+    // Add a byte for the sign if the most significant bit is already used.
+    let sign = if n < 0 { 0x80 } else { 0x00 };
+    match bytes.last_mut() {
+        Some(last) if *last & 0x80 != 0 => bytes.push(sign),
+        Some(last) => *last |= sign,
+        None => {}
+    }
+
+    bytes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bitcoin::types::opcodes::{OP_CHECKSIG, OP_DUP};
+
+    #[test]
+    fn test_against_rust_bitcoin() {
+        for n in [
+            -1,
+            0,
+            1,
+            16,
+            17,
+            -2,
+            127,
+            128,
+            -128,
+            255,
+            256,
+            0x7fff_ffff,
+            -0x8000_0000,
+        ] {
+            assert_eq!(
+                ScriptBuilder::new().push_int(n).into_script().0,
+                bitcoin::script::Builder::new()
+                    .push_int(n)
+                    .into_script()
+                    .into_bytes(),
+                "push_int({})",
+                n
+            );
+        }
+
+        for len in [0, 1, 75, 76, 255, 256, 520] {
+            let data = vec![0xab; len];
+            let expected = bitcoin::script::Builder::new()
+                .push_slice(<&bitcoin::script::PushBytes>::try_from(data.as_slice()).unwrap())
+                .into_script()
+                .into_bytes();
+            assert_eq!(
+                ScriptBuilder::new().push_slice(&data).into_script().0,
+                expected,
+                "push_slice of {} bytes",
+                len
+            );
+        }
+
+        assert_eq!(
+            ScriptBuilder::new()
+                .push_opcode(OP_DUP)
+                .push_opcode(OP_CHECKSIG)
+                .into_script()
+                .0,
+            vec![0x76, 0xac]
+        );
+    }
+}
@@ -1,5 +1,5 @@
 use borsh::{BorshDeserialize, BorshSerialize};
-use near_sdk::serde::{Deserialize, Serialize};
+use serde::{Deserialize, Serialize};
 
 #[derive(
     Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize,
@@ -8,4 +8,83 @@ use near_sdk::serde::{Deserialize, Serialize};
 pub enum EcdsaSighashType {
     /// 0x1: Sign all outputs.
     All = 0x01,
+    /// 0x2: Sign no outputs, anyone can choose where the funds go.
+    None = 0x02,
+    /// 0x3: Sign the output with the same index as the input.
+    Single = 0x03,
+    /// 0x81: Sign all outputs and only this input.
+    AllPlusAnyoneCanPay = 0x81,
+    /// 0x82: Sign no outputs and only this input.
+    NonePlusAnyoneCanPay = 0x82,
+    /// 0x83: Sign the output with the same index as the input, and only this input.
+    SinglePlusAnyoneCanPay = 0x83,
+}
+
+impl EcdsaSighashType {
+    /// Parses the sighash type byte of an ECDSA signature, only accepting standard types.
+    pub const fn from_consensus_u8(byte: u8) -> Option<Self> {
+        match byte {
+            0x01 => Some(Self::All),
+            0x02 => Some(Self::None),
+            0x03 => Some(Self::Single),
+            0x81 => Some(Self::AllPlusAnyoneCanPay),
+            0x82 => Some(Self::NonePlusAnyoneCanPay),
+            0x83 => Some(Self::SinglePlusAnyoneCanPay),
+            _ => None,
+        }
+    }
+
+    pub(crate) const fn anyone_can_pay(self) -> bool {
+        self as u8 & 0x80 != 0
+    }
+
+    pub(crate) const fn is_none(self) -> bool {
+        matches!(self, Self::None | Self::NonePlusAnyoneCanPay)
+    }
+
+    pub(crate) const fn is_single(self) -> bool {
+        matches!(self, Self::Single | Self::SinglePlusAnyoneCanPay)
+    }
+}
+
+/// Signature hash types of Taproot (BIP-341) signatures.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize,
+)]
+#[borsh(use_discriminant = true)]
+pub enum TapSighashType {
+    /// 0x0: Sign all inputs and outputs, without a trailing sighash type byte in the signature.
+    Default = 0x00,
+    /// 0x1: Sign all outputs.
+    All = 0x01,
+    /// 0x2: Sign no outputs.
+    None = 0x02,
+    /// 0x3: Sign the output with the same index as the input.
+    Single = 0x03,
+    /// 0x81: Sign all outputs and only this input.
+    AllPlusAnyoneCanPay = 0x81,
+    /// 0x82: Sign no outputs and only this input.
+    NonePlusAnyoneCanPay = 0x82,
+    /// 0x83: Sign the output with the same index as the input, and only this input.
+    SinglePlusAnyoneCanPay = 0x83,
+}
+
+impl TapSighashType {
+    /// Parses the sighash type byte of a Taproot signature.
+    pub const fn from_consensus_u8(byte: u8) -> Option<Self> {
+        match byte {
+            0x00 => Some(Self::Default),
+            0x01 => Some(Self::All),
+            0x02 => Some(Self::None),
+            0x03 => Some(Self::Single),
+            0x81 => Some(Self::AllPlusAnyoneCanPay),
+            0x82 => Some(Self::NonePlusAnyoneCanPay),
+            0x83 => Some(Self::SinglePlusAnyoneCanPay),
+            _ => None,
+        }
+    }
+
+    pub(crate) const fn anyone_can_pay(self) -> bool {
+        self as u8 & 0x80 != 0
+    }
 }
@@ -0,0 +1,220 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use serde::{Deserialize, Serialize};
+
+use super::ScriptBuf;
+use crate::bitcoin::bitcoin_transaction::tagged_hash;
+use crate::bitcoin::constants::{
+    TAPROOT_CONTROL_BASE_SIZE, TAPROOT_CONTROL_MAX_NODE_COUNT, TAPROOT_CONTROL_NODE_SIZE,
+    TAPROOT_LEAF_TAPSCRIPT,
+};
+use crate::bitcoin::encoding::Encodable;
+use crate::signature_error::SignatureError;
+
+/// The hash of a leaf of a Taproot script tree (BIP-341), committing to its script and leaf
+/// version.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+    Serialize,
+    Deserialize,
+    BorshSerialize,
+    BorshDeserialize,
+)]
+pub struct TapLeafHash(pub [u8; 32]);
+
+impl TapLeafHash {
+    pub fn from_script(script: &ScriptBuf, leaf_version: u8) -> Self {
+        let mut script_data = Vec::new();
+        script
+            .encode(&mut script_data)
+            .expect("writing to a vec never fails");
+
+        Self(tagged_hash("TapLeaf", &[&[leaf_version], &script_data]))
+    }
+
+    /// Returns the hash of a BIP-342 tapscript leaf, i.e. with [`TAPROOT_LEAF_TAPSCRIPT`].
+    pub fn from_tapscript(script: &ScriptBuf) -> Self {
+        Self::from_script(script, TAPROOT_LEAF_TAPSCRIPT)
+    }
+}
+
+/// The hash of an inner node of a Taproot script tree, committing to its two children.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+    Serialize,
+    Deserialize,
+    BorshSerialize,
+    BorshDeserialize,
+)]
+pub struct TapBranchHash(pub [u8; 32]);
+
+impl TapBranchHash {
+    /// Combines the hashes of two children, leaves or branches. They are sorted first, so the
+    /// order in which they are given does not matter.
+    pub fn from_node_hashes(a: [u8; 32], b: [u8; 32]) -> Self {
+        let (first, second) = if a <= b { (a, b) } else { (b, a) };
+        Self(tagged_hash("TapBranch", &[&first, &second]))
+    }
+}
+
+/// The control block of a Taproot script path spend, the last item of its witness.
+///
+/// It proves that the spent script is a leaf of the tree committed to by the output key. The
+/// full witness is the script inputs, the script and then the serialized control block,
+/// optionally followed by an annex.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub struct ControlBlock {
+    /// The leaf version of the spent script, usually [`TAPROOT_LEAF_TAPSCRIPT`].
+    pub leaf_version: u8,
+    /// Whether the y coordinate of the tweaked output key is odd.
+    pub output_key_parity: bool,
+    /// The x-only internal key.
+    pub internal_key: [u8; 32],
+    /// The hashes of the siblings on the path from the leaf to the root, starting at the leaf.
+    pub merkle_branch: Vec<[u8; 32]>,
+}
+
+impl ControlBlock {
+    pub fn from_slice(bytes: &[u8]) -> Result<Self, SignatureError> {
+        let branch_len = bytes
+            .len()
+            .checked_sub(TAPROOT_CONTROL_BASE_SIZE)
+            .ok_or(SignatureError::InvalidControlBlock)?;
+        let node_count = branch_len / TAPROOT_CONTROL_NODE_SIZE;
+        if node_count * TAPROOT_CONTROL_NODE_SIZE != branch_len
+            || node_count > TAPROOT_CONTROL_MAX_NODE_COUNT
+        {
+            return Err(SignatureError::InvalidControlBlock);
+        }
+
+        let first = bytes[0];
+        let (internal_key, merkle_branch) = bytes[1..].split_at(32);
+
+        Ok(Self {
+            leaf_version: first & 0xfe,
+            output_key_parity: first & 0x01 == 1,
+            internal_key: internal_key.try_into().expect("internal key is 32 bytes"),
+            merkle_branch: merkle_branch
+                .chunks(TAPROOT_CONTROL_NODE_SIZE)
+                .map(|node| node.try_into().expect("node is 32 bytes"))
+                .collect(),
+        })
+    }
+
+    pub fn serialize(&self) -> Vec<u8> {
+        let mut buffer = Vec::with_capacity(
+            TAPROOT_CONTROL_BASE_SIZE + self.merkle_branch.len() * TAPROOT_CONTROL_NODE_SIZE,
+        );
+        buffer.push(self.leaf_version | u8::from(self.output_key_parity));
+        buffer.extend_from_slice(&self.internal_key);
+        for node in &self.merkle_branch {
+            buffer.extend_from_slice(node);
+        }
+        buffer
+    }
+
+    /// Returns the root of the script tree, given the hash of the spent leaf.
+    ///
+    /// Together with the internal key, it must tweak into the output key being spent.
+    pub fn merkle_root(&self, leaf_hash: &TapLeafHash) -> [u8; 32] {
+        self.merkle_branch
+            .iter()
+            .fold(leaf_hash.0, |node, sibling| {
+                TapBranchHash::from_node_hashes(node, *sibling).0
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bitcoin::hashes::Hash;
+    use bitcoin::secp256k1::{Parity, Secp256k1, XOnlyPublicKey};
+    use bitcoin::taproot::{LeafVersion, TaprootBuilder};
+
+    #[test]
+    fn test_against_rust_bitcoin() {
+        let internal_key: XOnlyPublicKey =
+            "93c7378d96518a75448821c4f7c8f4bae7ce60f804d03d1f0628dd5dd0f5de51"
+                .parse()
+                .unwrap();
+        let scripts: Vec<_> = [vec![0x51], vec![0x52], vec![0x53, 0x87]]
+            .into_iter()
+            .map(bitcoin::ScriptBuf::from_bytes)
+            .collect();
+
+        let spend_info = TaprootBuilder::new()
+            .add_leaf(1, scripts[0].clone())
+            .unwrap()
+            .add_leaf(2, scripts[1].clone())
+            .unwrap()
+            .add_leaf(2, scripts[2].clone())
+            .unwrap()
+            .finalize(&Secp256k1::verification_only(), internal_key)
+            .unwrap();
+        let merkle_root = spend_info.merkle_root().unwrap().to_byte_array();
+
+        let leaf_hashes: Vec<_> = scripts
+            .iter()
+            .map(|script| {
+                let leaf_hash = TapLeafHash::from_tapscript(&ScriptBuf(script.to_bytes()));
+                assert_eq!(
+                    leaf_hash.0,
+                    bitcoin::taproot::TapLeafHash::from_script(script, LeafVersion::TapScript)
+                        .to_byte_array()
+                );
+                leaf_hash
+            })
+            .collect();
+
+        let branch = TapBranchHash::from_node_hashes(leaf_hashes[2].0, leaf_hashes[1].0);
+        assert_eq!(
+            TapBranchHash::from_node_hashes(leaf_hashes[0].0, branch.0).0,
+            merkle_root
+        );
+
+        for (script, leaf_hash) in scripts.iter().zip(&leaf_hashes) {
+            let expected = spend_info
+                .control_block(&(script.clone(), LeafVersion::TapScript))
+                .unwrap();
+
+            let control_block = ControlBlock {
+                leaf_version: TAPROOT_LEAF_TAPSCRIPT,
+                output_key_parity: expected.output_key_parity == Parity::Odd,
+                internal_key: internal_key.serialize(),
+                merkle_branch: expected
+                    .merkle_branch
+                    .iter()
+                    .map(|node| node.to_byte_array())
+                    .collect(),
+            };
+            assert_eq!(control_block.serialize(), expected.serialize());
+            assert_eq!(
+                ControlBlock::from_slice(&expected.serialize()).unwrap(),
+                control_block
+            );
+            assert_eq!(control_block.merkle_root(leaf_hash), merkle_root);
+        }
+    }
+
+    #[test]
+    fn test_control_block_from_slice_rejects_invalid_lengths() {
+        for len in [0, 32, 34, 64, 33 + 32 * 129] {
+            assert_eq!(
+                ControlBlock::from_slice(&vec![TAPROOT_LEAF_TAPSCRIPT; len]),
+                Err(SignatureError::InvalidControlBlock),
+                "{} bytes",
+                len
+            );
+        }
+    }
+}
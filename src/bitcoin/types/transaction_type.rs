@@ -1,3 +1,12 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use serde::{Deserialize, Serialize};
+
+use super::ScriptBuf;
+use crate::signature_error::SignatureError;
+
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize,
+)]
 pub enum TransactionType {
     /// Pay to public key hash
     P2PKH,
@@ -7,4 +16,85 @@ pub enum TransactionType {
     P2WPKH,
     /// Pay to witness script hash
     P2WSH,
+    /// Pay to taproot (key path spends)
+    P2TR,
+}
+
+impl TransactionType {
+    /// Returns the type of the output locked by `script_pubkey`, or `None` if it is not one of
+    /// the supported standard scripts.
+    ///
+    /// Nested SegWit outputs (P2SH-P2WPKH, P2SH-P2WSH) are classified as [`Self::P2SH`], as
+    /// their script pubkey doesn't reveal the wrapped script.
+    pub fn classify(script_pubkey: &ScriptBuf) -> Option<Self> {
+        if script_pubkey.is_p2pkh() {
+            Some(Self::P2PKH)
+        } else if script_pubkey.is_p2sh() {
+            Some(Self::P2SH)
+        } else if script_pubkey.is_p2wpkh() {
+            Some(Self::P2WPKH)
+        } else if script_pubkey.is_p2wsh() {
+            Some(Self::P2WSH)
+        } else if script_pubkey.is_p2tr() {
+            Some(Self::P2TR)
+        } else {
+            None
+        }
+    }
+
+    /// Returns `true` if inputs spending this type are finalized with a witness, i.e. with
+    /// `build_with_witness`, rather than a script sig.
+    pub const fn is_segwit(self) -> bool {
+        matches!(self, Self::P2WPKH | Self::P2WSH | Self::P2TR)
+    }
+
+    /// Checks that an input spending `script_pubkey` can be finalized as `self`, before
+    /// calling `build_with_script_sig` or `build_with_witness`.
+    ///
+    /// Returns [`SignatureError::WrongSpendType`] if `script_pubkey` is of another (or no
+    /// supported) type.
+    pub fn check_script_pubkey(self, script_pubkey: &ScriptBuf) -> Result<(), SignatureError> {
+        if Self::classify(script_pubkey) == Some(self) {
+            Ok(())
+        } else {
+            Err(SignatureError::WrongSpendType)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bitcoin::hash_types::{PubkeyHash, WPubkeyHash};
+
+    #[test]
+    fn test_classify() {
+        let p2wpkh = ScriptBuf::new_p2wpkh(&WPubkeyHash::from_byte_array([7; 20]));
+
+        assert_eq!(
+            TransactionType::classify(&ScriptBuf::new_p2pkh(&PubkeyHash::from_byte_array([7; 20]))),
+            Some(TransactionType::P2PKH)
+        );
+        assert_eq!(
+            TransactionType::classify(&p2wpkh),
+            Some(TransactionType::P2WPKH)
+        );
+        assert_eq!(
+            TransactionType::classify(&ScriptBuf::new_multisig(1, &[[2; 33]]).to_p2wsh()),
+            Some(TransactionType::P2WSH)
+        );
+        assert_eq!(TransactionType::classify(&ScriptBuf::new_p2a()), None);
+        assert_eq!(
+            TransactionType::classify(&ScriptBuf::new_op_return(b"omni")),
+            None
+        );
+
+        assert!(TransactionType::P2WPKH.is_segwit());
+        assert!(!TransactionType::P2SH.is_segwit());
+        assert_eq!(TransactionType::P2WPKH.check_script_pubkey(&p2wpkh), Ok(()));
+        assert_eq!(
+            TransactionType::P2PKH.check_script_pubkey(&p2wpkh),
+            Err(SignatureError::WrongSpendType)
+        );
+    }
 }
@@ -16,9 +16,18 @@ impl Hash {
         self.0
     }
 
+    /// Creates a hash from bytes in the same (display) order as returned by
+    /// [`Hash::as_byte_array`], i.e. the reverse of the order they are serialized in.
+    pub const fn from_byte_array(bytes: [u8; 32]) -> Self {
+        Self(bytes)
+    }
+
+    /// Parses a hash in display order, i.e. as printed by block explorers and bitcoind, which
+    /// is the reverse of the order it is serialized in.
     pub fn from_hex(hex: &str) -> Result<Self, hex::FromHexError> {
-        let bytes = hex::decode(hex)?;
-        Ok(Self(bytes.try_into().expect("Invalid length")))
+        let mut bytes = [0u8; 32];
+        hex::decode_to_slice(hex, &mut bytes)?;
+        Ok(Self(bytes))
     }
 }
 
@@ -8,7 +8,7 @@ pub mod tx_in;
 pub mod witness;
 
 pub use self::hash::Hash;
-pub use self::outpoint::OutPoint;
+pub use self::outpoint::{OutPoint, ParseOutPointError};
 pub use self::sequence::Sequence;
 pub use self::tx_id::Txid;
 pub use self::tx_in::TxIn;
@@ -1,10 +1,10 @@
-use std::io::{BufRead, Write};
+use std::fmt;
+use std::num::ParseIntError;
+use std::str::FromStr;
 
 use borsh::{BorshDeserialize, BorshSerialize};
 use serde::{Deserialize, Serialize};
 
-use crate::bitcoin::encoding::{Decodable, Encodable};
-
 use super::tx_id::Txid;
 
 /// A reference to a transaction output.
@@ -52,26 +52,55 @@ impl Default for OutPoint {
     }
 }
 
-impl Encodable for OutPoint {
-    fn encode<W: Write + ?Sized>(&self, w: &mut W) -> Result<usize, std::io::Error> {
-        let mut len = 0;
-        len += self.txid.encode(w)?;
-        len += self.vout.encode(w)?;
-        Ok(len)
+/// Errors returned when parsing an [`OutPoint`] from a `txid:vout` string.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParseOutPointError {
+    /// The string is not of the form `txid:vout`.
+    InvalidFormat,
+    /// The txid is not 32 bytes of hex.
+    InvalidTxid(hex::FromHexError),
+    /// The output index is not a u32.
+    InvalidVout(ParseIntError),
+}
+
+impl fmt::Display for ParseOutPointError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidFormat => write!(f, "outpoint should be of the form txid:vout"),
+            Self::InvalidTxid(e) => write!(f, "invalid txid: {}", e),
+            Self::InvalidVout(e) => write!(f, "invalid vout: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for ParseOutPointError {}
+
+impl FromStr for OutPoint {
+    type Err = ParseOutPointError;
+
+    /// Parses `txid:vout`, the txid being in display order as printed by bitcoind.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (txid, vout) = s.split_once(':').ok_or(ParseOutPointError::InvalidFormat)?;
+
+        Ok(Self {
+            txid: Txid::from_hex(txid).map_err(ParseOutPointError::InvalidTxid)?,
+            vout: vout.parse().map_err(ParseOutPointError::InvalidVout)?,
+        })
     }
 }
 
-impl Decodable for OutPoint {
-    fn decode<R: BufRead + ?Sized>(r: &mut R) -> Result<Self, std::io::Error> {
-        let txid = Txid::decode(r)?;
-        let vout = Decodable::decode(r)?;
-        Ok(Self { txid, vout })
+impl fmt::Display for OutPoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.txid, self.vout)
     }
 }
 
+crate::impl_consensus_encoding!(OutPoint, txid, vout);
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::bitcoin::encoding::{Decodable, Encodable};
 
     #[test]
     fn test_encode_decode() {
@@ -87,4 +116,32 @@ mod tests {
         let decoded_outpoint = OutPoint::decode_from_finite_reader(&mut buf.as_slice()).unwrap();
         assert_eq!(decoded_outpoint, outpoint);
     }
+
+    #[test]
+    fn test_from_str() {
+        let s = "2ece6cd71fee90ff613cee8f30a52c3ecc58685acf9b817b9c467b7ff199871c:1";
+        let outpoint: OutPoint = s.parse().unwrap();
+        assert_eq!(outpoint.vout, 1);
+        assert_eq!(outpoint.to_string(), s);
+
+        let expected: bitcoin::OutPoint = s.parse().unwrap();
+        let mut buf = Vec::new();
+        outpoint.encode(&mut buf).unwrap();
+        assert_eq!(buf, bitcoin::consensus::serialize(&expected));
+
+        assert_eq!(
+            "deadbeef".parse::<OutPoint>(),
+            Err(ParseOutPointError::InvalidFormat)
+        );
+        assert_eq!(
+            "deadbeef:1".parse::<OutPoint>(),
+            Err(ParseOutPointError::InvalidTxid(
+                hex::FromHexError::InvalidStringLength
+            ))
+        );
+        assert!(matches!(
+            s.replace(":1", ":-1").parse::<OutPoint>(),
+            Err(ParseOutPointError::InvalidVout(_))
+        ));
+    }
 }
@@ -4,6 +4,7 @@ use borsh::{BorshDeserialize, BorshSerialize};
 use serde::{Deserialize, Serialize};
 
 use crate::bitcoin::encoding::{Decodable, Encodable};
+use crate::bitcoin::types::LockTimeError;
 
 /// Bitcoin transaction input sequence number.
 #[derive(
@@ -23,6 +24,78 @@ impl Sequence {
     ///
     /// This sequence number enables replace-by-fee and absolute lock time.
     pub const ZERO: Self = Self(0);
+    /// The sequence number used by wallets to signal replace-by-fee (BIP-125) without a relative
+    /// lock time. Absolute lock time is enabled.
+    pub const ENABLE_RBF_NO_LOCKTIME: Self = Self(0xFFFFFFFD);
+    /// The highest sequence number enabling absolute lock time, without replace-by-fee nor a
+    /// relative lock time.
+    pub const ENABLE_LOCKTIME_NO_RBF: Self = Self(0xFFFFFFFE);
+
+    /// When set, the sequence number is not a relative lock time (BIP-68).
+    const LOCK_TIME_DISABLE_FLAG: u32 = 1 << 31;
+    /// When set, the relative lock time is in units of 512 seconds instead of blocks (BIP-68).
+    const LOCK_TYPE_FLAG: u32 = 1 << 22;
+    /// The bits holding the value of a relative lock time (BIP-68).
+    const LOCK_TIME_MASK: u32 = 0x0000FFFF;
+
+    /// Creates a relative lock time of `height` blocks (BIP-68), enforced by `OP_CSV` scripts.
+    ///
+    /// Relative lock times are only enforced in transactions of version 2 or higher. They also
+    /// signal replace-by-fee.
+    pub const fn from_height(height: u16) -> Self {
+        Self(height as u32)
+    }
+
+    /// Creates a relative lock time of `intervals` units of 512 seconds (BIP-68).
+    pub const fn from_512_second_intervals(intervals: u16) -> Self {
+        Self(Self::LOCK_TYPE_FLAG | intervals as u32)
+    }
+
+    /// Creates a relative lock time of at least `seconds`, rounded up to the next multiple of
+    /// 512 seconds, the granularity of BIP-68.
+    ///
+    /// Fails if it would be longer than `0xFFFF * 512` seconds (about 388 days).
+    pub fn from_seconds(seconds: u32) -> Result<Self, LockTimeError> {
+        u16::try_from(seconds.div_ceil(512))
+            .map(Self::from_512_second_intervals)
+            .map_err(|_| LockTimeError::InvalidRelativeTime(seconds))
+    }
+
+    /// Returns `true` if the sequence number signals replace-by-fee (BIP-125).
+    pub const fn is_rbf(&self) -> bool {
+        self.0 < Self::ENABLE_LOCKTIME_NO_RBF.0
+    }
+
+    /// Returns `true` if the sequence number is a relative lock time (BIP-68).
+    pub const fn is_relative_lock_time(&self) -> bool {
+        self.0 & Self::LOCK_TIME_DISABLE_FLAG == 0
+    }
+
+    /// Returns `true` if the sequence number is a relative lock time in blocks.
+    pub const fn is_height_locked(&self) -> bool {
+        self.is_relative_lock_time() && self.0 & Self::LOCK_TYPE_FLAG == 0
+    }
+
+    /// Returns `true` if the sequence number is a relative lock time in units of 512 seconds.
+    pub const fn is_time_locked(&self) -> bool {
+        self.is_relative_lock_time() && self.0 & Self::LOCK_TYPE_FLAG != 0
+    }
+
+    /// Returns `true` if the sequence number enables the absolute lock time of the transaction,
+    /// i.e. is not [`Self::MAX`].
+    pub const fn enables_absolute_lock_time(&self) -> bool {
+        self.0 != Self::MAX.0
+    }
+
+    /// Returns the value of the relative lock time, in blocks or units of 512 seconds, or `None`
+    /// if the sequence number is not a relative lock time.
+    pub const fn relative_lock_time_value(&self) -> Option<u16> {
+        if self.is_relative_lock_time() {
+            Some((self.0 & Self::LOCK_TIME_MASK) as u16)
+        } else {
+            None
+        }
+    }
 }
 
 impl Default for Sequence {
@@ -56,4 +129,82 @@ mod tests {
         assert_eq!(sequence.encode(&mut buf).unwrap(), 4);
         assert_eq!(Sequence::decode(&mut buf.as_slice()).unwrap(), sequence);
     }
+
+    #[test]
+    fn test_against_rust_bitcoin() {
+        use bitcoin::Sequence as RustBitcoinSequence;
+
+        assert_eq!(
+            Sequence::ENABLE_RBF_NO_LOCKTIME.0,
+            RustBitcoinSequence::ENABLE_RBF_NO_LOCKTIME.0
+        );
+        assert_eq!(
+            Sequence::ENABLE_LOCKTIME_NO_RBF.0,
+            RustBitcoinSequence::ENABLE_LOCKTIME_NO_RBF.0
+        );
+
+        for height in [0, 1, 144, u16::MAX] {
+            assert_eq!(
+                Sequence::from_height(height).0,
+                RustBitcoinSequence::from_height(height).0
+            );
+        }
+        for seconds in [0, 1, 512, 513, 86_400, 0xFFFF * 512] {
+            assert_eq!(
+                Sequence::from_seconds(seconds).unwrap().0,
+                RustBitcoinSequence::from_seconds_ceil(seconds).unwrap().0,
+                "{} seconds",
+                seconds
+            );
+        }
+        assert_eq!(
+            Sequence::from_seconds(0xFFFF * 512 + 1),
+            Err(LockTimeError::InvalidRelativeTime(0xFFFF * 512 + 1))
+        );
+
+        for value in [
+            0,
+            0x0000_0090,
+            0x0040_0010,
+            0x7fff_ffff,
+            0x8000_0000,
+            0xffff_fffd,
+            0xffff_fffe,
+            0xffff_ffff,
+        ] {
+            let sequence = Sequence(value);
+            let expected = RustBitcoinSequence(value);
+            assert_eq!(sequence.is_rbf(), expected.is_rbf(), "{:#x}", value);
+            assert_eq!(
+                sequence.is_relative_lock_time(),
+                expected.is_relative_lock_time(),
+                "{:#x}",
+                value
+            );
+            assert_eq!(
+                sequence.is_height_locked(),
+                expected.is_height_locked(),
+                "{:#x}",
+                value
+            );
+            assert_eq!(
+                sequence.is_time_locked(),
+                expected.is_time_locked(),
+                "{:#x}",
+                value
+            );
+            assert_eq!(
+                sequence.enables_absolute_lock_time(),
+                expected.enables_absolute_lock_time(),
+                "{:#x}",
+                value
+            );
+        }
+
+        assert_eq!(
+            Sequence::from_height(144).relative_lock_time_value(),
+            Some(144)
+        );
+        assert_eq!(Sequence::MAX.relative_lock_time_value(), None);
+    }
 }
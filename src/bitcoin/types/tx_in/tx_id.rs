@@ -1,5 +1,6 @@
 use core::fmt;
 use std::io::{BufRead, Write};
+use std::str::FromStr;
 
 use crate::bitcoin::encoding::{Decodable, Encodable};
 
@@ -26,6 +27,11 @@ impl Txid {
     pub const fn all_zeros() -> Self {
         Self(Hash::all_zeros())
     }
+
+    /// Parses a txid in display order, as printed by block explorers and bitcoind.
+    pub fn from_hex(hex: &str) -> Result<Self, hex::FromHexError> {
+        Hash::from_hex(hex).map(Self)
+    }
 }
 
 impl Encodable for Txid {
@@ -40,6 +46,14 @@ impl Decodable for Txid {
     }
 }
 
+impl FromStr for Txid {
+    type Err = hex::FromHexError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_hex(s)
+    }
+}
+
 impl fmt::Display for Txid {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{}", self.0)
@@ -58,4 +72,26 @@ mod tests {
         assert_eq!(txid.encode(&mut buf).unwrap(), 32);
         assert_eq!(Txid::decode(&mut buf.as_slice()).unwrap(), txid);
     }
+
+    #[test]
+    fn test_from_hex_uses_display_order() {
+        let hex = "2ece6cd71fee90ff613cee8f30a52c3ecc58685acf9b817b9c467b7ff199871c";
+        let txid: Txid = hex.parse().unwrap();
+        assert_eq!(txid.to_string(), hex);
+
+        // The serialization is in reverse order.
+        let mut buf = Vec::new();
+        txid.encode(&mut buf).unwrap();
+        assert_eq!(buf[0], 0x1c);
+        assert_eq!(buf[31], 0x2e);
+
+        let expected: bitcoin::Txid = hex.parse().unwrap();
+        assert_eq!(buf, bitcoin::consensus::serialize(&expected));
+
+        assert_eq!(
+            Txid::from_hex(&hex[2..]),
+            Err(hex::FromHexError::InvalidStringLength)
+        );
+        assert!(Txid::from_hex(&hex.replace('2', "z")).is_err());
+    }
 }
@@ -1,11 +1,16 @@
+use core::fmt;
 use std::io::{BufRead, Write};
 
 use borsh::{BorshDeserialize, BorshSerialize};
+use serde::de::{self, Deserializer, MapAccess, SeqAccess, Visitor};
+use serde::ser::{SerializeSeq, Serializer};
 use serde::{Deserialize, Serialize};
 
+use crate::bitcoin::bitcoin_transaction::BitcoinDecodeError;
 use crate::bitcoin::encoding::{
     decode::MAX_VEC_SIZE, extensions::WriteExt, utils::VarInt, Decodable, Encodable,
 };
+use crate::bitcoin::types::{EcdsaSighashType, TapSighashType};
 
 /// The Witness is the data used to unlock bitcoin since the [segwit upgrade].
 ///
@@ -16,8 +21,12 @@ use crate::bitcoin::encoding::{
 /// For serialization and deserialization performance it is stored internally as a single `Vec`,
 /// saving some allocations.
 ///
+/// With serde, the witness is a sequence of its items: hex strings in human-readable formats,
+/// like the `txinwitness` of bitcoind, and byte arrays otherwise. Either form is accepted when
+/// deserializing, as well as the former JSON object of the internal fields.
+///
 /// [segwit upgrade]: <https://github.com/bitcoin/bips/blob/master/bip-0143.mediawiki>
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
 pub struct Witness {
     /// Contains the witness `Vec<Vec<u8>>` serialization.
     ///
@@ -57,6 +66,47 @@ impl Witness {
         }
     }
 
+    /// Creates an empty [`Witness`] with enough capacity to hold `elements` items whose
+    /// serialization (each including its compact size prefix) takes `content_len` bytes in total.
+    pub fn with_capacity(elements: usize, content_len: usize) -> Self {
+        Self {
+            content: Vec::with_capacity(content_len + elements * 4),
+            witness_elements: 0,
+            indices_start: 0,
+        }
+    }
+
+    /// Appends a new element to the witness.
+    ///
+    /// The element is written in place after the existing elements and only the index area is
+    /// shifted, so building a witness piecewise does not re-encode the previous elements.
+    pub fn push<T: AsRef<[u8]>>(&mut self, new_element: T) {
+        let new_element = new_element.as_ref();
+        self.witness_elements += 1;
+        let previous_content_end = self.indices_start;
+        let element_len_varint = VarInt::from(new_element.len());
+        let current_content_len = self.content.len();
+        let new_item_total_len = element_len_varint.size() + new_element.len();
+        self.content
+            .resize(current_content_len + new_item_total_len + 4, 0);
+
+        // Move the index area to the end to make room for the new element.
+        self.content[previous_content_end..].rotate_right(new_item_total_len);
+        self.indices_start += new_item_total_len;
+        encode_cursor(
+            &mut self.content,
+            self.indices_start,
+            self.witness_elements - 1,
+            previous_content_end,
+        );
+
+        let end_varint = previous_content_end + element_len_varint.size();
+        element_len_varint
+            .encode(&mut &mut self.content[previous_content_end..end_varint])
+            .expect("writers on vec don't error, space granted through previous resize");
+        self.content[end_varint..end_varint + new_element.len()].copy_from_slice(new_element);
+    }
+
     /// Returns the number of elements this witness holds.
     pub const fn len(&self) -> usize {
         self.witness_elements
@@ -76,6 +126,38 @@ impl Witness {
         self.witness_elements == 0
     }
 
+    /// Returns the serialized size of the witness: the number of elements, then each element
+    /// prefixed by its length.
+    pub fn size(&self) -> usize {
+        self.encoded_size()
+    }
+
+    /// Returns the element at `index`, or `None` if it is out of range.
+    ///
+    /// Unlike indexing the result of [`Self::to_vec`], no element is copied.
+    pub fn nth(&self, index: usize) -> Option<&[u8]> {
+        if index >= self.witness_elements {
+            return None;
+        }
+        let position = decode_cursor(&self.content, self.indices_start, index)?;
+        decode_element(&self.content, position)
+    }
+
+    /// Returns the last element, e.g. the public key of a P2WPKH spend.
+    pub fn last(&self) -> Option<&[u8]> {
+        self.witness_elements
+            .checked_sub(1)
+            .and_then(|index| self.nth(index))
+    }
+
+    /// Returns the second-to-last element, e.g. the signature of a P2WPKH spend or the script
+    /// of a P2WSH spend.
+    pub fn second_to_last(&self) -> Option<&[u8]> {
+        self.witness_elements
+            .checked_sub(2)
+            .and_then(|index| self.nth(index))
+    }
+
     /// Convenience method to create an array of byte-arrays from this witness.
     pub fn to_bytes(&self) -> Vec<Vec<u8>> {
         self.iter().map(|s| s.to_vec()).collect()
@@ -114,6 +196,67 @@ impl Witness {
             indices_start: content_size,
         }
     }
+
+    /// Creates a [`Witness`] from its items as hex strings, e.g. the `txinwitness` array
+    /// returned by bitcoind.
+    pub fn from_hex_items<S: AsRef<str>>(items: &[S]) -> Result<Self, hex::FromHexError> {
+        let items = items
+            .iter()
+            .map(|item| hex::decode(item.as_ref()))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self::from_slice(&items))
+    }
+
+    /// Returns the items of the witness as hex strings, as in the `txinwitness` array of
+    /// bitcoind.
+    pub fn to_hex_items(&self) -> Vec<String> {
+        self.iter().map(hex::encode).collect()
+    }
+
+    /// Parses a witness from the hex of its consensus serialization: the number of items, then
+    /// each item prefixed by its length.
+    pub fn from_hex(s: &str) -> Result<Self, BitcoinDecodeError> {
+        let bytes = hex::decode(s).map_err(|e| BitcoinDecodeError::InvalidHex(e.to_string()))?;
+        let mut slice = bytes.as_slice();
+        let witness = Self::decode(&mut slice)
+            .map_err(|e| BitcoinDecodeError::InvalidEncoding(e.to_string()))?;
+        if !slice.is_empty() {
+            return Err(BitcoinDecodeError::TrailingBytes(slice.len()));
+        }
+        Ok(witness)
+    }
+
+    /// Creates the witness of a P2WPKH spend: the DER encoded signature followed by its sighash
+    /// type byte, then the compressed public key.
+    pub fn p2wpkh(der_signature: &[u8], sighash_type: EcdsaSighashType, public_key: &[u8]) -> Self {
+        let mut signature = Vec::with_capacity(der_signature.len() + 1);
+        signature.extend_from_slice(der_signature);
+        signature.push(sighash_type as u8);
+
+        // Both items are short enough for a single byte length prefix.
+        let mut witness = Self::with_capacity(2, signature.len() + public_key.len() + 2);
+        witness.push(signature);
+        witness.push(public_key);
+        witness
+    }
+
+    /// Creates the witness of a Taproot key path spend: the Schnorr signature, followed by its
+    /// sighash type byte unless it is [`TapSighashType::Default`].
+    pub fn p2tr_key_spend(signature: &[u8; 64], sighash_type: TapSighashType) -> Self {
+        let mut witness = Self::with_capacity(1, 66);
+        match sighash_type {
+            TapSighashType::Default => witness.push(signature),
+            _ => witness.push([&signature[..], &[sighash_type as u8]].concat()),
+        }
+        witness
+    }
+}
+
+/// Formats the items of the witness as hex strings, e.g. `[3044..01, 02ab..]`.
+impl fmt::Display for Witness {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}]", self.to_hex_items().join(", "))
+    }
 }
 
 impl Encodable for Witness {
@@ -126,6 +269,10 @@ impl Encodable for Witness {
         w.emit_slice(&self.content[..content_len])?;
         Ok(content_len + len.size())
     }
+
+    fn encoded_size(&self) -> usize {
+        VarInt::from(self.witness_elements).size() + self.indices_start
+    }
 }
 
 /// An iterator returning individual witness elements.
@@ -140,10 +287,7 @@ impl<'a> Iterator for Iter<'a> {
 
     fn next(&mut self) -> Option<Self::Item> {
         let index = decode_cursor(self.inner, self.indices_start, self.current_index)?;
-        let varint = VarInt::decode(&mut &self.inner[index..]).ok()?;
-        let start = index + varint.size();
-        let end = start + varint.0 as usize;
-        let slice = &self.inner[start..end];
+        let slice = decode_element(self.inner, index)?;
         self.current_index += 1;
         Some(slice)
     }
@@ -232,6 +376,139 @@ impl Decodable for Witness {
     }
 }
 
+/// A witness item serialized with [`Serializer::serialize_bytes`].
+struct SerializeBytes<'a>(&'a [u8]);
+
+impl Serialize for SerializeBytes<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bytes(self.0)
+    }
+}
+
+impl Serialize for Witness {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let human_readable = serializer.is_human_readable();
+        let mut seq = serializer.serialize_seq(Some(self.len()))?;
+        for item in self.iter() {
+            if human_readable {
+                seq.serialize_element(&hex::encode(item))?;
+            } else {
+                seq.serialize_element(&SerializeBytes(item))?;
+            }
+        }
+        seq.end()
+    }
+}
+
+/// A witness item deserialized from a hex string or a byte array.
+struct WitnessItem(Vec<u8>);
+
+struct WitnessItemVisitor;
+
+impl<'de> Visitor<'de> for WitnessItemVisitor {
+    type Value = WitnessItem;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a hex string or a byte array")
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        hex::decode(v).map(WitnessItem).map_err(E::custom)
+    }
+
+    fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+        Ok(WitnessItem(v.to_vec()))
+    }
+
+    fn visit_byte_buf<E: de::Error>(self, v: Vec<u8>) -> Result<Self::Value, E> {
+        Ok(WitnessItem(v))
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+        while let Some(byte) = seq.next_element()? {
+            bytes.push(byte);
+        }
+        Ok(WitnessItem(bytes))
+    }
+}
+
+impl<'de> Deserialize<'de> for WitnessItem {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_any(WitnessItemVisitor)
+        } else {
+            deserializer.deserialize_byte_buf(WitnessItemVisitor)
+        }
+    }
+}
+
+struct WitnessVisitor;
+
+impl<'de> Visitor<'de> for WitnessVisitor {
+    type Value = Witness;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a sequence of witness items")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut witness = Witness::new();
+        while let Some(WitnessItem(item)) = seq.next_element()? {
+            witness.push(item);
+        }
+        Ok(witness)
+    }
+
+    /// Reads the former serialization of the internal fields.
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut content: Option<Vec<u8>> = None;
+        let mut witness_elements: Option<u64> = None;
+        let mut indices_start: Option<usize> = None;
+        while let Some(key) = map.next_key::<String>()? {
+            match key.as_str() {
+                "content" => content = Some(map.next_value()?),
+                "witness_elements" => witness_elements = Some(map.next_value()?),
+                "indices_start" => indices_start = Some(map.next_value()?),
+                _ => {
+                    map.next_value::<de::IgnoredAny>()?;
+                }
+            }
+        }
+        let content = content.ok_or_else(|| de::Error::missing_field("content"))?;
+        let witness_elements =
+            witness_elements.ok_or_else(|| de::Error::missing_field("witness_elements"))?;
+        let indices_start =
+            indices_start.ok_or_else(|| de::Error::missing_field("indices_start"))?;
+
+        // The items are serialized as on the wire, before the index area.
+        let items = content
+            .get(..indices_start)
+            .ok_or_else(|| de::Error::custom("indices_start is out of bounds"))?;
+        let mut bytes = Vec::with_capacity(9 + items.len());
+        VarInt(witness_elements)
+            .encode(&mut bytes)
+            .map_err(de::Error::custom)?;
+        bytes.extend_from_slice(items);
+        let mut slice = bytes.as_slice();
+        let witness = Witness::decode(&mut slice).map_err(de::Error::custom)?;
+        if !slice.is_empty() {
+            return Err(de::Error::custom("witness content has trailing bytes"));
+        }
+        Ok(witness)
+    }
+}
+
+impl<'de> Deserialize<'de> for Witness {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_any(WitnessVisitor)
+        } else {
+            deserializer.deserialize_seq(WitnessVisitor)
+        }
+    }
+}
+
 fn decode_cursor(bytes: &[u8], start_of_indices: usize, index: usize) -> Option<usize> {
     let start = start_of_indices + index * 4;
     let end = start + 4;
@@ -241,6 +518,13 @@ fn decode_cursor(bytes: &[u8], start_of_indices: usize, index: usize) -> Option<
         Some(u32::from_ne_bytes(bytes[start..end].try_into().expect("is u32 size")) as usize)
     }
 }
+/// Returns the element whose length prefix starts at `position` in the content.
+fn decode_element(bytes: &[u8], position: usize) -> Option<&[u8]> {
+    let varint = VarInt::decode(&mut &bytes[position..]).ok()?;
+    let start = position + varint.size();
+    bytes.get(start..start + varint.0 as usize)
+}
+
 /// Correctness Requirements: value must always fit within u32
 fn encode_cursor(bytes: &mut [u8], start_of_indices: usize, index: usize, value: usize) {
     let start = start_of_indices + index * 4;
@@ -259,3 +543,200 @@ fn resize_if_needed(vec: &mut Vec<u8>, required_len: usize) {
         vec.resize(new_len, 0);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_push_matches_from_slice() {
+        let elements: Vec<Vec<u8>> = vec![vec![], vec![1u8; 72], vec![2u8; 33], vec![3u8; 300]];
+
+        let mut witness = Witness::new();
+        for element in &elements {
+            witness.push(element);
+        }
+
+        assert_eq!(witness, Witness::from_slice(&elements));
+        assert_eq!(witness.len(), elements.len());
+        assert_eq!(witness.to_vec(), elements);
+
+        let mut pushed = Vec::new();
+        witness.encode(&mut pushed).unwrap();
+        let mut expected = Vec::new();
+        Witness::from_slice(&elements)
+            .encode(&mut expected)
+            .unwrap();
+        assert_eq!(pushed, expected);
+    }
+
+    #[test]
+    fn test_push_after_from_slice() {
+        let mut witness = Witness::from_slice(&[vec![1u8; 10]]);
+        witness.push([2u8; 5]);
+
+        assert_eq!(witness.to_vec(), vec![vec![1u8; 10], vec![2u8; 5]]);
+    }
+
+    #[test]
+    fn test_accessors_against_rust_bitcoin() {
+        let elements: Vec<Vec<u8>> = vec![vec![], vec![1u8; 72], vec![2u8; 33], vec![3u8; 300]];
+
+        for count in 0..=elements.len() {
+            let witness = Witness::from_slice(&elements[..count]);
+            let expected = bitcoin::Witness::from_slice(&elements[..count]);
+
+            assert_eq!(witness.size(), expected.size(), "{} elements", count);
+            assert_eq!(witness.last(), expected.last(), "{} elements", count);
+            assert_eq!(
+                witness.second_to_last(),
+                expected.second_to_last(),
+                "{} elements",
+                count
+            );
+            for index in 0..=count {
+                assert_eq!(
+                    witness.nth(index),
+                    expected.nth(index),
+                    "{} elements",
+                    count
+                );
+            }
+        }
+
+        let witness = Witness::from_slice(&elements);
+        assert_eq!(witness.nth(1), Some(&[1u8; 72][..]));
+        assert_eq!(witness.last(), Some(&[3u8; 300][..]));
+        assert_eq!(witness.second_to_last(), Some(&[2u8; 33][..]));
+    }
+
+    #[test]
+    fn test_with_capacity() {
+        let mut witness = Witness::with_capacity(2, 73 + 34);
+
+        assert!(witness.is_empty());
+        assert!(witness.content.capacity() >= 73 + 34 + 2 * 4);
+
+        witness.push([1u8; 72]);
+        witness.push([2u8; 33]);
+
+        assert_eq!(witness.to_vec(), vec![vec![1u8; 72], vec![2u8; 33]]);
+    }
+
+    #[test]
+    fn test_push_then_decode_roundtrip() {
+        let mut witness = Witness::new();
+        witness.push([1u8; 64]);
+        witness.push([2u8; 32]);
+
+        let mut buf = Vec::new();
+        witness.encode(&mut buf).unwrap();
+
+        assert_eq!(Witness::decode(&mut buf.as_slice()).unwrap(), witness);
+    }
+
+    #[test]
+    fn test_hex_items() {
+        let items = [
+            "3044022062bc2e0a3ef4cf5a9a05a96d3d06ae0a4a6e7a1e0d3e8f1ce1ab5d3f2a4b8c7d02201f2f0b5a21c8f3d33b6e1c1f4c9a7a6b0b8d4a1d0e2c9f3b8a7d6c5e4f3a2b1c01",
+            "02a1633cafcc01ebfb6d78e39f687a1f0995c62fc95f51ead10a02ee0be551b5dc",
+        ];
+        let witness = Witness::from_hex_items(&items).unwrap();
+
+        assert_eq!(witness.len(), 2);
+        assert_eq!(witness.to_vec()[1][0], 0x02);
+        assert_eq!(witness.to_hex_items(), items);
+        assert_eq!(witness.to_string(), format!("[{}, {}]", items[0], items[1]));
+        assert_eq!(Witness::new().to_string(), "[]");
+        assert_eq!(
+            Witness::from_hex_items(&["0"]),
+            Err(hex::FromHexError::OddLength)
+        );
+
+        let mut buf = Vec::new();
+        witness.encode(&mut buf).unwrap();
+        assert_eq!(Witness::from_hex(&hex::encode(&buf)).unwrap(), witness);
+        assert!(matches!(
+            Witness::from_hex(&hex::encode(&buf[..buf.len() - 1])),
+            Err(BitcoinDecodeError::InvalidEncoding(_))
+        ));
+        assert_eq!(
+            Witness::from_hex(&format!("{}00", hex::encode(&buf))),
+            Err(BitcoinDecodeError::TrailingBytes(1))
+        );
+        assert!(matches!(
+            Witness::from_hex("0"),
+            Err(BitcoinDecodeError::InvalidHex(_))
+        ));
+    }
+
+    #[test]
+    fn test_serde() {
+        let witness = Witness::from_slice(&[vec![], vec![0xab, 0xcd], vec![0x01]]);
+
+        let json = serde_json::to_value(&witness).unwrap();
+        assert_eq!(json, serde_json::json!(["", "abcd", "01"]));
+        assert_eq!(serde_json::from_value::<Witness>(json).unwrap(), witness);
+
+        // Byte arrays and hex strings may be mixed.
+        let json = serde_json::json!([[], [0xab, 0xcd], "01"]);
+        assert_eq!(serde_json::from_value::<Witness>(json).unwrap(), witness);
+
+        // The former serialization of the internal fields.
+        let legacy = serde_json::json!({
+            "content": witness.content,
+            "witness_elements": witness.witness_elements,
+            "indices_start": witness.indices_start,
+        });
+        assert_eq!(serde_json::from_value::<Witness>(legacy).unwrap(), witness);
+
+        assert!(serde_json::from_value::<Witness>(serde_json::json!(["zz"])).is_err());
+        assert_eq!(
+            serde_json::from_value::<Witness>(serde_json::json!([])).unwrap(),
+            Witness::default()
+        );
+    }
+
+    #[test]
+    fn test_constructors_against_rust_bitcoin() {
+        use bitcoin::secp256k1::{Message, Secp256k1, SecretKey};
+
+        let secp = Secp256k1::new();
+        let secret_key = SecretKey::from_slice(&[0x42; 32]).unwrap();
+        let message = Message::from_digest([0x11; 32]);
+
+        let public_key = secret_key.public_key(&secp);
+        let signature = secp.sign_ecdsa(&message, &secret_key);
+        let expected = bitcoin::Witness::p2wpkh(
+            &bitcoin::ecdsa::Signature {
+                signature,
+                sighash_type: bitcoin::EcdsaSighashType::SinglePlusAnyoneCanPay,
+            },
+            &public_key,
+        );
+        let witness = Witness::p2wpkh(
+            &signature.serialize_der(),
+            EcdsaSighashType::SinglePlusAnyoneCanPay,
+            &public_key.serialize(),
+        );
+        assert_eq!(witness.to_vec(), expected.to_vec());
+
+        let keypair = bitcoin::secp256k1::Keypair::from_secret_key(&secp, &secret_key);
+        let signature = secp.sign_schnorr_no_aux_rand(&message, &keypair);
+        for (sighash_type, omni_sighash_type) in [
+            (bitcoin::TapSighashType::Default, TapSighashType::Default),
+            (bitcoin::TapSighashType::All, TapSighashType::All),
+            (
+                bitcoin::TapSighashType::NonePlusAnyoneCanPay,
+                TapSighashType::NonePlusAnyoneCanPay,
+            ),
+        ] {
+            let expected = bitcoin::Witness::p2tr_key_spend(&bitcoin::taproot::Signature {
+                signature,
+                sighash_type,
+            });
+            let witness = Witness::p2tr_key_spend(signature.as_ref(), omni_sighash_type);
+            assert_eq!(witness.to_vec(), expected.to_vec(), "{:?}", sighash_type);
+        }
+    }
+}
@@ -1,10 +1,12 @@
 use std::{
+    fmt,
     io::{BufRead, Write},
     ops,
 };
 
 use borsh::{BorshDeserialize, BorshSerialize};
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 
 use crate::bitcoin::encoding::{Decodable, Encodable};
 
@@ -13,7 +15,17 @@ use crate::bitcoin::encoding::{Decodable, Encodable};
 /// The [`Amount`] type can be used to express Bitcoin amounts that support
 /// arithmetic and conversion to various denominations.
 #[derive(
-    Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize,
+    Debug,
+    Copy,
+    Clone,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Serialize,
+    Deserialize,
+    BorshSerialize,
+    BorshDeserialize,
 )]
 pub struct Amount(u64);
 
@@ -57,6 +69,82 @@ impl Amount {
         }
     }
 
+    /// Parses a decimal amount of bitcoins, e.g. `"0.12345678"`, without going through floating
+    /// point.
+    ///
+    /// Scientific notation (e.g. `"1e-8"`) is accepted since that is how JSON serializers may
+    /// print small amounts. Fails if the amount is negative, has sub-satoshi precision or does
+    /// not fit in a u64.
+    pub fn from_btc_str(s: &str) -> Result<Self, ParseAmountError> {
+        let (mantissa, exponent) = match s.split_once(['e', 'E']) {
+            Some((mantissa, exponent)) => (
+                mantissa,
+                exponent
+                    .parse::<i64>()
+                    .map_err(|_| ParseAmountError::InvalidFormat)?,
+            ),
+            None => (s, 0),
+        };
+        let (int_part, frac_part) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+
+        let is_digits = |part: &str| part.bytes().all(|b| b.is_ascii_digit());
+        if (int_part.is_empty() && frac_part.is_empty())
+            || !is_digits(int_part)
+            || !is_digits(frac_part)
+        {
+            return Err(ParseAmountError::InvalidFormat);
+        }
+
+        // The amount in satoshis is `digits * 10^shift`.
+        let digits = format!("{}{}", int_part, frac_part);
+        let digits = digits.trim_start_matches('0');
+        let shift = 8i64
+            .saturating_add(exponent)
+            .saturating_sub(frac_part.len() as i64);
+
+        if digits.is_empty() {
+            return Ok(Self::ZERO);
+        }
+
+        let digits = if shift < 0 {
+            let precision = shift.unsigned_abs() as usize;
+            let split = digits.len().saturating_sub(precision);
+            let (significant, dropped) = digits.split_at(split);
+            if significant.is_empty() || dropped.bytes().any(|b| b != b'0') {
+                return Err(ParseAmountError::TooPrecise);
+            }
+            significant
+        } else {
+            digits
+        };
+
+        let sats = digits
+            .parse::<u64>()
+            .map_err(|_| ParseAmountError::TooLarge)?
+            .checked_mul(
+                10u64
+                    .checked_pow(
+                        u32::try_from(shift.max(0)).map_err(|_| ParseAmountError::TooLarge)?,
+                    )
+                    .ok_or(ParseAmountError::TooLarge)?,
+            )
+            .ok_or(ParseAmountError::TooLarge)?;
+
+        Ok(Self::from_sat(sats))
+    }
+
+    /// Parses the `amount` of a bitcoind RPC response, given either as a JSON number of bitcoins
+    /// (as bitcoind does) or as a decimal string.
+    ///
+    /// Unlike `value.as_f64() * 100_000_000.0`, this never loses precision.
+    pub fn from_rpc_value(value: &Value) -> Result<Self, ParseAmountError> {
+        match value {
+            Value::Number(n) => Self::from_btc_str(&n.to_string()),
+            Value::String(s) => Self::from_btc_str(s),
+            _ => Err(ParseAmountError::InvalidFormat),
+        }
+    }
+
     /// Checked addition.
     ///
     /// Returns [`None`] if overflow occurred.
@@ -72,6 +160,29 @@ impl Amount {
     }
 }
 
+/// Errors returned when parsing a decimal amount of bitcoins.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseAmountError {
+    /// The amount is not a non-negative decimal number.
+    InvalidFormat,
+    /// The amount has sub-satoshi precision.
+    TooPrecise,
+    /// The amount does not fit in a u64 number of satoshis.
+    TooLarge,
+}
+
+impl fmt::Display for ParseAmountError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidFormat => write!(f, "invalid BTC amount"),
+            Self::TooPrecise => write!(f, "BTC amount has sub-satoshi precision"),
+            Self::TooLarge => write!(f, "BTC amount is too large"),
+        }
+    }
+}
+
+impl std::error::Error for ParseAmountError {}
+
 impl Encodable for Amount {
     fn encode<W: Write + ?Sized>(&self, w: &mut W) -> Result<usize, std::io::Error> {
         self.0.encode(w)
@@ -115,4 +226,69 @@ mod tests {
         let decoded_amount = Amount::decode_from_finite_reader(&mut buf.as_slice()).unwrap();
         assert_eq!(decoded_amount, amount);
     }
+
+    #[test]
+    fn test_from_btc_str() {
+        assert_eq!(
+            Amount::from_btc_str("0.12345678").unwrap(),
+            Amount::from_sat(12_345_678)
+        );
+        assert_eq!(
+            Amount::from_btc_str("50.00000001").unwrap(),
+            Amount::from_sat(5_000_000_001)
+        );
+        assert_eq!(Amount::from_btc_str("1").unwrap(), Amount::ONE_BTC);
+        assert_eq!(
+            Amount::from_btc_str(".5").unwrap(),
+            Amount::from_sat(50_000_000)
+        );
+        assert_eq!(Amount::from_btc_str("0.000").unwrap(), Amount::ZERO);
+        assert_eq!(Amount::from_btc_str("1e-8").unwrap(), Amount::ONE_SAT);
+        assert_eq!(
+            Amount::from_btc_str("1.5E1").unwrap(),
+            Amount::from_int_btc(15)
+        );
+        assert_eq!(
+            Amount::from_btc_str("0.100000000").unwrap(),
+            Amount::from_sat(10_000_000)
+        );
+
+        assert_eq!(
+            Amount::from_btc_str("0.000000001"),
+            Err(ParseAmountError::TooPrecise)
+        );
+        assert_eq!(
+            Amount::from_btc_str("-1"),
+            Err(ParseAmountError::InvalidFormat)
+        );
+        assert!(Amount::from_btc_str("").is_err());
+        assert!(Amount::from_btc_str(".").is_err());
+        assert!(Amount::from_btc_str("1.2.3").is_err());
+        assert_eq!(
+            Amount::from_btc_str("1000000000000"),
+            Err(ParseAmountError::TooLarge)
+        );
+    }
+
+    #[test]
+    fn test_from_rpc_value() {
+        let response: Value =
+            serde_json::from_str(r#"{ "a": 0.1, "b": 0.00000001, "c": "21.3", "d": null }"#)
+                .unwrap();
+
+        // 0.1 has no exact f64 representation, multiplying by 1e8 would truncate it.
+        assert_eq!(
+            Amount::from_rpc_value(&response["a"]).unwrap(),
+            Amount::from_sat(10_000_000)
+        );
+        assert_eq!(
+            Amount::from_rpc_value(&response["b"]).unwrap(),
+            Amount::ONE_SAT
+        );
+        assert_eq!(
+            Amount::from_rpc_value(&response["c"]).unwrap(),
+            Amount::from_sat(2_130_000_000)
+        );
+        assert!(Amount::from_rpc_value(&response["d"]).is_err());
+    }
 }
@@ -3,5 +3,5 @@
 pub mod amount;
 mod tx_out;
 
-pub use self::amount::Amount;
-pub use self::tx_out::TxOut;
+pub use self::amount::{Amount, ParseAmountError};
+pub use self::tx_out::{TxOut, DEFAULT_DUST_RELAY_FEE_RATE};
@@ -29,6 +29,84 @@ pub struct TxOut {
     pub script_pubkey: ScriptBuf,
 }
 
+/// The default dust relay fee of Bitcoin Core, in sat/vB (`-dustrelayfee`).
+pub const DEFAULT_DUST_RELAY_FEE_RATE: u64 = 3;
+
+/// The size of the input spending an output, as assumed by Bitcoin Core to compute its dust
+/// threshold: the outpoint, the sequence and a script sig holding a 72-byte signature and a
+/// 33-byte public key, plus its length.
+const DUST_SPEND_SIZE: u64 = 32 + 4 + 1 + 107 + 4;
+/// Same as [`DUST_SPEND_SIZE`] for a segwit output, the signature and public key being
+/// discounted as witness data.
+const DUST_WITNESS_SPEND_SIZE: u64 = 32 + 4 + 1 + 107 / 4 + 4;
+
+impl TxOut {
+    /// Creates an output paying `value` to `script_pubkey`.
+    pub const fn new_to_script(value: Amount, script_pubkey: ScriptBuf) -> Self {
+        Self {
+            value,
+            script_pubkey,
+        }
+    }
+
+    /// Creates a zero value output carrying `data`, see [`ScriptBuf::new_op_return`].
+    pub fn op_return(data: &[u8]) -> Self {
+        Self::new_to_script(Amount::ZERO, ScriptBuf::new_op_return(data))
+    }
+
+    /// Creates a Pay-to-Anchor (P2A) output of the given value.
+    ///
+    /// Anchors let a CPFP child bump the fee of a presigned transaction, e.g. in the transaction
+    /// trees of L2 and bridge protocols. A non-zero anchor must be at least
+    /// [`P2A_DUST_THRESHOLD`](crate::bitcoin::change::P2A_DUST_THRESHOLD) to be standard.
+    pub fn p2a(value: Amount) -> Self {
+        Self {
+            value,
+            script_pubkey: ScriptBuf::new_p2a(),
+        }
+    }
+
+    /// Creates an ephemeral anchor: a zero value P2A output.
+    ///
+    /// Ephemeral dust is only relayed in zero fee version 3 (TRUC) transactions, and must be
+    /// spent by a child in the same package.
+    pub fn ephemeral_anchor() -> Self {
+        Self::p2a(Amount::ZERO)
+    }
+
+    /// Returns `true` if this output is a Pay-to-Anchor (P2A) output.
+    pub fn is_anchor(&self) -> bool {
+        self.script_pubkey.is_p2a()
+    }
+
+    /// Returns the minimum value of this output to be relayed at the dust relay fee `fee_rate`,
+    /// in sat/vB, as computed by Bitcoin Core: the fee of the output and of the input spending
+    /// it. Unspendable outputs, e.g. `OP_RETURN` ones, have no minimum.
+    ///
+    /// Nodes use [`DEFAULT_DUST_RELAY_FEE_RATE`] unless configured otherwise.
+    pub fn dust_threshold(&self, fee_rate: u64) -> Amount {
+        if self.script_pubkey.is_unspendable() {
+            return Amount::ZERO;
+        }
+        let spend_size = if self.script_pubkey.is_witness_program() {
+            DUST_WITNESS_SPEND_SIZE
+        } else {
+            DUST_SPEND_SIZE
+        };
+        let size = self.encoded_size() as u64 + spend_size;
+        Amount::from_sat(size.saturating_mul(fee_rate))
+    }
+
+    /// Returns `true` if the value of this output is below its [dust threshold](Self::dust_threshold),
+    /// in which case transactions creating it are not relayed.
+    ///
+    /// Ephemeral anchors (see [`Self::ephemeral_anchor`]) are dust, but are relayed anyway under
+    /// the conditions of the ephemeral dust policy.
+    pub fn is_dust(&self, fee_rate: u64) -> bool {
+        self.value < self.dust_threshold(fee_rate)
+    }
+}
+
 impl Encodable for TxOut {
     fn encode<W: Write + ?Sized>(&self, w: &mut W) -> Result<usize, std::io::Error> {
         let mut len = 0;
@@ -48,3 +126,68 @@ impl Decodable for TxOut {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bitcoin::change::P2A_DUST_THRESHOLD;
+    use crate::bitcoin::hash_types::{PubkeyHash, ScriptHash, WPubkeyHash, WScriptHash};
+
+    #[test]
+    fn test_ephemeral_anchor_encoding() {
+        let anchor = TxOut::ephemeral_anchor();
+
+        let mut buffer = Vec::new();
+        anchor.encode(&mut buffer).unwrap();
+
+        assert!(anchor.is_anchor());
+        assert_eq!(hex::encode(&buffer), "00000000000000000451024e73");
+        assert_eq!(
+            TxOut::decode_from_finite_reader(&mut &buffer[..]).unwrap(),
+            anchor
+        );
+    }
+
+    #[test]
+    fn test_dust_threshold_matches_core() {
+        let rate = DEFAULT_DUST_RELAY_FEE_RATE;
+        for (script_pubkey, threshold) in [
+            (
+                ScriptBuf::new_p2pkh(&PubkeyHash::from_byte_array([1; 20])),
+                546,
+            ),
+            (
+                ScriptBuf::new_p2sh(&ScriptHash::from_byte_array([1; 20])),
+                540,
+            ),
+            (
+                ScriptBuf::new_p2wpkh(&WPubkeyHash::from_byte_array([1; 20])),
+                294,
+            ),
+            (
+                ScriptBuf::new_p2wsh(&WScriptHash::from_byte_array([1; 32])),
+                330,
+            ),
+        ] {
+            let output = TxOut::new_to_script(Amount::ZERO, script_pubkey);
+            assert_eq!(output.dust_threshold(rate), Amount::from_sat(threshold));
+        }
+
+        assert_eq!(
+            TxOut::ephemeral_anchor().dust_threshold(rate),
+            P2A_DUST_THRESHOLD
+        );
+        assert_eq!(TxOut::op_return(b"omni").dust_threshold(rate), Amount::ZERO);
+    }
+
+    #[test]
+    fn test_is_dust() {
+        let script_pubkey = ScriptBuf::new_p2wpkh(&WPubkeyHash::from_byte_array([1; 20]));
+
+        assert!(TxOut::new_to_script(Amount::from_sat(293), script_pubkey.clone()).is_dust(3));
+        assert!(!TxOut::new_to_script(Amount::from_sat(294), script_pubkey.clone()).is_dust(3));
+        assert!(TxOut::new_to_script(Amount::from_sat(294), script_pubkey).is_dust(4));
+        assert!(TxOut::ephemeral_anchor().is_dust(DEFAULT_DUST_RELAY_FEE_RATE));
+        assert!(!TxOut::op_return(b"omni").is_dust(DEFAULT_DUST_RELAY_FEE_RATE));
+    }
+}
@@ -0,0 +1,208 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::{
+    script_buf::ScriptBuf,
+    tx_in::{OutPoint, Txid},
+    tx_out::Amount,
+};
+
+/// An unspent transaction output owned by a wallet.
+///
+/// This type is wallet-agnostic: its serde representation follows the field names used by
+/// bitcoind's `listunspent` RPC (`txid`, `vout`, `scriptPubKey`, `amount`, `confirmations`),
+/// so entries returned by a node can be deserialized directly. The value may be given either as
+/// `amount` (a BTC float, as returned by bitcoind) or as `value` (an integer number of satoshis).
+/// Serialization always emits `value` in satoshis to avoid floating point rounding.
+///
+/// The `amount` is parsed with [`Amount::from_rpc_value`], which should also be preferred over
+/// `amount.as_f64() * 100_000_000.0` when reading amounts from other RPC responses.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(try_from = "RpcUtxo", into = "RpcUtxo")]
+pub struct Utxo {
+    /// The reference to the output.
+    pub outpoint: OutPoint,
+    /// The value of the output.
+    pub value: Amount,
+    /// The script which must be satisfied to spend the output.
+    pub script_pubkey: ScriptBuf,
+    /// The number of confirmations of the transaction that created the output.
+    pub confirmations: u32,
+}
+
+impl Utxo {
+    pub const fn new(
+        outpoint: OutPoint,
+        value: Amount,
+        script_pubkey: ScriptBuf,
+        confirmations: u32,
+    ) -> Self {
+        Self {
+            outpoint,
+            value,
+            script_pubkey,
+            confirmations,
+        }
+    }
+}
+
+/// Wire representation of a [`Utxo`] matching the bitcoind RPC field names.
+#[derive(Serialize, Deserialize)]
+struct RpcUtxo {
+    txid: String,
+    vout: u32,
+    #[serde(rename = "scriptPubKey")]
+    script_pubkey: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    amount: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    value: Option<u64>,
+    #[serde(default)]
+    confirmations: u32,
+}
+
+impl TryFrom<RpcUtxo> for Utxo {
+    type Error = String;
+
+    fn try_from(rpc: RpcUtxo) -> Result<Self, Self::Error> {
+        let txid = Txid::from_hex(&rpc.txid).map_err(|e| format!("invalid txid: {}", e))?;
+
+        let script_pubkey = ScriptBuf::from_hex(&rpc.script_pubkey)
+            .map_err(|e| format!("invalid scriptPubKey: {}", e))?;
+
+        let value = match (rpc.value, rpc.amount) {
+            (Some(sats), _) => Amount::from_sat(sats),
+            (None, Some(btc)) => {
+                let amount = Amount::from_rpc_value(&btc).map_err(|e| format!("{}: {}", e, btc))?;
+                if amount.to_sat() > Amount::MAX_MONEY.to_sat() {
+                    return Err(format!("Amount exceeds the maximum money supply: {}", btc));
+                }
+                amount
+            }
+            (None, None) => return Err("either amount or value should be provided".to_string()),
+        };
+
+        Ok(Self {
+            outpoint: OutPoint::new(txid, rpc.vout),
+            value,
+            script_pubkey,
+            confirmations: rpc.confirmations,
+        })
+    }
+}
+
+impl From<Utxo> for RpcUtxo {
+    fn from(utxo: Utxo) -> Self {
+        Self {
+            txid: utxo.outpoint.txid.to_string(),
+            vout: utxo.outpoint.vout,
+            script_pubkey: hex::encode(&utxo.script_pubkey.0),
+            amount: None,
+            value: Some(utxo.value.to_sat()),
+            confirmations: utxo.confirmations,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TXID: &str = "2ece6cd71fee90ff613cee8f30a52c3ecc58685acf9b817b9c467b7ff199871c";
+    const SCRIPT_PUBKEY: &str = "76a914cb8a3018cf279311b148cb8d13728bd8cbe95bda88ac";
+
+    #[test]
+    fn test_deserialize_from_listunspent_entry() {
+        let json = format!(
+            r#"{{
+                "txid": "{}",
+                "vout": 1,
+                "address": "mz4HFYCMUdSdhFyFnmWGq6rYr35c6CTKf6",
+                "label": "",
+                "scriptPubKey": "{}",
+                "amount": 50.00000001,
+                "confirmations": 101,
+                "spendable": true,
+                "solvable": true,
+                "safe": true
+            }}"#,
+            TXID, SCRIPT_PUBKEY
+        );
+
+        let utxo: Utxo = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(utxo.outpoint.txid.to_string(), TXID);
+        assert_eq!(utxo.outpoint.vout, 1);
+        assert_eq!(utxo.value, Amount::from_sat(5_000_000_001));
+        assert_eq!(
+            utxo.script_pubkey,
+            ScriptBuf::from_hex(SCRIPT_PUBKEY).unwrap()
+        );
+        assert_eq!(utxo.confirmations, 101);
+    }
+
+    #[test]
+    fn test_deserialize_with_value_in_sats() {
+        let json = format!(
+            r#"{{ "txid": "{}", "vout": 0, "scriptPubKey": "{}", "value": 1000 }}"#,
+            TXID, SCRIPT_PUBKEY
+        );
+
+        let utxo: Utxo = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(utxo.value, Amount::from_sat(1000));
+        assert_eq!(utxo.confirmations, 0);
+    }
+
+    #[test]
+    fn test_deserialize_without_value_fails() {
+        let json = format!(
+            r#"{{ "txid": "{}", "vout": 0, "scriptPubKey": "{}" }}"#,
+            TXID, SCRIPT_PUBKEY
+        );
+
+        assert!(serde_json::from_str::<Utxo>(&json).is_err());
+    }
+
+    #[test]
+    fn test_deserialize_with_invalid_txid_fails() {
+        let json = format!(
+            r#"{{ "txid": "abcd", "vout": 0, "scriptPubKey": "{}", "value": 1 }}"#,
+            SCRIPT_PUBKEY
+        );
+
+        assert!(serde_json::from_str::<Utxo>(&json).is_err());
+    }
+
+    #[test]
+    fn test_serde_roundtrip() {
+        let utxo = Utxo::new(
+            OutPoint::new(Txid::from_hex(TXID).unwrap(), 3),
+            Amount::from_sat(12_345),
+            ScriptBuf::from_hex(SCRIPT_PUBKEY).unwrap(),
+            6,
+        );
+
+        let serialized = serde_json::to_string(&utxo).unwrap();
+        let deserialized: Utxo = serde_json::from_str(&serialized).unwrap();
+
+        assert!(serialized.contains("\"scriptPubKey\""));
+        assert_eq!(utxo, deserialized);
+    }
+
+    #[test]
+    fn test_borsh_roundtrip() {
+        let utxo = Utxo::new(
+            OutPoint::new(Txid::from_hex(TXID).unwrap(), 3),
+            Amount::from_sat(12_345),
+            ScriptBuf::from_hex(SCRIPT_PUBKEY).unwrap(),
+            6,
+        );
+
+        let serialized = borsh::to_vec(&utxo).unwrap();
+        let deserialized = Utxo::try_from_slice(&serialized).unwrap();
+
+        assert_eq!(utxo, deserialized);
+    }
+}
@@ -1,41 +1,53 @@
-use std::io::{self, BufRead, Write};
+use std::io::{self, BufRead, Read, Write};
 
 use borsh::{BorshDeserialize, BorshSerialize};
-use near_sdk::serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 use crate::bitcoin::encoding::{Decodable, Encodable};
 
+/// Borsh marker byte announcing a version that does not fit in a single byte.
+const BORSH_WIDE_VERSION_MARKER: u8 = 0xff;
+
 /// The transaction version.
 ///
-/// Currently, as specified by [BIP-68], only version 1 and 2 are considered standard.
+/// Currently, as specified by [BIP-68], only version 1 and 2 are considered standard. Any other
+/// value can still be represented (e.g. version 3 for the v3 transaction relay policy), use
+/// [`Version::is_standard`] to reject them where needed.
 ///
 /// [BIP-68]: https://github.com/bitcoin/bips/blob/master/bip-0068.mediawiki
-#[derive(
-    Debug, Copy, PartialEq, Eq, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize,
-)]
-#[borsh(use_discriminant = true)]
-pub enum Version {
+#[derive(Debug, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Clone)]
+pub struct Version(pub u32);
+
+impl Version {
     /// The original Bitcoin transaction version (pre-BIP-68)
-    One = 1,
+    pub const ONE: Self = Self(1);
     /// The second Bitcoin transaction version (post-BIP-68)
-    Two = 2,
-}
+    pub const TWO: Self = Self(2);
+
+    /// Creates a version from an arbitrary, possibly non-standard, value.
+    pub const fn non_standard(version: u32) -> Self {
+        Self(version)
+    }
+
+    /// Returns `true` if this is a standard transaction version (1 or 2).
+    pub const fn is_standard(&self) -> bool {
+        self.0 == Self::ONE.0 || self.0 == Self::TWO.0
+    }
 
-impl Version {
     /// Returns the hexadecimal representation of the version.
     pub fn to_hex(&self) -> String {
-        hex::encode((*self as i32).to_le_bytes())
+        hex::encode(self.0.to_le_bytes())
     }
 
     /// Serializes the version and returns the result as a `Vec<u8>`.
     pub fn to_vec(&self) -> Vec<u8> {
-        (*self as i32).to_le_bytes().to_vec()
+        self.0.to_le_bytes().to_vec()
     }
 }
 
 impl Encodable for Version {
     fn encode<W: Write + ?Sized>(&self, w: &mut W) -> Result<usize, io::Error> {
-        let bytes = (*self as i32).to_le_bytes();
+        let bytes = self.0.to_le_bytes();
         w.write_all(&bytes)?;
         Ok(bytes.len())
     }
@@ -45,15 +57,71 @@ impl Decodable for Version {
     fn decode<R: BufRead + ?Sized>(r: &mut R) -> Result<Self, std::io::Error> {
         let mut buf = [0u8; 4];
         r.read_exact(&mut buf)?;
-        let int = i32::from_le_bytes(buf);
-
-        match int {
-            1 => Ok(Self::One),
-            2 => Ok(Self::Two),
-            _ => Err(std::io::Error::new(
-                std::io::ErrorKind::InvalidData,
-                "Invalid version number",
-            )),
+        Ok(Self(u32::from_le_bytes(buf)))
+    }
+}
+
+/// Serde names of the standard versions, kept for compatibility with the former enum.
+#[derive(Serialize, Deserialize)]
+enum NamedVersion {
+    One,
+    Two,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum VersionRepr {
+    Named(NamedVersion),
+    Number(u32),
+}
+
+impl Serialize for Version {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match *self {
+            Self::ONE => NamedVersion::One.serialize(serializer),
+            Self::TWO => NamedVersion::Two.serialize(serializer),
+            Self(version) => serializer.serialize_u32(version),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Version {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Ok(match VersionRepr::deserialize(deserializer)? {
+            VersionRepr::Named(NamedVersion::One) => Self::ONE,
+            VersionRepr::Named(NamedVersion::Two) => Self::TWO,
+            VersionRepr::Number(version) => Self(version),
+        })
+    }
+}
+
+// Versions below 0xff are encoded as a single byte, matching the discriminant encoding of the
+// former enum. Larger values are prefixed with a marker byte and encoded as a u32.
+impl BorshSerialize for Version {
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<(), io::Error> {
+        match u8::try_from(self.0) {
+            Ok(version) if version != BORSH_WIDE_VERSION_MARKER => {
+                BorshSerialize::serialize(&version, writer)
+            }
+            _ => {
+                BorshSerialize::serialize(&BORSH_WIDE_VERSION_MARKER, writer)?;
+                BorshSerialize::serialize(&self.0, writer)
+            }
+        }
+    }
+}
+
+impl BorshDeserialize for Version {
+    fn deserialize_reader<R: Read>(reader: &mut R) -> Result<Self, io::Error> {
+        match u8::deserialize_reader(reader)? {
+            BORSH_WIDE_VERSION_MARKER => Ok(Self(u32::deserialize_reader(reader)?)),
+            version => Ok(Self(u32::from(version))),
         }
     }
 }
@@ -65,7 +133,7 @@ mod tests {
 
     #[test]
     fn test_version_serialization() {
-        let version = Version::One;
+        let version = Version::ONE;
         let mut buf = Vec::new();
 
         version.encode(&mut buf).unwrap();
@@ -84,12 +152,12 @@ mod tests {
         let version = Version::decode(&mut cursor).unwrap();
 
         // Check that the deserialized version is correct
-        assert_eq!(version, Version::One);
+        assert_eq!(version, Version::ONE);
     }
 
     #[test]
     fn test_version_round_trip() {
-        let version = Version::Two;
+        let version = Version::TWO;
         let mut buf = Vec::new();
         version.encode(&mut buf).unwrap();
         let mut cursor = Cursor::new(buf);
@@ -101,7 +169,7 @@ mod tests {
 
     #[test]
     fn test_version_to_vec() {
-        let version = Version::One;
+        let version = Version::ONE;
         let vec = version.to_vec();
 
         // Check that the serialized bytes are correct
@@ -110,7 +178,7 @@ mod tests {
 
     #[test]
     fn test_version_to_hex() {
-        let version = Version::One;
+        let version = Version::ONE;
         let hex = version.to_hex();
 
         // Check that the hexadecimal representation is correct
@@ -119,7 +187,7 @@ mod tests {
 
     #[test]
     fn test_version_serde_serialization() {
-        let version = Version::One;
+        let version = Version::ONE;
         let serialized = serde_json::to_string(&version).unwrap();
         let deserialized: Version = serde_json::from_str(&serialized).unwrap();
 
@@ -129,10 +197,43 @@ mod tests {
 
     #[test]
     fn test_version_borsh_serialization() {
-        let version = Version::One;
+        let version = Version::ONE;
         let buf = borsh::to_vec(&version).unwrap();
         let deserialized = Version::try_from_slice(&buf).unwrap();
 
         assert_eq!(version, deserialized);
     }
+
+    #[test]
+    fn test_non_standard_version_round_trip() {
+        let version = Version::non_standard(3);
+        let mut buf = Vec::new();
+        version.encode(&mut buf).unwrap();
+
+        assert_eq!(buf, vec![3, 0, 0, 0]);
+        assert!(!version.is_standard());
+        assert!(Version::ONE.is_standard() && Version::TWO.is_standard());
+        assert_eq!(Version::decode(&mut Cursor::new(buf)).unwrap(), version);
+    }
+
+    #[test]
+    fn test_version_serde_compatibility() {
+        assert_eq!(serde_json::to_string(&Version::TWO).unwrap(), "\"Two\"");
+        assert_eq!(serde_json::to_string(&Version(3)).unwrap(), "3");
+
+        let deserialized: Version = serde_json::from_str("\"One\"").unwrap();
+        assert_eq!(deserialized, Version::ONE);
+        let deserialized: Version = serde_json::from_str("3").unwrap();
+        assert_eq!(deserialized, Version(3));
+    }
+
+    #[test]
+    fn test_version_borsh_compatibility() {
+        assert_eq!(borsh::to_vec(&Version::TWO).unwrap(), vec![2]);
+
+        for version in [Version(3), Version(0xff), Version(u32::MAX)] {
+            let buf = borsh::to_vec(&version).unwrap();
+            assert_eq!(Version::try_from_slice(&buf).unwrap(), version);
+        }
+    }
 }
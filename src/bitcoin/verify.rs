@@ -0,0 +1,318 @@
+//! Final safety checks of signed transactions, before they are broadcast.
+//!
+//! [`BitcoinTransaction::verify_input`] checks the signature of a finalized P2PKH or P2WPKH
+//! input against the output it spends. A failure means the signature was made over another
+//! sighash (e.g. with a wrong amount or script code) or with another key, and the transaction
+//! would be rejected by the network.
+use std::fmt;
+
+use k256::ecdsa::{signature::hazmat::PrehashVerifier, Signature, VerifyingKey};
+use ripemd::Ripemd160;
+use sha2::{Digest, Sha256};
+
+use super::{
+    bitcoin_transaction::{parse_pushes, BitcoinTransaction},
+    hash_types::PubkeyHash,
+    types::{EcdsaSighashType, ScriptBuf, TxOut},
+};
+
+/// Errors returned by [`BitcoinTransaction::verify_input`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BitcoinVerifyError {
+    /// The input index is out of range.
+    InputIndexOutOfRange(usize),
+    /// The spent output is neither P2PKH nor P2WPKH.
+    UnsupportedScript,
+    /// The script sig or witness is not a signature followed by a public key, or the input
+    /// has both when only one is expected.
+    MalformedInput(String),
+    /// The public key does not hash to the public key hash of the spent output.
+    PublicKeyMismatch,
+    /// The public key is not a valid SEC1 key, or is uncompressed in a witness.
+    InvalidPublicKey,
+    /// The signature is not DER encoded or has a non-standard sighash type.
+    InvalidSignature(String),
+    /// The signature does not verify against the sighash of the input.
+    SignatureMismatch,
+}
+
+impl fmt::Display for BitcoinVerifyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InputIndexOutOfRange(index) => write!(f, "input {} does not exist", index),
+            Self::UnsupportedScript => write!(f, "only P2PKH and P2WPKH outputs are supported"),
+            Self::MalformedInput(reason) => write!(f, "malformed input: {}", reason),
+            Self::PublicKeyMismatch => {
+                write!(f, "public key does not match the spent output")
+            }
+            Self::InvalidPublicKey => write!(f, "invalid public key"),
+            Self::InvalidSignature(reason) => write!(f, "invalid signature: {}", reason),
+            Self::SignatureMismatch => write!(f, "signature does not match the sighash"),
+        }
+    }
+}
+
+impl std::error::Error for BitcoinVerifyError {}
+
+impl BitcoinTransaction {
+    /// Checks the signature of the P2PKH or P2WPKH input at `input_index`, spending `utxo`.
+    ///
+    /// The public key must match the spent output and the signature must verify against the
+    /// sighash of its sighash type. Signatures with a high `s` are rejected, as they are not
+    /// relayed by the network.
+    pub fn verify_input(&self, input_index: usize, utxo: &TxOut) -> Result<(), BitcoinVerifyError> {
+        let input = self
+            .input
+            .get(input_index)
+            .ok_or(BitcoinVerifyError::InputIndexOutOfRange(input_index))?;
+        let script_pubkey = &utxo.script_pubkey;
+
+        let (items, pubkey_hash) = if script_pubkey.is_p2pkh() {
+            if !input.witness.is_empty() {
+                return Err(BitcoinVerifyError::MalformedInput(
+                    "unexpected witness".to_string(),
+                ));
+            }
+            let items = parse_pushes(&input.script_sig.0).ok_or_else(|| {
+                BitcoinVerifyError::MalformedInput("script sig is not push only".to_string())
+            })?;
+            (items, &script_pubkey.0[3..23])
+        } else if script_pubkey.is_p2wpkh() {
+            if !input.script_sig.0.is_empty() {
+                return Err(BitcoinVerifyError::MalformedInput(
+                    "unexpected script sig".to_string(),
+                ));
+            }
+            (input.witness.iter().collect(), &script_pubkey.0[2..22])
+        } else {
+            return Err(BitcoinVerifyError::UnsupportedScript);
+        };
+
+        let [signature, public_key] = items.as_slice() else {
+            return Err(BitcoinVerifyError::MalformedInput(format!(
+                "expected a signature and a public key, got {} items",
+                items.len()
+            )));
+        };
+        if hash160(public_key) != pubkey_hash {
+            return Err(BitcoinVerifyError::PublicKeyMismatch);
+        }
+        if script_pubkey.is_p2wpkh() && public_key.len() != 33 {
+            return Err(BitcoinVerifyError::InvalidPublicKey);
+        }
+        let verifying_key = VerifyingKey::from_sec1_bytes(public_key)
+            .map_err(|_| BitcoinVerifyError::InvalidPublicKey)?;
+
+        let (&sighash_byte, der) = signature
+            .split_last()
+            .ok_or_else(|| BitcoinVerifyError::InvalidSignature("empty signature".to_string()))?;
+        let sighash_type = EcdsaSighashType::from_consensus_u8(sighash_byte).ok_or_else(|| {
+            BitcoinVerifyError::InvalidSignature(format!(
+                "non-standard sighash type {:#04x}",
+                sighash_byte
+            ))
+        })?;
+        let signature = Signature::from_der(der)
+            .map_err(|e| BitcoinVerifyError::InvalidSignature(e.to_string()))?;
+
+        let sighash = if script_pubkey.is_p2pkh() {
+            self.hash_for_signing_legacy_input(input_index, script_pubkey, sighash_type)
+        } else {
+            let pubkey_hash = PubkeyHash::from_byte_array(
+                pubkey_hash.try_into().expect("the slice is 20 bytes long"),
+            );
+            // Unlike the sighash builders, any version is accepted: version 1 transactions
+            // spending SegWit outputs are valid.
+            self.hash_for_signing_segwit_any_version(
+                sighash_type,
+                input_index,
+                &ScriptBuf::new_p2pkh(&pubkey_hash),
+                utxo.value.to_sat(),
+            )
+        };
+
+        verifying_key
+            .verify_prehash(&sighash, &signature)
+            .map_err(|_| BitcoinVerifyError::SignatureMismatch)
+    }
+}
+
+/// Returns the RIPEMD-160 of the SHA-256 of `data`, as used by public key hashes.
+fn hash160(data: &[u8]) -> [u8; 20] {
+    Ripemd160::digest(Sha256::digest(data)).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use k256::ecdsa::{signature::hazmat::PrehashSigner, SigningKey};
+
+    use super::*;
+    use crate::bitcoin::hash_types::{ScriptHash, WPubkeyHash};
+    use crate::bitcoin::types::{
+        Amount, Hash, LockTime, OutPoint, ScriptBuilder, Sequence, TxIn, Txid, Version, Witness,
+    };
+
+    fn transaction() -> BitcoinTransaction {
+        BitcoinTransaction {
+            version: Version::TWO,
+            lock_time: LockTime::from_height(0).unwrap(),
+            input: (0..2)
+                .map(|i| TxIn {
+                    previous_output: OutPoint::new(Txid(Hash::from_byte_array([i; 32])), i as u32),
+                    script_sig: ScriptBuf::default(),
+                    sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+                    witness: Witness::default(),
+                })
+                .collect(),
+            output: vec![TxOut::new_to_script(
+                Amount::from_sat(10_000),
+                ScriptBuf::new_p2wpkh(&WPubkeyHash::from_byte_array([1; 20])),
+            )],
+        }
+    }
+
+    fn signing_key() -> SigningKey {
+        SigningKey::from_slice(&[0x42; 32]).unwrap()
+    }
+
+    fn public_key(signing_key: &SigningKey) -> Vec<u8> {
+        signing_key
+            .verifying_key()
+            .to_encoded_point(true)
+            .as_bytes()
+            .to_vec()
+    }
+
+    fn sign_der(signing_key: &SigningKey, sighash: &[u8; 32]) -> Vec<u8> {
+        let signature: Signature = signing_key.sign_prehash(sighash).unwrap();
+        signature.to_der().as_bytes().to_vec()
+    }
+
+    #[test]
+    fn test_verify_p2wpkh_input() {
+        let signing_key = signing_key();
+        let public_key = public_key(&signing_key);
+        let pubkey_hash = hash160(&public_key);
+        let utxo = TxOut::new_to_script(
+            Amount::from_sat(50_000),
+            ScriptBuf::new_p2wpkh(&WPubkeyHash::from_byte_array(pubkey_hash)),
+        );
+
+        let mut tx = transaction();
+        let sighash = tx.hash_for_signing_segwit(
+            EcdsaSighashType::All,
+            1,
+            &ScriptBuf::new_p2pkh(&PubkeyHash::from_byte_array(pubkey_hash)),
+            utxo.value.to_sat(),
+        );
+        tx.input[1].witness = Witness::p2wpkh(
+            &sign_der(&signing_key, &sighash),
+            EcdsaSighashType::All,
+            &public_key,
+        );
+
+        assert_eq!(tx.verify_input(1, &utxo), Ok(()));
+
+        // Signed for another amount.
+        let other_amount =
+            TxOut::new_to_script(Amount::from_sat(60_000), utxo.script_pubkey.clone());
+        assert_eq!(
+            tx.verify_input(1, &other_amount),
+            Err(BitcoinVerifyError::SignatureMismatch)
+        );
+        assert_eq!(
+            tx.verify_input(0, &utxo),
+            Err(BitcoinVerifyError::MalformedInput(
+                "expected a signature and a public key, got 0 items".to_string()
+            ))
+        );
+        assert_eq!(
+            tx.verify_input(2, &utxo),
+            Err(BitcoinVerifyError::InputIndexOutOfRange(2))
+        );
+        let p2sh = TxOut::new_to_script(
+            utxo.value,
+            ScriptBuf::new_p2sh(&ScriptHash::from_byte_array(pubkey_hash)),
+        );
+        assert_eq!(
+            tx.verify_input(1, &p2sh),
+            Err(BitcoinVerifyError::UnsupportedScript)
+        );
+
+        // The version is part of the sighash.
+        tx.version = Version::ONE;
+        assert_eq!(
+            tx.verify_input(1, &utxo),
+            Err(BitcoinVerifyError::SignatureMismatch)
+        );
+
+        let sighash = tx.hash_for_signing_segwit_any_version(
+            EcdsaSighashType::All,
+            1,
+            &ScriptBuf::new_p2pkh(&PubkeyHash::from_byte_array(pubkey_hash)),
+            utxo.value.to_sat(),
+        );
+        tx.input[1].witness = Witness::p2wpkh(
+            &sign_der(&signing_key, &sighash),
+            EcdsaSighashType::All,
+            &public_key,
+        );
+        assert_eq!(tx.verify_input(1, &utxo), Ok(()));
+    }
+
+    #[test]
+    fn test_verify_version_1_p2wpkh_input() {
+        // The native P2WPKH example of BIP-143, a version 1 transaction.
+        let tx = BitcoinTransaction::from_bytes(&hex::decode("01000000000102fff7f7881a8099afa6940d42d1e7f6362bec38171ea3edf433541db4e4ad969f00000000494830450221008b9d1dc26ba6a9cb62127b02742fa9d754cd3bebf337f7a55d114c8e5cdd30be022040529b194ba3f9281a99f2b1c0a19c0489bc22ede944ccf4ecbab4cc618ef3ed01eeffffffef51e1b804cc89d182d279655c3aa89e815b1b309fe287d9b2b55d57b90ec68a0100000000ffffffff02202cb206000000001976a9148280b37df378db99f66f85c95a783a76ac7a6d5988ac9093510d000000001976a9143bde42dbee7e4dbe6a21b2d50ce2f0167faa815988ac000247304402203609e17b84f6a7d30c80bfa610b5b4542f32a8a0d5447a12fb1366d7f01cc44a0220573a954c4518331561406f90300e8f3358f51928d43c212a8caed02de67eebee0121025476c2e83188368da1ff3e292e7acafcdb3566bb0ad253f62fc70f07aeee635711000000").unwrap()).unwrap();
+        let utxo = TxOut::new_to_script(
+            Amount::from_sat(600_000_000),
+            ScriptBuf::from_hex("00141d0f172a0ecb48aee1be1f2687d2963ae33f71a1").unwrap(),
+        );
+
+        assert_eq!(tx.version, Version::ONE);
+        assert_eq!(tx.verify_input(1, &utxo), Ok(()));
+    }
+
+    #[test]
+    fn test_verify_p2pkh_input() {
+        let signing_key = signing_key();
+        let public_key = public_key(&signing_key);
+        let utxo = TxOut::new_to_script(
+            Amount::from_sat(50_000),
+            ScriptBuf::new_p2pkh(&PubkeyHash::from_byte_array(hash160(&public_key))),
+        );
+
+        let mut tx = transaction();
+        let sighash = tx.hash_for_signing_legacy_input(
+            0,
+            &utxo.script_pubkey,
+            EcdsaSighashType::AllPlusAnyoneCanPay,
+        );
+        let mut signature = sign_der(&signing_key, &sighash);
+        signature.push(EcdsaSighashType::AllPlusAnyoneCanPay as u8);
+        tx.input[0].script_sig = ScriptBuilder::new()
+            .push_slice(&signature)
+            .push_slice(&public_key)
+            .into_script();
+
+        assert_eq!(tx.verify_input(0, &utxo), Ok(()));
+
+        // Signed with another key than the one of the output.
+        let other_utxo = TxOut::new_to_script(
+            utxo.value,
+            ScriptBuf::new_p2pkh(&PubkeyHash::from_byte_array([7; 20])),
+        );
+        assert_eq!(
+            tx.verify_input(0, &other_utxo),
+            Err(BitcoinVerifyError::PublicKeyMismatch)
+        );
+
+        // The signature commits to the sighash type.
+        let last = tx.input[0].script_sig.0.len() - public_key.len() - 2;
+        tx.input[0].script_sig.0[last] = EcdsaSighashType::All as u8;
+        assert_eq!(
+            tx.verify_input(0, &utxo),
+            Err(BitcoinVerifyError::SignatureMismatch)
+        );
+    }
+}
@@ -0,0 +1,369 @@
+//! Deterministic JSON for cross-language test vectors.
+//!
+//! The canonical JSON of a transaction is meant to be compared byte for byte with the output
+//! of other implementations (e.g. in TypeScript or Python), next to the signing payload built
+//! from it. It follows a few conventions, so it does not depend on how serde or the chain RPCs
+//! happen to represent a value:
+//!
+//! * no whitespace, and object keys sorted by their UTF-8 bytes;
+//! * fields are named as in this crate, in snake_case;
+//! * integers are decimal strings, as they often do not fit in a JavaScript number;
+//! * binary values (addresses, keys, hashes, scripts, calldata, ...) are lowercase `0x` hex of
+//!   the bytes written to the signing payload, so e.g. Bitcoin txids are in wire order, not in
+//!   the reversed order shown by explorers;
+//! * a missing optional value is `null`;
+//! * an enum is its variant name, or an object with the variant name as single key if the
+//!   variant holds data.
+use serde_json::Value;
+
+/// A transaction with a canonical JSON representation.
+pub trait CanonicalJson {
+    /// Returns the canonical value of the transaction, see the [module docs](self).
+    fn canonical_value(&self) -> Value;
+
+    /// Returns the canonical JSON of the transaction, see the [module docs](self).
+    fn to_canonical_json(&self) -> String {
+        to_canonical_string(&self.canonical_value())
+    }
+}
+
+/// Writes a JSON value without whitespace and with the keys of its objects sorted.
+pub fn to_canonical_string(value: &Value) -> String {
+    let mut out = String::new();
+    write_value(&mut out, value);
+    out
+}
+
+fn write_value(out: &mut String, value: &Value) {
+    match value {
+        Value::Array(values) => {
+            out.push('[');
+            for (i, value) in values.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_value(out, value);
+            }
+            out.push(']');
+        }
+        Value::Object(map) => {
+            let mut entries: Vec<_> = map.iter().collect();
+            entries.sort_by(|(a, _), (b, _)| a.as_bytes().cmp(b.as_bytes()));
+
+            out.push('{');
+            for (i, (key, value)) in entries.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_string(out, key);
+                out.push(':');
+                write_value(out, value);
+            }
+            out.push('}');
+        }
+        Value::String(s) => write_string(out, s),
+        Value::Null | Value::Bool(_) | Value::Number(_) => out.push_str(&value.to_string()),
+    }
+}
+
+fn write_string(out: &mut String, s: &str) {
+    out.push_str(&Value::from(s).to_string());
+}
+
+/// Returns the canonical form of binary data, lowercase `0x` hex.
+#[cfg(any(
+    feature = "near",
+    feature = "evm",
+    feature = "bitcoin",
+    feature = "solana",
+    feature = "cosmos",
+    feature = "aptos"
+))]
+pub(crate) fn hex_value(bytes: impl AsRef<[u8]>) -> Value {
+    Value::String(format!("0x{}", hex::encode(bytes)))
+}
+
+/// Returns the canonical form of an integer, a decimal string.
+#[cfg(any(
+    feature = "near",
+    feature = "evm",
+    feature = "bitcoin",
+    feature = "cosmos",
+    feature = "aptos"
+))]
+pub(crate) fn int_value(value: impl std::fmt::Display) -> Value {
+    Value::String(value.to_string())
+}
+
+/// Returns the canonical form of an enum variant, an object with the variant name as key.
+#[cfg(any(feature = "near", feature = "aptos"))]
+pub(crate) fn variant_value(name: &str, value: Value) -> Value {
+    let mut map = serde_json::Map::new();
+    map.insert(name.to_string(), value);
+    Value::Object(map)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_to_canonical_string() {
+        let value = json!({
+            "b": [1, true, null],
+            "a": { "z": "\"quoted\"\n", "_": {} },
+            "B": [],
+        });
+
+        assert_eq!(
+            to_canonical_string(&value),
+            r#"{"B":[],"a":{"_":{},"z":"\"quoted\"\n"},"b":[1,true,null]}"#
+        );
+    }
+
+    #[cfg(any(feature = "near", feature = "aptos"))]
+    #[test]
+    fn test_values() {
+        assert_eq!(hex_value([0xab, 0x01]), json!("0xab01"));
+        assert_eq!(hex_value(b""), json!("0x"));
+        assert_eq!(int_value(u128::MAX), json!(u128::MAX.to_string()));
+        assert_eq!(
+            variant_value("Transfer", json!({})),
+            json!({ "Transfer": {} })
+        );
+    }
+
+    // Golden vectors: the canonical JSON of a transaction and the payload it signs, for other
+    // implementations to check against.
+
+    #[cfg(feature = "evm")]
+    #[test]
+    fn test_evm_vectors() {
+        use crate::evm::{
+            evm_transaction::EVMTransaction, evm_transaction_eip2930::EVMTransactionEIP2930,
+            types::AccessListItem,
+        };
+
+        let tx = EVMTransaction {
+            chain_id: 1,
+            nonce: 7,
+            to: Some([0x11; 20]),
+            value: 10_000_000_000_000_000_000,
+            input: vec![0xa9, 0x05, 0x9c, 0xbb],
+            gas_limit: 60_000,
+            max_fee_per_gas: 20_000_000_000,
+            max_priority_fee_per_gas: 1_000_000_000,
+            access_list: vec![AccessListItem::new([0x22; 20], vec![[0x33; 32]])],
+        };
+        assert_eq!(
+            tx.to_canonical_json(),
+            r#"{"access_list":[{"address":"0x2222222222222222222222222222222222222222","storage_keys":["0x3333333333333333333333333333333333333333333333333333333333333333"]}],"chain_id":"1","gas_limit":"60000","input":"0xa9059cbb","max_fee_per_gas":"20000000000","max_priority_fee_per_gas":"1000000000","nonce":"7","to":"0x1111111111111111111111111111111111111111","value":"10000000000000000000"}"#
+        );
+        assert_eq!(hex::encode(tx.build_for_signing()), "02f86d0107843b9aca008504a817c80082ea60941111111111111111111111111111111111111111888ac7230489e8000084a9059cbbf838f7942222222222222222222222222222222222222222e1a03333333333333333333333333333333333333333333333333333333333333333");
+
+        let tx = EVMTransactionEIP2930 {
+            chain_id: 1,
+            nonce: 0,
+            gas_price: 20_000_000_000,
+            gas_limit: 53_000,
+            to: None,
+            value: 0,
+            input: vec![0x60, 0x80],
+            access_list: vec![],
+        };
+        assert_eq!(
+            tx.to_canonical_json(),
+            r#"{"access_list":[],"chain_id":"1","gas_limit":"53000","gas_price":"20000000000","input":"0x6080","nonce":"0","to":null,"value":"0"}"#
+        );
+        assert_eq!(
+            hex::encode(tx.build_for_signing()),
+            "01d101808504a817c80082cf088080826080c0"
+        );
+    }
+
+    #[cfg(feature = "near")]
+    #[test]
+    fn test_near_vector() {
+        use crate::near::{
+            near_transaction::NearTransaction,
+            types::{
+                AccessKey, AccessKeyPermission, Action, AddKeyAction, BlockHash, ED25519PublicKey,
+                FunctionCallAction, FunctionCallPermission, NearGas, NearToken, PublicKey,
+                TransferAction, U128, U64,
+            },
+        };
+
+        let tx = NearTransaction {
+            signer_id: "alice.near".parse().unwrap(),
+            signer_public_key: PublicKey::ED25519(ED25519PublicKey([1u8; 32])),
+            nonce: U64(7),
+            receiver_id: "bob.near".parse().unwrap(),
+            block_hash: BlockHash([3u8; 32]),
+            actions: vec![
+                Action::Transfer(TransferAction {
+                    deposit: NearToken::from_yoctonear(1_000_000_000_000_000_000_000_000),
+                }),
+                Action::FunctionCall(Box::new(FunctionCallAction {
+                    method_name: "ping".to_string(),
+                    args: br#"{"a":1}"#.to_vec(),
+                    gas: NearGas::from_gas(30_000_000_000_000),
+                    deposit: NearToken::from_yoctonear(1),
+                })),
+                Action::AddKey(Box::new(AddKeyAction {
+                    public_key: PublicKey::ED25519(ED25519PublicKey([2u8; 32])),
+                    access_key: AccessKey {
+                        nonce: U64(0),
+                        permission: AccessKeyPermission::FunctionCall(FunctionCallPermission {
+                            allowance: Some(U128(250_000_000_000_000_000_000_000)),
+                            receiver_id: "bob.near".to_string(),
+                            method_names: vec!["ping".to_string()],
+                        }),
+                    },
+                })),
+            ],
+        };
+        assert_eq!(
+            tx.to_canonical_json(),
+            r#"{"actions":[{"Transfer":{"deposit":"1000000000000000000000000"}},{"FunctionCall":{"args":"0x7b2261223a317d","deposit":"1","gas":"30000000000000","method_name":"ping"}},{"AddKey":{"access_key":{"nonce":"0","permission":{"FunctionCall":{"allowance":"250000000000000000000000","method_names":["ping"],"receiver_id":"bob.near"}}},"public_key":"0x000202020202020202020202020202020202020202020202020202020202020202"}}],"block_hash":"0x0303030303030303030303030303030303030303030303030303030303030303","nonce":"7","receiver_id":"bob.near","signer_id":"alice.near","signer_public_key":"0x000101010101010101010101010101010101010101010101010101010101010101"}"#
+        );
+        assert_eq!(
+            hex::encode(tx.build_for_signing()),
+            "0a000000616c6963652e6e656172000101010101010101010101010101010101010101010101010101010101010101070000000000000008000000626f622e6e65617203030303030303030303030303030303030303030303030303030303030303030300000003000000a1edccce1bc2d3000000000000020400000070696e67070000007b2261223a317d00e057eb481b0000010000000000000000000000000000000500020202020202020202020202020202020202020202020202020202020202020200000000000000000001000040683bb3f386f03400000000000008000000626f622e6e656172010000000400000070696e67"
+        );
+    }
+
+    #[cfg(feature = "bitcoin")]
+    #[test]
+    fn test_bitcoin_vector() {
+        use crate::bitcoin::{
+            bitcoin_transaction::BitcoinTransaction,
+            types::{
+                Amount, LockTime, OutPoint, ScriptBuf, Sequence, TxIn, TxOut, Txid, Version,
+                Witness,
+            },
+        };
+
+        let tx = BitcoinTransaction {
+            version: Version(2),
+            lock_time: LockTime::from_height(840_000).unwrap(),
+            input: vec![TxIn {
+                previous_output: OutPoint::new(
+                    Txid::from_hex(
+                        "2ece6cd71fee90ff613cee8f30a52c3ecc58685acf9b817b9c467b7ff199871c",
+                    )
+                    .unwrap(),
+                    1,
+                ),
+                script_sig: ScriptBuf(vec![]),
+                sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+                witness: Witness::from_slice(&[vec![0x01, 0x02], vec![0x03]]),
+            }],
+            output: vec![TxOut {
+                value: Amount::from_sat(49_000),
+                script_pubkey: ScriptBuf(
+                    hex::decode("0014cb8a3018cf279311b148cb8d13728bd8cbe95bda").unwrap(),
+                ),
+            }],
+        };
+        assert_eq!(
+            tx.to_canonical_json(),
+            r#"{"input":[{"previous_output":{"txid":"0x1c8799f17f7b469c7b819bcf5a6858cc3e2ca5308fee3c61ff90ee1fd76cce2e","vout":"1"},"script_sig":"0x","sequence":"4294967293","witness":["0x0102","0x03"]}],"lock_time":"840000","output":[{"script_pubkey":"0x0014cb8a3018cf279311b148cb8d13728bd8cbe95bda","value":"49000"}],"version":"2"}"#
+        );
+        assert_eq!(hex::encode(tx.serialize()), "020000000001011c8799f17f7b469c7b819bcf5a6858cc3e2ca5308fee3c61ff90ee1fd76cce2e0100000000fdffffff0168bf000000000000160014cb8a3018cf279311b148cb8d13728bd8cbe95bda02020102010340d10c00");
+    }
+
+    #[cfg(feature = "solana")]
+    #[test]
+    fn test_solana_vector() {
+        use crate::solana::{
+            solana_transaction::SolanaTransaction,
+            types::{Hash, Pubkey},
+            utils::system_transfer,
+        };
+
+        let from = Pubkey([1u8; 32]);
+        let tx = SolanaTransaction {
+            fee_payer: from,
+            recent_blockhash: Hash([3u8; 32]),
+            instructions: vec![system_transfer(&from, &Pubkey([2u8; 32]), 1_000_000)],
+        };
+        assert_eq!(
+            tx.to_canonical_json(),
+            r#"{"fee_payer":"0x0101010101010101010101010101010101010101010101010101010101010101","instructions":[{"accounts":[{"is_signer":true,"is_writable":true,"pubkey":"0x0101010101010101010101010101010101010101010101010101010101010101"},{"is_signer":false,"is_writable":true,"pubkey":"0x0202020202020202020202020202020202020202020202020202020202020202"}],"data":"0x0200000040420f0000000000","program_id":"0x0000000000000000000000000000000000000000000000000000000000000000"}],"recent_blockhash":"0x0303030303030303030303030303030303030303030303030303030303030303"}"#
+        );
+        assert_eq!(hex::encode(tx.build_for_signing()), "01000103010101010101010101010101010101010101010101010101010101010101010102020202020202020202020202020202020202020202020202020202020202020000000000000000000000000000000000000000000000000000000000000000030303030303030303030303030303030303030303030303030303030303030301020200010c0200000040420f0000000000");
+    }
+
+    #[cfg(feature = "cosmos")]
+    #[test]
+    fn test_cosmos_vector() {
+        use crate::cosmos::{
+            cosmos_transaction::CosmosTransaction,
+            types::{Coin, Fee, MsgSend, PublicKey},
+        };
+
+        let tx = CosmosTransaction {
+            chain_id: "cosmoshub-4".to_string(),
+            account_number: 42,
+            sequence: 5,
+            messages: vec![MsgSend {
+                from_address: "cosmos1from".to_string(),
+                to_address: "cosmos1to".to_string(),
+                amount: vec![Coin::new("uatom", 1000)],
+            }
+            .to_any()],
+            memo: "memo".to_string(),
+            timeout_height: 0,
+            fee: Fee {
+                amount: vec![Coin::new("uatom", 500)],
+                gas_limit: 200_000,
+            },
+            public_key: PublicKey([2u8; 33]),
+        };
+        assert_eq!(
+            tx.to_canonical_json(),
+            r#"{"account_number":"42","chain_id":"cosmoshub-4","fee":{"amount":[{"amount":"500","denom":"uatom"}],"gas_limit":"200000"},"memo":"memo","messages":[{"type_url":"/cosmos.bank.v1beta1.MsgSend","value":"0x0a0b636f736d6f733166726f6d1209636f736d6f7331746f1a0d0a057561746f6d120431303030"}],"public_key":"0x020202020202020202020202020202020202020202020202020202020202020202","sequence":"5","timeout_height":"0"}"#
+        );
+        assert_eq!(hex::encode(tx.build_for_signing()), "0a4f0a470a1c2f636f736d6f732e62616e6b2e763162657461312e4d736753656e6412270a0b636f736d6f733166726f6d1209636f736d6f7331746f1a0d0a057561746f6d12043130303012046d656d6f12660a500a460a1f2f636f736d6f732e63727970746f2e736563703235366b312e5075624b657912230a2102020202020202020202020202020202020202020202020202020202020202020212040a020801180512120a0c0a057561746f6d120335303010c09a0c1a0b636f736d6f736875622d34202a");
+    }
+
+    #[cfg(feature = "aptos")]
+    #[test]
+    fn test_aptos_vector() {
+        use crate::aptos::{
+            aptos_transaction::AptosTransaction,
+            types::{AccountAddress, EntryFunction, ModuleId, StructTag, TypeTag},
+            utils::APTOS_FRAMEWORK_ADDRESS,
+        };
+
+        let tx = AptosTransaction {
+            sender: AccountAddress([1u8; 32]),
+            sequence_number: 7,
+            payload: EntryFunction {
+                module: ModuleId {
+                    address: APTOS_FRAMEWORK_ADDRESS,
+                    name: "coin".to_string(),
+                },
+                function: "transfer".to_string(),
+                ty_args: vec![TypeTag::Struct(Box::new(StructTag {
+                    address: APTOS_FRAMEWORK_ADDRESS,
+                    module: "aptos_coin".to_string(),
+                    name: "AptosCoin".to_string(),
+                    type_args: vec![TypeTag::Vector(Box::new(TypeTag::U8))],
+                }))],
+                args: vec![vec![2u8; 32], 1_000u64.to_le_bytes().to_vec()],
+            }
+            .into(),
+            max_gas_amount: 2_000,
+            gas_unit_price: 100,
+            expiration_timestamp_secs: 1_700_000_000,
+            chain_id: 2,
+        };
+        assert_eq!(
+            tx.to_canonical_json(),
+            r#"{"chain_id":"2","expiration_timestamp_secs":"1700000000","gas_unit_price":"100","max_gas_amount":"2000","payload":{"EntryFunction":{"args":["0x0202020202020202020202020202020202020202020202020202020202020202","0xe803000000000000"],"function":"transfer","module":{"address":"0x0000000000000000000000000000000000000000000000000000000000000001","name":"coin"},"ty_args":[{"Struct":{"address":"0x0000000000000000000000000000000000000000000000000000000000000001","module":"aptos_coin","name":"AptosCoin","type_args":[{"Vector":"U8"}]}}]}},"sender":"0x0101010101010101010101010101010101010101010101010101010101010101","sequence_number":"7"}"#
+        );
+        assert_eq!(hex::encode(tx.build_for_signing()), "b5e97db07fa0bd0e5598aa3643a9bc6f6693bddc1a9fec9e674a461eaa00b1930101010101010101010101010101010101010101010101010101010101010101070000000000000002000000000000000000000000000000000000000000000000000000000000000104636f696e087472616e73666572010700000000000000000000000000000000000000000000000000000000000000010a6170746f735f636f696e094170746f73436f696e0106010220020202020202020202020202020202020202020202020202020202020202020208e803000000000000d007000000000000640000000000000000f153650000000002");
+    }
+}
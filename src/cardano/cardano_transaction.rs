@@ -0,0 +1,213 @@
+use std::fmt;
+
+use blake2::{digest::consts::U32, Blake2b, Digest};
+use borsh::{BorshDeserialize, BorshSerialize};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    cbor::{encode_array_header, encode_map_header, encode_unsigned, NULL, TRUE},
+    types::{Ed25519PublicKey, Ed25519Signature, TransactionInput, TransactionOutput, VkeyWitness},
+};
+
+type Blake2b256 = Blake2b<U32>;
+
+/// The keys of the transaction body map.
+const INPUTS_KEY: u64 = 0;
+const OUTPUTS_KEY: u64 = 1;
+const FEE_KEY: u64 = 2;
+const TTL_KEY: u64 = 3;
+
+/// The key of the vkey witnesses in the witness set map.
+const VKEY_WITNESSES_KEY: u64 = 0;
+
+/// A Cardano transaction spending ada from key-locked outputs, serialized in CBOR.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub struct CardanoTransaction {
+    pub inputs: Vec<TransactionInput>,
+    pub outputs: Vec<TransactionOutput>,
+    /// The fee, in lovelace. It must cover the size of the signed transaction.
+    pub fee: u64,
+    /// The slot after which the transaction is no longer valid, or `None` for none.
+    pub ttl: Option<u64>,
+}
+
+impl CardanoTransaction {
+    /// Returns the CBOR encoded transaction body.
+    pub fn body_bytes(&self) -> Vec<u8> {
+        let mut buffer = Vec::new();
+        encode_map_header(&mut buffer, if self.ttl.is_some() { 4 } else { 3 });
+
+        encode_unsigned(&mut buffer, INPUTS_KEY);
+        encode_array_header(&mut buffer, self.inputs.len());
+        for input in &self.inputs {
+            input.encode(&mut buffer);
+        }
+
+        encode_unsigned(&mut buffer, OUTPUTS_KEY);
+        encode_array_header(&mut buffer, self.outputs.len());
+        for output in &self.outputs {
+            output.encode(&mut buffer);
+        }
+
+        encode_unsigned(&mut buffer, FEE_KEY);
+        encode_unsigned(&mut buffer, self.fee);
+
+        if let Some(ttl) = self.ttl {
+            encode_unsigned(&mut buffer, TTL_KEY);
+            encode_unsigned(&mut buffer, ttl);
+        }
+
+        buffer
+    }
+
+    /// Returns the Blake2b-256 hash of the body, which is also the transaction ID.
+    pub fn body_hash(&self) -> [u8; 32] {
+        Blake2b256::digest(self.body_bytes()).into()
+    }
+
+    /// Returns the message signed by the payment keys: the body hash.
+    pub fn build_for_signing(&self) -> Vec<u8> {
+        self.body_hash().to_vec()
+    }
+
+    /// Returns the CBOR encoded transaction signed by a single payment key, ready to be
+    /// submitted, e.g. with `cardano-cli transaction submit`.
+    pub fn build_with_signature(
+        &self,
+        public_key: &Ed25519PublicKey,
+        signature: &Ed25519Signature,
+    ) -> Vec<u8> {
+        self.build_with_witnesses(&[VkeyWitness::new(*public_key, *signature)])
+    }
+
+    /// Same as [`Self::build_with_signature`], for inputs locked by several payment keys.
+    pub fn build_with_witnesses(&self, witnesses: &[VkeyWitness]) -> Vec<u8> {
+        let mut buffer = Vec::new();
+        encode_array_header(&mut buffer, 4);
+        buffer.extend_from_slice(&self.body_bytes());
+
+        encode_map_header(&mut buffer, 1);
+        encode_unsigned(&mut buffer, VKEY_WITNESSES_KEY);
+        encode_array_header(&mut buffer, witnesses.len());
+        for witness in witnesses {
+            witness.encode(&mut buffer);
+        }
+
+        // The transaction is valid, and has no auxiliary data.
+        buffer.push(TRUE);
+        buffer.push(NULL);
+
+        buffer
+    }
+}
+
+/// A one-line summary of the transaction, for logs: its ID, the inputs it spends, the outputs
+/// it pays and its fee.
+impl fmt::Display for CardanoTransaction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let inputs: Vec<String> = self
+            .inputs
+            .iter()
+            .map(|input| format!("{}#{}", hex::encode(input.transaction_id), input.index))
+            .collect();
+        let outputs: Vec<String> = self
+            .outputs
+            .iter()
+            .map(|output| format!("{} lovelace to {}", output.amount, output.address))
+            .collect();
+
+        write!(
+            f,
+            "Cardano {}: spends [{}], pays [{}], fee {} lovelace",
+            hex::encode(self.body_hash()),
+            inputs.join(", "),
+            outputs.join(", "),
+            self.fee
+        )?;
+        match self.ttl {
+            Some(ttl) => write!(f, ", ttl {}", ttl),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cardano::types::CardanoAddress;
+
+    fn transfer_transaction() -> CardanoTransaction {
+        CardanoTransaction {
+            inputs: vec![TransactionInput::new([0x11; 32], 0)],
+            outputs: vec![TransactionOutput::new(
+                CardanoAddress([0x61; 29].to_vec()),
+                1_000_000,
+            )],
+            fee: 170_000,
+            ttl: Some(50_000_000),
+        }
+    }
+
+    #[test]
+    fn test_body_bytes() {
+        let mut expected = vec![0xa4, 0x00, 0x81, 0x82, 0x58, 0x20];
+        expected.extend_from_slice(&[0x11; 32]);
+        expected.extend_from_slice(&[0x00, 0x01, 0x81, 0x82, 0x58, 0x1d]);
+        expected.extend_from_slice(&[0x61; 29]);
+        expected.extend_from_slice(&[0x1a, 0x00, 0x0f, 0x42, 0x40]);
+        expected.extend_from_slice(&[0x02, 0x1a, 0x00, 0x02, 0x98, 0x10]);
+        expected.extend_from_slice(&[0x03, 0x1a, 0x02, 0xfa, 0xf0, 0x80]);
+
+        let tx = transfer_transaction();
+        assert_eq!(tx.body_bytes(), expected);
+
+        let tx = CardanoTransaction { ttl: None, ..tx };
+        assert_eq!(tx.body_bytes()[0], 0xa3);
+        assert_eq!(tx.body_bytes()[1..], expected[1..expected.len() - 6]);
+    }
+
+    #[test]
+    fn test_body_hash() {
+        let tx = transfer_transaction();
+
+        assert_eq!(
+            hex::encode(tx.body_hash()),
+            "077f66e7526a9e0dca9d94d81e41f1fb3a34e2a9a5d04341d09c7f433ac54887"
+        );
+        assert_eq!(tx.build_for_signing(), tx.body_hash());
+    }
+
+    #[test]
+    fn test_build_with_signature() {
+        let tx = transfer_transaction();
+        let signed =
+            tx.build_with_signature(&Ed25519PublicKey([3; 32]), &Ed25519Signature([4; 64]));
+
+        let body = tx.body_bytes();
+        let mut expected = vec![0x84];
+        expected.extend_from_slice(&body);
+        expected.extend_from_slice(&[0xa1, 0x00, 0x81, 0x82, 0x58, 0x20]);
+        expected.extend_from_slice(&[3; 32]);
+        expected.extend_from_slice(&[0x58, 0x40]);
+        expected.extend_from_slice(&[4; 64]);
+        expected.extend_from_slice(&[0xf5, 0xf6]);
+
+        assert_eq!(signed, expected);
+    }
+
+    #[test]
+    fn test_display_summary() {
+        let tx = transfer_transaction();
+
+        assert_eq!(
+            tx.to_string(),
+            format!(
+                "Cardano {}: spends [{}#0], pays [1000000 lovelace to {}], fee 170000 lovelace, \
+                 ttl 50000000",
+                hex::encode(tx.body_hash()),
+                "11".repeat(32),
+                "61".repeat(29)
+            )
+        );
+    }
+}
@@ -0,0 +1,119 @@
+use super::{
+    cardano_transaction::CardanoTransaction,
+    types::{TransactionInput, TransactionOutput},
+};
+use crate::transaction_builder::{BuildError, TxBuilder};
+
+pub struct CardanoTransactionBuilder {
+    pub inputs: Option<Vec<TransactionInput>>,
+    pub outputs: Option<Vec<TransactionOutput>>,
+    pub fee: Option<u64>,
+    pub ttl: Option<u64>,
+}
+
+impl Default for CardanoTransactionBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TxBuilder<CardanoTransaction> for CardanoTransactionBuilder {
+    fn build(&self) -> CardanoTransaction {
+        CardanoTransaction {
+            inputs: self.inputs.clone().expect("Missing inputs"),
+            outputs: self.outputs.clone().expect("Missing outputs"),
+            fee: self.fee.expect("Missing fee"),
+            ttl: self.ttl,
+        }
+    }
+}
+
+impl CardanoTransactionBuilder {
+    /// Same as [`TxBuilder::build`], but returns an error instead of panicking if a mandatory
+    /// field is missing.
+    pub fn try_build(&self) -> Result<CardanoTransaction, BuildError> {
+        Ok(CardanoTransaction {
+            inputs: self
+                .inputs
+                .clone()
+                .ok_or(BuildError::MissingField("inputs"))?,
+            outputs: self
+                .outputs
+                .clone()
+                .ok_or(BuildError::MissingField("outputs"))?,
+            fee: self.fee.ok_or(BuildError::MissingField("fee"))?,
+            ttl: self.ttl,
+        })
+    }
+
+    pub const fn new() -> Self {
+        Self {
+            inputs: None,
+            outputs: None,
+            fee: None,
+            ttl: None,
+        }
+    }
+
+    pub fn inputs(mut self, inputs: Vec<TransactionInput>) -> Self {
+        self.inputs = Some(inputs);
+        self
+    }
+
+    pub fn outputs(mut self, outputs: Vec<TransactionOutput>) -> Self {
+        self.outputs = Some(outputs);
+        self
+    }
+
+    pub const fn fee(mut self, fee: u64) -> Self {
+        self.fee = Some(fee);
+        self
+    }
+
+    /// The slot after which the transaction is no longer valid. Without it the transaction
+    /// stays valid forever.
+    pub const fn ttl(mut self, ttl: u64) -> Self {
+        self.ttl = Some(ttl);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cardano::types::CardanoAddress;
+    use crate::transaction_builder::TransactionBuilder;
+    use crate::types::CARDANO;
+
+    #[test]
+    fn test_build_transaction() {
+        let inputs = vec![TransactionInput::new([0x11; 32], 0)];
+        let outputs = vec![TransactionOutput::new(CardanoAddress(vec![0x61; 29]), 1)];
+
+        let tx = TransactionBuilder::new::<CARDANO>()
+            .inputs(inputs.clone())
+            .outputs(outputs.clone())
+            .fee(170_000)
+            .build();
+
+        assert_eq!(
+            tx,
+            CardanoTransaction {
+                inputs,
+                outputs,
+                fee: 170_000,
+                ttl: None,
+            }
+        );
+    }
+
+    #[test]
+    fn test_try_build_missing_field() {
+        let builder = CardanoTransactionBuilder::new().inputs(vec![]).ttl(10);
+
+        assert_eq!(
+            builder.try_build(),
+            Err(BuildError::MissingField("outputs"))
+        );
+    }
+}
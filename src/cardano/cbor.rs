@@ -0,0 +1,89 @@
+//! Minimal CBOR (RFC 8949) encoding of the values needed for transactions.
+//!
+//! Only definite lengths and the shortest header of each value are produced, as required by
+//! the canonical encoding of Cardano transaction bodies.
+
+pub(crate) const MAJOR_UNSIGNED: u8 = 0;
+pub(crate) const MAJOR_BYTES: u8 = 2;
+pub(crate) const MAJOR_ARRAY: u8 = 4;
+pub(crate) const MAJOR_MAP: u8 = 5;
+
+/// The simple value `true`.
+pub(crate) const TRUE: u8 = 0xf5;
+/// The simple value `null`.
+pub(crate) const NULL: u8 = 0xf6;
+
+/// Encodes the header of a value: its major type and its argument, an integer or a length.
+pub(crate) fn encode_header(buffer: &mut Vec<u8>, major: u8, argument: u64) {
+    let major = major << 5;
+    match argument {
+        0..=23 => buffer.push(major | argument as u8),
+        24..=0xff => buffer.extend_from_slice(&[major | 24, argument as u8]),
+        0x100..=0xffff => {
+            buffer.push(major | 25);
+            buffer.extend_from_slice(&(argument as u16).to_be_bytes());
+        }
+        0x1_0000..=0xffff_ffff => {
+            buffer.push(major | 26);
+            buffer.extend_from_slice(&(argument as u32).to_be_bytes());
+        }
+        _ => {
+            buffer.push(major | 27);
+            buffer.extend_from_slice(&argument.to_be_bytes());
+        }
+    }
+}
+
+pub(crate) fn encode_unsigned(buffer: &mut Vec<u8>, value: u64) {
+    encode_header(buffer, MAJOR_UNSIGNED, value);
+}
+
+pub(crate) fn encode_bytes(buffer: &mut Vec<u8>, bytes: &[u8]) {
+    encode_header(buffer, MAJOR_BYTES, bytes.len() as u64);
+    buffer.extend_from_slice(bytes);
+}
+
+/// Encodes the header of an array of `len` items, which must follow.
+pub(crate) fn encode_array_header(buffer: &mut Vec<u8>, len: usize) {
+    encode_header(buffer, MAJOR_ARRAY, len as u64);
+}
+
+/// Encodes the header of a map of `len` key-value pairs, which must follow.
+pub(crate) fn encode_map_header(buffer: &mut Vec<u8>, len: usize) {
+    encode_header(buffer, MAJOR_MAP, len as u64);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_unsigned() {
+        for (value, expected) in [
+            (0u64, vec![0x00]),
+            (23, vec![0x17]),
+            (24, vec![0x18, 0x18]),
+            (500, vec![0x19, 0x01, 0xf4]),
+            (1_000_000, vec![0x1a, 0x00, 0x0f, 0x42, 0x40]),
+            (
+                1_000_000_000_000,
+                vec![0x1b, 0x00, 0x00, 0x00, 0xe8, 0xd4, 0xa5, 0x10, 0x00],
+            ),
+        ] {
+            let mut buffer = vec![];
+            encode_unsigned(&mut buffer, value);
+            assert_eq!(buffer, expected, "value {}", value);
+        }
+    }
+
+    #[test]
+    fn test_encode_containers() {
+        let mut buffer = vec![];
+        encode_array_header(&mut buffer, 2);
+        encode_bytes(&mut buffer, &[0xaa; 3]);
+        encode_map_header(&mut buffer, 0);
+        buffer.push(NULL);
+
+        assert_eq!(buffer, [0x82, 0x43, 0xaa, 0xaa, 0xaa, 0xa0, 0xf6]);
+    }
+}
@@ -0,0 +1,4 @@
+pub mod cardano_transaction;
+pub mod cardano_transaction_builder;
+mod cbor;
+pub mod types;
@@ -0,0 +1,146 @@
+use std::{fmt, str::FromStr};
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use serde::{Deserialize, Serialize};
+use serde_big_array::BigArray;
+
+use super::cbor::{encode_array_header, encode_bytes, encode_unsigned};
+use crate::constants::ED25519_SIGNATURE_LENGTH;
+
+/// An output of a previous transaction, spent by an input.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+    BorshSerialize,
+    BorshDeserialize,
+)]
+pub struct TransactionInput {
+    /// The ID of the transaction, the hash of its body, in the order it is displayed.
+    pub transaction_id: [u8; 32],
+    pub index: u64,
+}
+
+impl TransactionInput {
+    pub const fn new(transaction_id: [u8; 32], index: u64) -> Self {
+        Self {
+            transaction_id,
+            index,
+        }
+    }
+
+    pub(crate) fn encode(&self, buffer: &mut Vec<u8>) {
+        encode_array_header(buffer, 2);
+        encode_bytes(buffer, &self.transaction_id);
+        encode_unsigned(buffer, self.index);
+    }
+}
+
+/// The binary form of an address, as found in the payload of its bech32 form (`addr1...`).
+///
+/// It parses from hex, the form returned for addresses by `cardano-cli` and most APIs.
+#[derive(
+    Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, BorshSerialize, BorshDeserialize,
+)]
+pub struct CardanoAddress(pub Vec<u8>);
+
+impl FromStr for CardanoAddress {
+    type Err = hex::FromHexError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        hex::decode(s).map(Self)
+    }
+}
+
+impl fmt::Display for CardanoAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", hex::encode(&self.0))
+    }
+}
+
+/// An output holding only ada.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub struct TransactionOutput {
+    pub address: CardanoAddress,
+    /// The amount, in lovelace (10^-6 ada).
+    pub amount: u64,
+}
+
+impl TransactionOutput {
+    pub const fn new(address: CardanoAddress, amount: u64) -> Self {
+        Self { address, amount }
+    }
+
+    /// Encodes the output in the legacy array form `[address, amount]`, still accepted by
+    /// the Babbage era for outputs without datum nor script reference.
+    pub(crate) fn encode(&self, buffer: &mut Vec<u8>) {
+        encode_array_header(buffer, 2);
+        encode_bytes(buffer, &self.address.0);
+        encode_unsigned(buffer, self.amount);
+    }
+}
+
+/// The Ed25519 public key of a payment key.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize,
+)]
+pub struct Ed25519PublicKey(pub [u8; 32]);
+
+/// An Ed25519 signature of the transaction body hash.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize,
+)]
+pub struct Ed25519Signature(#[serde(with = "BigArray")] pub [u8; ED25519_SIGNATURE_LENGTH]);
+
+/// The signature of the transaction by a payment key, authorizing the inputs it locks.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize,
+)]
+pub struct VkeyWitness {
+    pub vkey: Ed25519PublicKey,
+    pub signature: Ed25519Signature,
+}
+
+impl VkeyWitness {
+    pub const fn new(vkey: Ed25519PublicKey, signature: Ed25519Signature) -> Self {
+        Self { vkey, signature }
+    }
+
+    pub(crate) fn encode(&self, buffer: &mut Vec<u8>) {
+        encode_array_header(buffer, 2);
+        encode_bytes(buffer, &self.vkey.0);
+        encode_bytes(buffer, &self.signature.0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_input_and_output() {
+        let mut buffer = vec![];
+        TransactionInput::new([0x11; 32], 1).encode(&mut buffer);
+        assert_eq!(
+            buffer,
+            [&[0x82, 0x58, 0x20][..], &[0x11; 32], &[0x01]].concat()
+        );
+
+        let address: CardanoAddress = "61aabbcc".parse().unwrap();
+        assert_eq!(address.to_string(), "61aabbcc");
+
+        let mut buffer = vec![];
+        TransactionOutput::new(address, 2_000_000).encode(&mut buffer);
+        assert_eq!(
+            buffer,
+            [0x82, 0x44, 0x61, 0xaa, 0xbb, 0xcc, 0x1a, 0x00, 0x1e, 0x84, 0x80]
+        );
+    }
+}
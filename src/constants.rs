@@ -1,5 +1,9 @@
+/// EIP-2930 transaction type
+pub const EIP_2930_TYPE: u8 = 0x01;
 /// EIP-1559 transaction type
 pub const EIP_1559_TYPE: u8 = 0x02;
+/// EIP-4844 transaction type
+pub const EIP_4844_TYPE: u8 = 0x03;
 /// Length of an Ed25519 public key
 pub const ED25519_PUBLIC_KEY_LENGTH: usize = 32;
 /// Length of a secp256k1 public key
@@ -10,3 +14,8 @@ pub const SECP256K1_SIGNATURE_LENGTH: usize = 65;
 pub const COMPONENT_SIZE: usize = 32;
 /// Length of an Ed25519 signature
 pub const ED25519_SIGNATURE_LENGTH: usize = COMPONENT_SIZE * 2;
+/// Half the order of secp256k1, the largest `s` of a low-S signature.
+pub const SECP256K1_HALF_ORDER: [u8; 32] = [
+    0x7f, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+    0x5d, 0x57, 0x6e, 0x73, 0x57, 0xa4, 0x50, 0x1d, 0xdf, 0xe9, 0x2f, 0x46, 0x68, 0x1b, 0x20, 0xa0,
+];
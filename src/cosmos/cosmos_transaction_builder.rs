@@ -0,0 +1,175 @@
+use super::{
+    cosmos_transaction::CosmosTransaction,
+    types::{Any, Fee, PublicKey},
+};
+use crate::transaction_builder::{BuildError, TxBuilder};
+
+pub struct CosmosTransactionBuilder {
+    pub chain_id: Option<String>,
+    pub account_number: Option<u64>,
+    pub sequence: Option<u64>,
+    pub messages: Option<Vec<Any>>,
+    pub memo: Option<String>,
+    pub timeout_height: Option<u64>,
+    pub fee: Option<Fee>,
+    pub public_key: Option<PublicKey>,
+}
+
+impl Default for CosmosTransactionBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TxBuilder<CosmosTransaction> for CosmosTransactionBuilder {
+    fn build(&self) -> CosmosTransaction {
+        CosmosTransaction {
+            chain_id: self.chain_id.clone().expect("Missing chain ID"),
+            account_number: self.account_number.expect("Missing account number"),
+            sequence: self.sequence.expect("Missing sequence"),
+            messages: self.messages.clone().expect("Missing messages"),
+            memo: self.memo.clone().unwrap_or_default(),
+            timeout_height: self.timeout_height.unwrap_or_default(),
+            fee: self.fee.clone().expect("Missing fee"),
+            public_key: self.public_key.expect("Missing public key"),
+        }
+    }
+}
+
+impl CosmosTransactionBuilder {
+    /// Same as [`TxBuilder::build`], but returns an error instead of panicking if a mandatory
+    /// field is missing.
+    pub fn try_build(&self) -> Result<CosmosTransaction, BuildError> {
+        Ok(CosmosTransaction {
+            chain_id: self
+                .chain_id
+                .clone()
+                .ok_or(BuildError::MissingField("chain_id"))?,
+            account_number: self
+                .account_number
+                .ok_or(BuildError::MissingField("account_number"))?,
+            sequence: self.sequence.ok_or(BuildError::MissingField("sequence"))?,
+            messages: self
+                .messages
+                .clone()
+                .ok_or(BuildError::MissingField("messages"))?,
+            memo: self.memo.clone().unwrap_or_default(),
+            timeout_height: self.timeout_height.unwrap_or_default(),
+            fee: self.fee.clone().ok_or(BuildError::MissingField("fee"))?,
+            public_key: self
+                .public_key
+                .ok_or(BuildError::MissingField("public_key"))?,
+        })
+    }
+
+    pub const fn new() -> Self {
+        Self {
+            chain_id: None,
+            account_number: None,
+            sequence: None,
+            messages: None,
+            memo: None,
+            timeout_height: None,
+            fee: None,
+            public_key: None,
+        }
+    }
+
+    pub fn chain_id(mut self, chain_id: &str) -> Self {
+        self.chain_id = Some(chain_id.to_string());
+        self
+    }
+
+    pub const fn account_number(mut self, account_number: u64) -> Self {
+        self.account_number = Some(account_number);
+        self
+    }
+
+    pub const fn sequence(mut self, sequence: u64) -> Self {
+        self.sequence = Some(sequence);
+        self
+    }
+
+    pub fn messages(mut self, messages: Vec<Any>) -> Self {
+        self.messages = Some(messages);
+        self
+    }
+
+    pub fn memo(mut self, memo: &str) -> Self {
+        self.memo = Some(memo.to_string());
+        self
+    }
+
+    pub const fn timeout_height(mut self, timeout_height: u64) -> Self {
+        self.timeout_height = Some(timeout_height);
+        self
+    }
+
+    pub fn fee(mut self, fee: Fee) -> Self {
+        self.fee = Some(fee);
+        self
+    }
+
+    pub const fn public_key(mut self, public_key: PublicKey) -> Self {
+        self.public_key = Some(public_key);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cosmos::types::{Coin, MsgSend};
+    use crate::transaction_builder::TransactionBuilder;
+    use crate::types::COSMOS;
+
+    #[test]
+    fn test_cosmos_transaction_builder_typed() {
+        let messages = vec![MsgSend {
+            from_address: "cosmos1from".to_string(),
+            to_address: "cosmos1to".to_string(),
+            amount: vec![Coin::new("uatom", 1)],
+        }
+        .to_any()];
+        let fee = Fee {
+            amount: vec![Coin::new("uatom", 500)],
+            gas_limit: 200_000,
+        };
+
+        let tx = TransactionBuilder::new::<COSMOS>()
+            .chain_id("cosmoshub-4")
+            .account_number(42)
+            .sequence(5)
+            .messages(messages.clone())
+            .fee(fee.clone())
+            .public_key(PublicKey([2u8; 33]))
+            .build();
+
+        assert_eq!(
+            tx,
+            CosmosTransaction {
+                chain_id: "cosmoshub-4".to_string(),
+                account_number: 42,
+                sequence: 5,
+                messages,
+                memo: String::new(),
+                timeout_height: 0,
+                fee,
+                public_key: PublicKey([2u8; 33]),
+            }
+        );
+    }
+
+    #[test]
+    fn test_try_build_missing_field() {
+        let result = CosmosTransactionBuilder::new()
+            .chain_id("cosmoshub-4")
+            .account_number(42)
+            .sequence(5)
+            .messages(vec![])
+            .public_key(PublicKey([2u8; 33]))
+            .try_build();
+
+        assert_eq!(result, Err(BuildError::MissingField("fee")));
+    }
+}
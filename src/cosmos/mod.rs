@@ -0,0 +1,3 @@
+pub mod cosmos_transaction;
+pub mod cosmos_transaction_builder;
+pub mod types;
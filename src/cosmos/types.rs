@@ -0,0 +1,128 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use serde::{Deserialize, Serialize};
+use serde_big_array::BigArray;
+
+use crate::protobuf::{
+    encode_bytes_field, encode_message_field, encode_string_field, encode_uint64_field,
+};
+
+/// Length of a compressed secp256k1 public key.
+pub const SECP256K1_COMPRESSED_PUBLIC_KEY_LENGTH: usize = 33;
+/// Length of a secp256k1 signature in the `r || s` form used by the Cosmos SDK.
+pub const SECP256K1_COMPACT_SIGNATURE_LENGTH: usize = 64;
+
+/// Type URL of `cosmos.crypto.secp256k1.PubKey`.
+pub const SECP256K1_PUBKEY_TYPE_URL: &str = "/cosmos.crypto.secp256k1.PubKey";
+/// Type URL of `cosmos.bank.v1beta1.MsgSend`.
+pub const MSG_SEND_TYPE_URL: &str = "/cosmos.bank.v1beta1.MsgSend";
+
+/// An amount of a given denomination, e.g. `1000 uatom`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub struct Coin {
+    pub denom: String,
+    pub amount: u128,
+}
+
+impl Coin {
+    pub fn new(denom: &str, amount: u128) -> Self {
+        Self {
+            denom: denom.to_string(),
+            amount,
+        }
+    }
+
+    pub(crate) fn encode(&self) -> Vec<u8> {
+        let mut buffer = Vec::new();
+        encode_string_field(&mut buffer, 1, &self.denom);
+        // Amounts are arbitrary precision integers, serialized as decimal strings.
+        encode_string_field(&mut buffer, 2, &self.amount.to_string());
+        buffer
+    }
+}
+
+/// A protobuf message along with the URL identifying its type (`google.protobuf.Any`).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub struct Any {
+    pub type_url: String,
+    /// The encoded message.
+    pub value: Vec<u8>,
+}
+
+impl Any {
+    pub(crate) fn encode(&self) -> Vec<u8> {
+        let mut buffer = Vec::new();
+        encode_string_field(&mut buffer, 1, &self.type_url);
+        encode_bytes_field(&mut buffer, 2, &self.value);
+        buffer
+    }
+}
+
+/// The fee paid by the signer, and the gas limit it pays for.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub struct Fee {
+    pub amount: Vec<Coin>,
+    pub gas_limit: u64,
+}
+
+impl Fee {
+    pub(crate) fn encode(&self) -> Vec<u8> {
+        let mut buffer = Vec::new();
+        for coin in &self.amount {
+            encode_message_field(&mut buffer, 1, &coin.encode());
+        }
+        encode_uint64_field(&mut buffer, 2, self.gas_limit);
+        buffer
+    }
+}
+
+/// A compressed secp256k1 public key.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize,
+)]
+pub struct PublicKey(#[serde(with = "BigArray")] pub [u8; SECP256K1_COMPRESSED_PUBLIC_KEY_LENGTH]);
+
+impl PublicKey {
+    /// Returns the key wrapped in an [`Any`], as expected in `SignerInfo`.
+    pub fn to_any(&self) -> Any {
+        let mut value = Vec::new();
+        encode_bytes_field(&mut value, 1, &self.0);
+
+        Any {
+            type_url: SECP256K1_PUBKEY_TYPE_URL.to_string(),
+            value,
+        }
+    }
+}
+
+/// A secp256k1 signature of the `SignDoc` hash, as the concatenation of `r` and `s`.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize,
+)]
+pub struct Signature(#[serde(with = "BigArray")] pub [u8; SECP256K1_COMPACT_SIGNATURE_LENGTH]);
+
+/// A bank transfer (`cosmos.bank.v1beta1.MsgSend`).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub struct MsgSend {
+    /// Bech32 address of the sender, which must be the signer.
+    pub from_address: String,
+    /// Bech32 address of the recipient.
+    pub to_address: String,
+    pub amount: Vec<Coin>,
+}
+
+impl MsgSend {
+    /// Returns the message wrapped in an [`Any`], ready to be added to a transaction.
+    pub fn to_any(&self) -> Any {
+        let mut value = Vec::new();
+        encode_string_field(&mut value, 1, &self.from_address);
+        encode_string_field(&mut value, 2, &self.to_address);
+        for coin in &self.amount {
+            encode_message_field(&mut value, 3, &coin.encode());
+        }
+
+        Any {
+            type_url: MSG_SEND_TYPE_URL.to_string(),
+            value,
+        }
+    }
+}
@@ -0,0 +1,214 @@
+//! Minimal in-memory signers for producing valid signatures over omni payloads in tests.
+//!
+//! These signers keep raw private keys in memory and make no attempt at side-channel
+//! resistance. They exist so that consumer crates can write unit tests without pulling
+//! `near-crypto`, alloy signers and `secp256k1` at the same time. **Do not use them in
+//! production.**
+use ed25519_dalek::Signer;
+use k256::ecdsa::SigningKey;
+use sha2::{Digest, Sha256};
+
+#[cfg(feature = "bitcoin")]
+use crate::bitcoin::types::EcdsaSighashType;
+#[cfg(feature = "evm")]
+use crate::evm::{evm_transaction::EVMTransaction, types::Signature as EVMSignature};
+#[cfg(feature = "near")]
+use crate::near::{
+    near_transaction::NearTransaction,
+    types::{
+        ED25519PublicKey, ED25519Signature, PublicKey as NearPublicKey, Secp256K1PublicKey,
+        Secp256K1Signature, Signature as NearSignature,
+    },
+};
+
+/// An ed25519 signer holding its private key in memory.
+pub struct Ed25519DevSigner {
+    signing_key: ed25519_dalek::SigningKey,
+}
+
+impl Ed25519DevSigner {
+    /// Creates a signer from a 32-byte seed.
+    pub fn from_seed(seed: [u8; 32]) -> Self {
+        Self {
+            signing_key: ed25519_dalek::SigningKey::from_bytes(&seed),
+        }
+    }
+
+    /// Returns the 32-byte public key.
+    pub fn public_key_bytes(&self) -> [u8; 32] {
+        self.signing_key.verifying_key().to_bytes()
+    }
+
+    /// Signs an arbitrary message and returns the 64-byte signature.
+    pub fn sign(&self, message: &[u8]) -> [u8; 64] {
+        self.signing_key.sign(message).to_bytes()
+    }
+
+    /// Returns the public key in the NEAR format.
+    #[cfg(feature = "near")]
+    pub fn near_public_key(&self) -> NearPublicKey {
+        NearPublicKey::ED25519(ED25519PublicKey(self.public_key_bytes()))
+    }
+
+    /// Signs a NEAR transaction the same way `near-crypto` does, i.e. over the SHA-256 hash of
+    /// its borsh encoding.
+    #[cfg(feature = "near")]
+    pub fn sign_near_transaction(&self, transaction: &NearTransaction) -> NearSignature {
+        let hash = transaction.hash_for_signing();
+        let signature = self.sign(&hash);
+
+        NearSignature::ED25519(ED25519Signature {
+            r: signature[..32].try_into().unwrap(),
+            s: signature[32..].try_into().unwrap(),
+        })
+    }
+}
+
+/// A secp256k1 signer holding its private key in memory.
+pub struct Secp256k1DevSigner {
+    signing_key: SigningKey,
+}
+
+impl Secp256k1DevSigner {
+    /// Creates a signer from a 32-byte secret key.
+    pub fn from_secret_bytes(secret: &[u8; 32]) -> Result<Self, String> {
+        let signing_key = SigningKey::from_slice(secret).map_err(|e| e.to_string())?;
+        Ok(Self { signing_key })
+    }
+
+    /// Returns the 64-byte uncompressed public key, without the `0x04` prefix.
+    pub fn public_key_uncompressed(&self) -> [u8; 64] {
+        let point = self.signing_key.verifying_key().to_encoded_point(false);
+        point.as_bytes()[1..].try_into().unwrap()
+    }
+
+    /// Returns the 33-byte SEC1 compressed public key.
+    pub fn public_key_compressed(&self) -> [u8; 33] {
+        let point = self.signing_key.verifying_key().to_encoded_point(true);
+        point.as_bytes().try_into().unwrap()
+    }
+
+    /// Signs a 32-byte digest and returns the 64-byte `r || s` signature (with low `s`)
+    /// together with the recovery id.
+    pub fn sign_prehash(&self, digest: &[u8; 32]) -> ([u8; 64], u8) {
+        let (signature, recovery_id) = self
+            .signing_key
+            .sign_prehash_recoverable(digest)
+            .expect("signing a 32-byte digest should not fail");
+
+        (signature.to_bytes().into(), recovery_id.to_byte())
+    }
+
+    /// Returns the public key in the NEAR format.
+    #[cfg(feature = "near")]
+    pub fn near_public_key(&self) -> NearPublicKey {
+        NearPublicKey::SECP256K1(Secp256K1PublicKey(self.public_key_uncompressed()))
+    }
+
+    /// Signs a NEAR transaction the same way `near-crypto` does, i.e. over the SHA-256 hash of
+    /// its borsh encoding, returning `r || s || v`.
+    #[cfg(feature = "near")]
+    pub fn sign_near_transaction(&self, transaction: &NearTransaction) -> NearSignature {
+        let hash = transaction.hash_for_signing();
+        let (signature, recovery_id) = self.sign_prehash(&hash);
+
+        let mut bytes = [0u8; 65];
+        bytes[..64].copy_from_slice(&signature);
+        bytes[64] = recovery_id;
+
+        NearSignature::SECP256K1(Secp256K1Signature(bytes))
+    }
+
+    /// Signs an EVM transaction over the keccak256 hash of its signing payload.
+    ///
+    /// `v` is the y-parity (0 or 1), as expected by typed transactions.
+    #[cfg(feature = "evm")]
+    pub fn sign_evm_transaction(&self, transaction: &EVMTransaction) -> EVMSignature {
+        let hash = transaction.hash_for_signing();
+        let (signature, recovery_id) = self.sign_prehash(&hash);
+
+        EVMSignature {
+            v: u64::from(recovery_id),
+            r: signature[..32].to_vec(),
+            s: signature[32..].to_vec(),
+        }
+    }
+
+    /// Signs a Bitcoin sighash preimage (as returned by `build_for_signing_legacy` or
+    /// `build_for_signing_segwit`) and returns the DER encoded signature followed by the
+    /// sighash type byte, ready to be placed in a script sig or witness.
+    #[cfg(feature = "bitcoin")]
+    pub fn sign_bitcoin_preimage(
+        &self,
+        preimage: &[u8],
+        sighash_type: EcdsaSighashType,
+    ) -> Vec<u8> {
+        let digest = sha256(&sha256(preimage));
+        let (signature, _) = self
+            .signing_key
+            .sign_prehash_recoverable(&digest)
+            .expect("signing a 32-byte digest should not fail");
+
+        let mut bytes = signature.to_der().as_bytes().to_vec();
+        bytes.push(sighash_type as u8);
+        bytes
+    }
+}
+
+fn sha256(data: &[u8]) -> [u8; 32] {
+    Sha256::digest(data).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_dalek::Verifier;
+    use k256::ecdsa::{RecoveryId, Signature as K256Signature, VerifyingKey};
+
+    #[test]
+    fn test_ed25519_sign_and_verify() {
+        let signer = Ed25519DevSigner::from_seed([7u8; 32]);
+        let signature = signer.sign(b"omni");
+
+        let verifying_key = ed25519_dalek::VerifyingKey::from_bytes(&signer.public_key_bytes())
+            .expect("public key should be valid");
+
+        assert!(verifying_key
+            .verify(b"omni", &ed25519_dalek::Signature::from_bytes(&signature))
+            .is_ok());
+    }
+
+    #[test]
+    fn test_secp256k1_sign_prehash_recovers_public_key() {
+        let signer = Secp256k1DevSigner::from_secret_bytes(&[7u8; 32]).unwrap();
+        let digest = sha256(b"omni");
+        let (signature, recovery_id) = signer.sign_prehash(&digest);
+
+        let recovered = VerifyingKey::recover_from_prehash(
+            &digest,
+            &K256Signature::from_slice(&signature).unwrap(),
+            RecoveryId::from_byte(recovery_id).unwrap(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            &recovered.to_encoded_point(false).as_bytes()[1..],
+            signer.public_key_uncompressed().as_slice()
+        );
+    }
+
+    #[test]
+    fn test_secp256k1_invalid_secret() {
+        assert!(Secp256k1DevSigner::from_secret_bytes(&[0u8; 32]).is_err());
+    }
+
+    #[cfg(feature = "bitcoin")]
+    #[test]
+    fn test_bitcoin_signature_has_sighash_suffix() {
+        let signer = Secp256k1DevSigner::from_secret_bytes(&[7u8; 32]).unwrap();
+        let signature = signer.sign_bitcoin_preimage(b"preimage", EcdsaSighashType::All);
+
+        assert_eq!(signature[0], 0x30);
+        assert_eq!(*signature.last().unwrap(), EcdsaSighashType::All as u8);
+    }
+}
@@ -0,0 +1,212 @@
+//! Pure encoding functions for signed transactions.
+//!
+//! Unlike `build_with_script_sig`/`build_with_witness`, these functions only borrow the
+//! transaction and the signatures, so a relayer can share transactions (e.g. behind an `Arc`)
+//! and encode many of them in parallel, for instance from `tokio::task::spawn_blocking`,
+//! without cloning them first.
+#[cfg(feature = "bitcoin")]
+use crate::bitcoin::{
+    bitcoin_transaction::BitcoinTransaction,
+    constants::{SEGWIT_FLAG, SEGWIT_MARKER},
+    encoding::{utils::VarInt, Encodable, ToU64},
+    types::{ScriptBuf, Witness},
+};
+#[cfg(feature = "evm")]
+use crate::evm::{evm_transaction::EVMTransaction, types::Signature as EVMSignature};
+#[cfg(feature = "near")]
+use crate::near::{near_transaction::NearTransaction, types::Signature as NearSignature};
+
+/// A transaction which can be encoded together with its signatures.
+pub trait EncodeSigned {
+    /// The signatures needed to encode the signed transaction.
+    type Signatures: ?Sized;
+
+    /// Encodes the transaction with the given signatures, ready to be broadcast.
+    fn encode_signed(&self, signatures: &Self::Signatures) -> Vec<u8>;
+}
+
+/// Encodes a transaction with the given signatures, ready to be broadcast.
+pub fn encode_signed<T: EncodeSigned + ?Sized>(tx: &T, signatures: &T::Signatures) -> Vec<u8> {
+    tx.encode_signed(signatures)
+}
+
+#[cfg(feature = "near")]
+impl EncodeSigned for NearTransaction {
+    type Signatures = NearSignature;
+
+    fn encode_signed(&self, signature: &NearSignature) -> Vec<u8> {
+        // A signed transaction is the transaction followed by its signature.
+        let mut buffer = self.build_for_signing();
+        borsh::to_writer(&mut buffer, signature).expect("failed to serialize NEAR signature");
+        buffer
+    }
+}
+
+#[cfg(feature = "evm")]
+impl EncodeSigned for EVMTransaction {
+    type Signatures = EVMSignature;
+
+    fn encode_signed(&self, signature: &EVMSignature) -> Vec<u8> {
+        self.build_with_signature(signature)
+    }
+}
+
+/// The unlocking data of a Bitcoin input.
+#[cfg(feature = "bitcoin")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InputUnlock {
+    ScriptSig(ScriptBuf),
+    Witness(Witness),
+}
+
+#[cfg(feature = "bitcoin")]
+impl EncodeSigned for BitcoinTransaction {
+    /// The unlocking data of the signed inputs, by input index.
+    ///
+    /// Inputs which are not listed keep the script sig and witness they already have.
+    type Signatures = [(usize, InputUnlock)];
+
+    fn encode_signed(&self, unlocks: &[(usize, InputUnlock)]) -> Vec<u8> {
+        let script_sig = |index: usize| {
+            unlocks
+                .iter()
+                .find_map(|(i, unlock)| match unlock {
+                    InputUnlock::ScriptSig(script_sig) if *i == index => Some(script_sig),
+                    _ => None,
+                })
+                .unwrap_or(&self.input[index].script_sig)
+        };
+        let witness = |index: usize| {
+            unlocks
+                .iter()
+                .find_map(|(i, unlock)| match unlock {
+                    InputUnlock::Witness(witness) if *i == index => Some(witness),
+                    _ => None,
+                })
+                .unwrap_or(&self.input[index].witness)
+        };
+
+        // Same rules as the regular encoding: BIP-144 serialization if any input has a witness
+        // or if there are no inputs.
+        let uses_segwit_serialization =
+            self.input.is_empty() || (0..self.input.len()).any(|i| !witness(i).is_empty());
+
+        let mut buffer = Vec::new();
+        let w = &mut buffer;
+        let expect = "writing to a vec should not fail";
+
+        self.version.encode(w).expect(expect);
+        if uses_segwit_serialization {
+            SEGWIT_MARKER.encode(w).expect(expect);
+            SEGWIT_FLAG.encode(w).expect(expect);
+        }
+
+        VarInt(self.input.len().to_u64()).encode(w).expect(expect);
+        for (index, input) in self.input.iter().enumerate() {
+            input.previous_output.encode(w).expect(expect);
+            script_sig(index).encode(w).expect(expect);
+            input.sequence.encode(w).expect(expect);
+        }
+        self.output.encode(w).expect(expect);
+
+        if uses_segwit_serialization {
+            for index in 0..self.input.len() {
+                witness(index).encode(w).expect(expect);
+            }
+        }
+        self.lock_time.encode(w).expect(expect);
+
+        buffer
+    }
+}
+
+#[cfg(all(test, feature = "bitcoin"))]
+mod bitcoin_tests {
+    use super::*;
+    use crate::bitcoin::types::{
+        Amount, Hash, LockTime, OutPoint, Sequence, TransactionType, TxIn, TxOut, Txid, Version,
+    };
+
+    fn transaction() -> BitcoinTransaction {
+        let input = |vout| TxIn {
+            previous_output: OutPoint::new(Txid(Hash::all_zeros()), vout),
+            script_sig: ScriptBuf::default(),
+            sequence: Sequence::MAX,
+            witness: Witness::default(),
+        };
+
+        BitcoinTransaction {
+            version: Version::TWO,
+            lock_time: LockTime::from_height(0).unwrap(),
+            input: vec![input(0), input(1)],
+            output: vec![TxOut {
+                value: Amount::from_sat(1000),
+                script_pubkey: ScriptBuf(vec![0x51]),
+            }],
+        }
+    }
+
+    #[test]
+    fn test_encode_signed_matches_build_with_witness() {
+        let tx = transaction();
+        let items = vec![vec![1u8; 72], vec![2u8; 33]];
+
+        let encoded = encode_signed(
+            &tx,
+            &[(1, InputUnlock::Witness(Witness::from_slice(&items)))],
+        );
+
+        let mut expected = tx.clone();
+        assert_eq!(
+            encoded,
+            expected.build_with_witness(1, items, TransactionType::P2WPKH)
+        );
+    }
+
+    #[test]
+    fn test_encode_signed_matches_build_with_script_sig() {
+        let tx = transaction();
+        let script_sig = ScriptBuf(vec![0x01, 0x02]);
+
+        let encoded = encode_signed(&tx, &[(0, InputUnlock::ScriptSig(script_sig.clone()))]);
+
+        let mut expected = tx.clone();
+        assert_eq!(
+            encoded,
+            expected.build_with_script_sig(0, script_sig, TransactionType::P2PKH)
+        );
+        // The transaction itself is untouched.
+        assert_eq!(tx, transaction());
+    }
+}
+
+#[cfg(all(test, feature = "near"))]
+mod near_tests {
+    use super::*;
+    use crate::near::types::{
+        Action, BlockHash, ED25519Signature, NearToken, PublicKey, TransferAction, U64,
+    };
+
+    #[test]
+    fn test_encode_signed_matches_build_with_signature() {
+        let tx = NearTransaction {
+            signer_id: "alice.near".parse().unwrap(),
+            signer_public_key: PublicKey::ED25519([0u8; 32].into()),
+            nonce: U64(0),
+            receiver_id: "bob.near".parse().unwrap(),
+            block_hash: BlockHash([0u8; 32]),
+            actions: vec![Action::Transfer(TransferAction {
+                deposit: NearToken::from_yoctonear(1),
+            })],
+        };
+        let signature = NearSignature::ED25519(ED25519Signature {
+            r: [1u8; 32],
+            s: [2u8; 32],
+        });
+
+        assert_eq!(
+            encode_signed(&tx, &signature),
+            tx.build_with_signature(signature)
+        );
+    }
+}
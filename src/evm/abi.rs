@@ -0,0 +1,150 @@
+//! Minimal Solidity ABI encoding of contract calls, for building the `input` of a transaction.
+//!
+//! Only the common argument types are supported: `address`, `uint256`, `bool`, `bytes` and
+//! `string`. Static arguments are encoded in place as a 32-byte word, dynamic ones as an offset
+//! to their length-prefixed data, which follows all the in-place words.
+use super::{types::Address, utils::keccak256};
+
+/// An argument of a contract call.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Token {
+    Address(Address),
+    /// A big-endian `uint256`.
+    Uint([u8; 32]),
+    Bool(bool),
+    Bytes(Vec<u8>),
+    String(String),
+}
+
+impl Token {
+    /// Creates a `uint256` argument from a `u128`, e.g. an amount of wei or tokens.
+    pub fn uint(value: u128) -> Self {
+        Self::Uint(uint_word(value))
+    }
+}
+
+/// Returns the first four bytes of the keccak256 hash of a canonical function signature, such
+/// as `transfer(address,uint256)`, without spaces or argument names.
+pub fn function_selector(signature: &str) -> [u8; 4] {
+    let hash = keccak256(signature.as_bytes());
+    [hash[0], hash[1], hash[2], hash[3]]
+}
+
+/// Encodes the arguments of a call, without the function selector.
+pub fn encode(tokens: &[Token]) -> Vec<u8> {
+    let mut head = Vec::with_capacity(32 * tokens.len());
+    let mut tail = Vec::new();
+
+    for token in tokens {
+        match token {
+            Token::Address(address) => {
+                head.extend_from_slice(&[0u8; 12]);
+                head.extend_from_slice(address);
+            }
+            Token::Uint(word) => head.extend_from_slice(word),
+            Token::Bool(value) => head.extend_from_slice(&uint_word(u128::from(*value))),
+            Token::Bytes(data) => encode_dynamic(&mut head, &mut tail, tokens.len(), data),
+            Token::String(string) => {
+                encode_dynamic(&mut head, &mut tail, tokens.len(), string.as_bytes())
+            }
+        }
+    }
+
+    head.extend_from_slice(&tail);
+    head
+}
+
+/// Encodes a call: the selector of `signature` followed by the encoded arguments.
+///
+/// The arguments must match the types of the signature, which is not checked.
+pub fn encode_function_call(signature: &str, tokens: &[Token]) -> Vec<u8> {
+    let mut data = function_selector(signature).to_vec();
+    data.extend_from_slice(&encode(tokens));
+    data
+}
+
+/// Appends the offset of `data` to the head and its length-prefixed, zero-padded bytes to the
+/// tail. Offsets are relative to the start of the arguments.
+fn encode_dynamic(head: &mut Vec<u8>, tail: &mut Vec<u8>, token_count: usize, data: &[u8]) {
+    let offset = 32 * token_count + tail.len();
+    head.extend_from_slice(&uint_word(offset as u128));
+
+    tail.extend_from_slice(&uint_word(data.len() as u128));
+    tail.extend_from_slice(data);
+    let padding = (32 - data.len() % 32) % 32;
+    tail.resize(tail.len() + padding, 0);
+}
+
+fn uint_word(value: u128) -> [u8; 32] {
+    let mut word = [0u8; 32];
+    word[16..].copy_from_slice(&value.to_be_bytes());
+    word
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::evm::utils::parse_eth_address;
+
+    fn word(value: u128) -> Vec<u8> {
+        uint_word(value).to_vec()
+    }
+
+    #[test]
+    fn test_function_selector() {
+        assert_eq!(
+            hex::encode(function_selector("transfer(address,uint256)")),
+            "a9059cbb"
+        );
+        assert_eq!(
+            hex::encode(function_selector("approve(address,uint256)")),
+            "095ea7b3"
+        );
+    }
+
+    #[test]
+    fn test_encode_erc20_transfer() {
+        let to = parse_eth_address("d8dA6BF26964aF9D7eEd9e03E53415D37aA96045");
+
+        let data = encode_function_call(
+            "transfer(address,uint256)",
+            &[Token::Address(to), Token::uint(1_000)],
+        );
+
+        assert_eq!(
+            hex::encode(data),
+            concat!(
+                "a9059cbb",
+                "000000000000000000000000d8da6bf26964af9d7eed9e03e53415d37aa96045",
+                "00000000000000000000000000000000000000000000000000000000000003e8",
+            )
+        );
+    }
+
+    #[test]
+    fn test_encode_dynamic_arguments() {
+        let encoded = encode(&[
+            Token::Bytes(b"dave".to_vec()),
+            Token::Bool(true),
+            Token::String("a".repeat(33)),
+        ]);
+
+        let mut expected = [word(96), word(1), word(160)].concat();
+        expected.extend_from_slice(&word(4));
+        expected.extend_from_slice(b"dave");
+        expected.extend_from_slice(&[0u8; 28]);
+        expected.extend_from_slice(&word(33));
+        expected.extend_from_slice(&[b'a'; 33]);
+        expected.extend_from_slice(&[0u8; 31]);
+
+        assert_eq!(encoded, expected);
+    }
+
+    #[test]
+    fn test_encode_empty_bytes() {
+        assert_eq!(
+            encode(&[Token::Bytes(vec![])]),
+            [word(32), word(0)].concat()
+        );
+    }
+}
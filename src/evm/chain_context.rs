@@ -0,0 +1,240 @@
+//! Access to the state of an EVM chain, to fill in the nonce and fees of a transaction.
+//!
+//! [`ChainContext`] is object-safe and returns boxed futures, so it may be implemented by an
+//! RPC client off-chain, or by [`NoopChainContext`] in a contract which has no access to the
+//! chain. With the `alloy-context` feature, [`AlloyChainContext`] implements it on top of an
+//! alloy provider:
+//!
+//! ```ignore
+//! let provider = ProviderBuilder::new().on_http(rpc_url);
+//! let context = AlloyChainContext::new(provider);
+//! let tx = EVMTransactionBuilder::new()
+//!     .to(to)
+//!     .value(value)
+//!     .gas_limit(21_000)
+//!     .fill_from(&context, from)
+//!     .await?
+//!     .try_build()?;
+//! ```
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+
+use super::types::Address;
+
+/// The future returned by the methods of [`ChainContext`].
+pub type ContextFuture<'a, T> =
+    Pin<Box<dyn Future<Output = Result<T, ChainContextError>> + Send + 'a>>;
+
+/// Errors returned by a [`ChainContext`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChainContextError {
+    /// The context can't provide this information, e.g. [`NoopChainContext`].
+    Unsupported(&'static str),
+    /// The node or provider returned an error.
+    Provider(String),
+}
+
+impl fmt::Display for ChainContextError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unsupported(what) => write!(f, "{} is not available in this context", what),
+            Self::Provider(reason) => write!(f, "provider error: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for ChainContextError {}
+
+/// The fees suggested by a node, in wei per gas.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FeeEstimates {
+    pub max_fee_per_gas: u128,
+    pub max_priority_fee_per_gas: u128,
+    /// The gas price of legacy and EIP-2930 transactions.
+    pub gas_price: u128,
+}
+
+/// A source of the chain state needed to build a transaction.
+pub trait ChainContext {
+    fn fetch_chain_id(&self) -> ContextFuture<'_, u64>;
+
+    /// Returns the nonce of the next transaction sent by `address`.
+    fn fetch_nonce(&self, address: Address) -> ContextFuture<'_, u64>;
+
+    fn fetch_fee_estimates(&self) -> ContextFuture<'_, FeeEstimates>;
+}
+
+/// A context without access to the chain, every method returns
+/// [`ChainContextError::Unsupported`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct NoopChainContext;
+
+impl ChainContext for NoopChainContext {
+    fn fetch_chain_id(&self) -> ContextFuture<'_, u64> {
+        Box::pin(std::future::ready(Err(ChainContextError::Unsupported(
+            "chain id",
+        ))))
+    }
+
+    fn fetch_nonce(&self, _address: Address) -> ContextFuture<'_, u64> {
+        Box::pin(std::future::ready(Err(ChainContextError::Unsupported(
+            "nonce",
+        ))))
+    }
+
+    fn fetch_fee_estimates(&self) -> ContextFuture<'_, FeeEstimates> {
+        Box::pin(std::future::ready(Err(ChainContextError::Unsupported(
+            "fee estimates",
+        ))))
+    }
+}
+
+#[cfg(all(feature = "alloy-context", not(target_arch = "wasm32")))]
+pub use self::alloy_context::AlloyChainContext;
+
+#[cfg(all(feature = "alloy-context", not(target_arch = "wasm32")))]
+mod alloy_context {
+    use std::marker::PhantomData;
+
+    use alloy::providers::Provider;
+    use alloy::transports::{Transport, TransportError};
+
+    use super::{Address, ChainContext, ChainContextError, ContextFuture, FeeEstimates};
+
+    impl From<TransportError> for ChainContextError {
+        fn from(error: TransportError) -> Self {
+            Self::Provider(error.to_string())
+        }
+    }
+
+    /// A [`ChainContext`] querying a node through an alloy provider.
+    pub struct AlloyChainContext<P, T> {
+        provider: P,
+        _transport: PhantomData<fn() -> T>,
+    }
+
+    impl<P, T> AlloyChainContext<P, T>
+    where
+        P: Provider<T>,
+        T: Transport + Clone,
+    {
+        pub const fn new(provider: P) -> Self {
+            Self {
+                provider,
+                _transport: PhantomData,
+            }
+        }
+
+        pub const fn provider(&self) -> &P {
+            &self.provider
+        }
+    }
+
+    impl<P, T> ChainContext for AlloyChainContext<P, T>
+    where
+        P: Provider<T> + Send + Sync,
+        T: Transport + Clone,
+    {
+        fn fetch_chain_id(&self) -> ContextFuture<'_, u64> {
+            Box::pin(async move { Ok(self.provider.get_chain_id().await?) })
+        }
+
+        fn fetch_nonce(&self, address: Address) -> ContextFuture<'_, u64> {
+            Box::pin(async move { Ok(self.provider.get_transaction_count(address.into()).await?) })
+        }
+
+        fn fetch_fee_estimates(&self) -> ContextFuture<'_, FeeEstimates> {
+            Box::pin(async move {
+                let estimation = self.provider.estimate_eip1559_fees(None).await?;
+                let gas_price = self.provider.get_gas_price().await?;
+
+                Ok(FeeEstimates {
+                    max_fee_per_gas: estimation.max_fee_per_gas,
+                    max_priority_fee_per_gas: estimation.max_priority_fee_per_gas,
+                    gas_price,
+                })
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::evm::evm_transaction_builder::EVMTransactionBuilder;
+
+    struct FixedChainContext;
+
+    impl ChainContext for FixedChainContext {
+        fn fetch_chain_id(&self) -> ContextFuture<'_, u64> {
+            Box::pin(std::future::ready(Ok(11155111)))
+        }
+
+        fn fetch_nonce(&self, address: Address) -> ContextFuture<'_, u64> {
+            Box::pin(std::future::ready(Ok(address[19] as u64)))
+        }
+
+        fn fetch_fee_estimates(&self) -> ContextFuture<'_, FeeEstimates> {
+            Box::pin(std::future::ready(Ok(FeeEstimates {
+                max_fee_per_gas: 30,
+                max_priority_fee_per_gas: 2,
+                gas_price: 20,
+            })))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fill_from_keeps_set_fields() {
+        let context: &dyn ChainContext = &FixedChainContext;
+        let builder = EVMTransactionBuilder::new()
+            .to([0x11; 20])
+            .gas_limit(21_000)
+            .max_priority_fee_per_gas(1);
+
+        let tx = builder
+            .clone()
+            .fill_from(context, [0x07; 20])
+            .await
+            .unwrap()
+            .try_build()
+            .unwrap();
+
+        assert_eq!(tx.chain_id, 11155111);
+        assert_eq!(tx.nonce, 7);
+        assert_eq!(tx.max_fee_per_gas, 30);
+        assert_eq!(tx.max_priority_fee_per_gas, 1);
+
+        let tx = builder
+            .chain_id(1)
+            .nonce(42)
+            .fill_from(context, [0x07; 20])
+            .await
+            .unwrap()
+            .try_build_eip2930()
+            .unwrap();
+
+        assert_eq!((tx.chain_id, tx.nonce, tx.gas_price), (1, 42, 20));
+    }
+
+    #[tokio::test]
+    async fn test_noop_context() {
+        let err = EVMTransactionBuilder::new()
+            .fill_from(&NoopChainContext, [0; 20])
+            .await
+            .err()
+            .unwrap();
+
+        assert_eq!(err, ChainContextError::Unsupported("chain id"));
+        assert_eq!(err.to_string(), "chain id is not available in this context");
+
+        // Nothing is fetched when every field is set.
+        let builder = EVMTransactionBuilder::new()
+            .chain_id(1)
+            .nonce(0)
+            .max_fee_per_gas(2)
+            .max_priority_fee_per_gas(1)
+            .gas_price(2);
+        assert!(builder.fill_from(&NoopChainContext, [0; 20]).await.is_ok());
+    }
+}
@@ -0,0 +1,57 @@
+/// Chain specific limits applied when building EVM transactions with
+/// [`EVMTransactionBuilder::try_build`](super::evm_transaction_builder::EVMTransactionBuilder::try_build).
+///
+/// Some chains and relayers reject transactions with large access lists or calldata. Setting
+/// the corresponding limits lets the builder report the problem before the transaction is
+/// signed. A `None` limit is not enforced.
+///
+/// The profile can also require addresses given as strings to carry a valid EIP-55 checksum,
+/// which catches mistyped addresses coming from user input.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ChainProfile {
+    /// Maximum number of addresses in the access list.
+    pub max_access_list_entries: Option<usize>,
+    /// Maximum size of the transaction input, in bytes.
+    pub max_calldata_bytes: Option<usize>,
+    /// Whether addresses given as strings must be EIP-55 checksummed.
+    pub require_address_checksum: bool,
+    /// Whether all-lowercase (or all-uppercase) addresses are accepted when a checksum is
+    /// required.
+    pub allow_lowercase_addresses: bool,
+}
+
+impl ChainProfile {
+    /// Creates a profile without any limit.
+    pub const fn new() -> Self {
+        Self {
+            max_access_list_entries: None,
+            max_calldata_bytes: None,
+            require_address_checksum: false,
+            allow_lowercase_addresses: false,
+        }
+    }
+
+    /// Sets the maximum number of addresses in the access list.
+    pub const fn max_access_list_entries(mut self, max_access_list_entries: usize) -> Self {
+        self.max_access_list_entries = Some(max_access_list_entries);
+        self
+    }
+
+    /// Sets the maximum size of the transaction input, in bytes.
+    pub const fn max_calldata_bytes(mut self, max_calldata_bytes: usize) -> Self {
+        self.max_calldata_bytes = Some(max_calldata_bytes);
+        self
+    }
+
+    /// Requires addresses given as strings to be EIP-55 checksummed.
+    pub const fn require_address_checksum(mut self) -> Self {
+        self.require_address_checksum = true;
+        self
+    }
+
+    /// Accepts addresses without checksum information even when a checksum is required.
+    pub const fn allow_lowercase_addresses(mut self) -> Self {
+        self.allow_lowercase_addresses = true;
+        self
+    }
+}
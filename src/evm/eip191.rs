@@ -0,0 +1,81 @@
+//! EIP-191 version `0x45` signed messages, as produced by `personal_sign` and `eth_sign`.
+//!
+//! The message is prefixed with `"\x19Ethereum Signed Message:\n"` and its decimal length, so
+//! a signature of it can never be replayed as a transaction signature.
+use super::{types::Signature, utils::keccak256};
+use crate::constants::SECP256K1_SIGNATURE_LENGTH;
+use crate::signature_error::SignatureError;
+
+/// The prefix of personal messages, followed by the decimal length of the message.
+pub const PERSONAL_MESSAGE_PREFIX: &str = "\x19Ethereum Signed Message:\n";
+
+/// Returns the envelope of a personal message: the prefix, the length and the message.
+pub fn personal_message(message: &[u8]) -> Vec<u8> {
+    let mut envelope = format!("{}{}", PERSONAL_MESSAGE_PREFIX, message.len()).into_bytes();
+    envelope.extend_from_slice(message);
+    envelope
+}
+
+/// Returns the digest to sign for a personal message, the keccak256 hash of its envelope.
+pub fn personal_message_hash(message: &[u8]) -> [u8; 32] {
+    keccak256(&personal_message(message))
+}
+
+/// Returns the 65-byte `r || s || v` signature expected by `ecrecover` based verifiers, with
+/// `v` being 27 or 28.
+///
+/// `v` may be given as a recovery id, 0 or 1, as returned by the MPC signer.
+pub fn personal_signature(
+    signature: &Signature,
+) -> Result<[u8; SECP256K1_SIGNATURE_LENGTH], SignatureError> {
+    let (r, s, v) = signature.to_rsv_tuple()?;
+    let v = match v {
+        0 | 1 => v as u8 + 27,
+        27 | 28 => v as u8,
+        _ => return Err(SignatureError::InvalidRecoveryId(v)),
+    };
+
+    let mut bytes = [0u8; SECP256K1_SIGNATURE_LENGTH];
+    bytes[..32].copy_from_slice(&r);
+    bytes[32..64].copy_from_slice(&s);
+    bytes[64] = v;
+
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_personal_message_hash() {
+        assert_eq!(
+            personal_message(b"Hello World"),
+            b"\x19Ethereum Signed Message:\n11Hello World"
+        );
+        assert_eq!(
+            hex::encode(personal_message_hash(b"Hello World")),
+            "a1de988600a42c4b4ab089b619297c17d53cffae5d5120d82d8a92d0bb3b78f2"
+        );
+    }
+
+    #[test]
+    fn test_personal_signature() {
+        let signature = |v| Signature {
+            v,
+            r: vec![1u8; 32],
+            s: vec![2u8; 32],
+        };
+
+        assert_eq!(personal_signature(&signature(0)).unwrap()[64], 27);
+        assert_eq!(personal_signature(&signature(1)).unwrap()[64], 28);
+        assert_eq!(
+            personal_signature(&signature(28)).unwrap(),
+            signature(28).to_raw_65().unwrap()
+        );
+        assert_eq!(
+            personal_signature(&signature(37)),
+            Err(SignatureError::InvalidRecoveryId(37))
+        );
+    }
+}
@@ -0,0 +1,50 @@
+//! Experimental EIP-3074 `AUTH` commitment digests.
+//!
+//! EIP-3074 is not scheduled for any network upgrade, and its digest layout changed between
+//! revisions. This follows the latest revision, which includes the authority nonce:
+//! `keccak256(MAGIC || chainId || nonce || invokerAddress || commit)`, every field being
+//! left-padded to 32 bytes.
+use super::{types::Address, utils::keccak256};
+
+/// The EIP-3074 signature domain separator.
+pub const AUTH_MAGIC: u8 = 0x04;
+
+/// Computes the digest an authority signs to let `invoker` act on its behalf through
+/// `AUTH`/`AUTHCALL`.
+///
+/// `commit` is chosen by the invoker, usually the hash of the calls being authorized.
+pub fn auth_digest(chain_id: u64, nonce: u64, invoker: &Address, commit: &[u8; 32]) -> [u8; 32] {
+    let mut message = Vec::with_capacity(1 + 32 * 4);
+    message.push(AUTH_MAGIC);
+    message.extend_from_slice(&left_pad(&chain_id.to_be_bytes()));
+    message.extend_from_slice(&left_pad(&nonce.to_be_bytes()));
+    message.extend_from_slice(&left_pad(invoker));
+    message.extend_from_slice(commit);
+
+    keccak256(&message)
+}
+
+fn left_pad(bytes: &[u8]) -> [u8; 32] {
+    let mut padded = [0u8; 32];
+    padded[32 - bytes.len()..].copy_from_slice(bytes);
+    padded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::evm::utils::parse_eth_address;
+
+    #[test]
+    fn test_auth_digest() {
+        let invoker = parse_eth_address("d8dA6BF26964aF9D7eEd9e03E53415D37aA96045");
+
+        let digest = auth_digest(1, 7, &invoker, &[0x11; 32]);
+
+        assert_eq!(
+            hex::encode(digest),
+            "029073bc7c289aa061eed6d53de37d40bc8ba47555d24fb6143176f2bb4b0e00"
+        );
+        assert_ne!(auth_digest(2, 7, &invoker, &[0x11; 32]), digest);
+    }
+}
@@ -0,0 +1,412 @@
+//! EIP-712 typed structured data hashing, e.g. for permits and off-chain orders.
+//!
+//! The typed data is given in the JSON format of `eth_signTypedData_v4`, and the digest to
+//! sign is `keccak256(0x19 || 0x01 || domainSeparator || hashStruct(message))`.
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+use serde::Deserialize;
+use serde_json::{self, Value};
+
+use super::utils::{keccak256, parse_address_hex};
+
+/// The name of the domain type.
+pub const EIP712_DOMAIN_TYPE: &str = "EIP712Domain";
+
+/// Errors returned when hashing typed data.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Eip712Error {
+    /// The typed data is not valid JSON or does not have the expected shape.
+    InvalidJson(String),
+    /// A type is neither an atomic type nor defined in the schema.
+    UnknownType(String),
+    /// A struct value is missing one of the fields of its type.
+    MissingField { type_name: String, field: String },
+    /// A value cannot be encoded as its declared type.
+    InvalidValue { type_name: String, value: String },
+}
+
+impl fmt::Display for Eip712Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidJson(e) => write!(f, "invalid typed data: {}", e),
+            Self::UnknownType(type_name) => write!(f, "unknown type {}", type_name),
+            Self::MissingField { type_name, field } => {
+                write!(f, "{} is missing field {}", type_name, field)
+            }
+            Self::InvalidValue { type_name, value } => {
+                write!(f, "{} is not a valid {}", value, type_name)
+            }
+        }
+    }
+}
+
+impl std::error::Error for Eip712Error {}
+
+/// A member of a struct type.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Eip712Field {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub type_name: String,
+}
+
+/// Struct types by name.
+pub type Eip712Types = BTreeMap<String, Vec<Eip712Field>>;
+
+/// Typed data, as passed to `eth_signTypedData_v4`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TypedData {
+    /// The struct types. `EIP712Domain` may be omitted, it is then inferred from the domain.
+    pub types: Eip712Types,
+    pub primary_type: String,
+    pub domain: Value,
+    pub message: Value,
+}
+
+impl TypedData {
+    pub fn from_json(json: &str) -> Result<Self, Eip712Error> {
+        serde_json::from_str(json).map_err(|e| Eip712Error::InvalidJson(e.to_string()))
+    }
+
+    /// Returns the hash of the domain, which binds the signature to a contract and chain.
+    pub fn domain_separator(&self) -> Result<[u8; 32], Eip712Error> {
+        if self.types.contains_key(EIP712_DOMAIN_TYPE) {
+            return hash_struct(EIP712_DOMAIN_TYPE, &self.types, &self.domain);
+        }
+
+        let mut types = self.types.clone();
+        types.insert(
+            EIP712_DOMAIN_TYPE.to_string(),
+            self.inferred_domain_fields(),
+        );
+        hash_struct(EIP712_DOMAIN_TYPE, &types, &self.domain)
+    }
+
+    /// Returns the hash of the message, of type `primary_type`.
+    pub fn struct_hash(&self) -> Result<[u8; 32], Eip712Error> {
+        hash_struct(&self.primary_type, &self.types, &self.message)
+    }
+
+    /// Returns the digest to sign.
+    pub fn signing_hash(&self) -> Result<[u8; 32], Eip712Error> {
+        let mut data = vec![0x19, 0x01];
+        data.extend_from_slice(&self.domain_separator()?);
+        data.extend_from_slice(&self.struct_hash()?);
+
+        Ok(keccak256(&data))
+    }
+
+    /// The domain fields defined by EIP-712 which are present in the domain, in their
+    /// canonical order.
+    fn inferred_domain_fields(&self) -> Vec<Eip712Field> {
+        [
+            ("name", "string"),
+            ("version", "string"),
+            ("chainId", "uint256"),
+            ("verifyingContract", "address"),
+            ("salt", "bytes32"),
+        ]
+        .into_iter()
+        .filter(|(name, _)| self.domain.get(name).is_some_and(|value| !value.is_null()))
+        .map(|(name, type_name)| Eip712Field {
+            name: name.to_string(),
+            type_name: type_name.to_string(),
+        })
+        .collect()
+    }
+}
+
+/// Returns the encoding of a struct type: its own definition followed by the definitions of
+/// the struct types it references, sorted by name, e.g.
+/// `Mail(Person from,Person to,string contents)Person(string name,address wallet)`.
+pub fn encode_type(primary_type: &str, types: &Eip712Types) -> Result<String, Eip712Error> {
+    let mut dependencies = BTreeSet::new();
+    collect_dependencies(primary_type, types, &mut dependencies)?;
+    dependencies.remove(primary_type);
+
+    let mut encoded = String::new();
+    for type_name in std::iter::once(primary_type).chain(dependencies.iter().map(String::as_str)) {
+        let fields: Vec<String> = types[type_name]
+            .iter()
+            .map(|field| format!("{} {}", field.type_name, field.name))
+            .collect();
+        encoded.push_str(&format!("{}({})", type_name, fields.join(",")));
+    }
+
+    Ok(encoded)
+}
+
+/// Returns `keccak256(typeHash || encodeData(value))`, the hash of a struct value.
+pub fn hash_struct(
+    type_name: &str,
+    types: &Eip712Types,
+    value: &Value,
+) -> Result<[u8; 32], Eip712Error> {
+    let fields = types
+        .get(type_name)
+        .ok_or_else(|| Eip712Error::UnknownType(type_name.to_string()))?;
+
+    let mut data = keccak256(encode_type(type_name, types)?.as_bytes()).to_vec();
+    for field in fields {
+        let field_value = value
+            .get(&field.name)
+            .ok_or_else(|| Eip712Error::MissingField {
+                type_name: type_name.to_string(),
+                field: field.name.clone(),
+            })?;
+        data.extend_from_slice(&encode_value(&field.type_name, types, field_value)?);
+    }
+
+    Ok(keccak256(&data))
+}
+
+fn collect_dependencies(
+    type_name: &str,
+    types: &Eip712Types,
+    found: &mut BTreeSet<String>,
+) -> Result<(), Eip712Error> {
+    let fields = types
+        .get(type_name)
+        .ok_or_else(|| Eip712Error::UnknownType(type_name.to_string()))?;
+    if !found.insert(type_name.to_string()) {
+        return Ok(());
+    }
+
+    for field in fields {
+        let base_type = field.type_name.split('[').next().unwrap_or_default();
+        if types.contains_key(base_type) {
+            collect_dependencies(base_type, types, found)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Encodes a field value as a 32-byte word: atomic values are padded, dynamic values and
+/// structs are hashed.
+fn encode_value(
+    type_name: &str,
+    types: &Eip712Types,
+    value: &Value,
+) -> Result<[u8; 32], Eip712Error> {
+    let invalid = || Eip712Error::InvalidValue {
+        type_name: type_name.to_string(),
+        value: value.to_string(),
+    };
+
+    if let Some(element_type) = type_name
+        .strip_suffix(']')
+        .and_then(|t| t.rsplit_once('['))
+        .map(|(element_type, _)| element_type)
+    {
+        let items = value.as_array().ok_or_else(invalid)?;
+        let mut data = Vec::with_capacity(32 * items.len());
+        for item in items {
+            data.extend_from_slice(&encode_value(element_type, types, item)?);
+        }
+        return Ok(keccak256(&data));
+    }
+
+    if types.contains_key(type_name) {
+        return hash_struct(type_name, types, value);
+    }
+
+    match type_name {
+        "string" => Ok(keccak256(value.as_str().ok_or_else(invalid)?.as_bytes())),
+        "bytes" => Ok(keccak256(&decode_hex(value).ok_or_else(invalid)?)),
+        "bool" => {
+            let mut word = [0u8; 32];
+            word[31] = u8::from(value.as_bool().ok_or_else(invalid)?);
+            Ok(word)
+        }
+        "address" => {
+            let address =
+                parse_address_hex(value.as_str().ok_or_else(invalid)?).map_err(|_| invalid())?;
+            let mut word = [0u8; 32];
+            word[12..].copy_from_slice(&address);
+            Ok(word)
+        }
+        _ if type_name.starts_with("bytes") => {
+            let bytes = decode_hex(value).ok_or_else(invalid)?;
+            if bytes.len() > 32 {
+                return Err(invalid());
+            }
+            let mut word = [0u8; 32];
+            word[..bytes.len()].copy_from_slice(&bytes);
+            Ok(word)
+        }
+        _ if type_name.starts_with("uint") => encode_uint(value).ok_or_else(invalid),
+        _ if type_name.starts_with("int") => encode_int(value).ok_or_else(invalid),
+        _ => Err(Eip712Error::UnknownType(type_name.to_string())),
+    }
+}
+
+fn decode_hex(value: &Value) -> Option<Vec<u8>> {
+    let s = value.as_str()?;
+    hex::decode(s.strip_prefix("0x").unwrap_or(s)).ok()
+}
+
+/// Encodes an unsigned integer given as a JSON number, a decimal string or a `0x` prefixed hex
+/// string. Decimal values are limited to 128 bits, hex values to 256 bits.
+fn encode_uint(value: &Value) -> Option<[u8; 32]> {
+    let mut word = [0u8; 32];
+
+    if let Some(hex_str) = value.as_str().and_then(|s| s.strip_prefix("0x")) {
+        let padded = if hex_str.len() % 2 == 1 {
+            format!("0{}", hex_str)
+        } else {
+            hex_str.to_string()
+        };
+        let bytes = hex::decode(padded).ok()?;
+        if bytes.len() > 32 {
+            return None;
+        }
+        word[32 - bytes.len()..].copy_from_slice(&bytes);
+        return Some(word);
+    }
+
+    let number: u128 = match value {
+        Value::Number(n) => n.as_u64()?.into(),
+        Value::String(s) => s.parse().ok()?,
+        _ => return None,
+    };
+    word[16..].copy_from_slice(&number.to_be_bytes());
+    Some(word)
+}
+
+/// Encodes a signed integer, given as a JSON number or a decimal string, in two's complement.
+fn encode_int(value: &Value) -> Option<[u8; 32]> {
+    let number: i128 = match value {
+        Value::Number(n) => n.as_i64()?.into(),
+        Value::String(s) => s.parse().ok()?,
+        _ => return None,
+    };
+
+    let mut word = if number < 0 { [0xff; 32] } else { [0u8; 32] };
+    word[16..].copy_from_slice(&number.to_be_bytes());
+    Some(word)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The example of EIP-712.
+    const MAIL: &str = r#"{
+        "types": {
+            "EIP712Domain": [
+                {"name": "name", "type": "string"},
+                {"name": "version", "type": "string"},
+                {"name": "chainId", "type": "uint256"},
+                {"name": "verifyingContract", "type": "address"}
+            ],
+            "Person": [
+                {"name": "name", "type": "string"},
+                {"name": "wallet", "type": "address"}
+            ],
+            "Mail": [
+                {"name": "from", "type": "Person"},
+                {"name": "to", "type": "Person"},
+                {"name": "contents", "type": "string"}
+            ]
+        },
+        "primaryType": "Mail",
+        "domain": {
+            "name": "Ether Mail",
+            "version": "1",
+            "chainId": 1,
+            "verifyingContract": "0xCcCCccccCCCCcCCCCCCcCcCccCcCCCcCcccccccC"
+        },
+        "message": {
+            "from": {"name": "Cow", "wallet": "0xCD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826"},
+            "to": {"name": "Bob", "wallet": "0xbBbBBBBbbBBBbbbBbbBbbbbBBbBbbbbBbBbbBBbB"},
+            "contents": "Hello, Bob!"
+        }
+    }"#;
+
+    #[test]
+    fn test_mail_example() {
+        let typed_data = TypedData::from_json(MAIL).unwrap();
+
+        assert_eq!(
+            encode_type("Mail", &typed_data.types).unwrap(),
+            "Mail(Person from,Person to,string contents)Person(string name,address wallet)"
+        );
+        assert_eq!(
+            hex::encode(typed_data.domain_separator().unwrap()),
+            "f2cee375fa42b42143804025fc449deafd50cc031ca257e0b194a650a912090f"
+        );
+        assert_eq!(
+            hex::encode(typed_data.struct_hash().unwrap()),
+            "c52c0ee5d84264471806290a3f2c4cecfc5490626bf912d01f240d7a274b371e"
+        );
+        assert_eq!(
+            hex::encode(typed_data.signing_hash().unwrap()),
+            "be609aee343fb3c4b28e1df9e632fca64fcfaede20f02e86244efddf30957bd2"
+        );
+    }
+
+    #[test]
+    fn test_inferred_domain_type() {
+        let mut typed_data = TypedData::from_json(MAIL).unwrap();
+        let expected = typed_data.domain_separator().unwrap();
+
+        typed_data.types.remove(EIP712_DOMAIN_TYPE);
+        assert_eq!(typed_data.domain_separator().unwrap(), expected);
+    }
+
+    #[test]
+    fn test_encode_atomic_values() {
+        let types = Eip712Types::new();
+        let encode = |type_name: &str, value: Value| encode_value(type_name, &types, &value);
+
+        assert_eq!(
+            encode("uint256", Value::from("0x0100")).unwrap(),
+            encode("uint256", Value::from(256)).unwrap()
+        );
+        assert_eq!(
+            encode("uint256", Value::from("256")).unwrap(),
+            encode("uint256", Value::from(256)).unwrap()
+        );
+        assert_eq!(encode("int8", Value::from(-1)).unwrap(), [0xff; 32]);
+        assert_eq!(
+            encode("bytes4", Value::from("0x01020304")).unwrap()[..5],
+            [1, 2, 3, 4, 0]
+        );
+        assert_eq!(
+            encode("uint256[]", serde_json::json!([1, 2])).unwrap(),
+            keccak256(
+                &[
+                    encode("uint256", Value::from(1)).unwrap(),
+                    encode("uint256", Value::from(2)).unwrap()
+                ]
+                .concat()
+            )
+        );
+
+        assert!(matches!(
+            encode("uint256", Value::from(-1)),
+            Err(Eip712Error::InvalidValue { .. })
+        ));
+        assert_eq!(
+            encode("Unknown", Value::Null),
+            Err(Eip712Error::UnknownType("Unknown".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_missing_field() {
+        let mut typed_data = TypedData::from_json(MAIL).unwrap();
+        typed_data.message["to"] = serde_json::json!({"name": "Bob"});
+
+        assert_eq!(
+            typed_data.signing_hash(),
+            Err(Eip712Error::MissingField {
+                type_name: "Person".to_string(),
+                field: "wallet".to_string(),
+            })
+        );
+    }
+}
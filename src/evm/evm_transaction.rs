@@ -1,13 +1,71 @@
-use near_sdk::serde::{Deserialize, Serialize};
-use rlp::RlpStream;
+use std::fmt;
 
+use borsh::{BorshDeserialize, BorshSerialize};
+use rlp::{Rlp, RlpStream};
+use serde::{de, Deserialize, Deserializer, Serialize};
+use serde_json::{json, Value};
+
+use crate::canonical_json::{hex_value, int_value, CanonicalJson};
 use crate::constants::EIP_1559_TYPE;
+use crate::signature_error::SignatureError;
+use crate::validation::{Validate, ValidationIssue};
+
+use super::types::{AccessList, AccessListItem, Address, Signature};
+use super::utils::{format_recipient, keccak256, parse_address_hex, parse_u128};
+
+/// Errors returned by [`EVMTransaction::decode`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EVMDecodeError {
+    /// The input is empty.
+    Empty,
+    /// The transaction type is not EIP-1559.
+    UnsupportedType(u8),
+    /// The payload is not a single RLP list.
+    InvalidEncoding(String),
+    /// The list has neither the number of fields of an unsigned nor of a signed transaction.
+    InvalidFieldCount(usize),
+    /// A field is not a valid encoding of its value.
+    InvalidField { field: &'static str, reason: String },
+}
 
-use super::types::{AccessList, Address, Signature};
-use super::utils::parse_eth_address;
+impl fmt::Display for EVMDecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => write!(f, "empty transaction"),
+            Self::UnsupportedType(tx_type) => {
+                write!(f, "unsupported transaction type: {:#04x}", tx_type)
+            }
+            Self::InvalidEncoding(reason) => write!(f, "invalid encoding: {}", reason),
+            Self::InvalidFieldCount(count) => write!(
+                f,
+                "expected {} or {} fields, got {}",
+                UNSIGNED_FIELD_COUNT,
+                UNSIGNED_FIELD_COUNT + 3,
+                count
+            ),
+            Self::InvalidField { field, reason } => write!(f, "invalid {}: {}", field, reason),
+        }
+    }
+}
+
+impl std::error::Error for EVMDecodeError {}
 
-#[derive(Debug, Serialize, Deserialize)]
-#[serde(crate = "near_sdk::serde")]
+impl EVMDecodeError {
+    fn field(field: &'static str, reason: impl ToString) -> Self {
+        Self::InvalidField {
+            field,
+            reason: reason.to_string(),
+        }
+    }
+}
+
+/// An EIP-1559 (type 2) transaction.
+///
+/// The borsh encoding follows the order of the fields. It is how NEAR contracts store pending
+/// transactions in their state, so the fields must not be reordered; prefer
+/// [`VersionedOmniTransaction`](crate::versioned_transaction::VersionedOmniTransaction) to
+/// store them.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
 pub struct EVMTransaction {
     pub chain_id: u64,
     pub nonce: u64,
@@ -35,6 +93,12 @@ impl EVMTransaction {
         rlp_stream.out().to_vec()
     }
 
+    /// Returns the keccak256 hash of [`Self::build_for_signing`], the digest signed by the
+    /// sender.
+    pub fn hash_for_signing(&self) -> [u8; 32] {
+        keccak256(&self.build_for_signing())
+    }
+
     pub fn build_with_signature(&self, signature: &Signature) -> Vec<u8> {
         let mut rlp_stream = RlpStream::new();
 
@@ -53,9 +117,100 @@ impl EVMTransaction {
         rlp_stream.out().to_vec()
     }
 
+    /// Same as [`Self::build_with_signature`], but first checks that `r` and `s` are non-zero
+    /// 32-byte values and that `v` is a y-parity (0 or 1), as expected by typed transactions.
+    pub fn try_build_with_signature(
+        &self,
+        signature: &Signature,
+    ) -> Result<Vec<u8>, SignatureError> {
+        for (component, value) in [("r", &signature.r), ("s", &signature.s)] {
+            if value.len() != 32 {
+                return Err(SignatureError::InvalidLength {
+                    component,
+                    expected: 32,
+                    actual: value.len(),
+                });
+            }
+            if value.iter().all(|b| *b == 0) {
+                return Err(SignatureError::ZeroComponent(component));
+            }
+        }
+
+        if signature.v > 1 {
+            return Err(SignatureError::InvalidRecoveryId(signature.v));
+        }
+
+        Ok(self.build_with_signature(signature))
+    }
+
+    /// Decodes an EIP-1559 transaction, as returned by [`Self::build_for_signing`] or
+    /// [`Self::build_with_signature`], along with its signature if it has one.
+    pub fn decode(bytes: &[u8]) -> Result<(Self, Option<Signature>), EVMDecodeError> {
+        let (&tx_type, payload) = bytes.split_first().ok_or(EVMDecodeError::Empty)?;
+        if tx_type != EIP_1559_TYPE {
+            return Err(EVMDecodeError::UnsupportedType(tx_type));
+        }
+
+        let rlp = Rlp::new(payload);
+        let invalid_encoding =
+            |e: rlp::DecoderError| EVMDecodeError::InvalidEncoding(e.to_string());
+        let info = rlp.payload_info().map_err(invalid_encoding)?;
+        if !rlp.is_list() || info.header_len + info.value_len != payload.len() {
+            return Err(EVMDecodeError::InvalidEncoding(
+                "transaction is not a single RLP list".to_string(),
+            ));
+        }
+        let item_count = rlp.item_count().map_err(invalid_encoding)?;
+        if item_count != UNSIGNED_FIELD_COUNT && item_count != UNSIGNED_FIELD_COUNT + 3 {
+            return Err(EVMDecodeError::InvalidFieldCount(item_count));
+        }
+
+        let val_at = |index: usize, field: &'static str| {
+            rlp.at(index)
+                .and_then(|item| item.as_val::<u128>())
+                .map_err(|e| EVMDecodeError::field(field, e))
+        };
+        let u64_at = |index: usize, field: &'static str| {
+            u64::try_from(val_at(index, field)?)
+                .map_err(|_| EVMDecodeError::field(field, "too big"))
+        };
+        let to = match bytes_at(&rlp, 5, "to")? {
+            [] => None,
+            to => Some(to.try_into().map_err(|_| {
+                EVMDecodeError::field("to", format!("expected 20 bytes, got {}", to.len()))
+            })?),
+        };
+
+        let tx = Self {
+            chain_id: u64_at(0, "chain_id")?,
+            nonce: u64_at(1, "nonce")?,
+            max_priority_fee_per_gas: val_at(2, "max_priority_fee_per_gas")?,
+            max_fee_per_gas: val_at(3, "max_fee_per_gas")?,
+            gas_limit: val_at(4, "gas_limit")?,
+            to,
+            value: val_at(6, "value")?,
+            input: bytes_at(&rlp, 7, "input")?.to_vec(),
+            access_list: decode_access_list(
+                &rlp.at(8)
+                    .map_err(|e| EVMDecodeError::field("access_list", e))?,
+            )?,
+        };
+
+        let signature = if item_count == UNSIGNED_FIELD_COUNT {
+            None
+        } else {
+            Some(Signature {
+                v: u64_at(UNSIGNED_FIELD_COUNT, "v")?,
+                r: scalar_at(&rlp, UNSIGNED_FIELD_COUNT + 1, "r")?,
+                s: scalar_at(&rlp, UNSIGNED_FIELD_COUNT + 2, "s")?,
+            })
+        };
+
+        Ok((tx, signature))
+    }
+
     fn encode_fields(&self, rlp_stream: &mut RlpStream) {
         let to: Vec<u8> = self.to.map_or(vec![], |to| to.to_vec());
-        let access_list = self.access_list.clone();
 
         rlp_stream.append(&self.chain_id);
         rlp_stream.append(&self.nonce);
@@ -66,93 +221,331 @@ impl EVMTransaction {
         rlp_stream.append(&self.value);
         rlp_stream.append(&self.input);
 
-        // Write access list.
-        {
-            rlp_stream.begin_unbounded_list();
-            for access in access_list {
-                rlp_stream.begin_unbounded_list();
-                rlp_stream.append(&access.0.to_vec());
-                // Append list of storage keys.
-                {
-                    rlp_stream.begin_unbounded_list();
-                    for storage_key in access.1 {
-                        rlp_stream.append(&storage_key.to_vec());
-                    }
-                    rlp_stream.finalize_unbounded_list();
-                }
-                rlp_stream.finalize_unbounded_list();
-            }
-            rlp_stream.finalize_unbounded_list();
+        encode_access_list(rlp_stream, &self.access_list);
+    }
+
+    /// Returns the price per gas paid by the transaction for the given block base fee, as
+    /// defined by EIP-1559: `min(max_fee_per_gas, base_fee + max_priority_fee_per_gas)`.
+    ///
+    /// Returns `None` if the base fee exceeds `max_fee_per_gas`, in which case the transaction
+    /// cannot be included in the block.
+    pub fn effective_gas_price(&self, base_fee: u128) -> Option<u128> {
+        if base_fee > self.max_fee_per_gas {
+            return None;
         }
+
+        Some(
+            base_fee
+                .saturating_add(self.max_priority_fee_per_gas)
+                .min(self.max_fee_per_gas),
+        )
     }
 
-    pub fn from_json(json: &str) -> Result<Self, near_sdk::serde_json::Error> {
-        let v: near_sdk::serde_json::Value = near_sdk::serde_json::from_str(json)?;
+    /// Returns the maximum amount of wei the sender can be charged, i.e.
+    /// `gas_limit * max_fee_per_gas + value`, or `None` on overflow.
+    pub fn max_total_cost(&self) -> Option<u128> {
+        self.gas_limit
+            .checked_mul(self.max_fee_per_gas)?
+            .checked_add(self.value)
+    }
 
-        let to = v["to"].as_str().unwrap_or_default().to_string();
+    /// Parses a transaction from its JSON-RPC representation, e.g. as produced by ethers or
+    /// viem.
+    ///
+    /// Quantities can be numbers, decimal strings or `0x` prefixed hex strings. `to`, `value`,
+    /// `input` (or `data`) and `accessList` are optional: a missing `to` means a contract
+    /// creation.
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        let tx: EVMTransactionJson = serde_json::from_str(json)?;
 
-        let to_parsed = parse_eth_address(
-            to.strip_prefix("0x")
-                .unwrap_or("0000000000000000000000000000000000000000"),
-        );
+        Ok(Self {
+            chain_id: tx.chain_id,
+            nonce: tx.nonce,
+            to: tx.to,
+            value: tx.value,
+            input: tx.input,
+            gas_limit: tx.gas_limit,
+            max_fee_per_gas: tx.max_fee_per_gas,
+            max_priority_fee_per_gas: tx.max_priority_fee_per_gas,
+            access_list: tx.access_list,
+        })
+    }
+}
 
-        let nonce_str = v["nonce"].as_str().expect("nonce should be provided");
-        let nonce = parse_u64(nonce_str).expect("nonce should be a valid u64");
+/// The JSON-RPC representation of an [`EVMTransaction`], see [`EVMTransaction::from_json`].
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct EVMTransactionJson {
+    #[serde(deserialize_with = "deserialize_u64")]
+    chain_id: u64,
+    #[serde(deserialize_with = "deserialize_u64")]
+    nonce: u64,
+    #[serde(default, deserialize_with = "deserialize_optional_address")]
+    to: Option<Address>,
+    #[serde(default, deserialize_with = "deserialize_u128")]
+    value: u128,
+    #[serde(default, alias = "data", deserialize_with = "deserialize_bytes")]
+    input: Vec<u8>,
+    #[serde(alias = "gas", deserialize_with = "deserialize_u128")]
+    gas_limit: u128,
+    #[serde(deserialize_with = "deserialize_u128")]
+    max_fee_per_gas: u128,
+    #[serde(deserialize_with = "deserialize_u128")]
+    max_priority_fee_per_gas: u128,
+    #[serde(default)]
+    access_list: AccessList,
+}
 
-        let value_str = v["value"].as_str().expect("value should be provided");
-        let value = parse_u128(value_str).expect("value should be a valid u128");
+/// Reads a quantity given as a number, a decimal string or a `0x` prefixed hex string.
+fn deserialize_u128<'de, D>(deserializer: D) -> Result<u128, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    struct QuantityVisitor;
 
-        let gas_limit_str = v["gasLimit"].as_str().expect("gasLimit should be provided");
-        let gas_limit = parse_u128(gas_limit_str).expect("gasLimit should be a valid u128");
+    impl<'de> de::Visitor<'de> for QuantityVisitor {
+        type Value = u128;
 
-        let max_priority_fee_per_gas_str = v["maxPriorityFeePerGas"]
-            .as_str()
-            .expect("maxPriorityFeePerGas should be provided");
-        let max_priority_fee_per_gas = parse_u128(max_priority_fee_per_gas_str)
-            .expect("maxPriorityFeePerGas should be a valid u128");
+        fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+            formatter.write_str("a non-negative integer, as a number or a decimal or hex string")
+        }
 
-        let max_fee_per_gas_str = v["maxFeePerGas"]
-            .as_str()
-            .expect("maxFeePerGas should be provided");
-        let max_fee_per_gas =
-            parse_u128(max_fee_per_gas_str).expect("maxFeePerGas should be a valid u128");
+        fn visit_u64<E>(self, value: u64) -> Result<Self::Value, E>
+        where
+            E: de::Error,
+        {
+            Ok(u128::from(value))
+        }
 
-        let chain_id_str = v["chainId"].as_str().expect("chainId should be provided");
-        let chain_id = parse_u64(chain_id_str).expect("chainId should be a valid u64");
+        fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
+        where
+            E: de::Error,
+        {
+            parse_u128(value).map_err(|e| E::custom(format!("invalid quantity {:?}: {}", value, e)))
+        }
+    }
 
-        let input = v["input"].as_str().unwrap_or_default().to_string();
-        let input =
-            hex::decode(input.strip_prefix("0x").unwrap_or("")).expect("input should be hex");
+    deserializer.deserialize_any(QuantityVisitor)
+}
 
-        // TODO: Implement access list
-        // let access_list = v["accessList"].as_str().unwrap_or_default().to_string();
+fn deserialize_u64<'de, D>(deserializer: D) -> Result<u64, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let value = deserialize_u128(deserializer)?;
+    u64::try_from(value)
+        .map_err(|_| de::Error::custom(format!("quantity {} does not fit in a u64", value)))
+}
 
-        Ok(Self {
-            chain_id,
-            nonce,
-            to: Some(to_parsed),
-            value,
-            input,
-            gas_limit,
-            max_fee_per_gas,
-            max_priority_fee_per_gas,
-            access_list: vec![],
+fn deserialize_bytes<'de, D>(deserializer: D) -> Result<Vec<u8>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let value: String = Deserialize::deserialize(deserializer)?;
+    hex::decode(value.strip_prefix("0x").unwrap_or(&value))
+        .map_err(|e| de::Error::custom(format!("invalid hex data {:?}: {}", value, e)))
+}
+
+fn deserialize_optional_address<'de, D>(deserializer: D) -> Result<Option<Address>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let value: Option<String> = Deserialize::deserialize(deserializer)?;
+    match value {
+        None => Ok(None),
+        Some(value) if value.is_empty() || value == "0x" => Ok(None),
+        Some(value) => parse_address_hex(&value)
+            .map(Some)
+            .map_err(de::Error::custom),
+    }
+}
+
+/// Writes an access list, shared by all the typed transactions which carry one.
+pub(super) fn encode_access_list(rlp_stream: &mut RlpStream, access_list: &AccessList) {
+    rlp_stream.begin_unbounded_list();
+    for item in access_list {
+        rlp_stream.begin_unbounded_list();
+        rlp_stream.append(&item.address.to_vec());
+        // Append list of storage keys.
+        {
+            rlp_stream.begin_unbounded_list();
+            for storage_key in &item.storage_keys {
+                rlp_stream.append(&storage_key.to_vec());
+            }
+            rlp_stream.finalize_unbounded_list();
+        }
+        rlp_stream.finalize_unbounded_list();
+    }
+    rlp_stream.finalize_unbounded_list();
+}
+
+/// Returns the canonical JSON of an access list, shared by all the typed transactions which
+/// carry one.
+pub(super) fn canonical_access_list(access_list: &AccessList) -> Value {
+    access_list
+        .iter()
+        .map(|item| {
+            json!({
+                "address": hex_value(item.address),
+                "storage_keys": item.storage_keys.iter().map(hex_value).collect::<Vec<_>>(),
+            })
+        })
+        .collect()
+}
+
+/// Reads an access list written by [`encode_access_list`].
+pub(super) fn decode_access_list(rlp: &Rlp) -> Result<AccessList, EVMDecodeError> {
+    if !rlp.is_list() {
+        return Err(EVMDecodeError::field("access_list", "expected a list"));
+    }
+
+    rlp.iter()
+        .map(|item| {
+            let item_count = item
+                .item_count()
+                .map_err(|e| EVMDecodeError::field("access_list item", e))?;
+            if !item.is_list() || item_count != 2 {
+                return Err(EVMDecodeError::field(
+                    "access_list item",
+                    "expected an address and keys",
+                ));
+            }
+            let address = bytes_at(&item, 0, "access_list address")?
+                .try_into()
+                .map_err(|_| EVMDecodeError::field("access_list address", "expected 20 bytes"))?;
+            let storage_keys = item
+                .at(1)
+                .map_err(|e| EVMDecodeError::field("access_list storage keys", e))?;
+            if !storage_keys.is_list() {
+                return Err(EVMDecodeError::field(
+                    "access_list storage keys",
+                    "expected a list",
+                ));
+            }
+            let storage_keys = storage_keys
+                .iter()
+                .map(|key| {
+                    key.data()
+                        .ok()
+                        .filter(|_| key.is_data())
+                        .and_then(|key| key.try_into().ok())
+                        .ok_or_else(|| EVMDecodeError::field("storage key", "expected 32 bytes"))
+                })
+                .collect::<Result<Vec<[u8; 32]>, _>>()?;
+
+            Ok(AccessListItem::new(address, storage_keys))
         })
+        .collect()
+}
+
+/// Returns the bytes of the string item at `index` of a list.
+fn bytes_at<'a>(
+    rlp: &Rlp<'a>,
+    index: usize,
+    field: &'static str,
+) -> Result<&'a [u8], EVMDecodeError> {
+    let item = rlp.at(index).map_err(|e| EVMDecodeError::field(field, e))?;
+    if !item.is_data() {
+        return Err(EVMDecodeError::field(field, "expected bytes"));
+    }
+
+    item.data().map_err(|e| EVMDecodeError::field(field, e))
+}
+
+/// Reads a signature scalar, which RLP encodes as an integer without leading zeros, and left
+/// pads it to 32 bytes like the signatures passed to [`EVMTransaction::build_with_signature`].
+fn scalar_at(rlp: &Rlp, index: usize, field: &'static str) -> Result<Vec<u8>, EVMDecodeError> {
+    let bytes = bytes_at(rlp, index, field)?;
+    if bytes.len() > 32 {
+        return Err(EVMDecodeError::field(
+            field,
+            format!("expected at most 32 bytes, got {}", bytes.len()),
+        ));
+    }
+
+    let mut scalar = vec![0u8; 32 - bytes.len()];
+    scalar.extend_from_slice(bytes);
+    Ok(scalar)
+}
+
+/// Number of fields of an EIP-1559 transaction, without the signature.
+const UNSIGNED_FIELD_COUNT: usize = 9;
+
+/// Gas used by a plain transfer, the lowest gas limit a transaction can have.
+const INTRINSIC_GAS: u128 = 21_000;
+
+/// A one-line summary of the transaction, for logs.
+impl fmt::Display for EVMTransaction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "EVM chain {} nonce {}: {} wei to {}, gas limit {}, max fee {} wei/gas, \
+             priority fee {} wei/gas, {} bytes of input",
+            self.chain_id,
+            self.nonce,
+            self.value,
+            format_recipient(self.to.as_ref()),
+            self.gas_limit,
+            self.max_fee_per_gas,
+            self.max_priority_fee_per_gas,
+            self.input.len()
+        )
     }
 }
 
-fn parse_u64(value: &str) -> Result<u64, std::num::ParseIntError> {
-    value.strip_prefix("0x").map_or_else(
-        || value.parse::<u64>(),
-        |hex_str| u64::from_str_radix(hex_str, 16),
-    )
+impl CanonicalJson for EVMTransaction {
+    fn canonical_value(&self) -> Value {
+        json!({
+            "chain_id": int_value(self.chain_id),
+            "nonce": int_value(self.nonce),
+            "to": self.to.map(hex_value),
+            "value": int_value(self.value),
+            "input": hex_value(&self.input),
+            "gas_limit": int_value(self.gas_limit),
+            "max_fee_per_gas": int_value(self.max_fee_per_gas),
+            "max_priority_fee_per_gas": int_value(self.max_priority_fee_per_gas),
+            "access_list": canonical_access_list(&self.access_list),
+        })
+    }
 }
 
-fn parse_u128(value: &str) -> Result<u128, std::num::ParseIntError> {
-    value.strip_prefix("0x").map_or_else(
-        || value.parse::<u128>(),
-        |hex_str| u128::from_str_radix(hex_str, 16),
-    )
+impl Validate for EVMTransaction {
+    fn validate(&self) -> Vec<ValidationIssue> {
+        let mut issues = Vec::new();
+
+        if self.chain_id == 0 {
+            issues.push(ValidationIssue::error(
+                "chain_id",
+                "chain ID 0 is not replay protected",
+            ));
+        }
+        if self.gas_limit < INTRINSIC_GAS {
+            issues.push(ValidationIssue::error(
+                "gas_limit",
+                format!("gas limit is below the intrinsic gas of {}", INTRINSIC_GAS),
+            ));
+        }
+        if self.max_priority_fee_per_gas > self.max_fee_per_gas {
+            issues.push(ValidationIssue::error(
+                "max_priority_fee_per_gas",
+                "max priority fee per gas exceeds max fee per gas",
+            ));
+        }
+        if self.max_total_cost().is_none() {
+            issues.push(ValidationIssue::error(
+                "gas_limit",
+                "gas_limit * max_fee_per_gas + value overflows",
+            ));
+        }
+        if self.to.is_none() && self.input.is_empty() {
+            issues.push(ValidationIssue::warning(
+                "input",
+                "contract creation without init code",
+            ));
+        }
+
+        issues
+    }
 }
 
 #[cfg(test)]
@@ -165,8 +558,16 @@ mod tests {
     };
     use alloy_primitives::{b256, Signature};
 
-    use crate::evm::types::Signature as OmniSignature;
-    use crate::evm::{evm_transaction::EVMTransaction, utils::parse_eth_address};
+    use borsh::BorshDeserialize;
+    use rlp::RlpStream;
+
+    use crate::constants::EIP_1559_TYPE;
+    use crate::evm::types::{AccessListItem, Signature as OmniSignature};
+    use crate::evm::{
+        evm_transaction::{EVMDecodeError, EVMTransaction},
+        utils::parse_eth_address,
+    };
+    use crate::signature_error::SignatureError;
     const MAX_FEE_PER_GAS: u128 = 20_000_000_000;
     const MAX_PRIORITY_FEE_PER_GAS: u128 = 1_000_000_000;
     const GAS_LIMIT: u128 = 21_000;
@@ -218,6 +619,7 @@ mod tests {
         rlp_encoded.encode_for_signing(&mut buf);
 
         assert!(buf == rlp_bytes);
+        assert_eq!(tx.hash_for_signing(), rlp_encoded.signature_hash().0);
     }
 
     #[test]
@@ -404,4 +806,425 @@ mod tests {
                 .to_vec()
         );
     }
+
+    #[test]
+    fn test_from_json_with_numbers_and_access_list() {
+        let tx = r#"
+        {
+            "nonce": 7,
+            "data": "0x6080",
+            "maxPriorityFeePerGas": 1000000000,
+            "maxFeePerGas": "0x4a817c800",
+            "gas": "0x5208",
+            "chainId": 1,
+            "accessList": [
+                {
+                    "address": "0x525521d79134822a342d330bd91DA67976569aF1",
+                    "storageKeys": [
+                        "0x0000000000000000000000000000000000000000000000000000000000000001"
+                    ]
+                }
+            ]
+        }"#;
+
+        let evm_tx = EVMTransaction::from_json(tx).unwrap();
+
+        assert_eq!(evm_tx.chain_id, 1);
+        assert_eq!(evm_tx.nonce, 7);
+        assert_eq!(evm_tx.to, None);
+        assert_eq!(evm_tx.value, 0);
+        assert_eq!(evm_tx.input, vec![0x60, 0x80]);
+        assert_eq!(evm_tx.gas_limit, 21000);
+        assert_eq!(evm_tx.max_fee_per_gas, 0x4a817c800);
+        assert_eq!(evm_tx.max_priority_fee_per_gas, 1_000_000_000);
+        let mut storage_key = [0u8; 32];
+        storage_key[31] = 1;
+        assert_eq!(
+            evm_tx.access_list,
+            vec![AccessListItem::new(
+                address!("525521d79134822a342d330bd91DA67976569aF1")
+                    .0
+                    .into(),
+                vec![storage_key]
+            )]
+        );
+    }
+
+    #[test]
+    fn test_from_json_errors() {
+        let valid = r#"{"nonce": "1", "maxPriorityFeePerGas": "1", "maxFeePerGas": "1", "gasLimit": "21000", "chainId": "1"}"#;
+        assert!(EVMTransaction::from_json(valid).is_ok());
+
+        for (json, expected) in [
+            (
+                r#"{"maxPriorityFeePerGas": "1", "maxFeePerGas": "1", "gasLimit": "21000", "chainId": "1"}"#,
+                "missing field `nonce`",
+            ),
+            (
+                r#"{"nonce": "0xzz", "maxPriorityFeePerGas": "1", "maxFeePerGas": "1", "gasLimit": "21000", "chainId": "1"}"#,
+                "invalid quantity",
+            ),
+            (
+                r#"{"nonce": "1", "maxPriorityFeePerGas": "1", "maxFeePerGas": "1", "gasLimit": "21000", "chainId": "0x10000000000000000"}"#,
+                "does not fit in a u64",
+            ),
+            (
+                r#"{"nonce": "1", "to": "0x1234", "maxPriorityFeePerGas": "1", "maxFeePerGas": "1", "gasLimit": "21000", "chainId": "1"}"#,
+                "should be 20 bytes long",
+            ),
+            (
+                r#"{"nonce": "1", "input": "0x123", "maxPriorityFeePerGas": "1", "maxFeePerGas": "1", "gasLimit": "21000", "chainId": "1"}"#,
+                "invalid hex data",
+            ),
+            (
+                r#"{"nonce": "1", "maxPriorityFeePerGas": "1", "maxFeePerGas": "1", "gasLimit": "21000", "chainId": "1", "accessList": [{"address": "0x525521d79134822a342d330bd91DA67976569aF1", "storageKeys": ["0x01"]}]}"#,
+                "storage key should be 32 bytes long",
+            ),
+        ] {
+            let err = EVMTransaction::from_json(json).unwrap_err().to_string();
+            assert!(err.contains(expected), "{}: {}", json, err);
+        }
+    }
+
+    #[test]
+    fn test_effective_gas_price() {
+        let tx = EVMTransaction {
+            chain_id: 1,
+            nonce: 0,
+            to: None,
+            value: 0,
+            input: vec![],
+            gas_limit: GAS_LIMIT,
+            max_fee_per_gas: MAX_FEE_PER_GAS,
+            max_priority_fee_per_gas: MAX_PRIORITY_FEE_PER_GAS,
+            access_list: vec![],
+        };
+
+        // The full priority fee fits under the max fee.
+        assert_eq!(
+            tx.effective_gas_price(10_000_000_000),
+            Some(10_000_000_000 + MAX_PRIORITY_FEE_PER_GAS)
+        );
+        // The priority fee is capped by the max fee.
+        assert_eq!(
+            tx.effective_gas_price(19_500_000_000),
+            Some(MAX_FEE_PER_GAS)
+        );
+        // The base fee is too high for the transaction to be included.
+        assert_eq!(tx.effective_gas_price(MAX_FEE_PER_GAS + 1), None);
+    }
+
+    #[test]
+    fn test_max_total_cost() {
+        let mut tx = EVMTransaction {
+            chain_id: 1,
+            nonce: 0,
+            to: None,
+            value: 10000000000000000u128, // 0.01 ETH
+            input: vec![],
+            gas_limit: GAS_LIMIT,
+            max_fee_per_gas: MAX_FEE_PER_GAS,
+            max_priority_fee_per_gas: MAX_PRIORITY_FEE_PER_GAS,
+            access_list: vec![],
+        };
+
+        assert_eq!(
+            tx.max_total_cost(),
+            Some(GAS_LIMIT * MAX_FEE_PER_GAS + 10000000000000000u128)
+        );
+
+        tx.max_fee_per_gas = u128::MAX;
+        assert_eq!(tx.max_total_cost(), None);
+    }
+
+    #[test]
+    fn test_borsh_layout() {
+        let tx = EVMTransaction {
+            chain_id: 1,
+            nonce: 7,
+            to: Some([0x11; 20]),
+            value: 1_000,
+            input: vec![0xaa, 0xbb],
+            gas_limit: GAS_LIMIT,
+            max_fee_per_gas: MAX_FEE_PER_GAS,
+            max_priority_fee_per_gas: MAX_PRIORITY_FEE_PER_GAS,
+            access_list: vec![AccessListItem {
+                address: [0x22; 20],
+                storage_keys: vec![[0x33; 32]],
+            }],
+        };
+
+        // The fields in declaration order, as stored in contract state by previous versions.
+        let mut expected = vec![];
+        expected.extend_from_slice(&1u64.to_le_bytes());
+        expected.extend_from_slice(&7u64.to_le_bytes());
+        expected.push(1);
+        expected.extend_from_slice(&[0x11; 20]);
+        expected.extend_from_slice(&1_000u128.to_le_bytes());
+        expected.extend_from_slice(&[2, 0, 0, 0, 0xaa, 0xbb]);
+        expected.extend_from_slice(&GAS_LIMIT.to_le_bytes());
+        expected.extend_from_slice(&MAX_FEE_PER_GAS.to_le_bytes());
+        expected.extend_from_slice(&MAX_PRIORITY_FEE_PER_GAS.to_le_bytes());
+        expected.extend_from_slice(&[1, 0, 0, 0]);
+        expected.extend_from_slice(&[0x22; 20]);
+        expected.extend_from_slice(&[1, 0, 0, 0]);
+        expected.extend_from_slice(&[0x33; 32]);
+
+        let bytes = borsh::to_vec(&tx).unwrap();
+        assert_eq!(bytes, expected);
+        assert_eq!(EVMTransaction::try_from_slice(&bytes).unwrap(), tx);
+
+        // A contract creation stores `None` as a single byte.
+        let creation = EVMTransaction { to: None, ..tx };
+        let bytes = borsh::to_vec(&creation).unwrap();
+        assert_eq!(bytes[16], 0);
+        assert_eq!(bytes.len(), expected.len() - 20);
+        assert_eq!(EVMTransaction::try_from_slice(&bytes).unwrap(), creation);
+    }
+
+    #[test]
+    fn test_display_summary() {
+        let mut tx = EVMTransaction {
+            chain_id: 1,
+            nonce: 7,
+            to: Some(parse_eth_address(
+                "5aaeb6053f3e94c9b9a09f33669435e7ef1beaed",
+            )),
+            value: 1_000,
+            input: vec![0; 4],
+            gas_limit: GAS_LIMIT,
+            max_fee_per_gas: MAX_FEE_PER_GAS,
+            max_priority_fee_per_gas: MAX_PRIORITY_FEE_PER_GAS,
+            access_list: vec![],
+        };
+
+        assert_eq!(
+            tx.to_string(),
+            "EVM chain 1 nonce 7: 1000 wei to 0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed, \
+             gas limit 21000, max fee 20000000000 wei/gas, priority fee 1000000000 wei/gas, \
+             4 bytes of input"
+        );
+
+        tx.to = None;
+        assert!(tx.to_string().contains("1000 wei to contract creation,"));
+    }
+
+    #[test]
+    fn test_try_build_with_signature_validates_components() {
+        let tx = EVMTransaction {
+            chain_id: 1,
+            nonce: 0,
+            to: None,
+            value: 0,
+            input: vec![],
+            gas_limit: GAS_LIMIT,
+            max_fee_per_gas: MAX_FEE_PER_GAS,
+            max_priority_fee_per_gas: MAX_PRIORITY_FEE_PER_GAS,
+            access_list: vec![],
+        };
+        let signature = OmniSignature {
+            v: 1,
+            r: vec![1u8; 32],
+            s: vec![2u8; 32],
+        };
+
+        assert_eq!(
+            tx.try_build_with_signature(&signature).unwrap(),
+            tx.build_with_signature(&signature)
+        );
+
+        let short_r = OmniSignature {
+            r: vec![1u8; 31],
+            ..signature.clone()
+        };
+        assert_eq!(
+            tx.try_build_with_signature(&short_r),
+            Err(SignatureError::InvalidLength {
+                component: "r",
+                expected: 32,
+                actual: 31
+            })
+        );
+
+        let zero_s = OmniSignature {
+            s: vec![0u8; 32],
+            ..signature.clone()
+        };
+        assert_eq!(
+            tx.try_build_with_signature(&zero_s),
+            Err(SignatureError::ZeroComponent("s"))
+        );
+
+        let legacy_v = OmniSignature { v: 27, ..signature };
+        assert_eq!(
+            tx.try_build_with_signature(&legacy_v),
+            Err(SignatureError::InvalidRecoveryId(27))
+        );
+    }
+
+    #[test]
+    fn test_validate_reports_all_issues() {
+        use crate::validation::Validate;
+
+        let mut tx = EVMTransaction {
+            chain_id: 0,
+            nonce: 0,
+            to: None,
+            value: 0,
+            input: vec![],
+            gas_limit: 20_000,
+            max_fee_per_gas: 1,
+            max_priority_fee_per_gas: 2,
+            access_list: vec![],
+        };
+
+        let fields: Vec<_> = tx.validate().into_iter().map(|issue| issue.field).collect();
+        assert_eq!(
+            fields,
+            vec!["chain_id", "gas_limit", "max_priority_fee_per_gas", "input"]
+        );
+
+        tx.chain_id = 1;
+        tx.gas_limit = GAS_LIMIT;
+        tx.max_fee_per_gas = MAX_FEE_PER_GAS;
+        tx.max_priority_fee_per_gas = MAX_PRIORITY_FEE_PER_GAS;
+        tx.to = Some(parse_eth_address(
+            "d8dA6BF26964aF9D7eEd9e03E53415D37aA96045",
+        ));
+        assert!(tx.validate().is_empty());
+    }
+
+    #[test]
+    fn test_decode_roundtrip() {
+        let tx = EVMTransaction {
+            chain_id: 11155111,
+            nonce: 300,
+            to: Some(parse_eth_address(
+                "d8dA6BF26964aF9D7eEd9e03E53415D37aA96045",
+            )),
+            value: 10000000000000000,
+            input: hex!("a22cb465").to_vec(),
+            gas_limit: GAS_LIMIT,
+            max_fee_per_gas: MAX_FEE_PER_GAS,
+            max_priority_fee_per_gas: MAX_PRIORITY_FEE_PER_GAS,
+            access_list: vec![AccessListItem::new(
+                [0x11; 20],
+                vec![[0x22; 32], [0x33; 32]],
+            )],
+        };
+        let signature = OmniSignature {
+            v: 1,
+            r: vec![0x44; 32],
+            s: vec![0x55; 32],
+        };
+
+        assert_eq!(
+            EVMTransaction::decode(&tx.build_for_signing()),
+            Ok((tx.clone(), None))
+        );
+        assert_eq!(
+            EVMTransaction::decode(&tx.build_with_signature(&signature)),
+            Ok((tx.clone(), Some(signature)))
+        );
+
+        let creation = EVMTransaction {
+            to: None,
+            access_list: vec![],
+            ..tx
+        };
+        assert_eq!(
+            EVMTransaction::decode(&creation.build_for_signing()),
+            Ok((creation, None))
+        );
+    }
+
+    #[test]
+    fn test_decode_pads_short_signature_scalars() {
+        let tx = EVMTransaction {
+            chain_id: 1,
+            nonce: 0,
+            to: None,
+            value: 0,
+            input: vec![],
+            gas_limit: GAS_LIMIT,
+            max_fee_per_gas: MAX_FEE_PER_GAS,
+            max_priority_fee_per_gas: MAX_PRIORITY_FEE_PER_GAS,
+            access_list: vec![],
+        };
+        // Canonical RLP drops the leading zeros of r and s.
+        let signed = tx.build_with_signature(&OmniSignature {
+            v: 0,
+            r: vec![0x44; 31],
+            s: vec![0x55; 30],
+        });
+
+        let (_, signature) = EVMTransaction::decode(&signed).unwrap();
+        let signature = signature.unwrap();
+        assert_eq!(signature.r, [&[0u8][..], &[0x44; 31]].concat());
+        assert_eq!(signature.s, [&[0u8; 2][..], &[0x55; 30]].concat());
+
+        let too_long = tx.build_with_signature(&OmniSignature {
+            v: 0,
+            r: vec![0x44; 33],
+            s: vec![0x55; 32],
+        });
+        assert_eq!(
+            EVMTransaction::decode(&too_long),
+            Err(EVMDecodeError::InvalidField {
+                field: "r",
+                reason: "expected at most 32 bytes, got 33".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn test_decode_rejects_invalid_payloads() {
+        let tx = EVMTransaction {
+            chain_id: 1,
+            nonce: 0,
+            to: None,
+            value: 0,
+            input: vec![],
+            gas_limit: GAS_LIMIT,
+            max_fee_per_gas: MAX_FEE_PER_GAS,
+            max_priority_fee_per_gas: MAX_PRIORITY_FEE_PER_GAS,
+            access_list: vec![],
+        };
+        let encoded = tx.build_for_signing();
+
+        assert_eq!(EVMTransaction::decode(&[]), Err(EVMDecodeError::Empty));
+        // Wrong type byte.
+        assert_eq!(
+            EVMTransaction::decode(&[&[0x01], &encoded[1..]].concat()),
+            Err(EVMDecodeError::UnsupportedType(0x01))
+        );
+        // Trailing data.
+        assert!(matches!(
+            EVMTransaction::decode(&[&encoded[..], &[0x00]].concat()),
+            Err(EVMDecodeError::InvalidEncoding(_))
+        ));
+        // Truncated list.
+        assert!(matches!(
+            EVMTransaction::decode(&encoded[..encoded.len() - 1]),
+            Err(EVMDecodeError::InvalidEncoding(_))
+        ));
+        // A 19-byte recipient.
+        let mut rlp_stream = RlpStream::new();
+        rlp_stream.append(&EIP_1559_TYPE);
+        rlp_stream.begin_list(9);
+        for _ in 0..5 {
+            rlp_stream.append(&1u8);
+        }
+        rlp_stream.append(&vec![0x11u8; 19]);
+        rlp_stream.append(&0u8);
+        rlp_stream.append(&Vec::<u8>::new());
+        rlp_stream.begin_list(0);
+        assert_eq!(
+            EVMTransaction::decode(&rlp_stream.out()),
+            Err(EVMDecodeError::InvalidField {
+                field: "to",
+                reason: "expected 20 bytes, got 19".to_string(),
+            })
+        );
+    }
 }
@@ -1,20 +1,71 @@
+use std::fmt;
+
 use crate::transaction_builder::TxBuilder;
 
 use super::{
+    chain_context::{ChainContext, ChainContextError},
+    chain_profile::ChainProfile,
     evm_transaction::EVMTransaction,
+    evm_transaction_eip2930::EVMTransactionEIP2930,
     types::{AccessList, Address},
+    utils::{parse_address_hex, parse_checksummed_address, parse_u128, AddressError},
 };
 
+/// Errors returned by [`EVMTransactionBuilder::try_build`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EVMBuildError {
+    /// A mandatory field was not set.
+    MissingField(&'static str),
+    /// The access list has more entries than allowed by the chain profile.
+    AccessListTooLarge { entries: usize, limit: usize },
+    /// The input is larger than allowed by the chain profile.
+    CalldataTooLarge { size: usize, limit: usize },
+    /// An address given as a string is invalid or not properly checksummed.
+    InvalidAddress(AddressError),
+    /// The input given as a hex string is invalid.
+    InvalidInput(String),
+    /// The value given as a string is not a valid amount of wei.
+    InvalidValue(String),
+}
+
+impl fmt::Display for EVMBuildError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingField(field) => write!(f, "{} is mandatory", field),
+            Self::AccessListTooLarge { entries, limit } => write!(
+                f,
+                "access_list has {} entries, the limit is {}",
+                entries, limit
+            ),
+            Self::CalldataTooLarge { size, limit } => {
+                write!(f, "input is {} bytes long, the limit is {}", size, limit)
+            }
+            Self::InvalidAddress(error) => write!(f, "to: {}", error),
+            Self::InvalidInput(reason) | Self::InvalidValue(reason) => {
+                write!(f, "{}", reason)
+            }
+        }
+    }
+}
+
+impl std::error::Error for EVMBuildError {}
+
+#[derive(Clone)]
 pub struct EVMTransactionBuilder {
     chain_id: Option<u64>,
     nonce: Option<u64>,
     to: Option<Address>,
+    to_hex: Option<String>,
     value: Option<u128>,
+    value_str: Option<String>,
     input: Option<Vec<u8>>,
+    input_hex: Option<String>,
     gas_limit: Option<u128>,
     max_fee_per_gas: Option<u128>,
     max_priority_fee_per_gas: Option<u128>,
+    gas_price: Option<u128>,
     access_list: Option<AccessList>,
+    profile: ChainProfile,
 }
 
 impl Default for EVMTransactionBuilder {
@@ -28,9 +79,13 @@ impl TxBuilder<EVMTransaction> for EVMTransactionBuilder {
         EVMTransaction {
             chain_id: self.chain_id.expect("chain_id is mandatory"),
             nonce: self.nonce.expect("nonce is mandatory"),
-            to: self.to,
-            value: self.value.unwrap_or_default(),
-            input: self.input.clone().unwrap_or_default(),
+            to: self.to.or_else(|| {
+                self.to_hex
+                    .as_deref()
+                    .map(|to| parse_address_hex(to).expect("to should be a valid address"))
+            }),
+            value: self.checked_value().unwrap_or_else(|e| panic!("{}", e)),
+            input: self.checked_input().unwrap_or_else(|e| panic!("{}", e)),
             gas_limit: self.gas_limit.expect("gas_limit is mandatory"),
             max_fee_per_gas: self.max_fee_per_gas.expect("max_fee_per_gas is mandatory"),
             max_priority_fee_per_gas: self.max_priority_fee_per_gas.unwrap_or_default(),
@@ -45,12 +100,128 @@ impl EVMTransactionBuilder {
             chain_id: None,
             nonce: None,
             to: None,
+            to_hex: None,
             value: None,
+            value_str: None,
             input: None,
+            input_hex: None,
             gas_limit: None,
             max_fee_per_gas: None,
             max_priority_fee_per_gas: None,
+            gas_price: None,
             access_list: None,
+            profile: ChainProfile::new(),
+        }
+    }
+
+    /// Builds the transaction, returning an error instead of panicking if a mandatory field is
+    /// missing or a limit of the chain profile is exceeded.
+    pub fn try_build(&self) -> Result<EVMTransaction, EVMBuildError> {
+        let (to, input, access_list) = self.checked_common_fields()?;
+
+        Ok(EVMTransaction {
+            chain_id: self
+                .chain_id
+                .ok_or(EVMBuildError::MissingField("chain_id"))?,
+            nonce: self.nonce.ok_or(EVMBuildError::MissingField("nonce"))?,
+            to,
+            value: self.checked_value()?,
+            input,
+            gas_limit: self
+                .gas_limit
+                .ok_or(EVMBuildError::MissingField("gas_limit"))?,
+            max_fee_per_gas: self
+                .max_fee_per_gas
+                .ok_or(EVMBuildError::MissingField("max_fee_per_gas"))?,
+            max_priority_fee_per_gas: self.max_priority_fee_per_gas.unwrap_or_default(),
+            access_list,
+        })
+    }
+
+    /// Builds an EIP-2930 (type 1) transaction from the gas price and the common fields, with
+    /// the same checks as [`Self::try_build`]. The EIP-1559 fee fields are ignored.
+    pub fn try_build_eip2930(&self) -> Result<EVMTransactionEIP2930, EVMBuildError> {
+        let (to, input, access_list) = self.checked_common_fields()?;
+
+        Ok(EVMTransactionEIP2930 {
+            chain_id: self
+                .chain_id
+                .ok_or(EVMBuildError::MissingField("chain_id"))?,
+            nonce: self.nonce.ok_or(EVMBuildError::MissingField("nonce"))?,
+            gas_price: self
+                .gas_price
+                .ok_or(EVMBuildError::MissingField("gas_price"))?,
+            gas_limit: self
+                .gas_limit
+                .ok_or(EVMBuildError::MissingField("gas_limit"))?,
+            to,
+            value: self.checked_value()?,
+            input,
+            access_list,
+        })
+    }
+
+    /// Same as [`Self::try_build_eip2930`], but panics if a field is missing or invalid.
+    pub fn build_eip2930(&self) -> EVMTransactionEIP2930 {
+        self.try_build_eip2930().unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Resolves the recipient and checks the input and access list against the chain profile.
+    fn checked_common_fields(
+        &self,
+    ) -> Result<(Option<Address>, Vec<u8>, AccessList), EVMBuildError> {
+        let input = self.checked_input()?;
+        let access_list = self.access_list.clone().unwrap_or_default();
+
+        let to = match (&self.to, &self.to_hex) {
+            (None, Some(to)) if self.profile.require_address_checksum => Some(
+                parse_checksummed_address(to, self.profile.allow_lowercase_addresses)
+                    .map_err(EVMBuildError::InvalidAddress)?,
+            ),
+            (None, Some(to)) => Some(parse_address_hex(to).map_err(EVMBuildError::InvalidAddress)?),
+            (to, _) => *to,
+        };
+
+        if let Some(limit) = self.profile.max_access_list_entries {
+            if access_list.len() > limit {
+                return Err(EVMBuildError::AccessListTooLarge {
+                    entries: access_list.len(),
+                    limit,
+                });
+            }
+        }
+
+        if let Some(limit) = self.profile.max_calldata_bytes {
+            if input.len() > limit {
+                return Err(EVMBuildError::CalldataTooLarge {
+                    size: input.len(),
+                    limit,
+                });
+            }
+        }
+
+        Ok((to, input, access_list))
+    }
+
+    /// Returns the input, parsing it if it was given as a hex string.
+    fn checked_input(&self) -> Result<Vec<u8>, EVMBuildError> {
+        match (&self.input, &self.input_hex) {
+            (None, Some(input)) => {
+                hex::decode(input.strip_prefix("0x").unwrap_or(input)).map_err(|e| {
+                    EVMBuildError::InvalidInput(format!("Invalid input {}: {}", input, e))
+                })
+            }
+            (input, _) => Ok(input.clone().unwrap_or_default()),
+        }
+    }
+
+    /// Returns the value, parsing it if it was given as a string.
+    fn checked_value(&self) -> Result<u128, EVMBuildError> {
+        match (self.value, &self.value_str) {
+            (None, Some(value)) => parse_u128(value).map_err(|e| {
+                EVMBuildError::InvalidValue(format!("Invalid value {}: {}", value, e))
+            }),
+            (value, _) => Ok(value.unwrap_or_default()),
         }
     }
 
@@ -67,20 +238,54 @@ impl EVMTransactionBuilder {
     }
 
     /// Address of the recipient.
-    pub const fn to(mut self, to: Address) -> Self {
+    pub fn to(mut self, to: Address) -> Self {
         self.to = Some(to);
+        self.to_hex = None;
+        self
+    }
+
+    /// Address of the recipient as a hex string, with or without the `0x` prefix.
+    ///
+    /// The address is parsed when building, [`Self::try_build`] also checks its EIP-55 checksum
+    /// if required by the chain profile.
+    pub fn to_hex(mut self, to: &str) -> Self {
+        self.to = None;
+        self.to_hex = Some(to.to_string());
         self
     }
 
     /// Value attached to the transaction.
     pub const fn value(mut self, value: u128) -> Self {
+        // Takes precedence over a value given as a string.
         self.value = Some(value);
         self
     }
 
+    /// Value attached to the transaction in wei, as a `0x` prefixed hex or a decimal string,
+    /// e.g. the `value` of a JSON-RPC request.
+    ///
+    /// The value is parsed when building, [`Self::try_build`] returns an error if it is
+    /// invalid.
+    pub fn value_wei_str(mut self, value: &str) -> Self {
+        self.value = None;
+        self.value_str = Some(value.to_string());
+        self
+    }
+
     /// Input data of the transaction.
     pub fn input(mut self, input: Vec<u8>) -> Self {
         self.input = Some(input);
+        self.input_hex = None;
+        self
+    }
+
+    /// Input data of the transaction as a hex string, with or without the `0x` prefix.
+    ///
+    /// The input is parsed when building, [`Self::try_build`] returns an error if it is not
+    /// valid hex.
+    pub fn input_hex(mut self, input: &str) -> Self {
+        self.input = None;
+        self.input_hex = Some(input.to_string());
         self
     }
 
@@ -102,11 +307,50 @@ impl EVMTransactionBuilder {
         self
     }
 
+    /// Gas price of the transaction, only used by EIP-2930 transactions.
+    pub const fn gas_price(mut self, gas_price: u128) -> Self {
+        self.gas_price = Some(gas_price);
+        self
+    }
+
     /// Access list of the transaction.
     pub fn access_list(mut self, access_list: AccessList) -> Self {
         self.access_list = Some(access_list);
         self
     }
+
+    /// Chain profile whose limits are enforced by [`Self::try_build`].
+    pub const fn profile(mut self, profile: ChainProfile) -> Self {
+        self.profile = profile;
+        self
+    }
+
+    /// Fills in the chain ID, the nonce of `from` and the fees which were not set, from
+    /// `context`. Fields already set are kept, and the fees are only fetched if one is missing.
+    pub async fn fill_from<C: ChainContext + ?Sized>(
+        mut self,
+        context: &C,
+        from: Address,
+    ) -> Result<Self, ChainContextError> {
+        if self.chain_id.is_none() {
+            self.chain_id = Some(context.fetch_chain_id().await?);
+        }
+        if self.nonce.is_none() {
+            self.nonce = Some(context.fetch_nonce(from).await?);
+        }
+        if self.max_fee_per_gas.is_none()
+            || self.max_priority_fee_per_gas.is_none()
+            || self.gas_price.is_none()
+        {
+            let fees = context.fetch_fee_estimates().await?;
+            self.max_fee_per_gas.get_or_insert(fees.max_fee_per_gas);
+            self.max_priority_fee_per_gas
+                .get_or_insert(fees.max_priority_fee_per_gas);
+            self.gas_price.get_or_insert(fees.gas_price);
+        }
+
+        Ok(self)
+    }
 }
 
 #[cfg(test)]
@@ -119,7 +363,12 @@ mod tests {
     };
 
     use crate::{
-        evm::{evm_transaction_builder::EVMTransactionBuilder, utils::parse_eth_address},
+        evm::{
+            chain_profile::ChainProfile,
+            evm_transaction_builder::{EVMBuildError, EVMTransactionBuilder},
+            types::AccessListItem,
+            utils::parse_eth_address,
+        },
         transaction_builder::TxBuilder,
     };
 
@@ -224,4 +473,188 @@ mod tests {
 
         assert!(rlp_encoded_encoded_for_signing == rlp_bytes);
     }
+
+    #[test]
+    fn test_try_build_missing_field() {
+        let result = EVMTransactionBuilder::new()
+            .nonce(0)
+            .gas_limit(GAS_LIMIT)
+            .max_fee_per_gas(MAX_FEE_PER_GAS)
+            .try_build();
+
+        assert_eq!(result, Err(EVMBuildError::MissingField("chain_id")));
+    }
+
+    #[test]
+    fn test_try_build_enforces_profile_limits() {
+        let builder = EVMTransactionBuilder::new()
+            .chain_id(1)
+            .nonce(0)
+            .gas_limit(GAS_LIMIT)
+            .max_fee_per_gas(MAX_FEE_PER_GAS)
+            .input(vec![0u8; 64])
+            .access_list(vec![
+                AccessListItem::new([1u8; 20], vec![]),
+                AccessListItem::new([2u8; 20], vec![[0u8; 32]]),
+            ]);
+
+        assert!(builder
+            .clone()
+            .profile(
+                ChainProfile::new()
+                    .max_access_list_entries(2)
+                    .max_calldata_bytes(64)
+            )
+            .try_build()
+            .is_ok());
+
+        let err = builder
+            .clone()
+            .profile(ChainProfile::new().max_access_list_entries(1))
+            .try_build()
+            .unwrap_err();
+        assert_eq!(
+            err,
+            EVMBuildError::AccessListTooLarge {
+                entries: 2,
+                limit: 1
+            }
+        );
+        assert_eq!(err.to_string(), "access_list has 2 entries, the limit is 1");
+
+        let err = builder
+            .profile(ChainProfile::new().max_calldata_bytes(32))
+            .try_build()
+            .unwrap_err();
+        assert_eq!(
+            err,
+            EVMBuildError::CalldataTooLarge {
+                size: 64,
+                limit: 32
+            }
+        );
+        assert_eq!(err.to_string(), "input is 64 bytes long, the limit is 32");
+    }
+
+    #[test]
+    fn test_try_build_enforces_address_checksum() {
+        let checksummed = "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed";
+        let lowercase = checksummed.to_lowercase();
+        let builder = EVMTransactionBuilder::new()
+            .chain_id(1)
+            .nonce(0)
+            .gas_limit(GAS_LIMIT)
+            .max_fee_per_gas(MAX_FEE_PER_GAS);
+
+        // Without a profile requirement, any case is accepted.
+        assert!(builder.clone().to_hex(&lowercase).try_build().is_ok());
+
+        let strict = builder
+            .clone()
+            .profile(ChainProfile::new().require_address_checksum());
+        let tx = strict.clone().to_hex(checksummed).try_build().unwrap();
+        assert_eq!(
+            tx.to,
+            Some(parse_eth_address(
+                "5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed"
+            ))
+        );
+        assert!(matches!(
+            strict.clone().to_hex(&lowercase).try_build(),
+            Err(EVMBuildError::InvalidAddress(_))
+        ));
+        assert!(matches!(
+            strict
+                .to_hex("0x5AAeb6053F3E94C9b9A09f33669435E7Ef1BeAed")
+                .try_build(),
+            Err(EVMBuildError::InvalidAddress(_))
+        ));
+
+        let lenient = builder.profile(
+            ChainProfile::new()
+                .require_address_checksum()
+                .allow_lowercase_addresses(),
+        );
+        assert!(lenient.to_hex(&lowercase).try_build().is_ok());
+    }
+
+    #[test]
+    fn test_hex_setters() {
+        let builder = EVMTransactionBuilder::new()
+            .chain_id(1)
+            .nonce(0)
+            .gas_limit(GAS_LIMIT)
+            .max_fee_per_gas(MAX_FEE_PER_GAS);
+
+        let tx = builder
+            .clone()
+            .to_hex("0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045")
+            .input_hex("0xa9059cbb")
+            .value_wei_str("0x2386f26fc10000")
+            .try_build()
+            .unwrap();
+        assert_eq!(
+            tx,
+            builder
+                .clone()
+                .to(parse_eth_address(
+                    "d8dA6BF26964aF9D7eEd9e03E53415D37aA96045"
+                ))
+                .input(vec![0xa9, 0x05, 0x9c, 0xbb])
+                .value(10_000_000_000_000_000)
+                .build()
+        );
+        assert_eq!(builder.clone().value_wei_str("1000").build().value, 1000);
+        // The last setter wins.
+        assert_eq!(
+            builder.clone().input_hex("zz").input(vec![1]).build().input,
+            vec![1]
+        );
+
+        assert_eq!(
+            builder.clone().input_hex("0xabc").try_build(),
+            Err(EVMBuildError::InvalidInput(
+                "Invalid input 0xabc: Odd number of digits".to_string()
+            ))
+        );
+        assert!(matches!(
+            builder.clone().value_wei_str("0xg").try_build(),
+            Err(EVMBuildError::InvalidValue(_))
+        ));
+        assert!(matches!(
+            builder
+                .gas_price(MAX_FEE_PER_GAS)
+                .value_wei_str("-1")
+                .try_build_eip2930(),
+            Err(EVMBuildError::InvalidValue(_))
+        ));
+    }
+
+    #[test]
+    fn test_try_build_eip2930() {
+        let builder = EVMTransactionBuilder::new()
+            .chain_id(1)
+            .nonce(0)
+            .gas_limit(GAS_LIMIT)
+            .to(parse_eth_address(
+                "d8dA6BF26964aF9D7eEd9e03E53415D37aA96045",
+            ))
+            .access_list(vec![AccessListItem::new([1u8; 20], vec![[2u8; 32]])]);
+
+        assert_eq!(
+            builder.try_build_eip2930(),
+            Err(EVMBuildError::MissingField("gas_price"))
+        );
+
+        let tx = builder
+            .gas_price(MAX_FEE_PER_GAS)
+            .try_build_eip2930()
+            .unwrap();
+        assert_eq!(tx.gas_price, MAX_FEE_PER_GAS);
+        assert_eq!(
+            tx.access_list,
+            vec![AccessListItem::new([1u8; 20], vec![[2u8; 32]])]
+        );
+        assert_eq!(tx.build_for_signing()[0], 0x01);
+    }
 }
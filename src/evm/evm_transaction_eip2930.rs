@@ -0,0 +1,187 @@
+use std::fmt;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use rlp::RlpStream;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::canonical_json::{hex_value, int_value, CanonicalJson};
+use crate::constants::EIP_2930_TYPE;
+
+use super::evm_transaction::{canonical_access_list, encode_access_list};
+use super::types::{AccessList, Address, Signature};
+use super::utils::{format_recipient, keccak256};
+
+/// An EIP-2930 (type 1) transaction: a legacy gas price along with an access list.
+///
+/// Prefer [`EVMTransaction`](super::evm_transaction::EVMTransaction) (EIP-1559) unless the
+/// chain or the tooling only supports type 1 transactions.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub struct EVMTransactionEIP2930 {
+    pub chain_id: u64,
+    pub nonce: u64,
+    pub gas_price: u128,
+    pub gas_limit: u128,
+    pub to: Option<Address>,
+    pub value: u128,
+    pub input: Vec<u8>,
+    pub access_list: AccessList,
+}
+
+impl EVMTransactionEIP2930 {
+    pub fn build_for_signing(&self) -> Vec<u8> {
+        let mut rlp_stream = RlpStream::new();
+
+        rlp_stream.append(&EIP_2930_TYPE);
+
+        rlp_stream.begin_unbounded_list();
+
+        self.encode_fields(&mut rlp_stream);
+
+        rlp_stream.finalize_unbounded_list();
+
+        rlp_stream.out().to_vec()
+    }
+
+    /// Returns the keccak256 hash of [`Self::build_for_signing`], the digest signed by the
+    /// sender.
+    pub fn hash_for_signing(&self) -> [u8; 32] {
+        keccak256(&self.build_for_signing())
+    }
+
+    pub fn build_with_signature(&self, signature: &Signature) -> Vec<u8> {
+        let mut rlp_stream = RlpStream::new();
+
+        rlp_stream.append(&EIP_2930_TYPE);
+
+        rlp_stream.begin_unbounded_list();
+
+        self.encode_fields(&mut rlp_stream);
+
+        rlp_stream.append(&signature.v);
+        rlp_stream.append(&signature.r);
+        rlp_stream.append(&signature.s);
+
+        rlp_stream.finalize_unbounded_list();
+
+        rlp_stream.out().to_vec()
+    }
+
+    fn encode_fields(&self, rlp_stream: &mut RlpStream) {
+        let to: Vec<u8> = self.to.map_or(vec![], |to| to.to_vec());
+
+        rlp_stream.append(&self.chain_id);
+        rlp_stream.append(&self.nonce);
+        rlp_stream.append(&self.gas_price);
+        rlp_stream.append(&self.gas_limit);
+        rlp_stream.append(&to);
+        rlp_stream.append(&self.value);
+        rlp_stream.append(&self.input);
+
+        encode_access_list(rlp_stream, &self.access_list);
+    }
+}
+
+/// A one-line summary of the transaction, for logs.
+impl fmt::Display for EVMTransactionEIP2930 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "EVM chain {} nonce {}: {} wei to {}, gas limit {}, gas price {} wei/gas, \
+             {} bytes of input",
+            self.chain_id,
+            self.nonce,
+            self.value,
+            format_recipient(self.to.as_ref()),
+            self.gas_limit,
+            self.gas_price,
+            self.input.len()
+        )
+    }
+}
+
+impl CanonicalJson for EVMTransactionEIP2930 {
+    fn canonical_value(&self) -> Value {
+        json!({
+            "chain_id": int_value(self.chain_id),
+            "nonce": int_value(self.nonce),
+            "gas_price": int_value(self.gas_price),
+            "gas_limit": int_value(self.gas_limit),
+            "to": self.to.map(hex_value),
+            "value": int_value(self.value),
+            "input": hex_value(&self.input),
+            "access_list": canonical_access_list(&self.access_list),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::{
+        consensus::{SignableTransaction, TxEip2930},
+        eips::eip2930::{AccessList, AccessListItem},
+        primitives::{address, b256, hex, Bytes, Signature, U256},
+    };
+
+    use crate::evm::types::{AccessListItem as OmniAccessListItem, Signature as OmniSignature};
+    use crate::evm::{evm_transaction_eip2930::EVMTransactionEIP2930, utils::parse_eth_address};
+
+    #[test]
+    fn test_build_eip2930_against_alloy() {
+        let to = address!("6069a6c32cf691f5982febae4faf8a6f3ab2f0f6");
+        let storage_key = b256!("0000000000000000000000000000000000000000000000000000000000000001");
+        let input: Bytes = hex!("a22cb465").into();
+
+        let tx = TxEip2930 {
+            chain_id: 1,
+            nonce: 0x42,
+            gas_price: 0x4a817c800,
+            gas_limit: 44386,
+            to: to.into(),
+            value: U256::from(1u128),
+            input: input.clone(),
+            access_list: AccessList(vec![AccessListItem {
+                address: to,
+                storage_keys: vec![storage_key],
+            }]),
+        };
+
+        let tx_omni = EVMTransactionEIP2930 {
+            chain_id: 1,
+            nonce: 0x42,
+            gas_price: 0x4a817c800,
+            gas_limit: 44386,
+            to: Some(parse_eth_address(
+                "6069a6c32cf691f5982febae4faf8a6f3ab2f0f6",
+            )),
+            value: 1,
+            input: input.to_vec(),
+            access_list: vec![OmniAccessListItem::new(to.0.into(), vec![storage_key.0])],
+        };
+
+        let mut tx_encoded = vec![];
+        tx.encode_for_signing(&mut tx_encoded);
+        assert_eq!(tx_encoded, tx_omni.build_for_signing());
+
+        let sig = Signature::from_scalars_and_parity(
+            b256!("840cfc572845f5786e702984c2a582528cad4b49b2a10b9db1be7fca90058565"),
+            b256!("25e7109ceb98168d95b09b18bbf6b685130e0562f233877d492b94eee0c5b6d1"),
+            true,
+        )
+        .unwrap();
+
+        let mut tx_encoded_with_signature: Vec<u8> = vec![];
+        tx.encode_with_signature(&sig, &mut tx_encoded_with_signature, false);
+
+        let signature = OmniSignature {
+            v: sig.v().to_u64(),
+            r: sig.r().to_be_bytes::<32>().to_vec(),
+            s: sig.s().to_be_bytes::<32>().to_vec(),
+        };
+
+        assert_eq!(
+            tx_encoded_with_signature,
+            tx_omni.build_with_signature(&signature)
+        );
+    }
+}
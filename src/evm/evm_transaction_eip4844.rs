@@ -0,0 +1,282 @@
+use std::fmt;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use rlp::RlpStream;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::canonical_json::{hex_value, int_value, CanonicalJson};
+use crate::constants::EIP_4844_TYPE;
+use crate::validation::{Validate, ValidationIssue};
+
+use super::evm_transaction::{canonical_access_list, encode_access_list};
+use super::types::{AccessList, Address, Signature};
+use super::utils::{keccak256, to_checksum_address};
+
+/// First byte of a blob versioned hash committing to a KZG commitment (EIP-4844).
+pub const VERSIONED_HASH_VERSION_KZG: u8 = 0x01;
+
+/// An EIP-4844 (type 3) transaction: an EIP-1559 transaction carrying blobs.
+///
+/// Only the versioned hashes of the blobs are part of the signed transaction. The blobs, along
+/// with their KZG commitments and proofs, are sent next to it in the network (sidecar) form,
+/// which is not built by this crate.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub struct EVMTransactionEIP4844 {
+    pub chain_id: u64,
+    pub nonce: u64,
+    /// Blob transactions cannot create contracts, so the recipient is mandatory.
+    pub to: Address,
+    pub value: u128,
+    pub input: Vec<u8>,
+    pub gas_limit: u128,
+    pub max_fee_per_gas: u128,
+    pub max_priority_fee_per_gas: u128,
+    pub access_list: AccessList,
+    pub max_fee_per_blob_gas: u128,
+    pub blob_versioned_hashes: Vec<[u8; 32]>,
+}
+
+impl EVMTransactionEIP4844 {
+    pub fn build_for_signing(&self) -> Vec<u8> {
+        let mut rlp_stream = RlpStream::new();
+
+        rlp_stream.append(&EIP_4844_TYPE);
+
+        rlp_stream.begin_unbounded_list();
+
+        self.encode_fields(&mut rlp_stream);
+
+        rlp_stream.finalize_unbounded_list();
+
+        rlp_stream.out().to_vec()
+    }
+
+    /// Returns the keccak256 hash of [`Self::build_for_signing`], the digest signed by the
+    /// sender.
+    pub fn hash_for_signing(&self) -> [u8; 32] {
+        keccak256(&self.build_for_signing())
+    }
+
+    pub fn build_with_signature(&self, signature: &Signature) -> Vec<u8> {
+        let mut rlp_stream = RlpStream::new();
+
+        rlp_stream.append(&EIP_4844_TYPE);
+
+        rlp_stream.begin_unbounded_list();
+
+        self.encode_fields(&mut rlp_stream);
+
+        rlp_stream.append(&signature.v);
+        rlp_stream.append(&signature.r);
+        rlp_stream.append(&signature.s);
+
+        rlp_stream.finalize_unbounded_list();
+
+        rlp_stream.out().to_vec()
+    }
+
+    fn encode_fields(&self, rlp_stream: &mut RlpStream) {
+        rlp_stream.append(&self.chain_id);
+        rlp_stream.append(&self.nonce);
+        rlp_stream.append(&self.max_priority_fee_per_gas);
+        rlp_stream.append(&self.max_fee_per_gas);
+        rlp_stream.append(&self.gas_limit);
+        rlp_stream.append(&self.to.to_vec());
+        rlp_stream.append(&self.value);
+        rlp_stream.append(&self.input);
+
+        encode_access_list(rlp_stream, &self.access_list);
+
+        rlp_stream.append(&self.max_fee_per_blob_gas);
+
+        rlp_stream.begin_unbounded_list();
+        for versioned_hash in &self.blob_versioned_hashes {
+            rlp_stream.append(&versioned_hash.to_vec());
+        }
+        rlp_stream.finalize_unbounded_list();
+    }
+}
+
+/// A one-line summary of the transaction, for logs.
+impl fmt::Display for EVMTransactionEIP4844 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "EVM chain {} nonce {}: {} wei to {}, gas limit {}, max fee {} wei/gas, \
+             priority fee {} wei/gas, {} blobs at max {} wei/blob gas, {} bytes of input",
+            self.chain_id,
+            self.nonce,
+            self.value,
+            to_checksum_address(&self.to),
+            self.gas_limit,
+            self.max_fee_per_gas,
+            self.max_priority_fee_per_gas,
+            self.blob_versioned_hashes.len(),
+            self.max_fee_per_blob_gas,
+            self.input.len()
+        )
+    }
+}
+
+impl CanonicalJson for EVMTransactionEIP4844 {
+    fn canonical_value(&self) -> Value {
+        json!({
+            "chain_id": int_value(self.chain_id),
+            "nonce": int_value(self.nonce),
+            "to": hex_value(self.to),
+            "value": int_value(self.value),
+            "input": hex_value(&self.input),
+            "gas_limit": int_value(self.gas_limit),
+            "max_fee_per_gas": int_value(self.max_fee_per_gas),
+            "max_priority_fee_per_gas": int_value(self.max_priority_fee_per_gas),
+            "access_list": canonical_access_list(&self.access_list),
+            "max_fee_per_blob_gas": int_value(self.max_fee_per_blob_gas),
+            "blob_versioned_hashes": self
+                .blob_versioned_hashes
+                .iter()
+                .map(hex_value)
+                .collect::<Vec<_>>(),
+        })
+    }
+}
+
+impl Validate for EVMTransactionEIP4844 {
+    fn validate(&self) -> Vec<ValidationIssue> {
+        let mut issues = Vec::new();
+
+        if self.chain_id == 0 {
+            issues.push(ValidationIssue::error(
+                "chain_id",
+                "chain ID 0 is not replay protected",
+            ));
+        }
+        if self.max_priority_fee_per_gas > self.max_fee_per_gas {
+            issues.push(ValidationIssue::error(
+                "max_priority_fee_per_gas",
+                "max priority fee per gas exceeds max fee per gas",
+            ));
+        }
+        if self.blob_versioned_hashes.is_empty() {
+            issues.push(ValidationIssue::error(
+                "blob_versioned_hashes",
+                "a blob transaction must carry at least one blob",
+            ));
+        }
+        for (i, versioned_hash) in self.blob_versioned_hashes.iter().enumerate() {
+            if versioned_hash[0] != VERSIONED_HASH_VERSION_KZG {
+                issues.push(ValidationIssue::error(
+                    format!("blob_versioned_hashes[{}]", i),
+                    format!("unknown versioned hash version {:#04x}", versioned_hash[0]),
+                ));
+            }
+        }
+
+        issues
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::{
+        consensus::{SignableTransaction, TxEip4844},
+        eips::eip2930::{AccessList, AccessListItem},
+        primitives::{address, b256, hex, Bytes, Signature, U256},
+    };
+
+    use crate::evm::evm_transaction_eip4844::EVMTransactionEIP4844;
+    use crate::evm::types::{AccessListItem as OmniAccessListItem, Signature as OmniSignature};
+    use crate::validation::Validate;
+
+    fn omni_tx() -> EVMTransactionEIP4844 {
+        let to = address!("6069a6c32cf691f5982febae4faf8a6f3ab2f0f6");
+
+        EVMTransactionEIP4844 {
+            chain_id: 1,
+            nonce: 0x42,
+            to: to.0.into(),
+            value: 1,
+            input: hex!("a22cb465").to_vec(),
+            gas_limit: 44386,
+            max_fee_per_gas: 0x4a817c800,
+            max_priority_fee_per_gas: 0x3b9aca00,
+            access_list: vec![OmniAccessListItem::new(
+                to.0.into(),
+                vec![b256!("0000000000000000000000000000000000000000000000000000000000000001").0],
+            )],
+            max_fee_per_blob_gas: 0x77359400,
+            blob_versioned_hashes: vec![
+                b256!("01b0a4cdd5f55589f5c5b4d46c76704bb6ce95c0a8c09f77f197a57808dded28").0,
+                b256!("01c7cd1d5b14f1c7d1fbf6a35ad5fee91c79ad0b53a1cf52e2e5dfd0bf3b2e60").0,
+            ],
+        }
+    }
+
+    #[test]
+    fn test_build_eip4844_against_alloy() {
+        let to = address!("6069a6c32cf691f5982febae4faf8a6f3ab2f0f6");
+        let tx_omni = omni_tx();
+
+        let tx = TxEip4844 {
+            chain_id: 1,
+            nonce: 0x42,
+            gas_limit: 44386,
+            max_fee_per_gas: 0x4a817c800,
+            max_priority_fee_per_gas: 0x3b9aca00,
+            to,
+            value: U256::from(1u128),
+            access_list: AccessList(vec![AccessListItem {
+                address: to,
+                storage_keys: vec![b256!(
+                    "0000000000000000000000000000000000000000000000000000000000000001"
+                )],
+            }]),
+            blob_versioned_hashes: tx_omni
+                .blob_versioned_hashes
+                .iter()
+                .map(|hash| (*hash).into())
+                .collect(),
+            max_fee_per_blob_gas: 0x77359400,
+            input: Bytes::from(tx_omni.input.clone()),
+        };
+
+        let mut tx_encoded = vec![];
+        tx.encode_for_signing(&mut tx_encoded);
+        assert_eq!(tx_encoded, tx_omni.build_for_signing());
+
+        let sig = Signature::from_scalars_and_parity(
+            b256!("840cfc572845f5786e702984c2a582528cad4b49b2a10b9db1be7fca90058565"),
+            b256!("25e7109ceb98168d95b09b18bbf6b685130e0562f233877d492b94eee0c5b6d1"),
+            false,
+        )
+        .unwrap();
+
+        let mut tx_encoded_with_signature: Vec<u8> = vec![];
+        tx.encode_with_signature(&sig, &mut tx_encoded_with_signature, false);
+
+        let signature = OmniSignature {
+            v: sig.v().to_u64(),
+            r: sig.r().to_be_bytes::<32>().to_vec(),
+            s: sig.s().to_be_bytes::<32>().to_vec(),
+        };
+
+        assert_eq!(
+            tx_encoded_with_signature,
+            tx_omni.build_with_signature(&signature)
+        );
+    }
+
+    #[test]
+    fn test_validate_blob_versioned_hashes() {
+        let mut tx = omni_tx();
+        assert!(tx.validate().is_empty());
+
+        tx.blob_versioned_hashes[1][0] = 0x02;
+        let fields: Vec<_> = tx.validate().into_iter().map(|issue| issue.field).collect();
+        assert_eq!(fields, vec!["blob_versioned_hashes[1]"]);
+
+        tx.blob_versioned_hashes.clear();
+        let fields: Vec<_> = tx.validate().into_iter().map(|issue| issue.field).collect();
+        assert_eq!(fields, vec!["blob_versioned_hashes"]);
+    }
+}
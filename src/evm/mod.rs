@@ -1,4 +1,18 @@
+pub mod abi;
+pub mod chain_context;
+pub mod chain_profile;
+pub mod eip191;
+#[cfg(feature = "eip3074")]
+pub mod eip3074;
+pub mod eip712;
 pub mod evm_transaction;
 pub mod evm_transaction_builder;
+pub mod evm_transaction_eip2930;
+pub mod evm_transaction_eip4844;
+pub mod prelude;
+pub mod raw_transaction;
 pub mod types;
 pub mod utils;
+#[cfg(feature = "verify")]
+pub mod verify;
+pub mod wallet_connect;
@@ -0,0 +1,15 @@
+//! Everything needed to build an EVM transaction in a single import.
+//!
+//! ```ignore
+//! use omni_transaction::evm::prelude::*;
+//! ```
+pub use super::evm_transaction::{EVMDecodeError, EVMTransaction};
+pub use super::evm_transaction_builder::{EVMBuildError, EVMTransactionBuilder};
+pub use super::evm_transaction_eip2930::EVMTransactionEIP2930;
+pub use super::evm_transaction_eip4844::EVMTransactionEIP4844;
+pub use super::types::{AccessList, AccessListItem, Address, Signature};
+pub use super::utils::{
+    parse_checksummed_address, parse_eth_address, to_checksum_address, AddressError,
+};
+pub use crate::transaction_builder::{TransactionBuilder, TxBuilder};
+pub use crate::types::EVM;
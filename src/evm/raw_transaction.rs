@@ -0,0 +1,97 @@
+//! Hex encoding of raw transactions, as used by `eth_sendRawTransaction`.
+use std::fmt;
+
+use super::{evm_transaction::EVMTransaction, types::Signature};
+
+/// Errors returned when decoding a raw transaction hex string.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RawHexError {
+    /// The string does not start with `0x`.
+    MissingPrefix,
+    /// The string has an odd number of hex digits.
+    OddLength,
+    /// The string contains a character which is not a hex digit, at the given position.
+    InvalidCharacter { index: usize, character: char },
+    /// The string does not contain any byte.
+    Empty,
+}
+
+impl fmt::Display for RawHexError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingPrefix => write!(f, "raw transaction should start with 0x"),
+            Self::OddLength => write!(f, "raw transaction has an odd number of hex digits"),
+            Self::InvalidCharacter { index, character } => {
+                write!(f, "invalid character {:?} at position {}", character, index)
+            }
+            Self::Empty => write!(f, "raw transaction is empty"),
+        }
+    }
+}
+
+impl std::error::Error for RawHexError {}
+
+/// Encodes a signed transaction as a `0x` prefixed hex string.
+pub fn to_raw_hex(raw_transaction: &[u8]) -> String {
+    format!("0x{}", hex::encode(raw_transaction))
+}
+
+/// Decodes a `0x` prefixed hex string into the bytes of a signed transaction.
+pub fn from_raw_hex(raw_transaction: &str) -> Result<Vec<u8>, RawHexError> {
+    let digits = raw_transaction
+        .strip_prefix("0x")
+        .ok_or(RawHexError::MissingPrefix)?;
+
+    if digits.is_empty() {
+        return Err(RawHexError::Empty);
+    }
+    if let Some((index, character)) = digits.char_indices().find(|(_, c)| !c.is_ascii_hexdigit()) {
+        return Err(RawHexError::InvalidCharacter {
+            index: index + 2,
+            character,
+        });
+    }
+    if digits.len() % 2 != 0 {
+        return Err(RawHexError::OddLength);
+    }
+
+    Ok(hex::decode(digits).expect("digits were checked"))
+}
+
+impl EVMTransaction {
+    /// Encodes the signed transaction as a `0x` prefixed hex string, ready for
+    /// `eth_sendRawTransaction`.
+    pub fn build_with_signature_hex(&self, signature: &Signature) -> String {
+        to_raw_hex(&self.build_with_signature(signature))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_raw_hex_roundtrip() {
+        let raw = vec![0x02, 0xf8, 0x6c, 0x01];
+
+        let hex = to_raw_hex(&raw);
+
+        assert_eq!(hex, "0x02f86c01");
+        assert_eq!(from_raw_hex(&hex).unwrap(), raw);
+        assert_eq!(from_raw_hex("0x02F86C01").unwrap(), raw);
+    }
+
+    #[test]
+    fn test_from_raw_hex_errors() {
+        assert_eq!(from_raw_hex("02f86c01"), Err(RawHexError::MissingPrefix));
+        assert_eq!(from_raw_hex("0x"), Err(RawHexError::Empty));
+        assert_eq!(from_raw_hex("0x02f86c0"), Err(RawHexError::OddLength));
+        assert_eq!(
+            from_raw_hex("0x02fz"),
+            Err(RawHexError::InvalidCharacter {
+                index: 5,
+                character: 'z'
+            })
+        );
+    }
+}
@@ -1,13 +1,332 @@
-use near_sdk::serde::{Deserialize, Serialize};
+use borsh::{BorshDeserialize, BorshSerialize};
+use serde::{de, ser::SerializeSeq, Deserialize, Deserializer, Serialize, Serializer};
+
+use super::utils::parse_address_hex;
+use crate::constants::SECP256K1_SIGNATURE_LENGTH;
+use crate::signature_error::{check_secp256k1_parts, SignatureError};
 
 pub type Address = [u8; 20];
 
-pub type AccessList = Vec<(Address, Vec<[u8; 32]>)>;
+pub type AccessList = Vec<AccessListItem>;
+
+/// An entry of an EIP-2930 access list: a contract and the storage slots of it the transaction
+/// accesses.
+///
+/// In JSON it takes the JSON-RPC form, with a hex `address` and hex `storageKeys`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccessListItem {
+    #[serde(
+        serialize_with = "serialize_address",
+        deserialize_with = "deserialize_address"
+    )]
+    pub address: Address,
+    #[serde(
+        default,
+        serialize_with = "serialize_storage_keys",
+        deserialize_with = "deserialize_storage_keys"
+    )]
+    pub storage_keys: Vec<[u8; 32]>,
+}
+
+impl AccessListItem {
+    pub const fn new(address: Address, storage_keys: Vec<[u8; 32]>) -> Self {
+        Self {
+            address,
+            storage_keys,
+        }
+    }
+}
+
+fn serialize_address<S>(address: &Address, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serializer.serialize_str(&format!("0x{}", hex::encode(address)))
+}
+
+fn deserialize_address<'de, D>(deserializer: D) -> Result<Address, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let value: String = Deserialize::deserialize(deserializer)?;
+    parse_address_hex(&value).map_err(de::Error::custom)
+}
 
-#[derive(Debug, Serialize, Deserialize)]
-#[serde(crate = "near_sdk::serde")]
+fn serialize_storage_keys<S>(storage_keys: &[[u8; 32]], serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    let mut seq = serializer.serialize_seq(Some(storage_keys.len()))?;
+    for storage_key in storage_keys {
+        seq.serialize_element(&format!("0x{}", hex::encode(storage_key)))?;
+    }
+    seq.end()
+}
+
+fn deserialize_storage_keys<'de, D>(deserializer: D) -> Result<Vec<[u8; 32]>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let values: Vec<String> = Deserialize::deserialize(deserializer)?;
+    values
+        .iter()
+        .map(|value| {
+            let bytes = hex::decode(value.strip_prefix("0x").unwrap_or(value)).map_err(|e| {
+                de::Error::custom(format!("invalid storage key {:?}: {}", value, e))
+            })?;
+            bytes.try_into().map_err(|bytes: Vec<u8>| {
+                de::Error::custom(format!(
+                    "storage key should be 32 bytes long, got {}",
+                    bytes.len()
+                ))
+            })
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
 pub struct Signature {
     pub v: u64,
     pub r: Vec<u8>,
     pub s: Vec<u8>,
 }
+
+impl Signature {
+    /// Creates a signature from its 65-byte raw representation (`r || s || v`).
+    pub fn from_raw_65(bytes: &[u8; SECP256K1_SIGNATURE_LENGTH]) -> Self {
+        Self {
+            r: bytes[..32].to_vec(),
+            s: bytes[32..64].to_vec(),
+            v: u64::from(bytes[64]),
+        }
+    }
+
+    /// Creates the signature of a typed transaction (EIP-2930, EIP-1559, EIP-4844) from the
+    /// parts returned by an MPC signer: the big-endian `r` and `s` and the recovery id, which
+    /// is the `v` of typed transactions.
+    ///
+    /// Fails if `r` or `s` are not non-zero 32-byte values, if `s` is not low-S or if the
+    /// recovery id is not 0 or 1.
+    pub fn from_mpc_parts(r: &[u8], s: &[u8], recovery_id: u8) -> Result<Self, SignatureError> {
+        check_secp256k1_parts(r, s, recovery_id)?;
+
+        Ok(Self {
+            v: u64::from(recovery_id),
+            r: r.to_vec(),
+            s: s.to_vec(),
+        })
+    }
+
+    /// Same as [`Self::from_mpc_parts`], for a legacy transaction replay-protected with
+    /// EIP-155: `v` is `recovery_id + 35 + 2 * chain_id`.
+    ///
+    /// Also fails if that `v` does not fit in a `u64`.
+    pub fn from_mpc_parts_eip155(
+        r: &[u8],
+        s: &[u8],
+        recovery_id: u8,
+        chain_id: u64,
+    ) -> Result<Self, SignatureError> {
+        check_secp256k1_parts(r, s, recovery_id)?;
+        let v = chain_id
+            .checked_mul(2)
+            .and_then(|v| v.checked_add(u64::from(recovery_id) + 35))
+            .ok_or(SignatureError::InvalidChainId(chain_id))?;
+
+        Ok(Self {
+            v,
+            r: r.to_vec(),
+            s: s.to_vec(),
+        })
+    }
+
+    /// Returns the 65-byte raw representation of the signature (`r || s || v`).
+    ///
+    /// Fails if `r` or `s` are not 32 bytes long or if `v` does not fit in a single byte
+    /// (e.g. a legacy EIP-155 `v` value).
+    pub fn to_raw_65(&self) -> Result<[u8; SECP256K1_SIGNATURE_LENGTH], SignatureError> {
+        let (r, s, v) = self.to_rsv_tuple()?;
+        let v: u8 = v
+            .try_into()
+            .map_err(|_| SignatureError::InvalidRecoveryId(v))?;
+
+        let mut bytes = [0u8; SECP256K1_SIGNATURE_LENGTH];
+        bytes[..32].copy_from_slice(&r);
+        bytes[32..64].copy_from_slice(&s);
+        bytes[64] = v;
+
+        Ok(bytes)
+    }
+
+    /// Returns the `(r, s, v)` components of the signature as fixed size values.
+    pub fn to_rsv_tuple(&self) -> Result<([u8; 32], [u8; 32], u64), SignatureError> {
+        let scalar = |component: &'static str, value: &[u8]| {
+            value.try_into().map_err(|_| SignatureError::InvalidLength {
+                component,
+                expected: 32,
+                actual: value.len(),
+            })
+        };
+
+        Ok((scalar("r", &self.r)?, scalar("s", &self.s)?, self.v))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::SECP256K1_HALF_ORDER;
+
+    fn raw_signature() -> [u8; SECP256K1_SIGNATURE_LENGTH] {
+        let mut bytes = [0u8; SECP256K1_SIGNATURE_LENGTH];
+        bytes[..32].copy_from_slice(&[1u8; 32]);
+        bytes[32..64].copy_from_slice(&[2u8; 32]);
+        bytes[64] = 27;
+        bytes
+    }
+
+    #[test]
+    fn test_from_raw_65() {
+        let signature = Signature::from_raw_65(&raw_signature());
+
+        assert_eq!(signature.r, vec![1u8; 32]);
+        assert_eq!(signature.s, vec![2u8; 32]);
+        assert_eq!(signature.v, 27);
+    }
+
+    #[test]
+    fn test_raw_65_roundtrip() {
+        let raw = raw_signature();
+        let signature = Signature::from_raw_65(&raw);
+
+        assert_eq!(signature.to_raw_65().unwrap(), raw);
+    }
+
+    #[test]
+    fn test_to_rsv_tuple() {
+        let signature = Signature::from_raw_65(&raw_signature());
+        let (r, s, v) = signature.to_rsv_tuple().unwrap();
+
+        assert_eq!(r, [1u8; 32]);
+        assert_eq!(s, [2u8; 32]);
+        assert_eq!(v, 27);
+    }
+
+    #[test]
+    fn test_to_raw_65_with_invalid_components() {
+        let signature = Signature {
+            v: 0,
+            r: vec![1u8; 31],
+            s: vec![2u8; 32],
+        };
+        assert_eq!(
+            signature.to_raw_65(),
+            Err(SignatureError::InvalidLength {
+                component: "r",
+                expected: 32,
+                actual: 31,
+            })
+        );
+
+        let signature = Signature {
+            v: 37,
+            r: vec![1u8; 32],
+            s: vec![2u8; 33],
+        };
+        assert_eq!(
+            signature.to_rsv_tuple(),
+            Err(SignatureError::InvalidLength {
+                component: "s",
+                expected: 32,
+                actual: 33,
+            })
+        );
+
+        let signature = Signature {
+            v: 2 * 11155111 + 35,
+            r: vec![1u8; 32],
+            s: vec![2u8; 32],
+        };
+        assert_eq!(
+            signature.to_raw_65(),
+            Err(SignatureError::InvalidRecoveryId(2 * 11155111 + 35))
+        );
+    }
+
+    #[test]
+    fn test_access_list_item_json() {
+        let json = r#"{"address":"0x1111111111111111111111111111111111111111","storageKeys":["0x0000000000000000000000000000000000000000000000000000000000000001"]}"#;
+        let mut storage_key = [0u8; 32];
+        storage_key[31] = 1;
+
+        let item: AccessListItem = serde_json::from_str(json).unwrap();
+        assert_eq!(item, AccessListItem::new([0x11; 20], vec![storage_key]));
+        assert_eq!(serde_json::to_string(&item).unwrap(), json);
+
+        let item: AccessListItem =
+            serde_json::from_str(r#"{"address":"0x1111111111111111111111111111111111111111"}"#)
+                .unwrap();
+        assert!(item.storage_keys.is_empty());
+
+        assert!(serde_json::from_str::<AccessListItem>(
+            r#"{"address":"0x1111111111111111111111111111111111111111","storageKeys":["0x01"]}"#
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_from_mpc_parts() {
+        let signature = Signature::from_mpc_parts(&[1u8; 32], &[2u8; 32], 1).unwrap();
+        assert_eq!(signature.v, 1);
+        assert_eq!(signature.r, vec![1u8; 32]);
+        assert_eq!(signature.s, vec![2u8; 32]);
+
+        let signature = Signature::from_mpc_parts_eip155(&[1u8; 32], &[2u8; 32], 1, 1).unwrap();
+        assert_eq!(signature.v, 38);
+        let signature =
+            Signature::from_mpc_parts_eip155(&[1u8; 32], &[2u8; 32], 0, 11155111).unwrap();
+        assert_eq!(signature.v, 22310257);
+
+        assert!(Signature::from_mpc_parts(&[1u8; 32], &SECP256K1_HALF_ORDER, 0).is_ok());
+    }
+
+    #[test]
+    fn test_from_mpc_parts_errors() {
+        assert_eq!(
+            Signature::from_mpc_parts(&[1u8; 33], &[2u8; 32], 0),
+            Err(SignatureError::InvalidLength {
+                component: "r",
+                expected: 32,
+                actual: 33,
+            })
+        );
+        assert_eq!(
+            Signature::from_mpc_parts(&[1u8; 32], &[0u8; 32], 0),
+            Err(SignatureError::ZeroComponent("s"))
+        );
+        assert_eq!(
+            Signature::from_mpc_parts(&[1u8; 32], &[2u8; 32], 2),
+            Err(SignatureError::InvalidRecoveryId(2))
+        );
+
+        let mut high_s = SECP256K1_HALF_ORDER;
+        high_s[31] += 1;
+        assert_eq!(
+            Signature::from_mpc_parts(&[1u8; 32], &high_s, 0),
+            Err(SignatureError::HighS)
+        );
+        assert_eq!(
+            Signature::from_mpc_parts_eip155(&[1u8; 32], &[0xff; 32], 0, 1),
+            Err(SignatureError::HighS)
+        );
+        assert_eq!(
+            Signature::from_mpc_parts_eip155(&[1u8; 32], &[2u8; 32], 0, u64::MAX / 2),
+            Err(SignatureError::InvalidChainId(u64::MAX / 2))
+        );
+        assert_eq!(
+            Signature::from_mpc_parts_eip155(&[1u8; 32], &[2u8; 32], 0, u64::MAX),
+            Err(SignatureError::InvalidChainId(u64::MAX))
+        );
+    }
+}
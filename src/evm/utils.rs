@@ -1,7 +1,37 @@
 use hex;
+use sha3::{Digest, Keccak256};
+use std::fmt;
 
 use super::types::Address;
 
+/// Errors returned when parsing an address.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AddressError {
+    /// The address is not valid hex.
+    InvalidHex(String),
+    /// The address is not 20 bytes long.
+    InvalidLength(usize),
+    /// The address is all-lowercase or all-uppercase while a checksum is required.
+    NotChecksummed,
+    /// The mixed-case address does not match its EIP-55 checksum.
+    InvalidChecksum,
+}
+
+impl fmt::Display for AddressError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidHex(reason) => write!(f, "invalid address hex: {}", reason),
+            Self::InvalidLength(len) => {
+                write!(f, "address should be 20 bytes long, got {}", len)
+            }
+            Self::NotChecksummed => write!(f, "address is not checksummed"),
+            Self::InvalidChecksum => write!(f, "invalid address checksum"),
+        }
+    }
+}
+
+impl std::error::Error for AddressError {}
+
 pub fn parse_eth_address(address: &str) -> Address {
     let address = hex::decode(address).expect("address should be hex");
     assert_eq!(address.len(), 20, "address should be 20 bytes long");
@@ -9,3 +39,129 @@ pub fn parse_eth_address(address: &str) -> Address {
     result.copy_from_slice(&address);
     result
 }
+
+/// Computes the keccak256 hash of the given data.
+pub fn keccak256(data: &[u8]) -> [u8; 32] {
+    Keccak256::digest(data).into()
+}
+
+/// Parses a hex address, with or without the `0x` prefix, regardless of its case.
+pub fn parse_address_hex(address: &str) -> Result<Address, AddressError> {
+    let bytes = hex::decode(address.strip_prefix("0x").unwrap_or(address))
+        .map_err(|e| AddressError::InvalidHex(e.to_string()))?;
+
+    bytes
+        .try_into()
+        .map_err(|bytes: Vec<u8>| AddressError::InvalidLength(bytes.len()))
+}
+
+/// Parses a quantity, either `0x` prefixed hex or decimal.
+pub(crate) fn parse_u128(value: &str) -> Result<u128, std::num::ParseIntError> {
+    value.strip_prefix("0x").map_or_else(
+        || value.parse::<u128>(),
+        |hex_str| u128::from_str_radix(hex_str, 16),
+    )
+}
+
+/// Returns the EIP-55 mixed-case checksum encoding of an address, with the `0x` prefix.
+pub fn to_checksum_address(address: &Address) -> String {
+    let lowercase = hex::encode(address);
+    let hash = keccak256(lowercase.as_bytes());
+
+    let checksummed: String = lowercase
+        .chars()
+        .enumerate()
+        .map(|(i, c)| {
+            let nibble = (hash[i / 2] >> (if i % 2 == 0 { 4 } else { 0 })) & 0x0f;
+            if nibble >= 8 {
+                c.to_ascii_uppercase()
+            } else {
+                c
+            }
+        })
+        .collect();
+
+    format!("0x{}", checksummed)
+}
+
+/// Returns the checksummed recipient of a transaction, or `contract creation` if it has none.
+pub(crate) fn format_recipient(to: Option<&Address>) -> String {
+    to.map_or_else(|| "contract creation".to_string(), to_checksum_address)
+}
+
+/// Parses an address and checks its EIP-55 checksum.
+///
+/// Addresses without any checksum information, i.e. all-lowercase or all-uppercase, are only
+/// accepted if `allow_lowercase` is set.
+pub fn parse_checksummed_address(
+    address: &str,
+    allow_lowercase: bool,
+) -> Result<Address, AddressError> {
+    let parsed = parse_address_hex(address)?;
+    let digits = address.strip_prefix("0x").unwrap_or(address);
+
+    let is_single_case =
+        digits == digits.to_ascii_lowercase() || digits == digits.to_ascii_uppercase();
+    if is_single_case {
+        return if allow_lowercase {
+            Ok(parsed)
+        } else {
+            Err(AddressError::NotChecksummed)
+        };
+    }
+
+    if to_checksum_address(&parsed)[2..] != *digits {
+        return Err(AddressError::InvalidChecksum);
+    }
+
+    Ok(parsed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Test vectors from EIP-55.
+    const CHECKSUMMED: [&str; 4] = [
+        "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed",
+        "0xfB6916095ca1df60bB79Ce92cE3Ea74c37c5d359",
+        "0xdbF03B407c01E7cD3CBea99509d93f8DDDC8C6FB",
+        "0xD1220A0cf47c7B9Be7A2E6BA89F429762e7b9aDb",
+    ];
+
+    #[test]
+    fn test_to_checksum_address() {
+        for expected in CHECKSUMMED {
+            let address = parse_address_hex(expected).unwrap();
+            assert_eq!(to_checksum_address(&address), expected);
+        }
+    }
+
+    #[test]
+    fn test_parse_checksummed_address() {
+        for address in CHECKSUMMED {
+            assert!(parse_checksummed_address(address, false).is_ok());
+        }
+
+        let lowercase = CHECKSUMMED[0].to_lowercase();
+        assert_eq!(
+            parse_checksummed_address(&lowercase, false),
+            Err(AddressError::NotChecksummed)
+        );
+        assert!(parse_checksummed_address(&lowercase, true).is_ok());
+
+        // A single flipped case is detected.
+        assert_eq!(
+            parse_checksummed_address("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAeD", true),
+            Err(AddressError::InvalidChecksum)
+        );
+        assert_eq!(
+            parse_checksummed_address("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeA", true),
+            Err(AddressError::InvalidLength(19))
+        );
+        assert!(matches!(
+            parse_address_hex("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAe"),
+            Err(AddressError::InvalidHex(_))
+        ));
+    }
+}
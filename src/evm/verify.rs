@@ -0,0 +1,361 @@
+//! Final safety checks of signed transactions, before they are broadcast.
+//!
+//! [`verify_signed_payload`] decodes a raw signed transaction, recovers the address which
+//! signed it and compares it with the expected sender. A mismatch means the signature was made
+//! over a different payload (e.g. another chain id or nonce) or with a different key, and the
+//! transaction would either be rejected or spend from the wrong account.
+use std::fmt;
+
+use k256::ecdsa::{RecoveryId, Signature, VerifyingKey};
+use rlp::{Rlp, RlpStream};
+
+use crate::constants::{EIP_1559_TYPE, EIP_2930_TYPE};
+
+use super::{
+    evm_transaction::EVMTransaction,
+    types::{Address, Signature as EVMSignature},
+    utils::keccak256,
+};
+
+/// Number of fields of a signed EIP-2930 transaction, the signature included.
+const EIP_2930_FIELD_COUNT: usize = 11;
+/// Number of fields of a signed EIP-1559 transaction, the signature included.
+const EIP_1559_FIELD_COUNT: usize = 12;
+
+/// Errors returned by [`verify_signed_payload`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VerifyError {
+    /// The transaction type is not supported, only EIP-2930 and EIP-1559 transactions are.
+    UnsupportedType(u8),
+    /// The payload is not a valid RLP encoding of a signed transaction.
+    InvalidEncoding(String),
+    /// The signature is malformed or does not recover to a public key.
+    InvalidSignature(String),
+    /// The transaction was signed by another address.
+    SenderMismatch {
+        expected: Address,
+        recovered: Address,
+    },
+}
+
+impl fmt::Display for VerifyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnsupportedType(tx_type) => {
+                write!(f, "unsupported transaction type: {:#04x}", tx_type)
+            }
+            Self::InvalidEncoding(reason) => write!(f, "invalid encoding: {}", reason),
+            Self::InvalidSignature(reason) => write!(f, "invalid signature: {}", reason),
+            Self::SenderMismatch {
+                expected,
+                recovered,
+            } => write!(
+                f,
+                "signed by 0x{}, expected 0x{}",
+                hex::encode(recovered),
+                hex::encode(expected)
+            ),
+        }
+    }
+}
+
+impl std::error::Error for VerifyError {}
+
+/// Checks that `raw_signed_tx`, as returned by `build_with_signature`, was signed by
+/// `expected_sender`.
+pub fn verify_signed_payload(
+    raw_signed_tx: &[u8],
+    expected_sender: &Address,
+) -> Result<(), VerifyError> {
+    let recovered = recover_signed_payload_sender(raw_signed_tx)?;
+
+    if recovered != *expected_sender {
+        return Err(VerifyError::SenderMismatch {
+            expected: *expected_sender,
+            recovered,
+        });
+    }
+
+    Ok(())
+}
+
+/// Returns the address which signed `raw_signed_tx`.
+pub fn recover_signed_payload_sender(raw_signed_tx: &[u8]) -> Result<Address, VerifyError> {
+    let (&tx_type, payload) = raw_signed_tx
+        .split_first()
+        .ok_or_else(|| VerifyError::InvalidEncoding("empty payload".to_string()))?;
+    let field_count = match tx_type {
+        EIP_2930_TYPE => EIP_2930_FIELD_COUNT,
+        EIP_1559_TYPE => EIP_1559_FIELD_COUNT,
+        _ => return Err(VerifyError::UnsupportedType(tx_type)),
+    };
+
+    let rlp = Rlp::new(payload);
+    let decode_error = |e: rlp::DecoderError| VerifyError::InvalidEncoding(e.to_string());
+    let info = rlp.payload_info().map_err(decode_error)?;
+    if !rlp.is_list() || info.header_len + info.value_len != payload.len() {
+        return Err(VerifyError::InvalidEncoding(
+            "payload is not a single RLP list".to_string(),
+        ));
+    }
+    let item_count = rlp.item_count().map_err(decode_error)?;
+    if item_count != field_count {
+        return Err(VerifyError::InvalidEncoding(format!(
+            "expected {} fields, got {}",
+            field_count, item_count
+        )));
+    }
+
+    // The signing payload is the same list without the signature.
+    let unsigned_count = field_count - 3;
+    let mut rlp_stream = RlpStream::new();
+    rlp_stream.append(&tx_type);
+    rlp_stream.begin_list(unsigned_count);
+    for i in 0..unsigned_count {
+        rlp_stream.append_raw(rlp.at(i).map_err(decode_error)?.as_raw(), 1);
+    }
+    let hash = keccak256(&rlp_stream.out());
+
+    let v: u8 = rlp.val_at(unsigned_count).map_err(decode_error)?;
+    let r = scalar_at(&rlp, unsigned_count + 1)?;
+    let s = scalar_at(&rlp, unsigned_count + 2)?;
+
+    recover_address(&hash, &r, &s, v)
+}
+
+impl EVMTransaction {
+    /// Returns the address which signed the transaction with `signature`, whose `v` is the
+    /// y-parity, e.g. to check an MPC signature before broadcasting the transaction.
+    ///
+    /// A signature made for another chain id or over another payload recovers to an unrelated
+    /// address rather than failing, so the result must be compared with the expected sender.
+    pub fn recover_sender(&self, signature: &EVMSignature) -> Result<Address, VerifyError> {
+        let y_parity = u8::try_from(signature.v).map_err(|_| {
+            VerifyError::InvalidSignature(format!("invalid y-parity: {}", signature.v))
+        })?;
+
+        recover_address(
+            &self.hash_for_signing(),
+            &to_scalar(&signature.r)?,
+            &to_scalar(&signature.s)?,
+            y_parity,
+        )
+    }
+}
+
+/// Recovers the address which signed the 32-byte `hash` from an `(r, s, y-parity)` signature.
+///
+/// Signatures with a high `s` are rejected, as they are by the network since EIP-2.
+pub(crate) fn recover_address(
+    hash: &[u8; 32],
+    r: &[u8; 32],
+    s: &[u8; 32],
+    y_parity: u8,
+) -> Result<Address, VerifyError> {
+    let signature = Signature::from_scalars(*r, *s)
+        .map_err(|e| VerifyError::InvalidSignature(e.to_string()))?;
+    if signature.normalize_s().is_some() {
+        return Err(VerifyError::InvalidSignature("s is too high".to_string()));
+    }
+    let recovery_id = RecoveryId::from_byte(y_parity).filter(|id| !id.is_x_reduced());
+    let recovery_id = recovery_id
+        .ok_or_else(|| VerifyError::InvalidSignature(format!("invalid y-parity: {}", y_parity)))?;
+
+    let verifying_key = VerifyingKey::recover_from_prehash(hash, &signature, recovery_id)
+        .map_err(|e| VerifyError::InvalidSignature(e.to_string()))?;
+    let public_key = verifying_key.to_encoded_point(false);
+    let hash = keccak256(&public_key.as_bytes()[1..]);
+
+    let mut address = [0u8; 20];
+    address.copy_from_slice(&hash[12..]);
+    Ok(address)
+}
+
+/// Decodes a signature scalar, left-padding it to 32 bytes.
+fn scalar_at(rlp: &Rlp, index: usize) -> Result<[u8; 32], VerifyError> {
+    let bytes = rlp
+        .at(index)
+        .and_then(|item| item.data().map(<[u8]>::to_vec))
+        .map_err(|e| VerifyError::InvalidEncoding(e.to_string()))?;
+    to_scalar(&bytes)
+}
+
+/// Left-pads a big-endian signature scalar to 32 bytes.
+fn to_scalar(bytes: &[u8]) -> Result<[u8; 32], VerifyError> {
+    if bytes.len() > 32 {
+        return Err(VerifyError::InvalidSignature(format!(
+            "signature scalar is {} bytes long",
+            bytes.len()
+        )));
+    }
+
+    let mut scalar = [0u8; 32];
+    scalar[32 - bytes.len()..].copy_from_slice(bytes);
+    Ok(scalar)
+}
+
+#[cfg(test)]
+mod tests {
+    use k256::ecdsa::SigningKey;
+
+    use super::*;
+    use crate::evm::{evm_transaction_eip2930::EVMTransactionEIP2930, types::AccessListItem};
+
+    fn signing_key() -> SigningKey {
+        SigningKey::from_slice(&[0x42; 32]).unwrap()
+    }
+
+    fn address_of(signing_key: &SigningKey) -> Address {
+        let public_key = signing_key.verifying_key().to_encoded_point(false);
+        keccak256(&public_key.as_bytes()[1..])[12..]
+            .try_into()
+            .unwrap()
+    }
+
+    fn sign(signing_key: &SigningKey, payload: &[u8]) -> EVMSignature {
+        let (signature, recovery_id) = signing_key
+            .sign_prehash_recoverable(&keccak256(payload))
+            .unwrap();
+        EVMSignature {
+            v: u64::from(recovery_id.to_byte()),
+            r: signature.r().to_bytes().to_vec(),
+            s: signature.s().to_bytes().to_vec(),
+        }
+    }
+
+    fn transaction() -> EVMTransaction {
+        EVMTransaction {
+            chain_id: 1,
+            nonce: 7,
+            to: Some([0x11; 20]),
+            value: 1_000_000_000_000_000,
+            input: vec![0xde, 0xad],
+            gas_limit: 21_000,
+            max_fee_per_gas: 30_000_000_000,
+            max_priority_fee_per_gas: 1_000_000_000,
+            access_list: vec![AccessListItem::new([0x22; 20], vec![[0x33; 32]])],
+        }
+    }
+
+    #[test]
+    fn test_verify_eip1559_round_trip() {
+        let signing_key = signing_key();
+        let tx = transaction();
+        let signed = tx.build_with_signature(&sign(&signing_key, &tx.build_for_signing()));
+
+        assert_eq!(
+            verify_signed_payload(&signed, &address_of(&signing_key)),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn test_verify_eip2930_round_trip() {
+        let signing_key = signing_key();
+        let tx = EVMTransactionEIP2930 {
+            chain_id: 5,
+            nonce: 0,
+            gas_price: 20_000_000_000,
+            gas_limit: 50_000,
+            to: None,
+            value: 0,
+            input: vec![0x60, 0x80],
+            access_list: vec![],
+        };
+        let signed = tx.build_with_signature(&sign(&signing_key, &tx.build_for_signing()));
+
+        assert_eq!(
+            recover_signed_payload_sender(&signed),
+            Ok(address_of(&signing_key))
+        );
+    }
+
+    #[test]
+    fn test_verify_detects_wrong_payload() {
+        let signing_key = signing_key();
+        let tx = transaction();
+        // Signed for another chain.
+        let other_chain = EVMTransaction {
+            chain_id: 10,
+            ..tx.clone()
+        };
+        let signed = tx.build_with_signature(&sign(&signing_key, &other_chain.build_for_signing()));
+
+        let expected = address_of(&signing_key);
+        assert!(matches!(
+            verify_signed_payload(&signed, &expected),
+            Err(VerifyError::SenderMismatch { expected: e, .. }) if e == expected
+        ));
+    }
+
+    #[test]
+    fn test_recover_sender() {
+        let signing_key = signing_key();
+        let tx = transaction();
+        let signature = sign(&signing_key, &tx.build_for_signing());
+
+        assert_eq!(tx.recover_sender(&signature), Ok(address_of(&signing_key)));
+
+        let other_chain = EVMTransaction {
+            chain_id: 10,
+            ..tx.clone()
+        };
+        assert_ne!(
+            other_chain.recover_sender(&signature),
+            Ok(address_of(&signing_key))
+        );
+
+        let signature = EVMSignature { v: 27, ..signature };
+        assert_eq!(
+            tx.recover_sender(&signature),
+            Err(VerifyError::InvalidSignature(
+                "invalid y-parity: 27".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn test_verify_rejects_malformed_payloads() {
+        let signing_key = signing_key();
+        let tx = transaction();
+        let signed = tx.build_with_signature(&sign(&signing_key, &tx.build_for_signing()));
+        let sender = address_of(&signing_key);
+
+        assert_eq!(
+            verify_signed_payload(&[0x03], &sender),
+            Err(VerifyError::UnsupportedType(0x03))
+        );
+        assert!(matches!(
+            verify_signed_payload(&tx.build_for_signing(), &sender),
+            Err(VerifyError::InvalidEncoding(_))
+        ));
+        assert!(matches!(
+            verify_signed_payload(&[&signed[..], &[0x00]].concat(), &sender),
+            Err(VerifyError::InvalidEncoding(_))
+        ));
+    }
+
+    #[test]
+    fn test_verify_rejects_high_s() {
+        let signing_key = signing_key();
+        let tx = transaction();
+        let mut signature = sign(&signing_key, &tx.build_for_signing());
+        // (r, n - s) with the opposite parity is the same signature, with a high s.
+        let s: k256::Scalar = *k256::ecdsa::Signature::from_scalars(
+            <[u8; 32]>::try_from(&signature.r[..]).unwrap(),
+            <[u8; 32]>::try_from(&signature.s[..]).unwrap(),
+        )
+        .unwrap()
+        .s();
+        signature.s = (-s).to_bytes().to_vec();
+        signature.v ^= 1;
+
+        assert_eq!(
+            verify_signed_payload(
+                &tx.build_with_signature(&signature),
+                &address_of(&signing_key)
+            ),
+            Err(VerifyError::InvalidSignature("s is too high".to_string()))
+        );
+    }
+}
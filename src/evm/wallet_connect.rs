@@ -0,0 +1,386 @@
+//! Adapters for WalletConnect-style JSON-RPC requests.
+//!
+//! Both bare JSON-RPC requests (`{"method": ..., "params": [...]}`) and WalletConnect
+//! `session_request` payloads (`{"chainId": "eip155:1", "request": {...}}`) are accepted.
+use serde::Deserialize;
+use serde_json::{self, Value};
+use std::fmt;
+
+use super::{
+    evm_transaction_builder::EVMTransactionBuilder,
+    types::{AccessList, Address},
+    utils::parse_address_hex,
+};
+
+/// Errors returned when converting a WalletConnect request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WalletConnectError {
+    /// The request is not valid JSON.
+    InvalidJson(String),
+    /// The CAIP-2 chain ID is not an `eip155` chain.
+    UnsupportedChain(String),
+    /// A mandatory field of the request is missing or has the wrong JSON type.
+    MissingField(&'static str),
+    /// The request method is not the expected one.
+    WrongMethod {
+        expected: &'static str,
+        actual: String,
+    },
+    /// The params do not have the shape expected by the method.
+    InvalidParams(&'static str),
+    /// The transaction chain ID differs from the chain of the session request.
+    ChainIdMismatch { request: u64, transaction: u128 },
+    /// A field of the request has an invalid value.
+    InvalidField { field: &'static str, reason: String },
+}
+
+impl fmt::Display for WalletConnectError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidJson(reason) => write!(f, "invalid JSON: {}", reason),
+            Self::UnsupportedChain(chain) => write!(f, "unsupported chain: {}", chain),
+            Self::MissingField(field) => write!(f, "{} should be provided", field),
+            Self::WrongMethod { expected, actual } => {
+                write!(f, "expected a {} request, got {}", expected, actual)
+            }
+            Self::InvalidParams(expected) => write!(f, "params should be {}", expected),
+            Self::ChainIdMismatch {
+                request,
+                transaction,
+            } => write!(
+                f,
+                "transaction chainId {} does not match the request chain eip155:{}",
+                transaction, request
+            ),
+            Self::InvalidField { field, reason } => write!(f, "invalid {}: {}", field, reason),
+        }
+    }
+}
+
+impl std::error::Error for WalletConnectError {}
+
+impl WalletConnectError {
+    fn field(field: &'static str, reason: impl ToString) -> Self {
+        Self::InvalidField {
+            field,
+            reason: reason.to_string(),
+        }
+    }
+}
+
+/// An `eth_signTypedData` request: the signing address and the EIP-712 typed data to sign.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignTypedDataRequest {
+    pub address: Address,
+    /// The typed data (`types`, `primaryType`, `domain` and `message`) as sent by the dApp.
+    pub typed_data: Value,
+}
+
+/// Converts an `eth_sendTransaction` request into a builder.
+///
+/// Fields that dApps usually leave to the wallet (nonce, gas and fees) are only set when present,
+/// so the relayer can fill the missing ones before calling `try_build`. A legacy `gasPrice` is
+/// used as both the max fee and the max priority fee.
+pub fn eth_send_transaction(request: &str) -> Result<EVMTransactionBuilder, WalletConnectError> {
+    let (chain_id, params) = parse_request(request, &["eth_sendTransaction"])?;
+    let tx = params
+        .first()
+        .ok_or(WalletConnectError::InvalidParams("a transaction object"))?;
+
+    let mut builder = EVMTransactionBuilder::new();
+
+    let tx_chain_id = parse_quantity(tx, "chainId")?;
+    if let (Some(caip2), Some(tx_chain_id)) = (chain_id, tx_chain_id) {
+        if u128::from(caip2) != tx_chain_id {
+            return Err(WalletConnectError::ChainIdMismatch {
+                request: caip2,
+                transaction: tx_chain_id,
+            });
+        }
+    }
+    if let Some(chain_id) = tx_chain_id.or(chain_id.map(u128::from)) {
+        builder = builder.chain_id(
+            u64::try_from(chain_id).map_err(|e| WalletConnectError::field("chainId", e))?,
+        );
+    }
+    if let Some(nonce) = parse_quantity(tx, "nonce")? {
+        builder =
+            builder.nonce(u64::try_from(nonce).map_err(|e| WalletConnectError::field("nonce", e))?);
+    }
+    if let Some(to) = tx.get("to").and_then(Value::as_str) {
+        builder =
+            builder.to(parse_address_hex(to).map_err(|e| WalletConnectError::field("to", e))?);
+    }
+    if let Some(value) = parse_quantity(tx, "value")? {
+        builder = builder.value(value);
+    }
+    if let Some(data) = tx
+        .get("data")
+        .or_else(|| tx.get("input"))
+        .and_then(Value::as_str)
+    {
+        builder = builder.input(parse_hex(data)?);
+    }
+    if let Some(gas) = parse_quantity(tx, "gas")?.or(parse_quantity(tx, "gasLimit")?) {
+        builder = builder.gas_limit(gas);
+    }
+    if let Some(gas_price) = parse_quantity(tx, "gasPrice")? {
+        builder = builder
+            .max_fee_per_gas(gas_price)
+            .max_priority_fee_per_gas(gas_price);
+    }
+    if let Some(max_fee_per_gas) = parse_quantity(tx, "maxFeePerGas")? {
+        builder = builder.max_fee_per_gas(max_fee_per_gas);
+    }
+    if let Some(max_priority_fee_per_gas) = parse_quantity(tx, "maxPriorityFeePerGas")? {
+        builder = builder.max_priority_fee_per_gas(max_priority_fee_per_gas);
+    }
+    if let Some(access_list) = tx.get("accessList") {
+        builder = builder.access_list(parse_access_list(access_list)?);
+    }
+
+    Ok(builder)
+}
+
+/// Converts an `eth_signTypedData` (or `eth_signTypedData_v4`) request into its address and
+/// typed data. The typed data may be given either as a JSON object or as a JSON encoded string.
+pub fn eth_sign_typed_data(request: &str) -> Result<SignTypedDataRequest, WalletConnectError> {
+    let (_, params) = parse_request(request, &["eth_signTypedData", "eth_signTypedData_v4"])?;
+
+    let [address, typed_data] = params.as_slice() else {
+        return Err(WalletConnectError::InvalidParams(
+            "an address and the typed data",
+        ));
+    };
+
+    let address = address
+        .as_str()
+        .ok_or_else(|| WalletConnectError::field("address", "should be a string"))?;
+    let address =
+        parse_address_hex(address).map_err(|e| WalletConnectError::field("address", e))?;
+    let typed_data = match typed_data {
+        Value::String(s) => {
+            serde_json::from_str(s).map_err(|e| WalletConnectError::field("typed data", e))?
+        }
+        Value::Object(_) => typed_data.clone(),
+        _ => {
+            return Err(WalletConnectError::field(
+                "typed data",
+                "should be an object or a string",
+            ))
+        }
+    };
+
+    Ok(SignTypedDataRequest {
+        address,
+        typed_data,
+    })
+}
+
+/// Returns the CAIP-2 chain ID (if any) and the params of the request, checking that its method
+/// is one of `methods`.
+fn parse_request(
+    request: &str,
+    methods: &[&'static str],
+) -> Result<(Option<u64>, Vec<Value>), WalletConnectError> {
+    let v: Value = serde_json::from_str(request)
+        .map_err(|e| WalletConnectError::InvalidJson(e.to_string()))?;
+
+    let chain_id = v
+        .get("chainId")
+        .and_then(Value::as_str)
+        .map(|caip2| {
+            caip2
+                .strip_prefix("eip155:")
+                .ok_or_else(|| WalletConnectError::UnsupportedChain(caip2.to_string()))?
+                .parse::<u64>()
+                .map_err(|e| WalletConnectError::field("chainId", e))
+        })
+        .transpose()?;
+
+    let request = v.get("request").unwrap_or(&v);
+
+    let request_method = request
+        .get("method")
+        .and_then(Value::as_str)
+        .ok_or(WalletConnectError::MissingField("method"))?;
+    if !methods.contains(&request_method) {
+        return Err(WalletConnectError::WrongMethod {
+            expected: methods[0],
+            actual: request_method.to_string(),
+        });
+    }
+
+    let params = request
+        .get("params")
+        .and_then(Value::as_array)
+        .ok_or(WalletConnectError::MissingField("params"))?;
+
+    Ok((chain_id, params.clone()))
+}
+
+/// Parses a quantity given either as a `0x` prefixed hex string, a decimal string or a number.
+fn parse_quantity(tx: &Value, field: &'static str) -> Result<Option<u128>, WalletConnectError> {
+    match tx.get(field) {
+        None | Some(Value::Null) => Ok(None),
+        Some(Value::Number(n)) => n
+            .as_u64()
+            .map(|n| Some(u128::from(n)))
+            .ok_or_else(|| WalletConnectError::field(field, "should be a non-negative integer")),
+        Some(Value::String(s)) => s
+            .strip_prefix("0x")
+            .map_or_else(|| s.parse::<u128>(), |hex| u128::from_str_radix(hex, 16))
+            .map(Some)
+            .map_err(|e| WalletConnectError::field(field, e)),
+        Some(_) => Err(WalletConnectError::field(
+            field,
+            "should be a string or a number",
+        )),
+    }
+}
+
+fn parse_hex(s: &str) -> Result<Vec<u8>, WalletConnectError> {
+    hex::decode(s.strip_prefix("0x").unwrap_or(s)).map_err(|e| WalletConnectError::field("data", e))
+}
+
+fn parse_access_list(access_list: &Value) -> Result<AccessList, WalletConnectError> {
+    AccessList::deserialize(access_list).map_err(|e| WalletConnectError::field("accessList", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::evm::utils::parse_eth_address;
+
+    #[test]
+    fn test_eth_send_transaction_from_session_request() {
+        let request = r#"{
+            "chainId": "eip155:11155111",
+            "request": {
+                "method": "eth_sendTransaction",
+                "params": [{
+                    "from": "0x525521d79134822a342d330bd91DA67976569aF1",
+                    "to": "0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045",
+                    "data": "0xa9059cbb",
+                    "value": "0x2386f26fc10000",
+                    "gas": "0x5208"
+                }]
+            }
+        }"#;
+
+        let tx = eth_send_transaction(request)
+            .unwrap()
+            .nonce(7)
+            .max_fee_per_gas(20_000_000_000)
+            .try_build()
+            .unwrap();
+
+        assert_eq!(tx.chain_id, 11155111);
+        assert_eq!(tx.nonce, 7);
+        assert_eq!(
+            tx.to,
+            Some(parse_eth_address(
+                "d8dA6BF26964aF9D7eEd9e03E53415D37aA96045"
+            ))
+        );
+        assert_eq!(tx.value, 10000000000000000u128);
+        assert_eq!(tx.input, vec![0xa9, 0x05, 0x9c, 0xbb]);
+        assert_eq!(tx.gas_limit, 21000);
+    }
+
+    #[test]
+    fn test_eth_send_transaction_legacy_gas_price() {
+        let request = r#"{
+            "id": 1,
+            "jsonrpc": "2.0",
+            "method": "eth_sendTransaction",
+            "params": [{
+                "chainId": "0x1",
+                "nonce": "0x0",
+                "to": "0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045",
+                "gas": "21000",
+                "gasPrice": "0x3b9aca00",
+                "accessList": [{
+                    "address": "0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045",
+                    "storageKeys": ["0x0000000000000000000000000000000000000000000000000000000000000001"]
+                }]
+            }]
+        }"#;
+
+        let tx = eth_send_transaction(request).unwrap().try_build().unwrap();
+
+        assert_eq!(tx.chain_id, 1);
+        assert_eq!(tx.max_fee_per_gas, 1_000_000_000);
+        assert_eq!(tx.max_priority_fee_per_gas, 1_000_000_000);
+        assert_eq!(tx.access_list.len(), 1);
+        assert_eq!(tx.access_list[0].storage_keys[0][31], 1);
+    }
+
+    #[test]
+    fn test_eth_send_transaction_wrong_method() {
+        let request = r#"{ "method": "eth_sign", "params": [] }"#;
+
+        assert_eq!(
+            eth_send_transaction(request).err(),
+            Some(WalletConnectError::WrongMethod {
+                expected: "eth_sendTransaction",
+                actual: "eth_sign".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn test_eth_send_transaction_method_must_match_exactly() {
+        let request = r#"{ "method": "eth_sendTransactionFoo", "params": [{}] }"#;
+
+        assert!(eth_send_transaction(request).is_err());
+    }
+
+    #[test]
+    fn test_eth_send_transaction_chain_id_mismatch() {
+        let request = r#"{
+            "chainId": "eip155:1",
+            "request": {
+                "method": "eth_sendTransaction",
+                "params": [{ "chainId": "0xaa36a7" }]
+            }
+        }"#;
+
+        assert_eq!(
+            eth_send_transaction(request).err(),
+            Some(WalletConnectError::ChainIdMismatch {
+                request: 1,
+                transaction: 11155111,
+            })
+        );
+
+        let request = request.replace("eip155:1", "eip155:11155111");
+        let tx = eth_send_transaction(&request)
+            .unwrap()
+            .nonce(0)
+            .gas_limit(21_000)
+            .max_fee_per_gas(1)
+            .try_build()
+            .unwrap();
+        assert_eq!(tx.chain_id, 11155111);
+    }
+
+    #[test]
+    fn test_eth_sign_typed_data_with_string_payload() {
+        let request = r#"{
+            "method": "eth_signTypedData_v4",
+            "params": [
+                "0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045",
+                "{\"primaryType\":\"Mail\",\"types\":{},\"domain\":{},\"message\":{}}"
+            ]
+        }"#;
+
+        let request = eth_sign_typed_data(request).unwrap();
+
+        assert_eq!(
+            request.address,
+            parse_eth_address("d8dA6BF26964aF9D7eEd9e03E53415D37aA96045")
+        );
+        assert_eq!(request.typed_data["primaryType"], "Mail");
+    }
+}
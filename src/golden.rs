@@ -0,0 +1,150 @@
+//! Reports of golden compatibility checks against reference implementations.
+//!
+//! A [`GoldenReport`] collects the encodings produced by this crate next to the ones produced
+//! by a reference crate (`near-primitives`, `alloy`, `rust-bitcoin`, ...) for the same input.
+//! The suite in `tests/golden_test.rs` fills one with the reference crates resolved by the
+//! workspace, so consumers pinning other versions can check compatibility with:
+//!
+//! ```text
+//! cargo test --features golden --test golden_test
+//! ```
+use std::fmt;
+
+use crate::types::ChainKind;
+
+/// An encoding produced by this crate along with the one expected by a reference crate.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GoldenCase {
+    pub chain: ChainKind,
+    /// What is encoded, e.g. `eip1559/build_for_signing`.
+    pub name: String,
+    /// The encoding produced by the reference crate.
+    pub expected: Vec<u8>,
+    /// The encoding produced by this crate.
+    pub actual: Vec<u8>,
+}
+
+impl GoldenCase {
+    pub fn is_match(&self) -> bool {
+        self.expected == self.actual
+    }
+
+    /// Returns the offset of the first differing byte, or `None` if the encodings match.
+    ///
+    /// If one encoding is a prefix of the other, this is the length of the shorter one.
+    pub fn first_difference(&self) -> Option<usize> {
+        if self.is_match() {
+            return None;
+        }
+
+        Some(
+            self.expected
+                .iter()
+                .zip(&self.actual)
+                .position(|(expected, actual)| expected != actual)
+                .unwrap_or_else(|| self.expected.len().min(self.actual.len())),
+        )
+    }
+}
+
+/// The results of a golden compatibility run.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct GoldenReport {
+    pub cases: Vec<GoldenCase>,
+}
+
+impl GoldenReport {
+    pub const fn new() -> Self {
+        Self { cases: Vec::new() }
+    }
+
+    /// Records the encoding of this crate (`actual`) against the reference one (`expected`).
+    pub fn check(
+        &mut self,
+        chain: ChainKind,
+        name: impl Into<String>,
+        expected: impl Into<Vec<u8>>,
+        actual: impl Into<Vec<u8>>,
+    ) {
+        self.cases.push(GoldenCase {
+            chain,
+            name: name.into(),
+            expected: expected.into(),
+            actual: actual.into(),
+        });
+    }
+
+    /// Returns the cases whose encodings differ.
+    pub fn mismatches(&self) -> Vec<&GoldenCase> {
+        self.cases.iter().filter(|case| !case.is_match()).collect()
+    }
+
+    /// Returns `true` if every case matches its reference encoding.
+    pub fn is_compatible(&self) -> bool {
+        self.cases.iter().all(GoldenCase::is_match)
+    }
+}
+
+impl fmt::Display for GoldenReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for case in &self.cases {
+            match case.first_difference() {
+                None => writeln!(f, "ok        {:?} {}", case.chain, case.name)?,
+                Some(offset) => {
+                    writeln!(
+                        f,
+                        "MISMATCH  {:?} {} (first difference at byte {})",
+                        case.chain, case.name, offset
+                    )?;
+                    writeln!(f, "  expected: {}", hex::encode(&case.expected))?;
+                    writeln!(f, "  actual:   {}", hex::encode(&case.actual))?;
+                }
+            }
+        }
+        write!(
+            f,
+            "{} cases, {} mismatches",
+            self.cases.len(),
+            self.mismatches().len()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::BitcoinNetwork;
+
+    #[test]
+    fn test_report() {
+        let mut report = GoldenReport::new();
+        report.check(
+            ChainKind::Bitcoin {
+                network: BitcoinNetwork::Bitcoin,
+            },
+            "same",
+            vec![1, 2, 3],
+            vec![1, 2, 3],
+        );
+        assert!(report.is_compatible());
+
+        report.check(ChainKind::Near, "prefix", vec![1, 2], vec![1, 2, 3]);
+        report.check(
+            ChainKind::Evm { chain_id: 1 },
+            "byte",
+            vec![1, 2],
+            vec![1, 4],
+        );
+
+        assert!(!report.is_compatible());
+        let offsets: Vec<_> = report
+            .mismatches()
+            .iter()
+            .map(|case| (case.name.as_str(), case.first_difference()))
+            .collect();
+        assert_eq!(offsets, vec![("prefix", Some(2)), ("byte", Some(1))]);
+        assert!(report
+            .to_string()
+            .ends_with("MISMATCH  Evm { chain_id: 1 } byte (first difference at byte 1)\n  expected: 0102\n  actual:   0104\n3 cases, 2 mismatches"));
+    }
+}
@@ -1,10 +1,47 @@
+#[cfg(feature = "aptos")]
+pub mod aptos;
 #[cfg(feature = "bitcoin")]
 pub mod bitcoin;
+#[cfg(feature = "cardano")]
+pub mod cardano;
+#[cfg(feature = "cosmos")]
+pub mod cosmos;
 #[cfg(feature = "evm")]
 pub mod evm;
 #[cfg(feature = "near")]
 pub mod near;
+#[cfg(feature = "solana")]
+pub mod solana;
+#[cfg(feature = "starknet")]
+pub mod starknet;
+#[cfg(feature = "tron")]
+pub mod tron;
 
+pub mod canonical_json;
 pub mod constants;
+#[cfg(feature = "dev-signers")]
+pub mod dev_signers;
+#[cfg(any(feature = "near", feature = "evm", feature = "bitcoin"))]
+pub mod encode;
+#[cfg(feature = "golden")]
+pub mod golden;
+pub mod omni_builder;
+#[cfg(any(feature = "near", feature = "evm", feature = "bitcoin"))]
+pub mod payload_batch;
+#[cfg(any(feature = "cosmos", feature = "tron"))]
+pub(crate) mod protobuf;
+#[cfg(feature = "request-id")]
+pub mod request_id;
+#[cfg(any(
+    feature = "near",
+    feature = "evm",
+    feature = "bitcoin",
+    feature = "solana"
+))]
+pub mod signature_error;
+pub mod signature_payload;
 pub mod transaction_builder;
 pub mod types;
+pub mod validation;
+#[cfg(any(feature = "near", feature = "evm", feature = "bitcoin"))]
+pub mod versioned_transaction;
@@ -1,4 +1,8 @@
 pub mod near_transaction;
 pub mod near_transaction_builder;
+pub mod near_transaction_v1;
+pub mod prelude;
+pub mod templates;
 pub mod types;
 pub mod utils;
+pub mod wallet_selector;
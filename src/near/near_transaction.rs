@@ -1,10 +1,23 @@
+use std::fmt;
+use std::io::{self, Write};
+
 use borsh::{BorshDeserialize, BorshSerialize};
+use near_sdk::json_types::Base64VecU8;
 use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::serde_json::{json, Value};
 use near_sdk::{borsh, AccountId};
+use sha2::{Digest, Sha256};
+
+use super::types::{AccessKeyPermission, Action, BlockHash, PublicKey, Signature, U64};
+use super::wallet_selector::{self, WalletJsonError};
+use crate::canonical_json::{hex_value, int_value, variant_value, CanonicalJson};
+use crate::signature_error::SignatureError;
+use crate::validation::{Validate, ValidationIssue};
 
-use super::types::{Action, BlockHash, PublicKey, Signature, U64};
+/// Largest recovery id accepted in the last byte of a secp256k1 signature.
+const MAX_SECP256K1_RECOVERY_ID: u8 = 3;
 
-#[derive(Serialize, Deserialize, Debug, Clone, BorshSerialize, BorshDeserialize)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
 #[serde(crate = "near_sdk::serde")]
 pub struct NearTransaction {
     /// An account on which behalf transaction is signed
@@ -23,28 +36,308 @@ pub struct NearTransaction {
     pub actions: Vec<Action>,
 }
 
-#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
 pub struct SignedTransaction {
     pub transaction: NearTransaction,
     pub signature: Signature,
 }
 
+impl SignedTransaction {
+    pub const fn transaction(&self) -> &NearTransaction {
+        &self.transaction
+    }
+
+    pub const fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    /// Returns the hash identifying the transaction on chain, e.g. to query its status.
+    ///
+    /// This is the SHA-256 hash of the unsigned transaction, i.e. the digest that was signed,
+    /// see [`NearTransaction::hash_for_signing`].
+    pub fn hash(&self) -> [u8; 32] {
+        self.transaction.hash_for_signing()
+    }
+
+    #[deprecated(note = "use `hash` instead")]
+    pub fn tx_hash(&self) -> [u8; 32] {
+        self.hash()
+    }
+}
+
 impl NearTransaction {
     pub fn build_for_signing(&self) -> Vec<u8> {
         borsh::to_vec(self).expect("failed to serialize NEAR transaction")
     }
 
+    /// Writes [`Self::build_for_signing`] to `writer`, without first serializing the whole
+    /// transaction in memory, e.g. when it deploys a large contract.
+    pub fn encode_for_signing<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        BorshSerialize::serialize(self, writer)
+    }
+
+    /// Returns the SHA-256 hash of [`Self::build_for_signing`], the digest signed by the
+    /// access key.
+    ///
+    /// NEAR identifies a transaction by the digest its signer signs, so this is also the hash
+    /// of the transaction on chain, the bytes of its `CryptoHash`, e.g. to poll its status with
+    /// the `tx` RPC method once it has been sent.
+    pub fn hash_for_signing(&self) -> [u8; 32] {
+        let mut hasher = Sha256::new();
+        self.encode_for_signing(&mut hasher)
+            .expect("hashing never fails");
+        hasher.finalize().into()
+    }
+
+    #[deprecated(note = "use `hash_for_signing` instead, which is also the transaction hash")]
+    pub fn tx_hash(&self) -> [u8; 32] {
+        self.hash_for_signing()
+    }
+
     pub fn build_with_signature(&self, signature: Signature) -> Vec<u8> {
-        let signed_tx = SignedTransaction {
-            transaction: self.clone(),
-            signature,
-        };
-        borsh::to_vec(&signed_tx).expect("failed to serialize NEAR transaction")
+        let mut buffer = Vec::new();
+        self.encode_with_signature(&signature, &mut buffer)
+            .expect("failed to serialize NEAR transaction");
+        buffer
+    }
+
+    /// Writes [`Self::build_with_signature`] to `writer`, without cloning the transaction nor
+    /// serializing it in memory.
+    pub fn encode_with_signature<W: Write>(
+        &self,
+        signature: &Signature,
+        writer: &mut W,
+    ) -> io::Result<()> {
+        // Same layout as the borsh encoding of `SignedTransaction`.
+        self.encode_for_signing(writer)?;
+        BorshSerialize::serialize(signature, writer)
+    }
+
+    /// Parses a signed transaction, as returned by [`Self::build_with_signature`] or by a
+    /// signing service, so its fields can be checked before it is broadcast.
+    ///
+    /// Only V0 transactions are supported: V1 ones start with a tag byte, see
+    /// [`NearTransactionV1`](super::near_transaction_v1::NearTransactionV1).
+    pub fn from_signed_bytes(bytes: &[u8]) -> Result<SignedTransaction, std::io::Error> {
+        borsh::from_slice(bytes)
+    }
+
+    /// Same as [`Self::build_with_signature`], but first checks that the signature scheme
+    /// matches the signer public key and, for secp256k1, that the recovery id is in range.
+    pub fn try_build_with_signature(
+        &self,
+        signature: Signature,
+    ) -> Result<Vec<u8>, SignatureError> {
+        check_signature(&self.signer_public_key, &signature)?;
+
+        Ok(self.build_with_signature(signature))
+    }
+
+    /// Returns the signed transaction in a JSON form suitable for both logging and submission.
+    ///
+    /// `signed_tx_base64` holds the base64 encoded borsh of the signed transaction, as expected
+    /// by the `send_tx` and `broadcast_tx_*` RPC methods, while `transaction` and `signature`
+    /// give a readable view of what was signed.
+    pub fn to_rpc_json(&self, signature: Signature) -> Value {
+        let signed_tx = Base64VecU8::from(self.build_with_signature(signature.clone()));
+
+        json!({
+            "signed_tx_base64": signed_tx,
+            "transaction": self,
+            "signature": signature,
+        })
     }
 
     pub fn from_json(json: &str) -> Result<Self, near_sdk::serde_json::Error> {
         near_sdk::serde_json::from_str(json)
     }
+
+    /// Parses a transaction in the camelCase JSON of near-api-js, with `{"type", "params"}`
+    /// actions and amounts as strings, see [`wallet_selector::transaction_from_json`].
+    pub fn from_wallet_json(json: &str) -> Result<Self, WalletJsonError> {
+        wallet_selector::transaction_from_json(json)
+    }
+}
+
+/// Checks that the signature scheme matches the public key and, for secp256k1, that the
+/// recovery id is in range.
+pub(crate) fn check_signature(
+    public_key: &PublicKey,
+    signature: &Signature,
+) -> Result<(), SignatureError> {
+    match (public_key, signature) {
+        (PublicKey::ED25519(_), Signature::ED25519(_)) => Ok(()),
+        (PublicKey::SECP256K1(_), Signature::SECP256K1(sig)) => {
+            let v = sig.0[64];
+            if v > MAX_SECP256K1_RECOVERY_ID {
+                return Err(SignatureError::InvalidRecoveryId(u64::from(v)));
+            }
+            Ok(())
+        }
+        _ => Err(SignatureError::SchemeMismatch),
+    }
+}
+
+/// A one-line summary of the transaction, for logs, e.g.
+/// `NEAR alice.near -> bob.near nonce 7: [Transfer 1 NEAR]`.
+impl fmt::Display for NearTransaction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "NEAR {} -> {} nonce {}: [{}]",
+            self.signer_id,
+            self.receiver_id,
+            self.nonce.0,
+            format_actions(&self.actions)
+        )
+    }
+}
+
+/// Joins the descriptions of the actions, as in the summary of a transaction.
+pub(crate) fn format_actions(actions: &[Action]) -> String {
+    actions
+        .iter()
+        .map(Action::to_string)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+impl CanonicalJson for NearTransaction {
+    fn canonical_value(&self) -> Value {
+        json!({
+            "signer_id": self.signer_id.as_str(),
+            "signer_public_key": canonical_borsh(&self.signer_public_key),
+            "nonce": int_value(self.nonce.0),
+            "receiver_id": self.receiver_id.as_str(),
+            "block_hash": hex_value(self.block_hash.0),
+            "actions": self.actions.iter().map(canonical_action).collect::<Vec<_>>(),
+        })
+    }
+}
+
+/// Returns the hex of the borsh encoding of keys and signatures, which includes their type.
+fn canonical_borsh<T: BorshSerialize>(value: &T) -> Value {
+    hex_value(borsh::to_vec(value).expect("failed to serialize NEAR key or signature"))
+}
+
+fn canonical_action(action: &Action) -> Value {
+    match action {
+        Action::CreateAccount(_) => json!("CreateAccount"),
+        Action::DeployContract(action) => {
+            variant_value("DeployContract", json!({ "code": hex_value(&action.code) }))
+        }
+        Action::FunctionCall(action) => variant_value(
+            "FunctionCall",
+            json!({
+                "method_name": action.method_name,
+                "args": hex_value(&action.args),
+                "gas": int_value(action.gas.as_gas()),
+                "deposit": int_value(action.deposit.as_yoctonear()),
+            }),
+        ),
+        Action::Transfer(action) => variant_value(
+            "Transfer",
+            json!({ "deposit": int_value(action.deposit.as_yoctonear()) }),
+        ),
+        Action::Stake(action) => variant_value(
+            "Stake",
+            json!({
+                "stake": int_value(action.stake.as_yoctonear()),
+                "public_key": canonical_borsh(&action.public_key),
+            }),
+        ),
+        Action::AddKey(action) => {
+            let permission = match &action.access_key.permission {
+                AccessKeyPermission::FunctionCall(permission) => variant_value(
+                    "FunctionCall",
+                    json!({
+                        "allowance": permission.allowance.as_ref().map(|allowance| int_value(allowance.0)),
+                        "receiver_id": permission.receiver_id,
+                        "method_names": permission.method_names,
+                    }),
+                ),
+                AccessKeyPermission::FullAccess => json!("FullAccess"),
+            };
+
+            variant_value(
+                "AddKey",
+                json!({
+                    "public_key": canonical_borsh(&action.public_key),
+                    "access_key": {
+                        "nonce": int_value(action.access_key.nonce.0),
+                        "permission": permission,
+                    },
+                }),
+            )
+        }
+        Action::DeleteKey(action) => variant_value(
+            "DeleteKey",
+            json!({ "public_key": canonical_borsh(&action.public_key) }),
+        ),
+        Action::DeleteAccount(action) => variant_value(
+            "DeleteAccount",
+            json!({ "beneficiary_id": action.beneficiary_id.as_str() }),
+        ),
+        Action::Delegate(action) => {
+            let delegate_action = &action.delegate_action;
+
+            variant_value(
+                "Delegate",
+                json!({
+                    "delegate_action": {
+                        "sender_id": delegate_action.sender_id.as_str(),
+                        "receiver_id": delegate_action.receiver_id.as_str(),
+                        "actions": delegate_action
+                            .actions
+                            .iter()
+                            .map(|action| canonical_action(action.as_action()))
+                            .collect::<Vec<_>>(),
+                        "nonce": int_value(delegate_action.nonce.0),
+                        "max_block_height": int_value(delegate_action.max_block_height.0),
+                        "public_key": canonical_borsh(&delegate_action.public_key),
+                    },
+                    "signature": canonical_borsh(&action.signature),
+                }),
+            )
+        }
+    }
+}
+
+impl Validate for NearTransaction {
+    fn validate(&self) -> Vec<ValidationIssue> {
+        let mut issues = Vec::new();
+
+        if self.nonce.0 == 0 {
+            issues.push(ValidationIssue::error(
+                "nonce",
+                "nonce should be greater than the access key nonce",
+            ));
+        }
+        if self.block_hash.0 == [0u8; 32] {
+            issues.push(ValidationIssue::warning(
+                "block_hash",
+                "block hash is not set",
+            ));
+        }
+        if self.actions.is_empty() {
+            issues.push(ValidationIssue::warning(
+                "actions",
+                "transaction has no actions",
+            ));
+        }
+        // The runtime requires the account deletion to be the last action.
+        let last = self.actions.len().saturating_sub(1);
+        for (i, action) in self.actions.iter().enumerate() {
+            if matches!(action, Action::DeleteAccount(_)) && i != last {
+                issues.push(ValidationIssue::error(
+                    format!("actions[{}]", i),
+                    "DeleteAccount should be the last action",
+                ));
+            }
+        }
+
+        issues
+    }
 }
 
 #[cfg(test)]
@@ -56,9 +349,9 @@ mod tests {
         CreateAccountAction as OmniCreateAccountAction,
         DeleteAccountAction as OmniDeleteAccountAction, DeleteKeyAction as OmniDeleteKeyAction,
         DeployContractAction as OmniDeployContractAction, ED25519Signature,
-        FunctionCallAction as OmniFunctionCallAction, Secp256K1Signature,
+        FunctionCallAction as OmniFunctionCallAction, NearGas, NearToken, Secp256K1Signature,
         Signature as OmniSignature, StakeAction as OmniStakeAction,
-        TransferAction as OmniTransferAction, U128,
+        TransferAction as OmniTransferAction,
     };
     use crate::near::utils::PublicKeyStrExt;
     use near_crypto::{ED25519PublicKey, PublicKey};
@@ -128,8 +421,8 @@ mod tests {
                 omni_actions: vec![OmniAction::FunctionCall(Box::new(OmniFunctionCallAction {
                     method_name: "function1".to_string(),
                     args: vec![0x01, 0x02, 0x03],
-                    gas: U64(100),
-                    deposit: U128(1),
+                    gas: NearGas::from_gas(100),
+                    deposit: NearToken::from_yoctonear(1),
                 }))],
             },
             // Transfer
@@ -141,7 +434,7 @@ mod tests {
                 block_hash: "4reLvkAWfqk5fsqio1KLudk46cqRz9erQdaHkWZKMJDZ",
                 near_primitive_actions: vec![Action::Transfer(TransferAction { deposit: 1u128 })],
                 omni_actions: vec![OmniAction::Transfer(OmniTransferAction {
-                    deposit: U128(1),
+                    deposit: NearToken::from_yoctonear(1),
                 })],
             },
             // Stake
@@ -160,7 +453,7 @@ mod tests {
                     )),
                 }))],
                 omni_actions: vec![OmniAction::Stake(Box::new(OmniStakeAction {
-                    stake: U128(1),
+                    stake: NearToken::from_yoctonear(1),
                     public_key: "ed25519:6E8sCci9badyRkXb3JoRpBj5p8C6Tw41ELDZoiihKEtp"
                         .to_public_key()
                         .unwrap(),
@@ -252,7 +545,9 @@ mod tests {
                     })),
                 ],
                 omni_actions: vec![
-                    OmniAction::Transfer(OmniTransferAction { deposit: U128(1) }),
+                    OmniAction::Transfer(OmniTransferAction {
+                        deposit: NearToken::from_yoctonear(1),
+                    }),
                     OmniAction::AddKey(Box::new(OmniAddKeyAction {
                         public_key: "ed25519:6E8sCci9badyRkXb3JoRpBj5p8C6Tw41ELDZoiihKEtp"
                             .to_public_key()
@@ -307,6 +602,10 @@ mod tests {
                 "Test case {} failed: serialized transactions do not match.\nNEAR: {:?}\nOmni: {:?}",
                 i, serialized_near_primitive_v0_tx, serialized_omni_tx
             );
+            assert_eq!(
+                omni_tx.hash_for_signing(),
+                CryptoHash::hash_bytes(&serialized_near_primitive_v0_tx).0
+            );
         }
     }
 
@@ -363,13 +662,20 @@ mod tests {
                 s: signature_bytes[32..].try_into().unwrap(),
             });
 
-            let serialized_omni_tx = omni_tx.build_with_signature(omni_signature);
+            let serialized_omni_tx = omni_tx.build_with_signature(omni_signature.clone());
 
             assert_eq!(
                 serialized_omni_tx, encoded_signed_tx,
                 "Test case {} failed: serialized transactions do not match.\nNEAR: {:?}\nOmni: {:?}",
                 i, serialized_omni_tx, encoded_signed_tx
             );
+
+            let decoded = NearTransaction::from_signed_bytes(&encoded_signed_tx).unwrap();
+            assert_eq!(decoded.transaction(), &omni_tx);
+            assert_eq!(decoded.signature(), &omni_signature);
+            assert_eq!(decoded.hash(), signed_tx.get_hash().0);
+            assert_eq!(omni_tx.hash_for_signing(), signed_tx.get_hash().0);
+            assert!(NearTransaction::from_signed_bytes(&encoded_signed_tx[1..]).is_err());
         }
     }
 
@@ -416,26 +722,11 @@ mod tests {
             };
 
             // @dev For testing purposes, we are only supporting SECP256K1 signatures
-            let signature_serialized = serde_json::to_string(&signed_tx.signature).unwrap();
-
-            // Extract the actual signature bytes from the serialized string
-            let signature_str = signature_serialized
-                .trim_matches('"')
-                .split(':')
-                .nth(1)
-                .unwrap();
-
-            let signature_str = signature_str.trim();
-
-            // Decode the signature from Base58
-            let signature_bytes = bs58::decode(signature_str)
-                .into_vec()
-                .expect("Failed to decode Base58 signature");
-
-            let mut signature_array: [u8; 65] = [0; 65];
-            signature_array.copy_from_slice(&signature_bytes);
-
-            let omni_signature = OmniSignature::SECP256K1(Secp256K1Signature(signature_array));
+            let near_crypto::Signature::SECP256K1(signature) = signed_tx.signature.clone() else {
+                panic!("Test case {} should be signed with a SECP256K1 key", i);
+            };
+            let omni_signature =
+                OmniSignature::SECP256K1(Secp256K1Signature::from_rsv(&signature.into()).unwrap());
 
             let serialized_omni_tx = omni_tx.build_with_signature(omni_signature);
 
@@ -528,4 +819,136 @@ mod tests {
         );
         assert!(tx.actions.len() == 1);
     }
+
+    #[test]
+    fn test_to_rpc_json() {
+        let tx = NearTransaction::from_json(
+            r#"{
+            "signer_id": "forgetful-parent.testnet",
+            "signer_public_key": "ed25519:6E8sCci9badyRkXb3JoRpBj5p8C6Tw41ELDZoiihKEtp",
+            "nonce": 1,
+            "receiver_id": "forgetful-parent.testnet",
+            "block_hash": "4reLvkAWfqk5fsqio1KLudk46cqRz9erQdaHkWZKMJDZ",
+            "actions": [
+                { "Transfer": { "deposit": 1 } }
+            ]
+        }"#,
+        )
+        .unwrap();
+        let signature = OmniSignature::ED25519(ED25519Signature {
+            r: [1u8; 32],
+            s: [2u8; 32],
+        });
+
+        let json = tx.to_rpc_json(signature.clone());
+
+        let signed_tx: Base64VecU8 =
+            near_sdk::serde_json::from_value(json["signed_tx_base64"].clone()).unwrap();
+        assert_eq!(signed_tx.0, tx.build_with_signature(signature));
+        assert_eq!(json["transaction"]["signer_id"], "forgetful-parent.testnet");
+        assert!(json["signature"].as_str().unwrap().starts_with("ed25519:"));
+    }
+
+    #[test]
+    fn test_try_build_with_signature() {
+        let tx = NearTransaction {
+            signer_id: "alice.near".parse().unwrap(),
+            signer_public_key: crate::near::types::PublicKey::SECP256K1([0u8; 64].into()),
+            nonce: U64(0),
+            receiver_id: "bob.near".parse().unwrap(),
+            block_hash: BlockHash([0u8; 32]),
+            actions: vec![OmniAction::Transfer(OmniTransferAction {
+                deposit: NearToken::from_yoctonear(1),
+            })],
+        };
+
+        let mut bytes = [1u8; 65];
+        bytes[64] = 1;
+        let signature = OmniSignature::SECP256K1(Secp256K1Signature(bytes));
+        assert_eq!(
+            tx.try_build_with_signature(signature.clone()).unwrap(),
+            tx.build_with_signature(signature)
+        );
+
+        bytes[64] = 27;
+        assert_eq!(
+            tx.try_build_with_signature(OmniSignature::SECP256K1(Secp256K1Signature(bytes))),
+            Err(SignatureError::InvalidRecoveryId(27))
+        );
+
+        let ed25519 = OmniSignature::ED25519(ED25519Signature {
+            r: [1u8; 32],
+            s: [2u8; 32],
+        });
+        assert_eq!(
+            tx.try_build_with_signature(ed25519),
+            Err(SignatureError::SchemeMismatch)
+        );
+    }
+
+    #[test]
+    fn test_encode_matches_build() {
+        let tx = NearTransaction {
+            signer_id: "alice.near".parse().unwrap(),
+            signer_public_key: "ed25519:6E8sCci9badyRkXb3JoRpBj5p8C6Tw41ELDZoiihKEtp"
+                .to_public_key()
+                .unwrap(),
+            nonce: U64(1),
+            receiver_id: "alice.near".parse().unwrap(),
+            block_hash: BlockHash([0u8; 32]),
+            actions: vec![OmniAction::DeployContract(OmniDeployContractAction {
+                code: vec![0xab; 300_000],
+            })],
+        };
+        let signature = OmniSignature::ED25519(ED25519Signature {
+            r: [1u8; 32],
+            s: [2u8; 32],
+        });
+
+        let mut encoded = Vec::new();
+        tx.encode_for_signing(&mut encoded).unwrap();
+        assert_eq!(encoded, tx.build_for_signing());
+        assert_eq!(
+            tx.hash_for_signing(),
+            <[u8; 32]>::from(Sha256::digest(&encoded))
+        );
+
+        let mut encoded = Vec::new();
+        tx.encode_with_signature(&signature, &mut encoded).unwrap();
+        let signed_tx = SignedTransaction {
+            transaction: tx.clone(),
+            signature: signature.clone(),
+        };
+        assert_eq!(encoded, borsh::to_vec(&signed_tx).unwrap());
+        assert_eq!(encoded, tx.build_with_signature(signature));
+    }
+
+    #[test]
+    fn test_validate_delete_account_must_be_last() {
+        let mut tx = NearTransaction {
+            signer_id: "alice.near".parse().unwrap(),
+            signer_public_key: "ed25519:6E8sCci9badyRkXb3JoRpBj5p8C6Tw41ELDZoiihKEtp"
+                .to_public_key()
+                .unwrap(),
+            nonce: U64(1),
+            receiver_id: "alice.near".parse().unwrap(),
+            block_hash: BlockHash([1u8; 32]),
+            actions: vec![
+                OmniAction::DeleteAccount(OmniDeleteAccountAction {
+                    beneficiary_id: "bob.near".parse().unwrap(),
+                }),
+                OmniAction::Transfer(OmniTransferAction {
+                    deposit: NearToken::from_yoctonear(1),
+                }),
+            ],
+        };
+
+        let issues = tx.validate();
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].field, "actions[0]");
+        assert!(!tx.is_valid());
+
+        tx.actions.reverse();
+        assert!(tx.validate().is_empty());
+    }
 }
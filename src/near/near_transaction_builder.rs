@@ -1,9 +1,50 @@
+use std::fmt;
+
+use near_sdk::AccountId;
+
 use super::{
     near_transaction::NearTransaction,
-    types::{Action, BlockHash, PublicKey},
+    near_transaction_v1::NearTransactionV1,
+    types::{
+        AccessKey, AccessKeyPermission, Action, AddKeyAction, BlockHash, CreateAccountAction,
+        DeleteAccountAction, DeleteKeyAction, DeployContractAction, FunctionCallAction,
+        FunctionCallPermission, NearGas, NearToken, PublicKey, TransferAction, U128, U64,
+    },
+    utils::{AccountIdError, AccountIdValidation},
 };
 use crate::transaction_builder::TxBuilder;
 
+/// Errors returned by [`NearTransactionBuilder::try_build`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NearBuildError {
+    /// A mandatory field was not set.
+    MissingField(&'static str),
+    /// An account ID field was rejected by the configured validation.
+    InvalidAccountId {
+        field: &'static str,
+        error: AccountIdError,
+    },
+    /// A destructive action was included after calling
+    /// [`NearTransactionBuilder::deny_destructive_actions`].
+    DestructiveActionDenied { index: usize, action: &'static str },
+}
+
+impl fmt::Display for NearBuildError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingField(field) => write!(f, "{} is mandatory", field),
+            Self::InvalidAccountId { field, error } => write!(f, "{}: {}", field, error),
+            Self::DestructiveActionDenied { index, action } => write!(
+                f,
+                "actions[{}]: {} is destructive and destructive actions are denied",
+                index, action
+            ),
+        }
+    }
+}
+
+impl std::error::Error for NearBuildError {}
+
 pub struct NearTransactionBuilder {
     pub signer_id: Option<String>,
     pub signer_public_key: Option<PublicKey>,
@@ -11,6 +52,12 @@ pub struct NearTransactionBuilder {
     pub receiver_id: Option<String>,
     pub block_hash: Option<BlockHash>,
     pub actions: Option<Vec<Action>>,
+    pub account_id_validation: AccountIdValidation,
+    /// Whether [`Self::try_build`] rejects destructive actions, see
+    /// [`Self::deny_destructive_actions`].
+    pub deny_destructive_actions: bool,
+    /// Priority fee of the V1 transaction returned by [`Self::build_v1`], zero if not set.
+    pub priority_fee: Option<u64>,
 }
 
 impl Default for NearTransactionBuilder {
@@ -24,10 +71,10 @@ impl TxBuilder<NearTransaction> for NearTransactionBuilder {
         NearTransaction {
             signer_id: self
                 .signer_id
-                .clone()
+                .as_deref()
                 .expect("Missing sender ID")
                 .parse()
-                .unwrap(),
+                .expect("Invalid sender ID"),
             signer_public_key: self
                 .signer_public_key
                 .clone()
@@ -35,11 +82,11 @@ impl TxBuilder<NearTransaction> for NearTransactionBuilder {
             nonce: self.nonce.expect("Missing nonce").into(),
             receiver_id: self
                 .receiver_id
-                .clone()
+                .as_deref()
                 .expect("Missing receiver ID")
                 .parse()
-                .unwrap(),
-            block_hash: self.block_hash.clone().expect("Missing block hash"),
+                .expect("Invalid receiver ID"),
+            block_hash: self.block_hash.expect("Missing block hash"),
             actions: self.actions.clone().expect("Missing actions"),
         }
     }
@@ -54,11 +101,79 @@ impl NearTransactionBuilder {
             receiver_id: None,
             block_hash: None,
             actions: None,
+            account_id_validation: AccountIdValidation::new(),
+            deny_destructive_actions: false,
+            priority_fee: None,
         }
     }
 
-    pub fn signer_id(mut self, signer_id: String) -> Self {
-        self.signer_id = Some(signer_id);
+    /// Builds a V1 transaction (NEP-541) paying the configured priority fee.
+    pub fn build_v1(&self) -> NearTransactionV1 {
+        NearTransactionV1::from_transaction(self.build(), self.priority_fee.unwrap_or_default())
+    }
+
+    /// Same as [`Self::build_v1`], with the checks of [`Self::try_build`].
+    pub fn try_build_v1(&self) -> Result<NearTransactionV1, NearBuildError> {
+        Ok(NearTransactionV1::from_transaction(
+            self.try_build()?,
+            self.priority_fee.unwrap_or_default(),
+        ))
+    }
+
+    /// Builds the transaction, returning an error instead of panicking if a mandatory field is
+    /// missing or an account ID is rejected by the configured validation. Destructive actions
+    /// are also rejected after calling [`Self::deny_destructive_actions`].
+    pub fn try_build(&self) -> Result<NearTransaction, NearBuildError> {
+        let validate = |field: &'static str, account_id: &Option<String>| {
+            let account_id = account_id
+                .as_deref()
+                .ok_or(NearBuildError::MissingField(field))?;
+            self.account_id_validation
+                .validate(account_id)
+                .map_err(|error| NearBuildError::InvalidAccountId { field, error })
+        };
+
+        Ok(NearTransaction {
+            signer_id: validate("signer_id", &self.signer_id)?,
+            signer_public_key: self
+                .signer_public_key
+                .clone()
+                .ok_or(NearBuildError::MissingField("signer_public_key"))?,
+            nonce: self
+                .nonce
+                .ok_or(NearBuildError::MissingField("nonce"))?
+                .into(),
+            receiver_id: validate("receiver_id", &self.receiver_id)?,
+            block_hash: self
+                .block_hash
+                .ok_or(NearBuildError::MissingField("block_hash"))?,
+            actions: self.checked_actions()?,
+        })
+    }
+
+    fn checked_actions(&self) -> Result<Vec<Action>, NearBuildError> {
+        let actions = self
+            .actions
+            .clone()
+            .ok_or(NearBuildError::MissingField("actions"))?;
+
+        if self.deny_destructive_actions {
+            if let Some((index, action)) = actions
+                .iter()
+                .enumerate()
+                .find_map(|(i, action)| destructive_action_name(action).map(|name| (i, name)))
+            {
+                return Err(NearBuildError::DestructiveActionDenied { index, action });
+            }
+        }
+
+        Ok(actions)
+    }
+
+    /// Sets the signer, either an [`AccountId`] or a string, e.g. from user input, which is only
+    /// validated when building: use [`Self::try_build`] to get an error if it is invalid.
+    pub fn signer_id(mut self, signer_id: impl Into<String>) -> Self {
+        self.signer_id = Some(signer_id.into());
         self
     }
 
@@ -72,8 +187,9 @@ impl NearTransactionBuilder {
         self
     }
 
-    pub fn receiver_id(mut self, receiver_id: String) -> Self {
-        self.receiver_id = Some(receiver_id);
+    /// Sets the receiver, validated like the signer, see [`Self::signer_id`].
+    pub fn receiver_id(mut self, receiver_id: impl Into<String>) -> Self {
+        self.receiver_id = Some(receiver_id.into());
         self
     }
 
@@ -86,14 +202,154 @@ impl NearTransactionBuilder {
         self.actions = Some(actions);
         self
     }
+
+    /// Appends an action, after those already set.
+    pub fn action(mut self, action: Action) -> Self {
+        self.actions.get_or_insert_with(Vec::new).push(action);
+        self
+    }
+
+    pub fn create_account(self) -> Self {
+        self.action(Action::CreateAccount(CreateAccountAction {}))
+    }
+
+    pub fn deploy_contract(self, code: Vec<u8>) -> Self {
+        self.action(Action::DeployContract(DeployContractAction { code }))
+    }
+
+    /// Appends a call of `method_name` with the already serialized `args`, usually JSON.
+    ///
+    /// `gas` and `deposit` are either raw gas units and yoctoNEAR, or a
+    /// [`NearGas`](super::types::NearGas) and a [`NearToken`](super::types::NearToken), e.g.
+    /// `NearGas::from_tgas(30)` and `NearToken::from_near(1)`.
+    pub fn function_call(
+        self,
+        method_name: impl Into<String>,
+        args: Vec<u8>,
+        gas: impl Into<NearGas>,
+        deposit: impl Into<NearToken>,
+    ) -> Self {
+        self.action(Action::FunctionCall(Box::new(FunctionCallAction {
+            method_name: method_name.into(),
+            args,
+            gas: gas.into(),
+            deposit: deposit.into(),
+        })))
+    }
+
+    /// Appends a transfer of `deposit`, in yoctoNEAR or as a
+    /// [`NearToken`](super::types::NearToken), to the receiver.
+    pub fn transfer(self, deposit: impl Into<NearToken>) -> Self {
+        self.action(Action::Transfer(TransferAction {
+            deposit: deposit.into(),
+        }))
+    }
+
+    /// Appends an action adding a full access key, with a zero nonce. It is destructive, see
+    /// [`Self::deny_destructive_actions`].
+    pub fn add_full_access_key(self, public_key: PublicKey) -> Self {
+        self.add_key(public_key, AccessKeyPermission::FullAccess)
+    }
+
+    /// Appends an action adding a key which can only call `method_names` of `receiver_id`, or
+    /// any of its methods if empty, spending at most `allowance` yoctoNEAR on fees.
+    pub fn add_function_call_key(
+        self,
+        public_key: PublicKey,
+        receiver_id: impl Into<String>,
+        method_names: Vec<String>,
+        allowance: Option<u128>,
+    ) -> Self {
+        self.add_key(
+            public_key,
+            AccessKeyPermission::FunctionCall(FunctionCallPermission {
+                allowance: allowance.map(U128),
+                receiver_id: receiver_id.into(),
+                method_names,
+            }),
+        )
+    }
+
+    fn add_key(self, public_key: PublicKey, permission: AccessKeyPermission) -> Self {
+        self.action(Action::AddKey(Box::new(AddKeyAction {
+            public_key,
+            access_key: AccessKey {
+                nonce: U64(0),
+                permission,
+            },
+        })))
+    }
+
+    /// Appends an action deleting a key. It is destructive, see
+    /// [`Self::deny_destructive_actions`].
+    pub fn delete_key(self, public_key: PublicKey) -> Self {
+        self.action(Action::DeleteKey(Box::new(DeleteKeyAction { public_key })))
+    }
+
+    /// Appends an action deleting the receiver account and sending its balance to
+    /// `beneficiary_id`. It is destructive, see [`Self::deny_destructive_actions`].
+    pub fn delete_account(self, beneficiary_id: AccountId) -> Self {
+        self.action(Action::DeleteAccount(DeleteAccountAction {
+            beneficiary_id,
+        }))
+    }
+
+    /// Priority fee paid by the transaction, only serialized by [`Self::build_v1`] and
+    /// [`Self::try_build_v1`].
+    pub const fn priority_fee(mut self, priority_fee: u64) -> Self {
+        self.priority_fee = Some(priority_fee);
+        self
+    }
+
+    /// Account ID validation applied by [`Self::try_build`].
+    pub const fn account_id_validation(
+        mut self,
+        account_id_validation: AccountIdValidation,
+    ) -> Self {
+        self.account_id_validation = account_id_validation;
+        self
+    }
+
+    /// Makes [`Self::try_build`] reject `DeleteAccount`, `DeleteKey`, `Stake` and full access
+    /// `AddKey` actions.
+    ///
+    /// Relayers building transactions from untrusted input (e.g. wallet JSON) should call this
+    /// until the request was confirmed, so an injected action cannot delete the account, lock
+    /// out its keys, hand over full access or stake its balance.
+    pub const fn deny_destructive_actions(mut self) -> Self {
+        self.deny_destructive_actions = true;
+        self
+    }
+}
+
+/// Returns the name of the action if it is destructive.
+///
+/// Key permissions are not known offline, so every `DeleteKey` is treated as possibly removing
+/// a full access key.
+fn destructive_action_name(action: &Action) -> Option<&'static str> {
+    match action {
+        Action::AddKey(add_key)
+            if matches!(
+                add_key.access_key.permission,
+                AccessKeyPermission::FullAccess
+            ) =>
+        {
+            Some("AddKey full access")
+        }
+        Action::DeleteAccount(_) => Some("DeleteAccount"),
+        Action::DeleteKey(_) => Some("DeleteKey"),
+        Action::Stake(_) => Some("Stake"),
+        _ => None,
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::near::near_transaction_v1::TRANSACTION_V1_TAG;
     use crate::near::types::{
-        Action as OmniAction, PublicKey as OmniPublicKey, TransferAction as OmniTransferAction,
-        U128,
+        Action as OmniAction, NearGas, NearToken, PublicKey as OmniPublicKey,
+        TransferAction as OmniTransferAction,
     };
     use near_crypto::PublicKey;
     use near_primitives::{
@@ -107,7 +363,9 @@ mod tests {
         let nonce = 0;
         let receiver_id: &str = "bob.near";
         let block_hash = BlockHash([0u8; 32]);
-        let transfer_action = OmniAction::Transfer(OmniTransferAction { deposit: U128(1) });
+        let transfer_action = OmniAction::Transfer(OmniTransferAction {
+            deposit: NearToken::from_yoctonear(1),
+        });
         let omni_actions = vec![transfer_action];
         let actions = Action::Transfer(TransferAction { deposit: 1u128 });
 
@@ -135,4 +393,193 @@ mod tests {
 
         assert!(serialized_v0_tx == omni_tx_encoded);
     }
+
+    #[test]
+    fn test_try_build_with_strict_account_id_validation() {
+        let builder = NearTransactionBuilder::new()
+            .signer_id("alice.near".to_string())
+            .signer_public_key(OmniPublicKey::SECP256K1([0u8; 64].into()))
+            .nonce(0)
+            .receiver_id("bob".to_string())
+            .block_hash(BlockHash([0u8; 32]))
+            .actions(vec![]);
+
+        assert!(builder.try_build().is_ok());
+
+        let builder = builder.account_id_validation(AccountIdValidation::strict());
+        assert_eq!(
+            builder.try_build(),
+            Err(NearBuildError::InvalidAccountId {
+                field: "receiver_id",
+                error: AccountIdError::TopLevelNotAllowed("bob".to_string()),
+            })
+        );
+    }
+
+    #[test]
+    fn test_account_ids_from_parsed_or_invalid_input() {
+        let signer_id: AccountId = "alice.near".parse().unwrap();
+        let builder = NearTransactionBuilder::new()
+            .signer_id(signer_id.clone())
+            .signer_public_key(OmniPublicKey::SECP256K1([0u8; 64].into()))
+            .nonce(0)
+            .receiver_id("bob.near")
+            .block_hash(BlockHash([0u8; 32]))
+            .actions(vec![]);
+
+        let tx = builder.try_build().unwrap();
+        assert_eq!(tx.signer_id, signer_id);
+        assert_eq!(tx.receiver_id.as_str(), "bob.near");
+
+        let result = builder.receiver_id("Bob!").try_build();
+        assert!(matches!(
+            result,
+            Err(NearBuildError::InvalidAccountId {
+                field: "receiver_id",
+                error: AccountIdError::InvalidSyntax(_),
+            })
+        ));
+    }
+
+    #[test]
+    fn test_try_build_missing_field() {
+        let result = NearTransactionBuilder::new()
+            .signer_id("alice.near".to_string())
+            .try_build();
+
+        assert_eq!(
+            result,
+            Err(NearBuildError::MissingField("signer_public_key"))
+        );
+    }
+
+    #[test]
+    fn test_try_build_denies_destructive_actions() {
+        let builder = NearTransactionBuilder::new()
+            .signer_id("alice.near".to_string())
+            .signer_public_key(OmniPublicKey::SECP256K1([0u8; 64].into()))
+            .nonce(1)
+            .receiver_id("alice.near".to_string())
+            .block_hash(BlockHash([0u8; 32]))
+            .actions(vec![
+                OmniAction::Transfer(OmniTransferAction {
+                    deposit: NearToken::from_yoctonear(1),
+                }),
+                OmniAction::DeleteAccount(DeleteAccountAction {
+                    beneficiary_id: "bob.near".parse().unwrap(),
+                }),
+            ]);
+
+        assert_eq!(builder.build(), builder.try_build().unwrap());
+
+        let builder = builder.deny_destructive_actions();
+        assert_eq!(
+            builder.try_build(),
+            Err(NearBuildError::DestructiveActionDenied {
+                index: 1,
+                action: "DeleteAccount",
+            })
+        );
+        assert!(builder.try_build_v1().is_err());
+        // `build` stays unchecked.
+        assert_eq!(builder.build().actions.len(), 2);
+
+        let builder = builder.actions(vec![]).add_function_call_key(
+            OmniPublicKey::ED25519([1u8; 32].into()),
+            "app.near",
+            vec![],
+            None,
+        );
+        assert!(builder.try_build().is_ok());
+
+        let builder = builder.add_full_access_key(OmniPublicKey::ED25519([2u8; 32].into()));
+        assert_eq!(
+            builder.try_build(),
+            Err(NearBuildError::DestructiveActionDenied {
+                index: 1,
+                action: "AddKey full access",
+            })
+        );
+    }
+
+    #[test]
+    fn test_build_v1_with_priority_fee() {
+        let builder = NearTransactionBuilder::new()
+            .signer_id("alice.near".to_string())
+            .signer_public_key(OmniPublicKey::SECP256K1([0u8; 64].into()))
+            .nonce(1)
+            .receiver_id("bob.near".to_string())
+            .block_hash(BlockHash([0u8; 32]))
+            .actions(vec![OmniAction::Transfer(OmniTransferAction {
+                deposit: NearToken::from_yoctonear(1),
+            })]);
+
+        assert_eq!(builder.build_v1().priority_fee, U64(0));
+
+        let tx = builder.priority_fee(100).try_build_v1().unwrap();
+        assert_eq!(tx.priority_fee, U64(100));
+        assert_eq!(tx.build_for_signing()[0], TRANSACTION_V1_TAG);
+    }
+
+    #[test]
+    fn test_action_helpers_append_actions() {
+        let public_key = OmniPublicKey::ED25519([1u8; 32].into());
+        let builder = NearTransactionBuilder::new()
+            .transfer(1)
+            .function_call("ft_transfer", b"{}".to_vec(), 30_000_000_000_000, 1)
+            .add_full_access_key(public_key.clone())
+            .delete_key(public_key.clone());
+
+        assert_eq!(
+            builder.actions,
+            Some(vec![
+                OmniAction::Transfer(OmniTransferAction {
+                    deposit: NearToken::from_yoctonear(1)
+                }),
+                OmniAction::FunctionCall(Box::new(FunctionCallAction {
+                    method_name: "ft_transfer".to_string(),
+                    args: b"{}".to_vec(),
+                    gas: NearGas::from_gas(30_000_000_000_000),
+                    deposit: NearToken::from_yoctonear(1),
+                })),
+                OmniAction::AddKey(Box::new(AddKeyAction {
+                    public_key: public_key.clone(),
+                    access_key: AccessKey {
+                        nonce: U64(0),
+                        permission: AccessKeyPermission::FullAccess,
+                    },
+                })),
+                OmniAction::DeleteKey(Box::new(DeleteKeyAction { public_key })),
+            ])
+        );
+
+        let builder = NearTransactionBuilder::new()
+            .function_call(
+                "ft_transfer",
+                b"{}".to_vec(),
+                NearGas::from_tgas(30),
+                NearToken::from_yoctonear(1),
+            )
+            .transfer(NearToken::from_near(1));
+        assert_eq!(
+            builder.actions,
+            Some(vec![
+                OmniAction::FunctionCall(Box::new(FunctionCallAction {
+                    method_name: "ft_transfer".to_string(),
+                    args: b"{}".to_vec(),
+                    gas: NearGas::from_gas(30_000_000_000_000),
+                    deposit: NearToken::from_yoctonear(1),
+                })),
+                OmniAction::Transfer(OmniTransferAction {
+                    deposit: NearToken::from_yoctonear(10u128.pow(24)),
+                }),
+            ])
+        );
+
+        // Helpers append to actions set beforehand.
+        let builder = NearTransactionBuilder::new()
+            .actions(vec![OmniAction::CreateAccount(CreateAccountAction {})])
+            .transfer(2);
+        assert_eq!(builder.actions.map(|actions| actions.len()), Some(2));
+    }
 }
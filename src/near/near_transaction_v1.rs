@@ -0,0 +1,195 @@
+use std::fmt;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::{borsh, AccountId};
+use sha2::{Digest, Sha256};
+
+use super::near_transaction::{check_signature, format_actions, NearTransaction};
+use super::types::{Action, BlockHash, PublicKey, Signature, U64};
+use crate::signature_error::SignatureError;
+
+/// Byte prepended to the borsh of a [`NearTransactionV1`] to tell it apart from a V0
+/// transaction, which has no tag.
+pub const TRANSACTION_V1_TAG: u8 = 1;
+
+/// A NEAR transaction paying a priority fee (NEP-541).
+///
+/// Serializes as `near_primitives::transaction::TransactionV1`. Use [`NearTransaction`] for
+/// networks which don't accept V1 transactions yet.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct NearTransactionV1 {
+    /// An account on which behalf transaction is signed
+    pub signer_id: AccountId,
+    /// A public key of the access key which was used to sign an account.
+    /// Access key holds permissions for calling certain kinds of actions.
+    pub signer_public_key: PublicKey,
+    /// Nonce is used to determine order of transaction in the pool.
+    /// It increments for a combination of `signer_id` and `public_key`
+    pub nonce: U64,
+    /// Receiver account for this transaction
+    pub receiver_id: AccountId,
+    /// The hash of the block in the blockchain on top of which the given transaction is valid
+    pub block_hash: BlockHash,
+    /// A list of actions to be applied
+    pub actions: Vec<Action>,
+    /// Fee paid on top of the gas cost to get the transaction included earlier
+    pub priority_fee: U64,
+}
+
+impl NearTransactionV1 {
+    /// Creates a V1 transaction with the fields of `transaction` and the given priority fee.
+    pub fn from_transaction(transaction: NearTransaction, priority_fee: u64) -> Self {
+        Self {
+            signer_id: transaction.signer_id,
+            signer_public_key: transaction.signer_public_key,
+            nonce: transaction.nonce,
+            receiver_id: transaction.receiver_id,
+            block_hash: transaction.block_hash,
+            actions: transaction.actions,
+            priority_fee: priority_fee.into(),
+        }
+    }
+
+    /// Returns the tagged borsh of the transaction, as serialized by
+    /// `near_primitives::transaction::Transaction::V1`.
+    pub fn build_for_signing(&self) -> Vec<u8> {
+        borsh::to_vec(&(TRANSACTION_V1_TAG, self)).expect("failed to serialize NEAR transaction")
+    }
+
+    /// Returns the SHA-256 hash of [`Self::build_for_signing`], the digest signed by the
+    /// access key.
+    pub fn hash_for_signing(&self) -> [u8; 32] {
+        Sha256::digest(self.build_for_signing()).into()
+    }
+
+    pub fn build_with_signature(&self, signature: Signature) -> Vec<u8> {
+        borsh::to_vec(&(TRANSACTION_V1_TAG, self, signature))
+            .expect("failed to serialize NEAR transaction")
+    }
+
+    /// Same as [`Self::build_with_signature`], but first checks that the signature scheme
+    /// matches the signer public key and, for secp256k1, that the recovery id is in range.
+    pub fn try_build_with_signature(
+        &self,
+        signature: Signature,
+    ) -> Result<Vec<u8>, SignatureError> {
+        check_signature(&self.signer_public_key, &signature)?;
+
+        Ok(self.build_with_signature(signature))
+    }
+}
+
+/// Same as the summary of [`NearTransaction`], with the priority fee.
+impl fmt::Display for NearTransactionV1 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "NEAR {} -> {} nonce {}: [{}], priority fee {}",
+            self.signer_id,
+            self.receiver_id,
+            self.nonce.0,
+            format_actions(&self.actions),
+            self.priority_fee.0
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::near::types::{
+        ED25519Signature, NearToken, Signature as OmniSignature,
+        TransferAction as OmniTransferAction,
+    };
+    use crate::near::utils::PublicKeyStrExt;
+    use near_crypto::{ED25519PublicKey, PublicKey as NearPublicKey, Signature as NearSignature};
+    use near_primitives::{
+        action::{Action as NearAction, TransferAction},
+        hash::CryptoHash,
+        transaction::{SignedTransaction, Transaction, TransactionV1},
+    };
+
+    const SIGNER_PUBLIC_KEY: &str = "ed25519:6E8sCci9badyRkXb3JoRpBj5p8C6Tw41ELDZoiihKEtp";
+    const BLOCK_HASH: &str = "4reLvkAWfqk5fsqio1KLudk46cqRz9erQdaHkWZKMJDZ";
+
+    fn transactions() -> (NearTransactionV1, Transaction) {
+        let omni_tx = NearTransactionV1 {
+            signer_id: "alice.near".parse().unwrap(),
+            signer_public_key: SIGNER_PUBLIC_KEY.to_public_key().unwrap(),
+            nonce: U64(7),
+            receiver_id: "bob.near".parse().unwrap(),
+            block_hash: BLOCK_HASH.to_block_hash().unwrap(),
+            actions: vec![Action::Transfer(OmniTransferAction {
+                deposit: NearToken::from_yoctonear(1),
+            })],
+            priority_fee: U64(42),
+        };
+
+        let near_tx = Transaction::V1(TransactionV1 {
+            signer_id: "alice.near".parse().unwrap(),
+            public_key: NearPublicKey::ED25519(ED25519PublicKey(
+                SIGNER_PUBLIC_KEY.try_ed25519_into_bytes().unwrap(),
+            )),
+            nonce: 7,
+            receiver_id: "bob.near".parse().unwrap(),
+            block_hash: CryptoHash(BLOCK_HASH.to_fixed_32_bytes().unwrap()),
+            actions: vec![NearAction::Transfer(TransferAction { deposit: 1 })],
+            priority_fee: 42,
+        });
+
+        (omni_tx, near_tx)
+    }
+
+    #[test]
+    fn test_build_for_signing_against_near_primitives() {
+        let (omni_tx, near_tx) = transactions();
+
+        assert_eq!(
+            omni_tx.build_for_signing(),
+            borsh::to_vec(&near_tx).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_build_with_signature_against_near_primitives() {
+        let (omni_tx, near_tx) = transactions();
+        let signature_bytes = [5u8; 64];
+
+        let signed_tx = SignedTransaction::new(
+            NearSignature::from_parts(near_crypto::KeyType::ED25519, &signature_bytes).unwrap(),
+            near_tx,
+        );
+        let omni_signature = OmniSignature::ED25519(ED25519Signature {
+            r: signature_bytes[..32].try_into().unwrap(),
+            s: signature_bytes[32..].try_into().unwrap(),
+        });
+
+        assert_eq!(
+            omni_tx.try_build_with_signature(omni_signature).unwrap(),
+            borsh::to_vec(&signed_tx).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_display_summary() {
+        let (mut omni_tx, _) = transactions();
+        assert_eq!(
+            omni_tx.to_string(),
+            "NEAR alice.near -> bob.near nonce 7: [Transfer 0.000000000000000000000001 NEAR], \
+             priority fee 42"
+        );
+
+        omni_tx.actions.clear();
+        let tx = NearTransaction {
+            signer_id: omni_tx.signer_id,
+            signer_public_key: omni_tx.signer_public_key,
+            nonce: omni_tx.nonce,
+            receiver_id: omni_tx.receiver_id,
+            block_hash: omni_tx.block_hash,
+            actions: omni_tx.actions,
+        };
+        assert_eq!(tx.to_string(), "NEAR alice.near -> bob.near nonce 7: []");
+    }
+}
@@ -0,0 +1,18 @@
+//! Everything needed to build a NEAR transaction in a single import.
+//!
+//! ```ignore
+//! use omni_transaction::near::prelude::*;
+//! ```
+pub use super::near_transaction::{NearTransaction, SignedTransaction};
+pub use super::near_transaction_builder::{NearBuildError, NearTransactionBuilder};
+pub use super::near_transaction_v1::NearTransactionV1;
+pub use super::types::{
+    AccessKey, AccessKeyPermission, Action, AddKeyAction, BlockHash, CreateAccountAction,
+    DelegateAction, DeleteAccountAction, DeleteKeyAction, DeployContractAction, ED25519PublicKey,
+    ED25519Signature, FunctionCallAction, FunctionCallPermission, NearAmountParseError, NearGas,
+    NearToken, PublicKey, Secp256K1PublicKey, Secp256K1Signature, Signature, SignedDelegateAction,
+    StakeAction, TransferAction, U128, U64,
+};
+pub use super::utils::{NearParseError, PublicKeyStrExt, SignatureStrExt};
+pub use crate::transaction_builder::{TransactionBuilder, TxBuilder};
+pub use crate::types::NEAR;
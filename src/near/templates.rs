@@ -0,0 +1,260 @@
+//! Ready-made actions for common NEAR calls.
+use near_sdk::serde::Serialize;
+use near_sdk::serde_json;
+use near_sdk::AccountId;
+use std::fmt;
+
+use super::{
+    near_transaction_builder::NearTransactionBuilder,
+    types::{Action, FunctionCallAction, NearGas, NearToken},
+};
+use crate::signature_payload::{Curve, ToSignaturePayload};
+
+/// The method of the MPC signer contract which requests a chain signature.
+pub const SIGN_METHOD: &str = "sign";
+
+/// The MPC signer contract on mainnet.
+pub const MAINNET_SIGNER_CONTRACT: &str = "v1.signer";
+
+/// The MPC signer contract on testnet.
+pub const TESTNET_SIGNER_CONTRACT: &str = "v1.signer-prod.testnet";
+
+/// Errors returned by [`sign_transaction_call`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SignRequestError {
+    /// The transaction is not signed with secp256k1.
+    UnsupportedCurve(Curve),
+    /// The transaction is not signed over a 32-byte digest.
+    NotDigest,
+}
+
+impl fmt::Display for SignRequestError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnsupportedCurve(curve) => {
+                write!(
+                    f,
+                    "{:?} payloads cannot be signed with the sign method",
+                    curve
+                )
+            }
+            Self::NotDigest => write!(f, "the payload should be hashed into a 32-byte digest"),
+        }
+    }
+}
+
+impl std::error::Error for SignRequestError {}
+
+/// A `sign` call to the MPC signer contract: the contract to call and the function call action.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignRequestCall {
+    pub receiver_id: AccountId,
+    pub action: FunctionCallAction,
+}
+
+impl SignRequestCall {
+    /// Returns a builder with the receiver and the action set, leaving the signer, its key,
+    /// the nonce and the block hash to the caller.
+    pub fn into_builder(self) -> NearTransactionBuilder {
+        NearTransactionBuilder::new()
+            .receiver_id(self.receiver_id.to_string())
+            .actions(vec![self.into_action()])
+    }
+
+    /// Returns the function call as an action, e.g. to add it to a transaction to the signer
+    /// contract along with other actions.
+    pub fn into_action(self) -> Action {
+        Action::FunctionCall(Box::new(self.action))
+    }
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+struct SignArgs<'a> {
+    request: SignRequest<'a>,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+struct SignRequest<'a> {
+    payload: [u8; 32],
+    path: &'a str,
+    key_version: u32,
+}
+
+/// Builds the `sign` function call of the MPC signer contract, requesting a signature of
+/// `payload` with the key derived from the predecessor account and `path`.
+///
+/// The arguments follow the canonical signer interface:
+/// `{"request": {"payload": [..], "path": "..", "key_version": 0}}`.
+pub fn sign_request_call(
+    signer_contract_id: AccountId,
+    payload: [u8; 32],
+    path: &str,
+    key_version: u32,
+    gas: u64,
+    deposit: u128,
+) -> SignRequestCall {
+    let args = SignArgs {
+        request: SignRequest {
+            payload,
+            path,
+            key_version,
+        },
+    };
+
+    SignRequestCall {
+        receiver_id: signer_contract_id,
+        action: FunctionCallAction {
+            method_name: SIGN_METHOD.to_string(),
+            args: serde_json::to_vec(&args).expect("failed to serialize sign request"),
+            gas: NearGas::from_gas(gas),
+            deposit: NearToken::from_yoctonear(deposit),
+        },
+    }
+}
+
+/// Builds the `sign` function call of the MPC signer contract for a foreign transaction, e.g.
+/// an EVM transaction or a Bitcoin input, requesting a signature of its digest.
+///
+/// Fails if the transaction is not signed with secp256k1 over a 32-byte digest, the only
+/// payloads the `sign` arguments can hold.
+pub fn sign_transaction_call<T: ToSignaturePayload + ?Sized>(
+    signer_contract_id: AccountId,
+    transaction: &T,
+    path: &str,
+    key_version: u32,
+    gas: u64,
+    deposit: u128,
+) -> Result<SignRequestCall, SignRequestError> {
+    let payload = transaction.signature_payload();
+    if payload.curve != Curve::Secp256k1 {
+        return Err(SignRequestError::UnsupportedCurve(payload.curve));
+    }
+    let digest = payload.digest_32().ok_or(SignRequestError::NotDigest)?;
+
+    Ok(sign_request_call(
+        signer_contract_id,
+        digest,
+        path,
+        key_version,
+        gas,
+        deposit,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_sdk::serde_json::{json, Value};
+
+    #[test]
+    fn test_sign_request_call() {
+        let call = sign_request_call(
+            "v1.signer".parse().unwrap(),
+            [1u8; 32],
+            "ethereum-1",
+            0,
+            250_000_000_000_000,
+            1,
+        );
+
+        assert_eq!(call.receiver_id.as_str(), "v1.signer");
+        assert_eq!(call.action.method_name, "sign");
+        assert_eq!(call.action.gas, NearGas::from_tgas(250));
+        assert_eq!(call.action.deposit, NearToken::from_yoctonear(1));
+
+        let args: Value = serde_json::from_slice(&call.action.args).unwrap();
+        assert_eq!(
+            args,
+            json!({
+                "request": {
+                    "payload": vec![1u8; 32],
+                    "path": "ethereum-1",
+                    "key_version": 0
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn test_into_builder() {
+        let call = sign_request_call("v1.signer".parse().unwrap(), [0u8; 32], "", 0, 1, 1);
+        let action = call.action.clone();
+
+        let builder = call.into_builder();
+
+        assert_eq!(
+            builder.actions,
+            Some(vec![Action::FunctionCall(Box::new(action))])
+        );
+    }
+
+    #[cfg(feature = "evm")]
+    #[test]
+    fn test_sign_transaction_call() {
+        use crate::evm::evm_transaction::EVMTransaction;
+
+        let tx = EVMTransaction {
+            chain_id: 1,
+            nonce: 0,
+            to: Some([0x11; 20]),
+            value: 1,
+            input: vec![],
+            gas_limit: 21_000,
+            max_fee_per_gas: 2,
+            max_priority_fee_per_gas: 1,
+            access_list: vec![],
+        };
+
+        let call = sign_transaction_call(
+            MAINNET_SIGNER_CONTRACT.parse().unwrap(),
+            &tx,
+            "ethereum-1",
+            0,
+            250_000_000_000_000,
+            1,
+        )
+        .unwrap();
+
+        assert_eq!(
+            call,
+            sign_request_call(
+                "v1.signer".parse().unwrap(),
+                tx.hash_for_signing(),
+                "ethereum-1",
+                0,
+                250_000_000_000_000,
+                1,
+            )
+        );
+        assert!(matches!(call.into_action(), Action::FunctionCall(_)));
+    }
+
+    #[cfg(feature = "solana")]
+    #[test]
+    fn test_sign_transaction_call_rejects_ed25519() {
+        use crate::solana::{
+            solana_transaction::SolanaTransaction,
+            types::{Hash, Pubkey},
+        };
+
+        let tx = SolanaTransaction {
+            fee_payer: Pubkey([1u8; 32]),
+            recent_blockhash: Hash([2u8; 32]),
+            instructions: vec![],
+        };
+
+        assert_eq!(
+            sign_transaction_call(
+                TESTNET_SIGNER_CONTRACT.parse().unwrap(),
+                &tx,
+                "solana-1",
+                0,
+                1,
+                1
+            ),
+            Err(SignRequestError::UnsupportedCurve(Curve::Ed25519))
+        );
+    }
+}
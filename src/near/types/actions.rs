@@ -1,9 +1,11 @@
+use std::fmt;
+
 use crate::near::types::PublicKey;
 use borsh::{BorshDeserialize, BorshSerialize};
 use near_sdk::serde::{Deserialize, Serialize};
 use near_sdk::AccountId;
 
-use super::{U128, U64};
+use super::{NearGas, NearToken, SignedDelegateAction, U128, U64};
 
 #[derive(Serialize, Deserialize, Debug, Clone, BorshSerialize, BorshDeserialize, PartialEq, Eq)]
 #[serde(crate = "near_sdk::serde")]
@@ -20,6 +22,8 @@ pub enum Action {
     AddKey(Box<AddKeyAction>),
     DeleteKey(Box<DeleteKeyAction>),
     DeleteAccount(DeleteAccountAction),
+    /// Actions signed by another account, executed on its behalf (NEP-366)
+    Delegate(Box<SignedDelegateAction>),
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, BorshSerialize, BorshDeserialize, PartialEq, Eq)]
@@ -37,21 +41,21 @@ pub struct DeployContractAction {
 pub struct FunctionCallAction {
     pub method_name: String,
     pub args: Vec<u8>,
-    pub gas: U64,
-    pub deposit: U128,
+    pub gas: NearGas,
+    pub deposit: NearToken,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, BorshSerialize, BorshDeserialize, PartialEq, Eq)]
 #[serde(crate = "near_sdk::serde")]
 pub struct TransferAction {
-    pub deposit: U128,
+    pub deposit: NearToken,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, BorshSerialize, BorshDeserialize, PartialEq, Eq)]
 #[serde(crate = "near_sdk::serde")]
 pub struct StakeAction {
     /// Amount of tokens to stake.
-    pub stake: U128,
+    pub stake: NearToken,
     /// Validator key which will be used to sign transactions on behalf of signer_id
     pub public_key: PublicKey,
 }
@@ -106,6 +110,42 @@ pub struct DeleteAccountAction {
     pub beneficiary_id: AccountId,
 }
 
+/// A short description of the action, for logs, e.g. `Transfer 1 NEAR`.
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::CreateAccount(_) => write!(f, "CreateAccount"),
+            Self::DeployContract(deploy) => {
+                write!(f, "DeployContract {} bytes", deploy.code.len())
+            }
+            Self::FunctionCall(call) => write!(
+                f,
+                "FunctionCall {} ({}, {})",
+                call.method_name, call.gas, call.deposit
+            ),
+            Self::Transfer(transfer) => write!(f, "Transfer {}", transfer.deposit),
+            Self::Stake(stake) => write!(f, "Stake {}", stake.stake),
+            Self::AddKey(add_key) => match &add_key.access_key.permission {
+                AccessKeyPermission::FullAccess => write!(f, "AddKey full access"),
+                AccessKeyPermission::FunctionCall(permission) => {
+                    write!(f, "AddKey function call to {}", permission.receiver_id)
+                }
+            },
+            Self::DeleteKey(_) => write!(f, "DeleteKey"),
+            Self::DeleteAccount(delete) => {
+                write!(f, "DeleteAccount to {}", delete.beneficiary_id)
+            }
+            Self::Delegate(delegate) => write!(
+                f,
+                "Delegate {} -> {} ({} actions)",
+                delegate.delegate_action.sender_id,
+                delegate.delegate_action.receiver_id,
+                delegate.delegate_action.actions.len()
+            ),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -122,14 +162,14 @@ mod tests {
             Action::FunctionCall(Box::new(FunctionCallAction {
                 method_name: "test".to_string(),
                 args: vec![4, 5, 6],
-                gas: U64(1000000),
-                deposit: U128(0),
+                gas: NearGas::from_gas(1000000),
+                deposit: NearToken::from_yoctonear(0),
             })),
             Action::Transfer(TransferAction {
-                deposit: U128(1000000000),
+                deposit: NearToken::from_yoctonear(1000000000),
             }),
             Action::Stake(Box::new(StakeAction {
-                stake: U128(100000000),
+                stake: NearToken::from_yoctonear(100000000),
                 public_key: PublicKey::ED25519(ED25519PublicKey([0; ED25519_PUBLIC_KEY_LENGTH])),
             })),
             Action::AddKey(Box::new(AddKeyAction {
@@ -148,6 +188,25 @@ mod tests {
         ]
     }
 
+    #[test]
+    fn test_action_display() {
+        let descriptions: Vec<String> = get_actions().iter().map(Action::to_string).collect();
+
+        assert_eq!(
+            descriptions,
+            [
+                "CreateAccount",
+                "DeployContract 3 bytes",
+                "FunctionCall test (0.000001 Tgas, 0 NEAR)",
+                "Transfer 0.000000000000001 NEAR",
+                "Stake 0.0000000000000001 NEAR",
+                "AddKey full access",
+                "DeleteKey",
+                "DeleteAccount to alice.near",
+            ]
+        );
+    }
+
     #[test]
     fn test_action_serialization() {
         let actions = get_actions();
@@ -167,6 +226,32 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_action_deserialization_with_units() {
+        let call: Action = serde_json::from_str(
+            r#"{ "FunctionCall": { "method_name": "ping", "args": [], "gas": "30 Tgas", "deposit": "1.5 NEAR" } }"#,
+        )
+        .unwrap();
+        let transfer: Action =
+            serde_json::from_str(r#"{ "Transfer": { "deposit": "1 NEAR" } }"#).unwrap();
+
+        assert_eq!(
+            call,
+            Action::FunctionCall(Box::new(FunctionCallAction {
+                method_name: "ping".to_string(),
+                args: vec![],
+                gas: NearGas::from_tgas(30),
+                deposit: NearToken::from_yoctonear(1_500_000_000_000_000_000_000_000),
+            }))
+        );
+        assert_eq!(
+            transfer,
+            Action::Transfer(TransferAction {
+                deposit: NearToken::from_near(1),
+            })
+        );
+    }
+
     #[test]
     fn test_action_borsh_serialization() {
         let actions = get_actions();
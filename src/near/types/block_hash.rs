@@ -1,18 +1,84 @@
+use std::{fmt, str::FromStr};
+
 use borsh::{BorshDeserialize, BorshSerialize};
 use near_sdk::serde::{Deserialize, Deserializer, Serialize};
 use serde::de;
 use serde_big_array::BigArray;
 
-#[derive(Serialize, Debug, Clone, BorshSerialize, BorshDeserialize, PartialEq, Eq)]
+use crate::near::utils::NearParseError;
+
+/// The hash of a recent block, which bounds the lifetime of a transaction.
+///
+/// Parsed from and displayed as base58, the format used by RPC responses and explorers.
+#[derive(Serialize, Debug, Clone, Copy, BorshSerialize, BorshDeserialize, PartialEq, Eq, Hash)]
 #[serde(crate = "near_sdk::serde")]
 pub struct BlockHash(#[serde(with = "BigArray")] pub [u8; 32]);
 
+impl BlockHash {
+    pub const fn as_bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+
+    pub const fn to_bytes(self) -> [u8; 32] {
+        self.0
+    }
+}
+
 impl From<[u8; 32]> for BlockHash {
     fn from(data: [u8; 32]) -> Self {
         Self(data)
     }
 }
 
+impl From<BlockHash> for [u8; 32] {
+    fn from(block_hash: BlockHash) -> Self {
+        block_hash.0
+    }
+}
+
+impl AsRef<[u8]> for BlockHash {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+#[cfg(feature = "near-primitives-compat")]
+impl From<near_primitives::hash::CryptoHash> for BlockHash {
+    fn from(hash: near_primitives::hash::CryptoHash) -> Self {
+        Self(hash.0)
+    }
+}
+
+#[cfg(feature = "near-primitives-compat")]
+impl From<BlockHash> for near_primitives::hash::CryptoHash {
+    fn from(block_hash: BlockHash) -> Self {
+        Self(block_hash.0)
+    }
+}
+
+impl FromStr for BlockHash {
+    type Err = NearParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let bytes = bs58::decode(s).into_vec()?;
+        let array: [u8; 32] =
+            bytes
+                .try_into()
+                .map_err(|bytes: Vec<u8>| NearParseError::InvalidLength {
+                    expected: 32,
+                    actual: bytes.len(),
+                })?;
+
+        Ok(Self(array))
+    }
+}
+
+impl fmt::Display for BlockHash {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&bs58::encode(self.0).into_string())
+    }
+}
+
 impl<'de> Deserialize<'de> for BlockHash {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
@@ -31,12 +97,7 @@ impl<'de> Deserialize<'de> for BlockHash {
             where
                 E: de::Error,
             {
-                let bytes = bs58::decode(value).into_vec().map_err(de::Error::custom)?;
-                let array: [u8; 32] = bytes
-                    .try_into()
-                    .map_err(|_| de::Error::custom("Invalid length"))?;
-
-                Ok(BlockHash(array))
+                value.parse().map_err(de::Error::custom)
             }
 
             fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
@@ -157,11 +218,39 @@ mod tests {
         assert_eq!(original, deserialized);
     }
 
+    #[test]
+    fn test_blockhash_base58_roundtrip() {
+        let base58 = "CjNSmWXTWhC3EhRVtqLhRmWMTkRbU96wUACqxMtV1uGf";
+
+        let block_hash: BlockHash = base58.parse().unwrap();
+
+        assert_eq!(block_hash.as_bytes()[0], 174);
+        assert_eq!(block_hash.to_string(), base58);
+        assert!("invalid_base58_string".parse::<BlockHash>().is_err());
+        // Valid base58, but not 32 bytes.
+        assert!("3yZe7d".parse::<BlockHash>().is_err());
+    }
+
     #[test]
     fn test_blockhash_from_into() {
         let data = [4; 32];
         let block_hash: BlockHash = data.into();
 
         assert_eq!(block_hash.0, data);
+        assert_eq!(<[u8; 32]>::from(block_hash), data);
+    }
+
+    #[cfg(feature = "near-primitives-compat")]
+    #[test]
+    fn test_blockhash_crypto_hash_conversion() {
+        let crypto_hash = near_primitives::hash::CryptoHash([5; 32]);
+
+        let block_hash = BlockHash::from(crypto_hash);
+
+        assert_eq!(block_hash.to_bytes(), [5; 32]);
+        assert_eq!(
+            near_primitives::hash::CryptoHash::from(block_hash),
+            crypto_hash
+        );
     }
 }
@@ -0,0 +1,229 @@
+//! Delegate actions (NEP-366), which let a relayer pay for the gas of actions signed by another
+//! account.
+//!
+//! The sender signs a [`DelegateAction`] off-chain, the relayer then submits it wrapped in an
+//! [`Action::Delegate`] of a transaction it signs and pays for.
+use std::io::{Error, ErrorKind, Read};
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::AccountId;
+use sha2::{Digest, Sha256};
+
+use super::{Action, PublicKey, Signature, U64};
+
+/// Prefix of signed delegate actions, `2^30 + 366`, which keeps their signatures from ever
+/// being valid for a transaction (NEP-461).
+pub const DELEGATE_ACTION_DISCRIMINANT: u32 = (1 << 30) + 366;
+
+/// Actions to be executed on behalf of `sender_id`, as signed by the sender.
+#[derive(Serialize, Deserialize, Debug, Clone, BorshSerialize, BorshDeserialize, PartialEq, Eq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct DelegateAction {
+    /// The account the actions are executed for, which signs the delegate action.
+    pub sender_id: AccountId,
+    /// The receiver of the actions.
+    pub receiver_id: AccountId,
+    pub actions: Vec<NonDelegateAction>,
+    /// Nonce of the sender access key, which must be greater than its current nonce.
+    pub nonce: U64,
+    /// The block height after which the delegate action is no longer valid.
+    pub max_block_height: U64,
+    /// The public key of the sender access key.
+    pub public_key: PublicKey,
+}
+
+impl DelegateAction {
+    /// Wraps `actions` into a delegate action, failing if one of them is itself a delegate
+    /// action (they cannot be nested).
+    pub fn new(
+        sender_id: AccountId,
+        receiver_id: AccountId,
+        actions: Vec<Action>,
+        nonce: u64,
+        max_block_height: u64,
+        public_key: PublicKey,
+    ) -> Result<Self, String> {
+        let actions = actions
+            .into_iter()
+            .map(NonDelegateAction::try_from)
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self {
+            sender_id,
+            receiver_id,
+            actions,
+            nonce: U64(nonce),
+            max_block_height: U64(max_block_height),
+            public_key,
+        })
+    }
+
+    /// Returns the bytes whose SHA-256 hash is signed by the sender: the borsh encoding of the
+    /// delegate action prefixed with [`DELEGATE_ACTION_DISCRIMINANT`].
+    pub fn build_for_signing(&self) -> Vec<u8> {
+        borsh::to_vec(&(DELEGATE_ACTION_DISCRIMINANT, self))
+            .expect("failed to serialize NEAR delegate action")
+    }
+
+    /// Returns the SHA-256 hash of [`Self::build_for_signing`], the digest signed by the sender.
+    pub fn hash_for_signing(&self) -> [u8; 32] {
+        Sha256::digest(self.build_for_signing()).into()
+    }
+
+    /// Attaches the signature of the sender.
+    pub fn build_with_signature(&self, signature: Signature) -> SignedDelegateAction {
+        SignedDelegateAction {
+            delegate_action: self.clone(),
+            signature,
+        }
+    }
+}
+
+/// A delegate action along with the signature of its sender.
+#[derive(Serialize, Deserialize, Debug, Clone, BorshSerialize, BorshDeserialize, PartialEq, Eq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct SignedDelegateAction {
+    pub delegate_action: DelegateAction,
+    pub signature: Signature,
+}
+
+impl From<SignedDelegateAction> for Action {
+    fn from(signed_delegate_action: SignedDelegateAction) -> Self {
+        Self::Delegate(Box::new(signed_delegate_action))
+    }
+}
+
+/// Any action but [`Action::Delegate`], which cannot be nested. Encoded as the action itself.
+#[derive(Serialize, Deserialize, Debug, Clone, BorshSerialize, PartialEq, Eq)]
+#[serde(crate = "near_sdk::serde", try_from = "Action", into = "Action")]
+pub struct NonDelegateAction(Action);
+
+impl NonDelegateAction {
+    pub const fn as_action(&self) -> &Action {
+        &self.0
+    }
+}
+
+impl From<NonDelegateAction> for Action {
+    fn from(action: NonDelegateAction) -> Self {
+        action.0
+    }
+}
+
+impl TryFrom<Action> for NonDelegateAction {
+    type Error = String;
+
+    fn try_from(action: Action) -> Result<Self, Self::Error> {
+        match action {
+            Action::Delegate(_) => Err("Delegate actions cannot be nested".to_string()),
+            action => Ok(Self(action)),
+        }
+    }
+}
+
+impl BorshDeserialize for NonDelegateAction {
+    fn deserialize_reader<R: Read>(reader: &mut R) -> Result<Self, Error> {
+        Action::deserialize_reader(reader)?
+            .try_into()
+            .map_err(|e: String| Error::new(ErrorKind::InvalidData, e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::near::types::{ED25519PublicKey, ED25519Signature, NearToken, TransferAction};
+    use near_crypto::{KeyType, PublicKey as NearPublicKey, Signature as NearSignature};
+    use near_primitives::action::{
+        delegate::{
+            DelegateAction as NearDelegateAction, SignedDelegateAction as NearSignedDelegateAction,
+        },
+        Action as NearAction, TransferAction as NearTransferAction,
+    };
+    use near_primitives::signable_message::{SignableMessage, SignableMessageType};
+
+    fn delegate_action() -> DelegateAction {
+        DelegateAction::new(
+            "alice.near".parse().unwrap(),
+            "bob.near".parse().unwrap(),
+            vec![Action::Transfer(TransferAction {
+                deposit: NearToken::from_yoctonear(1),
+            })],
+            5,
+            1_000,
+            PublicKey::ED25519(ED25519PublicKey([1; 32])),
+        )
+        .unwrap()
+    }
+
+    fn near_delegate_action() -> NearDelegateAction {
+        NearDelegateAction {
+            sender_id: "alice.near".parse().unwrap(),
+            receiver_id: "bob.near".parse().unwrap(),
+            actions: vec![NearAction::Transfer(NearTransferAction { deposit: 1 })
+                .try_into()
+                .unwrap()],
+            nonce: 5,
+            max_block_height: 1_000,
+            public_key: NearPublicKey::ED25519(near_crypto::ED25519PublicKey([1; 32])),
+        }
+    }
+
+    #[test]
+    fn test_build_for_signing_against_near_primitives() {
+        let near_delegate_action = near_delegate_action();
+        let message =
+            SignableMessage::new(&near_delegate_action, SignableMessageType::DelegateAction);
+
+        assert_eq!(
+            delegate_action().build_for_signing(),
+            borsh::to_vec(&message).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_delegate_action_borsh_against_near_primitives() {
+        let signed = delegate_action().build_with_signature(Signature::ED25519(ED25519Signature {
+            r: [2; 32],
+            s: [3; 32],
+        }));
+        let near_signed = NearSignedDelegateAction {
+            delegate_action: near_delegate_action(),
+            signature: NearSignature::from_parts(
+                KeyType::ED25519,
+                &[[2u8; 32], [3u8; 32]].concat(),
+            )
+            .unwrap(),
+        };
+
+        let action: Action = signed.into();
+        let encoded = borsh::to_vec(&action).unwrap();
+
+        assert_eq!(
+            encoded,
+            borsh::to_vec(&NearAction::Delegate(Box::new(near_signed))).unwrap()
+        );
+        assert_eq!(Action::try_from_slice(&encoded).unwrap(), action);
+    }
+
+    #[test]
+    fn test_nested_delegate_action_is_rejected() {
+        let signed = delegate_action().build_with_signature(Signature::ED25519(ED25519Signature {
+            r: [2; 32],
+            s: [3; 32],
+        }));
+        let nested: Action = signed.into();
+
+        assert!(DelegateAction::new(
+            "alice.near".parse().unwrap(),
+            "bob.near".parse().unwrap(),
+            vec![nested.clone()],
+            6,
+            1_000,
+            PublicKey::ED25519(ED25519PublicKey([1; 32])),
+        )
+        .is_err());
+        assert!(NonDelegateAction::try_from_slice(&borsh::to_vec(&nested).unwrap()).is_err());
+    }
+}
@@ -1,11 +1,15 @@
 mod actions;
 mod block_hash;
+mod delegate;
 mod integers;
 mod public_key;
 mod signature;
+mod units;
 
 pub use actions::*;
 pub use block_hash::*;
+pub use delegate::*;
 pub use integers::*;
 pub use public_key::*;
 pub use signature::*;
+pub use units::*;
@@ -1,9 +1,11 @@
 use crate::constants::{ED25519_PUBLIC_KEY_LENGTH, SECP256K1_PUBLIC_KEY_LENGTH};
-use crate::near::utils::PublicKeyStrExt;
+use crate::near::utils::{NearParseError, PublicKeyStrExt};
 use borsh::{BorshDeserialize, BorshSerialize};
 use near_sdk::serde::{Deserialize, Deserializer, Serialize};
+use near_sdk::AccountId;
 use serde::de;
 use serde_big_array::BigArray;
+use sha3::{Digest, Keccak256};
 use std::io::{Error, Write};
 
 #[derive(Serialize, Deserialize, BorshDeserialize, PartialEq, Eq, Debug, Clone)]
@@ -71,6 +73,53 @@ impl BorshDeserialize for PublicKey {
     }
 }
 
+impl Secp256K1PublicKey {
+    /// Creates a key from its uncompressed SEC1 encoding, with or without the `0x04` prefix,
+    /// e.g. the public key derived by the MPC signer.
+    pub fn from_uncompressed(key: &[u8]) -> Result<Self, NearParseError> {
+        let key = match key {
+            [0x04, rest @ ..] if rest.len() == SECP256K1_PUBLIC_KEY_LENGTH => rest,
+            key => key,
+        };
+
+        key.try_into()
+            .map(Self)
+            .map_err(|_| NearParseError::InvalidLength {
+                expected: SECP256K1_PUBLIC_KEY_LENGTH,
+                actual: key.len(),
+            })
+    }
+
+    /// Returns the ETH implicit account controlled by this key: `0x` followed by the hex
+    /// encoded Ethereum address of the key, i.e. the last 20 bytes of its keccak256 hash.
+    pub fn eth_implicit_account_id(&self) -> AccountId {
+        let hash = Keccak256::digest(self.0);
+        format!("0x{}", hex::encode(&hash[12..]))
+            .parse()
+            .expect("ETH implicit account IDs are valid")
+    }
+}
+
+impl PublicKey {
+    /// Creates a `SECP256K1` key from its uncompressed SEC1 encoding, see
+    /// [`Secp256K1PublicKey::from_uncompressed`].
+    pub fn secp256k1_from_uncompressed(key: &[u8]) -> Result<Self, NearParseError> {
+        Secp256K1PublicKey::from_uncompressed(key).map(Self::SECP256K1)
+    }
+
+    /// Returns the implicit account controlled by this key: the hex encoded key for Ed25519
+    /// (NEAR implicit account), the hex encoded Ethereum address for secp256k1 (ETH implicit
+    /// account).
+    pub fn implicit_account_id(&self) -> AccountId {
+        match self {
+            Self::ED25519(public_key) => hex::encode(public_key.0)
+                .parse()
+                .expect("NEAR implicit account IDs are valid"),
+            Self::SECP256K1(public_key) => public_key.eth_implicit_account_id(),
+        }
+    }
+}
+
 // From implementations for fixed size arrays
 impl From<[u8; SECP256K1_PUBLIC_KEY_LENGTH]> for Secp256K1PublicKey {
     fn from(data: [u8; SECP256K1_PUBLIC_KEY_LENGTH]) -> Self {
@@ -185,6 +234,41 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_secp256k1_from_uncompressed() {
+        // The generator point, i.e. the public key of the secret key 1.
+        let generator = hex::decode(
+            "0479be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798\
+             483ada7726a3c4655da4fbfc0e1108a8fd17b448a68554199c47d08ffb10d4b8",
+        )
+        .unwrap();
+
+        let public_key = PublicKey::secp256k1_from_uncompressed(&generator).unwrap();
+        assert_eq!(
+            public_key,
+            PublicKey::secp256k1_from_uncompressed(&generator[1..]).unwrap()
+        );
+        assert_eq!(
+            public_key.implicit_account_id().as_str(),
+            "0x7e5f4552091a69125d5dfcb7b8c2659029395bdf"
+        );
+
+        assert_eq!(
+            PublicKey::secp256k1_from_uncompressed(&generator[..33]),
+            Err(NearParseError::InvalidLength {
+                expected: SECP256K1_PUBLIC_KEY_LENGTH,
+                actual: 33,
+            })
+        );
+    }
+
+    #[test]
+    fn test_ed25519_implicit_account_id() {
+        let public_key = PublicKey::ED25519(ED25519PublicKey([0xab; ED25519_PUBLIC_KEY_LENGTH]));
+
+        assert_eq!(public_key.implicit_account_id().as_str(), "ab".repeat(32));
+    }
+
     #[test]
     fn test_public_key_borsh_serialization() {
         let ed25519_key = PublicKey::ED25519(ED25519PublicKey([6; ED25519_PUBLIC_KEY_LENGTH]));
@@ -4,6 +4,7 @@ use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::fmt::Debug;
 
 use crate::constants::{COMPONENT_SIZE, SECP256K1_SIGNATURE_LENGTH};
+use crate::signature_error::{check_secp256k1_parts, SignatureError};
 
 #[derive(Debug, Clone, BorshSerialize, BorshDeserialize, PartialEq, Eq)]
 pub enum Signature {
@@ -23,6 +24,35 @@ pub type ComponentBytes = [u8; COMPONENT_SIZE];
 #[derive(Debug, Clone, BorshSerialize, BorshDeserialize, PartialEq, Eq)]
 pub struct Secp256K1Signature(pub [u8; SECP256K1_SIGNATURE_LENGTH]);
 
+impl Secp256K1Signature {
+    /// Creates a signature from the parts returned by an MPC signer: the big-endian `r` and
+    /// `s` and the recovery id, laid out as `r || s || recovery_id` like `near-crypto`.
+    ///
+    /// Fails if `r` or `s` are not non-zero 32-byte values, if `s` is not low-S or if the
+    /// recovery id is not 0 or 1.
+    pub fn from_mpc_parts(r: &[u8], s: &[u8], recovery_id: u8) -> Result<Self, SignatureError> {
+        check_secp256k1_parts(r, s, recovery_id)?;
+
+        let mut bytes = [0u8; SECP256K1_SIGNATURE_LENGTH];
+        bytes[..32].copy_from_slice(r);
+        bytes[32..64].copy_from_slice(s);
+        bytes[64] = recovery_id;
+
+        Ok(Self(bytes))
+    }
+
+    /// Creates a signature from a 65-byte `r || s || v` signature, where `v` is either the
+    /// recovery id or, as returned by Ethereum tooling, the recovery id plus 27.
+    pub fn from_rsv(bytes: &[u8; SECP256K1_SIGNATURE_LENGTH]) -> Result<Self, SignatureError> {
+        let recovery_id = match bytes[64] {
+            v @ (27 | 28) => v - 27,
+            v => v,
+        };
+
+        Self::from_mpc_parts(&bytes[..32], &bytes[32..64], recovery_id)
+    }
+}
+
 impl Serialize for Signature {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
@@ -100,6 +130,36 @@ mod tests {
     use super::*;
     use serde_json;
 
+    #[test]
+    fn test_secp256k1_signature_from_rsv() {
+        let mut rsv = [0u8; SECP256K1_SIGNATURE_LENGTH];
+        rsv[..32].copy_from_slice(&[1u8; 32]);
+        rsv[32..64].copy_from_slice(&[2u8; 32]);
+        rsv[64] = 28;
+
+        let signature = Secp256K1Signature::from_rsv(&rsv).unwrap();
+        assert_eq!(signature.0[..64], rsv[..64]);
+        assert_eq!(signature.0[64], 1);
+        assert_eq!(
+            Secp256K1Signature::from_mpc_parts(&[1u8; 32], &[2u8; 32], 1),
+            Ok(signature)
+        );
+
+        rsv[64] = 2;
+        assert_eq!(
+            Secp256K1Signature::from_rsv(&rsv),
+            Err(SignatureError::InvalidRecoveryId(2))
+        );
+        assert_eq!(
+            Secp256K1Signature::from_mpc_parts(&[1u8; 33], &[2u8; 32], 0),
+            Err(SignatureError::InvalidLength {
+                component: "r",
+                expected: 32,
+                actual: 33,
+            })
+        );
+    }
+
     #[test]
     fn test_deserialize_ed25519_signature() {
         let serialized = "\"ed25519:3s1dvZdQtcAjBksMHFrysqvF63wnyMHPA4owNQmCJZ2EBakZEKdtMsLqrHdKWQjJbSRN6kRknN2WdwSBLWGCokXj\"";
@@ -0,0 +1,486 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use near_sdk::serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::{fmt, str::FromStr};
+
+use super::{U128, U64};
+
+/// Decimals of a teragas (Tgas).
+const TGAS_DECIMALS: u32 = 12;
+/// Decimals of a gigagas (Ggas).
+const GGAS_DECIMALS: u32 = 9;
+/// Decimals of a NEAR, i.e. of its yoctoNEAR (10^-24 NEAR) amount.
+const NEAR_DECIMALS: u32 = 24;
+
+/// Errors returned when parsing an amount of gas or NEAR, e.g. `"30 Tgas"` or `"1.5 NEAR"`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NearAmountParseError {
+    /// The amount is not a decimal number.
+    InvalidNumber,
+    /// The unit is not one of those of the amount, e.g. `Tgas` for gas.
+    UnknownUnit,
+    /// The amount has more decimals than its unit allows, i.e. is a fraction of a gas unit or
+    /// of a yoctoNEAR.
+    TooManyDecimals,
+    /// The amount does not fit in the underlying integer.
+    Overflow,
+}
+
+impl fmt::Display for NearAmountParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidNumber => write!(f, "invalid number"),
+            Self::UnknownUnit => write!(f, "unknown unit"),
+            Self::TooManyDecimals => write!(f, "too many decimals for the unit"),
+            Self::Overflow => write!(f, "amount too large"),
+        }
+    }
+}
+
+impl std::error::Error for NearAmountParseError {}
+
+/// An amount of gas, attached to a function call.
+///
+/// It parses from a number of gas units or from a string with a unit, e.g. `"30 Tgas"`,
+/// `"0.5 Tgas"`, `"5 Ggas"` or `"1000 gas"`, and serializes as its number of gas units.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    BorshSerialize,
+    BorshDeserialize,
+)]
+pub struct NearGas(u64);
+
+impl NearGas {
+    pub const fn from_gas(gas: u64) -> Self {
+        Self(gas)
+    }
+
+    /// Creates an amount of `ggas` gigagas, i.e. 10^9 gas units.
+    ///
+    /// Panics if it does not fit in `u64` gas units, see [`Self::checked_from_ggas`].
+    pub const fn from_ggas(ggas: u64) -> Self {
+        match Self::checked_from_ggas(ggas) {
+            Some(gas) => gas,
+            None => panic!("Ggas amount overflows u64 gas units"),
+        }
+    }
+
+    /// Creates an amount of `ggas` gigagas, or `None` if it does not fit in `u64` gas units.
+    pub const fn checked_from_ggas(ggas: u64) -> Option<Self> {
+        match ggas.checked_mul(10u64.pow(GGAS_DECIMALS)) {
+            Some(gas) => Some(Self(gas)),
+            None => None,
+        }
+    }
+
+    /// Creates an amount of `tgas` teragas, i.e. 10^12 gas units. A function call can attach
+    /// at most 300 Tgas.
+    ///
+    /// Panics if it does not fit in `u64` gas units, see [`Self::checked_from_tgas`].
+    pub const fn from_tgas(tgas: u64) -> Self {
+        match Self::checked_from_tgas(tgas) {
+            Some(gas) => gas,
+            None => panic!("Tgas amount overflows u64 gas units"),
+        }
+    }
+
+    /// Creates an amount of `tgas` teragas, or `None` if it does not fit in `u64` gas units.
+    pub const fn checked_from_tgas(tgas: u64) -> Option<Self> {
+        match tgas.checked_mul(10u64.pow(TGAS_DECIMALS)) {
+            Some(gas) => Some(Self(gas)),
+            None => None,
+        }
+    }
+
+    pub const fn as_gas(self) -> u64 {
+        self.0
+    }
+
+    /// Returns the amount in teragas, rounded down.
+    pub const fn as_tgas(self) -> u64 {
+        self.0 / 10u64.pow(TGAS_DECIMALS)
+    }
+}
+
+impl FromStr for NearGas {
+    type Err = NearAmountParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (number, unit) = split_unit(s);
+        let decimals = match unit.to_ascii_lowercase().as_str() {
+            "" | "gas" => 0,
+            "ggas" => GGAS_DECIMALS,
+            "tgas" => TGAS_DECIMALS,
+            _ => return Err(NearAmountParseError::UnknownUnit),
+        };
+        let gas = parse_decimal(number, decimals)?;
+        u64::try_from(gas)
+            .map(Self)
+            .map_err(|_| NearAmountParseError::Overflow)
+    }
+}
+
+impl fmt::Display for NearGas {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} Tgas",
+            format_decimal(u128::from(self.0), TGAS_DECIMALS)
+        )
+    }
+}
+
+impl Serialize for NearGas {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_u64(self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for NearGas {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct NearGasVisitor;
+
+        impl<'de> serde::de::Visitor<'de> for NearGasVisitor {
+            type Value = NearGas;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a number of gas units or a string like \"30 Tgas\"")
+            }
+
+            fn visit_str<E>(self, value: &str) -> Result<NearGas, E>
+            where
+                E: serde::de::Error,
+            {
+                value.parse().map_err(serde::de::Error::custom)
+            }
+
+            fn visit_u64<E>(self, value: u64) -> Result<NearGas, E>
+            where
+                E: serde::de::Error,
+            {
+                Ok(NearGas(value))
+            }
+        }
+
+        deserializer.deserialize_any(NearGasVisitor)
+    }
+}
+
+impl From<u64> for NearGas {
+    fn from(gas: u64) -> Self {
+        Self(gas)
+    }
+}
+
+impl From<NearGas> for U64 {
+    fn from(gas: NearGas) -> Self {
+        Self(gas.0)
+    }
+}
+
+impl From<U64> for NearGas {
+    fn from(gas: U64) -> Self {
+        Self(gas.0)
+    }
+}
+
+/// An amount of NEAR, attached to a function call or transferred.
+///
+/// It parses from a number of yoctoNEAR or from a string with a unit, e.g. `"1.5 NEAR"` or
+/// `"1 yoctoNEAR"`, and serializes as its number of yoctoNEAR in a string, as it does not fit
+/// in a JSON number.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    BorshSerialize,
+    BorshDeserialize,
+)]
+pub struct NearToken(u128);
+
+impl NearToken {
+    /// Creates an amount of `yoctonear` yoctoNEAR, i.e. 10^-24 NEAR.
+    pub const fn from_yoctonear(yoctonear: u128) -> Self {
+        Self(yoctonear)
+    }
+
+    /// Creates an amount of `near` NEAR.
+    ///
+    /// Panics if it does not fit in `u128` yoctoNEAR, see [`Self::checked_from_near`].
+    pub const fn from_near(near: u128) -> Self {
+        match Self::checked_from_near(near) {
+            Some(amount) => amount,
+            None => panic!("NEAR amount overflows u128 yoctoNEAR"),
+        }
+    }
+
+    /// Creates an amount of `near` NEAR, or `None` if it does not fit in `u128` yoctoNEAR.
+    pub const fn checked_from_near(near: u128) -> Option<Self> {
+        match near.checked_mul(10u128.pow(NEAR_DECIMALS)) {
+            Some(yoctonear) => Some(Self(yoctonear)),
+            None => None,
+        }
+    }
+
+    pub const fn as_yoctonear(self) -> u128 {
+        self.0
+    }
+
+    /// Returns the amount in NEAR, rounded down.
+    pub const fn as_near(self) -> u128 {
+        self.0 / 10u128.pow(NEAR_DECIMALS)
+    }
+}
+
+impl FromStr for NearToken {
+    type Err = NearAmountParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (number, unit) = split_unit(s);
+        let decimals = match unit.to_ascii_lowercase().as_str() {
+            "" | "yoctonear" => 0,
+            "near" => NEAR_DECIMALS,
+            _ => return Err(NearAmountParseError::UnknownUnit),
+        };
+        parse_decimal(number, decimals).map(Self)
+    }
+}
+
+impl fmt::Display for NearToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} NEAR", format_decimal(self.0, NEAR_DECIMALS))
+    }
+}
+
+impl Serialize for NearToken {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.0.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for NearToken {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct NearTokenVisitor;
+
+        impl<'de> serde::de::Visitor<'de> for NearTokenVisitor {
+            type Value = NearToken;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a number of yoctoNEAR or a string like \"1.5 NEAR\"")
+            }
+
+            fn visit_str<E>(self, value: &str) -> Result<NearToken, E>
+            where
+                E: serde::de::Error,
+            {
+                value.parse().map_err(serde::de::Error::custom)
+            }
+
+            fn visit_u64<E>(self, value: u64) -> Result<NearToken, E>
+            where
+                E: serde::de::Error,
+            {
+                Ok(NearToken(u128::from(value)))
+            }
+
+            fn visit_u128<E>(self, value: u128) -> Result<NearToken, E>
+            where
+                E: serde::de::Error,
+            {
+                Ok(NearToken(value))
+            }
+        }
+
+        deserializer.deserialize_any(NearTokenVisitor)
+    }
+}
+
+impl From<u128> for NearToken {
+    fn from(yoctonear: u128) -> Self {
+        Self(yoctonear)
+    }
+}
+
+impl From<NearToken> for U128 {
+    fn from(amount: NearToken) -> Self {
+        Self(amount.0)
+    }
+}
+
+impl From<U128> for NearToken {
+    fn from(amount: U128) -> Self {
+        Self(amount.0)
+    }
+}
+
+/// Splits an amount into its number and its unit, empty if there is none.
+fn split_unit(s: &str) -> (&str, &str) {
+    let s = s.trim();
+    let unit_start = s.find(|c: char| c.is_ascii_alphabetic()).unwrap_or(s.len());
+    (s[..unit_start].trim_end(), &s[unit_start..])
+}
+
+/// Parses a decimal number, e.g. `1.5`, into an integer amount of its unit divided by
+/// 10^`decimals`, without any rounding.
+fn parse_decimal(number: &str, decimals: u32) -> Result<u128, NearAmountParseError> {
+    let (integer, fraction) = number.split_once('.').unwrap_or((number, ""));
+    let is_digits = |s: &str| s.bytes().all(|b| b.is_ascii_digit());
+    if (integer.is_empty() && fraction.is_empty()) || !is_digits(integer) || !is_digits(fraction) {
+        return Err(NearAmountParseError::InvalidNumber);
+    }
+    if fraction.len() > decimals as usize {
+        return Err(NearAmountParseError::TooManyDecimals);
+    }
+
+    let parse = |digits: &str| match digits {
+        "" => Ok(0),
+        digits => digits
+            .parse::<u128>()
+            .map_err(|_| NearAmountParseError::Overflow),
+    };
+    let scale = 10u128.pow(decimals);
+    let fraction = parse(fraction)? * 10u128.pow(decimals - fraction.len() as u32);
+    parse(integer)?
+        .checked_mul(scale)
+        .and_then(|integer| integer.checked_add(fraction))
+        .ok_or(NearAmountParseError::Overflow)
+}
+
+/// Formats an integer amount as a decimal number of the unit worth 10^`decimals` of it,
+/// without trailing zeros.
+fn format_decimal(amount: u128, decimals: u32) -> String {
+    let scale = 10u128.pow(decimals);
+    let fraction = format!("{:0width$}", amount % scale, width = decimals as usize);
+    match fraction.trim_end_matches('0') {
+        "" => (amount / scale).to_string(),
+        fraction => format!("{}.{}", amount / scale, fraction),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_near_gas_parse_and_display() {
+        assert_eq!("30 Tgas".parse(), Ok(NearGas::from_tgas(30)));
+        assert_eq!("0.5 tgas".parse(), Ok(NearGas::from_ggas(500)));
+        assert_eq!("1000".parse(), Ok(NearGas::from_gas(1000)));
+        assert_eq!("1000 gas".parse(), Ok(NearGas::from_gas(1000)));
+
+        assert_eq!(
+            "1.5 gas".parse::<NearGas>(),
+            Err(NearAmountParseError::TooManyDecimals)
+        );
+        assert_eq!(
+            "30 NEAR".parse::<NearGas>(),
+            Err(NearAmountParseError::UnknownUnit)
+        );
+        assert_eq!(
+            "30000000 Tgas".parse::<NearGas>(),
+            Err(NearAmountParseError::Overflow)
+        );
+        assert_eq!(
+            "Tgas".parse::<NearGas>(),
+            Err(NearAmountParseError::InvalidNumber)
+        );
+
+        assert_eq!(NearGas::from_tgas(30).to_string(), "30 Tgas");
+        assert_eq!(NearGas::from_ggas(2_500).to_string(), "2.5 Tgas");
+        assert_eq!(NearGas::from_tgas(300).as_tgas(), 300);
+        assert_eq!(
+            NearGas::checked_from_tgas(300),
+            Some(NearGas::from_tgas(300))
+        );
+        assert_eq!(NearGas::checked_from_tgas(u64::MAX), None);
+        assert_eq!(NearGas::checked_from_ggas(u64::MAX), None);
+        assert!(std::panic::catch_unwind(|| NearGas::from_tgas(u64::MAX)).is_err());
+    }
+
+    #[test]
+    fn test_near_token_parse_and_display() {
+        let one_and_a_half = NearToken::from_yoctonear(1_500_000_000_000_000_000_000_000);
+
+        assert_eq!("1.5 NEAR".parse(), Ok(one_and_a_half));
+        assert_eq!(" 1.5near ".parse(), Ok(one_and_a_half));
+        assert_eq!("1 yoctoNEAR".parse(), Ok(NearToken::from_yoctonear(1)));
+        assert_eq!(
+            "0.000000000000000000000001 NEAR".parse(),
+            Ok(NearToken::from_yoctonear(1))
+        );
+        assert_eq!(
+            "1.2.3 NEAR".parse::<NearToken>(),
+            Err(NearAmountParseError::InvalidNumber)
+        );
+        assert_eq!(
+            "-1 NEAR".parse::<NearToken>(),
+            Err(NearAmountParseError::InvalidNumber)
+        );
+
+        assert_eq!(one_and_a_half.to_string(), "1.5 NEAR");
+        assert_eq!(NearToken::from_near(2).to_string(), "2 NEAR");
+        assert_eq!(one_and_a_half.as_near(), 1);
+        assert_eq!(
+            NearToken::checked_from_near(2),
+            Some(NearToken::from_near(2))
+        );
+        assert_eq!(NearToken::checked_from_near(u128::MAX), None);
+        assert!(std::panic::catch_unwind(|| NearToken::from_near(u128::MAX)).is_err());
+    }
+
+    #[test]
+    fn test_serde() {
+        let gas: NearGas = serde_json::from_str("\"30 Tgas\"").unwrap();
+        assert_eq!(gas, NearGas::from_tgas(30));
+        assert_eq!(serde_json::to_string(&gas).unwrap(), "30000000000000");
+        assert_eq!(
+            serde_json::from_str::<NearGas>("30000000000000").unwrap(),
+            gas
+        );
+
+        let deposit: NearToken = serde_json::from_str("\"1.5 NEAR\"").unwrap();
+        assert_eq!(
+            serde_json::to_string(&deposit).unwrap(),
+            "\"1500000000000000000000000\""
+        );
+        assert_eq!(
+            serde_json::from_str::<NearToken>("\"1500000000000000000000000\"").unwrap(),
+            deposit
+        );
+        assert_eq!(
+            serde_json::from_str::<NearToken>("1").unwrap(),
+            NearToken::from_yoctonear(1)
+        );
+    }
+
+    #[test]
+    fn test_into_action_integers() {
+        assert_eq!(U64::from(NearGas::from_tgas(1)), U64(1_000_000_000_000));
+        assert_eq!(U128::from(NearToken::from_near(1)), U128(10u128.pow(24)));
+    }
+}
@@ -0,0 +1,183 @@
+use near_sdk::AccountId;
+use std::fmt;
+
+/// Length of a NEAR implicit account ID (hex encoded ed25519 public key).
+const NEAR_IMPLICIT_ACCOUNT_ID_LENGTH: usize = 64;
+
+/// Length of an ETH implicit account ID (`0x` followed by a hex encoded address).
+const ETH_IMPLICIT_ACCOUNT_ID_LENGTH: usize = 42;
+
+/// Errors returned when validating an account ID.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AccountIdError {
+    /// The account ID does not follow the nearcore syntax rules.
+    InvalidSyntax(String),
+    /// The account ID is a named top-level account, which only the registrar can create.
+    TopLevelNotAllowed(String),
+    /// The account ID looks like an implicit account but has an invalid length or encoding.
+    InvalidImplicit(String),
+}
+
+impl fmt::Display for AccountIdError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidSyntax(reason) => write!(f, "invalid account ID: {}", reason),
+            Self::TopLevelNotAllowed(account_id) => write!(
+                f,
+                "top-level account ID {} can only be created by the registrar",
+                account_id
+            ),
+            Self::InvalidImplicit(account_id) => {
+                write!(f, "invalid implicit account ID: {}", account_id)
+            }
+        }
+    }
+}
+
+impl std::error::Error for AccountIdError {}
+
+/// Configurable strictness for account ID validation.
+///
+/// The default only applies the syntax rules enforced by `near-sdk` (length, allowed characters
+/// and separators), which is what `str::parse::<AccountId>` accepts.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AccountIdValidation {
+    /// Reject named top-level accounts (e.g. `alice`), which can only be created by the registrar.
+    pub registrar_only_top_level: bool,
+    /// Reject account IDs that look implicit but do not have the exact implicit length and
+    /// encoding, e.g. a `0x` prefixed ID that is not followed by 40 hex characters.
+    pub strict_implicit: bool,
+}
+
+impl AccountIdValidation {
+    /// Validation matching `near-sdk`'s parsing rules only.
+    pub const fn new() -> Self {
+        Self {
+            registrar_only_top_level: false,
+            strict_implicit: false,
+        }
+    }
+
+    /// Validation with every additional nearcore rule enabled.
+    pub const fn strict() -> Self {
+        Self {
+            registrar_only_top_level: true,
+            strict_implicit: true,
+        }
+    }
+
+    pub const fn registrar_only_top_level(mut self, registrar_only_top_level: bool) -> Self {
+        self.registrar_only_top_level = registrar_only_top_level;
+        self
+    }
+
+    pub const fn strict_implicit(mut self, strict_implicit: bool) -> Self {
+        self.strict_implicit = strict_implicit;
+        self
+    }
+
+    /// Validates an account ID and parses it on success.
+    pub fn validate(&self, account_id: &str) -> Result<AccountId, AccountIdError> {
+        let parsed = account_id
+            .parse::<AccountId>()
+            .map_err(|e| AccountIdError::InvalidSyntax(e.to_string()))?;
+
+        let is_top_level = !account_id.contains('.');
+        let is_eth_implicit = is_eth_implicit(account_id);
+        let is_near_implicit = is_near_implicit(account_id);
+
+        if self.strict_implicit {
+            let looks_eth_implicit = account_id.starts_with("0x");
+            let looks_near_implicit =
+                is_top_level && account_id.len() == NEAR_IMPLICIT_ACCOUNT_ID_LENGTH;
+
+            if (looks_eth_implicit && !is_eth_implicit)
+                || (looks_near_implicit && !is_near_implicit)
+            {
+                return Err(AccountIdError::InvalidImplicit(account_id.to_string()));
+            }
+        }
+
+        if self.registrar_only_top_level && is_top_level && !is_eth_implicit && !is_near_implicit {
+            return Err(AccountIdError::TopLevelNotAllowed(account_id.to_string()));
+        }
+
+        Ok(parsed)
+    }
+}
+
+fn is_near_implicit(account_id: &str) -> bool {
+    account_id.len() == NEAR_IMPLICIT_ACCOUNT_ID_LENGTH && is_lowercase_hex(account_id)
+}
+
+fn is_eth_implicit(account_id: &str) -> bool {
+    account_id.len() == ETH_IMPLICIT_ACCOUNT_ID_LENGTH
+        && account_id.strip_prefix("0x").is_some_and(is_lowercase_hex)
+}
+
+fn is_lowercase_hex(s: &str) -> bool {
+    s.bytes()
+        .all(|b| b.is_ascii_digit() || (b'a'..=b'f').contains(&b))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_validation_matches_near_sdk() {
+        let validation = AccountIdValidation::new();
+
+        assert!(validation.validate("alice.near").is_ok());
+        assert!(validation.validate("alice").is_ok());
+        assert!(validation.validate("0x123").is_ok());
+        assert!(matches!(
+            validation.validate("Alice.near"),
+            Err(AccountIdError::InvalidSyntax(_))
+        ));
+        assert!(matches!(
+            validation.validate("a"),
+            Err(AccountIdError::InvalidSyntax(_))
+        ));
+    }
+
+    #[test]
+    fn test_strict_rejects_named_top_level() {
+        let validation = AccountIdValidation::strict();
+
+        assert!(validation.validate("alice.near").is_ok());
+        assert_eq!(
+            validation.validate("alice"),
+            Err(AccountIdError::TopLevelNotAllowed("alice".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_strict_implicit_accounts() {
+        let validation = AccountIdValidation::strict();
+        let near_implicit = "a".repeat(64);
+        let eth_implicit = format!("0x{}", "b".repeat(40));
+
+        assert!(validation.validate(&near_implicit).is_ok());
+        assert!(validation.validate(&eth_implicit).is_ok());
+
+        assert!(matches!(
+            validation.validate("0x123"),
+            Err(AccountIdError::InvalidImplicit(_))
+        ));
+        assert!(matches!(
+            validation.validate(&"z".repeat(64)),
+            Err(AccountIdError::InvalidImplicit(_))
+        ));
+    }
+
+    #[test]
+    fn test_registrar_only_without_strict_implicit() {
+        let validation = AccountIdValidation::new().registrar_only_top_level(true);
+
+        assert_eq!(
+            validation.validate("0x123"),
+            Err(AccountIdError::TopLevelNotAllowed("0x123".to_string()))
+        );
+    }
+}
@@ -1,5 +1,9 @@
+mod account_id_utils;
+mod parse_error;
 mod public_key_utils;
 mod signature_utils;
 
+pub use account_id_utils::*;
+pub use parse_error::NearParseError;
 pub use public_key_utils::*;
 pub use signature_utils::*;
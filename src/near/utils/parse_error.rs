@@ -0,0 +1,47 @@
+use std::fmt;
+
+/// Errors returned when parsing a base58 public key, signature or block hash.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NearParseError {
+    /// The string is not prefixed with its key type, e.g. `ed25519:`.
+    MissingKeyType,
+    /// The key type is neither `ed25519` nor `secp256k1`.
+    UnknownKeyType,
+    /// The data is not valid base58.
+    InvalidBase58(bs58::decode::Error),
+    /// The decoded data does not have the expected length.
+    InvalidLength { expected: usize, actual: usize },
+}
+
+impl fmt::Display for NearParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingKeyType => write!(f, "missing key type prefix"),
+            Self::UnknownKeyType => {
+                write!(f, "unknown key type, expected ed25519 or secp256k1")
+            }
+            Self::InvalidBase58(e) => write!(f, "failed to decode base58: {}", e),
+            Self::InvalidLength { expected, actual } => {
+                write!(f, "expected {} bytes, got {}", expected, actual)
+            }
+        }
+    }
+}
+
+impl std::error::Error for NearParseError {}
+
+impl From<bs58::decode::Error> for NearParseError {
+    fn from(e: bs58::decode::Error) -> Self {
+        Self::InvalidBase58(e)
+    }
+}
+
+/// Converts decoded bytes into a fixed-size array, reporting the actual length otherwise.
+pub(crate) fn to_fixed_bytes<const N: usize>(bytes: Vec<u8>) -> Result<[u8; N], NearParseError> {
+    bytes
+        .try_into()
+        .map_err(|bytes: Vec<u8>| NearParseError::InvalidLength {
+            expected: N,
+            actual: bytes.len(),
+        })
+}
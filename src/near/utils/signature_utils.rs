@@ -3,99 +3,69 @@ use crate::{
     near::types::{ED25519Signature, Secp256K1Signature, Signature},
 };
 use bs58;
-use std::convert::TryInto;
+
+use super::parse_error::{to_fixed_bytes, NearParseError};
 
 pub trait SignatureStrExt {
-    fn to_signature(&self) -> Result<Signature, String>;
-    fn to_signature_as_bytes(&self) -> Result<Vec<u8>, String>;
-    fn try_ed25519_into_bytes(&self) -> Result<[u8; ED25519_SIGNATURE_LENGTH], String>;
-    fn try_secp256k1_into_bytes(&self) -> Result<[u8; SECP256K1_SIGNATURE_LENGTH], String>;
-    fn to_ed25519_signature(&self) -> Result<ED25519Signature, String>;
-    fn to_secp256k1_signature(&self) -> Result<Secp256K1Signature, String>;
+    fn to_signature(&self) -> Result<Signature, NearParseError>;
+    fn to_signature_as_bytes(&self) -> Result<Vec<u8>, NearParseError>;
+    fn try_ed25519_into_bytes(&self) -> Result<[u8; ED25519_SIGNATURE_LENGTH], NearParseError>;
+    fn try_secp256k1_into_bytes(&self) -> Result<[u8; SECP256K1_SIGNATURE_LENGTH], NearParseError>;
+    fn to_ed25519_signature(&self) -> Result<ED25519Signature, NearParseError>;
+    fn to_secp256k1_signature(&self) -> Result<Secp256K1Signature, NearParseError>;
 }
 
 impl SignatureStrExt for str {
-    fn to_signature(&self) -> Result<Signature, String> {
-        let bytes = self.to_signature_as_bytes()?;
+    fn to_signature(&self) -> Result<Signature, NearParseError> {
         if self.starts_with("ed25519:") {
-            Ok(Signature::ED25519(ED25519Signature {
-                r: bytes[..32]
-                    .try_into()
-                    .map_err(|_| "Invalid length for ED25519 signature".to_string())?,
-                s: bytes[32..]
-                    .try_into()
-                    .map_err(|_| "Invalid length for ED25519 signature".to_string())?,
-            }))
+            self.to_ed25519_signature().map(Signature::ED25519)
         } else if self.starts_with("secp256k1:") {
-            Ok(Signature::SECP256K1(Secp256K1Signature(
-                bytes
-                    .try_into()
-                    .map_err(|_| "Invalid length for SECP256K1 signature".to_string())?,
-            )))
+            self.to_secp256k1_signature().map(Signature::SECP256K1)
+        } else if self.contains(':') {
+            Err(NearParseError::UnknownKeyType)
         } else {
-            Err("Unknown key type".into())
+            Err(NearParseError::MissingKeyType)
         }
     }
 
-    fn to_signature_as_bytes(&self) -> Result<Vec<u8>, String> {
-        let (key_type, key_data) = self
-            .split_once(':')
-            .ok_or_else(|| "Invalid key format".to_string())?;
-
-        let bytes = bs58::decode(key_data)
-            .into_vec()
-            .map_err(|e| format!("Failed to decode base58: {}", e))?;
-
-        match key_type {
-            "ed25519" => {
-                if bytes.len() == ED25519_SIGNATURE_LENGTH {
-                    Ok(bytes)
-                } else {
-                    Err("ED25519 public key should be 32 bytes long".to_string())
-                }
-            }
-            "secp256k1" => {
-                if bytes.len() == SECP256K1_SIGNATURE_LENGTH {
-                    Ok(bytes)
-                } else {
-                    Err("SECP256K1 public key should be 64 bytes long".to_string())
-                }
-            }
-            _ => Err("Unknown key type".into()),
-        }
-    }
+    fn to_signature_as_bytes(&self) -> Result<Vec<u8>, NearParseError> {
+        let (key_type, key_data) = self.split_once(':').ok_or(NearParseError::MissingKeyType)?;
 
-    fn try_ed25519_into_bytes(&self) -> Result<[u8; ED25519_SIGNATURE_LENGTH], String> {
-        let bytes = self.to_signature_as_bytes()?;
-        if bytes.len() == ED25519_SIGNATURE_LENGTH {
-            Ok(bytes.try_into().unwrap())
+        let expected = match key_type {
+            "ed25519" => ED25519_SIGNATURE_LENGTH,
+            "secp256k1" => SECP256K1_SIGNATURE_LENGTH,
+            _ => return Err(NearParseError::UnknownKeyType),
+        };
+
+        let bytes = bs58::decode(key_data).into_vec()?;
+        if bytes.len() == expected {
+            Ok(bytes)
         } else {
-            Err("Invalid length for ED25519 signature".to_string())
+            Err(NearParseError::InvalidLength {
+                expected,
+                actual: bytes.len(),
+            })
         }
     }
 
-    fn try_secp256k1_into_bytes(&self) -> Result<[u8; SECP256K1_SIGNATURE_LENGTH], String> {
-        let bytes = self.to_signature_as_bytes()?;
-        if bytes.len() == SECP256K1_SIGNATURE_LENGTH {
-            Ok(bytes.try_into().unwrap())
-        } else {
-            Err("Invalid length for SECP256K1 signature".to_string())
-        }
+    fn try_ed25519_into_bytes(&self) -> Result<[u8; ED25519_SIGNATURE_LENGTH], NearParseError> {
+        to_fixed_bytes(self.to_signature_as_bytes()?)
     }
 
-    fn to_ed25519_signature(&self) -> Result<ED25519Signature, String> {
+    fn try_secp256k1_into_bytes(&self) -> Result<[u8; SECP256K1_SIGNATURE_LENGTH], NearParseError> {
+        to_fixed_bytes(self.to_signature_as_bytes()?)
+    }
+
+    fn to_ed25519_signature(&self) -> Result<ED25519Signature, NearParseError> {
         let bytes = self.try_ed25519_into_bytes()?;
+        let (r, s) = bytes.split_at(32);
         Ok(ED25519Signature {
-            r: bytes[..32]
-                .try_into()
-                .map_err(|_| "Invalid length for r".to_string())?,
-            s: bytes[32..]
-                .try_into()
-                .map_err(|_| "Invalid length for s".to_string())?,
+            r: r.try_into().expect("r is 32 bytes long"),
+            s: s.try_into().expect("s is 32 bytes long"),
         })
     }
 
-    fn to_secp256k1_signature(&self) -> Result<Secp256K1Signature, String> {
+    fn to_secp256k1_signature(&self) -> Result<Secp256K1Signature, NearParseError> {
         let bytes = self.try_secp256k1_into_bytes()?;
         Ok(Secp256K1Signature(bytes))
     }
@@ -144,13 +114,31 @@ mod tests {
     #[test]
     fn test_to_signature_for_invalid_signature() {
         let signature = "invalid:3s1dvZdQtcAjBksMHFrysqvF63wnyMHPA4owNQmCJZ2EBakZEKdtMsLqrHdKWQjJbSRN6kRknN2WdwSBLWGCokXj";
-        assert!(signature.to_signature().is_err());
+        assert_eq!(
+            signature.to_signature(),
+            Err(NearParseError::UnknownKeyType)
+        );
+
+        let signature = "3s1dvZdQtcAjBksMHFrysqvF63wnyMHPA4owNQmCJZ2EBakZEKdtMsLqrHdKWQjJbSRN6kRknN2WdwSBLWGCokXj";
+        assert_eq!(
+            signature.to_signature(),
+            Err(NearParseError::MissingKeyType)
+        );
+
+        let signature = "ed25519:0OIl";
+        assert!(matches!(
+            signature.to_signature(),
+            Err(NearParseError::InvalidBase58(_))
+        ));
     }
 
     #[test]
     fn test_to_signature_for_invalid_signature_length() {
         let signature = "ed25519:3s1dvZ";
-        assert!(signature.to_signature().is_err());
+        assert!(matches!(
+            signature.to_signature(),
+            Err(NearParseError::InvalidLength { expected: 64, .. })
+        ));
 
         let signature = "secp256k1:5N5CB9H1dBv6iBh6";
         assert!(signature.to_signature().is_err());
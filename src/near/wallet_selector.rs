@@ -0,0 +1,498 @@
+//! Adapters for NEAR wallet-selector style `signAndSendTransaction(s)` requests.
+//!
+//! The requests only carry the receiver, the actions and optionally the signer, the remaining
+//! fields (public key, nonce and block hash) are left to the relayer to fill in the returned
+//! builders.
+//!
+//! Complete transactions in the same camelCase JSON, as produced by near-api-js based wallets,
+//! are parsed by [`transaction_from_json`].
+use near_sdk::serde::Deserialize;
+use near_sdk::serde_json::{self, Value};
+use near_sdk::AccountId;
+use std::fmt;
+
+use super::{
+    near_transaction::NearTransaction,
+    near_transaction_builder::NearTransactionBuilder,
+    types::{
+        AccessKey, AccessKeyPermission, Action, AddKeyAction, BlockHash, CreateAccountAction,
+        DeleteAccountAction, DeleteKeyAction, DeployContractAction, FunctionCallAction,
+        FunctionCallPermission, NearGas, NearToken, PublicKey, StakeAction, TransferAction, U128,
+        U64,
+    },
+    utils::{AccountIdError, AccountIdValidation, NearParseError, PublicKeyStrExt},
+};
+
+/// Errors returned when converting wallet JSON into a transaction or a builder.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WalletJsonError {
+    /// The JSON does not have the shape of a wallet request or transaction.
+    InvalidJson(String),
+    /// A public key is not a valid `ed25519:` or `secp256k1:` key.
+    InvalidPublicKey(NearParseError),
+    /// An account ID is invalid.
+    InvalidAccountId(AccountIdError),
+    /// An access key permission is neither `FullAccess` nor a function call permission.
+    UnknownPermission(String),
+    /// Function call arguments are neither a byte array nor a JSON value.
+    InvalidArgs(String),
+}
+
+impl fmt::Display for WalletJsonError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidJson(reason) => write!(f, "invalid wallet JSON: {}", reason),
+            Self::InvalidPublicKey(error) => write!(f, "invalid public key: {}", error),
+            Self::InvalidAccountId(error) => write!(f, "{}", error),
+            Self::UnknownPermission(permission) => {
+                write!(f, "unknown permission: {}", permission)
+            }
+            Self::InvalidArgs(reason) => write!(f, "invalid function call args: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for WalletJsonError {}
+
+impl From<serde_json::Error> for WalletJsonError {
+    fn from(e: serde_json::Error) -> Self {
+        Self::InvalidJson(e.to_string())
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(crate = "near_sdk::serde", rename_all = "camelCase")]
+struct WalletTransaction {
+    signer_id: Option<String>,
+    receiver_id: String,
+    actions: Vec<WalletAction>,
+}
+
+/// A complete transaction, including the public key, nonce and block hash.
+#[derive(Deserialize)]
+#[serde(crate = "near_sdk::serde", rename_all = "camelCase")]
+struct WalletSignedTransaction {
+    signer_id: String,
+    #[serde(alias = "signerPublicKey")]
+    public_key: String,
+    nonce: U64,
+    receiver_id: String,
+    block_hash: BlockHash,
+    actions: Vec<WalletAction>,
+}
+
+#[derive(Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+struct WalletTransactions {
+    transactions: Vec<WalletTransaction>,
+}
+
+#[derive(Deserialize)]
+#[serde(crate = "near_sdk::serde", tag = "type", content = "params")]
+enum WalletAction {
+    CreateAccount,
+    DeployContract {
+        code: Vec<u8>,
+    },
+    #[serde(rename_all = "camelCase")]
+    FunctionCall {
+        method_name: String,
+        args: Value,
+        gas: NearGas,
+        deposit: NearToken,
+    },
+    Transfer {
+        deposit: NearToken,
+    },
+    #[serde(rename_all = "camelCase")]
+    Stake {
+        stake: NearToken,
+        public_key: String,
+    },
+    #[serde(rename_all = "camelCase")]
+    AddKey {
+        public_key: String,
+        access_key: WalletAccessKey,
+    },
+    #[serde(rename_all = "camelCase")]
+    DeleteKey {
+        public_key: String,
+    },
+    #[serde(rename_all = "camelCase")]
+    DeleteAccount {
+        beneficiary_id: String,
+    },
+}
+
+#[derive(Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+struct WalletAccessKey {
+    #[serde(default)]
+    nonce: Option<U64>,
+    permission: WalletPermission,
+}
+
+#[derive(Deserialize)]
+#[serde(crate = "near_sdk::serde", untagged)]
+enum WalletPermission {
+    FullAccess(String),
+    #[serde(rename_all = "camelCase")]
+    FunctionCall {
+        receiver_id: String,
+        #[serde(default)]
+        allowance: Option<U128>,
+        #[serde(default)]
+        method_names: Vec<String>,
+    },
+}
+
+/// Converts a `signAndSendTransaction` request into a builder with the signer (if given), the
+/// receiver and the actions set.
+pub fn sign_and_send_transaction(request: &str) -> Result<NearTransactionBuilder, WalletJsonError> {
+    let transaction: WalletTransaction = serde_json::from_str(request)?;
+
+    transaction.into_builder()
+}
+
+/// Converts a `signAndSendTransactions` request into one builder per transaction.
+pub fn sign_and_send_transactions(
+    request: &str,
+) -> Result<Vec<NearTransactionBuilder>, WalletJsonError> {
+    let request: WalletTransactions = serde_json::from_str(request)?;
+
+    request
+        .transactions
+        .into_iter()
+        .map(WalletTransaction::into_builder)
+        .collect()
+}
+
+/// Parses a complete transaction in the near-api-js JSON format: camelCase fields, the public
+/// key as a `ed25519:...` string, the block hash in base58, and actions tagged by `type` with
+/// their `params`, amounts being decimal strings.
+///
+/// ```json
+/// {
+///     "signerId": "alice.near",
+///     "publicKey": "ed25519:6E8sCci9badyRkXb3JoRpBj5p8C6Tw41ELDZoiihKEtp",
+///     "nonce": "1",
+///     "receiverId": "bob.near",
+///     "blockHash": "4reLvkAWfqk5fsqio1KLudk46cqRz9erQdaHkWZKMJDZ",
+///     "actions": [{ "type": "Transfer", "params": { "deposit": "1" } }]
+/// }
+/// ```
+pub fn transaction_from_json(json: &str) -> Result<NearTransaction, WalletJsonError> {
+    let transaction: WalletSignedTransaction = serde_json::from_str(json)?;
+
+    Ok(NearTransaction {
+        signer_id: parse_account_id(&transaction.signer_id)?,
+        signer_public_key: parse_public_key(&transaction.public_key)?,
+        nonce: transaction.nonce,
+        receiver_id: parse_account_id(&transaction.receiver_id)?,
+        block_hash: transaction.block_hash,
+        actions: transaction
+            .actions
+            .into_iter()
+            .map(WalletAction::into_action)
+            .collect::<Result<_, _>>()?,
+    })
+}
+
+impl WalletTransaction {
+    fn into_builder(self) -> Result<NearTransactionBuilder, WalletJsonError> {
+        let actions = self
+            .actions
+            .into_iter()
+            .map(WalletAction::into_action)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut builder = NearTransactionBuilder::new()
+            .receiver_id(self.receiver_id)
+            .actions(actions);
+        if let Some(signer_id) = self.signer_id {
+            builder = builder.signer_id(signer_id);
+        }
+
+        Ok(builder)
+    }
+}
+
+impl WalletAction {
+    fn into_action(self) -> Result<Action, WalletJsonError> {
+        Ok(match self {
+            Self::CreateAccount => Action::CreateAccount(CreateAccountAction {}),
+            Self::DeployContract { code } => Action::DeployContract(DeployContractAction { code }),
+            Self::FunctionCall {
+                method_name,
+                args,
+                gas,
+                deposit,
+            } => Action::FunctionCall(Box::new(FunctionCallAction {
+                method_name,
+                args: function_call_args(args)?,
+                gas,
+                deposit,
+            })),
+            Self::Transfer { deposit } => Action::Transfer(TransferAction { deposit }),
+            Self::Stake { stake, public_key } => Action::Stake(Box::new(StakeAction {
+                stake,
+                public_key: parse_public_key(&public_key)?,
+            })),
+            Self::AddKey {
+                public_key,
+                access_key,
+            } => Action::AddKey(Box::new(AddKeyAction {
+                public_key: parse_public_key(&public_key)?,
+                access_key: AccessKey {
+                    nonce: access_key.nonce.unwrap_or(U64(0)),
+                    permission: access_key.permission.into_permission()?,
+                },
+            })),
+            Self::DeleteKey { public_key } => Action::DeleteKey(Box::new(DeleteKeyAction {
+                public_key: parse_public_key(&public_key)?,
+            })),
+            Self::DeleteAccount { beneficiary_id } => Action::DeleteAccount(DeleteAccountAction {
+                beneficiary_id: parse_account_id(&beneficiary_id)?,
+            }),
+        })
+    }
+}
+
+impl WalletPermission {
+    fn into_permission(self) -> Result<AccessKeyPermission, WalletJsonError> {
+        match self {
+            Self::FullAccess(permission) if permission == "FullAccess" => {
+                Ok(AccessKeyPermission::FullAccess)
+            }
+            Self::FullAccess(permission) => Err(WalletJsonError::UnknownPermission(permission)),
+            Self::FunctionCall {
+                receiver_id,
+                allowance,
+                method_names,
+            } => Ok(AccessKeyPermission::FunctionCall(FunctionCallPermission {
+                allowance,
+                receiver_id,
+                method_names,
+            })),
+        }
+    }
+}
+
+/// Function call arguments are either raw bytes (a serialized `Uint8Array`) or a JSON value
+/// which is serialized the same way wallets do.
+fn function_call_args(args: Value) -> Result<Vec<u8>, WalletJsonError> {
+    match args {
+        Value::Array(bytes) => bytes
+            .iter()
+            .map(|b| {
+                b.as_u64()
+                    .and_then(|b| u8::try_from(b).ok())
+                    .ok_or_else(|| {
+                        WalletJsonError::InvalidArgs(
+                            "args should be a byte array or a JSON object".to_string(),
+                        )
+                    })
+            })
+            .collect(),
+        args => serde_json::to_vec(&args).map_err(|e| WalletJsonError::InvalidArgs(e.to_string())),
+    }
+}
+
+fn parse_public_key(public_key: &str) -> Result<PublicKey, WalletJsonError> {
+    public_key
+        .to_public_key()
+        .map_err(WalletJsonError::InvalidPublicKey)
+}
+
+fn parse_account_id(account_id: &str) -> Result<AccountId, WalletJsonError> {
+    AccountIdValidation::new()
+        .validate(account_id)
+        .map_err(WalletJsonError::InvalidAccountId)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::near::types::BlockHash;
+
+    #[test]
+    fn test_sign_and_send_transaction() {
+        let request = r#"{
+            "signerId": "alice.near",
+            "receiverId": "wrap.near",
+            "actions": [
+                {
+                    "type": "FunctionCall",
+                    "params": {
+                        "methodName": "near_deposit",
+                        "args": {},
+                        "gas": "30000000000000",
+                        "deposit": "1000000000000000000000000"
+                    }
+                },
+                { "type": "Transfer", "params": { "deposit": "1" } }
+            ]
+        }"#;
+
+        let tx = sign_and_send_transaction(request)
+            .unwrap()
+            .signer_public_key(PublicKey::SECP256K1([0u8; 64].into()))
+            .nonce(1)
+            .block_hash(BlockHash([0u8; 32]))
+            .try_build()
+            .unwrap();
+
+        assert_eq!(tx.signer_id.as_str(), "alice.near");
+        assert_eq!(tx.receiver_id.as_str(), "wrap.near");
+        assert_eq!(
+            tx.actions,
+            vec![
+                Action::FunctionCall(Box::new(FunctionCallAction {
+                    method_name: "near_deposit".to_string(),
+                    args: b"{}".to_vec(),
+                    gas: NearGas::from_gas(30_000_000_000_000),
+                    deposit: NearToken::from_yoctonear(1_000_000_000_000_000_000_000_000),
+                })),
+                Action::Transfer(TransferAction {
+                    deposit: NearToken::from_yoctonear(1)
+                }),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_sign_and_send_transactions_with_keys() {
+        let request = r#"{
+            "transactions": [
+                {
+                    "receiverId": "alice.near",
+                    "actions": [
+                        {
+                            "type": "AddKey",
+                            "params": {
+                                "publicKey": "ed25519:6E8sCci9badyRkXb3JoRpBj5p8C6Tw41ELDZoiihKEtp",
+                                "accessKey": { "permission": "FullAccess" }
+                            }
+                        }
+                    ]
+                },
+                {
+                    "receiverId": "alice.near",
+                    "actions": [
+                        {
+                            "type": "AddKey",
+                            "params": {
+                                "publicKey": "ed25519:6E8sCci9badyRkXb3JoRpBj5p8C6Tw41ELDZoiihKEtp",
+                                "accessKey": {
+                                    "permission": {
+                                        "receiverId": "app.near",
+                                        "methodNames": ["play"]
+                                    }
+                                }
+                            }
+                        },
+                        { "type": "DeployContract", "params": { "code": [0, 97, 115, 109] } }
+                    ]
+                }
+            ]
+        }"#;
+
+        let builders = sign_and_send_transactions(request).unwrap();
+
+        assert_eq!(builders.len(), 2);
+        let actions = builders[1].actions.as_ref().unwrap();
+        assert!(matches!(
+            &actions[0],
+            Action::AddKey(add_key) if matches!(
+                &add_key.access_key.permission,
+                AccessKeyPermission::FunctionCall(permission) if permission.receiver_id == "app.near"
+            )
+        ));
+        assert_eq!(
+            actions[1],
+            Action::DeployContract(DeployContractAction {
+                code: vec![0, 97, 115, 109]
+            })
+        );
+    }
+
+    #[test]
+    fn test_transaction_from_json() {
+        let json = r#"{
+            "signerId": "alice.near",
+            "publicKey": "ed25519:6E8sCci9badyRkXb3JoRpBj5p8C6Tw41ELDZoiihKEtp",
+            "nonce": "172237399000001",
+            "receiverId": "wrap.near",
+            "blockHash": "4reLvkAWfqk5fsqio1KLudk46cqRz9erQdaHkWZKMJDZ",
+            "actions": [
+                {
+                    "type": "FunctionCall",
+                    "params": {
+                        "methodName": "ft_transfer",
+                        "args": { "receiver_id": "bob.near", "amount": "100" },
+                        "gas": "30000000000000",
+                        "deposit": "1"
+                    }
+                },
+                { "type": "Transfer", "params": { "deposit": "1000000000000000000000000" } }
+            ]
+        }"#;
+
+        let tx = transaction_from_json(json).unwrap();
+
+        assert_eq!(tx.signer_id.as_str(), "alice.near");
+        assert_eq!(
+            tx.signer_public_key,
+            parse_public_key("ed25519:6E8sCci9badyRkXb3JoRpBj5p8C6Tw41ELDZoiihKEtp").unwrap()
+        );
+        assert_eq!(tx.nonce, U64(172237399000001));
+        assert_eq!(
+            tx.block_hash,
+            "4reLvkAWfqk5fsqio1KLudk46cqRz9erQdaHkWZKMJDZ"
+                .parse()
+                .unwrap()
+        );
+        assert_eq!(
+            tx.actions[1],
+            Action::Transfer(TransferAction {
+                deposit: NearToken::from_yoctonear(1_000_000_000_000_000_000_000_000)
+            })
+        );
+        assert_eq!(NearTransaction::from_wallet_json(json), Ok(tx));
+
+        // Enum-style actions are not accepted.
+        let enum_style = json.replace(
+            r#"{ "type": "Transfer", "params": { "deposit": "1000000000000000000000000" } }"#,
+            r#"{ "Transfer": { "deposit": "1" } }"#,
+        );
+        assert!(matches!(
+            transaction_from_json(&enum_style),
+            Err(WalletJsonError::InvalidJson(_))
+        ));
+        assert!(matches!(
+            transaction_from_json(&json.replace("alice.near", "Alice")),
+            Err(WalletJsonError::InvalidAccountId(
+                AccountIdError::InvalidSyntax(_)
+            ))
+        ));
+    }
+
+    #[test]
+    fn test_unknown_permission_fails() {
+        let request = r#"{
+            "receiverId": "alice.near",
+            "actions": [{
+                "type": "AddKey",
+                "params": {
+                    "publicKey": "ed25519:6E8sCci9badyRkXb3JoRpBj5p8C6Tw41ELDZoiihKEtp",
+                    "accessKey": { "permission": "ReadOnly" }
+                }
+            }]
+        }"#;
+
+        assert_eq!(
+            sign_and_send_transaction(request).err(),
+            Some(WalletJsonError::UnknownPermission("ReadOnly".to_string()))
+        );
+    }
+}
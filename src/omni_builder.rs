@@ -0,0 +1,401 @@
+//! Building transactions for a chain chosen at runtime.
+//!
+//! The typed builders ([`NEAR`](crate::types::NEAR), [`EVM`](crate::types::EVM), ...) select the
+//! chain at compile time. A contract routing the requests of many chains through a single code
+//! path can instead pick the chain from a [`ChainKind`] and describe the transaction with the
+//! chain-agnostic [`OmniTransactionParams`]:
+//!
+//! ```ignore
+//! let params: OmniTransactionParams = serde_json::from_str(&request)?;
+//! let payload = OmniBuilder::for_chain(chain).build_for_signing(&params)?;
+//! // ... request a `payload.curve` signature of `payload.digest()` from the MPC signer ...
+//! ```
+//!
+//! Only simple transfers and calls are covered, and only NEAR and EVM transactions are
+//! supported. Every other [`ChainKind`] returns [`OmniBuildError::UnsupportedChain`]:
+//!
+//! - Bitcoin transactions spend UTXOs and need one signature per input, which a single
+//!   [`SignaturePayload`] can't describe. Build them with the typed builder and sign them
+//!   through a [`BitcoinPayload`](crate::payload_batch::BitcoinPayload).
+//! - Solana, Cosmos, Aptos, Starknet, Tron and Cardano transactions are made of chain-specific
+//!   instructions, messages or contracts, built with their typed builders.
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "evm")]
+use crate::evm::evm_transaction_builder::{EVMBuildError, EVMTransactionBuilder};
+#[cfg(feature = "near")]
+use crate::near::{
+    near_transaction_builder::{NearBuildError, NearTransactionBuilder},
+    types::BlockHash,
+    utils::PublicKeyStrExt,
+};
+use crate::signature_payload::SignaturePayload;
+#[cfg(any(feature = "near", feature = "evm"))]
+use crate::signature_payload::ToSignaturePayload;
+use crate::types::ChainKind;
+
+/// Errors returned by [`OmniBuilder::build_for_signing`].
+///
+/// The errors of each chain builder are only available with the chain feature, so matches
+/// must have a wildcard arm.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum OmniBuildError {
+    /// The chain can't be built from [`OmniTransactionParams`], or its feature is disabled.
+    UnsupportedChain(ChainKind),
+    /// A parameter needed by the chain was not set.
+    MissingField(&'static str),
+    /// A parameter could not be converted to the type of the chain.
+    InvalidField { field: &'static str, reason: String },
+    #[cfg(feature = "near")]
+    Near(NearBuildError),
+    #[cfg(feature = "evm")]
+    Evm(EVMBuildError),
+}
+
+impl fmt::Display for OmniBuildError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnsupportedChain(chain) => {
+                write!(f, "{:?} transactions can't be built at runtime", chain)
+            }
+            Self::MissingField(field) => write!(f, "{} is mandatory", field),
+            Self::InvalidField { field, reason } => write!(f, "{}: {}", field, reason),
+            #[cfg(feature = "near")]
+            Self::Near(error) => write!(f, "{}", error),
+            #[cfg(feature = "evm")]
+            Self::Evm(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl std::error::Error for OmniBuildError {}
+
+#[cfg(feature = "near")]
+impl From<NearBuildError> for OmniBuildError {
+    fn from(error: NearBuildError) -> Self {
+        Self::Near(error)
+    }
+}
+
+#[cfg(feature = "evm")]
+impl From<EVMBuildError> for OmniBuildError {
+    fn from(error: EVMBuildError) -> Self {
+        Self::Evm(error)
+    }
+}
+
+/// The parameters of a transaction, shared by every chain.
+///
+/// Each chain reads the fields it needs and ignores the others. Missing fields default to
+/// `None` when deserializing, so a request only carries what its chain uses.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OmniTransactionParams {
+    /// NEAR signer account ID.
+    pub signer_id: Option<String>,
+    /// NEAR signer public key, e.g. `ed25519:...`.
+    pub signer_public_key: Option<String>,
+    pub nonce: Option<u64>,
+    /// NEAR receiver account ID, or EVM recipient address in hex. An EVM transaction without
+    /// receiver deploys a contract.
+    pub receiver: Option<String>,
+    /// Amount sent to the receiver, in yoctoNEAR or wei.
+    pub value: Option<u128>,
+    /// NEAR method to call on the receiver. Without it, `value` is transferred.
+    pub method_name: Option<String>,
+    /// NEAR call arguments, or EVM input.
+    pub data: Vec<u8>,
+    /// NEAR call gas, or EVM gas limit.
+    pub gas: Option<u128>,
+    /// NEAR block hash in base58.
+    pub block_hash: Option<String>,
+    pub max_fee_per_gas: Option<u128>,
+    pub max_priority_fee_per_gas: Option<u128>,
+    /// EVM gas price. If set without `max_fee_per_gas`, an EIP-2930 transaction is built
+    /// instead of an EIP-1559 one.
+    pub gas_price: Option<u128>,
+}
+
+/// Builds the signing payloads of transactions for a chain chosen at runtime.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OmniBuilder {
+    chain: ChainKind,
+}
+
+impl OmniBuilder {
+    pub const fn for_chain(chain: ChainKind) -> Self {
+        Self { chain }
+    }
+
+    pub const fn chain(&self) -> &ChainKind {
+        &self.chain
+    }
+
+    /// Builds the transaction described by `params`, and returns its payload to sign.
+    ///
+    /// Fails with [`OmniBuildError::UnsupportedChain`] for chains other than NEAR and EVM, or
+    /// if their feature is disabled, see the [module documentation](self).
+    #[cfg_attr(not(any(feature = "near", feature = "evm")), allow(unused_variables))]
+    pub fn build_for_signing(
+        &self,
+        params: &OmniTransactionParams,
+    ) -> Result<SignaturePayload, OmniBuildError> {
+        match &self.chain {
+            #[cfg(feature = "near")]
+            ChainKind::Near => Self::build_near(params),
+            #[cfg(feature = "evm")]
+            ChainKind::Evm { chain_id } => Self::build_evm(*chain_id, params),
+            chain => Err(OmniBuildError::UnsupportedChain(chain.clone())),
+        }
+    }
+
+    #[cfg(feature = "near")]
+    fn build_near(params: &OmniTransactionParams) -> Result<SignaturePayload, OmniBuildError> {
+        let mut builder = NearTransactionBuilder::new();
+
+        if let Some(signer_id) = &params.signer_id {
+            builder = builder.signer_id(signer_id.clone());
+        }
+        if let Some(public_key) = &params.signer_public_key {
+            let public_key =
+                public_key
+                    .to_public_key()
+                    .map_err(|error| OmniBuildError::InvalidField {
+                        field: "signer_public_key",
+                        reason: error.to_string(),
+                    })?;
+            builder = builder.signer_public_key(public_key);
+        }
+        if let Some(nonce) = params.nonce {
+            builder = builder.nonce(nonce);
+        }
+        if let Some(receiver) = &params.receiver {
+            builder = builder.receiver_id(receiver.clone());
+        }
+        if let Some(block_hash) = &params.block_hash {
+            let block_hash =
+                block_hash
+                    .parse::<BlockHash>()
+                    .map_err(|error| OmniBuildError::InvalidField {
+                        field: "block_hash",
+                        reason: error.to_string(),
+                    })?;
+            builder = builder.block_hash(block_hash);
+        }
+
+        builder = match &params.method_name {
+            Some(method_name) => {
+                let gas = params.gas.ok_or(OmniBuildError::MissingField("gas"))?;
+                let gas = u64::try_from(gas).map_err(|_| OmniBuildError::InvalidField {
+                    field: "gas",
+                    reason: format!("{} does not fit in a u64", gas),
+                })?;
+                builder.function_call(
+                    method_name.clone(),
+                    params.data.clone(),
+                    gas,
+                    params.value.unwrap_or(0),
+                )
+            }
+            None => builder.transfer(params.value.ok_or(OmniBuildError::MissingField("value"))?),
+        };
+
+        Ok(builder.try_build()?.signature_payload())
+    }
+
+    #[cfg(feature = "evm")]
+    fn build_evm(
+        chain_id: u64,
+        params: &OmniTransactionParams,
+    ) -> Result<SignaturePayload, OmniBuildError> {
+        let mut builder = EVMTransactionBuilder::new()
+            .chain_id(chain_id)
+            .value(params.value.unwrap_or(0))
+            .input(params.data.clone());
+
+        if let Some(nonce) = params.nonce {
+            builder = builder.nonce(nonce);
+        }
+        if let Some(receiver) = &params.receiver {
+            builder = builder.to_hex(receiver);
+        }
+        if let Some(gas) = params.gas {
+            builder = builder.gas_limit(gas);
+        }
+        if let Some(max_fee_per_gas) = params.max_fee_per_gas {
+            builder = builder.max_fee_per_gas(max_fee_per_gas);
+        }
+        if let Some(max_priority_fee_per_gas) = params.max_priority_fee_per_gas {
+            builder = builder.max_priority_fee_per_gas(max_priority_fee_per_gas);
+        }
+
+        match params.gas_price {
+            Some(gas_price) if params.max_fee_per_gas.is_none() => Ok(builder
+                .gas_price(gas_price)
+                .try_build_eip2930()?
+                .signature_payload()),
+            _ => Ok(builder.try_build()?.signature_payload()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::BitcoinNetwork;
+
+    #[cfg(feature = "evm")]
+    #[test]
+    fn test_build_evm() {
+        let params: OmniTransactionParams = serde_json::from_str(
+            r#"{
+                "nonce": 3,
+                "receiver": "0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045",
+                "value": 10000000000000000,
+                "data": [1, 2, 3],
+                "gas": 21000,
+                "max_fee_per_gas": 20000000000,
+                "max_priority_fee_per_gas": 1000000000
+            }"#,
+        )
+        .unwrap();
+
+        let payload = OmniBuilder::for_chain(ChainKind::Evm { chain_id: 1 })
+            .build_for_signing(&params)
+            .unwrap();
+
+        let expected = EVMTransactionBuilder::new()
+            .chain_id(1)
+            .nonce(3)
+            .to_hex("d8dA6BF26964aF9D7eEd9e03E53415D37aA96045")
+            .value(10_000_000_000_000_000)
+            .input(vec![1, 2, 3])
+            .gas_limit(21_000)
+            .max_fee_per_gas(20_000_000_000)
+            .max_priority_fee_per_gas(1_000_000_000)
+            .try_build()
+            .unwrap();
+        assert_eq!(payload, expected.signature_payload());
+
+        // A gas price alone builds an EIP-2930 transaction.
+        let params = OmniTransactionParams {
+            max_fee_per_gas: None,
+            max_priority_fee_per_gas: None,
+            gas_price: Some(20_000_000_000),
+            ..params
+        };
+        let payload = OmniBuilder::for_chain(ChainKind::Evm { chain_id: 1 })
+            .build_for_signing(&params)
+            .unwrap();
+        assert_eq!(payload.payload[0], 0x01);
+    }
+
+    #[cfg(feature = "evm")]
+    #[test]
+    fn test_build_evm_errors() {
+        let builder = OmniBuilder::for_chain(ChainKind::Evm { chain_id: 1 });
+
+        assert_eq!(
+            builder.build_for_signing(&OmniTransactionParams::default()),
+            Err(OmniBuildError::Evm(EVMBuildError::MissingField("nonce")))
+        );
+
+        let params = OmniTransactionParams {
+            nonce: Some(0),
+            receiver: Some("0x1234".to_string()),
+            gas: Some(21_000),
+            max_fee_per_gas: Some(2),
+            max_priority_fee_per_gas: Some(1),
+            ..Default::default()
+        };
+        assert!(matches!(
+            builder.build_for_signing(&params),
+            Err(OmniBuildError::Evm(EVMBuildError::InvalidAddress(_)))
+        ));
+    }
+
+    #[cfg(feature = "near")]
+    #[test]
+    fn test_build_near() {
+        let params = OmniTransactionParams {
+            signer_id: Some("alice.near".to_string()),
+            signer_public_key: Some(
+                "ed25519:6E8sCci9badyRkXb3JoRpBj5p8C6Tw41ELDZoiihKEtp".to_string(),
+            ),
+            nonce: Some(1),
+            receiver: Some("wrap.near".to_string()),
+            block_hash: Some("4reLvkAWfqk5fsqio1KLudk46cqRz9erQdaHkWZKMJDZ".to_string()),
+            method_name: Some("near_deposit".to_string()),
+            data: b"{}".to_vec(),
+            gas: Some(30_000_000_000_000),
+            value: Some(1),
+            ..Default::default()
+        };
+
+        let payload = OmniBuilder::for_chain(ChainKind::Near)
+            .build_for_signing(&params)
+            .unwrap();
+
+        let expected = NearTransactionBuilder::new()
+            .signer_id("alice.near".to_string())
+            .signer_public_key(
+                "ed25519:6E8sCci9badyRkXb3JoRpBj5p8C6Tw41ELDZoiihKEtp"
+                    .to_public_key()
+                    .unwrap(),
+            )
+            .nonce(1)
+            .receiver_id("wrap.near".to_string())
+            .block_hash(
+                "4reLvkAWfqk5fsqio1KLudk46cqRz9erQdaHkWZKMJDZ"
+                    .parse()
+                    .unwrap(),
+            )
+            .function_call("near_deposit", b"{}".to_vec(), 30_000_000_000_000, 1)
+            .try_build()
+            .unwrap();
+        assert_eq!(payload, expected.signature_payload());
+
+        let params = OmniTransactionParams {
+            method_name: None,
+            value: None,
+            ..params
+        };
+        assert_eq!(
+            OmniBuilder::for_chain(ChainKind::Near).build_for_signing(&params),
+            Err(OmniBuildError::MissingField("value"))
+        );
+    }
+
+    #[test]
+    fn test_unsupported_chain() {
+        let err = OmniBuilder::for_chain(ChainKind::Solana)
+            .build_for_signing(&OmniTransactionParams::default())
+            .unwrap_err();
+
+        assert_eq!(err, OmniBuildError::UnsupportedChain(ChainKind::Solana));
+        assert_eq!(
+            err.to_string(),
+            "Solana transactions can't be built at runtime"
+        );
+
+        for chain in [
+            ChainKind::Bitcoin {
+                network: BitcoinNetwork::Bitcoin,
+            },
+            ChainKind::Starknet {
+                chain_id: "SN_MAIN".to_string(),
+            },
+            ChainKind::Tron,
+            ChainKind::Cardano,
+        ] {
+            assert_eq!(
+                OmniBuilder::for_chain(chain.clone())
+                    .build_for_signing(&OmniTransactionParams::default()),
+                Err(OmniBuildError::UnsupportedChain(chain))
+            );
+        }
+    }
+}
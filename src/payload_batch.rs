@@ -0,0 +1,901 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "bitcoin")]
+use crate::bitcoin::{
+    bitcoin_transaction::BitcoinTransaction,
+    types::{
+        EcdsaSighashType, Network, ScriptBuf, ScriptBuilder, TransactionType, Version, Witness,
+        MAX_SCRIPT_ELEMENT_SIZE,
+    },
+};
+#[cfg(feature = "evm")]
+use crate::evm::{evm_transaction::EVMTransaction, types::Signature as EVMSignature};
+#[cfg(feature = "near")]
+use crate::near::{near_transaction::NearTransaction, types::Signature as NearSignature};
+use crate::types::ChainKind;
+
+/// Errors returned when building or signing the payloads of a [`PayloadBatch`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PayloadBatchError {
+    /// The signature index is not below [`ChainPayload::signature_count`].
+    SignatureIndexOutOfRange(usize),
+    /// The Bitcoin input being signed does not exist in the transaction.
+    InputIndexOutOfRange(usize),
+    /// The legacy input is signed with `SIGHASH_SINGLE` but has no output at its index.
+    SighashSingleWithoutOutput(usize),
+    /// A SegWit input is signed in a transaction with a version lower than 2.
+    UnsupportedVersion,
+    /// The input is a Taproot input, whose sighash can't be built from a payload.
+    TaprootInput(usize),
+    /// The signature is for another chain than the payload.
+    ChainMismatch,
+    /// The number of signatures does not match [`ChainPayload::signature_count`].
+    WrongSignatureCount { expected: usize, actual: usize },
+    /// A script sig push is larger than `MAX_SCRIPT_ELEMENT_SIZE`.
+    PushTooLarge(usize),
+    /// No payload of the batch has this id.
+    UnknownPayload(PayloadId),
+    /// The signature slot is already filled.
+    AlreadyAttached { id: PayloadId, index: usize },
+    /// The payload is still missing signatures.
+    NotSigned(PayloadId),
+    /// A payload of the batch can't be signed.
+    InvalidPayload {
+        id: PayloadId,
+        error: Box<PayloadBatchError>,
+    },
+}
+
+impl fmt::Display for PayloadBatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::SignatureIndexOutOfRange(index) => {
+                write!(f, "signature index {} out of range", index)
+            }
+            Self::InputIndexOutOfRange(index) => write!(f, "input index {} out of range", index),
+            Self::SighashSingleWithoutOutput(index) => write!(
+                f,
+                "input {} is signed with SIGHASH_SINGLE but has no matching output",
+                index
+            ),
+            Self::UnsupportedVersion => {
+                write!(f, "SegWit transactions must be version 2 or higher")
+            }
+            Self::TaprootInput(index) => write!(
+                f,
+                "input {} is a Taproot input, which can't be signed from a payload",
+                index
+            ),
+            Self::ChainMismatch => write!(f, "signature does not match the payload chain"),
+            Self::WrongSignatureCount { expected, actual } => {
+                write!(f, "expected {} signatures, got {}", expected, actual)
+            }
+            Self::PushTooLarge(len) => write!(f, "script push of {} bytes is too large", len),
+            Self::UnknownPayload(id) => write!(f, "unknown payload id: {}", id),
+            Self::AlreadyAttached { id, index } => write!(
+                f,
+                "signature {} of payload {} is already attached",
+                index, id
+            ),
+            Self::NotSigned(id) => write!(f, "payload {} is not signed", id),
+            Self::InvalidPayload { id, error } => write!(f, "payload {}: {}", id, error),
+        }
+    }
+}
+
+impl std::error::Error for PayloadBatchError {}
+
+/// Identifier of a payload inside a [`PayloadBatch`].
+///
+/// Ids are assigned sequentially when payloads are added and never change, so they can be
+/// handed out to the signer and used to attach signatures in any order.
+pub type PayloadId = u32;
+
+/// A Bitcoin transaction along with the inputs that need to be signed.
+///
+/// Each signed input needs its own signature, so the payload asks for one signature per entry
+/// of `inputs`, in order.
+#[cfg(feature = "bitcoin")]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub struct BitcoinPayload {
+    pub network: Network,
+    /// The unsigned transaction.
+    pub transaction: BitcoinTransaction,
+    pub inputs: Vec<BitcoinInputSpec>,
+}
+
+/// How an input of a [`BitcoinPayload`] is signed.
+#[cfg(feature = "bitcoin")]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub struct BitcoinInputSpec {
+    /// The index of the input being signed.
+    pub input_index: usize,
+    /// The type of the output being spent.
+    pub tx_type: TransactionType,
+    pub sighash_type: EcdsaSighashType,
+    /// The script code of the input: the script pubkey (or redeem script for P2SH) of the
+    /// spent output for legacy inputs, the BIP-143 script code for SegWit inputs.
+    pub script_code: ScriptBuf,
+    /// The value of the output being spent, in satoshis (only used for SegWit inputs).
+    pub value: u64,
+}
+
+/// An unsigned transaction waiting for a signature, with a chain-agnostic signing interface.
+///
+/// Lets a contract keep heterogeneous pending transactions in a single collection:
+///
+/// ```ignore
+/// let payload = pending_transaction.build_for_signing(0)?;
+/// // ... request a signature for `payload` from the MPC signer ...
+/// let signed_transaction = pending_transaction.build_with_signatures(&[signature])?;
+/// ```
+///
+/// A Bitcoin transaction has one signing payload per input, so its variant holds the
+/// transaction along with the inputs being signed ([`BitcoinPayload`]). Transactions that only
+/// need to be stored are better kept as an
+/// [`OmniTransaction`](crate::versioned_transaction::OmniTransaction).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub enum ChainPayload {
+    #[cfg(feature = "near")]
+    Near(NearTransaction),
+    #[cfg(feature = "evm")]
+    Evm(EVMTransaction),
+    #[cfg(feature = "bitcoin")]
+    Bitcoin(BitcoinPayload),
+}
+
+/// A signature produced for a [`ChainPayload`].
+#[derive(Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+pub enum ChainSignature {
+    #[cfg(feature = "near")]
+    Near(NearSignature),
+    #[cfg(feature = "evm")]
+    Evm(EVMSignature),
+    /// The unlocking items of the input, e.g. `[signature, public_key]` for P2PKH and P2WPKH.
+    ///
+    /// For SegWit inputs they are placed in the witness, otherwise they are pushed into the
+    /// script sig.
+    #[cfg(feature = "bitcoin")]
+    Bitcoin(Vec<Vec<u8>>),
+}
+
+impl ChainPayload {
+    /// Returns the chain the transaction is for.
+    pub fn chain(&self) -> ChainKind {
+        match self {
+            #[cfg(feature = "near")]
+            Self::Near(_) => ChainKind::Near,
+            #[cfg(feature = "evm")]
+            Self::Evm(tx) => ChainKind::Evm {
+                chain_id: tx.chain_id,
+            },
+            #[cfg(feature = "bitcoin")]
+            Self::Bitcoin(payload) => ChainKind::Bitcoin {
+                network: payload.network,
+            },
+        }
+    }
+
+    /// Returns the number of signatures the payload needs: one per signed input for Bitcoin,
+    /// one otherwise.
+    pub fn signature_count(&self) -> usize {
+        match self {
+            #[cfg(feature = "bitcoin")]
+            Self::Bitcoin(payload) => payload.inputs.len(),
+            #[allow(unreachable_patterns)]
+            _ => 1,
+        }
+    }
+
+    /// Returns the bytes that must be signed for the signature at `index`, see
+    /// [`Self::signature_count`].
+    ///
+    /// Fails if `index` or the input it signs is out of range, or if the input can't be signed
+    /// from this payload: Taproot sighashes commit to every spent output, so P2TR inputs must
+    /// be signed with
+    /// [`BitcoinTransaction::build_for_signing_taproot`](crate::bitcoin::bitcoin_transaction::BitcoinTransaction::build_for_signing_taproot).
+    pub fn build_for_signing(&self, index: usize) -> Result<Vec<u8>, PayloadBatchError> {
+        if index >= self.signature_count() {
+            return Err(PayloadBatchError::SignatureIndexOutOfRange(index));
+        }
+
+        match self {
+            #[cfg(feature = "near")]
+            Self::Near(tx) => Ok(tx.build_for_signing()),
+            #[cfg(feature = "evm")]
+            Self::Evm(tx) => Ok(tx.build_for_signing()),
+            #[cfg(feature = "bitcoin")]
+            Self::Bitcoin(payload) => {
+                let input = &payload.inputs[index];
+                if input.input_index >= payload.transaction.input.len() {
+                    return Err(PayloadBatchError::InputIndexOutOfRange(input.input_index));
+                }
+
+                match input.tx_type {
+                    TransactionType::P2PKH | TransactionType::P2SH => {
+                        if input.sighash_type.is_single()
+                            && input.input_index >= payload.transaction.output.len()
+                        {
+                            return Err(PayloadBatchError::SighashSingleWithoutOutput(
+                                input.input_index,
+                            ));
+                        }
+                        Ok(payload.transaction.build_for_signing_legacy_input(
+                            input.input_index,
+                            &input.script_code,
+                            input.sighash_type,
+                        ))
+                    }
+                    TransactionType::P2WPKH | TransactionType::P2WSH => {
+                        if payload.transaction.version < Version::TWO {
+                            return Err(PayloadBatchError::UnsupportedVersion);
+                        }
+                        Ok(payload.transaction.build_for_signing_segwit(
+                            input.sighash_type,
+                            input.input_index,
+                            &input.script_code,
+                            input.value,
+                        ))
+                    }
+                    TransactionType::P2TR => {
+                        Err(PayloadBatchError::TaprootInput(input.input_index))
+                    }
+                }
+            }
+        }
+    }
+
+    /// Fails if `signature` can't be used as the signature at `index` of this payload, e.g.
+    /// because it is for another chain.
+    pub fn check_signature(
+        &self,
+        index: usize,
+        signature: &ChainSignature,
+    ) -> Result<(), PayloadBatchError> {
+        if index >= self.signature_count() {
+            return Err(PayloadBatchError::SignatureIndexOutOfRange(index));
+        }
+
+        match (self, signature) {
+            #[cfg(feature = "near")]
+            (Self::Near(_), ChainSignature::Near(_)) => Ok(()),
+            #[cfg(feature = "evm")]
+            (Self::Evm(_), ChainSignature::Evm(_)) => Ok(()),
+            #[cfg(feature = "bitcoin")]
+            (Self::Bitcoin(payload), ChainSignature::Bitcoin(items)) => {
+                match payload.inputs[index].tx_type {
+                    TransactionType::P2PKH | TransactionType::P2SH => {
+                        script_sig_from_pushes(items).map(|_| ())
+                    }
+                    TransactionType::P2WPKH | TransactionType::P2WSH | TransactionType::P2TR => {
+                        Ok(())
+                    }
+                }
+            }
+            #[allow(unreachable_patterns)]
+            _ => Err(PayloadBatchError::ChainMismatch),
+        }
+    }
+
+    /// Returns the finalized transaction bytes for the given signatures, one per
+    /// [`Self::signature_count`] in order.
+    ///
+    /// Fails if a signature is missing or is for another chain than the payload.
+    pub fn build_with_signatures(
+        &self,
+        signatures: &[ChainSignature],
+    ) -> Result<Vec<u8>, PayloadBatchError> {
+        if signatures.len() != self.signature_count() {
+            return Err(PayloadBatchError::WrongSignatureCount {
+                expected: self.signature_count(),
+                actual: signatures.len(),
+            });
+        }
+
+        match (self, signatures) {
+            #[cfg(feature = "near")]
+            (Self::Near(tx), [ChainSignature::Near(signature)]) => {
+                Ok(tx.build_with_signature(signature.clone()))
+            }
+            #[cfg(feature = "evm")]
+            (Self::Evm(tx), [ChainSignature::Evm(signature)]) => {
+                Ok(tx.build_with_signature(signature))
+            }
+            #[cfg(feature = "bitcoin")]
+            (Self::Bitcoin(payload), signatures) => {
+                let mut tx = payload.transaction.clone();
+                for (input, signature) in payload.inputs.iter().zip(signatures) {
+                    let items = match signature {
+                        ChainSignature::Bitcoin(items) => items,
+                        #[allow(unreachable_patterns)]
+                        _ => return Err(PayloadBatchError::ChainMismatch),
+                    };
+                    let txin = tx
+                        .input
+                        .get_mut(input.input_index)
+                        .ok_or(PayloadBatchError::InputIndexOutOfRange(input.input_index))?;
+                    match input.tx_type {
+                        TransactionType::P2PKH | TransactionType::P2SH => {
+                            txin.script_sig = script_sig_from_pushes(items)?;
+                        }
+                        TransactionType::P2WPKH
+                        | TransactionType::P2WSH
+                        | TransactionType::P2TR => {
+                            txin.witness = Witness::from_slice(items);
+                        }
+                    }
+                }
+                Ok(tx.serialize())
+            }
+            #[allow(unreachable_patterns)]
+            _ => Err(PayloadBatchError::ChainMismatch),
+        }
+    }
+}
+
+#[cfg(feature = "near")]
+impl From<NearTransaction> for ChainPayload {
+    fn from(transaction: NearTransaction) -> Self {
+        Self::Near(transaction)
+    }
+}
+
+#[cfg(feature = "evm")]
+impl From<EVMTransaction> for ChainPayload {
+    fn from(transaction: EVMTransaction) -> Self {
+        Self::Evm(transaction)
+    }
+}
+
+#[cfg(feature = "bitcoin")]
+impl From<BitcoinPayload> for ChainPayload {
+    fn from(payload: BitcoinPayload) -> Self {
+        Self::Bitcoin(payload)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+struct BatchEntry {
+    id: PayloadId,
+    payload: ChainPayload,
+    /// One slot per signature needed by the payload, see [`ChainPayload::signature_count`].
+    signatures: Vec<Option<ChainSignature>>,
+}
+
+impl BatchEntry {
+    fn is_signed(&self) -> bool {
+        self.signatures.iter().all(Option::is_some)
+    }
+}
+
+/// A set of signing requests spanning one or more chains.
+///
+/// This is the building block for cross-chain intents, e.g. a swap that needs both an EVM and a
+/// Bitcoin signature before anything can be broadcast. Signatures may be attached in any order;
+/// once every payload is signed the finalized transactions are returned.
+///
+/// A payload needing several signatures, e.g. a Bitcoin transaction with several inputs, is
+/// stored once and its signatures are addressed by their index within the payload.
+///
+/// The batch (including attached signatures) is borsh serializable so it can be persisted in
+/// contract state between the signing callbacks.
+#[derive(Debug, Clone, Default, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+pub struct PayloadBatch {
+    entries: Vec<BatchEntry>,
+    next_id: PayloadId,
+}
+
+impl PayloadBatch {
+    pub const fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+            next_id: 0,
+        }
+    }
+
+    /// Adds a payload to the batch and returns its id.
+    ///
+    /// Fails without modifying the batch if any of its signing payloads can't be built, see
+    /// [`ChainPayload::build_for_signing`].
+    pub fn add(&mut self, payload: ChainPayload) -> Result<PayloadId, PayloadBatchError> {
+        for index in 0..payload.signature_count() {
+            payload.build_for_signing(index)?;
+        }
+
+        let id = self.next_id;
+        self.next_id += 1;
+        self.entries.push(BatchEntry {
+            id,
+            signatures: vec![None; payload.signature_count()],
+            payload,
+        });
+        Ok(id)
+    }
+
+    /// Returns the payload with the given id.
+    pub fn payload(&self, id: PayloadId) -> Option<&ChainPayload> {
+        self.entry(id).map(|entry| &entry.payload)
+    }
+
+    /// Returns the payload id, signature index and signing bytes of every signature still
+    /// missing.
+    ///
+    /// Payloads are checked when added, but a batch deserialized from untrusted state may hold
+    /// payloads that can't be signed, in which case this fails.
+    pub fn pending(&self) -> Result<Vec<(PayloadId, usize, Vec<u8>)>, PayloadBatchError> {
+        let mut pending = Vec::new();
+        for entry in &self.entries {
+            for (index, signature) in entry.signatures.iter().enumerate() {
+                if signature.is_none() {
+                    let payload = entry.payload.build_for_signing(index).map_err(|error| {
+                        PayloadBatchError::InvalidPayload {
+                            id: entry.id,
+                            error: Box::new(error),
+                        }
+                    })?;
+                    pending.push((entry.id, index, payload));
+                }
+            }
+        }
+        Ok(pending)
+    }
+
+    /// Returns the number of payloads in the batch.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if the batch contains no payload.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns `true` if every payload in the batch has all its signatures attached.
+    pub fn is_complete(&self) -> bool {
+        self.entries.iter().all(BatchEntry::is_signed)
+    }
+
+    /// Attaches the signature at `index` of the payload with the given id.
+    ///
+    /// Returns the finalized transaction bytes of every payload, keyed by id, once the last
+    /// missing signature has been attached and `None` otherwise. Attaching a signature to an
+    /// unknown id or index, to an already signed index or of the wrong chain fails without
+    /// modifying the batch, as does attaching the last signature if finalizing fails.
+    pub fn attach(
+        &mut self,
+        id: PayloadId,
+        index: usize,
+        signature: ChainSignature,
+    ) -> Result<Option<BTreeMap<PayloadId, Vec<u8>>>, PayloadBatchError> {
+        let position = self
+            .entries
+            .iter()
+            .position(|entry| entry.id == id)
+            .ok_or(PayloadBatchError::UnknownPayload(id))?;
+        let entry = &mut self.entries[position];
+
+        // Fail early if the signature cannot be used for this payload.
+        entry.payload.check_signature(index, &signature)?;
+
+        let slot = &mut entry.signatures[index];
+        if slot.is_some() {
+            return Err(PayloadBatchError::AlreadyAttached { id, index });
+        }
+        *slot = Some(signature);
+
+        if !self.is_complete() {
+            return Ok(None);
+        }
+        self.finalize().map(Some).inspect_err(|_| {
+            // Leave the slot free so that the signature can be attached again.
+            self.entries[position].signatures[index] = None;
+        })
+    }
+
+    /// Returns the finalized transaction bytes of every payload, keyed by id.
+    pub fn finalize(&self) -> Result<BTreeMap<PayloadId, Vec<u8>>, PayloadBatchError> {
+        self.entries
+            .iter()
+            .map(|entry| {
+                let signatures = entry
+                    .signatures
+                    .iter()
+                    .cloned()
+                    .collect::<Option<Vec<_>>>()
+                    .ok_or(PayloadBatchError::NotSigned(entry.id))?;
+                Ok((entry.id, entry.payload.build_with_signatures(&signatures)?))
+            })
+            .collect()
+    }
+
+    fn entry(&self, id: PayloadId) -> Option<&BatchEntry> {
+        self.entries.iter().find(|entry| entry.id == id)
+    }
+}
+
+/// Builds a script sig pushing each item on the stack.
+#[cfg(feature = "bitcoin")]
+fn script_sig_from_pushes(items: &[Vec<u8>]) -> Result<ScriptBuf, PayloadBatchError> {
+    items
+        .iter()
+        .try_fold(ScriptBuilder::new(), |builder, item| {
+            if item.len() > MAX_SCRIPT_ELEMENT_SIZE {
+                return Err(PayloadBatchError::PushTooLarge(item.len()));
+            }
+            Ok(builder.push_slice(item))
+        })
+        .map(ScriptBuilder::into_script)
+}
+
+#[cfg(all(test, feature = "near", feature = "evm", feature = "bitcoin"))]
+mod tests {
+    use super::*;
+    use crate::bitcoin::types::{
+        Amount, Hash, LockTime, OutPoint, Sequence, TxIn, TxOut, Txid, Version, Witness,
+    };
+    use crate::near::types::{
+        Action, BlockHash, ED25519PublicKey, ED25519Signature, NearToken, PublicKey,
+        TransferAction, U64,
+    };
+
+    fn near_payload() -> ChainPayload {
+        ChainPayload::Near(NearTransaction {
+            signer_id: "alice.near".parse().unwrap(),
+            signer_public_key: PublicKey::ED25519(ED25519PublicKey([1; 32])),
+            nonce: U64(1),
+            receiver_id: "bob.near".parse().unwrap(),
+            block_hash: BlockHash([2; 32]),
+            actions: vec![Action::Transfer(TransferAction {
+                deposit: NearToken::from_yoctonear(1),
+            })],
+        })
+    }
+
+    fn evm_payload() -> ChainPayload {
+        ChainPayload::Evm(EVMTransaction {
+            chain_id: 1,
+            nonce: 0,
+            to: Some([3; 20]),
+            value: 1,
+            input: vec![],
+            gas_limit: 21_000,
+            max_fee_per_gas: 20_000_000_000,
+            max_priority_fee_per_gas: 1_000_000_000,
+            access_list: vec![],
+        })
+    }
+
+    fn bitcoin_payload(tx_types: &[TransactionType]) -> ChainPayload {
+        ChainPayload::Bitcoin(BitcoinPayload {
+            network: Network::Bitcoin,
+            transaction: BitcoinTransaction {
+                version: Version::TWO,
+                lock_time: LockTime::from_height(0).unwrap(),
+                input: (0..tx_types.len() as u32)
+                    .map(|vout| TxIn {
+                        previous_output: OutPoint::new(Txid(Hash::all_zeros()), vout),
+                        script_sig: ScriptBuf::default(),
+                        sequence: Sequence::MAX,
+                        witness: Witness::default(),
+                    })
+                    .collect(),
+                output: vec![TxOut {
+                    value: Amount::from_sat(1000),
+                    script_pubkey: ScriptBuf::default(),
+                }],
+            },
+            inputs: tx_types
+                .iter()
+                .enumerate()
+                .map(|(input_index, &tx_type)| BitcoinInputSpec {
+                    input_index,
+                    tx_type,
+                    sighash_type: EcdsaSighashType::All,
+                    script_code: ScriptBuf::default(),
+                    value: 2000,
+                })
+                .collect(),
+        })
+    }
+
+    fn near_signature() -> ChainSignature {
+        ChainSignature::Near(NearSignature::ED25519(ED25519Signature {
+            r: [4; 32],
+            s: [5; 32],
+        }))
+    }
+
+    fn evm_signature() -> ChainSignature {
+        ChainSignature::Evm(EVMSignature {
+            v: 0,
+            r: vec![6; 32],
+            s: vec![7; 32],
+        })
+    }
+
+    #[test]
+    fn test_attach_in_any_order() {
+        let mut batch = PayloadBatch::new();
+        let near_id = batch.add(near_payload()).unwrap();
+        let evm_id = batch.add(evm_payload()).unwrap();
+        let bitcoin_id = batch
+            .add(bitcoin_payload(&[TransactionType::P2WPKH]))
+            .unwrap();
+
+        assert_eq!(batch.pending().unwrap().len(), 3);
+
+        assert!(batch
+            .attach(
+                bitcoin_id,
+                0,
+                ChainSignature::Bitcoin(vec![vec![8; 71], vec![9; 33]])
+            )
+            .unwrap()
+            .is_none());
+        assert!(batch
+            .attach(near_id, 0, near_signature())
+            .unwrap()
+            .is_none());
+
+        let finalized = batch.attach(evm_id, 0, evm_signature()).unwrap().unwrap();
+
+        assert_eq!(finalized.len(), 3);
+        assert!(batch.is_complete());
+
+        let ChainPayload::Evm(evm_tx) = batch.payload(evm_id).unwrap() else {
+            panic!("Expected an EVM payload");
+        };
+        let ChainSignature::Evm(signature) = evm_signature() else {
+            unreachable!()
+        };
+        assert_eq!(finalized[&evm_id], evm_tx.build_with_signature(&signature));
+    }
+
+    #[test]
+    fn test_attach_errors() {
+        let mut batch = PayloadBatch::new();
+        let near_id = batch.add(near_payload()).unwrap();
+        batch.add(evm_payload()).unwrap();
+
+        assert_eq!(
+            batch.attach(42, 0, near_signature()),
+            Err(PayloadBatchError::UnknownPayload(42))
+        );
+        assert_eq!(
+            batch.attach(near_id, 0, evm_signature()),
+            Err(PayloadBatchError::ChainMismatch)
+        );
+        assert!(batch
+            .pending()
+            .unwrap()
+            .iter()
+            .any(|(id, _, _)| *id == near_id));
+
+        batch.attach(near_id, 0, near_signature()).unwrap();
+        assert_eq!(
+            batch.attach(near_id, 0, near_signature()),
+            Err(PayloadBatchError::AlreadyAttached {
+                id: near_id,
+                index: 0
+            })
+        );
+        assert_eq!(batch.finalize(), Err(PayloadBatchError::NotSigned(1)));
+    }
+
+    #[test]
+    fn test_legacy_bitcoin_signature_goes_to_script_sig() {
+        let mut batch = PayloadBatch::new();
+        let id = batch
+            .add(bitcoin_payload(&[TransactionType::P2PKH]))
+            .unwrap();
+
+        let finalized = batch
+            .attach(
+                id,
+                0,
+                ChainSignature::Bitcoin(vec![vec![1; 71], vec![2; 33]]),
+            )
+            .unwrap()
+            .unwrap();
+
+        let mut expected_script_sig = vec![71];
+        expected_script_sig.extend_from_slice(&[1; 71]);
+        expected_script_sig.push(33);
+        expected_script_sig.extend_from_slice(&[2; 33]);
+
+        let ChainPayload::Bitcoin(payload) = batch.payload(id).unwrap() else {
+            panic!("Expected a Bitcoin payload");
+        };
+        let mut tx = payload.transaction.clone();
+        let expected =
+            tx.build_with_script_sig(0, ScriptBuf(expected_script_sig), TransactionType::P2PKH);
+
+        assert_eq!(finalized[&id], expected);
+    }
+
+    #[test]
+    fn test_bitcoin_inputs_share_the_transaction() {
+        let mut batch = PayloadBatch::new();
+        let id = batch
+            .add(bitcoin_payload(&[
+                TransactionType::P2WPKH,
+                TransactionType::P2PKH,
+            ]))
+            .unwrap();
+
+        let pending = batch.pending().unwrap();
+        assert_eq!(
+            pending
+                .iter()
+                .map(|(id, index, _)| (*id, *index))
+                .collect::<Vec<_>>(),
+            vec![(id, 0), (id, 1)]
+        );
+
+        assert!(batch
+            .attach(
+                id,
+                1,
+                ChainSignature::Bitcoin(vec![vec![1; 71], vec![2; 33]])
+            )
+            .unwrap()
+            .is_none());
+        assert!(batch
+            .attach(id, 1, ChainSignature::Bitcoin(vec![vec![1; 71]]))
+            .is_err());
+        assert!(batch
+            .attach(id, 2, ChainSignature::Bitcoin(vec![vec![1; 71]]))
+            .is_err());
+
+        let finalized = batch
+            .attach(
+                id,
+                0,
+                ChainSignature::Bitcoin(vec![vec![3; 71], vec![4; 33]]),
+            )
+            .unwrap()
+            .unwrap();
+
+        let signed = BitcoinTransaction::from_bytes(&finalized[&id]).unwrap();
+        assert_eq!(
+            signed.input[0].witness,
+            Witness::from_slice(&[vec![3; 71], vec![4; 33]])
+        );
+        assert!(signed.input[1].witness.is_empty());
+        assert_eq!(signed.input[1].script_sig.0.len(), 1 + 71 + 1 + 33);
+    }
+
+    #[test]
+    fn test_legacy_inputs_sign_their_own_preimage() {
+        let script_code =
+            ScriptBuf::from_hex("76a914751e76e8199196d454941c45d1b3a323f1433bd688ac").unwrap();
+        let ChainPayload::Bitcoin(mut payload) =
+            bitcoin_payload(&[TransactionType::P2PKH, TransactionType::P2PKH])
+        else {
+            panic!("Expected a Bitcoin payload");
+        };
+        for input in &mut payload.inputs {
+            input.script_code = script_code.clone();
+        }
+        let tx = payload.transaction.clone();
+        let chain_payload = ChainPayload::Bitcoin(payload.clone());
+
+        let preimages: Vec<_> = (0..2)
+            .map(|index| chain_payload.build_for_signing(index).unwrap())
+            .collect();
+        assert_eq!(
+            preimages[0],
+            tx.build_for_signing_legacy_input(0, &script_code, EcdsaSighashType::All)
+        );
+        assert_eq!(
+            preimages[1],
+            tx.build_for_signing_legacy_input(1, &script_code, EcdsaSighashType::All)
+        );
+        assert_ne!(preimages[0], preimages[1]);
+
+        // SIGHASH_SINGLE without an output at the input index would sign the number 1.
+        payload.inputs[1].sighash_type = EcdsaSighashType::Single;
+        let chain_payload = ChainPayload::Bitcoin(payload);
+        assert!(chain_payload.build_for_signing(1).is_err());
+    }
+
+    #[test]
+    fn test_invalid_stored_payload() {
+        // A payload that `add` would reject, as could be found in deserialized state.
+        let ChainPayload::Bitcoin(mut payload) = bitcoin_payload(&[TransactionType::P2WPKH]) else {
+            unreachable!()
+        };
+        payload.inputs[0].input_index = 5;
+        let mut batch = PayloadBatch {
+            entries: vec![BatchEntry {
+                id: 0,
+                payload: ChainPayload::Bitcoin(payload),
+                signatures: vec![None],
+            }],
+            next_id: 1,
+        };
+        let signature = ChainSignature::Bitcoin(vec![vec![8; 71], vec![9; 33]]);
+
+        assert_eq!(
+            batch.pending(),
+            Err(PayloadBatchError::InvalidPayload {
+                id: 0,
+                error: Box::new(PayloadBatchError::InputIndexOutOfRange(5))
+            })
+        );
+        assert!(batch.attach(0, 0, signature.clone()).is_err());
+        assert!(!batch.is_complete());
+        // The failed attempt did not keep the signature.
+        assert_eq!(
+            batch.attach(0, 0, signature),
+            Err(PayloadBatchError::InputIndexOutOfRange(5))
+        );
+    }
+
+    #[test]
+    fn test_taproot_payload_is_rejected() {
+        let payload = bitcoin_payload(&[TransactionType::P2WPKH, TransactionType::P2TR]);
+
+        assert!(payload.build_for_signing(0).is_ok());
+        assert!(payload.build_for_signing(1).is_err());
+        assert!(payload.build_for_signing(2).is_err());
+
+        let mut batch = PayloadBatch::new();
+        assert!(batch.add(payload).is_err());
+        assert!(batch.is_empty());
+    }
+
+    #[test]
+    fn test_borsh_roundtrip_with_partial_signatures() {
+        let mut batch = PayloadBatch::new();
+        let near_id = batch.add(near_payload()).unwrap();
+        let evm_id = batch.add(evm_payload()).unwrap();
+        batch.attach(near_id, 0, near_signature()).unwrap();
+
+        let serialized = borsh::to_vec(&batch).unwrap();
+        let mut deserialized = PayloadBatch::try_from_slice(&serialized).unwrap();
+
+        assert_eq!(batch, deserialized);
+        assert_eq!(deserialized.pending().unwrap().len(), 1);
+        assert!(deserialized
+            .attach(evm_id, 0, evm_signature())
+            .unwrap()
+            .is_some());
+        assert_eq!(deserialized.add(near_payload()).unwrap(), 2);
+    }
+
+    #[test]
+    fn test_heterogeneous_payloads() {
+        let transactions: Vec<ChainPayload> = vec![
+            near_payload(),
+            evm_payload(),
+            bitcoin_payload(&[TransactionType::P2WPKH]),
+        ];
+
+        let chains: Vec<_> = transactions.iter().map(ChainPayload::chain).collect();
+        assert_eq!(
+            chains,
+            vec![
+                ChainKind::Near,
+                ChainKind::Evm { chain_id: 1 },
+                ChainKind::Bitcoin {
+                    network: Network::Bitcoin
+                }
+            ]
+        );
+
+        let json = serde_json::to_string(&transactions).unwrap();
+        let deserialized: Vec<ChainPayload> = serde_json::from_str(&json).unwrap();
+        assert_eq!(deserialized, transactions);
+
+        let signature = evm_signature();
+        assert!(transactions[1]
+            .build_with_signatures(std::slice::from_ref(&signature))
+            .is_ok());
+        assert!(transactions[0].build_with_signatures(&[signature]).is_err());
+    }
+}
@@ -0,0 +1,112 @@
+//! Minimal protobuf (proto3) encoding, for the Cosmos SIGN_MODE_DIRECT messages and the Tron
+//! raw transactions.
+//!
+//! As proto3 does, fields holding their default value (zero, empty string or bytes) are omitted,
+//! so the output matches the encoding produced by the Cosmos SDK.
+
+/// Wire type of varint fields.
+const WIRE_TYPE_VARINT: u8 = 0;
+/// Wire type of length-delimited fields: strings, bytes and embedded messages.
+const WIRE_TYPE_LEN: u8 = 2;
+
+/// Encodes an unsigned integer as a base 128 varint.
+pub(crate) fn encode_varint(buffer: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        buffer.push((value as u8 & 0x7f) | 0x80);
+        value >>= 7;
+    }
+    buffer.push(value as u8);
+}
+
+fn encode_key(buffer: &mut Vec<u8>, field: u32, wire_type: u8) {
+    encode_varint(buffer, u64::from(field << 3 | u32::from(wire_type)));
+}
+
+/// Encodes a `uint64` (or enum) field, omitted if zero.
+pub(crate) fn encode_uint64_field(buffer: &mut Vec<u8>, field: u32, value: u64) {
+    if value == 0 {
+        return;
+    }
+    encode_key(buffer, field, WIRE_TYPE_VARINT);
+    encode_varint(buffer, value);
+}
+
+/// Encodes an `int64` field, omitted if zero. Negative values take ten bytes, as they are
+/// encoded as their two's complement.
+#[cfg(feature = "tron")]
+pub(crate) fn encode_int64_field(buffer: &mut Vec<u8>, field: u32, value: i64) {
+    encode_uint64_field(buffer, field, value as u64);
+}
+
+/// Encodes a `bytes` field, omitted if empty.
+pub(crate) fn encode_bytes_field(buffer: &mut Vec<u8>, field: u32, value: &[u8]) {
+    if value.is_empty() {
+        return;
+    }
+    encode_message_field(buffer, field, value);
+}
+
+/// Encodes a `string` field, omitted if empty.
+pub(crate) fn encode_string_field(buffer: &mut Vec<u8>, field: u32, value: &str) {
+    encode_bytes_field(buffer, field, value.as_bytes());
+}
+
+/// Encodes an embedded message field. Unlike scalars, it is written even if empty, which is
+/// what distinguishes a present message from an absent one.
+pub(crate) fn encode_message_field(buffer: &mut Vec<u8>, field: u32, message: &[u8]) {
+    encode_key(buffer, field, WIRE_TYPE_LEN);
+    encode_varint(buffer, message.len() as u64);
+    buffer.extend_from_slice(message);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_varint() {
+        for (value, expected) in [
+            (0u64, vec![0x00]),
+            (1, vec![0x01]),
+            (127, vec![0x7f]),
+            (128, vec![0x80, 0x01]),
+            (300, vec![0xac, 0x02]),
+            (
+                u64::MAX,
+                vec![0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x01],
+            ),
+        ] {
+            let mut buffer = vec![];
+            encode_varint(&mut buffer, value);
+            assert_eq!(buffer, expected, "value {}", value);
+        }
+    }
+
+    #[test]
+    fn test_default_values_are_omitted() {
+        let mut buffer = vec![];
+        encode_uint64_field(&mut buffer, 1, 0);
+        encode_string_field(&mut buffer, 2, "");
+        encode_bytes_field(&mut buffer, 3, &[]);
+        assert!(buffer.is_empty());
+
+        encode_message_field(&mut buffer, 4, &[]);
+        encode_uint64_field(&mut buffer, 1, 150);
+        encode_string_field(&mut buffer, 2, "testing");
+        assert_eq!(
+            buffer,
+            [&[0x22, 0x00, 0x08, 0x96, 0x01, 0x12, 0x07][..], b"testing"].concat()
+        );
+    }
+
+    #[cfg(feature = "tron")]
+    #[test]
+    fn test_encode_negative_int64() {
+        let mut buffer = vec![];
+        encode_int64_field(&mut buffer, 1, -1);
+        assert_eq!(
+            buffer,
+            [0x08, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x01]
+        );
+    }
+}
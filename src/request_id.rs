@@ -0,0 +1,73 @@
+//! Deterministic identifiers for signing requests.
+//!
+//! A request id is the SHA-256 hash of a domain tag followed by the borsh encoding of
+//! `(chain, payload_hash, salt)`. Borsh encodes the chain variant with a tag byte, strings and
+//! the salt with a length prefix, and the payload hash with a fixed size, so two different
+//! requests can never be serialized to the same bytes: ids only collide if SHA-256 does. The
+//! domain tag keeps ids apart from any other hash computed over the same data.
+//!
+//! Ids only depend on their inputs, so they are identical in a contract, a relayer or a test.
+use sha2::{Digest, Sha256};
+
+use crate::types::ChainKind;
+
+/// Domain tag prepended to the hashed data, versioned in case the scheme ever changes.
+pub const REQUEST_ID_DOMAIN: &[u8] = b"omni-transaction/request-id/v1";
+
+/// Returns a 32-byte id for a request to sign `payload_hash` on `chain`.
+///
+/// The salt distinguishes requests signing the same payload, e.g. a retry after the first
+/// request expired. It can be empty.
+pub fn request_id(chain: &ChainKind, payload_hash: &[u8; 32], salt: &[u8]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(REQUEST_ID_DOMAIN);
+    hasher.update(borsh::to_vec(&(chain, payload_hash, salt)).expect("Failed to serialize"));
+    hasher.finalize().into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_request_id_is_stable() {
+        let id = request_id(&ChainKind::Evm { chain_id: 1 }, &[0x11; 32], b"salt");
+
+        assert_eq!(
+            hex::encode(id),
+            "552d6e1ab5a71a2c84de4ee2e224b550f337f8fbfa422b8f7785a33c61591a70"
+        );
+    }
+
+    #[test]
+    fn test_request_id_inputs_are_not_ambiguous() {
+        let payload_hash = [0x22; 32];
+
+        // Moving bytes between the chain id and the salt changes the id.
+        assert_ne!(
+            request_id(
+                &ChainKind::Cosmos {
+                    chain_id: "a".to_string()
+                },
+                &payload_hash,
+                b"b"
+            ),
+            request_id(
+                &ChainKind::Cosmos {
+                    chain_id: "ab".to_string()
+                },
+                &payload_hash,
+                b""
+            )
+        );
+        // Chains without parameters are told apart by their tag.
+        assert_ne!(
+            request_id(&ChainKind::Near, &payload_hash, b""),
+            request_id(&ChainKind::Solana, &payload_hash, b"")
+        );
+        assert_ne!(
+            request_id(&ChainKind::Evm { chain_id: 1 }, &payload_hash, b""),
+            request_id(&ChainKind::Evm { chain_id: 10 }, &payload_hash, b"")
+        );
+    }
+}
@@ -0,0 +1,135 @@
+use std::fmt;
+
+#[cfg(any(feature = "evm", feature = "near"))]
+use crate::constants::SECP256K1_HALF_ORDER;
+
+/// Structural problems found when attaching a signature (or witness) to a transaction.
+///
+/// These checks do not verify the signature against the signed payload, they only catch
+/// malformed signer outputs (wrong lengths, invalid encodings, missing sighash bytes, ...)
+/// before the transaction is broadcast.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SignatureError {
+    /// A signature component does not have the expected length.
+    InvalidLength {
+        component: &'static str,
+        expected: usize,
+        actual: usize,
+    },
+    /// A signature component is zero.
+    ZeroComponent(&'static str),
+    /// The recovery id (`v`) is out of range.
+    InvalidRecoveryId(u64),
+    /// The signature scheme does not match the scheme of the signer's public key.
+    SchemeMismatch,
+    /// The signature is not a strict DER encoding (BIP-66).
+    InvalidDer,
+    /// The DER signature is not followed by a sighash type byte.
+    MissingSighashType,
+    /// The trailing sighash type byte is not a standard sighash type.
+    InvalidSighashType(u8),
+    /// The public key is neither a compressed nor (where allowed) an uncompressed SEC1 key.
+    InvalidPublicKey,
+    /// The script sig, witness or list of signatures does not have the expected number of
+    /// items.
+    WrongItemCount { expected: usize, actual: usize },
+    /// The script sig contains something other than data pushes.
+    MalformedScriptSig,
+    /// The signature was attached with the wrong method for the type of the spent output.
+    WrongSpendType,
+    /// The input index is out of range.
+    InputIndexOutOfRange(usize),
+    /// The control block of a Taproot script path spend is malformed.
+    InvalidControlBlock,
+    /// `s` is in the upper half of the curve order, which is malleable and not accepted by
+    /// Ethereum (EIP-2).
+    HighS,
+    /// The redeem (or witness) script is not a standard multisig script.
+    InvalidRedeemScript,
+    /// The public key of a signature is not in the multisig script, or has already signed.
+    UnknownPublicKey,
+    /// The input has neither a script sig nor a witness.
+    UnsignedInput(usize),
+    /// The chain id is too large for an EIP-155 `v` to fit in a `u64`.
+    InvalidChainId(u64),
+}
+
+impl fmt::Display for SignatureError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidLength {
+                component,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "{} should be {} bytes long, got {}",
+                component, expected, actual
+            ),
+            Self::ZeroComponent(component) => write!(f, "{} should not be zero", component),
+            Self::InvalidRecoveryId(v) => write!(f, "invalid recovery id: {}", v),
+            Self::SchemeMismatch => {
+                write!(f, "signature scheme does not match the public key")
+            }
+            Self::InvalidDer => write!(f, "signature is not strictly DER encoded"),
+            Self::MissingSighashType => write!(f, "signature is missing the sighash type byte"),
+            Self::InvalidSighashType(sighash_type) => {
+                write!(f, "invalid sighash type: {:#04x}", sighash_type)
+            }
+            Self::InvalidPublicKey => write!(f, "invalid public key"),
+            Self::WrongItemCount { expected, actual } => {
+                write!(f, "expected {} items, got {}", expected, actual)
+            }
+            Self::MalformedScriptSig => write!(f, "script sig should only contain data pushes"),
+            Self::WrongSpendType => write!(f, "wrong method for the type of the spent output"),
+            Self::InputIndexOutOfRange(index) => write!(f, "input {} does not exist", index),
+            Self::InvalidControlBlock => write!(f, "invalid Taproot control block"),
+            Self::HighS => write!(f, "s should be in the lower half of the curve order"),
+            Self::InvalidRedeemScript => write!(f, "redeem script is not a multisig script"),
+            Self::UnknownPublicKey => {
+                write!(
+                    f,
+                    "public key is not in the multisig script or already signed"
+                )
+            }
+            Self::UnsignedInput(index) => write!(f, "input {} is not signed", index),
+            Self::InvalidChainId(chain_id) => {
+                write!(f, "chain id {} is too large for an EIP-155 v", chain_id)
+            }
+        }
+    }
+}
+
+impl std::error::Error for SignatureError {}
+
+/// Checks the parts of a secp256k1 signature returned by an MPC signer: `r` and `s` must be
+/// non-zero 32-byte big-endian values, `s` must be low-S and the recovery id 0 or 1.
+#[cfg(any(feature = "evm", feature = "near"))]
+pub(crate) fn check_secp256k1_parts(
+    r: &[u8],
+    s: &[u8],
+    recovery_id: u8,
+) -> Result<(), SignatureError> {
+    for (component, value) in [("r", r), ("s", s)] {
+        if value.len() != 32 {
+            return Err(SignatureError::InvalidLength {
+                component,
+                expected: 32,
+                actual: value.len(),
+            });
+        }
+        if value.iter().all(|b| *b == 0) {
+            return Err(SignatureError::ZeroComponent(component));
+        }
+    }
+
+    // Both are big-endian 32-byte values, so they compare like the integers.
+    if s > SECP256K1_HALF_ORDER.as_slice() {
+        return Err(SignatureError::HighS);
+    }
+    if recovery_id > 1 {
+        return Err(SignatureError::InvalidRecoveryId(u64::from(recovery_id)));
+    }
+
+    Ok(())
+}
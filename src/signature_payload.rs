@@ -0,0 +1,443 @@
+//! What an MPC signer needs to know to sign a transaction of any chain.
+//!
+//! Each chain signs the output of `build_for_signing` differently: EVM signs its keccak256
+//! hash with secp256k1, Bitcoin its double SHA-256, Solana and Aptos sign the message itself
+//! with Ed25519, and so on. A [`SignaturePayload`] carries the payload along with the hash
+//! algorithm and the curve, so a signing request can be built the same way for every chain:
+//!
+//! ```ignore
+//! let payload = transaction.signature_payload();
+//! // ... request a `payload.curve` signature of `payload.digest()` from the MPC signer ...
+//! ```
+#[cfg(feature = "sha2")]
+use sha2::{Digest, Sha256};
+#[cfg(feature = "sha3")]
+use sha3::Keccak256;
+
+#[cfg(feature = "aptos")]
+use crate::aptos::aptos_transaction::AptosTransaction;
+#[cfg(feature = "cardano")]
+use crate::cardano::cardano_transaction::CardanoTransaction;
+#[cfg(feature = "cosmos")]
+use crate::cosmos::cosmos_transaction::CosmosTransaction;
+#[cfg(feature = "evm")]
+use crate::evm::{
+    evm_transaction::EVMTransaction, evm_transaction_eip2930::EVMTransactionEIP2930,
+    evm_transaction_eip4844::EVMTransactionEIP4844,
+};
+#[cfg(feature = "near")]
+use crate::near::{near_transaction::NearTransaction, types::PublicKey as NearPublicKey};
+#[cfg(any(feature = "near", feature = "evm", feature = "bitcoin"))]
+use crate::payload_batch::{ChainPayload, PayloadBatchError};
+#[cfg(feature = "solana")]
+use crate::solana::solana_transaction::SolanaTransaction;
+#[cfg(feature = "starknet")]
+use crate::starknet::starknet_transaction::StarknetTransaction;
+#[cfg(feature = "tron")]
+use crate::tron::tron_transaction::TronTransaction;
+use crate::types::ChainKind;
+
+/// How the payload is hashed into the digest that is signed.
+///
+/// The variants don't depend on the enabled features, but computing a digest needs the `sha2`
+/// feature for SHA-256 and the `sha3` feature for Keccak-256, see [`SignaturePayload::digest`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HashAlgorithm {
+    /// The payload is signed as is, e.g. by Ed25519 which hashes the message itself.
+    Raw,
+    Sha256,
+    /// SHA-256 applied twice, as used by Bitcoin.
+    Sha256d,
+    Keccak256,
+}
+
+/// The curve of the key signing the digest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Curve {
+    Secp256k1,
+    Ed25519,
+    /// The STARK curve of Starknet accounts.
+    Stark,
+}
+
+/// A payload to sign, along with how it must be signed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignaturePayload {
+    pub chain: ChainKind,
+    /// The output of `build_for_signing`.
+    pub payload: Vec<u8>,
+    pub hash_algorithm: HashAlgorithm,
+    pub curve: Curve,
+}
+
+impl SignaturePayload {
+    pub const fn new(
+        chain: ChainKind,
+        payload: Vec<u8>,
+        hash_algorithm: HashAlgorithm,
+        curve: Curve,
+    ) -> Self {
+        Self {
+            chain,
+            payload,
+            hash_algorithm,
+            curve,
+        }
+    }
+
+    /// Returns the bytes handed to the signer: the hash of the payload, or the payload itself
+    /// for [`HashAlgorithm::Raw`].
+    ///
+    /// Panics if the feature computing the hash is disabled. Every chain feature enables the
+    /// hash it signs with, so this can't happen for payloads built by this crate.
+    pub fn digest(&self) -> Vec<u8> {
+        match self.hash_algorithm {
+            HashAlgorithm::Raw => self.payload.clone(),
+            #[cfg(feature = "sha2")]
+            HashAlgorithm::Sha256 => Sha256::digest(&self.payload).to_vec(),
+            #[cfg(feature = "sha2")]
+            HashAlgorithm::Sha256d => Sha256::digest(Sha256::digest(&self.payload)).to_vec(),
+            #[cfg(not(feature = "sha2"))]
+            HashAlgorithm::Sha256 | HashAlgorithm::Sha256d => {
+                panic!("SHA-256 digests need the `sha2` feature")
+            }
+            #[cfg(feature = "sha3")]
+            HashAlgorithm::Keccak256 => <Keccak256 as sha3::Digest>::digest(&self.payload).to_vec(),
+            #[cfg(not(feature = "sha3"))]
+            HashAlgorithm::Keccak256 => panic!("Keccak-256 digests need the `sha3` feature"),
+        }
+    }
+
+    /// Returns the 32-byte digest signed with secp256k1, or `None` if the payload is signed
+    /// as is, e.g. an Ed25519 message.
+    pub fn digest_32(&self) -> Option<[u8; 32]> {
+        match self.hash_algorithm {
+            HashAlgorithm::Raw => None,
+            HashAlgorithm::Sha256 | HashAlgorithm::Sha256d | HashAlgorithm::Keccak256 => {
+                self.digest().try_into().ok()
+            }
+        }
+    }
+}
+
+/// A transaction which can describe how it must be signed.
+pub trait ToSignaturePayload {
+    /// Returns the payload to sign, with its hash algorithm and curve.
+    fn signature_payload(&self) -> SignaturePayload;
+}
+
+#[cfg(feature = "near")]
+impl ToSignaturePayload for NearTransaction {
+    /// NEAR signs the SHA-256 hash of the transaction with the curve of the signer key.
+    fn signature_payload(&self) -> SignaturePayload {
+        let curve = match self.signer_public_key {
+            NearPublicKey::ED25519(_) => Curve::Ed25519,
+            NearPublicKey::SECP256K1(_) => Curve::Secp256k1,
+        };
+
+        SignaturePayload::new(
+            ChainKind::Near,
+            self.build_for_signing(),
+            HashAlgorithm::Sha256,
+            curve,
+        )
+    }
+}
+
+#[cfg(feature = "evm")]
+impl ToSignaturePayload for EVMTransaction {
+    fn signature_payload(&self) -> SignaturePayload {
+        SignaturePayload::new(
+            ChainKind::Evm {
+                chain_id: self.chain_id,
+            },
+            self.build_for_signing(),
+            HashAlgorithm::Keccak256,
+            Curve::Secp256k1,
+        )
+    }
+}
+
+#[cfg(feature = "evm")]
+impl ToSignaturePayload for EVMTransactionEIP2930 {
+    fn signature_payload(&self) -> SignaturePayload {
+        SignaturePayload::new(
+            ChainKind::Evm {
+                chain_id: self.chain_id,
+            },
+            self.build_for_signing(),
+            HashAlgorithm::Keccak256,
+            Curve::Secp256k1,
+        )
+    }
+}
+
+#[cfg(feature = "evm")]
+impl ToSignaturePayload for EVMTransactionEIP4844 {
+    fn signature_payload(&self) -> SignaturePayload {
+        SignaturePayload::new(
+            ChainKind::Evm {
+                chain_id: self.chain_id,
+            },
+            self.build_for_signing(),
+            HashAlgorithm::Keccak256,
+            Curve::Secp256k1,
+        )
+    }
+}
+
+#[cfg(feature = "solana")]
+impl ToSignaturePayload for SolanaTransaction {
+    fn signature_payload(&self) -> SignaturePayload {
+        SignaturePayload::new(
+            ChainKind::Solana,
+            self.build_for_signing(),
+            HashAlgorithm::Raw,
+            Curve::Ed25519,
+        )
+    }
+}
+
+#[cfg(feature = "cosmos")]
+impl ToSignaturePayload for CosmosTransaction {
+    fn signature_payload(&self) -> SignaturePayload {
+        SignaturePayload::new(
+            ChainKind::Cosmos {
+                chain_id: self.chain_id.clone(),
+            },
+            self.build_for_signing(),
+            HashAlgorithm::Sha256,
+            Curve::Secp256k1,
+        )
+    }
+}
+
+#[cfg(feature = "aptos")]
+impl ToSignaturePayload for AptosTransaction {
+    fn signature_payload(&self) -> SignaturePayload {
+        SignaturePayload::new(
+            ChainKind::Aptos {
+                chain_id: self.chain_id,
+            },
+            self.build_for_signing(),
+            HashAlgorithm::Raw,
+            Curve::Ed25519,
+        )
+    }
+}
+
+#[cfg(feature = "starknet")]
+impl ToSignaturePayload for StarknetTransaction {
+    /// The transaction hash is signed as is. Accounts using another curve than the STARK one
+    /// (e.g. secp256k1) need a custom payload.
+    fn signature_payload(&self) -> SignaturePayload {
+        SignaturePayload::new(
+            ChainKind::Starknet {
+                chain_id: self
+                    .chain_id
+                    .to_short_string()
+                    .unwrap_or_else(|| self.chain_id.to_hex()),
+            },
+            self.build_for_signing(),
+            HashAlgorithm::Raw,
+            Curve::Stark,
+        )
+    }
+}
+
+#[cfg(feature = "tron")]
+impl ToSignaturePayload for TronTransaction {
+    fn signature_payload(&self) -> SignaturePayload {
+        SignaturePayload::new(
+            ChainKind::Tron,
+            self.build_for_signing(),
+            HashAlgorithm::Sha256,
+            Curve::Secp256k1,
+        )
+    }
+}
+
+#[cfg(feature = "cardano")]
+impl ToSignaturePayload for CardanoTransaction {
+    /// The payload is the body hash, signed as is by Ed25519.
+    fn signature_payload(&self) -> SignaturePayload {
+        SignaturePayload::new(
+            ChainKind::Cardano,
+            self.build_for_signing(),
+            HashAlgorithm::Raw,
+            Curve::Ed25519,
+        )
+    }
+}
+
+#[cfg(any(feature = "near", feature = "evm", feature = "bitcoin"))]
+impl ChainPayload {
+    /// Returns how the signature at `index` of the payload must be signed, see
+    /// [`ChainPayload::signature_count`].
+    ///
+    /// Fails in the same cases as [`ChainPayload::build_for_signing`].
+    pub fn signature_payload(&self, index: usize) -> Result<SignaturePayload, PayloadBatchError> {
+        match self {
+            // Only checks the index, the chain builds the same payload again.
+            #[cfg(feature = "near")]
+            Self::Near(tx) => self
+                .build_for_signing(index)
+                .map(|_| tx.signature_payload()),
+            #[cfg(feature = "evm")]
+            Self::Evm(tx) => self
+                .build_for_signing(index)
+                .map(|_| tx.signature_payload()),
+            // P2TR inputs are rejected by `build_for_signing`: their tagged hash sighash isn't
+            // a double SHA-256.
+            #[cfg(feature = "bitcoin")]
+            Self::Bitcoin(_) => Ok(SignaturePayload::new(
+                self.chain(),
+                self.build_for_signing(index)?,
+                HashAlgorithm::Sha256d,
+                Curve::Secp256k1,
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "sha2")]
+    #[test]
+    fn test_digest() {
+        let payload = |hash_algorithm| {
+            SignaturePayload::new(
+                ChainKind::Solana,
+                b"abc".to_vec(),
+                hash_algorithm,
+                Curve::Secp256k1,
+            )
+        };
+
+        assert_eq!(payload(HashAlgorithm::Raw).digest(), b"abc");
+        assert_eq!(payload(HashAlgorithm::Raw).digest_32(), None);
+        assert_eq!(
+            hex::encode(payload(HashAlgorithm::Sha256).digest()),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        assert_eq!(
+            hex::encode(payload(HashAlgorithm::Sha256d).digest_32().unwrap()),
+            "4f8b42c22dd3729b519ba6f68d2da7cc5b2d606d05daed5ad5128cc03e6c6358"
+        );
+    }
+
+    #[cfg(feature = "evm")]
+    #[test]
+    fn test_evm_signature_payload() {
+        use crate::evm::evm_transaction::EVMTransaction;
+
+        let tx = EVMTransaction {
+            chain_id: 11155111,
+            nonce: 0,
+            to: Some([0x11; 20]),
+            value: 1,
+            input: vec![],
+            gas_limit: 21_000,
+            max_fee_per_gas: 2,
+            max_priority_fee_per_gas: 1,
+            access_list: vec![],
+        };
+        let payload = tx.signature_payload();
+
+        assert_eq!(payload.chain, ChainKind::Evm { chain_id: 11155111 });
+        assert_eq!(payload.curve, Curve::Secp256k1);
+        assert_eq!(payload.payload, tx.build_for_signing());
+        assert_eq!(payload.digest_32(), Some(tx.hash_for_signing()));
+        assert_eq!(ChainPayload::Evm(tx).signature_payload(0), Ok(payload));
+    }
+
+    #[cfg(feature = "solana")]
+    #[test]
+    fn test_solana_signature_payload() {
+        use crate::solana::{
+            solana_transaction::SolanaTransaction,
+            types::{Hash, Pubkey},
+            utils::system_transfer,
+        };
+
+        let from = Pubkey([1u8; 32]);
+        let tx = SolanaTransaction {
+            fee_payer: from,
+            recent_blockhash: Hash([3u8; 32]),
+            instructions: vec![system_transfer(&from, &Pubkey([2u8; 32]), 1)],
+        };
+        let payload = tx.signature_payload();
+
+        assert_eq!(payload.curve, Curve::Ed25519);
+        assert_eq!(payload.digest(), tx.build_for_signing());
+        assert_eq!(payload.digest_32(), None);
+    }
+
+    #[cfg(feature = "cardano")]
+    #[test]
+    fn test_cardano_signature_payload() {
+        let tx = CardanoTransaction {
+            inputs: vec![],
+            outputs: vec![],
+            fee: 170_000,
+            ttl: Some(1_000),
+        };
+        let payload = tx.signature_payload();
+
+        assert_eq!(payload.chain, ChainKind::Cardano);
+        assert_eq!(payload.curve, Curve::Ed25519);
+        assert_eq!(payload.digest(), tx.body_hash());
+    }
+
+    #[cfg(feature = "bitcoin")]
+    #[test]
+    fn test_bitcoin_signature_payload() {
+        use crate::bitcoin::{
+            bitcoin_transaction::BitcoinTransaction,
+            types::{
+                Amount, EcdsaSighashType, Hash, LockTime, Network, OutPoint, ScriptBuf, Sequence,
+                TransactionType, TxIn, TxOut, Txid, Version, Witness,
+            },
+        };
+        use crate::payload_batch::{BitcoinInputSpec, BitcoinPayload};
+
+        let transaction = BitcoinTransaction {
+            version: Version::TWO,
+            lock_time: LockTime::from_height(0).unwrap(),
+            input: vec![TxIn {
+                previous_output: OutPoint::new(Txid(Hash::all_zeros()), 0),
+                script_sig: ScriptBuf::default(),
+                sequence: Sequence::MAX,
+                witness: Witness::default(),
+            }],
+            output: vec![TxOut {
+                value: Amount::from_sat(1000),
+                script_pubkey: ScriptBuf::default(),
+            }],
+        };
+        let payload = |tx_type| {
+            ChainPayload::Bitcoin(BitcoinPayload {
+                network: Network::Bitcoin,
+                transaction: transaction.clone(),
+                inputs: vec![BitcoinInputSpec {
+                    input_index: 0,
+                    tx_type,
+                    sighash_type: EcdsaSighashType::All,
+                    script_code: ScriptBuf::default(),
+                    value: 2000,
+                }],
+            })
+        };
+
+        let p2wpkh = payload(TransactionType::P2WPKH);
+        let signature_payload = p2wpkh.signature_payload(0).unwrap();
+        assert_eq!(signature_payload.hash_algorithm, HashAlgorithm::Sha256d);
+        assert_eq!(
+            signature_payload.payload,
+            p2wpkh.build_for_signing(0).unwrap()
+        );
+        assert!(payload(TransactionType::P2TR).signature_payload(0).is_err());
+    }
+}
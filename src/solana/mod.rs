@@ -0,0 +1,4 @@
+pub mod solana_transaction;
+pub mod solana_transaction_builder;
+pub mod types;
+pub mod utils;
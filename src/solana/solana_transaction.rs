@@ -0,0 +1,328 @@
+use std::fmt;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use super::{
+    types::{Hash, Instruction, Pubkey, Signature},
+    utils::encode_length,
+};
+use crate::canonical_json::{hex_value, CanonicalJson};
+use crate::signature_error::SignatureError;
+
+/// A Solana transaction, serialized as a legacy message.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub struct SolanaTransaction {
+    /// The account paying the fees, always the first signer.
+    pub fee_payer: Pubkey,
+    /// A recent blockhash, the transaction expires about 150 blocks after it.
+    pub recent_blockhash: Hash,
+    pub instructions: Vec<Instruction>,
+}
+
+/// The accounts of the message, in wire order, along with the message header.
+struct CompiledKeys {
+    keys: Vec<Pubkey>,
+    num_required_signatures: u8,
+    num_readonly_signed_accounts: u8,
+    num_readonly_unsigned_accounts: u8,
+}
+
+impl SolanaTransaction {
+    /// Returns the serialized message, which is what each signer signs.
+    pub fn build_for_signing(&self) -> Vec<u8> {
+        let compiled = self.compile_keys();
+        let index_of = |pubkey: &Pubkey| {
+            let index = compiled
+                .keys
+                .iter()
+                .position(|key| key == pubkey)
+                .expect("every account was compiled");
+            u8::try_from(index).expect("a message has at most 256 accounts")
+        };
+
+        let mut buffer = vec![
+            compiled.num_required_signatures,
+            compiled.num_readonly_signed_accounts,
+            compiled.num_readonly_unsigned_accounts,
+        ];
+
+        encode_length(&mut buffer, compiled.keys.len());
+        for key in &compiled.keys {
+            buffer.extend_from_slice(&key.0);
+        }
+
+        buffer.extend_from_slice(&self.recent_blockhash.0);
+
+        encode_length(&mut buffer, self.instructions.len());
+        for instruction in &self.instructions {
+            buffer.push(index_of(&instruction.program_id));
+            encode_length(&mut buffer, instruction.accounts.len());
+            for account in &instruction.accounts {
+                buffer.push(index_of(&account.pubkey));
+            }
+            encode_length(&mut buffer, instruction.data.len());
+            buffer.extend_from_slice(&instruction.data);
+        }
+
+        buffer
+    }
+
+    /// Returns the serialized transaction: the signatures followed by the message.
+    ///
+    /// The signatures must be given in the order of [`Self::signers`], one per signer: any
+    /// other count is rejected, as the network would reject the transaction.
+    pub fn build_with_signature(
+        &self,
+        signatures: &[Signature],
+    ) -> Result<Vec<u8>, SignatureError> {
+        let expected = usize::from(self.compile_keys().num_required_signatures);
+        if signatures.len() != expected {
+            return Err(SignatureError::WrongItemCount {
+                expected,
+                actual: signatures.len(),
+            });
+        }
+
+        let mut buffer = Vec::new();
+
+        encode_length(&mut buffer, signatures.len());
+        for signature in signatures {
+            buffer.extend_from_slice(&signature.0);
+        }
+        buffer.extend_from_slice(&self.build_for_signing());
+
+        Ok(buffer)
+    }
+
+    /// Returns the accounts which have to sign the message, in the order their signatures are
+    /// expected by [`Self::build_with_signature`]. The fee payer is always first.
+    pub fn signers(&self) -> Vec<Pubkey> {
+        let compiled = self.compile_keys();
+        compiled.keys[..usize::from(compiled.num_required_signatures)].to_vec()
+    }
+
+    /// Orders the accounts as the Solana SDK does: the fee payer first, then writable signers,
+    /// read-only signers, writable non-signers and read-only non-signers, each group sorted by
+    /// address. An account used several times gets the union of its permissions.
+    fn compile_keys(&self) -> CompiledKeys {
+        // (pubkey, is_signer, is_writable)
+        let mut metas: Vec<(Pubkey, bool, bool)> = Vec::new();
+        let mut upsert = |pubkey: Pubkey, is_signer: bool, is_writable: bool| match metas
+            .iter_mut()
+            .find(|(key, _, _)| *key == pubkey)
+        {
+            Some(meta) => {
+                meta.1 |= is_signer;
+                meta.2 |= is_writable;
+            }
+            None => metas.push((pubkey, is_signer, is_writable)),
+        };
+
+        for instruction in &self.instructions {
+            upsert(instruction.program_id, false, false);
+            for account in &instruction.accounts {
+                upsert(account.pubkey, account.is_signer, account.is_writable);
+            }
+        }
+
+        metas.retain(|(key, _, _)| *key != self.fee_payer);
+        metas.sort_by_key(|(key, is_signer, is_writable)| (!is_signer, !is_writable, *key));
+
+        let count = |signer: bool, writable: bool| {
+            metas
+                .iter()
+                .filter(|(_, is_signer, is_writable)| {
+                    *is_signer == signer && *is_writable == writable
+                })
+                .count()
+        };
+        let to_u8 = |n: usize| u8::try_from(n).expect("a message has at most 256 accounts");
+
+        CompiledKeys {
+            num_required_signatures: to_u8(1 + count(true, true) + count(true, false)),
+            num_readonly_signed_accounts: to_u8(count(true, false)),
+            num_readonly_unsigned_accounts: to_u8(count(false, false)),
+            keys: std::iter::once(self.fee_payer)
+                .chain(metas.into_iter().map(|(key, _, _)| key))
+                .collect(),
+        }
+    }
+}
+
+/// A one-line summary of the transaction, for logs: the fee payer and the called programs.
+impl fmt::Display for SolanaTransaction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let programs: Vec<String> = self
+            .instructions
+            .iter()
+            .map(|instruction| instruction.program_id.to_string())
+            .collect();
+
+        write!(
+            f,
+            "Solana fee payer {} blockhash {}: calls [{}]",
+            self.fee_payer,
+            self.recent_blockhash,
+            programs.join(", ")
+        )
+    }
+}
+
+impl CanonicalJson for SolanaTransaction {
+    fn canonical_value(&self) -> Value {
+        let instructions: Vec<Value> = self
+            .instructions
+            .iter()
+            .map(|instruction| {
+                let accounts: Vec<Value> = instruction
+                    .accounts
+                    .iter()
+                    .map(|account| {
+                        json!({
+                            "pubkey": hex_value(account.pubkey.0),
+                            "is_signer": account.is_signer,
+                            "is_writable": account.is_writable,
+                        })
+                    })
+                    .collect();
+
+                json!({
+                    "program_id": hex_value(instruction.program_id.0),
+                    "accounts": accounts,
+                    "data": hex_value(&instruction.data),
+                })
+            })
+            .collect();
+
+        json!({
+            "fee_payer": hex_value(self.fee_payer.0),
+            "recent_blockhash": hex_value(self.recent_blockhash.0),
+            "instructions": instructions,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solana::{
+        types::AccountMeta,
+        utils::{system_transfer, SYSTEM_PROGRAM_ID},
+    };
+
+    #[test]
+    fn test_build_for_signing_transfer() {
+        let from = Pubkey([1u8; 32]);
+        let to = Pubkey([2u8; 32]);
+        let tx = SolanaTransaction {
+            fee_payer: from,
+            recent_blockhash: Hash([3u8; 32]),
+            instructions: vec![system_transfer(&from, &to, 1_000_000)],
+        };
+
+        let mut expected = vec![1, 0, 1, 3];
+        expected.extend_from_slice(&[1u8; 32]);
+        expected.extend_from_slice(&[2u8; 32]);
+        expected.extend_from_slice(&SYSTEM_PROGRAM_ID.0);
+        expected.extend_from_slice(&[3u8; 32]);
+        expected.extend_from_slice(&[1, 2, 2, 0, 1, 12, 2, 0, 0, 0]);
+        expected.extend_from_slice(&1_000_000u64.to_le_bytes());
+
+        assert_eq!(tx.build_for_signing(), expected);
+        assert_eq!(tx.signers(), vec![from]);
+    }
+
+    #[test]
+    fn test_account_ordering() {
+        let payer = Pubkey([9u8; 32]);
+        let program = Pubkey([8u8; 32]);
+        let readonly_signer = Pubkey([7u8; 32]);
+        let writable = Pubkey([6u8; 32]);
+        let promoted = Pubkey([5u8; 32]);
+        let tx = SolanaTransaction {
+            fee_payer: payer,
+            recent_blockhash: Hash([0u8; 32]),
+            instructions: vec![
+                Instruction {
+                    program_id: program,
+                    accounts: vec![
+                        AccountMeta::new(writable, false),
+                        AccountMeta::new_readonly(readonly_signer, true),
+                        AccountMeta::new_readonly(promoted, false),
+                    ],
+                    data: vec![],
+                },
+                Instruction {
+                    program_id: program,
+                    accounts: vec![AccountMeta::new(promoted, true)],
+                    data: vec![],
+                },
+            ],
+        };
+
+        let message = tx.build_for_signing();
+
+        // Three signers, one of them read-only, and the program is read-only.
+        assert_eq!(message[..3], [3, 1, 1]);
+        assert_eq!(tx.signers(), vec![payer, promoted, readonly_signer]);
+        let keys: Vec<&[u8]> = message[4..4 + 5 * 32].chunks(32).collect();
+        assert_eq!(
+            keys,
+            vec![
+                &payer.0[..],
+                &promoted.0[..],
+                &readonly_signer.0[..],
+                &writable.0[..],
+                &program.0[..],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_build_with_signature() {
+        let from = Pubkey([1u8; 32]);
+        let tx = SolanaTransaction {
+            fee_payer: from,
+            recent_blockhash: Hash([3u8; 32]),
+            instructions: vec![system_transfer(&from, &Pubkey([2u8; 32]), 1)],
+        };
+
+        let signed = tx.build_with_signature(&[Signature([4u8; 64])]).unwrap();
+
+        assert_eq!(signed[0], 1);
+        assert_eq!(signed[1..65], [4u8; 64]);
+        assert_eq!(signed[65..], tx.build_for_signing());
+
+        assert_eq!(
+            tx.build_with_signature(&[]),
+            Err(SignatureError::WrongItemCount {
+                expected: 1,
+                actual: 0
+            })
+        );
+        assert!(tx
+            .build_with_signature(&[Signature([4u8; 64]), Signature([5u8; 64])])
+            .is_err());
+    }
+
+    #[test]
+    fn test_display_summary() {
+        let from = Pubkey([1u8; 32]);
+        let tx = SolanaTransaction {
+            fee_payer: from,
+            recent_blockhash: Hash([3u8; 32]),
+            instructions: vec![system_transfer(&from, &Pubkey([2u8; 32]), 1_000_000)],
+        };
+
+        assert_eq!(
+            tx.to_string(),
+            format!(
+                "Solana fee payer {} blockhash {}: calls [11111111111111111111111111111111]",
+                from,
+                Hash([3u8; 32])
+            )
+        );
+    }
+}
@@ -0,0 +1,111 @@
+use super::{
+    solana_transaction::SolanaTransaction,
+    types::{Hash, Instruction, Pubkey},
+};
+use crate::transaction_builder::{BuildError, TxBuilder};
+
+pub struct SolanaTransactionBuilder {
+    pub fee_payer: Option<Pubkey>,
+    pub recent_blockhash: Option<Hash>,
+    pub instructions: Option<Vec<Instruction>>,
+}
+
+impl Default for SolanaTransactionBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TxBuilder<SolanaTransaction> for SolanaTransactionBuilder {
+    fn build(&self) -> SolanaTransaction {
+        SolanaTransaction {
+            fee_payer: self.fee_payer.expect("Missing fee payer"),
+            recent_blockhash: self.recent_blockhash.expect("Missing recent blockhash"),
+            instructions: self.instructions.clone().expect("Missing instructions"),
+        }
+    }
+}
+
+impl SolanaTransactionBuilder {
+    /// Same as [`TxBuilder::build`], but returns an error instead of panicking if a mandatory
+    /// field is missing.
+    pub fn try_build(&self) -> Result<SolanaTransaction, BuildError> {
+        Ok(SolanaTransaction {
+            fee_payer: self
+                .fee_payer
+                .ok_or(BuildError::MissingField("fee_payer"))?,
+            recent_blockhash: self
+                .recent_blockhash
+                .ok_or(BuildError::MissingField("recent_blockhash"))?,
+            instructions: self
+                .instructions
+                .clone()
+                .ok_or(BuildError::MissingField("instructions"))?,
+        })
+    }
+
+    pub const fn new() -> Self {
+        Self {
+            fee_payer: None,
+            recent_blockhash: None,
+            instructions: None,
+        }
+    }
+
+    pub const fn fee_payer(mut self, fee_payer: Pubkey) -> Self {
+        self.fee_payer = Some(fee_payer);
+        self
+    }
+
+    pub const fn recent_blockhash(mut self, recent_blockhash: Hash) -> Self {
+        self.recent_blockhash = Some(recent_blockhash);
+        self
+    }
+
+    pub fn instructions(mut self, instructions: Vec<Instruction>) -> Self {
+        self.instructions = Some(instructions);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solana::utils::system_transfer;
+    use crate::transaction_builder::TransactionBuilder;
+    use crate::types::SOLANA;
+
+    #[test]
+    fn test_solana_transaction_builder_typed() {
+        let from: Pubkey = "4Nd1mBQtrMJVYVfKf2PJy9NZUZdTAsp7D4xWLs4gDB4T"
+            .parse()
+            .unwrap();
+        let to = Pubkey([2u8; 32]);
+        let instructions = vec![system_transfer(&from, &to, 42)];
+
+        let tx = TransactionBuilder::new::<SOLANA>()
+            .fee_payer(from)
+            .recent_blockhash(Hash([3u8; 32]))
+            .instructions(instructions.clone())
+            .build();
+
+        assert_eq!(
+            tx,
+            SolanaTransaction {
+                fee_payer: from,
+                recent_blockhash: Hash([3u8; 32]),
+                instructions,
+            }
+        );
+    }
+
+    #[test]
+    fn test_try_build_missing_field() {
+        let result = SolanaTransactionBuilder::new()
+            .fee_payer(Pubkey([1u8; 32]))
+            .instructions(vec![])
+            .try_build();
+
+        assert_eq!(result, Err(BuildError::MissingField("recent_blockhash")));
+    }
+}
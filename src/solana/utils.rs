@@ -0,0 +1,63 @@
+use super::types::{AccountMeta, Instruction, Pubkey};
+
+/// The address of the system program, `11111111111111111111111111111111`.
+pub const SYSTEM_PROGRAM_ID: Pubkey = Pubkey([0u8; 32]);
+
+/// Index of the `Transfer` instruction of the system program.
+const SYSTEM_TRANSFER_INDEX: u32 = 2;
+
+/// Creates a system program instruction transferring `lamports` from `from` to `to`.
+pub fn system_transfer(from: &Pubkey, to: &Pubkey, lamports: u64) -> Instruction {
+    let mut data = SYSTEM_TRANSFER_INDEX.to_le_bytes().to_vec();
+    data.extend_from_slice(&lamports.to_le_bytes());
+
+    Instruction {
+        program_id: SYSTEM_PROGRAM_ID,
+        accounts: vec![AccountMeta::new(*from, true), AccountMeta::new(*to, false)],
+        data,
+    }
+}
+
+/// Encodes a length in the compact-u16 ("shortvec") format used by the wire format.
+pub(crate) fn encode_length(buffer: &mut Vec<u8>, len: usize) {
+    let mut rem = u16::try_from(len).expect("length should fit in a u16");
+    loop {
+        let mut byte = (rem & 0x7f) as u8;
+        rem >>= 7;
+        if rem == 0 {
+            buffer.push(byte);
+            break;
+        }
+        byte |= 0x80;
+        buffer.push(byte);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_length() {
+        for (len, expected) in [
+            (0usize, vec![0x00]),
+            (0x7f, vec![0x7f]),
+            (0x80, vec![0x80, 0x01]),
+            (0x3fff, vec![0xff, 0x7f]),
+            (0x4000, vec![0x80, 0x80, 0x01]),
+            (0xffff, vec![0xff, 0xff, 0x03]),
+        ] {
+            let mut buffer = vec![];
+            encode_length(&mut buffer, len);
+            assert_eq!(buffer, expected, "length {}", len);
+        }
+    }
+
+    #[test]
+    fn test_system_program_id() {
+        assert_eq!(
+            SYSTEM_PROGRAM_ID.to_string(),
+            "11111111111111111111111111111111"
+        );
+    }
+}
@@ -0,0 +1,4 @@
+pub mod starknet_transaction;
+pub mod starknet_transaction_builder;
+pub mod types;
+pub mod utils;
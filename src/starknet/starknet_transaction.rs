@@ -0,0 +1,288 @@
+use std::fmt;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use super::types::{DataAvailabilityMode, Felt, ResourceBounds, ResourceBoundsMapping};
+use super::utils::poseidon_hash_many;
+
+/// The prefix of invoke transaction hashes, the short string `invoke`.
+const INVOKE_PREFIX: Felt = Felt::from_ascii(b"invoke");
+
+const TRANSACTION_VERSION: u64 = 3;
+
+/// A Starknet invoke v3 transaction, executing calls from an account contract.
+///
+/// Starknet accounts are contracts verifying the signature themselves, so the transaction can be
+/// signed by any key the account supports, e.g. a secp256k1 MPC key for an Ethereum-signer
+/// account.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub struct StarknetTransaction {
+    /// The address of the account contract.
+    pub sender_address: Felt,
+    /// The calldata of the `__execute__` entry point of the account, usually built with
+    /// [`encode_calls`](super::utils::encode_calls).
+    pub calldata: Vec<Felt>,
+    pub nonce: Felt,
+    /// E.g. [`SN_MAIN`](super::utils::SN_MAIN).
+    pub chain_id: Felt,
+    pub resource_bounds: ResourceBoundsMapping,
+    /// Paid on top of the fee to be prioritized by the sequencer.
+    pub tip: u64,
+    pub paymaster_data: Vec<Felt>,
+    pub account_deployment_data: Vec<Felt>,
+    pub nonce_data_availability_mode: DataAvailabilityMode,
+    pub fee_data_availability_mode: DataAvailabilityMode,
+}
+
+impl StarknetTransaction {
+    /// Returns the transaction hash, the Poseidon hash of the transaction fields as specified
+    /// by SNIP-8.
+    pub fn transaction_hash(&self) -> Felt {
+        let fee_fields = poseidon_hash_many(&[
+            Felt::from_u64(self.tip),
+            self.resource_bounds.l1_gas.encode(b"L1_GAS"),
+            self.resource_bounds.l2_gas.encode(b"L2_GAS"),
+            self.resource_bounds.l1_data_gas.encode(b"L1_DATA"),
+        ]);
+
+        poseidon_hash_many(&[
+            INVOKE_PREFIX,
+            Felt::from_u64(TRANSACTION_VERSION),
+            self.sender_address,
+            fee_fields,
+            poseidon_hash_many(&self.paymaster_data),
+            self.chain_id,
+            self.nonce,
+            self.data_availability_modes(),
+            poseidon_hash_many(&self.account_deployment_data),
+            poseidon_hash_many(&self.calldata),
+        ])
+    }
+
+    /// Returns the data availability modes packed as `nonce mode (32 bits) | fee mode (32 bits)`.
+    fn data_availability_modes(&self) -> Felt {
+        Felt::from_u64(
+            ((self.nonce_data_availability_mode as u64) << 32)
+                + self.fee_data_availability_mode as u64,
+        )
+    }
+
+    /// Returns the transaction hash in big endian, the message signed by the account key.
+    pub fn build_for_signing(&self) -> Vec<u8> {
+        self.transaction_hash().0.to_vec()
+    }
+
+    /// Returns the signed transaction as the JSON `invoke_transaction` parameter of the
+    /// `starknet_addInvokeTransaction` RPC method.
+    ///
+    /// The layout of `signature` is defined by the account contract, e.g. `[r, s]` for the
+    /// STARK curve, or the low and high halves of `r` and `s` for secp256k1.
+    pub fn build_with_signature(&self, signature: &[Felt]) -> Vec<u8> {
+        serde_json::to_vec(&self.json_value(signature)).expect("JSON values always serialize")
+    }
+
+    fn json_value(&self, signature: &[Felt]) -> Value {
+        json!({
+            "type": "INVOKE",
+            "version": Felt::from_u64(TRANSACTION_VERSION).to_hex(),
+            "sender_address": self.sender_address.to_hex(),
+            "calldata": hex_values(&self.calldata),
+            "signature": hex_values(signature),
+            "nonce": self.nonce.to_hex(),
+            "resource_bounds": {
+                "l1_gas": resource_bounds_value(&self.resource_bounds.l1_gas),
+                "l1_data_gas": resource_bounds_value(&self.resource_bounds.l1_data_gas),
+                "l2_gas": resource_bounds_value(&self.resource_bounds.l2_gas),
+            },
+            "tip": format!("{:#x}", self.tip),
+            "paymaster_data": hex_values(&self.paymaster_data),
+            "account_deployment_data": hex_values(&self.account_deployment_data),
+            "nonce_data_availability_mode": self.nonce_data_availability_mode.as_str(),
+            "fee_data_availability_mode": self.fee_data_availability_mode.as_str(),
+        })
+    }
+}
+
+fn hex_values(values: &[Felt]) -> Vec<String> {
+    values.iter().map(Felt::to_hex).collect()
+}
+
+fn resource_bounds_value(bounds: &ResourceBounds) -> Value {
+    json!({
+        "max_amount": format!("{:#x}", bounds.max_amount),
+        "max_price_per_unit": format!("{:#x}", bounds.max_price_per_unit),
+    })
+}
+
+/// A one-line summary of the transaction, for logs: the account, its nonce and the fee bounds.
+impl fmt::Display for StarknetTransaction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let ResourceBoundsMapping {
+            l1_gas,
+            l1_data_gas,
+            l2_gas,
+        } = &self.resource_bounds;
+
+        write!(
+            f,
+            "Starknet chain {} {} nonce {}: {} calldata felts, l1 gas {} at {} fri, \
+             l1 data gas {} at {} fri, l2 gas {} at {} fri, tip {}",
+            self.chain_id
+                .to_short_string()
+                .unwrap_or_else(|| self.chain_id.to_hex()),
+            self.sender_address,
+            self.nonce,
+            self.calldata.len(),
+            l1_gas.max_amount,
+            l1_gas.max_price_per_unit,
+            l1_data_gas.max_amount,
+            l1_data_gas.max_price_per_unit,
+            l2_gas.max_amount,
+            l2_gas.max_price_per_unit,
+            self.tip
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::starknet::{
+        types::Call,
+        utils::{encode_calls, SN_SEPOLIA},
+    };
+
+    fn transfer_transaction() -> StarknetTransaction {
+        let token = "0x4718f5a0fc34cc1af16a1cdee98ffb20c31f5cd61d6ab07201858f4287c938d"
+            .parse()
+            .unwrap();
+
+        StarknetTransaction {
+            sender_address: Felt::from_u64(0x1234),
+            calldata: encode_calls(&[Call::new(
+                token,
+                "transfer",
+                vec![Felt::from_u64(0x5678), Felt::from_u64(1_000), Felt::ZERO],
+            )]),
+            nonce: Felt::from_u64(7),
+            chain_id: SN_SEPOLIA,
+            resource_bounds: ResourceBoundsMapping {
+                l1_gas: ResourceBounds::new(0, 0x1000),
+                l1_data_gas: ResourceBounds::new(0x100, 0x10),
+                l2_gas: ResourceBounds::new(0x100000, 0x10),
+            },
+            tip: 0,
+            paymaster_data: vec![],
+            account_deployment_data: vec![],
+            nonce_data_availability_mode: DataAvailabilityMode::L1,
+            fee_data_availability_mode: DataAvailabilityMode::L1,
+        }
+    }
+
+    #[test]
+    fn test_transaction_hash_fields() {
+        let tx = transfer_transaction();
+        let hash = tx.transaction_hash();
+
+        assert_eq!(tx.build_for_signing(), hash.0);
+        assert_eq!(
+            hash,
+            poseidon_hash_many(&[
+                Felt::from_short_string("invoke").unwrap(),
+                Felt::from_u64(3),
+                tx.sender_address,
+                poseidon_hash_many(&[
+                    Felt::ZERO,
+                    tx.resource_bounds.l1_gas.encode(b"L1_GAS"),
+                    tx.resource_bounds.l2_gas.encode(b"L2_GAS"),
+                    tx.resource_bounds.l1_data_gas.encode(b"L1_DATA"),
+                ]),
+                poseidon_hash_many(&[]),
+                SN_SEPOLIA,
+                Felt::from_u64(7),
+                Felt::ZERO,
+                poseidon_hash_many(&[]),
+                poseidon_hash_many(&tx.calldata),
+            ])
+        );
+
+        // Every field is committed to.
+        let mut other = tx.clone();
+        other.nonce = Felt::from_u64(8);
+        assert_ne!(other.transaction_hash(), hash);
+        let mut other = tx.clone();
+        other.fee_data_availability_mode = DataAvailabilityMode::L2;
+        assert_ne!(other.transaction_hash(), hash);
+    }
+
+    #[test]
+    fn test_transaction_hash_encodings() {
+        // The felts hashed into the transaction hash, laid out as by `get_concat_resource` and
+        // `concat_data_availability_mode` in StarkWare's `starknet_api::transaction_hash`.
+        let tx = transfer_transaction();
+        let ResourceBoundsMapping {
+            l1_gas,
+            l1_data_gas,
+            l2_gas,
+        } = tx.resource_bounds;
+
+        assert_eq!(INVOKE_PREFIX.to_hex(), "0x696e766f6b65");
+        assert_eq!(
+            l1_gas.encode(b"L1_GAS").to_hex(),
+            "0x4c315f474153000000000000000000000000000000000000000000001000"
+        );
+        assert_eq!(
+            l2_gas.encode(b"L2_GAS").to_hex(),
+            "0x4c325f474153000000000010000000000000000000000000000000000010"
+        );
+        assert_eq!(
+            l1_data_gas.encode(b"L1_DATA").to_hex(),
+            "0x4c315f44415441000000000000010000000000000000000000000000000010"
+        );
+        assert_eq!(tx.data_availability_modes(), Felt::ZERO);
+
+        let mut other = tx.clone();
+        other.nonce_data_availability_mode = DataAvailabilityMode::L2;
+        assert_eq!(other.data_availability_modes().to_hex(), "0x100000000");
+        other.fee_data_availability_mode = DataAvailabilityMode::L2;
+        assert_eq!(other.data_availability_modes().to_hex(), "0x100000001");
+    }
+
+    #[test]
+    fn test_build_with_signature() {
+        let tx = transfer_transaction();
+        let signed = tx.build_with_signature(&[Felt::from_u64(1), Felt::from_u64(2)]);
+        let value: Value = serde_json::from_slice(&signed).unwrap();
+
+        assert_eq!(value["type"], "INVOKE");
+        assert_eq!(value["version"], "0x3");
+        assert_eq!(value["sender_address"], "0x1234");
+        assert_eq!(value["signature"], json!(["0x1", "0x2"]));
+        assert_eq!(value["calldata"][0], "0x1");
+        assert_eq!(
+            value["calldata"][2],
+            "0x83afd3f4caedc6eebf44246fe54e38c95e3179a5ec9ea81740eca5b482d12e"
+        );
+        assert_eq!(
+            value["resource_bounds"]["l2_gas"],
+            json!({ "max_amount": "0x100000", "max_price_per_unit": "0x10" })
+        );
+        assert_eq!(value["nonce_data_availability_mode"], "L1");
+    }
+
+    #[test]
+    fn test_display_summary() {
+        let tx = transfer_transaction();
+
+        assert_eq!(
+            tx.to_string(),
+            format!(
+                "Starknet chain SN_SEPOLIA 0x1234 nonce 0x7: {} calldata felts, l1 gas 0 at 4096 \
+                 fri, l1 data gas 256 at 16 fri, l2 gas 1048576 at 16 fri, tip 0",
+                tx.calldata.len()
+            )
+        );
+    }
+}
@@ -0,0 +1,191 @@
+use super::{
+    starknet_transaction::StarknetTransaction,
+    types::{Call, DataAvailabilityMode, Felt, ResourceBoundsMapping},
+    utils::encode_calls,
+};
+use crate::transaction_builder::{BuildError, TxBuilder};
+
+pub struct StarknetTransactionBuilder {
+    pub sender_address: Option<Felt>,
+    pub calldata: Option<Vec<Felt>>,
+    pub nonce: Option<Felt>,
+    pub chain_id: Option<Felt>,
+    pub resource_bounds: Option<ResourceBoundsMapping>,
+    /// Zero if not set.
+    pub tip: Option<u64>,
+    pub paymaster_data: Vec<Felt>,
+    pub account_deployment_data: Vec<Felt>,
+    pub nonce_data_availability_mode: DataAvailabilityMode,
+    pub fee_data_availability_mode: DataAvailabilityMode,
+}
+
+impl Default for StarknetTransactionBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TxBuilder<StarknetTransaction> for StarknetTransactionBuilder {
+    fn build(&self) -> StarknetTransaction {
+        StarknetTransaction {
+            sender_address: self.sender_address.expect("Missing sender address"),
+            calldata: self.calldata.clone().expect("Missing calldata"),
+            nonce: self.nonce.expect("Missing nonce"),
+            chain_id: self.chain_id.expect("Missing chain ID"),
+            resource_bounds: self.resource_bounds.expect("Missing resource bounds"),
+            tip: self.tip.unwrap_or_default(),
+            paymaster_data: self.paymaster_data.clone(),
+            account_deployment_data: self.account_deployment_data.clone(),
+            nonce_data_availability_mode: self.nonce_data_availability_mode,
+            fee_data_availability_mode: self.fee_data_availability_mode,
+        }
+    }
+}
+
+impl StarknetTransactionBuilder {
+    /// Same as [`TxBuilder::build`], but returns an error instead of panicking if a mandatory
+    /// field is missing.
+    pub fn try_build(&self) -> Result<StarknetTransaction, BuildError> {
+        Ok(StarknetTransaction {
+            sender_address: self
+                .sender_address
+                .ok_or(BuildError::MissingField("sender_address"))?,
+            calldata: self
+                .calldata
+                .clone()
+                .ok_or(BuildError::MissingField("calldata"))?,
+            nonce: self.nonce.ok_or(BuildError::MissingField("nonce"))?,
+            chain_id: self.chain_id.ok_or(BuildError::MissingField("chain_id"))?,
+            resource_bounds: self
+                .resource_bounds
+                .ok_or(BuildError::MissingField("resource_bounds"))?,
+            tip: self.tip.unwrap_or_default(),
+            paymaster_data: self.paymaster_data.clone(),
+            account_deployment_data: self.account_deployment_data.clone(),
+            nonce_data_availability_mode: self.nonce_data_availability_mode,
+            fee_data_availability_mode: self.fee_data_availability_mode,
+        })
+    }
+
+    pub const fn new() -> Self {
+        Self {
+            sender_address: None,
+            calldata: None,
+            nonce: None,
+            chain_id: None,
+            resource_bounds: None,
+            tip: None,
+            paymaster_data: Vec::new(),
+            account_deployment_data: Vec::new(),
+            nonce_data_availability_mode: DataAvailabilityMode::L1,
+            fee_data_availability_mode: DataAvailabilityMode::L1,
+        }
+    }
+
+    pub const fn sender_address(mut self, sender_address: Felt) -> Self {
+        self.sender_address = Some(sender_address);
+        self
+    }
+
+    /// The raw calldata of the `__execute__` entry point of the account.
+    pub fn calldata(mut self, calldata: Vec<Felt>) -> Self {
+        self.calldata = Some(calldata);
+        self
+    }
+
+    /// The calls executed by the account, encoded as its calldata by [`encode_calls`].
+    pub fn calls(self, calls: &[Call]) -> Self {
+        self.calldata(encode_calls(calls))
+    }
+
+    pub const fn nonce(mut self, nonce: Felt) -> Self {
+        self.nonce = Some(nonce);
+        self
+    }
+
+    pub const fn chain_id(mut self, chain_id: Felt) -> Self {
+        self.chain_id = Some(chain_id);
+        self
+    }
+
+    pub const fn resource_bounds(mut self, resource_bounds: ResourceBoundsMapping) -> Self {
+        self.resource_bounds = Some(resource_bounds);
+        self
+    }
+
+    pub const fn tip(mut self, tip: u64) -> Self {
+        self.tip = Some(tip);
+        self
+    }
+
+    pub fn paymaster_data(mut self, paymaster_data: Vec<Felt>) -> Self {
+        self.paymaster_data = paymaster_data;
+        self
+    }
+
+    pub fn account_deployment_data(mut self, account_deployment_data: Vec<Felt>) -> Self {
+        self.account_deployment_data = account_deployment_data;
+        self
+    }
+
+    pub const fn nonce_data_availability_mode(mut self, mode: DataAvailabilityMode) -> Self {
+        self.nonce_data_availability_mode = mode;
+        self
+    }
+
+    pub const fn fee_data_availability_mode(mut self, mode: DataAvailabilityMode) -> Self {
+        self.fee_data_availability_mode = mode;
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::starknet::{types::ResourceBounds, utils::SN_MAIN};
+    use crate::transaction_builder::TransactionBuilder;
+    use crate::types::STARKNET;
+
+    #[test]
+    fn test_build_transaction() {
+        let calls = vec![Call::new(
+            Felt::from_u64(0x100),
+            "transfer",
+            vec![Felt::ONE],
+        )];
+        let resource_bounds = ResourceBoundsMapping {
+            l2_gas: ResourceBounds::new(1_000_000, 10),
+            ..Default::default()
+        };
+
+        let tx = TransactionBuilder::new::<STARKNET>()
+            .sender_address(Felt::from_u64(0x1234))
+            .calls(&calls)
+            .nonce(Felt::ONE)
+            .chain_id(SN_MAIN)
+            .resource_bounds(resource_bounds)
+            .build();
+
+        assert_eq!(tx.calldata, encode_calls(&calls));
+        assert_eq!(tx.tip, 0);
+        assert_eq!(tx.nonce_data_availability_mode, DataAvailabilityMode::L1);
+        assert_eq!(tx.resource_bounds, resource_bounds);
+    }
+
+    #[test]
+    fn test_try_build_missing_field() {
+        let builder = StarknetTransactionBuilder::new()
+            .sender_address(Felt::from_u64(0x1234))
+            .calldata(vec![])
+            .nonce(Felt::ZERO);
+
+        assert_eq!(
+            builder.try_build(),
+            Err(BuildError::MissingField("chain_id"))
+        );
+        assert_eq!(
+            BuildError::MissingField("chain_id").to_string(),
+            "chain_id is mandatory"
+        );
+    }
+}
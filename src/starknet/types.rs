@@ -0,0 +1,349 @@
+use std::{fmt, str::FromStr};
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use serde::{Deserialize, Serialize};
+
+use super::utils::get_selector_from_name;
+
+/// The prime of the STARK field, `2^251 + 17 * 2^192 + 1`, in big endian.
+const FIELD_PRIME: [u8; 32] = {
+    let mut prime = [0u8; 32];
+    prime[0] = 0x08;
+    prime[7] = 0x11;
+    prime[31] = 0x01;
+    prime
+};
+
+/// Errors returned when parsing a [`Felt`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StarknetParseError {
+    /// The string is not valid hex.
+    InvalidHex(String),
+    /// The value is not lower than the field prime.
+    OutOfRange,
+    /// A short string has more than 31 characters.
+    ShortStringTooLong(usize),
+    /// A short string has non-ASCII characters.
+    NonAsciiShortString,
+}
+
+impl fmt::Display for StarknetParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidHex(reason) => write!(f, "invalid hex: {}", reason),
+            Self::OutOfRange => write!(f, "value is not lower than the field prime"),
+            Self::ShortStringTooLong(len) => {
+                write!(f, "short string has {} characters, the limit is 31", len)
+            }
+            Self::NonAsciiShortString => write!(f, "short string is not ASCII"),
+        }
+    }
+}
+
+impl std::error::Error for StarknetParseError {}
+
+/// An element of the STARK field, the word of every Starknet value, in big endian.
+///
+/// It parses from hex with or without the `0x` prefix and displays in its shortest `0x` hex
+/// form, as in JSON-RPC requests.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+    BorshSerialize,
+    BorshDeserialize,
+)]
+pub struct Felt(pub [u8; 32]);
+
+impl Felt {
+    pub const ZERO: Self = Self([0; 32]);
+    pub const ONE: Self = Self::from_u64(1);
+
+    pub const fn from_u64(value: u64) -> Self {
+        Self::from_u128(value as u128)
+    }
+
+    pub const fn from_u128(value: u128) -> Self {
+        let bytes = value.to_be_bytes();
+        let mut felt = [0u8; 32];
+        let mut i = 0;
+        while i < 16 {
+            felt[16 + i] = bytes[i];
+            i += 1;
+        }
+        Self(felt)
+    }
+
+    /// Encodes an ASCII string of at most 31 characters, like Cairo short strings, e.g. the
+    /// `SN_MAIN` chain ID.
+    pub fn from_short_string(value: &str) -> Result<Self, StarknetParseError> {
+        if !value.is_ascii() {
+            return Err(StarknetParseError::NonAsciiShortString);
+        }
+        if value.len() > 31 {
+            return Err(StarknetParseError::ShortStringTooLong(value.len()));
+        }
+
+        Ok(Self::from_ascii(value.as_bytes()))
+    }
+
+    /// Decodes a short string, or returns `None` if the felt is not made of printable ASCII
+    /// characters after its leading zeros.
+    pub fn to_short_string(&self) -> Option<String> {
+        let start = self.0.iter().position(|&byte| byte != 0)?;
+        let bytes = &self.0[start..];
+        if bytes.iter().all(|byte| byte.is_ascii_graphic()) {
+            Some(String::from_utf8_lossy(bytes).into_owned())
+        } else {
+            None
+        }
+    }
+
+    /// Same as [`Self::from_short_string`] for constants, the bytes must be at most 31 ASCII
+    /// characters.
+    pub(crate) const fn from_ascii(value: &[u8]) -> Self {
+        let mut felt = [0u8; 32];
+        let offset = 32 - value.len();
+        let mut i = 0;
+        while i < value.len() {
+            felt[offset + i] = value[i];
+            i += 1;
+        }
+        Self(felt)
+    }
+
+    /// Parses a big endian hex value, with or without the `0x` prefix.
+    pub fn from_hex(value: &str) -> Result<Self, StarknetParseError> {
+        let digits = value.strip_prefix("0x").unwrap_or(value);
+        if digits.is_empty() || digits.len() > 64 {
+            return Err(StarknetParseError::InvalidHex(format!(
+                "{} should have between 1 and 64 digits",
+                value
+            )));
+        }
+
+        let padded = format!("{:0>64}", digits);
+        let mut felt = [0u8; 32];
+        hex::decode_to_slice(&padded, &mut felt)
+            .map_err(|e| StarknetParseError::InvalidHex(e.to_string()))?;
+
+        if felt >= FIELD_PRIME {
+            return Err(StarknetParseError::OutOfRange);
+        }
+
+        Ok(Self(felt))
+    }
+
+    /// Returns the shortest `0x` hex form, e.g. `0x0` or `0x1a`.
+    pub fn to_hex(&self) -> String {
+        let digits = hex::encode(self.0);
+        let digits = digits.trim_start_matches('0');
+        if digits.is_empty() {
+            "0x0".to_string()
+        } else {
+            format!("0x{}", digits)
+        }
+    }
+}
+
+impl From<u64> for Felt {
+    fn from(value: u64) -> Self {
+        Self::from_u64(value)
+    }
+}
+
+impl From<u128> for Felt {
+    fn from(value: u128) -> Self {
+        Self::from_u128(value)
+    }
+}
+
+impl FromStr for Felt {
+    type Err = StarknetParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_hex(s)
+    }
+}
+
+impl fmt::Display for Felt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_hex())
+    }
+}
+
+/// The limits of a resource the transaction may consume.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    BorshSerialize,
+    BorshDeserialize,
+)]
+pub struct ResourceBounds {
+    /// The maximum amount of the resource.
+    pub max_amount: u64,
+    /// The maximum price of a unit of the resource, in fri (10^-18 STRK).
+    pub max_price_per_unit: u128,
+}
+
+impl ResourceBounds {
+    pub const fn new(max_amount: u64, max_price_per_unit: u128) -> Self {
+        Self {
+            max_amount,
+            max_price_per_unit,
+        }
+    }
+
+    /// Returns the felt hashed in the transaction hash: the resource name, the maximum amount
+    /// and the maximum price packed as `name (60 bits) | max_amount (64) | max_price (128)`.
+    pub(crate) fn encode(&self, name: &[u8]) -> Felt {
+        let mut felt = [0u8; 32];
+        felt[8 - name.len()..8].copy_from_slice(name);
+        felt[8..16].copy_from_slice(&self.max_amount.to_be_bytes());
+        felt[16..].copy_from_slice(&self.max_price_per_unit.to_be_bytes());
+        Felt(felt)
+    }
+}
+
+/// The limits of every resource, as required by v3 transactions since Starknet 0.13.4.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    BorshSerialize,
+    BorshDeserialize,
+)]
+pub struct ResourceBoundsMapping {
+    pub l1_gas: ResourceBounds,
+    pub l1_data_gas: ResourceBounds,
+    pub l2_gas: ResourceBounds,
+}
+
+/// Where the nonce or the fee of an account is stored.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    BorshSerialize,
+    BorshDeserialize,
+)]
+#[borsh(use_discriminant = true)]
+pub enum DataAvailabilityMode {
+    #[default]
+    L1 = 0,
+    L2 = 1,
+}
+
+impl DataAvailabilityMode {
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::L1 => "L1",
+            Self::L2 => "L2",
+        }
+    }
+}
+
+/// A call of a contract entry point, executed by the account.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub struct Call {
+    pub to: Felt,
+    /// The selector of the entry point, see [`get_selector_from_name`].
+    pub selector: Felt,
+    pub calldata: Vec<Felt>,
+}
+
+impl Call {
+    /// Creates a call of the entry point named `entry_point`, e.g. `transfer`.
+    pub fn new(to: Felt, entry_point: &str, calldata: Vec<Felt>) -> Self {
+        Self {
+            to,
+            selector: get_selector_from_name(entry_point),
+            calldata,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_felt_hex() {
+        let felt: Felt = "0x49d36570d4e46f48e99674bd3fcc84644ddd6b96f7c741b1562b82f9e004dc7"
+            .parse()
+            .unwrap();
+
+        assert_eq!(felt.0[0], 0x04);
+        assert_eq!(
+            felt.to_string(),
+            "0x49d36570d4e46f48e99674bd3fcc84644ddd6b96f7c741b1562b82f9e004dc7"
+        );
+        assert_eq!(Felt::from_hex("1a"), Ok(Felt::from_u64(26)));
+        assert_eq!(Felt::ZERO.to_hex(), "0x0");
+
+        assert_eq!(
+            Felt::from_hex("0x0800000000000011000000000000000000000000000000000000000000000001"),
+            Err(StarknetParseError::OutOfRange)
+        );
+        assert!(Felt::from_hex("0x").is_err());
+        assert!(Felt::from_hex("0xzz").is_err());
+    }
+
+    #[test]
+    fn test_short_string() {
+        assert_eq!(
+            Felt::from_short_string("SN_MAIN").unwrap().to_hex(),
+            "0x534e5f4d41494e"
+        );
+        assert_eq!(
+            Felt::from_short_string("SN_MAIN")
+                .unwrap()
+                .to_short_string(),
+            Some("SN_MAIN".to_string())
+        );
+        assert_eq!(Felt::from_u64(0x1234).to_short_string(), None);
+        assert_eq!(Felt::ZERO.to_short_string(), None);
+        assert_eq!(
+            Felt::from_short_string(&"a".repeat(32)),
+            Err(StarknetParseError::ShortStringTooLong(32))
+        );
+        assert_eq!(
+            Felt::from_short_string("é"),
+            Err(StarknetParseError::NonAsciiShortString)
+        );
+    }
+
+    #[test]
+    fn test_resource_bounds_encoding() {
+        let bounds = ResourceBounds::new(0x10, 0x20);
+
+        assert_eq!(
+            bounds.encode(b"L1_GAS").to_hex(),
+            "0x4c315f474153000000000000001000000000000000000000000000000020"
+        );
+    }
+}
@@ -0,0 +1,98 @@
+//! Hashes and encodings of Starknet values.
+//!
+//! Selectors are computed with `starknet_keccak`, a Keccak-256 truncated to 250 bits, and
+//! transaction hashes with the Poseidon hash of the STARK field, from `starknet-crypto`.
+use sha3::{Digest, Keccak256};
+
+use super::types::{Call, Felt};
+
+/// The chain ID of Starknet mainnet, the short string `SN_MAIN`.
+pub const SN_MAIN: Felt = Felt::from_ascii(b"SN_MAIN");
+
+/// The chain ID of the Starknet Sepolia testnet, the short string `SN_SEPOLIA`.
+pub const SN_SEPOLIA: Felt = Felt::from_ascii(b"SN_SEPOLIA");
+
+/// Returns the Keccak-256 hash of `data`, keeping its 250 least significant bits so it fits in
+/// a felt.
+pub fn starknet_keccak(data: &[u8]) -> Felt {
+    let mut hash: [u8; 32] = Keccak256::digest(data).into();
+    hash[0] &= 0x03;
+    Felt(hash)
+}
+
+/// Returns the selector of the entry point named `name`, e.g. `transfer`.
+pub fn get_selector_from_name(name: &str) -> Felt {
+    starknet_keccak(name.as_bytes())
+}
+
+/// Returns the Poseidon hash of a sequence of felts.
+pub fn poseidon_hash_many(values: &[Felt]) -> Felt {
+    let values: Vec<starknet_crypto::Felt> = values
+        .iter()
+        .map(|value| starknet_crypto::Felt::from_bytes_be(&value.0))
+        .collect();
+
+    Felt(starknet_crypto::poseidon_hash_many(&values).to_bytes_be())
+}
+
+/// Encodes calls as the calldata of the `__execute__` entry point of Cairo 1 accounts: the
+/// number of calls, then for each call its contract, selector, calldata length and calldata.
+pub fn encode_calls(calls: &[Call]) -> Vec<Felt> {
+    let mut calldata = vec![Felt::from_u64(calls.len() as u64)];
+
+    for call in calls {
+        calldata.push(call.to);
+        calldata.push(call.selector);
+        calldata.push(Felt::from_u64(call.calldata.len() as u64));
+        calldata.extend_from_slice(&call.calldata);
+    }
+
+    calldata
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_selectors() {
+        assert_eq!(
+            get_selector_from_name("transfer").to_hex(),
+            "0x83afd3f4caedc6eebf44246fe54e38c95e3179a5ec9ea81740eca5b482d12e"
+        );
+        assert_eq!(
+            get_selector_from_name("__execute__").to_hex(),
+            "0x15d40a3d6ca2ac30f4031e42be28da9b056fef9bb7357ac5e85627ee876e5ad"
+        );
+    }
+
+    #[test]
+    fn test_chain_ids() {
+        assert_eq!(SN_MAIN, Felt::from_short_string("SN_MAIN").unwrap());
+        assert_eq!(SN_SEPOLIA.to_hex(), "0x534e5f5345504f4c4941");
+    }
+
+    #[test]
+    fn test_encode_calls() {
+        let token = Felt::from_u64(0x100);
+        let calls = vec![
+            Call::new(token, "approve", vec![Felt::from_u64(2), Felt::from_u64(3)]),
+            Call::new(token, "transfer", vec![]),
+        ];
+
+        assert_eq!(
+            encode_calls(&calls),
+            vec![
+                Felt::from_u64(2),
+                token,
+                get_selector_from_name("approve"),
+                Felt::from_u64(2),
+                Felt::from_u64(2),
+                Felt::from_u64(3),
+                token,
+                get_selector_from_name("transfer"),
+                Felt::ZERO,
+            ]
+        );
+    }
+}
@@ -1,3 +1,23 @@
+use std::fmt;
+
+/// Errors returned by the `try_build` of the builders whose only failure is a field left
+/// unset, i.e. every chain but NEAR and EVM which also validate their fields.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BuildError {
+    /// A mandatory field was not set.
+    MissingField(&'static str),
+}
+
+impl fmt::Display for BuildError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingField(field) => write!(f, "{} is mandatory", field),
+        }
+    }
+}
+
+impl std::error::Error for BuildError {}
+
 pub trait TxBuilder<T> {
     fn build(&self) -> T;
 }
@@ -19,8 +39,8 @@ mod tests {
 
     use super::{TransactionBuilder as OmniTransactionBuilder, TxBuilder};
     use crate::near::types::{
-        Action as OmniAction, BlockHash, PublicKey as OmniPublicKey,
-        TransferAction as OmniTransferAction, U128,
+        Action as OmniAction, BlockHash, NearToken, PublicKey as OmniPublicKey,
+        TransferAction as OmniTransferAction,
     };
     use crate::{
         evm::utils::parse_eth_address,
@@ -44,7 +64,9 @@ mod tests {
         let nonce = 0;
         let receiver_id: &str = "bob.near";
         let block_hash = BlockHash([0u8; 32]);
-        let transfer_action = OmniAction::Transfer(OmniTransferAction { deposit: U128(1) });
+        let transfer_action = OmniAction::Transfer(OmniTransferAction {
+            deposit: NearToken::from_yoctonear(1),
+        });
         let omni_actions = vec![transfer_action];
         let actions = Action::Transfer(TransferAction { deposit: 1u128 });
 
@@ -0,0 +1,3 @@
+pub mod tron_transaction;
+pub mod tron_transaction_builder;
+pub mod types;
@@ -0,0 +1,213 @@
+use std::fmt;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use super::types::{TronContract, TronSignature};
+use crate::protobuf::{
+    encode_bytes_field, encode_int64_field, encode_message_field, encode_uint64_field,
+};
+
+/// A Tron transaction executing a single contract, e.g. a TRX transfer or a TRC-20 call.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub struct TronTransaction {
+    /// Bytes 6 and 7 of the reference block number, in big endian.
+    pub ref_block_bytes: [u8; 2],
+    /// Bytes 8 to 15 of the reference block ID.
+    pub ref_block_hash: [u8; 8],
+    /// The transaction is discarded if it is not included before this Unix timestamp, in
+    /// milliseconds.
+    pub expiration: i64,
+    /// The creation time of the transaction, in milliseconds.
+    pub timestamp: i64,
+    pub contract: TronContract,
+    /// The maximum amount of TRX burnt for energy, in sun, or 0 for none. Only used by smart
+    /// contract calls.
+    pub fee_limit: i64,
+    /// A memo, stored along with the transaction.
+    pub data: Vec<u8>,
+}
+
+impl TronTransaction {
+    /// Returns the encoded `Transaction.raw`.
+    pub fn raw_data(&self) -> Vec<u8> {
+        let mut contract = Vec::new();
+        encode_uint64_field(&mut contract, 1, self.contract.contract_type());
+        encode_message_field(&mut contract, 2, &self.contract.encode_parameter());
+
+        let mut buffer = Vec::new();
+        encode_bytes_field(&mut buffer, 1, &self.ref_block_bytes);
+        encode_bytes_field(&mut buffer, 4, &self.ref_block_hash);
+        encode_int64_field(&mut buffer, 8, self.expiration);
+        encode_bytes_field(&mut buffer, 10, &self.data);
+        encode_message_field(&mut buffer, 11, &contract);
+        encode_int64_field(&mut buffer, 14, self.timestamp);
+        encode_int64_field(&mut buffer, 18, self.fee_limit);
+        buffer
+    }
+
+    /// Returns the encoded raw data. Its SHA-256 hash, the transaction ID, is what the signer
+    /// signs.
+    pub fn build_for_signing(&self) -> Vec<u8> {
+        self.raw_data()
+    }
+
+    /// Returns the transaction ID, the SHA-256 hash of the raw data.
+    pub fn tx_id(&self) -> [u8; 32] {
+        Sha256::digest(self.raw_data()).into()
+    }
+
+    /// Returns the encoded `Transaction`, ready to be broadcast in hex to the
+    /// `/wallet/broadcasthex` endpoint of a node.
+    pub fn build_with_signature(&self, signature: &TronSignature) -> Vec<u8> {
+        let mut buffer = Vec::new();
+        encode_message_field(&mut buffer, 1, &self.raw_data());
+        encode_bytes_field(&mut buffer, 2, &signature.0);
+        buffer
+    }
+}
+
+/// A one-line summary of the transaction, for logs: the contract it executes and the TRX it
+/// moves.
+impl fmt::Display for TronTransaction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.contract {
+            TronContract::Transfer(transfer) => write!(
+                f,
+                "Tron transfer {} -> {}: {} sun",
+                transfer.owner_address, transfer.to_address, transfer.amount
+            )?,
+            TronContract::TriggerSmartContract(call) => write!(
+                f,
+                "Tron call {} -> {}: {} sun, {} bytes of data",
+                call.owner_address,
+                call.contract_address,
+                call.call_value,
+                call.data.len()
+            )?,
+        }
+        write!(
+            f,
+            ", fee limit {} sun, expires at {} ms",
+            self.fee_limit, self.expiration
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tron::types::{TransferContract, TriggerSmartContract, TronAddress};
+
+    // The expected encodings below were produced by `prost` from the message definitions of
+    // java-tron's `Tron.proto`, `balance_contract.proto` and `smart_contract.proto`.
+    const TRANSFER_RAW_DATA: &str = "0a025b3c2208e8c3a1f07d2b495640e0a499ffbc315a67080112630a2d74\
+        7970652e676f6f676c65617069732e636f6d2f70726f746f636f6c2e5472616e73666572436f6e7472616374\
+        12320a15415cbdd86a2fa8dc4bddd8a8f69dba48572eec07fb12154174472e7d35395a6b5add427eecb7f4b6\
+        2ad2b07118c0843d7080d095ffbc31";
+    const TRIGGER_RAW_DATA: &str = "0a025b3c2208e8c3a1f07d2b495640e0a499ffbc315aae01081f12a9010a\
+        31747970652e676f6f676c65617069732e636f6d2f70726f746f636f6c2e54726967676572536d617274436f\
+        6e747261637412740a15415cbdd86a2fa8dc4bddd8a8f69dba48572eec07fb121541a614f803b6fd780986a4\
+        2c78ec9c7f77e6ded13c2244a9059cbb00000000000000000000000074472e7d35395a6b5add427eecb7f4b6\
+        2ad2b07100000000000000000000000000000000000000000000000000000000009896807080d095ffbc3190\
+        0180c2d72f";
+
+    fn owner() -> TronAddress {
+        "TJRabPrwbZy45sbavfcjinPJC18kjpRTv8".parse().unwrap()
+    }
+
+    fn recipient() -> TronAddress {
+        "TLa2f6VPqDgRE67v1736s7bJ8Ray5wYjU7".parse().unwrap()
+    }
+
+    fn transaction(contract: TronContract, fee_limit: i64) -> TronTransaction {
+        TronTransaction {
+            ref_block_bytes: [0x5b, 0x3c],
+            ref_block_hash: [0xe8, 0xc3, 0xa1, 0xf0, 0x7d, 0x2b, 0x49, 0x56],
+            expiration: 1_700_000_060_000,
+            timestamp: 1_700_000_000_000,
+            contract,
+            fee_limit,
+            data: vec![],
+        }
+    }
+
+    fn transfer_transaction() -> TronTransaction {
+        let transfer = TransferContract {
+            owner_address: owner(),
+            to_address: recipient(),
+            amount: 1_000_000,
+        };
+        transaction(transfer.into(), 0)
+    }
+
+    /// A transfer of 10 USDT to the recipient.
+    fn trc20_transfer_transaction() -> TronTransaction {
+        let mut data = hex::decode("a9059cbb").unwrap();
+        data.extend_from_slice(&[0; 12]);
+        data.extend_from_slice(&recipient().evm_address());
+        data.extend_from_slice(&[0; 28]);
+        data.extend_from_slice(&10_000_000u32.to_be_bytes());
+
+        let call = TriggerSmartContract {
+            owner_address: owner(),
+            contract_address: "TR7NHqjeKQxGTCi8q8ZY4pL8otSzgjLj6t".parse().unwrap(),
+            call_value: 0,
+            data,
+            call_token_value: 0,
+            token_id: 0,
+        };
+        transaction(call.into(), 100_000_000)
+    }
+
+    #[test]
+    fn test_transfer_raw_data() {
+        let tx = transfer_transaction();
+
+        assert_eq!(hex::encode(tx.raw_data()), TRANSFER_RAW_DATA);
+        assert_eq!(tx.build_for_signing(), tx.raw_data());
+        assert_eq!(
+            hex::encode(tx.tx_id()),
+            "67fbc863b2776472e399c739edd9025fdfd13cf70b6ef090fef06b25750d764e"
+        );
+    }
+
+    #[test]
+    fn test_trigger_smart_contract_raw_data() {
+        let tx = trc20_transfer_transaction();
+
+        assert_eq!(hex::encode(tx.raw_data()), TRIGGER_RAW_DATA);
+        assert_eq!(
+            hex::encode(tx.tx_id()),
+            "773a5b3f1442967b637c62161b148731d20fe700b0f4d08ebc70491557fe8b6e"
+        );
+    }
+
+    #[test]
+    fn test_build_with_signature() {
+        let signature = TronSignature::from_rsv(&[1; 32], &[2; 32], 1);
+        let signature_field = format!("1241{}{}1c", "01".repeat(32), "02".repeat(32));
+
+        assert_eq!(
+            hex::encode(transfer_transaction().build_with_signature(&signature)),
+            format!("0a8501{}{}", TRANSFER_RAW_DATA, signature_field)
+        );
+        assert_eq!(
+            hex::encode(trc20_transfer_transaction().build_with_signature(&signature)),
+            format!("0ad301{}{}", TRIGGER_RAW_DATA, signature_field)
+        );
+    }
+
+    #[test]
+    fn test_display_summary() {
+        let tx = transfer_transaction();
+
+        assert_eq!(
+            tx.to_string(),
+            "Tron transfer TJRabPrwbZy45sbavfcjinPJC18kjpRTv8 -> \
+             TLa2f6VPqDgRE67v1736s7bJ8Ray5wYjU7: 1000000 sun, fee limit 0 sun, expires at \
+             1700000060000 ms"
+        );
+    }
+}
@@ -0,0 +1,162 @@
+use super::{tron_transaction::TronTransaction, types::TronContract};
+use crate::transaction_builder::{BuildError, TxBuilder};
+
+pub struct TronTransactionBuilder {
+    pub ref_block_bytes: Option<[u8; 2]>,
+    pub ref_block_hash: Option<[u8; 8]>,
+    pub expiration: Option<i64>,
+    pub timestamp: Option<i64>,
+    pub contract: Option<TronContract>,
+    /// Zero if not set.
+    pub fee_limit: Option<i64>,
+    pub data: Vec<u8>,
+}
+
+impl Default for TronTransactionBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TxBuilder<TronTransaction> for TronTransactionBuilder {
+    fn build(&self) -> TronTransaction {
+        TronTransaction {
+            ref_block_bytes: self.ref_block_bytes.expect("Missing reference block"),
+            ref_block_hash: self.ref_block_hash.expect("Missing reference block"),
+            expiration: self.expiration.expect("Missing expiration"),
+            timestamp: self.timestamp.expect("Missing timestamp"),
+            contract: self.contract.clone().expect("Missing contract"),
+            fee_limit: self.fee_limit.unwrap_or_default(),
+            data: self.data.clone(),
+        }
+    }
+}
+
+impl TronTransactionBuilder {
+    /// Same as [`TxBuilder::build`], but returns an error instead of panicking if a mandatory
+    /// field is missing.
+    pub fn try_build(&self) -> Result<TronTransaction, BuildError> {
+        Ok(TronTransaction {
+            ref_block_bytes: self
+                .ref_block_bytes
+                .ok_or(BuildError::MissingField("ref_block_bytes"))?,
+            ref_block_hash: self
+                .ref_block_hash
+                .ok_or(BuildError::MissingField("ref_block_hash"))?,
+            expiration: self
+                .expiration
+                .ok_or(BuildError::MissingField("expiration"))?,
+            timestamp: self
+                .timestamp
+                .ok_or(BuildError::MissingField("timestamp"))?,
+            contract: self
+                .contract
+                .clone()
+                .ok_or(BuildError::MissingField("contract"))?,
+            fee_limit: self.fee_limit.unwrap_or_default(),
+            data: self.data.clone(),
+        })
+    }
+
+    pub const fn new() -> Self {
+        Self {
+            ref_block_bytes: None,
+            ref_block_hash: None,
+            expiration: None,
+            timestamp: None,
+            contract: None,
+            fee_limit: None,
+            data: Vec::new(),
+        }
+    }
+
+    /// Sets the reference block from its number and ID, usually the latest block, as returned
+    /// by the `/wallet/getnowblock` endpoint. The transaction is only valid on the chain
+    /// containing this block.
+    pub fn reference_block(mut self, block_number: u64, block_id: &[u8; 32]) -> Self {
+        let number = block_number.to_be_bytes();
+        self.ref_block_bytes = Some([number[6], number[7]]);
+        self.ref_block_hash = Some(
+            block_id[8..16]
+                .try_into()
+                .expect("the slice is 8 bytes long"),
+        );
+        self
+    }
+
+    pub const fn expiration(mut self, expiration: i64) -> Self {
+        self.expiration = Some(expiration);
+        self
+    }
+
+    pub const fn timestamp(mut self, timestamp: i64) -> Self {
+        self.timestamp = Some(timestamp);
+        self
+    }
+
+    pub fn contract(mut self, contract: impl Into<TronContract>) -> Self {
+        self.contract = Some(contract.into());
+        self
+    }
+
+    pub const fn fee_limit(mut self, fee_limit: i64) -> Self {
+        self.fee_limit = Some(fee_limit);
+        self
+    }
+
+    /// A memo, stored along with the transaction.
+    pub fn data(mut self, data: Vec<u8>) -> Self {
+        self.data = data;
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transaction_builder::TransactionBuilder;
+    use crate::tron::types::{TriggerSmartContract, TronAddress};
+    use crate::types::TRON;
+
+    #[test]
+    fn test_build_transaction() {
+        let mut block_id = [0u8; 32];
+        block_id[..8].copy_from_slice(&58_000_123u64.to_be_bytes());
+        block_id[8..16].copy_from_slice(&[7; 8]);
+        let contract = TriggerSmartContract {
+            owner_address: TronAddress([0x41; 21]),
+            contract_address: TronAddress([0x41; 21]),
+            call_value: 0,
+            data: vec![0xa9, 0x05, 0x9c, 0xbb],
+            call_token_value: 0,
+            token_id: 0,
+        };
+
+        let tx = TransactionBuilder::new::<TRON>()
+            .reference_block(58_000_123, &block_id)
+            .expiration(1_700_000_060_000)
+            .timestamp(1_700_000_000_000)
+            .contract(contract.clone())
+            .fee_limit(100_000_000)
+            .build();
+
+        assert_eq!(tx.ref_block_bytes, [0x02, 0xfb]);
+        assert_eq!(tx.ref_block_hash, [7; 8]);
+        assert_eq!(tx.contract, TronContract::TriggerSmartContract(contract));
+        assert_eq!(tx.fee_limit, 100_000_000);
+        assert!(tx.data.is_empty());
+    }
+
+    #[test]
+    fn test_try_build_missing_field() {
+        let builder = TronTransactionBuilder::new()
+            .reference_block(1, &[0; 32])
+            .expiration(1)
+            .timestamp(0);
+
+        assert_eq!(
+            builder.try_build(),
+            Err(BuildError::MissingField("contract"))
+        );
+    }
+}
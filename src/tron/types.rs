@@ -0,0 +1,322 @@
+use std::{fmt, str::FromStr};
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use serde::{Deserialize, Serialize};
+use serde_big_array::BigArray;
+use sha2::{Digest, Sha256};
+
+use crate::protobuf::{encode_bytes_field, encode_int64_field, encode_string_field};
+
+/// The first byte of mainnet addresses, which makes their base58 form start with `T`.
+pub const TRON_ADDRESS_PREFIX: u8 = 0x41;
+/// Length of an address, the prefix followed by the 20-byte account hash.
+pub const TRON_ADDRESS_LENGTH: usize = 21;
+/// Length of a signature in the `r || s || v` form.
+pub const TRON_SIGNATURE_LENGTH: usize = 65;
+
+/// Type URL of `protocol.TransferContract`.
+pub const TRANSFER_CONTRACT_TYPE_URL: &str = "type.googleapis.com/protocol.TransferContract";
+/// Type URL of `protocol.TriggerSmartContract`.
+pub const TRIGGER_SMART_CONTRACT_TYPE_URL: &str =
+    "type.googleapis.com/protocol.TriggerSmartContract";
+
+/// Errors returned when parsing a [`TronAddress`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TronParseError {
+    /// The address is neither valid base58 nor valid hex.
+    InvalidEncoding(String),
+    /// The base58 checksum does not match.
+    InvalidChecksum,
+    /// The decoded address is not 21 bytes long.
+    InvalidLength(usize),
+    /// The address does not start with [`TRON_ADDRESS_PREFIX`].
+    InvalidPrefix(u8),
+}
+
+impl fmt::Display for TronParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidEncoding(reason) => write!(f, "invalid address: {}", reason),
+            Self::InvalidChecksum => write!(f, "invalid address checksum"),
+            Self::InvalidLength(len) => write!(
+                f,
+                "address is {} bytes long, expected {}",
+                len, TRON_ADDRESS_LENGTH
+            ),
+            Self::InvalidPrefix(prefix) => write!(
+                f,
+                "address starts with {:#04x}, expected {:#04x}",
+                prefix, TRON_ADDRESS_PREFIX
+            ),
+        }
+    }
+}
+
+impl std::error::Error for TronParseError {}
+
+/// A Tron address, the [`TRON_ADDRESS_PREFIX`] followed by the last 20 bytes of the Keccak-256
+/// hash of the public key, as for Ethereum.
+///
+/// It parses from base58check (`T...`) or from hex (`41...`), and displays in base58check.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+    BorshSerialize,
+    BorshDeserialize,
+)]
+pub struct TronAddress(pub [u8; TRON_ADDRESS_LENGTH]);
+
+impl TronAddress {
+    /// Returns the Tron address of the same key as the Ethereum address `address`.
+    pub fn from_evm_address(address: [u8; 20]) -> Self {
+        let mut bytes = [TRON_ADDRESS_PREFIX; TRON_ADDRESS_LENGTH];
+        bytes[1..].copy_from_slice(&address);
+        Self(bytes)
+    }
+
+    /// Returns the 20-byte account hash, the address used by the TVM in ABI encoded calldata.
+    pub fn evm_address(&self) -> [u8; 20] {
+        self.0[1..].try_into().expect("addresses are 21 bytes long")
+    }
+
+    pub fn to_base58(&self) -> String {
+        let checksum = Sha256::digest(Sha256::digest(self.0));
+        let mut bytes = self.0.to_vec();
+        bytes.extend_from_slice(&checksum[..4]);
+        bs58::encode(bytes).into_string()
+    }
+
+    pub fn from_base58(address: &str) -> Result<Self, TronParseError> {
+        let bytes = bs58::decode(address)
+            .into_vec()
+            .map_err(|e| TronParseError::InvalidEncoding(e.to_string()))?;
+        if bytes.len() != TRON_ADDRESS_LENGTH + 4 {
+            return Err(TronParseError::InvalidLength(bytes.len().saturating_sub(4)));
+        }
+
+        let (payload, checksum) = bytes.split_at(TRON_ADDRESS_LENGTH);
+        if Sha256::digest(Sha256::digest(payload))[..4] != *checksum {
+            return Err(TronParseError::InvalidChecksum);
+        }
+
+        Self::from_bytes(payload)
+    }
+
+    pub fn from_hex(address: &str) -> Result<Self, TronParseError> {
+        let bytes = hex::decode(address.strip_prefix("0x").unwrap_or(address))
+            .map_err(|e| TronParseError::InvalidEncoding(e.to_string()))?;
+        Self::from_bytes(&bytes)
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Result<Self, TronParseError> {
+        let bytes: [u8; TRON_ADDRESS_LENGTH] = bytes
+            .try_into()
+            .map_err(|_| TronParseError::InvalidLength(bytes.len()))?;
+        if bytes[0] != TRON_ADDRESS_PREFIX {
+            return Err(TronParseError::InvalidPrefix(bytes[0]));
+        }
+        Ok(Self(bytes))
+    }
+}
+
+impl FromStr for TronAddress {
+    type Err = TronParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.starts_with('T') {
+            Self::from_base58(s)
+        } else {
+            Self::from_hex(s)
+        }
+    }
+}
+
+impl fmt::Display for TronAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_base58())
+    }
+}
+
+/// A secp256k1 signature of the transaction ID, as `r || s || v` with `v` being 27 or 28.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize,
+)]
+pub struct TronSignature(#[serde(with = "BigArray")] pub [u8; TRON_SIGNATURE_LENGTH]);
+
+impl TronSignature {
+    /// Creates a signature from its components, `recovery_id` being 0 or 1 as returned by the
+    /// MPC signer.
+    pub fn from_rsv(r: &[u8; 32], s: &[u8; 32], recovery_id: u8) -> Self {
+        let mut bytes = [0u8; TRON_SIGNATURE_LENGTH];
+        bytes[..32].copy_from_slice(r);
+        bytes[32..64].copy_from_slice(s);
+        bytes[64] = recovery_id + 27;
+        Self(bytes)
+    }
+}
+
+/// A TRX transfer (`protocol.TransferContract`).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub struct TransferContract {
+    /// The sender, which must be the signer.
+    pub owner_address: TronAddress,
+    pub to_address: TronAddress,
+    /// The amount, in sun (10^-6 TRX).
+    pub amount: i64,
+}
+
+impl TransferContract {
+    pub(crate) fn encode(&self) -> Vec<u8> {
+        let mut buffer = Vec::new();
+        encode_bytes_field(&mut buffer, 1, &self.owner_address.0);
+        encode_bytes_field(&mut buffer, 2, &self.to_address.0);
+        encode_int64_field(&mut buffer, 3, self.amount);
+        buffer
+    }
+}
+
+/// A smart contract call (`protocol.TriggerSmartContract`), e.g. a TRC-20 transfer.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub struct TriggerSmartContract {
+    /// The caller, which must be the signer.
+    pub owner_address: TronAddress,
+    pub contract_address: TronAddress,
+    /// The TRX sent along with the call, in sun.
+    pub call_value: i64,
+    /// The ABI encoded call, as for the EVM.
+    pub data: Vec<u8>,
+    /// The amount of the TRC-10 token `token_id` sent along with the call.
+    pub call_token_value: i64,
+    pub token_id: i64,
+}
+
+impl TriggerSmartContract {
+    pub(crate) fn encode(&self) -> Vec<u8> {
+        let mut buffer = Vec::new();
+        encode_bytes_field(&mut buffer, 1, &self.owner_address.0);
+        encode_bytes_field(&mut buffer, 2, &self.contract_address.0);
+        encode_int64_field(&mut buffer, 3, self.call_value);
+        encode_bytes_field(&mut buffer, 4, &self.data);
+        encode_int64_field(&mut buffer, 5, self.call_token_value);
+        encode_int64_field(&mut buffer, 6, self.token_id);
+        buffer
+    }
+}
+
+/// The contract executed by a transaction.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub enum TronContract {
+    Transfer(TransferContract),
+    TriggerSmartContract(TriggerSmartContract),
+}
+
+impl TronContract {
+    /// Returns the `ContractType` of the contract.
+    pub const fn contract_type(&self) -> u64 {
+        match self {
+            Self::Transfer(_) => 1,
+            Self::TriggerSmartContract(_) => 31,
+        }
+    }
+
+    /// Returns the encoded `google.protobuf.Any` wrapping the contract.
+    pub(crate) fn encode_parameter(&self) -> Vec<u8> {
+        let (type_url, value) = match self {
+            Self::Transfer(contract) => (TRANSFER_CONTRACT_TYPE_URL, contract.encode()),
+            Self::TriggerSmartContract(contract) => {
+                (TRIGGER_SMART_CONTRACT_TYPE_URL, contract.encode())
+            }
+        };
+
+        let mut buffer = Vec::new();
+        encode_string_field(&mut buffer, 1, type_url);
+        encode_bytes_field(&mut buffer, 2, &value);
+        buffer
+    }
+}
+
+impl From<TransferContract> for TronContract {
+    fn from(contract: TransferContract) -> Self {
+        Self::Transfer(contract)
+    }
+}
+
+impl From<TriggerSmartContract> for TronContract {
+    fn from(contract: TriggerSmartContract) -> Self {
+        Self::TriggerSmartContract(contract)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_address_encodings() {
+        let address: TronAddress = "TNPeeaaFB7K9cmo4uQpcU32zGK8G1NYqeL".parse().unwrap();
+
+        assert_eq!(
+            hex::encode(address.0),
+            "418840e6c55b9ada326d211d818c34a994aeced808"
+        );
+        assert_eq!(
+            "418840E6C55B9ADA326D211D818C34A994AECED808".parse(),
+            Ok(address)
+        );
+        assert_eq!(address.to_string(), "TNPeeaaFB7K9cmo4uQpcU32zGK8G1NYqeL");
+        assert_eq!(
+            TronAddress::from_evm_address(address.evm_address()),
+            address
+        );
+    }
+
+    #[test]
+    fn test_invalid_addresses() {
+        assert_eq!(
+            TronAddress::from_base58("TNPeeaaFB7K9cmo4uQpcU32zGK8G1NYqeM"),
+            Err(TronParseError::InvalidChecksum)
+        );
+        assert_eq!(
+            TronAddress::from_hex("428840e6c55b9ada326d211d818c34a994aeced808"),
+            Err(TronParseError::InvalidPrefix(0x42))
+        );
+        assert_eq!(
+            TronAddress::from_hex("8840e6c55b9ada326d211d818c34a994aeced808"),
+            Err(TronParseError::InvalidLength(20))
+        );
+    }
+
+    #[test]
+    fn test_contract_parameter() {
+        let contract = TronContract::from(TransferContract {
+            owner_address: TronAddress([0x41; 21]),
+            to_address: TronAddress([0x41; 21]),
+            amount: 1_000_000,
+        });
+
+        let parameter = contract.encode_parameter();
+
+        assert_eq!(contract.contract_type(), 1);
+        assert_eq!(parameter[0], 0x0a);
+        assert_eq!(parameter[1] as usize, TRANSFER_CONTRACT_TYPE_URL.len());
+        assert_eq!(
+            parameter[2 + TRANSFER_CONTRACT_TYPE_URL.len()..],
+            [
+                &[0x12, 50, 0x0a, 21][..],
+                &[0x41; 21],
+                &[0x12, 21],
+                &[0x41; 21],
+                &[0x18, 0xc0, 0x84, 0x3d],
+            ]
+            .concat()
+        );
+    }
+}
@@ -7,6 +7,24 @@ use crate::evm::evm_transaction_builder::EVMTransactionBuilder;
 #[cfg(feature = "near")]
 use crate::near::near_transaction_builder::NearTransactionBuilder;
 
+#[cfg(feature = "solana")]
+use crate::solana::solana_transaction_builder::SolanaTransactionBuilder;
+
+#[cfg(feature = "cosmos")]
+use crate::cosmos::cosmos_transaction_builder::CosmosTransactionBuilder;
+
+#[cfg(feature = "aptos")]
+use crate::aptos::aptos_transaction_builder::AptosTransactionBuilder;
+
+#[cfg(feature = "starknet")]
+use crate::starknet::starknet_transaction_builder::StarknetTransactionBuilder;
+
+#[cfg(feature = "tron")]
+use crate::tron::tron_transaction_builder::TronTransactionBuilder;
+
+#[cfg(feature = "cardano")]
+use crate::cardano::cardano_transaction_builder::CardanoTransactionBuilder;
+
 #[cfg(feature = "near")]
 pub type NEAR = NearTransactionBuilder;
 
@@ -15,3 +33,84 @@ pub type EVM = EVMTransactionBuilder;
 
 #[cfg(feature = "bitcoin")]
 pub type BITCOIN = BitcoinTransactionBuilder;
+
+#[cfg(feature = "solana")]
+pub type SOLANA = SolanaTransactionBuilder;
+
+#[cfg(feature = "cosmos")]
+pub type COSMOS = CosmosTransactionBuilder;
+
+#[cfg(feature = "aptos")]
+pub type APTOS = AptosTransactionBuilder;
+
+#[cfg(feature = "starknet")]
+pub type STARKNET = StarknetTransactionBuilder;
+
+#[cfg(feature = "tron")]
+pub type TRON = TronTransactionBuilder;
+
+#[cfg(feature = "cardano")]
+pub type CARDANO = CardanoTransactionBuilder;
+
+/// Identifies the chain a transaction or signing request is for.
+///
+/// New variants must only be appended: the borsh encoding of this enum is part of
+/// stable identifiers such as [`request_id`](crate::request_id::request_id).
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    Hash,
+    serde::Serialize,
+    serde::Deserialize,
+    borsh::BorshSerialize,
+    borsh::BorshDeserialize,
+)]
+pub enum ChainKind {
+    Near,
+    Evm {
+        chain_id: u64,
+    },
+    Bitcoin {
+        network: BitcoinNetwork,
+    },
+    Solana,
+    Cosmos {
+        chain_id: String,
+    },
+    Aptos {
+        chain_id: u8,
+    },
+    /// The chain ID as a short string, e.g. `SN_MAIN`, or in hex if it is not one.
+    Starknet {
+        chain_id: String,
+    },
+    Tron,
+    Cardano,
+}
+
+/// The Bitcoin network a key, an address or a transaction belongs to.
+///
+/// Defined here so [`ChainKind`] does not depend on the `bitcoin` feature, and re-exported as
+/// `bitcoin::types::Network`.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+    serde::Serialize,
+    serde::Deserialize,
+    borsh::BorshSerialize,
+    borsh::BorshDeserialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum BitcoinNetwork {
+    /// Mainnet.
+    Bitcoin,
+    Testnet,
+    Signet,
+    Regtest,
+}
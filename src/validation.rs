@@ -0,0 +1,114 @@
+//! Shared machinery for validators reporting every problem they find.
+//!
+//! Validators return all the [`ValidationIssue`]s of a value instead of stopping at the first
+//! one, so a relayer can show a complete report (or log the warnings and only reject on errors).
+//!
+//! [`Validate`] covers the content of an unsigned transaction, checked before it is signed.
+//! Checks of the signature being attached are not validators: the `try_build_with_*` methods
+//! (e.g. the EVM `r`/`s`/`v` checks, or the Bitcoin DER signature and public key checks) reject
+//! a malformed signature with a `SignatureError` at the first problem, since there is nothing
+//! left to report once a signature is unusable.
+use std::fmt;
+
+/// How serious a validation issue is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    /// The value is valid, but probably not what was intended (e.g. it will not be relayed by
+    /// default policies, or wastes fees).
+    Warning,
+    /// The value is invalid and would be rejected by the network.
+    Error,
+}
+
+/// A problem found while validating a value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationIssue {
+    pub severity: Severity,
+    /// The field the issue is about, e.g. `output[1].value`.
+    pub field: String,
+    pub message: String,
+}
+
+impl ValidationIssue {
+    pub fn error(field: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Error,
+            field: field.into(),
+            message: message.into(),
+        }
+    }
+
+    pub fn warning(field: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Warning,
+            field: field.into(),
+            message: message.into(),
+        }
+    }
+
+    pub fn is_error(&self) -> bool {
+        self.severity == Severity::Error
+    }
+}
+
+impl fmt::Display for ValidationIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let severity = match self.severity {
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        };
+        write!(f, "{}: {}: {}", severity, self.field, self.message)
+    }
+}
+
+/// A value which can be checked for problems before it is signed.
+pub trait Validate {
+    /// Returns every issue found, or an empty vector if there is none.
+    fn validate(&self) -> Vec<ValidationIssue>;
+
+    /// Returns `true` if no issue with [`Severity::Error`] was found.
+    fn is_valid(&self) -> bool {
+        !has_errors(&self.validate())
+    }
+}
+
+/// Returns `true` if any of the issues is an error.
+pub fn has_errors(issues: &[ValidationIssue]) -> bool {
+    issues.iter().any(ValidationIssue::is_error)
+}
+
+/// Turns issues into a result: the warnings if there is no error, or only the errors otherwise,
+/// so callers can fail on them with `?`.
+pub fn into_result(
+    issues: Vec<ValidationIssue>,
+) -> Result<Vec<ValidationIssue>, Vec<ValidationIssue>> {
+    if has_errors(&issues) {
+        Err(issues
+            .into_iter()
+            .filter(ValidationIssue::is_error)
+            .collect())
+    } else {
+        Ok(issues)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_into_result() {
+        let warning = ValidationIssue::warning("version", "non-standard version");
+        let error = ValidationIssue::error("output", "no outputs");
+
+        assert_eq!(
+            into_result(vec![warning.clone()]),
+            Ok(vec![warning.clone()])
+        );
+        assert_eq!(
+            into_result(vec![warning, error.clone()]),
+            Err(vec![error.clone()])
+        );
+        assert_eq!(error.to_string(), "error: output: no outputs");
+    }
+}
@@ -0,0 +1,631 @@
+//! Versioned envelope for transactions persisted in contract storage.
+//!
+//! Storing a bare transaction ties the stored bytes to the current layout of its type, so any
+//! field addition would make previously stored state undecodable. Wrapping it in
+//! [`VersionedOmniTransaction`] prefixes the bytes with an explicit version tag (and a chain
+//! tag), which lets future layouts be added as new versions next to the existing ones.
+use std::fmt;
+use std::io::{self, Read, Write};
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "bitcoin")]
+use crate::bitcoin::bitcoin_transaction::BitcoinTransaction;
+#[cfg(feature = "evm")]
+use crate::evm::{
+    evm_transaction::EVMTransaction, evm_transaction_eip2930::EVMTransactionEIP2930,
+    evm_transaction_eip4844::EVMTransactionEIP4844,
+};
+#[cfg(feature = "near")]
+use crate::near::{near_transaction::NearTransaction, near_transaction_v1::NearTransactionV1};
+use crate::payload_batch::ChainSignature;
+
+/// Borsh tag of [`VersionedOmniTransaction::V1`].
+const VERSION_1: u8 = 1;
+
+// Borsh chain tags of `OmniTransactionV1`. They are fixed so the encoding does not depend on the
+// enabled features.
+#[cfg(feature = "near")]
+const NEAR_TAG: u8 = 0;
+#[cfg(feature = "evm")]
+const EVM_TAG: u8 = 1;
+#[cfg(feature = "bitcoin")]
+const BITCOIN_TAG: u8 = 2;
+#[cfg(feature = "evm")]
+const EVM_EIP2930_TAG: u8 = 3;
+#[cfg(feature = "evm")]
+const EVM_EIP4844_TAG: u8 = 4;
+#[cfg(feature = "near")]
+const NEAR_V1_TAG: u8 = 5;
+
+/// Errors returned when migrating or signing an [`OmniTransaction`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OmniTransactionError {
+    /// The bytes are not a valid borsh encoding of the unversioned transaction.
+    InvalidEncoding(String),
+    /// The transaction is a Bitcoin transaction, whose inputs are each signed over their own
+    /// sighash.
+    SignedPerInput,
+    /// The signature is for another chain than the transaction.
+    ChainMismatch,
+}
+
+impl fmt::Display for OmniTransactionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidEncoding(reason) => write!(f, "invalid encoding: {}", reason),
+            Self::SignedPerInput => write!(
+                f,
+                "Bitcoin transactions are signed per input, use a BitcoinPayload instead"
+            ),
+            Self::ChainMismatch => write!(f, "signature does not match the transaction chain"),
+        }
+    }
+}
+
+impl std::error::Error for OmniTransactionError {}
+
+/// A transaction tagged with the version of its layout, recommended for contract storage.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "version")]
+pub enum VersionedOmniTransaction {
+    #[serde(rename = "1")]
+    V1(OmniTransactionV1),
+}
+
+/// The first layout of a persisted transaction, holding the transaction types as they were
+/// when versioning was introduced.
+///
+/// The wrapped types are the live ones, so their borsh layouts are pinned by golden tests: a
+/// change to any of them must come with a new version rather than altering this one. New
+/// transaction types are appended with their own chain tag, which keeps stored bytes readable.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OmniTransactionV1 {
+    #[cfg(feature = "near")]
+    Near(NearTransaction),
+    #[cfg(feature = "evm")]
+    Evm(EVMTransaction),
+    #[cfg(feature = "bitcoin")]
+    Bitcoin(BitcoinTransaction),
+    #[cfg(feature = "evm")]
+    EvmEip2930(EVMTransactionEIP2930),
+    #[cfg(feature = "evm")]
+    EvmEip4844(EVMTransactionEIP4844),
+    #[cfg(feature = "near")]
+    NearV1(NearTransactionV1),
+}
+
+/// A transaction of any enabled chain, in the latest layout.
+///
+/// Lets a contract keep heterogeneous pending transactions in a single collection and sign them
+/// without chain-specific branches:
+///
+/// ```ignore
+/// let payload = pending_transaction.build_for_signing()?;
+/// // ... request a signature for `payload` from the MPC signer ...
+/// let signed_transaction = pending_transaction.build_with_signature(&signature)?;
+/// ```
+///
+/// Store it wrapped in a [`VersionedOmniTransaction`].
+pub type OmniTransaction = OmniTransactionV1;
+
+impl VersionedOmniTransaction {
+    /// Migrates a transaction stored without an envelope, i.e. the plain borsh encoding of
+    /// `T`, to the current version.
+    ///
+    /// ```ignore
+    /// let versioned = VersionedOmniTransaction::migrate_unversioned::<NearTransaction>(&bytes)?;
+    /// ```
+    pub fn migrate_unversioned<T>(bytes: &[u8]) -> Result<Self, OmniTransactionError>
+    where
+        T: BorshDeserialize + Into<OmniTransactionV1>,
+    {
+        let transaction = T::try_from_slice(bytes)
+            .map_err(|e| OmniTransactionError::InvalidEncoding(e.to_string()))?;
+        Ok(Self::V1(transaction.into()))
+    }
+
+    /// Upgrades the transaction to the latest layout.
+    pub fn into_latest(self) -> OmniTransactionV1 {
+        match self {
+            Self::V1(transaction) => transaction,
+        }
+    }
+}
+
+impl OmniTransactionV1 {
+    /// Returns the bytes that must be signed.
+    ///
+    /// Fails for Bitcoin, whose inputs are each signed over their own sighash: use a
+    /// [`BitcoinPayload`](crate::payload_batch::BitcoinPayload) to describe the signed inputs.
+    pub fn build_for_signing(&self) -> Result<Vec<u8>, OmniTransactionError> {
+        match self {
+            #[cfg(feature = "near")]
+            Self::Near(tx) => Ok(tx.build_for_signing()),
+            #[cfg(feature = "near")]
+            Self::NearV1(tx) => Ok(tx.build_for_signing()),
+            #[cfg(feature = "evm")]
+            Self::Evm(tx) => Ok(tx.build_for_signing()),
+            #[cfg(feature = "evm")]
+            Self::EvmEip2930(tx) => Ok(tx.build_for_signing()),
+            #[cfg(feature = "evm")]
+            Self::EvmEip4844(tx) => Ok(tx.build_for_signing()),
+            #[cfg(feature = "bitcoin")]
+            Self::Bitcoin(_) => Err(OmniTransactionError::SignedPerInput),
+        }
+    }
+
+    /// Returns the signed transaction bytes.
+    ///
+    /// Fails if the signature is for another chain than the transaction, and for Bitcoin (see
+    /// [`Self::build_for_signing`]).
+    pub fn build_with_signature(
+        &self,
+        signature: &ChainSignature,
+    ) -> Result<Vec<u8>, OmniTransactionError> {
+        match (self, signature) {
+            #[cfg(feature = "near")]
+            (Self::Near(tx), ChainSignature::Near(signature)) => {
+                Ok(tx.build_with_signature(signature.clone()))
+            }
+            #[cfg(feature = "near")]
+            (Self::NearV1(tx), ChainSignature::Near(signature)) => {
+                Ok(tx.build_with_signature(signature.clone()))
+            }
+            #[cfg(feature = "evm")]
+            (Self::Evm(tx), ChainSignature::Evm(signature)) => {
+                Ok(tx.build_with_signature(signature))
+            }
+            #[cfg(feature = "evm")]
+            (Self::EvmEip2930(tx), ChainSignature::Evm(signature)) => {
+                Ok(tx.build_with_signature(signature))
+            }
+            #[cfg(feature = "evm")]
+            (Self::EvmEip4844(tx), ChainSignature::Evm(signature)) => {
+                Ok(tx.build_with_signature(signature))
+            }
+            #[cfg(feature = "bitcoin")]
+            (Self::Bitcoin(_), _) => Err(OmniTransactionError::SignedPerInput),
+            #[allow(unreachable_patterns)]
+            _ => Err(OmniTransactionError::ChainMismatch),
+        }
+    }
+}
+
+impl From<OmniTransactionV1> for VersionedOmniTransaction {
+    fn from(transaction: OmniTransactionV1) -> Self {
+        Self::V1(transaction)
+    }
+}
+
+#[cfg(feature = "near")]
+impl From<NearTransaction> for OmniTransactionV1 {
+    fn from(transaction: NearTransaction) -> Self {
+        Self::Near(transaction)
+    }
+}
+
+#[cfg(feature = "near")]
+impl From<NearTransaction> for VersionedOmniTransaction {
+    fn from(transaction: NearTransaction) -> Self {
+        Self::V1(transaction.into())
+    }
+}
+
+#[cfg(feature = "evm")]
+impl From<EVMTransaction> for OmniTransactionV1 {
+    fn from(transaction: EVMTransaction) -> Self {
+        Self::Evm(transaction)
+    }
+}
+
+#[cfg(feature = "evm")]
+impl From<EVMTransaction> for VersionedOmniTransaction {
+    fn from(transaction: EVMTransaction) -> Self {
+        Self::V1(transaction.into())
+    }
+}
+
+#[cfg(feature = "bitcoin")]
+impl From<BitcoinTransaction> for OmniTransactionV1 {
+    fn from(transaction: BitcoinTransaction) -> Self {
+        Self::Bitcoin(transaction)
+    }
+}
+
+#[cfg(feature = "bitcoin")]
+impl From<BitcoinTransaction> for VersionedOmniTransaction {
+    fn from(transaction: BitcoinTransaction) -> Self {
+        Self::V1(transaction.into())
+    }
+}
+
+#[cfg(feature = "evm")]
+impl From<EVMTransactionEIP2930> for OmniTransactionV1 {
+    fn from(transaction: EVMTransactionEIP2930) -> Self {
+        Self::EvmEip2930(transaction)
+    }
+}
+
+#[cfg(feature = "evm")]
+impl From<EVMTransactionEIP4844> for OmniTransactionV1 {
+    fn from(transaction: EVMTransactionEIP4844) -> Self {
+        Self::EvmEip4844(transaction)
+    }
+}
+
+#[cfg(feature = "near")]
+impl From<NearTransactionV1> for OmniTransactionV1 {
+    fn from(transaction: NearTransactionV1) -> Self {
+        Self::NearV1(transaction)
+    }
+}
+
+impl BorshSerialize for VersionedOmniTransaction {
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<(), io::Error> {
+        match self {
+            Self::V1(transaction) => {
+                BorshSerialize::serialize(&VERSION_1, writer)?;
+                BorshSerialize::serialize(transaction, writer)
+            }
+        }
+    }
+}
+
+impl BorshDeserialize for VersionedOmniTransaction {
+    fn deserialize_reader<R: Read>(reader: &mut R) -> Result<Self, io::Error> {
+        match u8::deserialize_reader(reader)? {
+            VERSION_1 => Ok(Self::V1(OmniTransactionV1::deserialize_reader(reader)?)),
+            version => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("Unknown transaction version: {}", version),
+            )),
+        }
+    }
+}
+
+impl BorshSerialize for OmniTransactionV1 {
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<(), io::Error> {
+        match self {
+            #[cfg(feature = "near")]
+            Self::Near(transaction) => {
+                BorshSerialize::serialize(&NEAR_TAG, writer)?;
+                BorshSerialize::serialize(transaction, writer)
+            }
+            #[cfg(feature = "evm")]
+            Self::Evm(transaction) => {
+                BorshSerialize::serialize(&EVM_TAG, writer)?;
+                BorshSerialize::serialize(transaction, writer)
+            }
+            #[cfg(feature = "bitcoin")]
+            Self::Bitcoin(transaction) => {
+                BorshSerialize::serialize(&BITCOIN_TAG, writer)?;
+                BorshSerialize::serialize(transaction, writer)
+            }
+            #[cfg(feature = "evm")]
+            Self::EvmEip2930(transaction) => {
+                BorshSerialize::serialize(&EVM_EIP2930_TAG, writer)?;
+                BorshSerialize::serialize(transaction, writer)
+            }
+            #[cfg(feature = "evm")]
+            Self::EvmEip4844(transaction) => {
+                BorshSerialize::serialize(&EVM_EIP4844_TAG, writer)?;
+                BorshSerialize::serialize(transaction, writer)
+            }
+            #[cfg(feature = "near")]
+            Self::NearV1(transaction) => {
+                BorshSerialize::serialize(&NEAR_V1_TAG, writer)?;
+                BorshSerialize::serialize(transaction, writer)
+            }
+        }
+    }
+}
+
+impl BorshDeserialize for OmniTransactionV1 {
+    fn deserialize_reader<R: Read>(reader: &mut R) -> Result<Self, io::Error> {
+        match u8::deserialize_reader(reader)? {
+            #[cfg(feature = "near")]
+            NEAR_TAG => Ok(Self::Near(NearTransaction::deserialize_reader(reader)?)),
+            #[cfg(feature = "evm")]
+            EVM_TAG => Ok(Self::Evm(EVMTransaction::deserialize_reader(reader)?)),
+            #[cfg(feature = "bitcoin")]
+            BITCOIN_TAG => Ok(Self::Bitcoin(BitcoinTransaction::deserialize_reader(
+                reader,
+            )?)),
+            #[cfg(feature = "evm")]
+            EVM_EIP2930_TAG => Ok(Self::EvmEip2930(EVMTransactionEIP2930::deserialize_reader(
+                reader,
+            )?)),
+            #[cfg(feature = "evm")]
+            EVM_EIP4844_TAG => Ok(Self::EvmEip4844(EVMTransactionEIP4844::deserialize_reader(
+                reader,
+            )?)),
+            #[cfg(feature = "near")]
+            NEAR_V1_TAG => Ok(Self::NearV1(NearTransactionV1::deserialize_reader(reader)?)),
+            tag => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("Unknown or disabled chain tag: {}", tag),
+            )),
+        }
+    }
+}
+
+#[cfg(all(test, feature = "near", feature = "evm"))]
+mod tests {
+    use super::*;
+    use crate::near::types::{Action, BlockHash, NearToken, PublicKey, TransferAction, U64};
+
+    fn near_transaction() -> NearTransaction {
+        NearTransaction {
+            signer_id: "alice.near".parse().unwrap(),
+            signer_public_key: PublicKey::SECP256K1([0u8; 64].into()),
+            nonce: U64(1),
+            receiver_id: "bob.near".parse().unwrap(),
+            block_hash: BlockHash([0u8; 32]),
+            actions: vec![Action::Transfer(TransferAction {
+                deposit: NearToken::from_yoctonear(1),
+            })],
+        }
+    }
+
+    #[test]
+    fn test_borsh_layout_has_explicit_tags() {
+        let transaction = near_transaction();
+        let versioned = VersionedOmniTransaction::from(transaction.clone());
+
+        let bytes = borsh::to_vec(&versioned).unwrap();
+
+        assert_eq!(bytes[0], VERSION_1);
+        assert_eq!(bytes[1], NEAR_TAG);
+        assert_eq!(bytes[2..], borsh::to_vec(&transaction).unwrap());
+        assert_eq!(
+            VersionedOmniTransaction::try_from_slice(&bytes).unwrap(),
+            versioned
+        );
+    }
+
+    #[test]
+    fn test_state_roundtrip_with_evm_transaction() {
+        let evm_transaction = EVMTransaction {
+            chain_id: 1,
+            nonce: 0,
+            to: Some([0x11; 20]),
+            value: 1,
+            input: vec![],
+            gas_limit: 21_000,
+            max_fee_per_gas: 2,
+            max_priority_fee_per_gas: 1,
+            access_list: vec![],
+        };
+        let versioned = VersionedOmniTransaction::from(evm_transaction.clone());
+
+        let bytes = borsh::to_vec(&versioned).unwrap();
+        assert_eq!(bytes[..2], [VERSION_1, EVM_TAG]);
+        assert_eq!(bytes[2..], borsh::to_vec(&evm_transaction).unwrap());
+
+        // Pending transactions of several chains, as kept in the state of a contract.
+        let state = vec![
+            VersionedOmniTransaction::from(near_transaction()),
+            versioned,
+        ];
+        let bytes = borsh::to_vec(&state).unwrap();
+        assert_eq!(
+            Vec::<VersionedOmniTransaction>::try_from_slice(&bytes).unwrap(),
+            state
+        );
+    }
+
+    // Golden encodings of each V1 variant, as stored by contracts. These must never change.
+
+    #[test]
+    fn test_v1_near_golden_bytes() {
+        let expected = concat!(
+            "01",                           // version
+            "00",                           // chain tag
+            "0a000000616c6963652e6e656172", // signer_id
+            // SECP256K1 public key
+            "01",
+            "0000000000000000000000000000000000000000000000000000000000000000",
+            "0000000000000000000000000000000000000000000000000000000000000000",
+            "0100000000000000",         // nonce
+            "08000000626f622e6e656172", // receiver_id
+            // block_hash
+            "0000000000000000000000000000000000000000000000000000000000000000",
+            "01000000",                         // one action
+            "03",                               // Transfer
+            "01000000000000000000000000000000", // deposit
+        );
+
+        let versioned = VersionedOmniTransaction::from(near_transaction());
+        assert_eq!(hex::encode(borsh::to_vec(&versioned).unwrap()), expected);
+        assert_eq!(
+            VersionedOmniTransaction::try_from_slice(&hex::decode(expected).unwrap()).unwrap(),
+            versioned
+        );
+    }
+
+    #[test]
+    fn test_v1_evm_golden_bytes() {
+        let expected = concat!(
+            "01",                                         // version
+            "01",                                         // chain tag
+            "0100000000000000",                           // chain_id
+            "0000000000000000",                           // nonce
+            "011111111111111111111111111111111111111111", // to
+            "01000000000000000000000000000000",           // value
+            "01000000aa",                                 // input
+            "08520000000000000000000000000000",           // gas_limit
+            "02000000000000000000000000000000",           // max_fee_per_gas
+            "01000000000000000000000000000000",           // max_priority_fee_per_gas
+            "00000000",                                   // access_list
+        );
+
+        let versioned = VersionedOmniTransaction::from(EVMTransaction {
+            chain_id: 1,
+            nonce: 0,
+            to: Some([0x11; 20]),
+            value: 1,
+            input: vec![0xaa],
+            gas_limit: 21_000,
+            max_fee_per_gas: 2,
+            max_priority_fee_per_gas: 1,
+            access_list: vec![],
+        });
+        assert_eq!(hex::encode(borsh::to_vec(&versioned).unwrap()), expected);
+        assert_eq!(
+            VersionedOmniTransaction::try_from_slice(&hex::decode(expected).unwrap()).unwrap(),
+            versioned
+        );
+    }
+
+    #[cfg(feature = "bitcoin")]
+    #[test]
+    fn test_v1_bitcoin_golden_bytes() {
+        use crate::bitcoin::types::{
+            Amount, Hash, LockTime, OutPoint, ScriptBuf, Sequence, TxIn, TxOut, Txid, Version,
+            Witness,
+        };
+
+        let expected = concat!(
+            "01",       // version
+            "02",       // chain tag
+            "02",       // tx version, a single byte below 0xff
+            "00000000", // lock time
+            "01000000", // one input
+            // previous txid
+            "4444444444444444444444444444444444444444444444444444444444444444",
+            "01000000", // vout
+            "00000000", // script_sig
+            "ffffffff", // sequence
+            // witness content, element count and index start
+            "00000000",
+            "0000000000000000",
+            "0000000000000000",
+            "01000000",         // one output
+            "e803000000000000", // value
+            "0100000051",       // script_pubkey
+        );
+
+        let versioned = VersionedOmniTransaction::from(BitcoinTransaction {
+            version: Version::TWO,
+            lock_time: LockTime::from_height(0).unwrap(),
+            input: vec![TxIn {
+                previous_output: OutPoint::new(Txid(Hash::from_byte_array([0x44; 32])), 1),
+                script_sig: ScriptBuf(vec![]),
+                sequence: Sequence::MAX,
+                witness: Witness::default(),
+            }],
+            output: vec![TxOut {
+                value: Amount::from_sat(1000),
+                script_pubkey: ScriptBuf(vec![0x51]),
+            }],
+        });
+        assert_eq!(hex::encode(borsh::to_vec(&versioned).unwrap()), expected);
+        assert_eq!(
+            VersionedOmniTransaction::try_from_slice(&hex::decode(expected).unwrap()).unwrap(),
+            versioned
+        );
+    }
+
+    #[test]
+    fn test_migrate_unversioned() {
+        let transaction = near_transaction();
+        let bytes = borsh::to_vec(&transaction).unwrap();
+
+        let versioned =
+            VersionedOmniTransaction::migrate_unversioned::<NearTransaction>(&bytes).unwrap();
+
+        assert_eq!(
+            versioned.into_latest(),
+            OmniTransactionV1::Near(transaction)
+        );
+        assert!(matches!(
+            VersionedOmniTransaction::migrate_unversioned::<EVMTransaction>(&bytes),
+            Err(OmniTransactionError::InvalidEncoding(_))
+        ));
+    }
+
+    #[test]
+    fn test_unknown_version_fails() {
+        let mut bytes = borsh::to_vec(&VersionedOmniTransaction::from(near_transaction())).unwrap();
+        bytes[0] = 2;
+
+        assert!(VersionedOmniTransaction::try_from_slice(&bytes).is_err());
+    }
+
+    #[test]
+    fn test_serde_roundtrip() {
+        let versioned = VersionedOmniTransaction::from(near_transaction());
+
+        let json = serde_json::to_value(&versioned).unwrap();
+        assert_eq!(json["version"], "1");
+
+        let deserialized: VersionedOmniTransaction = serde_json::from_value(json).unwrap();
+        assert_eq!(deserialized, versioned);
+    }
+
+    #[test]
+    fn test_omni_transaction_signing() {
+        use crate::evm::types::Signature as EVMSignature;
+        use crate::near::types::{ED25519Signature, Signature as NearSignature};
+
+        let near_v1 = NearTransactionV1::from_transaction(near_transaction(), 7);
+        let eip2930 = EVMTransactionEIP2930 {
+            chain_id: 1,
+            nonce: 0,
+            gas_price: 2,
+            gas_limit: 21_000,
+            to: Some([0x11; 20]),
+            value: 1,
+            input: vec![],
+            access_list: vec![],
+        };
+        let transactions: Vec<OmniTransaction> =
+            vec![near_v1.clone().into(), eip2930.clone().into()];
+
+        let bytes = borsh::to_vec(&transactions).unwrap();
+        assert_eq!(
+            Vec::<OmniTransaction>::try_from_slice(&bytes).unwrap(),
+            transactions
+        );
+        assert_eq!(borsh::to_vec(&transactions[0]).unwrap()[0], NEAR_V1_TAG);
+        assert_eq!(borsh::to_vec(&transactions[1]).unwrap()[0], EVM_EIP2930_TAG);
+        let json = serde_json::to_string(&transactions).unwrap();
+        assert_eq!(
+            serde_json::from_str::<Vec<OmniTransaction>>(&json).unwrap(),
+            transactions
+        );
+
+        assert_eq!(
+            transactions[0].build_for_signing(),
+            Ok(near_v1.build_for_signing())
+        );
+        assert_eq!(
+            transactions[1].build_for_signing(),
+            Ok(eip2930.build_for_signing())
+        );
+
+        let near_signature = NearSignature::ED25519(ED25519Signature {
+            r: [1u8; 32],
+            s: [2u8; 32],
+        });
+        let evm_signature = EVMSignature {
+            v: 0,
+            r: vec![0x44; 32],
+            s: vec![0x55; 32],
+        };
+        assert_eq!(
+            transactions[0].build_with_signature(&ChainSignature::Near(near_signature.clone())),
+            Ok(near_v1.build_with_signature(near_signature))
+        );
+        assert_eq!(
+            transactions[1].build_with_signature(&ChainSignature::Evm(evm_signature.clone())),
+            Ok(eip2930.build_with_signature(&evm_signature))
+        );
+        assert_eq!(
+            transactions[0].build_with_signature(&ChainSignature::Evm(evm_signature)),
+            Err(OmniTransactionError::ChainMismatch)
+        );
+    }
+}
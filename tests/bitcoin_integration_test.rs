@@ -1,18 +1,17 @@
 // Rust Bitcoin
 use bitcoin::hashes::{sha256d, Hash};
-use bitcoin::script::Builder;
 use bitcoin::secp256k1::{Message, Secp256k1};
 use bitcoin::Address;
 use bitcoin::EcdsaSighashType;
 use bitcoin::ScriptBuf;
-use bitcoin::Witness;
 // Omni library
 use omni_transaction::bitcoin::bitcoin_transaction::BitcoinTransaction;
 use omni_transaction::bitcoin::types::{
     Amount as OmniAmount, EcdsaSighashType as OmniSighashType, Hash as OmniHash,
     LockTime as OmniLockTime, OutPoint as OmniOutPoint, ScriptBuf as OmniScriptBuf,
-    Sequence as OmniSequence, TransactionType, TxIn as OmniTxIn, TxOut as OmniTxOut,
-    Txid as OmniTxid, Version as OmniVersion, Witness as OmniWitness,
+    ScriptBuilder as OmniScriptBuilder, Sequence as OmniSequence, TransactionType,
+    TxIn as OmniTxIn, TxOut as OmniTxOut, Txid as OmniTxid, Version as OmniVersion,
+    Witness as OmniWitness,
 };
 use omni_transaction::transaction_builder::TransactionBuilder;
 use omni_transaction::transaction_builder::TxBuilder;
@@ -106,8 +105,7 @@ async fn test_send_p2pkh_using_rust_bitcoin_and_omni_library() -> Result<()> {
         script_pubkey: OmniScriptBuf(alice.script_pubkey.as_bytes().to_vec()),
     };
 
-    let utxo_amount =
-        OmniAmount::from_sat((first_unspent["amount"].as_f64().unwrap() * 100_000_000.0) as u64);
+    let utxo_amount = OmniAmount::from_rpc_value(&first_unspent["amount"]).unwrap();
 
     let change_amount: OmniAmount = utxo_amount - OMNI_SPEND_AMOUNT - OmniAmount::from_sat(1000); // 1000 satoshis for fee
 
@@ -117,21 +115,23 @@ async fn test_send_p2pkh_using_rust_bitcoin_and_omni_library() -> Result<()> {
     };
 
     let mut omni_tx: BitcoinTransaction = TransactionBuilder::new::<BITCOIN>()
-        .version(OmniVersion::One)
+        .version(OmniVersion::ONE)
         .lock_time(OmniLockTime::from_height(1).unwrap())
         .inputs(vec![txin])
         .outputs(vec![txout, change_txout])
         .build();
 
-    // Add the script_sig to the transaction
-    omni_tx.input[0].script_sig = OmniScriptBuf(bob.script_pubkey.as_bytes().to_vec());
-
-    // Encode the transaction for signing
+    // Encode the transaction for signing, committing to the script pubkey of the spent output
+    let script_code = OmniScriptBuf(bob.script_pubkey.as_bytes().to_vec());
     let sighash_type = OmniSighashType::All;
-    let encoded_data = omni_tx.build_for_signing_legacy(sighash_type);
+    let encoded_data = omni_tx.build_for_signing_legacy_input(0, &script_code, sighash_type);
 
     // Calculate the sighash
     let sighash_omni = sha256d::Hash::hash(&encoded_data);
+    assert_eq!(
+        &omni_tx.hash_for_signing_legacy_input(0, &script_code, sighash_type),
+        sighash_omni.as_byte_array()
+    );
     let msg_omni = Message::from_digest_slice(sighash_omni.as_byte_array()).unwrap();
 
     // Sign the sighash and broadcast the transaction using the Omni library
@@ -152,13 +152,12 @@ async fn test_send_p2pkh_using_rust_bitcoin_and_omni_library() -> Result<()> {
     };
 
     // Create the script_sig
-    let script_sig_new = Builder::new()
-        .push_slice(signature.serialize())
-        .push_key(&bob.bitcoin_public_key)
+    let omni_script_sig = OmniScriptBuilder::new()
+        .push_slice(&signature.to_vec())
+        .push_slice(&bob.bitcoin_public_key.to_bytes())
         .into_script();
 
     // Assign script_sig to txin
-    let omni_script_sig = OmniScriptBuf(script_sig_new.as_bytes().to_vec());
     let encoded_omni_tx = omni_tx.build_with_script_sig(0, omni_script_sig, TransactionType::P2PKH);
 
     // Convert the transaction to a hexadecimal string
@@ -224,8 +223,7 @@ async fn test_send_p2wpkh_using_rust_bitcoin_and_omni_library() -> Result<()> {
         script_pubkey: OmniScriptBuf(alice.script_pubkey.as_bytes().to_vec()),
     };
 
-    let utxo_amount =
-        OmniAmount::from_sat((first_unspent["amount"].as_f64().unwrap() * 100_000_000.0) as u64);
+    let utxo_amount = OmniAmount::from_rpc_value(&first_unspent["amount"]).unwrap();
 
     let change_amount: OmniAmount = utxo_amount - OMNI_SPEND_AMOUNT - OmniAmount::from_sat(1000); // 1000 satoshis for fee
 
@@ -238,7 +236,7 @@ async fn test_send_p2wpkh_using_rust_bitcoin_and_omni_library() -> Result<()> {
     };
 
     let mut omni_tx: BitcoinTransaction = TransactionBuilder::new::<BITCOIN>()
-        .version(OmniVersion::Two)
+        .version(OmniVersion::TWO)
         .lock_time(OmniLockTime::from_height(1).unwrap())
         .inputs(vec![txin])
         .outputs(vec![spend_txout, change_txout])
@@ -256,6 +254,15 @@ async fn test_send_p2wpkh_using_rust_bitcoin_and_omni_library() -> Result<()> {
 
     // Calculate the sighash
     let sighash_omni = sha256d::Hash::hash(&encoded_data);
+    assert_eq!(
+        &omni_tx.hash_for_signing_segwit(
+            sighash_type,
+            input_index,
+            &OmniScriptBuf(alice.script_pubkey.as_bytes().to_vec()),
+            utxo_amount.to_sat(),
+        ),
+        sighash_omni.as_byte_array()
+    );
     let msg_omni = Message::from_digest_slice(sighash_omni.as_byte_array()).unwrap();
 
     // Sign the sighash and broadcast the transaction using the Omni library
@@ -269,14 +276,12 @@ async fn test_send_p2wpkh_using_rust_bitcoin_and_omni_library() -> Result<()> {
 
     assert!(is_valid, "The signature should be valid");
 
-    // Encode the signature
-    let signature = bitcoin::ecdsa::Signature {
-        signature: signature_omni,
-        sighash_type: EcdsaSighashType::All,
-    };
-
     // Create the witness
-    let witness = Witness::p2wpkh(&signature, &bob.public_key);
+    let witness = OmniWitness::p2wpkh(
+        &signature_omni.serialize_der(),
+        sighash_type,
+        &bob.public_key.serialize(),
+    );
 
     // Assign script_sig to txin
     let encoded_omni_tx = omni_tx.build_with_witness(0, witness.to_vec(), TransactionType::P2WPKH);
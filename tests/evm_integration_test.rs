@@ -65,6 +65,7 @@ async fn test_send_raw_transaction_created_with_omnitransactionbuilder_for_evm()
 
     // Hash the encoded transaction
     let omni_evm_tx_hash = keccak256(&omni_evm_tx_encoded);
+    assert_eq!(omni_evm_tx.hash_for_signing(), omni_evm_tx_hash.0);
 
     // Sign the transaction hash
     let signature = signer.sign_hash(&omni_evm_tx_hash).await?;
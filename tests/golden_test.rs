@@ -0,0 +1,331 @@
+//! Golden compatibility suite: encodes the same transactions with omni-transaction and with
+//! the reference crates resolved by the workspace, and reports every difference.
+//!
+//! Run with `cargo test --features golden --test golden_test -- --nocapture` to print the
+//! report.
+use alloy::{
+    consensus::{SignableTransaction, TxEip1559, TxEip2930},
+    eips::eip2930::{AccessList, AccessListItem},
+    primitives::{address, b256, hex, Bytes, Signature, U256},
+};
+use bitcoin::hashes::Hash;
+use bitcoin::sighash::SighashCache;
+use near_crypto::{ED25519PublicKey, KeyType, PublicKey, Signature as NearSignature};
+use near_primitives::{
+    action::{Action, FunctionCallAction, TransferAction},
+    hash::CryptoHash,
+    transaction::{SignedTransaction, Transaction, TransactionV0, TransactionV1},
+};
+
+use omni_transaction::bitcoin::prelude::{
+    Amount as OmniAmount, BitcoinTransaction, EcdsaSighashType as OmniSighashType,
+    Hash as OmniHash, LockTime, OutPoint as OmniOutPoint, ScriptBuf as OmniScriptBuf,
+    Sequence as OmniSequence, TransactionType, TxIn as OmniTxIn, TxOut as OmniTxOut,
+    Txid as OmniTxid, Version as OmniVersion, Witness as OmniWitness,
+};
+use omni_transaction::evm::prelude::{
+    AccessListItem as OmniAccessListItem, EVMTransaction, EVMTransactionEIP2930,
+    Signature as OmniEvmSignature,
+};
+use omni_transaction::golden::GoldenReport;
+use omni_transaction::near::prelude::{
+    Action as OmniAction, ED25519Signature, FunctionCallAction as OmniFunctionCallAction, NearGas,
+    NearToken, NearTransaction, NearTransactionV1, PublicKeyStrExt, Signature as OmniNearSignature,
+    TransferAction as OmniTransferAction, U64,
+};
+use omni_transaction::types::{BitcoinNetwork, ChainKind};
+
+const NEAR_PUBLIC_KEY: &str = "ed25519:6E8sCci9badyRkXb3JoRpBj5p8C6Tw41ELDZoiihKEtp";
+const NEAR_BLOCK_HASH: &str = "4reLvkAWfqk5fsqio1KLudk46cqRz9erQdaHkWZKMJDZ";
+
+fn check_near(report: &mut GoldenReport) {
+    let public_key = PublicKey::ED25519(ED25519PublicKey(
+        NEAR_PUBLIC_KEY.try_ed25519_into_bytes().unwrap(),
+    ));
+    let block_hash = CryptoHash(NEAR_BLOCK_HASH.to_fixed_32_bytes().unwrap());
+    let actions = vec![
+        Action::Transfer(TransferAction { deposit: 1 }),
+        Action::FunctionCall(Box::new(FunctionCallAction {
+            method_name: "ft_transfer".to_string(),
+            args: br#"{"receiver_id":"bob.near","amount":"1"}"#.to_vec(),
+            gas: 30_000_000_000_000,
+            deposit: 1,
+        })),
+    ];
+    let omni_actions = vec![
+        OmniAction::Transfer(OmniTransferAction {
+            deposit: NearToken::from_yoctonear(1),
+        }),
+        OmniAction::FunctionCall(Box::new(OmniFunctionCallAction {
+            method_name: "ft_transfer".to_string(),
+            args: br#"{"receiver_id":"bob.near","amount":"1"}"#.to_vec(),
+            gas: NearGas::from_gas(30_000_000_000_000),
+            deposit: NearToken::from_yoctonear(1),
+        })),
+    ];
+    let signature_bytes = [7u8; 64];
+    let signature = NearSignature::from_parts(KeyType::ED25519, &signature_bytes).unwrap();
+    let omni_signature = OmniNearSignature::ED25519(ED25519Signature {
+        r: signature_bytes[..32].try_into().unwrap(),
+        s: signature_bytes[32..].try_into().unwrap(),
+    });
+
+    let v0 = Transaction::V0(TransactionV0 {
+        signer_id: "alice.near".parse().unwrap(),
+        public_key: public_key.clone(),
+        nonce: 42,
+        receiver_id: "token.near".parse().unwrap(),
+        block_hash,
+        actions: actions.clone(),
+    });
+    let omni_v0 = NearTransaction {
+        signer_id: "alice.near".parse().unwrap(),
+        signer_public_key: NEAR_PUBLIC_KEY.to_public_key().unwrap(),
+        nonce: U64(42),
+        receiver_id: "token.near".parse().unwrap(),
+        block_hash: NEAR_BLOCK_HASH.to_block_hash().unwrap(),
+        actions: omni_actions,
+    };
+
+    report.check(
+        ChainKind::Near,
+        "v0/build_for_signing",
+        borsh::to_vec(&v0).unwrap(),
+        omni_v0.build_for_signing(),
+    );
+    report.check(
+        ChainKind::Near,
+        "v0/build_with_signature",
+        borsh::to_vec(&SignedTransaction::new(signature.clone(), v0)).unwrap(),
+        omni_v0.build_with_signature(omni_signature.clone()),
+    );
+
+    let v1 = Transaction::V1(TransactionV1 {
+        signer_id: "alice.near".parse().unwrap(),
+        public_key,
+        nonce: 42,
+        receiver_id: "token.near".parse().unwrap(),
+        block_hash,
+        actions,
+        priority_fee: 1_000,
+    });
+    let omni_v1 = NearTransactionV1::from_transaction(omni_v0, 1_000);
+
+    report.check(
+        ChainKind::Near,
+        "v1/build_for_signing",
+        borsh::to_vec(&v1).unwrap(),
+        omni_v1.build_for_signing(),
+    );
+    report.check(
+        ChainKind::Near,
+        "v1/build_with_signature",
+        borsh::to_vec(&SignedTransaction::new(signature, v1)).unwrap(),
+        omni_v1.build_with_signature(omni_signature),
+    );
+}
+
+fn check_evm(report: &mut GoldenReport) {
+    let to = address!("d8dA6BF26964aF9D7eEd9e03E53415D37aA96045");
+    let storage_key = b256!("0000000000000000000000000000000000000000000000000000000000000001");
+    let input: Bytes = hex!("a9059cbb0000000000000000000000005eee75727d804a2b13038928d36f8b188945a57a0000000000000000000000000000000000000000000000000000000000000001").into();
+    let access_list = AccessList(vec![AccessListItem {
+        address: to,
+        storage_keys: vec![storage_key],
+    }]);
+    let omni_access_list = vec![OmniAccessListItem::new(to.0 .0, vec![storage_key.0])];
+
+    let sig = Signature::from_scalars_and_parity(
+        b256!("840cfc572845f5786e702984c2a582528cad4b49b2a10b9db1be7fca90058565"),
+        b256!("25e7109ceb98168d95b09b18bbf6b685130e0562f233877d492b94eee0c5b6d1"),
+        true,
+    )
+    .unwrap();
+    let omni_signature = OmniEvmSignature {
+        v: sig.v().to_u64(),
+        r: sig.r().to_be_bytes::<32>().to_vec(),
+        s: sig.s().to_be_bytes::<32>().to_vec(),
+    };
+
+    let eip1559 = TxEip1559 {
+        chain_id: 1,
+        nonce: 9,
+        gas_limit: 60_000,
+        max_fee_per_gas: 20_000_000_000,
+        max_priority_fee_per_gas: 1_000_000_000,
+        to: to.into(),
+        value: U256::from(10_000_000_000_000_000u128),
+        access_list: access_list.clone(),
+        input: input.clone(),
+    };
+    let omni_eip1559 = EVMTransaction {
+        chain_id: 1,
+        nonce: 9,
+        to: Some(to.0 .0),
+        value: 10_000_000_000_000_000,
+        input: input.to_vec(),
+        gas_limit: 60_000,
+        max_fee_per_gas: 20_000_000_000,
+        max_priority_fee_per_gas: 1_000_000_000,
+        access_list: omni_access_list.clone(),
+    };
+
+    let mut expected = vec![];
+    eip1559.encode_for_signing(&mut expected);
+    report.check(
+        ChainKind::Evm { chain_id: 1 },
+        "eip1559/build_for_signing",
+        expected,
+        omni_eip1559.build_for_signing(),
+    );
+    let mut expected = vec![];
+    eip1559.encode_with_signature(&sig, &mut expected, false);
+    report.check(
+        ChainKind::Evm { chain_id: 1 },
+        "eip1559/build_with_signature",
+        expected,
+        omni_eip1559.build_with_signature(&omni_signature),
+    );
+
+    let eip2930 = TxEip2930 {
+        chain_id: 1,
+        nonce: 9,
+        gas_price: 20_000_000_000,
+        gas_limit: 60_000,
+        to: to.into(),
+        value: U256::from(1u128),
+        access_list,
+        input: input.clone(),
+    };
+    let omni_eip2930 = EVMTransactionEIP2930 {
+        chain_id: 1,
+        nonce: 9,
+        gas_price: 20_000_000_000,
+        gas_limit: 60_000,
+        to: Some(to.0 .0),
+        value: 1,
+        input: input.to_vec(),
+        access_list: omni_access_list,
+    };
+
+    let mut expected = vec![];
+    eip2930.encode_for_signing(&mut expected);
+    report.check(
+        ChainKind::Evm { chain_id: 1 },
+        "eip2930/build_for_signing",
+        expected,
+        omni_eip2930.build_for_signing(),
+    );
+    let mut expected = vec![];
+    eip2930.encode_with_signature(&sig, &mut expected, false);
+    report.check(
+        ChainKind::Evm { chain_id: 1 },
+        "eip2930/build_with_signature",
+        expected,
+        omni_eip2930.build_with_signature(&omni_signature),
+    );
+}
+
+fn check_bitcoin(report: &mut GoldenReport) {
+    let script_pubkey_hex = "0014751e76e8199196d454941c45d1b3a323f1433bd6";
+    let script_code_hex = "76a914751e76e8199196d454941c45d1b3a323f1433bd688ac";
+    let script_pubkey = bitcoin::ScriptBuf::from_hex(script_pubkey_hex).unwrap();
+    let script_code = bitcoin::ScriptBuf::from_hex(script_code_hex).unwrap();
+
+    let mut tx = bitcoin::Transaction {
+        version: bitcoin::transaction::Version(2),
+        lock_time: bitcoin::absolute::LockTime::from_height(800_000).unwrap(),
+        input: vec![bitcoin::TxIn {
+            previous_output: bitcoin::OutPoint {
+                txid: bitcoin::Txid::from_byte_array([7; 32]),
+                vout: 1,
+            },
+            script_sig: bitcoin::ScriptBuf::default(),
+            sequence: bitcoin::Sequence(0xffff_fffd),
+            witness: bitcoin::Witness::default(),
+        }],
+        output: vec![bitcoin::TxOut {
+            value: bitcoin::Amount::from_sat(90_000),
+            script_pubkey: script_pubkey.clone(),
+        }],
+    };
+    let mut omni_tx = BitcoinTransaction {
+        version: OmniVersion::TWO,
+        lock_time: LockTime::from_height(800_000).unwrap(),
+        input: vec![OmniTxIn {
+            // A palindromic txid: rust-bitcoin keeps hashes in wire order, omni in display order.
+            previous_output: OmniOutPoint::new(OmniTxid(OmniHash::from_byte_array([7; 32])), 1),
+            script_sig: OmniScriptBuf::default(),
+            sequence: OmniSequence(0xffff_fffd),
+            witness: OmniWitness::default(),
+        }],
+        output: vec![OmniTxOut {
+            value: OmniAmount::from_sat(90_000),
+            script_pubkey: OmniScriptBuf::from_hex(script_pubkey_hex).unwrap(),
+        }],
+    };
+
+    let mut expected = vec![];
+    SighashCache::new(&tx)
+        .legacy_encode_signing_data_to(&mut expected, 0, &script_pubkey, 1u32)
+        .is_sighash_single_bug()
+        .unwrap();
+    report.check(
+        ChainKind::Bitcoin {
+            network: BitcoinNetwork::Bitcoin,
+        },
+        "legacy/build_for_signing",
+        expected,
+        omni_tx.build_for_signing_legacy_input(
+            0,
+            &OmniScriptBuf::from_hex(script_pubkey_hex).unwrap(),
+            OmniSighashType::All,
+        ),
+    );
+
+    let mut expected = vec![];
+    SighashCache::new(&tx)
+        .segwit_v0_encode_signing_data_to(
+            &mut expected,
+            0,
+            &script_code,
+            bitcoin::Amount::from_sat(100_000),
+            bitcoin::EcdsaSighashType::All,
+        )
+        .unwrap();
+    report.check(
+        ChainKind::Bitcoin {
+            network: BitcoinNetwork::Bitcoin,
+        },
+        "segwit/build_for_signing",
+        expected,
+        omni_tx.build_for_signing_segwit(
+            OmniSighashType::All,
+            0,
+            &OmniScriptBuf::from_hex(script_code_hex).unwrap(),
+            100_000,
+        ),
+    );
+
+    let witness = vec![vec![0x30; 71], vec![0x02; 33]];
+    tx.input[0].witness = bitcoin::Witness::from_slice(&witness);
+    report.check(
+        ChainKind::Bitcoin {
+            network: BitcoinNetwork::Bitcoin,
+        },
+        "segwit/build_with_witness",
+        bitcoin::consensus::serialize(&tx),
+        omni_tx.build_with_witness(0, witness, TransactionType::P2WPKH),
+    );
+}
+
+#[test]
+fn test_golden_compatibility() {
+    let mut report = GoldenReport::new();
+
+    check_near(&mut report);
+    check_evm(&mut report);
+    check_bitcoin(&mut report);
+
+    assert!(report.is_compatible(), "{}", report);
+}
@@ -5,7 +5,7 @@ use near_jsonrpc_client::{methods, JsonRpcClient};
 use near_primitives::hash::CryptoHash;
 use near_workspaces::sandbox;
 use omni_transaction::near::types::{
-    Action, ED25519Signature, Signature as OmniSignature, TransferAction, U128,
+    Action, ED25519Signature, NearToken, Signature as OmniSignature, TransferAction,
 };
 use omni_transaction::near::utils::PublicKeyStrExt;
 use omni_transaction::transaction_builder::{TransactionBuilder, TxBuilder};
@@ -26,7 +26,9 @@ async fn test_send_raw_transaction_created_with_omnitransactionbuilder_for_near(
     let alice_original_balance = alice.view_account().await?.balance;
     let bob_original_balance = bob.view_account().await?.balance;
 
-    let transfer_action = Action::Transfer(TransferAction { deposit: U128(1) });
+    let transfer_action = Action::Transfer(TransferAction {
+        deposit: NearToken::from_yoctonear(1),
+    });
     let actions = vec![transfer_action];
 
     // Configure the signer from the first default Sandbox account (Alice).
@@ -71,8 +73,8 @@ async fn test_send_raw_transaction_created_with_omnitransactionbuilder_for_near(
     // Sign the hashed transaction
     let signature = signer.sign(&hashed_tx_value);
 
-    // Create CryptoHash from the SHA256 digest of the signed transaction
-    let tx_hash = CryptoHash::hash_bytes(&sha2::Sha256::digest(&near_tx_encoded));
+    // The hash to poll the transaction status with
+    let tx_hash = CryptoHash(near_tx.hash_for_signing());
 
     // @dev For simplicity we only support ED25519 signature in this test
     let signature_bytes: [u8; 64] = match &signature {